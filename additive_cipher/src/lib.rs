@@ -0,0 +1,184 @@
+//! This module provides a simple implementation of an additive cipher,
+//! including encryption and decryption functionality, as well as a CLI
+//! interface for user interaction.
+
+use clap::{Parser, ValueEnum};
+use logging::{English, Verbosity};
+use tool_error::ToolError;
+use classical::{Alphabet, Language, NgramModel};
+
+/// Command-line arguments for the additive cipher program.
+#[derive(Parser, Debug)]
+struct Cli {
+    /// Path to the input file.
+    #[arg(short,long, help = "Path to the input file")]
+    file: String,
+
+    /// Key for the cipher.
+    #[arg(short,long,required_if_eq_any = [("mode", "encrypt"), ("mode", "decrypt")],help = "Key for the cipher")]
+    key: Option<usize>,
+
+    /// Path to the output file.
+    #[arg(short,long,help = "Path to the output file")]
+    output: String,
+
+    /// Mode of operation (encrypt, decrypt or brute-force).
+    #[arg(short,long,help = "Mode of operation (encrypt/decrypt/brute-force)")]
+    mode: OperationMode,
+
+    /// Alphabet to operate on.
+    #[arg(short,long,default_value = "latin",help = "Alphabet: latin, german, alnum, printable or a literal list of symbols")]
+    alphabet: String,
+
+    /// Language used to score brute-force candidates.
+    #[arg(short,long,value_enum,default_value = "de",help = "Plaintext language (de, en, fr, es, it)")]
+    language: Language,
+
+    /// Number of ranked candidates printed in brute-force mode.
+    #[arg(long,default_value_t = 5,help = "Number of candidates to print in brute-force mode")]
+    top: usize,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+/// Enum representing the mode of operation for the cipher.
+#[derive(Clone, Debug, ValueEnum)]
+enum OperationMode {
+    /// Encrypt mode.
+    Encrypt,
+    /// Decrypt mode.
+    Decrypt,
+    /// Try every key and rank the results by language score.
+    BruteForce,
+}
+
+/// A decryption candidate found in brute-force mode.
+struct Candidate {
+    key: usize,
+    score: f64,
+    plaintext: String,
+}
+
+/// Main entry point of the program.
+///
+/// Parses the command-line arguments and performs the requested operation
+/// (encryption, decryption or a brute-force attack) on the input file.
+///
+/// The first argument is the program name, as with `std::env::args_os`.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+    let alphabet = Alphabet::parse(&cli.alphabet).map_err(ToolError::usage)?;
+    match cli.mode {
+        OperationMode::Encrypt => {
+            let content: String = tool_error::fs::read_to_string(&cli.file)?;
+            //println!("{}", content);
+            let cypher = encrypt(&content, cli.key.expect("clap enforces --key"), &alphabet);
+            tool_error::fs::write(cli.output, cypher)?;
+        }
+        OperationMode::Decrypt => {
+            let content: String = tool_error::fs::read_to_string(&cli.file)?;
+            //println!("{}", content);
+            let message: String = decrypt(&content, cli.key.expect("clap enforces --key"), &alphabet);
+            tool_error::fs::write(cli.output, message)?;
+        }
+        OperationMode::BruteForce => {
+            let content: String = tool_error::fs::read_to_string(&cli.file)?;
+            let candidates = brute_force(&content, &NgramModel::bigrams(cli.language), &alphabet);
+
+            println!("{:>4} {:>10}  Preview", "Key", "Score");
+            for candidate in candidates.iter().take(cli.top) {
+                let preview: String = candidate.plaintext.chars()
+                    .take(60)
+                    .map(|c| if c.is_control() { ' ' } else { c })
+                    .collect();
+                println!("{:>4} {:>10.2}  {}", candidate.key, candidate.score, preview);
+            }
+
+            // The best candidate is saved, its key is printed for reuse
+            println!("Detected cipher key: {}", candidates[0].key);
+            tool_error::fs::write(cli.output, &candidates[0].plaintext)?;
+        }
+    }
+    Ok(())
+}
+
+/// Encrypts the given content using a simple additive cipher.
+///
+/// # Arguments
+///
+/// * `content` - The input string to be encrypted.
+/// * `key` - The encryption key.
+/// * `alphabet` - The alphabet the shift is applied in.
+///
+/// # Returns
+///
+/// A `String` containing the encrypted content.
+fn encrypt(content: &str, key: usize, alphabet: &Alphabet) -> String {
+    // Simple additive cipher encryption logic, symbols outside the alphabet stay unchanged
+    content.chars()
+        .map(|c| alphabet.shift(c, key % alphabet.len()))
+        .collect()
+}
+
+/// Decrypts the given content using a simple additive cipher.
+///
+/// # Arguments
+///
+/// * `content` - The input string to be decrypted.
+/// * `key` - The decryption key.
+/// * `alphabet` - The alphabet the shift is applied in.
+///
+/// # Returns
+///
+/// A `String` containing the decrypted content.
+fn decrypt(content: &str, key: usize, alphabet: &Alphabet) -> String {
+    content.chars()
+        .map(|c| alphabet.unshift(c, key % alphabet.len()))
+        .collect()
+}
+
+/// Decrypts the content under every key of the alphabet and ranks the
+/// results by their n-gram score, best first.
+fn brute_force(content: &str, model: &NgramModel, alphabet: &Alphabet) -> Vec<Candidate> {
+    let mut candidates: Vec<Candidate> = (0..alphabet.len())
+        .map(|key| {
+            let plaintext = decrypt(content, key, alphabet);
+            let score = model.score(&plaintext, alphabet);
+            Candidate { key, score, plaintext }
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let alphabet = Alphabet::latin();
+        let ciphertext = encrypt("Hello, World!", 3, &alphabet);
+        assert_eq!(ciphertext, "Khoor, Zruog!");
+        assert_eq!(decrypt(&ciphertext, 3, &alphabet), "Hello, World!");
+    }
+
+    #[test]
+    fn test_brute_force_ranks_correct_key_first() {
+        let alphabet = Alphabet::latin();
+        let text = "the enemy is moving north and will attack at dawn";
+        let ciphertext = encrypt(text, 17, &alphabet);
+
+        let candidates = brute_force(&ciphertext, &NgramModel::bigrams(Language::En), &alphabet);
+        assert_eq!(candidates.len(), 26);
+        assert_eq!(candidates[0].key, 17);
+        assert_eq!(candidates[0].plaintext, text);
+    }
+}
@@ -0,0 +1,267 @@
+//! ADFGVX cipher, used by the German army in 1918.
+//!
+//! Every letter and digit is first replaced by its row and column label in a
+//! 6x6 Polybius square (labels A, D, F, G, V, X). The resulting letter pairs
+//! are then written row by row under a transposition keyword and read off
+//! column by column in the alphabetical order of the keyword letters.
+
+use clap::{Parser, ValueEnum};
+use logging::{English, Verbosity};
+use tool_error::ToolError;
+use classical::Alphabet;
+
+/// Row and column labels of the Polybius square
+const LABELS: [char; 6] = ['A', 'D', 'F', 'G', 'V', 'X'];
+
+/// Command-line arguments for the ADFGVX cipher program.
+#[derive(Parser, Debug)]
+struct Cli {
+    /// Path to the input file
+    #[arg(short, long, help = "Path to the input file")]
+    file: String,
+
+    /// Path to the output file where the result will be saved
+    #[arg(short, long, help = "Path to the output file")]
+    output: String,
+
+    /// Mode of operation
+    #[arg(short, long, help = "Mode of operation (encrypt/decrypt)")]
+    mode: OperationMode,
+
+    /// Keyword of the columnar transposition
+    #[arg(short, long, help = "Transposition keyword")]
+    key: String,
+
+    /// Keyword the Polybius square is built from
+    #[arg(short, long, conflicts_with = "square", required_unless_present = "square", help = "Keyword for the Polybius square")]
+    square_key: Option<String>,
+
+    /// Complete Polybius square, row by row
+    #[arg(long, help = "All 36 letters and digits of the square, row by row")]
+    square: Option<String>,
+
+    /// Size of the letter groups in the ciphertext
+    #[arg(short, long, default_value_t = 5, help = "Group size of the ciphertext (0 disables grouping)")]
+    group: usize,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+/// Enum representing the mode of operation for the cipher.
+#[derive(Clone, Debug, ValueEnum)]
+enum OperationMode {
+    /// Encrypt mode
+    Encrypt,
+    /// Decrypt mode
+    Decrypt,
+}
+
+/// Main entry point for the ADFGVX cipher program.
+///
+/// The first argument is the program name, as with `std::env::args_os`.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+
+    let content: String = tool_error::fs::read_to_string(&cli.file)?;
+
+    let result = build_square(cli.square_key.as_deref(), cli.square.as_deref())
+        .and_then(|square| {
+            validate_key(&cli.key)?;
+            match cli.mode {
+                OperationMode::Encrypt => Ok(group(&encrypt(&content, &square, &cli.key), cli.group)),
+                OperationMode::Decrypt => decrypt(&content, &square, &cli.key),
+            }
+        });
+
+    let result = result.map_err(ToolError::data)?;
+
+    tool_error::fs::write(&cli.output, format!("{}\n", result))
+}
+
+/// Builds the Polybius square from a keyword or takes a complete square.
+///
+/// A keyword square starts with the distinct keyword symbols followed by the
+/// remaining letters A-Z and digits 0-9.
+fn build_square(keyword: Option<&str>, square: Option<&str>) -> Result<Vec<char>, String> {
+    let alnum = Alphabet::parse("alnum")?;
+
+    if let Some(square) = square {
+        let symbols: Vec<char> = square.chars().filter(|c| !c.is_whitespace()).map(|c| c.to_ascii_uppercase()).collect();
+        let valid = symbols.len() == alnum.len()
+            && (0..alnum.len()).all(|i| symbols.contains(&alnum.symbol(i)));
+        if !valid {
+            return Err("Square must contain every letter A-Z and digit 0-9 exactly once".to_string());
+        }
+        return Ok(symbols);
+    }
+
+    let mut symbols: Vec<char> = Vec::with_capacity(alnum.len());
+    let keyword = alnum.normalize(keyword.unwrap_or_default());
+    for c in keyword.chars().chain((0..alnum.len()).map(|i| alnum.symbol(i))) {
+        if !symbols.contains(&c) {
+            symbols.push(c);
+        }
+    }
+    Ok(symbols)
+}
+
+/// Checks that the transposition keyword contains at least two letters.
+fn validate_key(key: &str) -> Result<(), String> {
+    if key.chars().filter(|c| c.is_alphanumeric()).count() < 2 {
+        return Err("Transposition keyword must have at least two letters".to_string());
+    }
+    Ok(())
+}
+
+/// Column indices in the order they are read off: alphabetical by keyword
+/// letter, equal letters from left to right.
+fn column_order(key: &str) -> Vec<usize> {
+    let key: Vec<char> = key.chars()
+        .filter(|c| c.is_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let mut order: Vec<usize> = (0..key.len()).collect();
+    order.sort_by_key(|&i| key[i]);
+    order
+}
+
+/// Replaces every letter and digit by its pair of square labels.
+fn substitute(text: &str, square: &[char]) -> String {
+    Alphabet::parse("alnum").expect("valid preset")
+        .normalize(text)
+        .chars()
+        .flat_map(|c| {
+            let index = square.iter().position(|&s| s == c).expect("square contains all symbols");
+            [LABELS[index / 6], LABELS[index % 6]]
+        })
+        .collect()
+}
+
+/// Encrypts text with the ADFGVX cipher.
+///
+/// # Arguments
+///
+/// * `text` - The input text; only letters and digits are encrypted.
+/// * `square` - The 36 symbols of the Polybius square, row by row.
+/// * `key` - The transposition keyword.
+///
+/// # Returns
+///
+/// A `String` containing the ungrouped ciphertext.
+fn encrypt(text: &str, square: &[char], key: &str) -> String {
+    let intermediate: Vec<char> = substitute(text, square).chars().collect();
+    let order = column_order(key);
+    let columns = order.len();
+
+    order.iter()
+        .flat_map(|&column| intermediate.iter().skip(column).step_by(columns))
+        .collect()
+}
+
+/// Decrypts an ADFGVX ciphertext.
+///
+/// # Arguments
+///
+/// * `text` - The ciphertext; whitespace is ignored.
+/// * `square` - The 36 symbols of the Polybius square, row by row.
+/// * `key` - The transposition keyword.
+///
+/// # Returns
+///
+/// The plaintext in uppercase, or an error if the ciphertext is malformed.
+fn decrypt(text: &str, square: &[char], key: &str) -> Result<String, String> {
+    let ciphertext: Vec<char> = text.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if let Some(c) = ciphertext.iter().find(|c| !LABELS.contains(c)) {
+        return Err(format!("Invalid ciphertext character '{}'", c));
+    }
+    if !ciphertext.len().is_multiple_of(2) {
+        return Err("Ciphertext length must be even".to_string());
+    }
+
+    // Columns left of the last row's end are one symbol longer
+    let order = column_order(key);
+    let columns = order.len();
+    let rows = ciphertext.len().div_ceil(columns);
+    let long_columns = match ciphertext.len() % columns {
+        0 => columns,
+        remainder => remainder,
+    };
+
+    let mut intermediate = vec![' '; ciphertext.len()];
+    let mut position = 0;
+    for &column in &order {
+        let height = if column < long_columns { rows } else { rows - 1 };
+        for row in 0..height {
+            intermediate[row * columns + column] = ciphertext[position];
+            position += 1;
+        }
+    }
+
+    Ok(intermediate.chunks(2)
+        .map(|pair| {
+            let row = LABELS.iter().position(|&l| l == pair[0]).expect("validated label");
+            let column = LABELS.iter().position(|&l| l == pair[1]).expect("validated label");
+            square[row * 6 + column]
+        })
+        .collect())
+}
+
+/// Splits text into space-separated groups of `size` characters.
+fn group(text: &str, size: usize) -> String {
+    if size == 0 {
+        return text.to_string();
+    }
+    let chars: Vec<char> = text.chars().collect();
+    chars.chunks(size)
+        .map(|chunk| chunk.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Square of the well-known textbook example
+    const EXAMPLE_SQUARE: &str = "NA1C3H8TB2OME5WRPD4F6G7I9J0KLQSUVXYZ";
+
+    #[test]
+    fn test_textbook_example() {
+        let square = build_square(None, Some(EXAMPLE_SQUARE)).unwrap();
+        let ciphertext = encrypt("ATTACK AT 1200AM", &square, "PRIVACY");
+        assert_eq!(group(&ciphertext, 4), "DGDD DAGD DGAF ADDF DADV DVFA ADVX");
+        assert_eq!(decrypt(&ciphertext, &square, "PRIVACY").unwrap(), "ATTACKAT1200AM");
+    }
+
+    #[test]
+    fn test_keyword_square() {
+        let square: String = build_square(Some("Geheimnis"), None).unwrap().into_iter().collect();
+        assert_eq!(square, "GEHIMNSABCDFJKLOPQRTUVWXYZ0123456789");
+        assert!(build_square(None, Some("ABC")).is_err());
+    }
+
+    #[test]
+    fn test_roundtrip_with_incomplete_last_row() {
+        let square = build_square(Some("KRYPTOGRAPHIE"), None).unwrap();
+        for key in ["AB", "ZEBRA", "DEUTSCHLAND", "AAB"] {
+            let ciphertext = encrypt("Angriff im Morgengrauen 0530", &square, key);
+            assert_eq!(decrypt(&group(&ciphertext, 5), &square, key).unwrap(), "ANGRIFFIMMORGENGRAUEN0530");
+        }
+    }
+
+    #[test]
+    fn test_decrypt_rejects_invalid_ciphertext() {
+        let square = build_square(Some("KEY"), None).unwrap();
+        assert!(decrypt("ADFGB", &square, "KEY").is_err());
+        assert!(decrypt("ADF", &square, "KEY").is_err());
+    }
+}
@@ -0,0 +1,5 @@
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    tool_error::report(adfgvx::run(std::env::args_os()))
+}
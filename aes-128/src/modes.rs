@@ -0,0 +1,161 @@
+//! AES-128 als Blockchiffre für die Betriebsmodi aus `cipher-modes`
+//!
+//! Die Implementierung dafür liegt inzwischen in `cipher-modes` selbst
+//! ([`cipher_modes::cipher::Aes`]), damit die Modi ohne diese Crate mit AES
+//! laufen. [`Aes128`] wird hier weiter angeboten; die Testvektoren der Modi
+//! mit AES stehen unten. Das Kommandozeilenwerkzeug verwendet weiterhin die
+//! ausführlich kommentierte Implementierung aus `lib.rs`.
+
+pub use cipher_modes::cipher::Aes128;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Aes, BLOCK_SIZE};
+    use cipher_modes::{BlockCipher, CipherModeError, CipherModes, KeyInit, Padding};
+
+    #[test]
+    fn test_matches_lab_implementation() {
+        // Beide Implementierungen müssen dieselben Blöcke liefern
+        let key: [u8; 16] = std::array::from_fn(|i| (i * 17) as u8);
+        let (lab, cipher) = (Aes::new(&key), Aes128::new(&key).unwrap());
+        for seed in 0..16u8 {
+            let mut expected: [u8; BLOCK_SIZE] = std::array::from_fn(|i| seed.wrapping_mul(31).wrapping_add(i as u8 * 7));
+            let mut block = expected;
+            lab.encrypt_block(&mut expected);
+            cipher.encrypt_block(&mut block);
+            assert_eq!(block, expected);
+            cipher.decrypt_block(&mut block);
+            lab.decrypt_block(&mut expected);
+            assert_eq!(block, expected);
+        }
+    }
+
+    #[test]
+    fn test_cbc_matches_sp800_38a() {
+        // NIST SP 800-38A, F.2.1 CBC-AES128.Encrypt, erste zwei Blöcke
+        let key = codec::hex::decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+        let iv = codec::hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let plaintext = codec::hex::decode("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51").unwrap();
+        let cipher = Aes128::new(&key).unwrap();
+
+        let ciphertext = CipherModes::cbc_encrypt(&cipher, &iv, &plaintext, Padding::None).unwrap();
+        assert_eq!(codec::hex::encode(&ciphertext), "7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b2");
+        assert_eq!(CipherModes::cbc_decrypt(&cipher, &iv, &ciphertext, Padding::None).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn test_cmac_matches_rfc4493() {
+        // RFC 4493, Abschnitt 4: Nachrichten mit 0, 16, 40 und 64 Bytes
+        let key = codec::hex::decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+        let message = codec::hex::decode(
+            "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+             30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710",
+        )
+        .unwrap();
+        let tags = [
+            (0, "bb1d6929e95937287fa37d129b756746"),
+            (16, "070a16b46b4d4144f79bdd9dd04a287c"),
+            (40, "dfa66747de9ae63030ca32611497c827"),
+            (64, "51f0bebf7e3b9d92fc49741779363cfe"),
+        ];
+        let cipher = Aes128::new(&key).unwrap();
+        for (length, tag) in tags {
+            assert_eq!(codec::hex::encode(&cipher_modes::mac::cmac(&cipher, &message[..length]).unwrap()), tag);
+        }
+    }
+
+    #[test]
+    fn test_eax_matches_paper() {
+        // Bellare, Rogaway, Wagner: "The EAX Mode of Operation", Anhang, erste drei Vektoren
+        let vectors = [
+            ("233952dee4d5ed5f9b9c6d6ff80ff478", "62ec67f9c3a4a407fcb2a8c49031a8b3", "6bfb914fd07eae6b", "", "e037830e8389f27b025a2d6527e79d01"),
+            ("91945d3f4dcbee0bf45ef52255f095a4", "becaf043b0a23d843194ba972c66debd", "fa3bfd4806eb53fa", "f7fb", "19dd5c4c9331049d0bdab0277408f67967e5"),
+            ("01f74ad64077f2e704c0f60ada3dd523", "70c3db4f0d26368400a10ed05d2bff5e", "234a3463c1264ac6", "1a47cb4933", "d851d5bae03a59f238a23e39199dc9266626c40f80"),
+        ];
+        for (key, nonce, header, message, expected) in vectors {
+            let [key, nonce, header, message] = [key, nonce, header, message].map(|hex| codec::hex::decode(hex).unwrap());
+            let cipher = Aes128::new(&key).unwrap();
+            let ciphertext = cipher_modes::eax_encrypt(&cipher, &nonce, &header, &message).unwrap();
+            assert_eq!(codec::hex::encode(&ciphertext), expected);
+            assert_eq!(cipher_modes::eax_decrypt(&cipher, &nonce, &header, &ciphertext).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn test_key_wrap_matches_rfc3394() {
+        // RFC 3394, Abschnitt 4.1: 128 Bit Schlüsseldaten mit 128-Bit-KEK
+        let kek = codec::hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let key = codec::hex::decode("00112233445566778899aabbccddeeff").unwrap();
+        let cipher = Aes128::new(&kek).unwrap();
+
+        let wrapped = cipher_modes::key_wrap(&cipher, &key).unwrap();
+        assert_eq!(codec::hex::encode(&wrapped), "1fa68b0a8112b447aef34bd8fb5a7b829d3e862371d2cfe5");
+        assert_eq!(cipher_modes::key_unwrap(&cipher, &wrapped).unwrap(), key);
+    }
+
+    #[test]
+    fn test_key_unwrap_rejects_tampering() {
+        // Jedes veränderte Byte und ein falscher KEK fallen auf, mit und ohne Padding
+        let cipher = Aes128::new(&[0x2b; 16]).unwrap();
+        let other = Aes128::new(&[0x2c; 16]).unwrap();
+        let wrapped = cipher_modes::key_wrap(&cipher, &[0x42; 24]).unwrap();
+        let padded = cipher_modes::key_wrap_padded(&cipher, &[0x42; 5]).unwrap();
+        for position in 0..wrapped.len() {
+            let mut tampered = wrapped.clone();
+            tampered[position] ^= 1;
+            assert_eq!(cipher_modes::key_unwrap(&cipher, &tampered), Err(CipherModeError::AuthenticationFailed));
+        }
+        for position in 0..padded.len() {
+            let mut tampered = padded.clone();
+            tampered[position] ^= 1;
+            assert_eq!(cipher_modes::key_unwrap_padded(&cipher, &tampered), Err(CipherModeError::AuthenticationFailed));
+        }
+        assert_eq!(cipher_modes::key_unwrap(&other, &wrapped), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(cipher_modes::key_unwrap_padded(&other, &padded), Err(CipherModeError::AuthenticationFailed));
+    }
+
+    #[test]
+    fn test_siv_matches_rfc5297() {
+        // RFC 5297, Anhang A.1 (deterministisch) und A.2 (Nonce als letzter Header)
+        let vectors: [(&str, &[&str], &str, &str); 2] = [
+            (
+                "fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff",
+                &["101112131415161718191a1b1c1d1e1f2021222324252627"],
+                "112233445566778899aabbccddee",
+                "85632d07c6e8f37f950acd320a2ecc9340c02b9690c4dc04daef7f6afe5c",
+            ),
+            (
+                "7f7e7d7c7b7a79787776757473727170404142434445464748494a4b4c4d4e4f",
+                &[
+                    "00112233445566778899aabbccddeeffdeaddadadeaddadaffeeddccbbaa99887766554433221100",
+                    "102030405060708090a0",
+                    "09f911029d74e35bd84156c5635688c0",
+                ],
+                "7468697320697320736f6d6520706c61696e7465787420746f20656e6372797074207573696e67205349562d414553",
+                "7bdb6e3b432667eb06f4d14bff2fbd0fcb900f2fddbe404326601965c889bf17dba77ceb094fa663b7a3f748ba8af829ea64ad544a272e9c485b62a3fd5c0d",
+            ),
+        ];
+        for (key, headers, plaintext, expected) in vectors {
+            let key = codec::hex::decode(key).unwrap();
+            let headers: Vec<Vec<u8>> = headers.iter().map(|header| codec::hex::decode(header).unwrap()).collect();
+            let headers: Vec<&[u8]> = headers.iter().map(Vec::as_slice).collect();
+            let plaintext = codec::hex::decode(plaintext).unwrap();
+            let (mac, ctr) = (Aes128::new(&key[..16]).unwrap(), Aes128::new(&key[16..]).unwrap());
+
+            let ciphertext = cipher_modes::siv_encrypt(&mac, &ctr, &headers, &plaintext).unwrap();
+            assert_eq!(codec::hex::encode(&ciphertext), expected);
+            assert_eq!(cipher_modes::siv_decrypt(&mac, &ctr, &headers, &ciphertext).unwrap(), plaintext);
+            for position in 0..ciphertext.len() {
+                let mut tampered = ciphertext.clone();
+                tampered[position] ^= 1;
+                assert_eq!(cipher_modes::siv_decrypt(&mac, &ctr, &headers, &tampered), Err(CipherModeError::AuthenticationFailed));
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_wrong_key_length() {
+        assert_eq!(Aes128::new(&[0; 24]).unwrap_err(), CipherModeError::InvalidKeyLength { expected: 16, got: 24 });
+    }
+}
@@ -0,0 +1,483 @@
+//! Das Nötigste aus ASN.1 DER
+//!
+//! Gemeinsame Kodierung für die PEM-Ausgabe von `dh_params`, die DigestInfo
+//! in PKCS #1, DSA-Signaturen als `SEQUENCE { r, s }` und die Zertifikate
+//! von `x509-lite`. Unterstützt werden INTEGER (nur nicht negativ), BOOLEAN,
+//! NULL, OBJECT IDENTIFIER, BIT STRING (ganze Bytes), OCTET STRING,
+//! UTF8String, UTCTime/GeneralizedTime sowie SEQUENCE, SET und
+//! kontextspezifische Tags.
+//!
+//! Jedes Element ist Tag, Länge und Inhalt (TLV). Die Kodierer geben
+//! fertige TLV-Bytes zurück, die sich einfach aneinanderhängen lassen;
+//! [`Reader`] liest sie in derselben Reihenfolge wieder und lehnt alles ab,
+//! was nicht die eindeutige DER-Form hat.
+
+use num_bigint::BigUint;
+use std::fmt;
+
+pub const BOOLEAN: u8 = 0x01;
+pub const INTEGER: u8 = 0x02;
+pub const BIT_STRING: u8 = 0x03;
+pub const OCTET_STRING: u8 = 0x04;
+pub const NULL: u8 = 0x05;
+pub const OID: u8 = 0x06;
+pub const UTF8_STRING: u8 = 0x0c;
+pub const UTC_TIME: u8 = 0x17;
+pub const GENERALIZED_TIME: u8 = 0x18;
+pub const SEQUENCE: u8 = 0x30;
+pub const SET: u8 = 0x31;
+
+/// Tag eines zusammengesetzten, kontextspezifischen Elements `[n]`
+pub const fn context(number: u8) -> u8 {
+    0xa0 | number
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DerError {
+    Truncated,
+    UnexpectedTag { expected: u8, found: u8 },
+    /// Unbestimmte oder nicht minimal kodierte Länge
+    Length,
+    TrailingData,
+    Integer,
+    Oid,
+    BitString,
+    Boolean,
+    Text,
+    Time,
+}
+
+impl fmt::Display for DerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DerError::Truncated => write!(f, "DER-Daten sind abgeschnitten"),
+            DerError::UnexpectedTag { expected, found } => write!(f, "Tag 0x{:02x} erwartet, 0x{:02x} gefunden", expected, found),
+            DerError::Length => write!(f, "Länge ist nicht in DER-Form"),
+            DerError::TrailingData => write!(f, "Überzählige Bytes nach dem letzten Element"),
+            DerError::Integer => write!(f, "INTEGER ist negativ oder nicht minimal kodiert"),
+            DerError::Oid => write!(f, "Ungültiger OBJECT IDENTIFIER"),
+            DerError::BitString => write!(f, "BIT STRING mit ungenutzten Bits wird nicht unterstützt"),
+            DerError::Boolean => write!(f, "BOOLEAN muss 0x00 oder 0xff sein"),
+            DerError::Text => write!(f, "Zeichenkette ist kein UTF-8"),
+            DerError::Time => write!(f, "Ungültige Zeitangabe"),
+        }
+    }
+}
+
+impl std::error::Error for DerError {}
+
+/// Kodiert eine Länge (Kurzform bis 127, sonst Langform)
+fn encode_length(length: usize) -> Vec<u8> {
+    if length < 0x80 {
+        return vec![length as u8];
+    }
+
+    let bytes: Vec<u8> = length.to_be_bytes().iter().copied().skip_while(|&b| b == 0).collect();
+    let mut encoded = vec![0x80 | bytes.len() as u8];
+    encoded.extend(bytes);
+    encoded
+}
+
+pub fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![tag];
+    encoded.extend(encode_length(content.len()));
+    encoded.extend_from_slice(content);
+    encoded
+}
+
+/// Hängt bereits kodierte Elemente zu einem zusammengesetzten Element zusammen
+pub fn constructed(tag: u8, elements: &[Vec<u8>]) -> Vec<u8> {
+    tlv(tag, &elements.concat())
+}
+
+pub fn sequence(elements: &[Vec<u8>]) -> Vec<u8> {
+    constructed(SEQUENCE, elements)
+}
+
+/// Nicht-negative Zahl, mit führendem Nullbyte, wenn das höchste Bit gesetzt ist
+pub fn integer(value: &BigUint) -> Vec<u8> {
+    let mut bytes = value.to_bytes_be();
+    if bytes[0] & 0x80 != 0 {
+        bytes.insert(0, 0);
+    }
+    tlv(INTEGER, &bytes)
+}
+
+pub fn boolean(value: bool) -> Vec<u8> {
+    tlv(BOOLEAN, &[if value { 0xff } else { 0x00 }])
+}
+
+pub fn null() -> Vec<u8> {
+    tlv(NULL, &[])
+}
+
+/// OID aus seinen Bögen; die ersten beiden teilen sich ein Byte (40·a + b)
+pub fn oid(arcs: &[u64]) -> Vec<u8> {
+    let mut content = Vec::new();
+    for arc in std::iter::once(arcs[0] * 40 + arcs[1]).chain(arcs[2..].iter().copied()) {
+        // Basis 128, höchstes Bit gesetzt außer im letzten Byte
+        let mut groups = vec![(arc & 0x7f) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            groups.push((rest & 0x7f) as u8 | 0x80);
+            rest >>= 7;
+        }
+        content.extend(groups.iter().rev());
+    }
+    tlv(OID, &content)
+}
+
+/// Bitfolge aus ganzen Bytes (kein ungenutztes Bit am Ende)
+pub fn bit_string(bytes: &[u8]) -> Vec<u8> {
+    tlv(BIT_STRING, &[&[0], bytes].concat())
+}
+
+pub fn octet_string(bytes: &[u8]) -> Vec<u8> {
+    tlv(OCTET_STRING, bytes)
+}
+
+pub fn utf8_string(text: &str) -> Vec<u8> {
+    tlv(UTF8_STRING, text.as_bytes())
+}
+
+/// Zeitpunkt in Sekunden seit 1970: UTCTime bis 2049, danach GeneralizedTime (RFC 5280, 4.1.2.5)
+pub fn time(unix: u64) -> Vec<u8> {
+    let (year, month, day, hour, minute, second) = civil_from_unix(unix);
+    if (1950..2050).contains(&year) {
+        tlv(UTC_TIME, format!("{:02}{:02}{:02}{:02}{:02}{:02}Z", year % 100, month, day, hour, minute, second).as_bytes())
+    } else {
+        tlv(GENERALIZED_TIME, format!("{:04}{:02}{:02}{:02}{:02}{:02}Z", year, month, day, hour, minute, second).as_bytes())
+    }
+}
+
+/// Datum und Uhrzeit (UTC) zu Sekunden seit 1970 (Kalenderrechnung nach H. Hinnant)
+fn civil_from_unix(unix: u64) -> (u64, u64, u64, u64, u64, u64) {
+    let days = unix / 86400;
+    let seconds = unix % 86400;
+    // Tage ab dem 1. März 0000, damit der Schalttag am Jahresende liegt
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+    (year, month, day, seconds / 3600, seconds % 3600 / 60, seconds % 60)
+}
+
+fn unix_from_civil(year: u64, month: u64, day: u64, hour: u64, minute: u64, second: u64) -> Option<u64> {
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        _ => return None,
+    };
+    if year < 1970 || day == 0 || day > days_in_month || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    let (year, month) = if month <= 2 { (year - 1, month + 9) } else { (year, month - 3) };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146_097 + day_of_era).checked_sub(719_468)?;
+    Some(days * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+/// Zeitpunkt als `JJJJ-MM-TT HH:MM:SS UTC`
+pub fn format_time(unix: u64) -> String {
+    let (year, month, day, hour, minute, second) = civil_from_unix(unix);
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC", year, month, day, hour, minute, second)
+}
+
+/// Liest DER-Elemente nacheinander aus einem Byte-Slice
+pub struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Reader(bytes)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.0.first().copied()
+    }
+
+    /// Fehler, wenn nach dem letzten erwarteten Element noch Bytes folgen
+    pub fn finish(&self) -> Result<(), DerError> {
+        if self.0.is_empty() { Ok(()) } else { Err(DerError::TrailingData) }
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], DerError> {
+        if self.0.len() < length {
+            return Err(DerError::Truncated);
+        }
+        let (head, rest) = self.0.split_at(length);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn length(&mut self) -> Result<usize, DerError> {
+        let first = self.take(1)?[0];
+        if first < 0x80 {
+            return Ok(first as usize);
+        }
+        let count = (first & 0x7f) as usize;
+        // 0x80 wäre die unbestimmte Länge aus BER
+        if count == 0 || count > size_of::<usize>() {
+            return Err(DerError::Length);
+        }
+        let bytes = self.take(count)?;
+        let length = bytes.iter().fold(0usize, |length, &b| (length << 8) | b as usize);
+        if bytes[0] == 0 || length < 0x80 {
+            return Err(DerError::Length);
+        }
+        Ok(length)
+    }
+
+    /// Ganzes Element samt Tag und Länge, etwa um es unverändert zu signieren
+    pub fn raw(&mut self, tag: u8) -> Result<&'a [u8], DerError> {
+        let start = self.0;
+        self.read(tag)?;
+        Ok(&start[..start.len() - self.0.len()])
+    }
+
+    /// Inhalt des nächsten Elements, das das Tag `tag` haben muss
+    pub fn read(&mut self, tag: u8) -> Result<&'a [u8], DerError> {
+        let found = self.take(1)?[0];
+        if found != tag {
+            return Err(DerError::UnexpectedTag { expected: tag, found });
+        }
+        let length = self.length()?;
+        self.take(length)
+    }
+
+    /// Liest das Element `[n]` nur, wenn es als nächstes kommt
+    pub fn optional(&mut self, tag: u8) -> Result<Option<Reader<'a>>, DerError> {
+        if self.peek_tag() == Some(tag) { self.read(tag).map(|content| Some(Reader(content))) } else { Ok(None) }
+    }
+
+    pub fn constructed(&mut self, tag: u8) -> Result<Reader<'a>, DerError> {
+        self.read(tag).map(Reader)
+    }
+
+    pub fn sequence(&mut self) -> Result<Reader<'a>, DerError> {
+        self.constructed(SEQUENCE)
+    }
+
+    pub fn integer(&mut self) -> Result<BigUint, DerError> {
+        let content = self.read(INTEGER)?;
+        match content {
+            [] => Err(DerError::Integer),
+            [byte, ..] if byte & 0x80 != 0 => Err(DerError::Integer),
+            [0, next, ..] if next & 0x80 == 0 => Err(DerError::Integer),
+            _ => Ok(BigUint::from_bytes_be(content)),
+        }
+    }
+
+    pub fn boolean(&mut self) -> Result<bool, DerError> {
+        match self.read(BOOLEAN)? {
+            [0x00] => Ok(false),
+            [0xff] => Ok(true),
+            _ => Err(DerError::Boolean),
+        }
+    }
+
+    pub fn null(&mut self) -> Result<(), DerError> {
+        if self.read(NULL)?.is_empty() { Ok(()) } else { Err(DerError::Length) }
+    }
+
+    pub fn oid(&mut self) -> Result<Vec<u64>, DerError> {
+        let content = self.read(OID)?;
+        if content.last().is_none_or(|&b| b & 0x80 != 0) {
+            return Err(DerError::Oid);
+        }
+        let mut arcs = Vec::new();
+        let mut value = 0u64;
+        let mut first_byte = true;
+        for &byte in content {
+            // Ein führendes 0x80 wäre eine nicht minimale Kodierung
+            if first_byte && byte == 0x80 {
+                return Err(DerError::Oid);
+            }
+            if value > u64::MAX >> 7 {
+                return Err(DerError::Oid);
+            }
+            value = (value << 7) | (byte & 0x7f) as u64;
+            first_byte = byte & 0x80 == 0;
+            if first_byte {
+                if arcs.is_empty() {
+                    let first = (value / 40).min(2);
+                    arcs.extend([first, value - 40 * first]);
+                } else {
+                    arcs.push(value);
+                }
+                value = 0;
+            }
+        }
+        Ok(arcs)
+    }
+
+    pub fn bit_string(&mut self) -> Result<&'a [u8], DerError> {
+        match self.read(BIT_STRING)? {
+            [0, bytes @ ..] => Ok(bytes),
+            _ => Err(DerError::BitString),
+        }
+    }
+
+    pub fn octet_string(&mut self) -> Result<&'a [u8], DerError> {
+        self.read(OCTET_STRING)
+    }
+
+    pub fn utf8_string(&mut self) -> Result<&'a str, DerError> {
+        std::str::from_utf8(self.read(UTF8_STRING)?).map_err(|_| DerError::Text)
+    }
+
+    /// UTCTime oder GeneralizedTime, jeweils in UTC mit Sekunden
+    pub fn time(&mut self) -> Result<u64, DerError> {
+        let (text, year_digits) = match self.peek_tag() {
+            Some(GENERALIZED_TIME) => (self.read(GENERALIZED_TIME)?, 4),
+            _ => (self.read(UTC_TIME)?, 2),
+        };
+        let digits = text.strip_suffix(b"Z").filter(|digits| digits.len() == year_digits + 10 && digits.iter().all(u8::is_ascii_digit))
+            .ok_or(DerError::Time)?;
+        let number = |range: std::ops::Range<usize>| digits[range].iter().fold(0u64, |n, &d| n * 10 + (d - b'0') as u64);
+
+        let mut year = number(0..year_digits);
+        if year_digits == 2 {
+            year += if year < 50 { 2000 } else { 1900 };
+        }
+        let rest = year_digits;
+        unix_from_civil(year, number(rest..rest + 2), number(rest + 2..rest + 4), number(rest + 4..rest + 6), number(rest + 6..rest + 8), number(rest + 8..rest + 10))
+            .ok_or(DerError::Time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer() {
+        assert_eq!(integer(&BigUint::from(0u32)), vec![0x02, 0x01, 0x00]);
+        assert_eq!(integer(&BigUint::from(0x80u32)), vec![0x02, 0x02, 0x00, 0x80]);
+        for value in [0u32, 1, 0x7f, 0x80, 0xffff, 65537] {
+            assert_eq!(Reader::new(&integer(&value.into())).integer().unwrap(), BigUint::from(value));
+        }
+        // Negativ bzw. überflüssige führende Null
+        assert_eq!(Reader::new(&[0x02, 0x01, 0x80]).integer(), Err(DerError::Integer));
+        assert_eq!(Reader::new(&[0x02, 0x02, 0x00, 0x01]).integer(), Err(DerError::Integer));
+        assert_eq!(Reader::new(&[0x02, 0x00]).integer(), Err(DerError::Integer));
+    }
+
+    #[test]
+    fn test_length() {
+        assert_eq!(encode_length(0x7f), vec![0x7f]);
+        assert_eq!(encode_length(0x80), vec![0x81, 0x80]);
+        assert_eq!(encode_length(0x0102), vec![0x82, 0x01, 0x02]);
+
+        let long = octet_string(&[7; 300]);
+        assert_eq!(Reader::new(&long).octet_string().unwrap(), &[7; 300]);
+        assert_eq!(Reader::new(&[0x04, 0x81, 0x05, 0, 0, 0, 0, 0]).octet_string(), Err(DerError::Length));
+        assert_eq!(Reader::new(&[0x04, 0x80, 0x00, 0x00]).octet_string(), Err(DerError::Length));
+        assert_eq!(Reader::new(&[0x04, 0x05, 0, 0]).octet_string(), Err(DerError::Truncated));
+    }
+
+    #[test]
+    fn test_oid() {
+        // sha256WithRSAEncryption
+        let encoded = oid(&[1, 2, 840, 113549, 1, 1, 11]);
+        assert_eq!(encoded, [0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b]);
+        assert_eq!(Reader::new(&encoded).oid().unwrap(), vec![1, 2, 840, 113549, 1, 1, 11]);
+        assert_eq!(Reader::new(&oid(&[2, 5, 4, 3])).oid().unwrap(), vec![2, 5, 4, 3]);
+        assert_eq!(Reader::new(&oid(&[2, 999, 3])).oid().unwrap(), vec![2, 999, 3]);
+        assert_eq!(Reader::new(&[0x06, 0x02, 0x2a, 0x86]).oid(), Err(DerError::Oid));
+        assert_eq!(Reader::new(&[0x06, 0x02, 0x80, 0x01]).oid(), Err(DerError::Oid));
+    }
+
+    #[test]
+    fn test_sequence_and_tags() {
+        let encoded = sequence(&[boolean(true), null(), utf8_string("Zertifikat"), bit_string(&[1, 2])]);
+        let mut outer = Reader::new(&encoded);
+        let mut inner = outer.sequence().unwrap();
+        outer.finish().unwrap();
+        assert!(inner.boolean().unwrap());
+        inner.null().unwrap();
+        assert_eq!(inner.utf8_string().unwrap(), "Zertifikat");
+        assert_eq!(inner.bit_string().unwrap(), &[1, 2]);
+        assert!(inner.is_empty());
+
+        let mut reader = Reader::new(&encoded);
+        assert_eq!(reader.integer(), Err(DerError::UnexpectedTag { expected: INTEGER, found: SEQUENCE }));
+        let both = [encoded.clone(), null()].concat();
+        let mut reader = Reader::new(&both);
+        assert_eq!(reader.raw(SEQUENCE).unwrap(), &encoded[..]);
+        assert_eq!(reader.finish(), Err(DerError::TrailingData));
+        assert!(reader.optional(context(0)).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_nested_roundtrip() {
+        let big = BigUint::from_bytes_be(&[0xab; 300]);
+        let encoded = sequence(&[
+            constructed(context(0), &[integer(&BigUint::from(2u32))]),
+            constructed(SET, &[sequence(&[oid(&[2, 5, 4, 3]), utf8_string("Ä")])]),
+            integer(&big),
+            octet_string(&sequence(&[boolean(false)])),
+        ]);
+
+        let mut outer = Reader::new(&encoded);
+        let mut reader = outer.sequence().unwrap();
+        outer.finish().unwrap();
+        let mut version = reader.optional(context(0)).unwrap().unwrap();
+        assert_eq!(version.integer().unwrap(), BigUint::from(2u32));
+        version.finish().unwrap();
+        let mut attribute = reader.constructed(SET).unwrap().sequence().unwrap();
+        assert_eq!(attribute.oid().unwrap(), vec![2, 5, 4, 3]);
+        assert_eq!(attribute.utf8_string().unwrap(), "Ä");
+        assert_eq!(reader.integer().unwrap(), big);
+        let mut wrapped = Reader::new(reader.octet_string().unwrap());
+        assert!(!wrapped.sequence().unwrap().boolean().unwrap());
+        reader.finish().unwrap();
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        assert_eq!(Reader::new(&[]).sequence().err(), Some(DerError::Truncated));
+        assert_eq!(Reader::new(&[0x30]).sequence().err(), Some(DerError::Truncated));
+        assert_eq!(Reader::new(&[0x30, 0x82, 0x01]).sequence().err(), Some(DerError::Truncated));
+        // Länge mit mehr Bytes, als ein usize hat
+        assert_eq!(Reader::new(&[0x30, 0x89, 1, 0, 0, 0, 0, 0, 0, 0, 0]).sequence().err(), Some(DerError::Length));
+        assert_eq!(Reader::new(&[0x01, 0x01, 0x01]).boolean(), Err(DerError::Boolean));
+        assert_eq!(Reader::new(&[0x05, 0x01, 0x00]).null(), Err(DerError::Length));
+        assert_eq!(Reader::new(&[0x03, 0x02, 0x04, 0xf0]).bit_string(), Err(DerError::BitString));
+        assert_eq!(Reader::new(&[0x03, 0x00]).bit_string(), Err(DerError::BitString));
+        assert_eq!(Reader::new(&[0x0c, 0x01, 0xff]).utf8_string(), Err(DerError::Text));
+        assert_eq!(Reader::new(&[0x06, 0x00]).oid(), Err(DerError::Oid));
+        // Ein Bogen, der nicht in 64 Bit passt
+        assert_eq!(Reader::new(&[0x06, 0x0b, 0x2a, 0x82, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00]).oid(), Err(DerError::Oid));
+    }
+
+    #[test]
+    fn test_time() {
+        for (unix, encoded) in [
+            (0, "700101000000Z"),
+            (951_782_400, "000229000000Z"),
+            (1_767_225_599, "251231235959Z"),
+            (2_524_608_000, "20500101000000Z"),
+        ] {
+            let der = time(unix);
+            assert_eq!(&der[2..], encoded.as_bytes(), "{}", unix);
+            assert_eq!(Reader::new(&der).time().unwrap(), unix);
+        }
+        assert_eq!(format_time(951_782_400), "2000-02-29 00:00:00 UTC");
+        assert_eq!(Reader::new(&tlv(UTC_TIME, b"250230000000Z")).time(), Err(DerError::Time));
+        assert_eq!(Reader::new(&tlv(UTC_TIME, b"2501010000Z")).time(), Err(DerError::Time));
+    }
+}
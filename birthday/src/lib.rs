@@ -0,0 +1,182 @@
+//! birthday: Geburtstagsangriff auf gekürzte Hashwerte
+//!
+//! Betrachtet werden nur die ersten n Bit von SHA3-224 (die eigene
+//! Implementierung aus `sha3`) oder SHA-256. Nach dem Geburtstagsparadoxon
+//! genügen etwa √(π/2 · 2^n) Hashwerte für eine Kollision statt 2^n für ein
+//! vorgegebenes Urbild. Die Suche iteriert x ↦ H(x) gekürzt auf n Bit und
+//! findet die Kollision mit Pollards Rho-Methode ohne nennenswerten
+//! Speicher. Ausgegeben werden die beiden Nachrichten, ihr gemeinsames
+//! Präfix und der Aufwand im Vergleich zur Geburtstagsschranke.
+
+use clap::{Parser, ValueEnum};
+use csprng::RngArgs;
+use logging::{Verbosity, debug, info};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::time::Instant;
+use tool_error::ToolError;
+
+pub mod rho;
+
+use rho::{Collision, birthday_bound, find_collision};
+
+/// Größte unterstützte Präfixlänge; 64 Bit würden bereits Jahre dauern
+const MAX_BITS: u32 = 64;
+
+/// Hashfunktion, deren Präfix angegriffen wird
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HashFunction {
+    #[value(name = "sha3-224")]
+    Sha3_224,
+    #[value(name = "sha256")]
+    Sha256,
+}
+
+impl HashFunction {
+    pub fn digest(self, message: &[u8]) -> Vec<u8> {
+        match self {
+            HashFunction::Sha3_224 => sha3::sha3_224(message),
+            HashFunction::Sha256 => Sha256::digest(message).to_vec(),
+        }
+    }
+}
+
+#[derive(Parser)]
+#[command(name = "birthday")]
+#[command(about = "Sucht Kollisionen auf den ersten n Bit von SHA3-224 oder SHA-256 (Pollard-Rho)")]
+#[command(version = "1.0")]
+struct Args {
+    /// Anzahl der Bits des Hashwerts, die übereinstimmen sollen
+    #[arg(short = 'n', long = "bits", default_value = "32")]
+    bits: u32,
+
+    /// Hashfunktion
+    #[arg(long = "hash", value_enum, default_value_t = HashFunction::Sha3_224)]
+    hash: HashFunction,
+
+    /// Anzahl unabhängiger Suchen; der mittlere Aufwand nähert sich der Schranke
+    #[arg(short = 'r', long = "runs", default_value = "1")]
+    runs: u32,
+
+    #[command(flatten)]
+    rng: RngArgs,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+/// Die Abbildung x ↦ erste `bits` Bit von H(x) auf n-Bit-Werten
+///
+/// Ein Wert wird als Nachricht mit ⌈n/8⌉ Bytes in Big Endian gehasht.
+pub struct TruncatedHash {
+    hash: HashFunction,
+    bits: u32,
+    evaluations: u64,
+}
+
+impl TruncatedHash {
+    pub fn new(hash: HashFunction, bits: u32) -> Self {
+        assert!((1..=MAX_BITS).contains(&bits), "Präfixlänge muss zwischen 1 und {} liegen", MAX_BITS);
+        TruncatedHash { hash, bits, evaluations: 0 }
+    }
+
+    /// Die Nachricht, als die ein n-Bit-Wert gehasht wird
+    pub fn message(&self, value: u64) -> Vec<u8> {
+        value.to_be_bytes()[8 - self.bits.div_ceil(8) as usize..].to_vec()
+    }
+
+    pub fn apply(&mut self, value: u64) -> u64 {
+        self.evaluations += 1;
+        let digest = self.hash.digest(&self.message(value));
+        let prefix = u64::from_be_bytes(digest[..8].try_into().expect("Hashwert hat mindestens 8 Bytes"));
+        prefix >> (64 - self.bits)
+    }
+
+    pub fn evaluations(&self) -> u64 {
+        self.evaluations
+    }
+}
+
+/// Sucht mit zufälligen Startwerten, bis eine Kollision gefunden ist
+pub fn search(f: &mut TruncatedHash, rng: &mut impl Rng) -> Collision {
+    let mask = u64::MAX >> (64 - f.bits);
+    loop {
+        let start = rng.r#gen::<u64>() & mask;
+        if let Some(collision) = find_collision(start, |x| f.apply(x)) {
+            return collision;
+        }
+        debug!("Startwert {:#x} liegt auf dem Zyklus, neuer Versuch", start);
+    }
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+
+    if !(1..=MAX_BITS).contains(&args.bits) {
+        return Err(ToolError::usage(format!("--bits muss zwischen 1 und {} liegen", MAX_BITS)));
+    }
+    if args.runs == 0 {
+        return Err(ToolError::usage("--runs muss mindestens 1 sein"));
+    }
+
+    let mut rng = args.rng.rng();
+    let expected = birthday_bound(args.bits);
+    info!("Erwarteter Aufwand für {} Bit: etwa {:.0} Hashwerte", args.bits, expected);
+
+    let mut total = 0u64;
+    for run in 1..=args.runs {
+        let started = Instant::now();
+        let mut f = TruncatedHash::new(args.hash, args.bits);
+        let collision = search(&mut f, &mut rng);
+        total += f.evaluations();
+
+        let (a, b) = (f.message(collision.a), f.message(collision.b));
+        println!("Kollision {}:", run);
+        println!("  m1 = {}  H(m1) = {}", codec::hex::encode(&a), codec::hex::encode(&args.hash.digest(&a)));
+        println!("  m2 = {}  H(m2) = {}", codec::hex::encode(&b), codec::hex::encode(&args.hash.digest(&b)));
+        println!("  {} Hashauswertungen in {:.2?}", f.evaluations(), started.elapsed());
+    }
+
+    let mean = total as f64 / args.runs as f64;
+    println!("Geburtstagsschranke √(π/2 · 2^{}): {:.0} Hashwerte", args.bits, expected);
+    println!("Mittlerer Aufwand: {:.0} Hashauswertungen ({:.2} × Schranke, Floyd braucht etwa 3 pro Schritt)", mean, mean / expected);
+    println!("Zum Vergleich, zweites Urbild: etwa 2^{} = {:.0} Hashwerte", args.bits, 2f64.powi(args.bits as i32));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::{Algorithm, Drbg};
+
+    #[test]
+    fn test_truncation() {
+        let mut f = TruncatedHash::new(HashFunction::Sha256, 12);
+        assert_eq!(f.message(0xabc), [0x0a, 0xbc]);
+        // SHA-256(0a bc) beginnt mit den Bits des ersten Bytes und der oberen Hälfte des zweiten
+        let digest = Sha256::digest([0x0a, 0xbc]);
+        assert_eq!(f.apply(0xabc), (digest[0] as u64) << 4 | (digest[1] >> 4) as u64);
+        assert_eq!(f.evaluations(), 1);
+    }
+
+    #[test]
+    fn test_finds_collisions() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"birthday");
+        for hash in [HashFunction::Sha3_224, HashFunction::Sha256] {
+            let mut f = TruncatedHash::new(hash, 20);
+            let collision = search(&mut f, &mut rng);
+            let (a, b) = (f.message(collision.a), f.message(collision.b));
+            assert_ne!(a, b);
+
+            let (ha, hb) = (hash.digest(&a), hash.digest(&b));
+            assert_eq!(ha[..2], hb[..2]);
+            assert_eq!(ha[2] >> 4, hb[2] >> 4);
+        }
+    }
+}
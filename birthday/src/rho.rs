@@ -0,0 +1,76 @@
+//! Kollisionssuche nach Pollard (Rho-Methode) mit Floyds Zyklenerkennung
+//!
+//! Die Folge x_(i+1) = f(x_i) über einer endlichen Menge läuft nach μ
+//! Schritten in einen Zyklus der Länge λ. Der Eintrittspunkt in den Zyklus
+//! hat zwei Vorgänger, einen aus dem Anlauf und einen aus dem Zyklus: das
+//! ist eine Kollision von f. Floyd findet den Zyklus mit zwei Zeigern
+//! (einfache und doppelte Geschwindigkeit) und braucht dafür nur konstanten
+//! Speicher. Für eine zufällige Abbildung auf N Werte ist μ + λ im Mittel
+//! etwa √(πN/2) – die Geburtstagsschranke.
+
+/// Zwei verschiedene Urbilder mit gleichem Bild
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Collision {
+    pub a: u64,
+    pub b: u64,
+    pub image: u64,
+}
+
+/// Sucht ab `start` eine Kollision von `f`
+///
+/// Liefert `None`, wenn `start` selbst auf dem Zyklus liegt; dann hat der
+/// Eintrittspunkt keinen zweiten Vorgänger und die Suche muss mit einem
+/// anderen Startwert wiederholt werden.
+pub fn find_collision(start: u64, mut f: impl FnMut(u64) -> u64) -> Option<Collision> {
+    // Phase 1: Schildkröte und Hase treffen sich irgendwo im Zyklus
+    let mut tortoise = f(start);
+    let mut hare = f(tortoise);
+    while tortoise != hare {
+        tortoise = f(tortoise);
+        let step = f(hare);
+        hare = f(step);
+    }
+
+    // Phase 2: vom Start und vom Treffpunkt aus laufen beide nach μ Schritten
+    // gleichzeitig auf den Eintrittspunkt
+    let mut tortoise = start;
+    if tortoise == hare {
+        return None;
+    }
+    loop {
+        let (next_tortoise, next_hare) = (f(tortoise), f(hare));
+        if next_tortoise == next_hare {
+            return Some(Collision { a: tortoise, b: hare, image: next_tortoise });
+        }
+        tortoise = next_tortoise;
+        hare = next_hare;
+    }
+}
+
+/// Erwartete Anzahl Auswertungen bis zur ersten Kollision bei 2^bits möglichen Werten
+pub fn birthday_bound(bits: u32) -> f64 {
+    (std::f64::consts::PI / 2.0 * 2f64.powi(bits as i32)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_rho() {
+        // 0 → 1 → 2 → 3 → 4 → 2: Eintritt bei 2, Vorgänger 1 und 4
+        let successor = [1, 2, 3, 4, 2];
+        let collision = find_collision(0, |x| successor[x as usize]).unwrap();
+        assert_eq!(collision, Collision { a: 1, b: 4, image: 2 });
+    }
+
+    #[test]
+    fn test_start_on_cycle() {
+        assert_eq!(find_collision(0, |x| (x + 1) % 5), None);
+    }
+
+    #[test]
+    fn test_birthday_bound() {
+        assert!((birthday_bound(32) - 82137.0).abs() < 1.0);
+    }
+}
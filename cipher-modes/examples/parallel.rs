@@ -0,0 +1,73 @@
+//! Throughput of the sequential and parallel ECB and CTR functions
+//!
+//! ```text
+//! cargo run --release -p cipher-modes --features parallel --example parallel [MiB]
+//! ```
+//!
+//! Encrypts the same data with AES-128 sequentially and then on thread
+//! pools of 1, 2, 4, … up to the number of cores, checks that every result
+//! matches the sequential one and prints MiB/s and the speedup.
+
+use std::time::{Duration, Instant};
+
+use cipher_modes::{Aes128, CipherModes, KeyInit, Padding};
+
+/// Best of this many runs, to keep out noise from the rest of the system
+const RUNS: usize = 3;
+
+fn main() {
+    let mib: usize = std::env::args().nth(1).map_or(16, |arg| arg.parse().expect("size in MiB"));
+    let cipher = Aes128::new(&[0x42; 16]).unwrap();
+    let data: Vec<u8> = (0..mib << 20).map(|i| i as u8).collect();
+    let counter = [0; 16];
+
+    let ecb = CipherModes::ecb_encrypt(&cipher, &data, Padding::None).unwrap();
+    let ctr = CipherModes::ctr_encrypt(&cipher, &counter, &data).unwrap();
+    let sequential = [
+        ("ECB", fastest(|| CipherModes::ecb_encrypt(&cipher, &data, Padding::None).unwrap())),
+        ("CTR", fastest(|| CipherModes::ctr_encrypt(&cipher, &counter, &data).unwrap())),
+    ];
+
+    let cores = std::thread::available_parallelism().map_or(1, |cores| cores.get());
+    println!("{} MiB, {} cores", mib, cores);
+    println!("{:<5} {:>8} {:>10} {:>8}", "mode", "threads", "MiB/s", "speedup");
+    for (mode, time) in sequential {
+        println!("{:<5} {:>8} {:>10.1} {:>8}", mode, "seq", mib as f64 / time.as_secs_f64(), "1.00");
+    }
+
+    let mut threads = 1;
+    while threads <= cores {
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+        let parallel = pool.install(|| {
+            [
+                ("ECB", fastest(|| {
+                    let output = CipherModes::ecb_encrypt_parallel(&cipher, &data, Padding::None).unwrap();
+                    assert_eq!(output, ecb);
+                    output
+                })),
+                ("CTR", fastest(|| {
+                    let output = CipherModes::ctr_encrypt_parallel(&cipher, &counter, &data).unwrap();
+                    assert_eq!(output, ctr);
+                    output
+                })),
+            ]
+        });
+        for ((mode, time), (_, base)) in parallel.into_iter().zip(sequential) {
+            let speedup = base.as_secs_f64() / time.as_secs_f64();
+            println!("{:<5} {:>8} {:>10.1} {:>8.2}", mode, threads, mib as f64 / time.as_secs_f64(), speedup);
+        }
+        threads = if threads == cores { cores + 1 } else { (threads * 2).min(cores) };
+    }
+}
+
+/// Shortest of [`RUNS`] runs of `f`
+fn fastest<T>(mut f: impl FnMut() -> T) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            std::hint::black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
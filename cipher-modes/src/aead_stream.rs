@@ -0,0 +1,178 @@
+//! STREAM, authenticated encryption of long data in segments
+//! (Hoang, Reyhanitabar, Rogaway, Vizár 2015)
+//!
+//! A single AEAD call needs the whole message before the tag can be
+//! checked. STREAM splits the data into segments and encrypts each with
+//! ChaCha20-Poly1305 under its own nonce, built from a random prefix, the
+//! segment number and a flag for the last segment:
+//!
+//! ```text
+//! nonce_i = prefix (7) ‖ be32(i) ‖ 0x00     for every segment but the last
+//! nonce_n = prefix (7) ‖ be32(n) ‖ 0x01     for the last one
+//! ```
+//!
+//! Every segment is checked before its plaintext is released. Since the
+//! number is in the nonce, swapping, repeating or dropping segments fails;
+//! since the last one is marked, so does cutting the data off after a
+//! segment, which a plain sequence of AEAD messages would not notice.
+//!
+//! The prefix must not repeat under the same key, and one prefix covers at
+//! most 2^32 segments. [`AeadWriter`](crate::io::AeadWriter) and
+//! [`AeadReader`](crate::io::AeadReader) apply STREAM to `std::io`.
+
+use secret::Secret;
+
+use crate::chacha20poly1305::{chacha20_poly1305_decrypt, chacha20_poly1305_encrypt};
+use crate::CipherModeError;
+
+/// Length of the nonce prefix in bytes
+pub const NONCE_PREFIX_SIZE: usize = 7;
+
+/// Bytes a segment grows by, the Poly1305 tag
+pub const OVERHEAD: usize = 16;
+
+const KEY_SIZE: usize = 32;
+
+/// Encrypts segment after segment
+pub struct StreamEncryptor {
+    nonces: Nonces,
+}
+
+impl StreamEncryptor {
+    /// `key` is 32 bytes, `nonce_prefix` 7
+    pub fn new(key: &[u8], nonce_prefix: &[u8]) -> Result<Self, CipherModeError> {
+        Ok(StreamEncryptor { nonces: Nonces::new(key, nonce_prefix)? })
+    }
+
+    /// Encrypts a segment that is not the last; `aad` is authenticated with it
+    pub fn encrypt_next(&mut self, aad: &[u8], segment: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        let nonce = self.nonces.next(false)?;
+        chacha20_poly1305_encrypt(&self.nonces.key, &nonce, aad, segment)
+    }
+
+    /// Encrypts the last segment, which may be empty
+    pub fn encrypt_last(mut self, aad: &[u8], segment: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        let nonce = self.nonces.next(true)?;
+        chacha20_poly1305_encrypt(&self.nonces.key, &nonce, aad, segment)
+    }
+}
+
+/// Decrypts segment after segment, in the order they were encrypted
+pub struct StreamDecryptor {
+    nonces: Nonces,
+}
+
+impl StreamDecryptor {
+    pub fn new(key: &[u8], nonce_prefix: &[u8]) -> Result<Self, CipherModeError> {
+        Ok(StreamDecryptor { nonces: Nonces::new(key, nonce_prefix)? })
+    }
+
+    /// Checks and decrypts a segment that is not the last;
+    /// [`CipherModeError::AuthenticationFailed`] if it was changed, moved or
+    /// is in fact the last
+    pub fn decrypt_next(&mut self, aad: &[u8], segment: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        let nonce = self.nonces.peek(false)?;
+        let plaintext = chacha20_poly1305_decrypt(&self.nonces.key, &nonce, aad, segment)?;
+        self.nonces.next(false)?;
+        Ok(plaintext)
+    }
+
+    /// Checks and decrypts the last segment;
+    /// [`CipherModeError::AuthenticationFailed`] also if the data was cut
+    /// off and this is not the last
+    pub fn decrypt_last(self, aad: &[u8], segment: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        let nonce = self.nonces.peek(true)?;
+        chacha20_poly1305_decrypt(&self.nonces.key, &nonce, aad, segment)
+    }
+}
+
+/// Key, prefix and the number of the next segment
+struct Nonces {
+    key: Secret<Vec<u8>>,
+    prefix: [u8; NONCE_PREFIX_SIZE],
+    /// `None` once all 2^32 numbers are used
+    counter: Option<u32>,
+}
+
+impl Nonces {
+    fn new(key: &[u8], prefix: &[u8]) -> Result<Self, CipherModeError> {
+        if key.len() != KEY_SIZE {
+            return Err(CipherModeError::InvalidKeyLength { expected: KEY_SIZE, got: key.len() });
+        }
+        let prefix = prefix
+            .try_into()
+            .map_err(|_| CipherModeError::InvalidIvLength { expected: NONCE_PREFIX_SIZE, actual: prefix.len() })?;
+        Ok(Nonces { key: Secret::new(key.to_vec()), prefix, counter: Some(0) })
+    }
+
+    /// The nonce of the next segment, without moving on
+    fn peek(&self, last: bool) -> Result<[u8; 12], CipherModeError> {
+        let counter = self.counter.ok_or(CipherModeError::NonceReused)?;
+        let mut nonce = [0; 12];
+        nonce[..NONCE_PREFIX_SIZE].copy_from_slice(&self.prefix);
+        nonce[NONCE_PREFIX_SIZE..11].copy_from_slice(&counter.to_be_bytes());
+        nonce[11] = last.into();
+        Ok(nonce)
+    }
+
+    fn next(&mut self, last: bool) -> Result<[u8; 12], CipherModeError> {
+        let nonce = self.peek(last)?;
+        self.counter = self.counter.and_then(|counter| counter.checked_add(1));
+        Ok(nonce)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [0x42; 32];
+    const PREFIX: [u8; 7] = [7; 7];
+
+    fn encrypt(segments: &[&[u8]]) -> Vec<Vec<u8>> {
+        let mut encryptor = StreamEncryptor::new(&KEY, &PREFIX).unwrap();
+        let (last, others) = segments.split_last().unwrap();
+        let mut output: Vec<_> = others.iter().map(|segment| encryptor.encrypt_next(b"", segment).unwrap()).collect();
+        output.push(encryptor.encrypt_last(b"", last).unwrap());
+        output
+    }
+
+    fn decrypt(segments: &[Vec<u8>]) -> Result<Vec<u8>, CipherModeError> {
+        let mut decryptor = StreamDecryptor::new(&KEY, &PREFIX)?;
+        let (last, others) = segments.split_last().unwrap();
+        let mut plaintext = Vec::new();
+        for segment in others {
+            plaintext.extend(decryptor.decrypt_next(b"", segment)?);
+        }
+        plaintext.extend(decryptor.decrypt_last(b"", last)?);
+        Ok(plaintext)
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let segments = encrypt(&[b"first ", b"second ", b"last"]);
+        assert_eq!(segments[1].len(), 7 + OVERHEAD);
+        assert_eq!(decrypt(&segments).unwrap(), b"first second last");
+
+        // Segment 1 is ChaCha20-Poly1305 under prefix ‖ 00000001 ‖ 00
+        let nonce = [&PREFIX[..], &[0, 0, 0, 1, 0]].concat();
+        assert_eq!(segments[1], chacha20_poly1305_encrypt(&KEY, &nonce, b"", b"second ").unwrap());
+        assert_eq!(decrypt(&encrypt(&[b""])).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_detects_reordering_and_truncation() {
+        let segments = encrypt(&[b"first ", b"second ", b"last"]);
+        let swapped = [segments[1].clone(), segments[0].clone(), segments[2].clone()];
+        assert_eq!(decrypt(&swapped), Err(CipherModeError::AuthenticationFailed));
+        // Cut off after a segment, or a segment dropped
+        assert_eq!(decrypt(&segments[..2]), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(decrypt(&[segments[0].clone(), segments[2].clone()]), Err(CipherModeError::AuthenticationFailed));
+        // The last segment cannot be followed by more
+        let extended = [segments[2].clone(), segments[2].clone()];
+        assert_eq!(decrypt(&extended), Err(CipherModeError::AuthenticationFailed));
+
+        assert!(StreamEncryptor::new(&KEY, &[0; 8]).is_err());
+        assert!(StreamDecryptor::new(&KEY[..16], &PREFIX).is_err());
+    }
+}
@@ -0,0 +1,245 @@
+//! Ascon-128, lightweight authenticated encryption (Ascon v1.2)
+//!
+//! Ascon won the NIST lightweight cryptography competition. Like Keccak in
+//! SHA-3 it is built from a permutation over a state larger than a block:
+//! five 64-bit words, 320 bits, mixed by a bitsliced 5-bit S-box (χ from
+//! Keccak with a few XORs around it) and a rotate-and-XOR layer per word.
+//! Ascon-128 runs it as a duplex: 64 bits of data go into the first word
+//! per call of the six-round permutation p⁶, the other 256 bits are never
+//! output directly.
+//!
+//! ```text
+//! S = IV ‖ K ‖ N           p¹²    S ⊕= 0 ‖ K
+//! x0 ⊕= A_i                p⁶     for every block of aad‖10…0, if any
+//! S ⊕= 0…01
+//! C_i = x0 ⊕= P_i          p⁶     for every block of P‖10…0, not after the last
+//! S ⊕= 0 ‖ K ‖ 0           p¹²    tag = (x3, x4) ⊕ K
+//! ```
+//!
+//! The ciphertext is as long as the plaintext and followed by the 16-byte
+//! tag. Decryption only returns the plaintext once the tag matches. The
+//! 16-byte nonce must never repeat under the same key.
+//!
+//! This is Ascon-128 as submitted to the competition, with big-endian
+//! words; the later NIST standard Ascon-AEAD128 (SP 800-232) differs in
+//! byte order, rate and IV and gives other ciphertexts.
+
+use secret::Secret;
+
+use crate::CipherModeError;
+
+/// Key length in bytes
+pub const KEY_SIZE: usize = 16;
+
+/// Nonce length in bytes
+pub const NONCE_SIZE: usize = 16;
+
+/// Tag length in bytes
+pub const TAG_SIZE: usize = 16;
+
+/// Bytes absorbed per permutation call
+const RATE: usize = 8;
+
+/// Key size, rate and rounds of p¹² and p⁶, one byte each
+const IV: u64 = 0x8040_0c06_0000_0000;
+
+/// Encrypts and authenticates `plaintext` and authenticates `aad`
+///
+/// `key` and `nonce` are 16 bytes each. Returns the ciphertext with the
+/// tag appended.
+pub fn ascon128_encrypt(key: &[u8], nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    let key = check_key(key)?;
+    let mut state = initialize(&key, nonce)?;
+    absorb_aad(&mut state, aad);
+
+    let mut output = Vec::with_capacity(plaintext.len() + TAG_SIZE);
+    let mut blocks = plaintext.chunks_exact(RATE);
+    for block in &mut blocks {
+        state[0] ^= word(block);
+        output.extend(state[0].to_be_bytes());
+        permute(&mut state, 6);
+    }
+    let last = blocks.remainder();
+    state[0] ^= padded(last);
+    output.extend(&state[0].to_be_bytes()[..last.len()]);
+
+    output.extend(finalize(&mut state, &key));
+    Ok(output)
+}
+
+/// Checks the tag and decrypts; [`CipherModeError::AuthenticationFailed`]
+/// if anything was modified
+pub fn ascon128_decrypt(key: &[u8], nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    let key = check_key(key)?;
+    let mut state = initialize(&key, nonce)?;
+    let Some(split) = ciphertext.len().checked_sub(TAG_SIZE) else {
+        return Err(CipherModeError::AuthenticationFailed);
+    };
+    let (ciphertext, tag) = ciphertext.split_at(split);
+    absorb_aad(&mut state, aad);
+
+    let mut plaintext = Vec::with_capacity(ciphertext.len());
+    let mut blocks = ciphertext.chunks_exact(RATE);
+    for block in &mut blocks {
+        let c = word(block);
+        plaintext.extend((state[0] ^ c).to_be_bytes());
+        state[0] = c;
+        permute(&mut state, 6);
+    }
+    // The last block replaces only its own bytes of x0; the padding is XORed in
+    let last = blocks.remainder();
+    let keystream = state[0].to_be_bytes();
+    plaintext.extend(last.iter().zip(keystream).map(|(c, k)| c ^ k));
+    let mut x0 = keystream;
+    x0[..last.len()].copy_from_slice(last);
+    x0[last.len()] ^= 0x80;
+    state[0] = u64::from_be_bytes(x0);
+
+    if !secret::ct::eq(&finalize(&mut state, &key), tag) {
+        return Err(CipherModeError::AuthenticationFailed);
+    }
+    Ok(plaintext)
+}
+
+/// The key as two big-endian words
+fn check_key(key: &[u8]) -> Result<Secret<[u64; 2]>, CipherModeError> {
+    if key.len() != KEY_SIZE {
+        return Err(CipherModeError::InvalidKeyLength { expected: KEY_SIZE, got: key.len() });
+    }
+    Ok(Secret::new([word(&key[..8]), word(&key[8..])]))
+}
+
+fn initialize(key: &[u64; 2], nonce: &[u8]) -> Result<Secret<[u64; 5]>, CipherModeError> {
+    if nonce.len() != NONCE_SIZE {
+        return Err(CipherModeError::InvalidIvLength { expected: NONCE_SIZE, actual: nonce.len() });
+    }
+    let mut state = Secret::new([IV, key[0], key[1], word(&nonce[..8]), word(&nonce[8..])]);
+    permute(&mut state, 12);
+    state[3] ^= key[0];
+    state[4] ^= key[1];
+    Ok(state)
+}
+
+/// Absorbs the padded associated data, nothing if there is none, and
+/// separates it from the plaintext
+fn absorb_aad(state: &mut [u64; 5], aad: &[u8]) {
+    if !aad.is_empty() {
+        let mut blocks = aad.chunks_exact(RATE);
+        for block in &mut blocks {
+            state[0] ^= word(block);
+            permute(state, 6);
+        }
+        state[0] ^= padded(blocks.remainder());
+        permute(state, 6);
+    }
+    state[4] ^= 1;
+}
+
+fn finalize(state: &mut [u64; 5], key: &[u64; 2]) -> [u8; TAG_SIZE] {
+    state[1] ^= key[0];
+    state[2] ^= key[1];
+    permute(state, 12);
+    let mut tag = [0; TAG_SIZE];
+    tag[..8].copy_from_slice(&(state[3] ^ key[0]).to_be_bytes());
+    tag[8..].copy_from_slice(&(state[4] ^ key[1]).to_be_bytes());
+    tag
+}
+
+/// The last `rounds` of the twelve rounds of the permutation
+fn permute(state: &mut [u64; 5], rounds: usize) {
+    let [mut x0, mut x1, mut x2, mut x3, mut x4] = *state;
+    for round in 12 - rounds..12 {
+        // Round constant: 0xf0, 0xe1, …, 0x4b
+        x2 ^= (((0xf - round) << 4) | round) as u64;
+
+        // S-box on the 64 columns of five bits
+        x0 ^= x4;
+        x4 ^= x3;
+        x2 ^= x1;
+        let t = [!x0 & x1, !x1 & x2, !x2 & x3, !x3 & x4, !x4 & x0];
+        x0 ^= t[1];
+        x1 ^= t[2];
+        x2 ^= t[3];
+        x3 ^= t[4];
+        x4 ^= t[0];
+        x1 ^= x0;
+        x0 ^= x4;
+        x3 ^= x2;
+        x2 = !x2;
+
+        // Linear layer, different rotations per word
+        x0 ^= x0.rotate_right(19) ^ x0.rotate_right(28);
+        x1 ^= x1.rotate_right(61) ^ x1.rotate_right(39);
+        x2 ^= x2.rotate_right(1) ^ x2.rotate_right(6);
+        x3 ^= x3.rotate_right(10) ^ x3.rotate_right(17);
+        x4 ^= x4.rotate_right(7) ^ x4.rotate_right(41);
+    }
+    *state = [x0, x1, x2, x3, x4];
+}
+
+/// Fewer than 8 bytes followed by 10…0, as a word
+fn padded(last: &[u8]) -> u64 {
+    let mut block = [0; RATE];
+    block[..last.len()].copy_from_slice(last);
+    block[last.len()] = 0x80;
+    u64::from_be_bytes(block)
+}
+
+fn word(bytes: &[u8]) -> u64 {
+    u64::from_be_bytes(bytes.try_into().expect("8 bytes for a word"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_kat() {
+        // LWC_AEAD_KAT_128_128.txt of the Ascon submission, counts 1 and 2
+        let key: Vec<u8> = (0..16).collect();
+        let nonce: Vec<u8> = (0..16).collect();
+        for (plaintext, aad, expected) in [
+            (&[][..], &[][..], "e355159f292911f794cb1432a0103a8a"),
+            (&[], &[0], "944df887cd4901614c5dedbc42fc0da0"),
+        ] {
+            let ciphertext = ascon128_encrypt(&key, &nonce, aad, plaintext).unwrap();
+            assert_eq!(ciphertext, codec::hex::decode(expected).unwrap());
+            assert_eq!(ascon128_decrypt(&key, &nonce, aad, &ciphertext).unwrap(), plaintext);
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let key = [0x42; 16];
+        let nonce = [7; 16];
+        for length in [0, 1, 7, 8, 9, 16, 40] {
+            let plaintext: Vec<u8> = (0..length as u8).collect();
+            for aad in [&b""[..], b"header", b"exactly 16 bytes"] {
+                let ciphertext = ascon128_encrypt(&key, &nonce, aad, &plaintext).unwrap();
+                assert_eq!(ciphertext.len(), length + 16);
+                assert_eq!(ascon128_decrypt(&key, &nonce, aad, &ciphertext).unwrap(), plaintext);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_tampering() {
+        let key = [0x42; 16];
+        let nonce = [7; 16];
+        let ciphertext = ascon128_encrypt(&key, &nonce, b"header", b"attack at dawn").unwrap();
+        for position in [0, 8, 13, 14, 29] {
+            let mut tampered = ciphertext.clone();
+            tampered[position] ^= 1;
+            assert_eq!(ascon128_decrypt(&key, &nonce, b"header", &tampered), Err(CipherModeError::AuthenticationFailed));
+        }
+        assert_eq!(ascon128_decrypt(&key, &nonce, b"Header", &ciphertext), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(ascon128_decrypt(&key, &nonce, b"", &ciphertext), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(ascon128_decrypt(&key, &[8; 16], b"header", &ciphertext), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(ascon128_decrypt(&[0x43; 16], &nonce, b"header", &ciphertext), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(ascon128_decrypt(&key, &nonce, b"header", &ciphertext[..15]), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(
+            ascon128_encrypt(&key, &[0; 12], b"", b"data"),
+            Err(CipherModeError::InvalidIvLength { expected: 16, actual: 12 })
+        );
+    }
+}
@@ -0,0 +1,295 @@
+//! Known-answer tests in the `.rsp` format of NIST's CAVP
+//!
+//! The Cryptographic Algorithm Validation Program publishes its test
+//! vectors as text files of `NAME = hex` lines, one record per block of
+//! lines, grouped into `[ENCRYPT]` and `[DECRYPT]` sections:
+//!
+//! ```text
+//! [ENCRYPT]
+//!
+//! COUNT = 0
+//! KEY = 00000000000000000000000000000000
+//! PLAINTEXT = f34481ec3cc627bacd5dc3fb08f273e6
+//! CIPHERTEXT = 0336763e966d92595a567cc9ce537f5e
+//! ```
+//!
+//! [`run`] parses such a file and checks every record against a
+//! [`BlockCipher`] in ECB, CBC, OFB or CTR, so a new cipher is validated
+//! with one call:
+//!
+//! ```text
+//! cavp::run::<MyAes>(CavpMode::Cbc, include_str!("CBCVarKey128.rsp"))?;
+//! ```
+//!
+//! `IV` is the IV, for CTR the initial counter block; other fields are
+//! ignored. This covers the KAT and MMT files of the AESAVS (GFSbox,
+//! KeySbox, VarKey, VarTxt, MMT); the Monte Carlo files chain thousands of
+//! encryptions per record and give other expected values. GCM files need a
+//! GCM mode, which this crate does not have yet.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::{BlockCipher, CipherModeError, CipherModes, KeyInit, Padding};
+
+/// The modes [`run`] checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CavpMode {
+    Ecb,
+    Cbc,
+    Ofb,
+    /// `IV` is the whole initial counter block
+    Ctr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Encrypt,
+    Decrypt,
+}
+
+/// One record of a `.rsp` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestVector {
+    /// Line of the record in the file, counted from 1
+    pub line: usize,
+    pub count: Option<u32>,
+    pub direction: Direction,
+    pub key: Vec<u8>,
+    /// Empty for ECB
+    pub iv: Vec<u8>,
+    pub plaintext: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CavpError {
+    /// The file is not in the `.rsp` format
+    Parse { line: usize, message: String },
+    /// The cipher rejected the key, IV or data of a record
+    Cipher { line: usize, error: CipherModeError },
+    /// The cipher gave another result than the record
+    Mismatch { line: usize, count: Option<u32>, expected: Vec<u8>, actual: Vec<u8> },
+}
+
+impl fmt::Display for CavpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CavpError::Parse { line, message } => write!(f, "Line {}: {}", line, message),
+            CavpError::Cipher { line, error } => write!(f, "Record in line {}: {}", line, error),
+            CavpError::Mismatch { line, count, expected, actual } => {
+                let count = count.map_or(String::new(), |count| format!(" (COUNT = {})", count));
+                write!(f, "Record in line {}{}: expected {}, got {}", line, count, codec::hex::encode(expected), codec::hex::encode(actual))
+            }
+        }
+    }
+}
+
+impl Error for CavpError {}
+
+/// Checks every record of a `.rsp` file; the number of records checked
+pub fn run<C: BlockCipher + KeyInit>(mode: CavpMode, rsp: &str) -> Result<usize, CavpError> {
+    let vectors = parse(rsp)?;
+    for vector in &vectors {
+        check::<C>(mode, vector)?;
+    }
+    Ok(vectors.len())
+}
+
+/// Encrypts or decrypts one record and compares the result
+pub fn check<C: BlockCipher + KeyInit>(mode: CavpMode, vector: &TestVector) -> Result<(), CavpError> {
+    let line = vector.line;
+    let result = C::new(&vector.key).and_then(|cipher| {
+        let iv = &vector.iv;
+        let input = match vector.direction {
+            Direction::Encrypt => &vector.plaintext,
+            Direction::Decrypt => &vector.ciphertext,
+        };
+        match (mode, vector.direction) {
+            (CavpMode::Ecb, Direction::Encrypt) => CipherModes::ecb_encrypt(&cipher, input, Padding::None),
+            (CavpMode::Ecb, Direction::Decrypt) => CipherModes::ecb_decrypt(&cipher, input, Padding::None),
+            (CavpMode::Cbc, Direction::Encrypt) => CipherModes::cbc_encrypt(&cipher, iv, input, Padding::None),
+            (CavpMode::Cbc, Direction::Decrypt) => CipherModes::cbc_decrypt(&cipher, iv, input, Padding::None),
+            (CavpMode::Ofb, _) => CipherModes::ofb_encrypt(&cipher, iv, input),
+            (CavpMode::Ctr, _) => CipherModes::ctr_encrypt(&cipher, iv, input),
+        }
+    });
+    let actual = result.map_err(|error| CavpError::Cipher { line, error })?;
+    let expected = match vector.direction {
+        Direction::Encrypt => &vector.ciphertext,
+        Direction::Decrypt => &vector.plaintext,
+    };
+    if actual != *expected {
+        return Err(CavpError::Mismatch { line, count: vector.count, expected: expected.clone(), actual });
+    }
+    Ok(())
+}
+
+/// The records of a `.rsp` file
+///
+/// Every record needs `KEY`, `PLAINTEXT` and `CIPHERTEXT` and has to be in
+/// an `[ENCRYPT]` or `[DECRYPT]` section.
+pub fn parse(rsp: &str) -> Result<Vec<TestVector>, CavpError> {
+    let mut vectors = Vec::new();
+    let mut direction = None;
+    let mut record: Option<Record> = None;
+
+    // A blank line after the last record ends it as well
+    for (index, line) in rsp.lines().chain([""]).enumerate() {
+        let number = index + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            if let Some(record) = record.take() {
+                vectors.push(record.finish()?);
+            }
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        if let Some(section) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            // [Keylen = 128] and the like carry nothing the records do not repeat
+            match section.trim().to_ascii_uppercase().as_str() {
+                "ENCRYPT" => direction = Some(Direction::Encrypt),
+                "DECRYPT" => direction = Some(Direction::Decrypt),
+                _ => {}
+            }
+            continue;
+        }
+
+        let Some((name, value)) = line.split_once('=') else {
+            return Err(parse_error(number, "expected NAME = value"));
+        };
+        let name = name.trim().to_ascii_uppercase();
+        let value = value.trim();
+        if name == "COUNT"
+            && let Some(previous) = record.take()
+        {
+            vectors.push(previous.finish()?);
+        }
+        let record = match &mut record {
+            Some(record) => record,
+            None => {
+                let direction = direction.ok_or_else(|| parse_error(number, "record outside [ENCRYPT] and [DECRYPT]"))?;
+                record.insert(Record::new(number, direction))
+            }
+        };
+        match name.as_str() {
+            "COUNT" => record.vector.count = Some(value.parse().map_err(|_| parse_error(number, "COUNT is not a number"))?),
+            "KEY" => record.key = Some(unhex(number, value)?),
+            "IV" => record.vector.iv = unhex(number, value)?,
+            "PLAINTEXT" => record.plaintext = Some(unhex(number, value)?),
+            "CIPHERTEXT" => record.ciphertext = Some(unhex(number, value)?),
+            _ => {}
+        }
+    }
+    Ok(vectors)
+}
+
+/// A record while its lines are read
+struct Record {
+    /// Line, count, direction and IV; the rest is filled in by `finish`
+    vector: TestVector,
+    key: Option<Vec<u8>>,
+    plaintext: Option<Vec<u8>>,
+    ciphertext: Option<Vec<u8>>,
+}
+
+impl Record {
+    fn new(line: usize, direction: Direction) -> Self {
+        let vector = TestVector { line, count: None, direction, key: vec![], iv: vec![], plaintext: vec![], ciphertext: vec![] };
+        Record { vector, key: None, plaintext: None, ciphertext: None }
+    }
+
+    fn finish(self) -> Result<TestVector, CavpError> {
+        let line = self.vector.line;
+        let missing = |name: &str| parse_error(line, &format!("record without {}", name));
+        Ok(TestVector {
+            key: self.key.ok_or_else(|| missing("KEY"))?,
+            plaintext: self.plaintext.ok_or_else(|| missing("PLAINTEXT"))?,
+            ciphertext: self.ciphertext.ok_or_else(|| missing("CIPHERTEXT"))?,
+            ..self.vector
+        })
+    }
+}
+
+fn parse_error(line: usize, message: &str) -> CavpError {
+    CavpError::Parse { line, message: message.to_string() }
+}
+
+fn unhex(line: usize, text: &str) -> Result<Vec<u8>, CavpError> {
+    codec::hex::decode(text).map_err(|_| parse_error(line, &format!("{:?} is not hex", text)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Aes128;
+
+    const ECB: &str = "\
+# CAVS 11.1
+# Config info for aes_values
+# AESVS GFSbox test data for ECB
+# State : Encrypt and Decrypt
+# Key Length : 128
+
+[ENCRYPT]
+
+COUNT = 0
+KEY = 00000000000000000000000000000000
+PLAINTEXT = f34481ec3cc627bacd5dc3fb08f273e6
+CIPHERTEXT = 0336763e966d92595a567cc9ce537f5e
+
+COUNT = 1
+KEY = 00000000000000000000000000000000
+PLAINTEXT = 9798c4640bad75c7c3227db910174e72
+CIPHERTEXT = a9a1631bf4996954ebc093957b234589
+
+[DECRYPT]
+
+COUNT = 0
+KEY = 00000000000000000000000000000000
+CIPHERTEXT = 0336763e966d92595a567cc9ce537f5e
+PLAINTEXT = f34481ec3cc627bacd5dc3fb08f273e6
+";
+
+    /// SP 800-38A, F.2.1, F.4.1 and F.5.1, the first block each
+    fn sp800_38a(iv: &str, ciphertext: &str) -> String {
+        format!(
+            "[ENCRYPT]\nCOUNT = 0\nKEY = 2b7e151628aed2a6abf7158809cf4f3c\nIV = {iv}\n\
+             PLAINTEXT = 6bc1bee22e409f96e93d7e117393172a\nCIPHERTEXT = {ciphertext}\n\n\
+             [DECRYPT]\nCOUNT = 0\nKEY = 2b7e151628aed2a6abf7158809cf4f3c\nIV = {iv}\n\
+             CIPHERTEXT = {ciphertext}\nPLAINTEXT = 6bc1bee22e409f96e93d7e117393172a\n"
+        )
+    }
+
+    #[test]
+    fn test_runs_vectors() {
+        assert_eq!(run::<Aes128>(CavpMode::Ecb, ECB), Ok(3));
+        let iv = "000102030405060708090a0b0c0d0e0f";
+        assert_eq!(run::<Aes128>(CavpMode::Cbc, &sp800_38a(iv, "7649abac8119b246cee98e9b12e9197d")), Ok(2));
+        assert_eq!(run::<Aes128>(CavpMode::Ofb, &sp800_38a(iv, "3b3fd92eb72dad20333449f8e83cfb4a")), Ok(2));
+        let counter = "f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff";
+        assert_eq!(run::<Aes128>(CavpMode::Ctr, &sp800_38a(counter, "874d6191b620e3261bef6864990db6ce")), Ok(2));
+    }
+
+    #[test]
+    fn test_parses_records() {
+        let vectors = parse(ECB).unwrap();
+        assert_eq!(vectors[1].line, 14);
+        assert_eq!(vectors[1].count, Some(1));
+        assert_eq!(vectors[2].direction, Direction::Decrypt);
+        assert_eq!(vectors[2].key, [0; 16]);
+        assert!(vectors[2].iv.is_empty());
+    }
+
+    #[test]
+    fn test_reports_failures() {
+        let wrong = ECB.replace("a9a1631bf4996954ebc093957b234589", "a9a1631bf4996954ebc093957b234588");
+        assert!(matches!(run::<Aes128>(CavpMode::Ecb, &wrong), Err(CavpError::Mismatch { line: 14, count: Some(1), .. })));
+        assert!(matches!(run::<Aes128>(CavpMode::Cbc, ECB), Err(CavpError::Cipher { line: 9, .. })));
+        assert_eq!(parse("[ENCRYPT]\nKEY = 0g\n"), Err(parse_error(2, "\"0g\" is not hex")));
+        assert_eq!(parse("KEY = 00\n"), Err(parse_error(1, "record outside [ENCRYPT] and [DECRYPT]")));
+        assert_eq!(parse("[DECRYPT]\nCOUNT = 3\nKEY = 00\n"), Err(parse_error(2, "record without PLAINTEXT")));
+    }
+}
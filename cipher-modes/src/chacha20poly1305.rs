@@ -0,0 +1,127 @@
+//! ChaCha20-Poly1305, authenticated encryption (RFC 8439)
+//!
+//! ChaCha20 encrypts, Poly1305 authenticates. The one-time Poly1305 key is
+//! the first 32 bytes of ChaCha20 block 0, so every nonce gets its own; the
+//! plaintext is encrypted from block 1 on:
+//!
+//! ```text
+//! K_mac = ChaCha20(key, nonce, block 0)[0..32]    C = ChaCha20(key, nonce, from block 1) ⊕ P
+//! tag = Poly1305(K_mac, aad ‖ pad16 ‖ C ‖ pad16 ‖ le64(|aad|) ‖ le64(|C|))
+//! ```
+//!
+//! The ciphertext is C followed by the 16-byte tag. Decryption checks the
+//! tag before it decrypts anything. The 12-byte nonce must never repeat
+//! under the same key: that would reuse the keystream and the Poly1305 key,
+//! giving away both the plaintexts and the means to forge tags.
+//!
+//! Unlike EAX and SIV this needs no block cipher, and without table lookups
+//! it runs in constant time in software.
+
+use crate::cipher::ChaCha20;
+use crate::cipher::chacha20::BLOCK_SIZE;
+use crate::poly1305::{KEY_SIZE, Poly1305, TAG_SIZE};
+use crate::{CipherModeError, StreamCipher};
+
+/// Encrypts and authenticates `plaintext` and authenticates `aad`
+///
+/// `key` is 32 bytes, `nonce` 12 bytes. Returns the ciphertext with the tag
+/// appended.
+pub fn chacha20_poly1305_encrypt(key: &[u8], nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    let (mut chacha, mac) = setup(key, nonce)?;
+    let mut output = plaintext.to_vec();
+    chacha.apply_keystream(&mut output);
+    let tag = tag(mac, aad, &output);
+    output.extend(tag);
+    Ok(output)
+}
+
+/// Checks the tag and decrypts; [`CipherModeError::AuthenticationFailed`]
+/// if anything was modified
+pub fn chacha20_poly1305_decrypt(key: &[u8], nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    let (mut chacha, mac) = setup(key, nonce)?;
+    let Some(split) = ciphertext.len().checked_sub(TAG_SIZE) else {
+        return Err(CipherModeError::AuthenticationFailed);
+    };
+    let (ciphertext, tag_received) = ciphertext.split_at(split);
+    if !secret::ct::eq(&tag(mac, aad, ciphertext), tag_received) {
+        return Err(CipherModeError::AuthenticationFailed);
+    }
+    let mut plaintext = ciphertext.to_vec();
+    chacha.apply_keystream(&mut plaintext);
+    Ok(plaintext)
+}
+
+/// ChaCha20 positioned at block 1 and Poly1305 keyed from block 0
+fn setup(key: &[u8], nonce: &[u8]) -> Result<(ChaCha20, Poly1305), CipherModeError> {
+    let mut chacha = ChaCha20::new(key, nonce)?;
+    let mut mac_key = secret::Secret::new([0; KEY_SIZE]);
+    chacha.apply_keystream(&mut *mac_key);
+    chacha.seek(BLOCK_SIZE as u64);
+    Ok((chacha, Poly1305::new(&*mac_key)?))
+}
+
+fn tag(mut mac: Poly1305, aad: &[u8], ciphertext: &[u8]) -> [u8; TAG_SIZE] {
+    let zeros = [0; 16];
+    for data in [aad, ciphertext] {
+        mac.update(data);
+        mac.update(&zeros[..data.len().next_multiple_of(16) - data.len()]);
+    }
+    mac.update(&(aad.len() as u64).to_le_bytes());
+    mac.update(&(ciphertext.len() as u64).to_le_bytes());
+    mac.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_rfc8439() {
+        // RFC 8439, section 2.8.2
+        let key: Vec<u8> = (0x80..=0x9f).collect();
+        let nonce = codec::hex::decode("070000004041424344454647").unwrap();
+        let aad = codec::hex::decode("50515253c0c1c2c3c4c5c6c7").unwrap();
+        let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+        let expected = codec::hex::decode(
+            "d31a8d34648e60db7b86afbc53ef7ec2 a4aded51296e08fea9e2b5a736ee62d6
+             3dbea45e8ca9671282fafb69da92728b 1a71de0a9e060b2905d6a5b67ecd3b36
+             92ddbd7f2d778b8c9803aee328091b58 fab324e4fad675945585808b4831d7bc
+             3ff4def08e4b7a9de576d26586cec64b 6116
+             1ae10b594f09e26a7e902ecbd0600691",
+        ).unwrap();
+        let ciphertext = chacha20_poly1305_encrypt(&key, &nonce, &aad, plaintext).unwrap();
+        assert_eq!(ciphertext, expected);
+        assert_eq!(chacha20_poly1305_decrypt(&key, &nonce, &aad, &ciphertext).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let key = [0x42; 32];
+        for length in [0, 1, 16, 63, 64, 65, 200] {
+            let plaintext: Vec<u8> = (0..length as u8).collect();
+            let ciphertext = chacha20_poly1305_encrypt(&key, &[7; 12], b"header", &plaintext).unwrap();
+            assert_eq!(ciphertext.len(), length + 16);
+            assert_eq!(chacha20_poly1305_decrypt(&key, &[7; 12], b"header", &ciphertext).unwrap(), plaintext);
+        }
+    }
+
+    #[test]
+    fn test_rejects_tampering() {
+        let key = [0x42; 32];
+        let nonce = [7; 12];
+        let ciphertext = chacha20_poly1305_encrypt(&key, &nonce, b"header", b"attack at dawn").unwrap();
+        for position in [0, 13, 14, 29] {
+            let mut tampered = ciphertext.clone();
+            tampered[position] ^= 1;
+            assert_eq!(chacha20_poly1305_decrypt(&key, &nonce, b"header", &tampered), Err(CipherModeError::AuthenticationFailed));
+        }
+        assert_eq!(chacha20_poly1305_decrypt(&key, &nonce, b"Header", &ciphertext), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(chacha20_poly1305_decrypt(&key, &[8; 12], b"header", &ciphertext), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(chacha20_poly1305_decrypt(&[0x43; 32], &nonce, b"header", &ciphertext), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(chacha20_poly1305_decrypt(&key, &nonce, b"header", &ciphertext[..15]), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(
+            chacha20_poly1305_encrypt(&key, &[0; 8], b"", b"data"),
+            Err(CipherModeError::InvalidIvLength { expected: 12, actual: 8 })
+        );
+    }
+}
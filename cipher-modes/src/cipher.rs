@@ -0,0 +1,15 @@
+//! Real ciphers for the modes
+//!
+//! Unlike [`DummyCipher`](crate::DummyCipher) these are meant to be used:
+//! `CipherModes::cbc_encrypt(&Aes128::new(key)?, iv, data, padding)` works
+//! without any other crate. [`Blowfish`] has 8-byte blocks and shows how
+//! the modes behave with a smaller block size. [`ChaCha20`] is a stream
+//! cipher and needs no mode at all.
+
+pub mod aes;
+pub mod blowfish;
+pub mod chacha20;
+
+pub use aes::{Aes, Aes128, Aes192, Aes256};
+pub use blowfish::Blowfish;
+pub use chacha20::ChaCha20;
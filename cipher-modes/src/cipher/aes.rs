@@ -0,0 +1,250 @@
+//! AES (FIPS 197) with 128-, 192- and 256-bit keys
+//!
+//! A straightforward byte-oriented implementation: the state is the block
+//! itself, column by column, and MixColumns multiplies with [`gf256::mul`]
+//! without branching on the data. SubBytes looks up [`S_BOX`], so the
+//! memory accesses depend on the key and the data; on shared hardware a
+//! cache-timing attacker can learn from them. The round keys are
+//! overwritten when the cipher is dropped.
+
+use std::ops::RangeInclusive;
+
+use gf::gf256;
+use secret::Secret;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{BlockCipher, CipherModeError, KeyInit};
+
+/// Block size of AES in bytes, for every key length
+pub const BLOCK_SIZE: usize = 16;
+
+/// AES-128, 10 rounds
+pub type Aes128 = Aes<16>;
+/// AES-192, 12 rounds
+pub type Aes192 = Aes<24>;
+/// AES-256, 14 rounds
+pub type Aes256 = Aes<32>;
+
+/// SubBytes: inverse in GF(2^8) followed by an affine map
+pub const S_BOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16
+];
+
+const INV_S_BOX: [u8; 256] = [
+    0x52, 0x09, 0x6a, 0xd5, 0x30, 0x36, 0xa5, 0x38, 0xbf, 0x40, 0xa3, 0x9e, 0x81, 0xf3, 0xd7, 0xfb,
+    0x7c, 0xe3, 0x39, 0x82, 0x9b, 0x2f, 0xff, 0x87, 0x34, 0x8e, 0x43, 0x44, 0xc4, 0xde, 0xe9, 0xcb,
+    0x54, 0x7b, 0x94, 0x32, 0xa6, 0xc2, 0x23, 0x3d, 0xee, 0x4c, 0x95, 0x0b, 0x42, 0xfa, 0xc3, 0x4e,
+    0x08, 0x2e, 0xa1, 0x66, 0x28, 0xd9, 0x24, 0xb2, 0x76, 0x5b, 0xa2, 0x49, 0x6d, 0x8b, 0xd1, 0x25,
+    0x72, 0xf8, 0xf6, 0x64, 0x86, 0x68, 0x98, 0x16, 0xd4, 0xa4, 0x5c, 0xcc, 0x5d, 0x65, 0xb6, 0x92,
+    0x6c, 0x70, 0x48, 0x50, 0xfd, 0xed, 0xb9, 0xda, 0x5e, 0x15, 0x46, 0x57, 0xa7, 0x8d, 0x9d, 0x84,
+    0x90, 0xd8, 0xab, 0x00, 0x8c, 0xbc, 0xd3, 0x0a, 0xf7, 0xe4, 0x58, 0x05, 0xb8, 0xb3, 0x45, 0x06,
+    0xd0, 0x2c, 0x1e, 0x8f, 0xca, 0x3f, 0x0f, 0x02, 0xc1, 0xaf, 0xbd, 0x03, 0x01, 0x13, 0x8a, 0x6b,
+    0x3a, 0x91, 0x11, 0x41, 0x4f, 0x67, 0xdc, 0xea, 0x97, 0xf2, 0xcf, 0xce, 0xf0, 0xb4, 0xe6, 0x73,
+    0x96, 0xac, 0x74, 0x22, 0xe7, 0xad, 0x35, 0x85, 0xe2, 0xf9, 0x37, 0xe8, 0x1c, 0x75, 0xdf, 0x6e,
+    0x47, 0xf1, 0x1a, 0x71, 0x1d, 0x29, 0xc5, 0x89, 0x6f, 0xb7, 0x62, 0x0e, 0xaa, 0x18, 0xbe, 0x1b,
+    0xfc, 0x56, 0x3e, 0x4b, 0xc6, 0xd2, 0x79, 0x20, 0x9a, 0xdb, 0xc0, 0xfe, 0x78, 0xcd, 0x5a, 0xf4,
+    0x1f, 0xdd, 0xa8, 0x33, 0x88, 0x07, 0xc7, 0x31, 0xb1, 0x12, 0x10, 0x59, 0x27, 0x80, 0xec, 0x5f,
+    0x60, 0x51, 0x7f, 0xa9, 0x19, 0xb5, 0x4a, 0x0d, 0x2d, 0xe5, 0x7a, 0x9f, 0x93, 0xc9, 0x9c, 0xef,
+    0xa0, 0xe0, 0x3b, 0x4d, 0xae, 0x2a, 0xf5, 0xb0, 0xc8, 0xeb, 0xbb, 0x3c, 0x83, 0x53, 0x99, 0x61,
+    0x17, 0x2b, 0x04, 0x7e, 0xba, 0x77, 0xd6, 0x26, 0xe1, 0x69, 0x14, 0x63, 0x55, 0x21, 0x0c, 0x7d
+];
+
+/// Round constants x^(i-1) in GF(2^8) of the key expansion
+const RCON: [u8; 11] = [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+/// AES with a key of `K` bytes, 16, 24 or 32; see [`Aes128`], [`Aes192`] and [`Aes256`]
+#[derive(Debug, Clone)]
+pub struct Aes<const K: usize> {
+    /// The round keys one after the other, 16 bytes each
+    round_keys: Secret<Vec<u8>>,
+}
+
+impl<const K: usize> Aes<K> {
+    const ROUNDS: usize = K / 4 + 6;
+
+    /// Expands the key into `ROUNDS + 1` round keys
+    ///
+    /// Every word is the word `K` bytes earlier XOR the previous word; at the
+    /// start of each key length that one is rotated, substituted and XORed
+    /// with a round constant, and for 256-bit keys also substituted halfway.
+    fn expand(key: &[u8; K]) -> Self {
+        const { assert!(K == 16 || K == 24 || K == 32, "AES keys are 16, 24 or 32 bytes long") };
+        let nk = K / 4;
+        let mut w = Secret::new(vec![0; BLOCK_SIZE * (Self::ROUNDS + 1)]);
+        w[..K].copy_from_slice(key);
+        for i in nk..w.len() / 4 {
+            let mut temp: [u8; 4] = w[4 * (i - 1)..4 * i].try_into().expect("a word is 4 bytes");
+            if i % nk == 0 {
+                temp.rotate_left(1);
+                temp = temp.map(|byte| S_BOX[byte as usize]);
+                temp[0] ^= RCON[i / nk];
+            } else if nk > 6 && i % nk == 4 {
+                temp = temp.map(|byte| S_BOX[byte as usize]);
+            }
+            for j in 0..4 {
+                w[4 * i + j] = w[4 * (i - nk) + j] ^ temp[j];
+            }
+        }
+        Aes { round_keys: w }
+    }
+
+    fn add_round_key(&self, state: &mut [u8], round: usize) {
+        let key = &self.round_keys[round * BLOCK_SIZE..][..BLOCK_SIZE];
+        state.iter_mut().zip(key).for_each(|(byte, k)| *byte ^= k);
+    }
+}
+
+/// Overwrites the round keys with zeros
+#[cfg(feature = "zeroize")]
+impl<const K: usize> Zeroize for Aes<K> {
+    fn zeroize(&mut self) {
+        self.round_keys.as_mut_slice().zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const K: usize> ZeroizeOnDrop for Aes<K> {}
+
+impl<const K: usize> KeyInit for Aes<K> {
+    /// [`CipherModeError::InvalidKeyLength`] unless the key is `K` bytes long
+    fn new(key: &[u8]) -> Result<Self, CipherModeError> {
+        let key: &[u8; K] = key.try_into().map_err(|_| CipherModeError::InvalidKeyLength { expected: K, got: key.len() })?;
+        Ok(Self::expand(key))
+    }
+}
+
+impl<const K: usize> BlockCipher for Aes<K> {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        let state = state_mut(block);
+        self.add_round_key(state, 0);
+        for round in 1..Self::ROUNDS {
+            sub_bytes(state, &S_BOX);
+            shift_rows(state);
+            mix_columns(state);
+            self.add_round_key(state, round);
+        }
+        sub_bytes(state, &S_BOX);
+        shift_rows(state);
+        self.add_round_key(state, Self::ROUNDS);
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        let state = state_mut(block);
+        self.add_round_key(state, Self::ROUNDS);
+        for round in (1..Self::ROUNDS).rev() {
+            inv_shift_rows(state);
+            sub_bytes(state, &INV_S_BOX);
+            self.add_round_key(state, round);
+            inv_mix_columns(state);
+        }
+        inv_shift_rows(state);
+        sub_bytes(state, &INV_S_BOX);
+        self.add_round_key(state, 0);
+    }
+
+    fn valid_key_lengths() -> RangeInclusive<usize> {
+        K..=K
+    }
+}
+
+/// The block as state; byte `row + 4 · column` is in the given row and column
+fn state_mut(block: &mut [u8]) -> &mut [u8; BLOCK_SIZE] {
+    let length = block.len();
+    block.try_into().unwrap_or_else(|_| panic!("an AES block is {} bytes long, not {}", BLOCK_SIZE, length))
+}
+
+fn sub_bytes(state: &mut [u8; BLOCK_SIZE], table: &[u8; 256]) {
+    state.iter_mut().for_each(|byte| *byte = table[*byte as usize]);
+}
+
+/// Rotates row r to the left by r positions
+fn shift_rows(state: &mut [u8; BLOCK_SIZE]) {
+    for row in 1..4 {
+        let mut bytes = [0u8; 4];
+        (0..4).for_each(|column| bytes[column] = state[row + 4 * column]);
+        bytes.rotate_left(row);
+        (0..4).for_each(|column| state[row + 4 * column] = bytes[column]);
+    }
+}
+
+fn inv_shift_rows(state: &mut [u8; BLOCK_SIZE]) {
+    for row in 1..4 {
+        let mut bytes = [0u8; 4];
+        (0..4).for_each(|column| bytes[column] = state[row + 4 * column]);
+        bytes.rotate_right(row);
+        (0..4).for_each(|column| state[row + 4 * column] = bytes[column]);
+    }
+}
+
+/// Multiplies every column with the circulant matrix of `coefficients`
+fn mix_with(state: &mut [u8; BLOCK_SIZE], coefficients: [u8; 4]) {
+    for column in state.chunks_exact_mut(4) {
+        let input: [u8; 4] = column.try_into().expect("a column is 4 bytes");
+        for (row, output) in column.iter_mut().enumerate() {
+            *output = (0..4).fold(0, |sum, i| sum ^ gf256::mul(coefficients[(4 + i - row) % 4], input[i]));
+        }
+    }
+}
+
+/// Columns times 03x³ + 01x² + 01x + 02
+fn mix_columns(state: &mut [u8; BLOCK_SIZE]) {
+    mix_with(state, [0x02, 0x03, 0x01, 0x01]);
+}
+
+/// Columns times 0Bx³ + 0Dx² + 09x + 0E, the inverse of [`mix_columns`]
+fn inv_mix_columns(state: &mut [u8; BLOCK_SIZE]) {
+    mix_with(state, [0x0e, 0x0b, 0x0d, 0x09]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check<const K: usize>(key: &str, expected: &str) {
+        // FIPS 197, appendix C: plaintext 00112233…eeff, key 000102…
+        let plaintext = codec::hex::decode("00112233445566778899aabbccddeeff").unwrap();
+        let cipher = Aes::<K>::new(&codec::hex::decode(key).unwrap()).unwrap();
+        let mut block = plaintext.clone();
+        cipher.encrypt_block(&mut block);
+        assert_eq!(block, codec::hex::decode(expected).unwrap());
+        cipher.decrypt_block(&mut block);
+        assert_eq!(block, plaintext);
+    }
+
+    #[test]
+    fn test_matches_fips197() {
+        check::<16>("000102030405060708090a0b0c0d0e0f", "69c4e0d86a7b0430d8cdb78070b4c55a");
+        check::<24>("000102030405060708090a0b0c0d0e0f1011121314151617", "dda97ca4864cdfe06eaf70a0ec0d7191");
+        check::<32>("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f", "8ea2b7ca516745bfeafc49904b496089");
+    }
+
+    #[test]
+    fn test_key_expansion() {
+        // FIPS 197, appendix A.1: the last round key of 2b7e1516…
+        let cipher = Aes128::new(&codec::hex::decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap()).unwrap();
+        assert_eq!(&cipher.round_keys[160..], codec::hex::decode("d014f9a8c9ee2589e13f0cc8b6630ca6").unwrap());
+    }
+
+    #[test]
+    fn test_rejects_wrong_key_length() {
+        assert_eq!(Aes256::new(&[0; 16]).unwrap_err(), CipherModeError::InvalidKeyLength { expected: 32, got: 16 });
+    }
+}
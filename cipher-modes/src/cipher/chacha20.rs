@@ -0,0 +1,216 @@
+//! ChaCha20 (RFC 8439), a stream cipher from add, rotate and XOR
+//!
+//! The state is a 4×4 matrix of 32-bit words: four constants, the 256-bit
+//! key, a 32-bit block counter and the 96-bit nonce. Twenty rounds of
+//! quarter rounds, alternating over columns and diagonals, mix it; adding
+//! the input state afterwards makes the block function one-way. Each block
+//! gives 64 bytes of keystream.
+//!
+//! There are no table lookups, so unlike the AES here the running time
+//! does not depend on key or data. The counter limits one nonce to 2^32
+//! blocks, 256 GiB; [`ChaCha20`] panics rather than wrap around and repeat
+//! the keystream.
+
+use secret::Secret;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{CipherModeError, StreamCipher};
+
+/// Key length in bytes
+pub const KEY_SIZE: usize = 32;
+
+/// Nonce length in bytes
+pub const NONCE_SIZE: usize = 12;
+
+/// Keystream bytes per block
+pub const BLOCK_SIZE: usize = 64;
+
+/// Keystream bytes per nonce, 2^32 blocks
+const MAX_POSITION: u64 = (BLOCK_SIZE as u64) << 32;
+
+/// "expand 32-byte k" as little-endian words
+const CONSTANTS: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+/// ChaCha20 keystream for one key and nonce, starting at block 0
+///
+/// RFC 8439 encrypts from block 1 on and keeps block 0 for the Poly1305
+/// key; [`StreamCipher::seek`] to 64 for that.
+#[derive(Debug, Clone)]
+pub struct ChaCha20 {
+    key: Secret<[u32; 8]>,
+    nonce: [u32; 3],
+    /// Position in the keystream in bytes
+    position: u64,
+    /// The keystream block that contains `position`, if computed
+    keystream: Secret<[u8; BLOCK_SIZE]>,
+    block: Option<u32>,
+}
+
+impl ChaCha20 {
+    /// [`CipherModeError::InvalidKeyLength`] unless the key is 32 bytes,
+    /// [`CipherModeError::InvalidIvLength`] unless the nonce is 12 bytes long
+    pub fn new(key: &[u8], nonce: &[u8]) -> Result<Self, CipherModeError> {
+        if key.len() != KEY_SIZE {
+            return Err(CipherModeError::InvalidKeyLength { expected: KEY_SIZE, got: key.len() });
+        }
+        if nonce.len() != NONCE_SIZE {
+            return Err(CipherModeError::InvalidIvLength { expected: NONCE_SIZE, actual: nonce.len() });
+        }
+        Ok(ChaCha20 {
+            key: Secret::new(words(key)),
+            nonce: words(nonce),
+            position: 0,
+            keystream: Secret::new([0; BLOCK_SIZE]),
+            block: None,
+        })
+    }
+}
+
+/// Overwrites key and buffered keystream with zeros
+#[cfg(feature = "zeroize")]
+impl Zeroize for ChaCha20 {
+    fn zeroize(&mut self) {
+        self.key.as_mut_slice().zeroize();
+        self.keystream.as_mut_slice().zeroize();
+        self.block = None;
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for ChaCha20 {}
+
+impl StreamCipher for ChaCha20 {
+    /// Panics if the keystream would run past block 2^32 - 1
+    fn apply_keystream(&mut self, data: &mut [u8]) {
+        let end = self.position.checked_add(data.len() as u64);
+        assert!(end.is_some_and(|end| end <= MAX_POSITION), "ChaCha20 keystream exhausted for this nonce");
+        for byte in data {
+            let block = (self.position / BLOCK_SIZE as u64) as u32;
+            if self.block != Some(block) {
+                *self.keystream = chacha20_block(&self.key, block, &self.nonce);
+                self.block = Some(block);
+            }
+            *byte ^= self.keystream[(self.position % BLOCK_SIZE as u64) as usize];
+            self.position += 1;
+        }
+    }
+
+    fn seek(&mut self, position: u64) {
+        self.position = position;
+    }
+
+    fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+/// The block function: 20 rounds over the state, plus the state
+pub fn chacha20_block(key: &[u32; 8], counter: u32, nonce: &[u32; 3]) -> [u8; BLOCK_SIZE] {
+    let mut state = [0; 16];
+    state[..4].copy_from_slice(&CONSTANTS);
+    state[4..12].copy_from_slice(key);
+    state[12] = counter;
+    state[13..].copy_from_slice(nonce);
+
+    let mut working = state;
+    for _ in 0..10 {
+        quarter_round(&mut working, 0, 4, 8, 12);
+        quarter_round(&mut working, 1, 5, 9, 13);
+        quarter_round(&mut working, 2, 6, 10, 14);
+        quarter_round(&mut working, 3, 7, 11, 15);
+        quarter_round(&mut working, 0, 5, 10, 15);
+        quarter_round(&mut working, 1, 6, 11, 12);
+        quarter_round(&mut working, 2, 7, 8, 13);
+        quarter_round(&mut working, 3, 4, 9, 14);
+    }
+
+    let mut output = [0; BLOCK_SIZE];
+    for (i, chunk) in output.chunks_exact_mut(4).enumerate() {
+        chunk.copy_from_slice(&working[i].wrapping_add(state[i]).to_le_bytes());
+    }
+    state.fill(0);
+    working.fill(0);
+    output
+}
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+/// Little-endian words of a byte string of 4·N bytes
+fn words<const N: usize>(bytes: &[u8]) -> [u32; N] {
+    std::array::from_fn(|i| u32::from_le_bytes(bytes[4 * i..4 * i + 4].try_into().expect("a word is 4 bytes")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 00 01 02 … 1f, the key of the RFC examples
+    fn key() -> Vec<u8> {
+        (0..32).collect()
+    }
+
+    #[test]
+    fn test_block_function() {
+        // RFC 8439, section 2.3.2
+        let nonce = words(&codec::hex::decode("000000090000004a00000000").unwrap());
+        let block = chacha20_block(&words(&key()), 1, &nonce);
+        let expected = codec::hex::decode(
+            "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4e
+             d2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e",
+        ).unwrap();
+        assert_eq!(block.as_slice(), expected);
+    }
+
+    #[test]
+    fn test_encryption() {
+        // RFC 8439, section 2.4.2, starting at block 1
+        let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+        let expected = codec::hex::decode(
+            "6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0b
+             f91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d8
+             07ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab7793736
+             5af90bbf74a35be6b40b8eedf2785e42874d",
+        ).unwrap();
+        let mut cipher = ChaCha20::new(&key(), &codec::hex::decode("000000000000004a00000000").unwrap()).unwrap();
+        cipher.seek(64);
+        let mut data = plaintext.to_vec();
+        cipher.apply_keystream(&mut data);
+        assert_eq!(data, expected);
+        assert_eq!(cipher.position(), 64 + plaintext.len() as u64);
+
+        // In pieces across block boundaries, and back again after seeking
+        cipher.seek(64);
+        let mut pieces = plaintext.to_vec();
+        let (first, rest) = pieces.split_at_mut(50);
+        cipher.apply_keystream(first);
+        cipher.apply_keystream(rest);
+        assert_eq!(pieces, expected);
+        cipher.seek(64);
+        cipher.apply_keystream(&mut pieces);
+        assert_eq!(pieces, plaintext);
+    }
+
+    #[test]
+    fn test_rejects_wrong_lengths() {
+        assert_eq!(ChaCha20::new(&[0; 16], &[0; 12]).unwrap_err(), CipherModeError::InvalidKeyLength { expected: 32, got: 16 });
+        assert_eq!(ChaCha20::new(&[0; 32], &[0; 8]).unwrap_err(), CipherModeError::InvalidIvLength { expected: 12, actual: 8 });
+    }
+
+    #[test]
+    #[should_panic(expected = "keystream exhausted")]
+    fn test_counter_does_not_wrap() {
+        let mut cipher = ChaCha20::new(&key(), &[0; 12]).unwrap();
+        cipher.seek(MAX_POSITION);
+        cipher.apply_keystream(&mut [0]);
+    }
+}
@@ -0,0 +1,149 @@
+//! The previous cipher interface with a key per call
+//!
+//! Before [`BlockCipher`] held its key, a cipher got the key with every
+//! block. [`LegacyBlockCipher`] is that interface, unchanged; [`WithKey`]
+//! pairs such a cipher with a key and so turns it into a [`BlockCipher`]
+//! for the modes:
+//!
+//! ```text
+//! let cipher = WithKey::<_, 16>::new(OldCipher, &key)?;
+//! CipherModes::cbc_encrypt(&cipher, &iv, plaintext, Padding::Pkcs7)?;
+//! ```
+//!
+//! The shim calls the allocating methods once per block, so a cipher that
+//! expands its key on each call still does so. New ciphers implement
+//! [`BlockCipher`] and [`KeyInit`] directly.
+
+use std::ops::RangeInclusive;
+
+use secret::Secret;
+
+use crate::{BlockCipher, CipherModeError, KeyInit};
+
+/// A block cipher that takes the key with every block
+pub trait LegacyBlockCipher {
+    /// Encrypts exactly one block of `block_size()` bytes
+    fn encrypt(&self, key: &[u8], block: &[u8]) -> Vec<u8>;
+
+    /// Decrypts exactly one block of `block_size()` bytes
+    fn decrypt(&self, key: &[u8], block: &[u8]) -> Vec<u8>;
+
+    /// Block size in bytes
+    fn block_size(&self) -> usize;
+
+    /// Key lengths in bytes the cipher works with, any by default
+    fn valid_key_lengths() -> RangeInclusive<usize>
+    where
+        Self: Sized,
+    {
+        0..=usize::MAX
+    }
+}
+
+/// A [`LegacyBlockCipher`] together with its key
+///
+/// The legacy trait only knows its block size at run time; `B` states it
+/// for [`BlockCipher::BLOCK_SIZE`] and is checked against the cipher once,
+/// here. The key is wiped when the wrapper is dropped.
+#[derive(Debug, Clone)]
+pub struct WithKey<C, const B: usize> {
+    cipher: C,
+    key: Secret<Vec<u8>>,
+}
+
+impl<C: LegacyBlockCipher, const B: usize> WithKey<C, B> {
+    /// [`CipherModeError::InvalidBlockSize`] if the cipher's block size is not
+    /// `B`, [`CipherModeError::InvalidKeyLength`] if it does not take keys of
+    /// this length
+    ///
+    /// A legacy cipher would only notice a wrong key in the middle of a
+    /// mode, with every block; this checks it once.
+    pub fn new(cipher: C, key: &[u8]) -> Result<Self, CipherModeError> {
+        if cipher.block_size() != B {
+            return Err(CipherModeError::InvalidBlockSize);
+        }
+        Self::check_key_length(key)?;
+        Ok(WithKey { cipher, key: Secret::new(key.to_vec()) })
+    }
+}
+
+impl<C: LegacyBlockCipher, const B: usize> BlockCipher for WithKey<C, B> {
+    const BLOCK_SIZE: usize = B;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        let encrypted = self.cipher.encrypt(&self.key, block);
+        block.copy_from_slice(&encrypted);
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        let decrypted = self.cipher.decrypt(&self.key, block);
+        block.copy_from_slice(&decrypted);
+    }
+
+    fn valid_key_lengths() -> RangeInclusive<usize> {
+        C::valid_key_lengths()
+    }
+}
+
+/// For legacy ciphers without state of their own
+impl<C: LegacyBlockCipher + Default, const B: usize> KeyInit for WithKey<C, B> {
+    fn new(key: &[u8]) -> Result<Self, CipherModeError> {
+        WithKey::new(C::default(), key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CipherModes, DummyCipher, KeyInit, Padding};
+
+    const KEY: &[u8] = b"secret key 16 by";
+    const IV: &[u8] = b"initial vector!!";
+
+    /// The old DummyCipher: XOR with the repeated key
+    #[derive(Default)]
+    struct XorCipher;
+
+    impl LegacyBlockCipher for XorCipher {
+        fn encrypt(&self, key: &[u8], block: &[u8]) -> Vec<u8> {
+            block.iter().zip(key.iter().cycle()).map(|(b, k)| b ^ k).collect()
+        }
+
+        fn decrypt(&self, key: &[u8], block: &[u8]) -> Vec<u8> {
+            self.encrypt(key, block)
+        }
+
+        fn block_size(&self) -> usize {
+            16
+        }
+
+        /// An empty key would leave the blocks short
+        fn valid_key_lengths() -> RangeInclusive<usize> {
+            1..=usize::MAX
+        }
+    }
+
+    #[test]
+    fn test_matches_keyed_cipher() {
+        let plaintext: Vec<u8> = (0..40).collect();
+        let legacy = <WithKey<XorCipher, 16> as KeyInit>::new(KEY).unwrap();
+        let keyed = DummyCipher::<16>::new(KEY).unwrap();
+        assert_eq!(
+            CipherModes::cbc_encrypt(&legacy, IV, &plaintext, Padding::Pkcs7).unwrap(),
+            CipherModes::cbc_encrypt(&keyed, IV, &plaintext, Padding::Pkcs7).unwrap()
+        );
+        let ciphertext = CipherModes::ctr_encrypt(&legacy, IV, &plaintext).unwrap();
+        assert_eq!(CipherModes::ctr_decrypt(&keyed, IV, &ciphertext).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn test_checks_key_up_front() {
+        assert_eq!(
+            WithKey::<_, 16>::new(XorCipher, b"").map(|_| ()),
+            Err(CipherModeError::InvalidKeyLength { expected: 1, got: 0 })
+        );
+        assert_eq!(WithKey::<XorCipher, 16>::valid_key_lengths(), 1..=usize::MAX);
+        assert_eq!(crate::Aes192::valid_key_lengths(), 24..=24);
+        assert_eq!(crate::Aes192::check_key_length(&[0; 16]), Err(CipherModeError::InvalidKeyLength { expected: 24, got: 16 }));
+    }
+}
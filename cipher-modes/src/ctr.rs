@@ -0,0 +1,130 @@
+//! Counter block layouts for CTR
+//!
+//! [`CipherModes::ctr_encrypt`](crate::CipherModes::ctr_encrypt) treats the
+//! whole block as one big-endian counter. Protocols split the block
+//! instead: a fixed nonce in one part, a counter of a few bytes in the
+//! other, which wraps around without touching the nonce. [`CtrLayout`]
+//! describes such a split:
+//!
+//! ```text
+//! CtrLayout::new(12, 4)                      nonce (12) ‖ counter (4, big-endian)
+//! position: Start, endianness: Little, 8/8   counter (8, little-endian) ‖ nonce (8)
+//! ```
+//!
+//! Bytes covered by neither nonce nor counter are zero.
+
+use crate::CipherModeError;
+
+/// Byte order of the counter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Endianness {
+    #[default]
+    Big,
+    Little,
+}
+
+/// Which end of the block holds the counter; the nonce starts at the other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CounterPosition {
+    Start,
+    #[default]
+    End,
+}
+
+/// How a CTR counter block is built from a nonce and the block number
+///
+/// Block i is encrypted under the counter `initial_counter + i · step`,
+/// taken modulo 2^(8 · counter_width).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CtrLayout {
+    /// Length of the nonce in bytes
+    pub nonce_length: usize,
+    /// Length of the counter in bytes, 1 to 16
+    pub counter_width: usize,
+    pub endianness: Endianness,
+    pub position: CounterPosition,
+    /// Added to the counter after every block
+    pub step: u64,
+    /// Counter of the first block
+    pub initial_counter: u64,
+}
+
+impl CtrLayout {
+    /// Nonce followed by a big-endian counter that starts at 0 and counts up by 1
+    pub fn new(nonce_length: usize, counter_width: usize) -> Self {
+        CtrLayout {
+            nonce_length,
+            counter_width,
+            endianness: Endianness::Big,
+            position: CounterPosition::End,
+            step: 1,
+            initial_counter: 0,
+        }
+    }
+
+    /// [`CipherModeError::InvalidBlockSize`] if nonce and counter do not fit
+    /// into a block, [`CipherModeError::InvalidIvLength`] for a nonce of the
+    /// wrong length
+    pub(crate) fn check(&self, nonce: &[u8], block_size: usize) -> Result<(), CipherModeError> {
+        if !(1..=16).contains(&self.counter_width) || self.nonce_length + self.counter_width > block_size {
+            return Err(CipherModeError::InvalidBlockSize);
+        }
+        if nonce.len() != self.nonce_length {
+            return Err(CipherModeError::InvalidIvLength { expected: self.nonce_length, actual: nonce.len() });
+        }
+        Ok(())
+    }
+
+    /// The counter block for block number `index`; the layout must be checked
+    pub(crate) fn counter_block(&self, nonce: &[u8], index: u64, block: &mut [u8]) {
+        let counter = u128::from(self.initial_counter).wrapping_add(u128::from(index) * u128::from(self.step));
+        let bytes = match self.endianness {
+            Endianness::Big => counter.to_be_bytes()[16 - self.counter_width..].to_vec(),
+            Endianness::Little => counter.to_le_bytes()[..self.counter_width].to_vec(),
+        };
+        block.fill(0);
+        let length = block.len();
+        match self.position {
+            CounterPosition::Start => {
+                block[..self.counter_width].copy_from_slice(&bytes);
+                block[length - self.nonce_length..].copy_from_slice(nonce);
+            }
+            CounterPosition::End => {
+                block[..self.nonce_length].copy_from_slice(nonce);
+                block[length - self.counter_width..].copy_from_slice(&bytes);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_blocks() {
+        let mut block = [0; 8];
+        CtrLayout::new(4, 2).counter_block(b"abcd", 0x1234, &mut block);
+        assert_eq!(block, *b"abcd\0\0\x12\x34");
+
+        let little = CtrLayout { endianness: Endianness::Little, position: CounterPosition::Start, ..CtrLayout::new(4, 4) };
+        little.counter_block(b"abcd", 0x0102, &mut block);
+        assert_eq!(block, *b"\x02\x01\0\0abcd");
+
+        // The counter wraps around within its width
+        let stepped = CtrLayout { step: 0x80, initial_counter: 0xff00, ..CtrLayout::new(6, 2) };
+        stepped.counter_block(b"nonce!", 2, &mut block);
+        assert_eq!(block, *b"nonce!\0\0");
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(CtrLayout::new(12, 4).check(&[0; 12], 16), Ok(()));
+        assert_eq!(CtrLayout::new(12, 8).check(&[0; 12], 16), Err(CipherModeError::InvalidBlockSize));
+        assert_eq!(CtrLayout::new(8, 0).check(&[0; 8], 16), Err(CipherModeError::InvalidBlockSize));
+        assert_eq!(CtrLayout::new(8, 8).check(&[0; 7], 16), Err(CipherModeError::InvalidIvLength { expected: 8, actual: 7 }));
+    }
+}
@@ -0,0 +1,105 @@
+//! EAX, authenticated encryption from CTR and OMAC (Bellare, Rogaway, Wagner 2004)
+//!
+//! OMAC is [`Cmac`] with a one-block tweak in front of the message:
+//! `OMAC^t(M) = CMAC([t]_n ‖ M)`. EAX uses three tweaks to keep the three
+//! MACs apart:
+//!
+//! ```text
+//! N = OMAC^0(nonce)    H = OMAC^1(aad)    C = CTR_N(plaintext)
+//! tag = N ⊕ H ⊕ OMAC^2(C)
+//! ```
+//!
+//! The ciphertext is C followed by the tag, one block long. Decryption
+//! checks the tag before it decrypts anything, so a modified ciphertext,
+//! nonce or associated data never produces plaintext. The nonce may have
+//! any length but must never repeat under the same key: two messages with
+//! the same nonce share the CTR keystream.
+//!
+//! Like CMAC, EAX needs a block size of 8 or 16 bytes.
+
+use crate::mac::Cmac;
+use crate::modes::CipherModes;
+use crate::utils::xor_blocks;
+use crate::{BlockCipher, CipherModeError, block_size_of};
+
+/// Encrypts and authenticates `plaintext` and authenticates `aad`
+///
+/// Returns the ciphertext with the tag appended.
+pub fn eax_encrypt<C: BlockCipher>(cipher: &C, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    let n = omac(cipher, 0, nonce)?;
+    let mut output = CipherModes::ctr_encrypt(cipher, &n, plaintext)?;
+    let tag = tag(cipher, &n, aad, &output)?;
+    output.extend(tag);
+    Ok(output)
+}
+
+/// Checks the tag and decrypts; [`CipherModeError::AuthenticationFailed`]
+/// if anything was modified
+pub fn eax_decrypt<C: BlockCipher>(cipher: &C, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    let block_size = block_size_of::<C>();
+    let Some(split) = ciphertext.len().checked_sub(block_size) else {
+        return Err(CipherModeError::AuthenticationFailed);
+    };
+    let (ciphertext, received) = ciphertext.split_at(split);
+    let n = omac(cipher, 0, nonce)?;
+    if !secret::ct::eq(&tag(cipher, &n, aad, ciphertext)?, received) {
+        return Err(CipherModeError::AuthenticationFailed);
+    }
+    CipherModes::ctr_decrypt(cipher, &n, ciphertext)
+}
+
+/// N ⊕ OMAC^1(aad) ⊕ OMAC^2(ciphertext)
+fn tag<C: BlockCipher>(cipher: &C, n: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    let h = omac(cipher, 1, aad)?;
+    let c = omac(cipher, 2, ciphertext)?;
+    Ok(xor_blocks(&xor_blocks(n, &h), &c))
+}
+
+/// OMAC^t(M) = CMAC([t]_n ‖ M)
+fn omac<C: BlockCipher>(cipher: &C, tweak: u8, message: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    let mut tweaked = vec![0; block_size_of::<C>()];
+    *tweaked.last_mut().ok_or(CipherModeError::InvalidBlockSize)? = tweak;
+    let mut mac = Cmac::new(cipher)?;
+    mac.update(&tweaked);
+    mac.update(message);
+    Ok(mac.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DummyCipher, KeyInit};
+
+    const KEY: &[u8] = b"secret key 16 by";
+
+    fn roundtrip<const B: usize>() {
+        let cipher = DummyCipher::<B>::new(KEY).unwrap();
+        for length in [0, 1, B, 40] {
+            let plaintext: Vec<u8> = (0..length as u8).collect();
+            let ciphertext = eax_encrypt(&cipher, b"nonce", b"header", &plaintext).unwrap();
+            assert_eq!(ciphertext.len(), length + B);
+            assert_eq!(eax_decrypt(&cipher, b"nonce", b"header", &ciphertext).unwrap(), plaintext);
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        roundtrip::<8>();
+        roundtrip::<16>();
+    }
+
+    #[test]
+    fn test_rejects_tampering() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let ciphertext = eax_encrypt(&cipher, b"nonce", b"header", b"attack at dawn").unwrap();
+        for position in [0, 13, 14, ciphertext.len() - 1] {
+            let mut tampered = ciphertext.clone();
+            tampered[position] ^= 1;
+            assert_eq!(eax_decrypt(&cipher, b"nonce", b"header", &tampered), Err(CipherModeError::AuthenticationFailed));
+        }
+        assert_eq!(eax_decrypt(&cipher, b"nonce", b"Header", &ciphertext), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(eax_decrypt(&cipher, b"Nonce", b"header", &ciphertext), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(eax_decrypt(&cipher, b"nonce", b"header", &ciphertext[..15]), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(eax_encrypt(&DummyCipher::<12>::new(KEY).unwrap(), b"nonce", b"", b"data"), Err(CipherModeError::InvalidBlockSize));
+    }
+}
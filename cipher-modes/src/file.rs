@@ -0,0 +1,425 @@
+//! A self-describing file format for encrypted data
+//!
+//! An [`EncryptedFile`] names everything needed to decrypt it except the
+//! key or password: algorithm, key derivation, nonce. All numbers are
+//! big-endian:
+//!
+//! ```text
+//! "CMEF" u8 version (1)
+//! u8 algorithm                     see FileAlgorithm
+//! u8 KDF                           0 raw key | 1 PBKDF2-HMAC-SHA256: u32 rounds, u8 length ‖ salt
+//! u8 length ‖ nonce
+//! u64 length ‖ ciphertext
+//! tag                              32 bytes HMAC-SHA256 or 16 bytes AEAD tag
+//! ```
+//!
+//! Everything before the ciphertext is the header. The AEADs take it as
+//! associated data; CBC and CTR are followed by HMAC-SHA256 over header and
+//! ciphertext (encrypt-then-MAC). A changed algorithm, salt or round count
+//! therefore fails like a changed ciphertext.
+//!
+//! The master key is either 32 bytes given directly or PBKDF2-HMAC-SHA256
+//! of a password. Cipher and MAC key are derived from it as
+//! HMAC-SHA256(master, label), so the two never coincide.
+
+use std::io::{self, Read, Write};
+
+use rand_core::{CryptoRng, RngCore};
+use secret::Secret;
+
+use crate::hash::Sha256;
+use crate::kdf::pbkdf2;
+use crate::mac::hmac;
+use crate::{
+    Aes256, CipherModeError, CipherModes, KeyInit, Padding, ascon128_decrypt, ascon128_encrypt, chacha20_poly1305_decrypt,
+    chacha20_poly1305_encrypt, eax_decrypt, eax_encrypt,
+};
+
+const MAGIC: &[u8] = b"CMEF";
+const VERSION: u8 = 1;
+
+/// Length of a raw master key and of the PBKDF2 output
+pub const MASTER_KEY_SIZE: usize = 32;
+
+/// Salt length for new files
+const SALT_SIZE: usize = 16;
+
+/// PBKDF2-HMAC-SHA256 rounds for new files (OWASP recommendation)
+pub const DEFAULT_ROUNDS: u32 = 600_000;
+
+/// Most PBKDF2 rounds [`EncryptedFile::read_from`] accepts
+///
+/// The round count is read before anything is authenticated; without a
+/// bound a forged file could make [`EncryptedFile::decrypt`] run 2^32 rounds.
+pub const MAX_ROUNDS: u32 = 16 * DEFAULT_ROUNDS;
+
+const ENCRYPTION_LABEL: &[u8] = b"cipher-modes file encryption";
+const AUTHENTICATION_LABEL: &[u8] = b"cipher-modes file authentication";
+
+/// The cipher and how the file is authenticated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileAlgorithm {
+    /// AES-256-CBC with PKCS#7 padding and HMAC-SHA256
+    Aes256CbcHmac = 1,
+    /// AES-256-CTR, the nonce is the initial counter, and HMAC-SHA256
+    Aes256CtrHmac = 2,
+    Aes256Eax = 3,
+    ChaCha20Poly1305 = 4,
+    Ascon128 = 5,
+}
+
+impl FileAlgorithm {
+    const ALL: [FileAlgorithm; 5] = [
+        FileAlgorithm::Aes256CbcHmac,
+        FileAlgorithm::Aes256CtrHmac,
+        FileAlgorithm::Aes256Eax,
+        FileAlgorithm::ChaCha20Poly1305,
+        FileAlgorithm::Ascon128,
+    ];
+
+    fn from_byte(byte: u8) -> Option<FileAlgorithm> {
+        FileAlgorithm::ALL.into_iter().find(|&algorithm| algorithm as u8 == byte)
+    }
+
+    fn key_size(self) -> usize {
+        match self {
+            FileAlgorithm::Ascon128 => crate::ascon::KEY_SIZE,
+            _ => 32,
+        }
+    }
+
+    fn nonce_size(self) -> usize {
+        match self {
+            FileAlgorithm::ChaCha20Poly1305 => 12,
+            _ => 16,
+        }
+    }
+
+    fn tag_size(self) -> usize {
+        match self {
+            FileAlgorithm::Aes256CbcHmac | FileAlgorithm::Aes256CtrHmac => 32,
+            _ => 16,
+        }
+    }
+}
+
+/// How the master key is obtained
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kdf {
+    /// The key is given directly, [`MASTER_KEY_SIZE`] bytes
+    Raw,
+    /// PBKDF2-HMAC-SHA256 of a password
+    Pbkdf2Sha256 { rounds: u32, salt: Vec<u8> },
+}
+
+/// An encrypted file, as read or to be written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedFile {
+    pub algorithm: FileAlgorithm,
+    pub kdf: Kdf,
+    pub nonce: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+    pub tag: Vec<u8>,
+}
+
+impl EncryptedFile {
+    /// Encrypts under a master key of [`MASTER_KEY_SIZE`] bytes
+    pub fn encrypt_with_key(algorithm: FileAlgorithm, key: &[u8], plaintext: &[u8], rng: &mut (impl RngCore + CryptoRng)) -> Result<Self, CipherModeError> {
+        Self::encrypt(algorithm, Kdf::Raw, key, plaintext, rng)
+    }
+
+    /// Encrypts under a key derived from `password` with a fresh salt
+    ///
+    /// [`CipherModeError::InvalidRoundCount`] unless `rounds` is in
+    /// `1..=`[`MAX_ROUNDS`], since [`EncryptedFile::read_from`] would refuse
+    /// the file.
+    pub fn encrypt_with_password(algorithm: FileAlgorithm, password: &[u8], rounds: u32, plaintext: &[u8], rng: &mut (impl RngCore + CryptoRng)) -> Result<Self, CipherModeError> {
+        if !(1..=MAX_ROUNDS).contains(&rounds) {
+            return Err(CipherModeError::InvalidRoundCount { rounds, max: MAX_ROUNDS });
+        }
+        let mut salt = vec![0; SALT_SIZE];
+        rng.fill_bytes(&mut salt);
+        Self::encrypt(algorithm, Kdf::Pbkdf2Sha256 { rounds, salt }, password, plaintext, rng)
+    }
+
+    fn encrypt(algorithm: FileAlgorithm, kdf: Kdf, secret: &[u8], plaintext: &[u8], rng: &mut (impl RngCore + CryptoRng)) -> Result<Self, CipherModeError> {
+        let mut nonce = vec![0; algorithm.nonce_size()];
+        rng.fill_bytes(&mut nonce);
+        let mut file = EncryptedFile { algorithm, kdf, nonce, ciphertext: Vec::new(), tag: Vec::new() };
+        let Keys { cipher: key, mac: mac_key } = file.keys(secret)?;
+
+        let output = match algorithm {
+            FileAlgorithm::Aes256CbcHmac => CipherModes::cbc_encrypt(&Aes256::new(&key)?, &file.nonce, plaintext, Padding::Pkcs7)?,
+            FileAlgorithm::Aes256CtrHmac => CipherModes::ctr_encrypt(&Aes256::new(&key)?, &file.nonce, plaintext)?,
+            // The AEAD output is as long as the plaintext plus the tag
+            FileAlgorithm::Aes256Eax => eax_encrypt(&Aes256::new(&key)?, &file.nonce, &file.header(plaintext.len()), plaintext)?,
+            FileAlgorithm::ChaCha20Poly1305 => chacha20_poly1305_encrypt(&key, &file.nonce, &file.header(plaintext.len()), plaintext)?,
+            FileAlgorithm::Ascon128 => ascon128_encrypt(&key, &file.nonce, &file.header(plaintext.len()), plaintext)?,
+        };
+        match algorithm {
+            FileAlgorithm::Aes256CbcHmac | FileAlgorithm::Aes256CtrHmac => {
+                file.ciphertext = output;
+                file.tag = file.mac(&mac_key);
+            }
+            _ => {
+                let split = output.len() - algorithm.tag_size();
+                file.ciphertext = output[..split].to_vec();
+                file.tag = output[split..].to_vec();
+            }
+        }
+        Ok(file)
+    }
+
+    /// Decrypts with the key or password it was encrypted with
+    ///
+    /// [`CipherModeError::AuthenticationFailed`] if the password is wrong or
+    /// any part of the file was changed.
+    pub fn decrypt(&self, secret: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        if self.nonce.len() != self.algorithm.nonce_size() {
+            return Err(CipherModeError::InvalidIvLength { expected: self.algorithm.nonce_size(), actual: self.nonce.len() });
+        }
+        let Keys { cipher: key, mac: mac_key } = self.keys(secret)?;
+        let header = self.header(self.ciphertext.len());
+        let sealed = [&self.ciphertext[..], &self.tag].concat();
+        match self.algorithm {
+            FileAlgorithm::Aes256CbcHmac | FileAlgorithm::Aes256CtrHmac => {
+                if !secret::ct::eq(&self.mac(&mac_key), &self.tag) {
+                    return Err(CipherModeError::AuthenticationFailed);
+                }
+                let cipher = Aes256::new(&key)?;
+                if self.algorithm == FileAlgorithm::Aes256CbcHmac {
+                    CipherModes::cbc_decrypt(&cipher, &self.nonce, &self.ciphertext, Padding::Pkcs7)
+                } else {
+                    CipherModes::ctr_decrypt(&cipher, &self.nonce, &self.ciphertext)
+                }
+            }
+            FileAlgorithm::Aes256Eax => eax_decrypt(&Aes256::new(&key)?, &self.nonce, &header, &sealed),
+            FileAlgorithm::ChaCha20Poly1305 => chacha20_poly1305_decrypt(&key, &self.nonce, &header, &sealed),
+            FileAlgorithm::Ascon128 => ascon128_decrypt(&key, &self.nonce, &header, &sealed),
+        }
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(&self.header(self.ciphertext.len()))?;
+        writer.write_all(&self.ciphertext)?;
+        writer.write_all(&self.tag)
+    }
+
+    /// Reads a file; [`io::ErrorKind::InvalidData`] if it is not one or of
+    /// an unknown version
+    pub fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid_data("not an encrypted file".to_string()));
+        }
+        let version = read_u8(reader)?;
+        if version != VERSION {
+            return Err(invalid_data(format!("unsupported version {}", version)));
+        }
+        let algorithm = read_u8(reader)?;
+        let algorithm = FileAlgorithm::from_byte(algorithm).ok_or_else(|| invalid_data(format!("unknown algorithm {}", algorithm)))?;
+        let kdf = match read_u8(reader)? {
+            0 => Kdf::Raw,
+            1 => {
+                let mut rounds = [0; 4];
+                reader.read_exact(&mut rounds)?;
+                let rounds = u32::from_be_bytes(rounds);
+                if rounds == 0 {
+                    return Err(invalid_data("PBKDF2 with 0 rounds".to_string()));
+                }
+                if rounds > MAX_ROUNDS {
+                    return Err(invalid_data(format!("PBKDF2 with {} rounds, at most {} allowed", rounds, MAX_ROUNDS)));
+                }
+                let length = read_u8(reader)?;
+                Kdf::Pbkdf2Sha256 { rounds, salt: read_bytes(reader, length.into())? }
+            }
+            kdf => return Err(invalid_data(format!("unknown KDF {}", kdf))),
+        };
+        let length = read_u8(reader)?;
+        let nonce = read_bytes(reader, length.into())?;
+        let mut length = [0; 8];
+        reader.read_exact(&mut length)?;
+        let ciphertext = read_bytes(reader, u64::from_be_bytes(length))?;
+        let tag = read_bytes(reader, algorithm.tag_size() as u64)?;
+        Ok(EncryptedFile { algorithm, kdf, nonce, ciphertext, tag })
+    }
+
+    /// Everything before the ciphertext, for a ciphertext of `length` bytes
+    fn header(&self, length: usize) -> Vec<u8> {
+        let mut header = MAGIC.to_vec();
+        header.extend([VERSION, self.algorithm as u8]);
+        match &self.kdf {
+            Kdf::Raw => header.push(0),
+            Kdf::Pbkdf2Sha256 { rounds, salt } => {
+                header.push(1);
+                header.extend(rounds.to_be_bytes());
+                header.push(salt.len() as u8);
+                header.extend(salt);
+            }
+        }
+        header.push(self.nonce.len() as u8);
+        header.extend(&self.nonce);
+        header.extend((length as u64).to_be_bytes());
+        header
+    }
+
+    /// HMAC-SHA256 over header and ciphertext
+    fn mac(&self, mac_key: &[u8]) -> Vec<u8> {
+        hmac::<Sha256>(mac_key, &[self.header(self.ciphertext.len()), self.ciphertext.clone()].concat())
+    }
+
+    fn keys(&self, secret: &[u8]) -> Result<Keys, CipherModeError> {
+        let master = match &self.kdf {
+            Kdf::Raw => {
+                if secret.len() != MASTER_KEY_SIZE {
+                    return Err(CipherModeError::InvalidKeyLength { expected: MASTER_KEY_SIZE, got: secret.len() });
+                }
+                Secret::new(secret.to_vec())
+            }
+            Kdf::Pbkdf2Sha256 { rounds, salt } => pbkdf2::<Sha256>(secret, salt, *rounds, MASTER_KEY_SIZE),
+        };
+        let mut cipher = Secret::new(hmac::<Sha256>(&master, ENCRYPTION_LABEL));
+        cipher.truncate(self.algorithm.key_size());
+        Ok(Keys { cipher, mac: Secret::new(hmac::<Sha256>(&master, AUTHENTICATION_LABEL)) })
+    }
+}
+
+/// Cipher key and MAC key, derived from the master key
+struct Keys {
+    cipher: Secret<Vec<u8>>,
+    mac: Secret<Vec<u8>>,
+}
+
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut byte = [0];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+/// Exactly `length` bytes, without trusting `length` for the allocation
+fn read_bytes(reader: &mut impl Read, length: u64) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.take(length).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != length {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+    }
+    Ok(bytes)
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use csprng::{Algorithm, Drbg};
+
+    use super::*;
+
+    const PLAINTEXT: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+    fn rng() -> Drbg {
+        Drbg::from_seed(Algorithm::ChaCha20, b"encrypted file test")
+    }
+
+    fn roundtrip(file: &EncryptedFile) -> EncryptedFile {
+        let mut bytes = Vec::new();
+        file.write_to(&mut bytes).unwrap();
+        EncryptedFile::read_from(&mut &bytes[..]).unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut rng = rng();
+        for algorithm in FileAlgorithm::ALL {
+            let file = EncryptedFile::encrypt_with_key(algorithm, &[0x42; 32], PLAINTEXT, &mut rng).unwrap();
+            assert_eq!(file.tag.len(), algorithm.tag_size());
+            let read = roundtrip(&file);
+            assert_eq!(read, file);
+            assert_eq!(read.decrypt(&[0x42; 32]).unwrap(), PLAINTEXT, "{:?}", algorithm);
+
+            let file = EncryptedFile::encrypt_with_password(algorithm, b"correct horse", 10, PLAINTEXT, &mut rng).unwrap();
+            assert_eq!(roundtrip(&file).decrypt(b"correct horse").unwrap(), PLAINTEXT, "{:?}", algorithm);
+            assert_eq!(file.decrypt(b"battery staple"), Err(CipherModeError::AuthenticationFailed), "{:?}", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_layout() {
+        let file = EncryptedFile {
+            algorithm: FileAlgorithm::ChaCha20Poly1305,
+            kdf: Kdf::Pbkdf2Sha256 { rounds: 1000, salt: vec![0xaa; 2] },
+            nonce: vec![0xbb; 12],
+            ciphertext: vec![0xcc; 3],
+            tag: vec![0xdd; 16],
+        };
+        let mut bytes = Vec::new();
+        file.write_to(&mut bytes).unwrap();
+        let expected = [
+            &b"CMEF\x01\x04\x01"[..],
+            &[0x00, 0x00, 0x03, 0xe8, 2, 0xaa, 0xaa],
+            &[12],
+            &[0xbb; 12],
+            &[0, 0, 0, 0, 0, 0, 0, 3, 0xcc, 0xcc, 0xcc],
+            &[0xdd; 16],
+        ]
+        .concat();
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn test_rejects_changes() {
+        let mut rng = rng();
+        for algorithm in [FileAlgorithm::Aes256CbcHmac, FileAlgorithm::Aes256CtrHmac, FileAlgorithm::Ascon128] {
+            let file = EncryptedFile::encrypt_with_password(algorithm, b"password", 10, PLAINTEXT, &mut rng).unwrap();
+            let mut bytes = Vec::new();
+            file.write_to(&mut bytes).unwrap();
+            // Rounds, salt, nonce, ciphertext and tag
+            for position in [10, 12, 30, 60, bytes.len() - 1] {
+                let mut changed = bytes.clone();
+                changed[position] ^= 1;
+                let changed = EncryptedFile::read_from(&mut &changed[..]).unwrap();
+                assert_eq!(changed.decrypt(b"password"), Err(CipherModeError::AuthenticationFailed), "{:?} {}", algorithm, position);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        let file = EncryptedFile::encrypt_with_key(FileAlgorithm::Aes256Eax, &[1; 32], b"data", &mut rng()).unwrap();
+        let mut bytes = Vec::new();
+        file.write_to(&mut bytes).unwrap();
+
+        let read = |bytes: &[u8]| EncryptedFile::read_from(&mut &bytes[..]).unwrap_err().kind();
+        assert_eq!(read(&bytes[..bytes.len() - 1]), io::ErrorKind::UnexpectedEof);
+        assert_eq!(read(b"PK\x03\x04 and more"), io::ErrorKind::InvalidData);
+        for (position, value) in [(4, 2), (5, 0), (5, 6), (6, 2)] {
+            let mut changed = bytes.clone();
+            changed[position] = value;
+            assert_eq!(read(&changed), io::ErrorKind::InvalidData, "{} {}", position, value);
+        }
+        assert_eq!(file.decrypt(&[1; 16]), Err(CipherModeError::InvalidKeyLength { expected: 32, got: 16 }));
+    }
+
+    #[test]
+    fn test_rejects_round_counts() {
+        let file = EncryptedFile::encrypt_with_password(FileAlgorithm::Aes256Eax, b"password", 10, b"data", &mut rng()).unwrap();
+        let mut bytes = Vec::new();
+        file.write_to(&mut bytes).unwrap();
+
+        for rounds in [0, MAX_ROUNDS + 1, u32::MAX] {
+            bytes[7..11].copy_from_slice(&rounds.to_be_bytes());
+            let error = EncryptedFile::read_from(&mut &bytes[..]).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{}", rounds);
+        }
+        bytes[7..11].copy_from_slice(&MAX_ROUNDS.to_be_bytes());
+        assert!(EncryptedFile::read_from(&mut &bytes[..]).is_ok());
+
+        // Files that could not be read back are not written in the first place
+        for rounds in [0, MAX_ROUNDS + 1] {
+            let error = EncryptedFile::encrypt_with_password(FileAlgorithm::Aes256Eax, b"password", rounds, b"data", &mut rng()).unwrap_err();
+            assert_eq!(error, CipherModeError::InvalidRoundCount { rounds, max: MAX_ROUNDS });
+        }
+    }
+}
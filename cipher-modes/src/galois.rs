@@ -0,0 +1,154 @@
+//! Multiplication in GF(2^8) and GF(2^128)
+//!
+//! AES MixColumns multiplies in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1,
+//! GHASH and the XTS tweak in GF(2^128) modulo x^128 + x^7 + x^2 + x + 1.
+//! The arithmetic itself lives in the `gf` crate; this module picks the
+//! implementation at runtime with [`Backend`], so the fast path can be
+//! checked against the reference and timed against it:
+//!
+//! | | [`Backend::Reference`] | [`Backend::Accelerated`] |
+//! |---|---|---|
+//! | [`mul8`] | shift and add, constant time | log/exp tables |
+//! | [`mul128`] | SP 800-38D Algorithm 1, bit by bit | Karatsuba over 64-bit halves |
+//!
+//! The tables are indexed by the operands, so [`mul8`] with
+//! [`Backend::Accelerated`] leaks them through the cache; do not use it
+//! on secrets.
+//!
+//! GCM and XTS write the same field differently. GCM reads a block with the
+//! coefficient of x^0 in the top bit of the first byte, XTS in the low bit
+//! of the first byte. [`mul128`] takes GCM blocks, [`xts_double`] XTS ones.
+
+use gf::{gf128, gf256};
+
+/// Block size of GF(2^128) elements in bytes
+pub const BLOCK_SIZE: usize = 16;
+
+/// x^128 ≡ x^7 + x^2 + x + 1, in GCM bit order
+const GCM_REDUCTION: u128 = 0xe1 << 120;
+
+/// x^128 ≡ x^7 + x^2 + x + 1, in XTS bit order
+const XTS_REDUCTION: u8 = 0x87;
+
+/// Which implementation of the field arithmetic to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// Textbook shift and add, one bit at a time
+    Reference,
+    /// Tables for GF(2^8), Karatsuba for GF(2^128)
+    #[default]
+    Accelerated,
+}
+
+/// Product in GF(2^8), as in AES MixColumns
+pub fn mul8(a: u8, b: u8, backend: Backend) -> u8 {
+    match backend {
+        Backend::Reference => gf256::mul(a, b),
+        Backend::Accelerated => gf256::mul_table(a, b),
+    }
+}
+
+/// Product in GF(2^128) of two blocks in GCM bit order, as in GHASH
+pub fn mul128(x: &[u8; BLOCK_SIZE], y: &[u8; BLOCK_SIZE], backend: Backend) -> [u8; BLOCK_SIZE] {
+    let (x, y) = (gf128::from_block(x), gf128::from_block(y));
+    let product = match backend {
+        Backend::Reference => mul128_reference(x, y),
+        Backend::Accelerated => gf128::mul(x, y),
+    };
+    gf128::to_block(product)
+}
+
+/// NIST SP 800-38D, Algorithm 1: add `v` for every bit of `x`, then
+/// multiply `v` by x; no branches on the operands
+fn mul128_reference(x: u128, y: u128) -> u128 {
+    let (mut z, mut v) = (0, y);
+    for i in (0..128).rev() {
+        z ^= v & 0u128.wrapping_sub(x >> i & 1);
+        v = (v >> 1) ^ (GCM_REDUCTION & 0u128.wrapping_sub(v & 1));
+    }
+    z
+}
+
+/// Multiplies the XTS tweak by α = x (IEEE 1619, section 5.2)
+///
+/// The block is a little-endian number, so this shifts left by one bit and
+/// folds the bit shifted out of the last byte back into the first.
+pub fn xts_double(tweak: &[u8; BLOCK_SIZE]) -> [u8; BLOCK_SIZE] {
+    let value = u128::from_le_bytes(*tweak);
+    let carry = (value >> 127) as u8;
+    let mut doubled = (value << 1).to_le_bytes();
+    doubled[0] ^= XTS_REDUCTION & 0u8.wrapping_sub(carry);
+    doubled
+}
+
+#[cfg(test)]
+mod tests {
+    use csprng::{Algorithm, Drbg};
+    use rand_core::RngCore;
+
+    use super::*;
+
+    fn block(hex: &str) -> [u8; BLOCK_SIZE] {
+        u128::from_str_radix(hex, 16).unwrap().to_be_bytes()
+    }
+
+    #[test]
+    fn test_mul8() {
+        for backend in [Backend::Reference, Backend::Accelerated] {
+            // FIPS 197, section 4.2
+            assert_eq!(mul8(0x57, 0x83, backend), 0xc1);
+            assert_eq!(mul8(0x57, 0x13, backend), 0xfe);
+            assert_eq!(mul8(0, 0x13, backend), 0);
+        }
+        for a in 0..=255 {
+            for b in 0..=255 {
+                assert_eq!(mul8(a, b, Backend::Reference), mul8(a, b, Backend::Accelerated));
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul128() {
+        // Test case 2 of the GCM specification: X1 = C · H
+        let h = block("66e94bd4ef8a2c3b884cfa59ca342b2e");
+        let c = block("0388dace60b6a392f328c2b971b2fe78");
+        let one = gf128::to_block(gf128::ONE);
+        for backend in [Backend::Reference, Backend::Accelerated] {
+            assert_eq!(mul128(&c, &h, backend), block("5e2ec746917062882c85b0685353deb7"));
+            assert_eq!(mul128(&h, &one, backend), h);
+            assert_eq!(mul128(&h, &[0; BLOCK_SIZE], backend), [0; BLOCK_SIZE]);
+        }
+
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"galois test");
+        for _ in 0..100 {
+            let (mut x, mut y) = ([0; BLOCK_SIZE], [0; BLOCK_SIZE]);
+            rng.fill_bytes(&mut x);
+            rng.fill_bytes(&mut y);
+            assert_eq!(mul128(&x, &y, Backend::Reference), mul128(&x, &y, Backend::Accelerated));
+        }
+    }
+
+    #[test]
+    fn test_xts_double() {
+        let mut tweak = [0; BLOCK_SIZE];
+        tweak[0] = 1;
+        assert_eq!(xts_double(&tweak)[0], 2);
+        // x^127 · x = x^128 ≡ x^7 + x^2 + x + 1
+        tweak = [0; BLOCK_SIZE];
+        tweak[15] = 0x80;
+        let mut expected = [0; BLOCK_SIZE];
+        expected[0] = 0x87;
+        assert_eq!(xts_double(&tweak), expected);
+        // A carry between bytes
+        assert_eq!(xts_double(&[0x80, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0])[..3], [0, 1, 1]);
+
+        // The same as multiplying by x in GCM order, with every bit mirrored
+        let to_gcm = |block: [u8; BLOCK_SIZE]| u128::from_le_bytes(block).reverse_bits().to_be_bytes();
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"xts tweak test");
+        let x = to_gcm([2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        for _ in 0..100 {
+            rng.fill_bytes(&mut tweak);
+            assert_eq!(to_gcm(xts_double(&tweak)), mul128(&to_gcm(tweak), &x, Backend::Reference));
+        }
+    }
+}
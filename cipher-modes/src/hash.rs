@@ -0,0 +1,56 @@
+//! [`Hash`] for the hash functions HMAC is used with
+//!
+//! SHA3-224 comes from the `sha3` crate of this workspace, SHA-224, SHA-256,
+//! SHA-384 and SHA-512 from the `sha2` crate. Both are re-exported so that
+//! `Hmac<Sha256>` needs no further dependency.
+
+pub use sha2::{Sha224, Sha256, Sha384, Sha512};
+pub use sha3::Sha3_224;
+
+use crate::Hash;
+
+impl Hash for Sha3_224 {
+    /// The rate, 1152 bits
+    const BLOCK_SIZE: usize = 144;
+    const OUTPUT_SIZE: usize = 28;
+
+    fn new() -> Self {
+        Sha3_224::new()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha3_224::update(self, data)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha3_224::finalize(self)
+    }
+}
+
+macro_rules! impl_hash_for_sha2 {
+    ($($hash:ty: $block_size:expr, $output_size:expr;)*) => {$(
+        impl Hash for $hash {
+            const BLOCK_SIZE: usize = $block_size;
+            const OUTPUT_SIZE: usize = $output_size;
+
+            fn new() -> Self {
+                sha2::Digest::new()
+            }
+
+            fn update(&mut self, data: &[u8]) {
+                sha2::Digest::update(self, data)
+            }
+
+            fn finalize(self) -> Vec<u8> {
+                sha2::Digest::finalize(self).to_vec()
+            }
+        }
+    )*};
+}
+
+impl_hash_for_sha2! {
+    Sha224: 64, 28;
+    Sha256: 64, 32;
+    Sha384: 128, 48;
+    Sha512: 128, 64;
+}
@@ -0,0 +1,333 @@
+//! `std::io` adapters around [`Encryptor`] and [`Decryptor`]
+//!
+//! [`EncryptingWriter`] encrypts everything written to it and passes the
+//! ciphertext on; [`DecryptingReader`] reads ciphertext and hands out
+//! plaintext. Both work in any of the four modes and never hold more than a
+//! read buffer and a block or two in memory:
+//!
+//! ```text
+//! let mut writer = EncryptingWriter::new(Encryptor::ctr(Aes128::new(&key)?, &counter)?, File::create(path)?);
+//! std::io::copy(&mut input, &mut writer)?;
+//! writer.finish()?;
+//! ```
+//!
+//! Errors of the mode (bad padding, truncated ciphertext) surface as
+//! [`io::ErrorKind::InvalidData`].
+//!
+//! [`AeadWriter`] and [`AeadReader`] do the same with the segments of
+//! [STREAM](crate::aead_stream). Unlike the modes, the reader only returns
+//! plaintext that was authenticated, and reports data that was cut off.
+
+use std::io::{self, Read, Write};
+
+use crate::aead_stream::{OVERHEAD, StreamDecryptor, StreamEncryptor};
+use crate::streaming::{Decryptor, Encryptor};
+use crate::{BlockCipher, CipherModeError};
+
+/// Bytes read from the inner reader at a time
+const READ_BUFFER_SIZE: usize = 8192;
+
+/// Encrypts on write and writes the ciphertext to `W`
+///
+/// The padding and any incomplete block are only written by
+/// [`EncryptingWriter::finish`]; dropping the writer without it loses them.
+pub struct EncryptingWriter<C: BlockCipher, W: Write> {
+    encryptor: Encryptor<C>,
+    inner: W,
+}
+
+impl<C: BlockCipher, W: Write> EncryptingWriter<C, W> {
+    pub fn new(encryptor: Encryptor<C>, inner: W) -> Self {
+        EncryptingWriter { encryptor, inner }
+    }
+
+    /// Writes the last block, flushes and returns the inner writer
+    pub fn finish(mut self) -> io::Result<W> {
+        let last = self.encryptor.finalize().map_err(invalid_data)?;
+        self.inner.write_all(&last)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<C: BlockCipher, W: Write> Write for EncryptingWriter<C, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write_all(&self.encryptor.update(buf))?;
+        Ok(buf.len())
+    }
+
+    /// Flushes the inner writer; an incomplete block stays held back
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reads ciphertext from `R` and returns the plaintext
+///
+/// The padding is checked when the inner reader reaches its end; a
+/// malformed one makes that last `read` fail. As with
+/// [`Decryptor`], plaintext read before that point is not authenticated.
+pub struct DecryptingReader<C: BlockCipher, R: Read> {
+    /// `None` once the inner reader is exhausted and the padding removed
+    decryptor: Option<Decryptor<C>>,
+    inner: R,
+    /// Plaintext not handed out yet, from `position` on
+    plaintext: Vec<u8>,
+    position: usize,
+}
+
+impl<C: BlockCipher, R: Read> DecryptingReader<C, R> {
+    pub fn new(decryptor: Decryptor<C>, inner: R) -> Self {
+        DecryptingReader { decryptor: Some(decryptor), inner, plaintext: Vec::new(), position: 0 }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<C: BlockCipher, R: Read> Read for DecryptingReader<C, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut chunk = [0; READ_BUFFER_SIZE];
+        // Update may hold everything back, so read until there is plaintext or the end
+        while self.position == self.plaintext.len() {
+            let Some(decryptor) = self.decryptor.as_mut() else {
+                return Ok(0);
+            };
+            let read = self.inner.read(&mut chunk)?;
+            self.plaintext = if read == 0 {
+                self.decryptor.take().expect("checked above").finalize().map_err(invalid_data)?
+            } else {
+                decryptor.update(&chunk[..read])
+            };
+            self.position = 0;
+        }
+        let available = &self.plaintext[self.position..];
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+/// Encrypts on write in segments of `segment_size` bytes with STREAM
+///
+/// A full segment is only written once more data follows, since the last
+/// one is encrypted differently. It is written by [`AeadWriter::finish`];
+/// without it the reader reports the data as truncated.
+pub struct AeadWriter<W: Write> {
+    encryptor: StreamEncryptor,
+    inner: W,
+    segment_size: usize,
+    /// Plaintext of the segment being filled
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> AeadWriter<W> {
+    /// Panics if `segment_size` is 0
+    pub fn new(encryptor: StreamEncryptor, segment_size: usize, inner: W) -> Self {
+        assert!(segment_size > 0, "segment size must not be 0");
+        AeadWriter { encryptor, inner, segment_size, buffer: Vec::new() }
+    }
+
+    /// Writes the last segment, flushes and returns the inner writer
+    pub fn finish(mut self) -> io::Result<W> {
+        let last = self.encryptor.encrypt_last(b"", &self.buffer).map_err(invalid_data)?;
+        self.inner.write_all(&last)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for AeadWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        let mut start = 0;
+        while self.buffer.len() - start > self.segment_size {
+            let segment = &self.buffer[start..start + self.segment_size];
+            self.inner.write_all(&self.encryptor.encrypt_next(b"", segment).map_err(invalid_data)?)?;
+            start += self.segment_size;
+        }
+        self.buffer.drain(..start);
+        Ok(buf.len())
+    }
+
+    /// Flushes the inner writer; the current segment stays held back
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reads STREAM segments from `R` and returns the plaintext
+///
+/// `segment_size` must be the one the [`AeadWriter`] used. Each segment is
+/// checked before any of it is returned; a changed, reordered or missing
+/// segment makes `read` fail.
+pub struct AeadReader<R: Read> {
+    /// `None` once the last segment is decrypted
+    decryptor: Option<StreamDecryptor>,
+    inner: R,
+    segment_size: usize,
+    /// Ciphertext read but not decrypted yet
+    ciphertext: Vec<u8>,
+    /// Plaintext not handed out yet, from `position` on
+    plaintext: Vec<u8>,
+    position: usize,
+}
+
+impl<R: Read> AeadReader<R> {
+    /// Panics if `segment_size` is 0
+    pub fn new(decryptor: StreamDecryptor, segment_size: usize, inner: R) -> Self {
+        assert!(segment_size > 0, "segment size must not be 0");
+        AeadReader { decryptor: Some(decryptor), inner, segment_size, ciphertext: Vec::new(), plaintext: Vec::new(), position: 0 }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for AeadReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let segment_length = self.segment_size + OVERHEAD;
+        let mut chunk = [0; READ_BUFFER_SIZE];
+        while self.position == self.plaintext.len() {
+            let Some(decryptor) = self.decryptor.as_mut() else {
+                return Ok(0);
+            };
+            // A segment is the last one if nothing follows it
+            let mut end = false;
+            while !end && self.ciphertext.len() <= segment_length {
+                let read = self.inner.read(&mut chunk)?;
+                self.ciphertext.extend_from_slice(&chunk[..read]);
+                end = read == 0;
+            }
+            self.plaintext = if self.ciphertext.len() > segment_length {
+                let plaintext = decryptor.decrypt_next(b"", &self.ciphertext[..segment_length]).map_err(invalid_data)?;
+                self.ciphertext.drain(..segment_length);
+                plaintext
+            } else {
+                let decryptor = self.decryptor.take().expect("checked above");
+                decryptor.decrypt_last(b"", &std::mem::take(&mut self.ciphertext)).map_err(invalid_data)?
+            };
+            self.position = 0;
+        }
+        let available = &self.plaintext[self.position..];
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+fn invalid_data(error: CipherModeError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CipherModes, DummyCipher, KeyInit, Padding};
+
+    const KEY: &[u8] = b"secret key 16 by";
+    const IV: &[u8] = b"initial vector!!";
+
+    /// Hands out at most three bytes per read
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let count = self.0.len().min(buf.len()).min(3);
+            buf[..count].copy_from_slice(&self.0[..count]);
+            self.0 = &self.0[count..];
+            Ok(count)
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let plaintext: Vec<u8> = (0..=255).cycle().take(20000).collect();
+
+        let mut writer = EncryptingWriter::new(Encryptor::cbc(&cipher, IV, Padding::Pkcs7).unwrap(), Vec::new());
+        for chunk in plaintext.chunks(1000) {
+            writer.write_all(chunk).unwrap();
+        }
+        let ciphertext = writer.finish().unwrap();
+        assert_eq!(ciphertext, CipherModes::cbc_encrypt(&cipher, IV, &plaintext, Padding::Pkcs7).unwrap());
+
+        let mut reader = DecryptingReader::new(Decryptor::cbc(&cipher, IV, Padding::Pkcs7).unwrap(), ciphertext.as_slice());
+        let mut decrypted = Vec::new();
+        reader.read_to_end(&mut decrypted).unwrap();
+        assert_eq!(decrypted, plaintext);
+
+        let ciphertext = CipherModes::ctr_encrypt(&cipher, IV, &plaintext[..100]).unwrap();
+        let mut reader = DecryptingReader::new(Decryptor::ctr(&cipher, IV).unwrap(), Trickle(&ciphertext));
+        let mut decrypted = Vec::new();
+        reader.read_to_end(&mut decrypted).unwrap();
+        assert_eq!(decrypted, &plaintext[..100]);
+    }
+
+    #[test]
+    fn test_errors_are_invalid_data() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let ciphertext = CipherModes::cbc_encrypt(&cipher, IV, b"attack at dawn", Padding::Pkcs7).unwrap();
+        let mut reader = DecryptingReader::new(Decryptor::cbc(&cipher, IV, Padding::Pkcs7).unwrap(), &ciphertext[..10]);
+        let error = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let mut writer = EncryptingWriter::new(Encryptor::ecb(&cipher, Padding::None).unwrap(), Vec::new());
+        writer.write_all(b"not a whole block").unwrap();
+        assert_eq!(writer.finish().unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    fn stream_encrypt(plaintext: &[u8], segment_size: usize) -> Vec<u8> {
+        let mut writer = AeadWriter::new(StreamEncryptor::new(&[0x42; 32], &[7; 7]).unwrap(), segment_size, Vec::new());
+        for chunk in plaintext.chunks(1000) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    fn stream_decrypt(ciphertext: &[u8], segment_size: usize) -> io::Result<Vec<u8>> {
+        let mut reader = AeadReader::new(StreamDecryptor::new(&[0x42; 32], &[7; 7]).unwrap(), segment_size, Trickle(ciphertext));
+        let mut plaintext = Vec::new();
+        reader.read_to_end(&mut plaintext)?;
+        Ok(plaintext)
+    }
+
+    #[test]
+    fn test_aead_roundtrip() {
+        let plaintext: Vec<u8> = (0..=255).cycle().take(20000).collect();
+        for (length, segment_size) in [(20000, 4096), (8192, 4096), (0, 4096), (20000, 7)] {
+            let ciphertext = stream_encrypt(&plaintext[..length], segment_size);
+            assert_eq!(ciphertext.len(), length + length.div_ceil(segment_size).max(1) * OVERHEAD);
+            assert_eq!(stream_decrypt(&ciphertext, segment_size).unwrap(), &plaintext[..length]);
+        }
+    }
+
+    #[test]
+    fn test_aead_detects_truncation() {
+        let plaintext: Vec<u8> = (0..=255).cycle().take(10000).collect();
+        let ciphertext = stream_encrypt(&plaintext, 4096);
+        let segment_length = 4096 + OVERHEAD;
+        for length in [0, 10, segment_length, 2 * segment_length, ciphertext.len() - 1] {
+            let error = stream_decrypt(&ciphertext[..length], 4096).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+
+        let mut reordered = ciphertext.clone();
+        reordered[..2 * segment_length].rotate_left(segment_length);
+        assert!(stream_decrypt(&reordered, 4096).is_err());
+        let mut extended = ciphertext.clone();
+        extended.extend_from_slice(&ciphertext[2 * segment_length..]);
+        assert!(stream_decrypt(&extended, 4096).is_err());
+
+        // The segments before the damaged one are returned
+        let mut reader = AeadReader::new(StreamDecryptor::new(&[0x42; 32], &[7; 7]).unwrap(), 4096, &ciphertext[..2 * segment_length]);
+        let mut buffer = vec![0; 4096];
+        reader.read_exact(&mut buffer).unwrap();
+        assert_eq!(buffer, &plaintext[..4096]);
+        assert!(reader.read(&mut buffer).is_err());
+    }
+}
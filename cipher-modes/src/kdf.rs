@@ -0,0 +1,66 @@
+//! PBKDF2, a key from a password (RFC 8018)
+//!
+//! Every block of output is HMAC iterated `rounds` times over the salt and
+//! the block number, with all intermediate results XORed together:
+//!
+//! ```text
+//! U_1 = HMAC(P, S ‖ be32(i))    U_j = HMAC(P, U_(j-1))
+//! T_i = U_1 ⊕ U_2 ⊕ … ⊕ U_rounds
+//! key = T_1 ‖ T_2 ‖ …           truncated to the requested length
+//! ```
+//!
+//! The rounds make every password guess as expensive as the derivation;
+//! the salt keeps one guess from testing many files at once. Unlike
+//! scrypt and Argon2 in `pwhash`, PBKDF2 needs almost no memory and is
+//! therefore cheap on GPUs, so it needs a high round count.
+
+use secret::Secret;
+
+use crate::Hash;
+use crate::mac::Hmac;
+
+/// PBKDF2 with HMAC over `H`, `length` bytes of key
+///
+/// A round count of 0 is treated as 1.
+pub fn pbkdf2<H: Hash + Clone>(password: &[u8], salt: &[u8], rounds: u32, length: usize) -> Secret<Vec<u8>> {
+    let keyed = Hmac::<H>::new(password);
+    let mut key = Secret::new(Vec::with_capacity(length.next_multiple_of(H::OUTPUT_SIZE)));
+    for block in 1..=length.div_ceil(H::OUTPUT_SIZE) as u32 {
+        let mut mac = keyed.clone();
+        mac.update(salt);
+        mac.update(&block.to_be_bytes());
+        let mut u = Secret::new(mac.finalize());
+        let mut t = u.clone();
+        for _ in 1..rounds {
+            let mut mac = keyed.clone();
+            mac.update(&u);
+            u = Secret::new(mac.finalize());
+            t.iter_mut().zip(u.iter()).for_each(|(t, u)| *t ^= u);
+        }
+        key.extend_from_slice(&t);
+    }
+    key.truncate(length);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::Sha256;
+
+    #[test]
+    fn test_pbkdf2_hmac_sha256() {
+        // The inputs of RFC 6070 with SHA-256 instead of SHA-1
+        for (rounds, expected) in [
+            (1, "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"),
+            (2, "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43"),
+            (4096, "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a"),
+        ] {
+            assert_eq!(*pbkdf2::<Sha256>(b"password", b"salt", rounds, 32), codec::hex::decode(expected).unwrap());
+        }
+        // Longer than one block
+        let key = pbkdf2::<Sha256>(b"passwordPASSWORDpassword", b"saltSALTsaltSALTsaltSALTsaltSALTsalt", 4096, 40);
+        assert_eq!(*key, codec::hex::decode("348c89dbcbd32b2f32d814b8116e84cf2b17347ebc1800181c4e2a1fb8dd53e1c635518c7dac47e9").unwrap());
+        assert_eq!(pbkdf2::<Sha256>(b"password", b"salt", 0, 32), pbkdf2::<Sha256>(b"password", b"salt", 1, 32));
+    }
+}
@@ -0,0 +1,217 @@
+//! AES key wrap (RFC 3394) and key wrap with padding (RFC 5649)
+//!
+//! Encrypts key material under a key-encryption key so that it can be
+//! stored or sent. The key is split into 64-bit halves R_1 … R_n, which go
+//! through six rounds together with an integrity register A:
+//!
+//! ```text
+//! B = E(A ‖ R_i)    A = MSB64(B) ⊕ t    R_i = LSB64(B)    t = n·j + i
+//! ```
+//!
+//! The output is A ‖ R_1 ‖ … ‖ R_n, eight bytes longer than the key.
+//! Unwrapping runs the rounds backwards; A has to come out as the initial
+//! value again, so it works as an eight-byte tag and no nonce is needed.
+//!
+//! [`key_wrap`] takes keys of at least 16 bytes in whole halves;
+//! [`key_wrap_padded`] pads any length and stores the original length in A.
+//! Both need a cipher with 16-byte blocks.
+
+use crate::utils::xor_blocks_in_place;
+use crate::{BlockCipher, CipherModeError, block_size_of};
+
+/// Length of A and of the halves R_i
+const HALF: usize = 8;
+
+/// Initial value of A in RFC 3394
+const IV: [u8; HALF] = [0xa6; HALF];
+
+/// First four bytes of A in RFC 5649, followed by the key length
+const PADDED_IV_PREFIX: [u8; 4] = [0xa6, 0x59, 0x59, 0xa6];
+
+/// Wraps `key`, which must be a multiple of 8 and at least 16 bytes long
+pub fn key_wrap<C: BlockCipher>(cipher: &C, key: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    check_block_size::<C>()?;
+    check_length(key, 2 * HALF)?;
+    Ok(wrap(cipher, IV, key))
+}
+
+/// Unwraps a key from [`key_wrap`]; [`CipherModeError::AuthenticationFailed`]
+/// if the wrapped key was modified or the key-encryption key is wrong
+pub fn key_unwrap<C: BlockCipher>(cipher: &C, wrapped: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    check_block_size::<C>()?;
+    check_length(wrapped, 3 * HALF)?;
+    let (a, key) = unwrap(cipher, wrapped);
+    if !secret::ct::eq(&a, &IV) {
+        return Err(CipherModeError::AuthenticationFailed);
+    }
+    Ok(key)
+}
+
+/// Wraps a key of any length from 1 byte to 4 GiB
+///
+/// The key is padded with zeros to whole halves. A key of at most 8 bytes
+/// is encrypted as a single block together with A instead of going through
+/// the rounds.
+pub fn key_wrap_padded<C: BlockCipher>(cipher: &C, key: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    check_block_size::<C>()?;
+    let length = u32::try_from(key.len())
+        .ok()
+        .filter(|&length| length > 0)
+        .ok_or(CipherModeError::InvalidInputLength { block_size: HALF, actual: key.len() })?;
+
+    let mut a = [0; HALF];
+    a[..4].copy_from_slice(&PADDED_IV_PREFIX);
+    a[4..].copy_from_slice(&length.to_be_bytes());
+    let mut padded = key.to_vec();
+    padded.resize(key.len().next_multiple_of(HALF), 0);
+
+    if padded.len() == HALF {
+        let mut block = [&a[..], &padded].concat();
+        cipher.encrypt_block(&mut block);
+        return Ok(block);
+    }
+    Ok(wrap(cipher, a, &padded))
+}
+
+/// Unwraps a key from [`key_wrap_padded`] and removes the padding;
+/// [`CipherModeError::AuthenticationFailed`] if the wrapped key was modified
+/// or the key-encryption key is wrong
+pub fn key_unwrap_padded<C: BlockCipher>(cipher: &C, wrapped: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    check_block_size::<C>()?;
+    check_length(wrapped, 2 * HALF)?;
+    let (a, mut padded) = if wrapped.len() == 2 * HALF {
+        let mut block = wrapped.to_vec();
+        cipher.decrypt_block(&mut block);
+        let padded = block.split_off(HALF);
+        (block.try_into().expect("A is one half long"), padded)
+    } else {
+        unwrap(cipher, wrapped)
+    };
+
+    // The key length must leave between 0 and 7 zero bytes of padding
+    let length = u32::from_be_bytes(a[4..].try_into().expect("A is one half long")) as usize;
+    let valid = secret::ct::eq(&a[..4], &PADDED_IV_PREFIX)
+        && length <= padded.len()
+        && length + HALF > padded.len()
+        && padded[length..].iter().all(|&byte| byte == 0);
+    if !valid {
+        return Err(CipherModeError::AuthenticationFailed);
+    }
+    padded.truncate(length);
+    Ok(padded)
+}
+
+/// The six rounds over `data` with `a` as initial value; returns A ‖ R_1 ‖ … ‖ R_n
+fn wrap<C: BlockCipher>(cipher: &C, a: [u8; HALF], data: &[u8]) -> Vec<u8> {
+    let n = data.len() / HALF;
+    let mut output = [&a[..], data].concat();
+    let mut block = [0; 2 * HALF];
+    for j in 0..6 {
+        for i in 1..=n {
+            block[..HALF].copy_from_slice(&output[..HALF]);
+            block[HALF..].copy_from_slice(&output[i * HALF..][..HALF]);
+            cipher.encrypt_block(&mut block);
+            xor_blocks_in_place(&mut block[..HALF], &((n * j + i) as u64).to_be_bytes());
+            output[..HALF].copy_from_slice(&block[..HALF]);
+            output[i * HALF..][..HALF].copy_from_slice(&block[HALF..]);
+        }
+    }
+    output
+}
+
+/// The rounds of [`wrap`] backwards; returns A and R_1 ‖ … ‖ R_n, A unchecked
+fn unwrap<C: BlockCipher>(cipher: &C, wrapped: &[u8]) -> ([u8; HALF], Vec<u8>) {
+    let n = wrapped.len() / HALF - 1;
+    let mut data = wrapped.to_vec();
+    let mut block = [0; 2 * HALF];
+    for j in (0..6).rev() {
+        for i in (1..=n).rev() {
+            block[..HALF].copy_from_slice(&data[..HALF]);
+            xor_blocks_in_place(&mut block[..HALF], &((n * j + i) as u64).to_be_bytes());
+            block[HALF..].copy_from_slice(&data[i * HALF..][..HALF]);
+            cipher.decrypt_block(&mut block);
+            data[..HALF].copy_from_slice(&block[..HALF]);
+            data[i * HALF..][..HALF].copy_from_slice(&block[HALF..]);
+        }
+    }
+    let key = data.split_off(HALF);
+    (data.try_into().expect("A is one half long"), key)
+}
+
+fn check_block_size<C: BlockCipher>() -> Result<(), CipherModeError> {
+    if block_size_of::<C>() != 2 * HALF {
+        return Err(CipherModeError::InvalidBlockSize);
+    }
+    Ok(())
+}
+
+/// [`CipherModeError::InvalidInputLength`] unless `data` is whole halves and at least `minimum` bytes long
+fn check_length(data: &[u8], minimum: usize) -> Result<(), CipherModeError> {
+    if data.len() < minimum || !data.len().is_multiple_of(HALF) {
+        return Err(CipherModeError::InvalidInputLength { block_size: HALF, actual: data.len() });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DummyCipher, KeyInit};
+
+    const KEK: &[u8] = b"key encryption k";
+
+    #[test]
+    fn test_roundtrip() {
+        let cipher = DummyCipher::<16>::new(KEK).unwrap();
+        for length in [16, 24, 32, 64] {
+            let key: Vec<u8> = (0..length as u8).collect();
+            let wrapped = key_wrap(&cipher, &key).unwrap();
+            assert_eq!(wrapped.len(), length + 8);
+            assert_eq!(key_unwrap(&cipher, &wrapped).unwrap(), key);
+        }
+        for length in [1usize, 7, 8, 9, 16, 33] {
+            let key: Vec<u8> = (0..length as u8).collect();
+            let wrapped = key_wrap_padded(&cipher, &key).unwrap();
+            assert_eq!(wrapped.len(), length.next_multiple_of(8) + 8);
+            assert_eq!(key_unwrap_padded(&cipher, &wrapped).unwrap(), key);
+        }
+    }
+
+    #[test]
+    fn test_rejects_tampering() {
+        let cipher = DummyCipher::<16>::new(KEK).unwrap();
+        let key = [0x42; 24];
+        let unwrap = |cipher: &DummyCipher, wrapped: &[u8], padded: bool| match padded {
+            false => key_unwrap(cipher, wrapped),
+            true => key_unwrap_padded(cipher, wrapped),
+        };
+        for (wrapped, padded) in [
+            (key_wrap(&cipher, &key).unwrap(), false),
+            (key_wrap_padded(&cipher, &key[..5]).unwrap(), true),
+            (key_wrap_padded(&cipher, &key[..21]).unwrap(), true),
+        ] {
+            // The XOR cipher is linear, so only changes to A itself show; AES also catches the rest
+            // and a wrong key-encryption key
+            let mut tampered = wrapped.clone();
+            tampered[0] ^= 1;
+            assert_eq!(unwrap(&cipher, &tampered, padded), Err(CipherModeError::AuthenticationFailed));
+        }
+
+        // A key wrapped without padding is not accepted as padded and vice versa
+        let wrapped = key_wrap(&cipher, &key).unwrap();
+        assert_eq!(key_unwrap_padded(&cipher, &wrapped), Err(CipherModeError::AuthenticationFailed));
+        let wrapped = key_wrap_padded(&cipher, &key).unwrap();
+        assert_eq!(key_unwrap(&cipher, &wrapped), Err(CipherModeError::AuthenticationFailed));
+    }
+
+    #[test]
+    fn test_rejects_invalid_lengths() {
+        let cipher = DummyCipher::<16>::new(KEK).unwrap();
+        assert_eq!(key_wrap(&cipher, &[0; 8]), Err(CipherModeError::InvalidInputLength { block_size: 8, actual: 8 }));
+        assert_eq!(key_wrap(&cipher, &[0; 20]), Err(CipherModeError::InvalidInputLength { block_size: 8, actual: 20 }));
+        assert_eq!(key_unwrap(&cipher, &[0; 16]), Err(CipherModeError::InvalidInputLength { block_size: 8, actual: 16 }));
+        assert_eq!(key_wrap_padded(&cipher, &[]), Err(CipherModeError::InvalidInputLength { block_size: 8, actual: 0 }));
+        assert_eq!(key_unwrap_padded(&cipher, &[0; 15]), Err(CipherModeError::InvalidInputLength { block_size: 8, actual: 15 }));
+        assert_eq!(key_wrap(&DummyCipher::<8>::new(KEK).unwrap(), &[0; 16]), Err(CipherModeError::InvalidBlockSize));
+    }
+}
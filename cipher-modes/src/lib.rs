@@ -0,0 +1,320 @@
+//! Block cipher modes of operation
+//!
+//! The modes are written against the small [`BlockCipher`] trait so they can
+//! be demonstrated with any block cipher, from the toy [`DummyCipher`] to
+//! the 16-bit SPN of the linear cryptanalysis exercises. AES and Blowfish
+//! come with the crate, see [`cipher`]. A cipher is created
+//! once per key with [`KeyInit::new`] and then passed to the modes. All
+//! functions work on byte slices; [`Encryptor`] and [`Decryptor`] take the
+//! data in pieces instead, and [`EncryptingWriter`] and [`DecryptingReader`]
+//! plug them into `std::io`. Stream ciphers such as ChaCha20 implement
+//! [`StreamCipher`] instead of [`BlockCipher`]. With the `rustcrypto`
+//! feature, the `rustcrypto` module adapts the block ciphers of RustCrypto's
+//! `cipher` crate, such as `aes::Aes128`, and vice versa. With the
+//! `test-vectors` feature, the `cavp` module checks a cipher against the
+//! `.rsp` test vector files of NIST's CAVP.
+//!
+//! [`CipherModes::seal`] draws the IV from a random number generator and
+//! puts it in front of the ciphertext, [`CipherModes::open`] takes it from
+//! there. [`SealedMessage`] keeps IV or nonce, ciphertext and tag apart,
+//! and with the `serde` feature it and the mode parameters can be stored as
+//! JSON or CBOR. [`EncryptedFile`] is a binary file format that also
+//! records the algorithm and derives the key from a password with
+//! [`pbkdf2`]. [`NonceManager`] hands out counter blocks and nonces and refuses
+//! those that were used before.
+//!
+//! [`CipherModes`] only encrypts; [`eax_encrypt`] also authenticates, and
+//! [`eax_decrypt`] refuses ciphertexts that were modified. [`siv_encrypt`]
+//! does the same without relying on a unique nonce, and
+//! [`chacha20_poly1305_encrypt`] and [`ascon128_encrypt`] without a block
+//! cipher at all. [`StreamEncryptor`] encrypts long data in
+//! ChaCha20-Poly1305 segments that are checked one by one, and
+//! [`AeadWriter`] and [`AeadReader`] do so through `std::io`. [`Cmac`]
+//! authenticates without encrypting; [`CbcMac`] is its insecure predecessor
+//! and the MAC inside CCM; [`Poly1305`] is a one-time MAC that needs a fresh key
+//! per message. [`Hmac`] authenticates with a [`Hash`] instead of a
+//! cipher. [`key_wrap`] encrypts keys for storage and
+//! transport with an integrity check and without a nonce. [`galois`]
+//! multiplies in the fields of MixColumns, GHASH and the XTS tweak.
+//!
+//! Keys, round keys, chaining values and keystream are kept in
+//! [`secret::Secret`] and overwritten when they are dropped. With the
+//! `zeroize` feature the ciphers, [`Encryptor`], [`Decryptor`] and
+//! [`CtrStream`] also implement `Zeroize`, to wipe them before they go out
+//! of scope, and `ZeroizeOnDrop`, so that types containing them can derive
+//! it.
+
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use secret::Secret;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub mod aead_stream;
+pub mod ascon;
+#[cfg(feature = "test-vectors")]
+pub mod cavp;
+pub mod chacha20poly1305;
+pub mod cipher;
+pub mod compat;
+pub mod ctr;
+pub mod eax;
+pub mod file;
+pub mod galois;
+pub mod hash;
+#[cfg(feature = "serde")]
+mod hex;
+pub mod io;
+pub mod kdf;
+pub mod keywrap;
+pub mod mac;
+pub mod modes;
+pub mod nonce;
+pub mod padding;
+pub mod poly1305;
+#[cfg(feature = "rustcrypto")]
+pub mod rustcrypto;
+pub mod sealed;
+pub mod siv;
+pub mod stream;
+pub mod streaming;
+pub mod utils;
+
+pub use aead_stream::{StreamDecryptor, StreamEncryptor};
+pub use ascon::{ascon128_decrypt, ascon128_encrypt};
+pub use chacha20poly1305::{chacha20_poly1305_decrypt, chacha20_poly1305_encrypt};
+pub use cipher::{Aes128, Aes192, Aes256, Blowfish, ChaCha20};
+pub use compat::{LegacyBlockCipher, WithKey};
+pub use ctr::{CounterPosition, CtrLayout, Endianness};
+pub use eax::{eax_decrypt, eax_encrypt};
+pub use file::{EncryptedFile, FileAlgorithm, Kdf};
+pub use io::{AeadReader, AeadWriter, DecryptingReader, EncryptingWriter};
+pub use kdf::pbkdf2;
+pub use keywrap::{key_unwrap, key_unwrap_padded, key_wrap, key_wrap_padded};
+pub use mac::{CbcMac, Cmac, Hmac};
+pub use modes::{CipherModes, Mode, SealMode};
+pub use nonce::NonceManager;
+pub use padding::Padding;
+pub use poly1305::Poly1305;
+pub use sealed::{Scheme, SealedMessage};
+pub use siv::{siv_decrypt, siv_encrypt};
+pub use stream::CtrStream;
+pub use streaming::{Decryptor, Encryptor};
+
+/// A block cipher instance with its key already set up
+///
+/// The key schedule runs once when the instance is created, see
+/// [`KeyInit`]; the mode functions then only pass blocks. Ciphers written
+/// against the old interface with a key per call keep working through
+/// [`compat::WithKey`].
+///
+/// The block size is part of the type, so the modes take it from there
+/// instead of a parameter that could disagree with the cipher. A block
+/// size of zero is rejected when the mode functions are compiled.
+pub trait BlockCipher {
+    /// Block size in bytes
+    const BLOCK_SIZE: usize;
+
+    /// Encrypts exactly one block of `BLOCK_SIZE` bytes in place
+    fn encrypt_block(&self, block: &mut [u8]);
+
+    /// Decrypts exactly one block of `BLOCK_SIZE` bytes in place
+    fn decrypt_block(&self, block: &mut [u8]);
+
+    /// The key lengths in bytes the cipher is set up with, any by default
+    ///
+    /// Lets a caller check a key, or tell its user which lengths there are,
+    /// before anything is set up.
+    fn valid_key_lengths() -> RangeInclusive<usize> {
+        0..=usize::MAX
+    }
+
+    /// [`CipherModeError::InvalidKeyLength`] unless the length of `key` is
+    /// one of [`BlockCipher::valid_key_lengths`], with the nearest valid one
+    /// as `expected`
+    fn check_key_length(key: &[u8]) -> Result<(), CipherModeError> {
+        check_key_in(key, Self::valid_key_lengths())
+    }
+}
+
+/// A keystream generator that data is XORed with
+///
+/// Encryption and decryption are the same operation. The position counts
+/// keystream bytes from the start; seeking lets a reader decrypt from the
+/// middle of a message without producing the keystream before it.
+/// [`cipher::ChaCha20`] implements it, and [`stream::CtrStream`] puts CTR
+/// over any block cipher behind the same interface.
+pub trait StreamCipher {
+    /// XORs the keystream from the current position into `data` and moves
+    /// the position past it
+    fn apply_keystream(&mut self, data: &mut [u8]);
+
+    /// Moves to byte `position` of the keystream
+    fn seek(&mut self, position: u64);
+
+    /// Current position in bytes
+    fn position(&self) -> u64;
+}
+
+/// Creates a cipher instance from a key
+///
+/// Separate from [`BlockCipher`] so that references and other wrappers
+/// that cannot be built from a key still implement it.
+pub trait KeyInit: Sized {
+    /// Runs the key schedule; [`CipherModeError::InvalidKeyLength`] for a key
+    /// the cipher does not support, see [`BlockCipher::valid_key_lengths`]
+    fn new(key: &[u8]) -> Result<Self, CipherModeError>;
+}
+
+/// A hash function fed in pieces, the building block of [`mac::Hmac`]
+///
+/// [`hash`] implements it for SHA3-224 from the `sha3` crate of this
+/// workspace and for SHA-2. HMAC needs the block size besides the output
+/// size: keys are padded to one block.
+pub trait Hash {
+    /// Bytes per compression function call, the rate for SHA-3
+    const BLOCK_SIZE: usize;
+
+    /// Length of the digest in bytes
+    const OUTPUT_SIZE: usize;
+
+    fn new() -> Self;
+
+    fn update(&mut self, data: &[u8]);
+
+    /// The digest, `OUTPUT_SIZE` bytes
+    fn finalize(self) -> Vec<u8>;
+}
+
+/// Lets [`Cmac`] and other types that own their cipher borrow one instead
+impl<C: BlockCipher + ?Sized> BlockCipher for &C {
+    const BLOCK_SIZE: usize = C::BLOCK_SIZE;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        (**self).encrypt_block(block)
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        (**self).decrypt_block(block)
+    }
+
+    fn valid_key_lengths() -> RangeInclusive<usize> {
+        C::valid_key_lengths()
+    }
+}
+
+/// `C::BLOCK_SIZE`, with a compile-time check that it is not zero
+pub(crate) const fn block_size_of<C: BlockCipher + ?Sized>() -> usize {
+    const { assert!(C::BLOCK_SIZE > 0, "the block size of a cipher must not be zero") };
+    C::BLOCK_SIZE
+}
+
+/// [`CipherModeError::InvalidKeyLength`] with the nearer end of `lengths`
+/// unless the key is as long as one of them
+pub(crate) fn check_key_in(key: &[u8], lengths: RangeInclusive<usize>) -> Result<(), CipherModeError> {
+    if !lengths.contains(&key.len()) {
+        let expected = if key.len() < *lengths.start() { *lengths.start() } else { *lengths.end() };
+        return Err(CipherModeError::InvalidKeyLength { expected, got: key.len() });
+    }
+    Ok(())
+}
+
+/// Insecure demonstration cipher: XORs every block of `B` bytes with the repeated key
+///
+/// Only useful to show how data flows through the modes.
+#[derive(Debug, Clone)]
+pub struct DummyCipher<const B: usize = 16> {
+    key: Secret<Vec<u8>>,
+}
+
+impl<const B: usize> KeyInit for DummyCipher<B> {
+    /// [`CipherModeError::InvalidKeyLength`] for an empty key
+    fn new(key: &[u8]) -> Result<Self, CipherModeError> {
+        Self::check_key_length(key)?;
+        Ok(DummyCipher { key: Secret::new(key.to_vec()) })
+    }
+}
+
+impl<const B: usize> BlockCipher for DummyCipher<B> {
+    const BLOCK_SIZE: usize = B;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        block.iter_mut().zip(self.key.iter().cycle()).for_each(|(b, k)| *b ^= k);
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        self.encrypt_block(block)
+    }
+
+    fn valid_key_lengths() -> RangeInclusive<usize> {
+        1..=usize::MAX
+    }
+}
+
+/// Overwrites the key with zeros; blocks then pass unchanged
+#[cfg(feature = "zeroize")]
+impl<const B: usize> Zeroize for DummyCipher<B> {
+    fn zeroize(&mut self) {
+        self.key.as_mut_slice().zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const B: usize> ZeroizeOnDrop for DummyCipher<B> {}
+
+/// Errors reported by the mode functions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CipherModeError {
+    /// The block size is not supported by the mode, MAC or padding
+    InvalidBlockSize,
+    /// The key does not have a length the cipher supports
+    InvalidKeyLength { expected: usize, got: usize },
+    /// The IV, counter or nonce does not have the required length
+    InvalidIvLength { expected: usize, actual: usize },
+    /// The ciphertext is not a whole number of blocks
+    InvalidInputLength { block_size: usize, actual: usize },
+    /// The decrypted data does not end in valid PKCS#7 padding
+    InvalidPadding,
+    /// The PBKDF2 round count is 0 or larger than the format allows
+    InvalidRoundCount { rounds: u32, max: u32 },
+    /// The ciphertext could not be decrypted, for a reason that is not told
+    /// apart; see [`CipherModes::open`]
+    DecryptionError,
+    /// The tag does not match: ciphertext, nonce or associated data were modified
+    AuthenticationFailed,
+    /// The message is not as long as announced for a length-prefixed MAC
+    LengthMismatch { announced: usize, actual: usize },
+    /// The nonce or counter was used before under the same key, see [`NonceManager`]
+    NonceReused,
+}
+
+impl fmt::Display for CipherModeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CipherModeError::InvalidBlockSize => write!(f, "Block size is not supported"),
+            CipherModeError::InvalidKeyLength { expected, got } => {
+                write!(f, "Key must be {} bytes long, got {}", expected, got)
+            }
+            CipherModeError::InvalidIvLength { expected, actual } => {
+                write!(f, "IV must be {} bytes long, got {}", expected, actual)
+            }
+            CipherModeError::InvalidInputLength { block_size, actual } => {
+                write!(f, "Input length {} is not a multiple of the block size {}", actual, block_size)
+            }
+            CipherModeError::InvalidPadding => write!(f, "Invalid padding"),
+            CipherModeError::InvalidRoundCount { rounds, max } => {
+                write!(f, "Round count must be between 1 and {}, got {}", max, rounds)
+            }
+            CipherModeError::DecryptionError => write!(f, "Decryption failed"),
+            CipherModeError::AuthenticationFailed => write!(f, "Authentication failed"),
+            CipherModeError::LengthMismatch { announced, actual } => {
+                write!(f, "Message is {} bytes long, {} were announced", actual, announced)
+            }
+            CipherModeError::NonceReused => write!(f, "Nonce was already used"),
+        }
+    }
+}
+
+impl Error for CipherModeError {}
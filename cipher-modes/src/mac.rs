@@ -0,0 +1,461 @@
+//! Message authentication codes from a block cipher or a hash function
+//!
+//! [`Cmac`] is CMAC (RFC 4493, NIST SP 800-38B), the CBC-MAC that stays
+//! secure for messages of any length: the last block is masked with one of
+//! two subkeys derived from E(0), so an attacker cannot extend a tagged
+//! message by further blocks. With AES it is AES-CMAC.
+//!
+//! [`CbcMac`] is plain CBC-MAC, the last block of CBC encryption under a
+//! zero IV. Raw, it is only secure when all messages have the same length:
+//! from the tag t of a one-block message M anyone can compute the tag of
+//! M ‖ (M ⊕ t), which is t again. Prefixing the length, as CCM does, closes
+//! that hole because the attacker's longer message starts with a different
+//! block.
+//!
+//! [`Hmac`] (RFC 2104) is built from a hash function instead, any that
+//! implements [`Hash`]; with SHA-256 it is HMAC-SHA-256.
+
+use secret::Secret;
+
+use crate::utils::{xor_blocks, xor_blocks_in_place};
+use crate::{BlockCipher, CipherModeError, Hash, block_size_of};
+
+/// CMAC over a message fed in pieces
+///
+/// ```text
+/// K1 = 2·E(0), K2 = 4·E(0)                      (multiplication in GF(2^n))
+/// tag = CBC-MAC(M_1, …, M_(k-1), M_k ⊕ K1)      for a complete last block
+/// tag = CBC-MAC(M_1, …, M_(k-1), M_k‖10…0 ⊕ K2) otherwise
+/// ```
+///
+/// Only block sizes of 8 and 16 bytes have a doubling constant.
+pub struct Cmac<C: BlockCipher> {
+    cipher: C,
+    k1: Secret<Vec<u8>>,
+    k2: Secret<Vec<u8>>,
+    /// CBC-MAC over all blocks before `pending`
+    state: Vec<u8>,
+    /// Up to one block that is not encrypted yet; it may be the last one
+    pending: Vec<u8>,
+}
+
+impl<C: BlockCipher> Cmac<C> {
+    /// Derives the subkeys; [`CipherModeError::InvalidBlockSize`] for block
+    /// sizes other than 8 and 16
+    pub fn new(cipher: C) -> Result<Self, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        let mut l = Secret::new(vec![0; block_size]);
+        cipher.encrypt_block(&mut l);
+        let k1 = Secret::new(double(&l)?);
+        let k2 = Secret::new(double(&k1)?);
+        Ok(Cmac { cipher, k1, k2, state: vec![0; block_size], pending: Vec::with_capacity(block_size) })
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        let block_size = block_size_of::<C>();
+        while !data.is_empty() {
+            // A full pending block is only encrypted once more data follows
+            if self.pending.len() == block_size {
+                xor_blocks_in_place(&mut self.state, &self.pending);
+                self.cipher.encrypt_block(&mut self.state);
+                self.pending.clear();
+            }
+            let take = data.len().min(block_size - self.pending.len());
+            self.pending.extend_from_slice(&data[..take]);
+            data = &data[take..];
+        }
+    }
+
+    /// The tag, one block long
+    pub fn finalize(mut self) -> Vec<u8> {
+        let block_size = block_size_of::<C>();
+        let last = if self.pending.len() == block_size {
+            xor_blocks(&self.pending, &self.k1)
+        } else {
+            self.pending.push(0x80);
+            self.pending.resize(block_size, 0);
+            xor_blocks(&self.pending, &self.k2)
+        };
+        xor_blocks_in_place(&mut self.state, &last);
+        self.cipher.encrypt_block(&mut self.state);
+        self.state
+    }
+
+    /// Compares the tag with `tag` in constant time
+    ///
+    /// A truncated tag (RFC 4493 allows keeping the leading bytes) is
+    /// compared with as many leading bytes; an empty one never matches.
+    pub fn verify(self, tag: &[u8]) -> Result<(), CipherModeError> {
+        let expected = self.finalize();
+        if tag.is_empty() || tag.len() > expected.len() || !secret::ct::eq(&expected[..tag.len()], tag) {
+            return Err(CipherModeError::AuthenticationFailed);
+        }
+        Ok(())
+    }
+}
+
+/// CMAC of a whole message
+pub fn cmac<C: BlockCipher>(cipher: C, message: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    let mut mac = Cmac::new(cipher)?;
+    mac.update(message);
+    Ok(mac.finalize())
+}
+
+/// CBC-MAC over a message fed in pieces, raw or with the length in front
+///
+/// ```text
+/// raw:             tag = E(… E(E(M_1) ⊕ M_2) … ⊕ M_k)
+/// length-prefixed: tag = raw CBC-MAC of [len]_n ‖ M‖0…0
+/// ```
+///
+/// The raw variant takes whole blocks only and leaves any formatting to the
+/// caller, as CCM needs it. The length-prefixed one announces the message
+/// length in bytes up front, as a big-endian block, and pads the last block
+/// with zeros.
+pub struct CbcMac<C: BlockCipher> {
+    cipher: C,
+    /// CBC-MAC over all complete blocks so far
+    state: Vec<u8>,
+    /// The start of a block that is not complete yet
+    pending: Vec<u8>,
+    /// Length announced by [`CbcMac::with_length`]
+    announced: Option<usize>,
+    received: usize,
+}
+
+impl<C: BlockCipher> CbcMac<C> {
+    /// Raw CBC-MAC; secure only if every message has the same length
+    pub fn new(cipher: C) -> Self {
+        let block_size = block_size_of::<C>();
+        CbcMac { cipher, state: vec![0; block_size], pending: Vec::with_capacity(block_size), announced: None, received: 0 }
+    }
+
+    /// CBC-MAC of a message of exactly `length` bytes, behind a length block
+    ///
+    /// The length must fit into 8 bytes, so the block size must be at least 8.
+    pub fn with_length(cipher: C, length: usize) -> Result<Self, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        if block_size < 8 {
+            return Err(CipherModeError::InvalidBlockSize);
+        }
+        let mut mac = Self::new(cipher);
+        let mut prefix = vec![0; block_size];
+        prefix[block_size - 8..].copy_from_slice(&(length as u64).to_be_bytes());
+        mac.update(&prefix);
+        mac.received = 0;
+        mac.announced = Some(length);
+        Ok(mac)
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        let block_size = block_size_of::<C>();
+        self.received += data.len();
+        while !data.is_empty() {
+            let take = data.len().min(block_size - self.pending.len());
+            self.pending.extend_from_slice(&data[..take]);
+            data = &data[take..];
+            if self.pending.len() == block_size {
+                xor_blocks_in_place(&mut self.state, &self.pending);
+                self.cipher.encrypt_block(&mut self.state);
+                self.pending.clear();
+            }
+        }
+    }
+
+    /// The tag, one block long
+    ///
+    /// Raw: [`CipherModeError::InvalidInputLength`] unless the message was a
+    /// whole number of blocks; an empty message has the tag 0…0.
+    /// Length-prefixed: [`CipherModeError::LengthMismatch`] unless exactly
+    /// the announced number of bytes arrived.
+    pub fn finalize(mut self) -> Result<Vec<u8>, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        match self.announced {
+            None if !self.pending.is_empty() => {
+                return Err(CipherModeError::InvalidInputLength { block_size, actual: self.received });
+            }
+            Some(announced) if announced != self.received => {
+                return Err(CipherModeError::LengthMismatch { announced, actual: self.received });
+            }
+            Some(_) if !self.pending.is_empty() => self.update(&vec![0; block_size - self.pending.len()]),
+            _ => {}
+        }
+        Ok(self.state)
+    }
+
+    /// Compares the tag with `tag` in constant time
+    ///
+    /// Unlike [`Cmac::verify`] only the full tag is accepted.
+    pub fn verify(self, tag: &[u8]) -> Result<(), CipherModeError> {
+        if !secret::ct::eq(&self.finalize()?, tag) {
+            return Err(CipherModeError::AuthenticationFailed);
+        }
+        Ok(())
+    }
+}
+
+/// Raw CBC-MAC of a whole message
+pub fn cbc_mac<C: BlockCipher>(cipher: C, message: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    let mut mac = CbcMac::new(cipher);
+    mac.update(message);
+    mac.finalize()
+}
+
+/// Length-prefixed CBC-MAC of a whole message
+pub fn cbc_mac_with_length<C: BlockCipher>(cipher: C, message: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    let mut mac = CbcMac::with_length(cipher, message.len())?;
+    mac.update(message);
+    mac.finalize()
+}
+
+/// HMAC over a message fed in pieces
+///
+/// ```text
+/// K' = H(K) if K is longer than a block, else K; padded with zeros to one block
+/// tag = H(K' ⊕ opad ‖ H(K' ⊕ ipad ‖ M))     ipad = 0x36…36, opad = 0x5c…5c
+/// ```
+///
+/// The inner hash shields the key from length extension: the outer hash
+/// only ever sees a fixed-length input. Keys of any length are accepted,
+/// though one shorter than the output gives less security than the hash.
+#[derive(Clone)]
+pub struct Hmac<H: Hash> {
+    /// The inner hash, with K' ⊕ ipad already absorbed
+    inner: H,
+    /// K' ⊕ opad
+    outer_key: Secret<Vec<u8>>,
+}
+
+impl<H: Hash> Hmac<H> {
+    pub fn new(key: &[u8]) -> Self {
+        let mut padded_key = Secret::new(if key.len() > H::BLOCK_SIZE { hash::<H>(key) } else { key.to_vec() });
+        padded_key.resize(H::BLOCK_SIZE, 0);
+
+        let inner_key: Secret<Vec<u8>> = Secret::new(padded_key.iter().map(|byte| byte ^ 0x36).collect());
+        let mut inner = H::new();
+        inner.update(&inner_key);
+        let outer_key = Secret::new(padded_key.iter().map(|byte| byte ^ 0x5c).collect());
+        Hmac { inner, outer_key }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// The tag, as long as the hash output
+    pub fn finalize(self) -> Vec<u8> {
+        let mut outer = H::new();
+        outer.update(&self.outer_key);
+        outer.update(&self.inner.finalize());
+        outer.finalize()
+    }
+
+    /// Compares the tag with `tag` in constant time
+    ///
+    /// As with [`Cmac::verify`], a truncated tag (RFC 2104, section 5) is
+    /// compared with as many leading bytes; an empty one never matches.
+    pub fn verify(self, tag: &[u8]) -> Result<(), CipherModeError> {
+        let expected = self.finalize();
+        if tag.is_empty() || tag.len() > expected.len() || !secret::ct::eq(&expected[..tag.len()], tag) {
+            return Err(CipherModeError::AuthenticationFailed);
+        }
+        Ok(())
+    }
+}
+
+/// HMAC of a whole message
+pub fn hmac<H: Hash>(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<H>::new(key);
+    mac.update(message);
+    mac.finalize()
+}
+
+fn hash<H: Hash>(data: &[u8]) -> Vec<u8> {
+    let mut hash = H::new();
+    hash.update(data);
+    hash.finalize()
+}
+
+/// Multiplication by x in GF(2^n), with the reduction constant of SP 800-38B
+pub(crate) fn double(block: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    let reduction = match block.len() {
+        8 => 0x1b,
+        16 => 0x87,
+        _ => return Err(CipherModeError::InvalidBlockSize),
+    };
+    let mut doubled: Vec<u8> = block.windows(2).map(|pair| pair[0] << 1 | pair[1] >> 7).collect();
+    doubled.push(block[block.len() - 1] << 1);
+    if block[0] & 0x80 != 0 {
+        *doubled.last_mut().expect("block is not empty") ^= reduction;
+    }
+    Ok(doubled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::{Sha3_224, Sha224, Sha256, Sha384, Sha512};
+    use crate::{CipherModes, DummyCipher, KeyInit, Padding};
+
+    const KEY: &[u8] = b"secret key 16 by";
+
+    #[test]
+    fn test_double() {
+        // Subkey K1 of RFC 4493, section 4: L = 7df76b0c1ab899b33e42f047b91b546f
+        let l = [0x7d, 0xf7, 0x6b, 0x0c, 0x1a, 0xb8, 0x99, 0xb3, 0x3e, 0x42, 0xf0, 0x47, 0xb9, 0x1b, 0x54, 0x6f];
+        let k1 = [0xfb, 0xee, 0xd6, 0x18, 0x35, 0x71, 0x33, 0x66, 0x7c, 0x85, 0xe0, 0x8f, 0x72, 0x36, 0xa8, 0xde];
+        let k2 = [0xf7, 0xdd, 0xac, 0x30, 0x6a, 0xe2, 0x66, 0xcc, 0xf9, 0x0b, 0xc1, 0x1e, 0xe4, 0x6d, 0x51, 0x3b];
+        assert_eq!(double(&l).unwrap(), k1);
+        assert_eq!(double(&k1).unwrap(), k2);
+        assert_eq!(double(&[0; 12]), Err(CipherModeError::InvalidBlockSize));
+    }
+
+    #[test]
+    fn test_streaming_matches_one_shot() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let message: Vec<u8> = (0..50).collect();
+        for length in [0, 1, 15, 16, 17, 32, 50] {
+            let expected = cmac(&cipher, &message[..length]).unwrap();
+            for piece in [1, 5, 16, 20] {
+                let mut mac = Cmac::new(&cipher).unwrap();
+                message[..length].chunks(piece).for_each(|chunk| mac.update(chunk));
+                assert_eq!(mac.finalize(), expected, "length {}, pieces of {}", length, piece);
+            }
+        }
+        // A complete and a padded last block use different subkeys
+        assert_ne!(cmac(&cipher, &[0x80]).unwrap(), cmac(&cipher, &[0x80; 16]).unwrap());
+    }
+
+    #[test]
+    fn test_verify() {
+        let cipher = DummyCipher::<8>::new(KEY).unwrap();
+        let tag = cmac(&cipher, b"message").unwrap();
+        let verify = |message: &[u8], tag: &[u8]| {
+            let mut mac = Cmac::new(&cipher).unwrap();
+            mac.update(message);
+            mac.verify(tag)
+        };
+        assert_eq!(verify(b"message", &tag), Ok(()));
+        assert_eq!(verify(b"message", &tag[..4]), Ok(()));
+        assert_eq!(verify(b"massage", &tag), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(verify(b"message", &[]), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(verify(b"message", &[tag.as_slice(), &[0]].concat()), Err(CipherModeError::AuthenticationFailed));
+        assert!(Cmac::new(DummyCipher::<12>::new(KEY).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_cbc_mac_is_last_cbc_block() {
+        let cipher = DummyCipher::<8>::new(KEY).unwrap();
+        let message: Vec<u8> = (0..40).collect();
+        let cbc = CipherModes::cbc_encrypt(&cipher, &[0; 8], &message, Padding::None).unwrap();
+        assert_eq!(cbc_mac(&cipher, &message).unwrap(), &cbc[32..]);
+        let mut mac = CbcMac::new(&cipher);
+        message.chunks(3).for_each(|chunk| mac.update(chunk));
+        assert_eq!(mac.finalize().unwrap(), &cbc[32..]);
+        assert_eq!(cbc_mac(&cipher, &message[..39]), Err(CipherModeError::InvalidInputLength { block_size: 8, actual: 39 }));
+    }
+
+    #[test]
+    fn test_cbc_mac_with_length() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let mut prefixed = vec![0; 15];
+        prefixed.push(5);
+        prefixed.extend_from_slice(b"hello");
+        prefixed.resize(32, 0);
+        assert_eq!(cbc_mac_with_length(&cipher, b"hello").unwrap(), cbc_mac(&cipher, &prefixed).unwrap());
+
+        let mut mac = CbcMac::with_length(&cipher, 6).unwrap();
+        mac.update(b"hello");
+        assert_eq!(mac.finalize(), Err(CipherModeError::LengthMismatch { announced: 6, actual: 5 }));
+        assert!(CbcMac::with_length(DummyCipher::<4>::new(KEY).unwrap(), 0).is_err());
+    }
+
+    #[test]
+    fn test_length_prefix_stops_extension_forgery() {
+        // The forger needs one tag, not the key
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let message = b"pay 100 to alice";
+        let tag = cbc_mac(&cipher, message).unwrap();
+        let forged = [message.as_slice(), &xor_blocks(message, &tag)].concat();
+        let mut mac = CbcMac::new(&cipher);
+        mac.update(&forged);
+        assert_eq!(mac.verify(&tag), Ok(()));
+
+        let tag = cbc_mac_with_length(&cipher, message).unwrap();
+        let forged = [message.as_slice(), &xor_blocks(message, &tag)].concat();
+        let mut mac = CbcMac::with_length(&cipher, forged.len()).unwrap();
+        mac.update(&forged);
+        assert_eq!(mac.verify(&tag), Err(CipherModeError::AuthenticationFailed));
+    }
+
+    /// Test cases 1, 2 and 6 of RFC 4231: a short key, a key shorter than
+    /// the output and a key longer than the SHA-2 blocks
+    const RFC4231_CASES: [(&[u8], &[u8]); 3] = [
+        (&[0x0b; 20], b"Hi There"),
+        (b"Jefe", b"what do ya want for nothing?"),
+        (&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First"),
+    ];
+
+    fn check_vectors<H: Hash>(expected: [&str; 3]) {
+        for ((key, message), expected) in RFC4231_CASES.into_iter().zip(expected) {
+            assert_eq!(hmac::<H>(key, message), codec::hex::decode(expected).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_hmac_sha2_matches_rfc4231() {
+        check_vectors::<Sha224>([
+            "896fb1128abbdf196832107cd49df33f47b4b1169912ba4f53684b22",
+            "a30e01098bc6dbbf45690f3a7e9e6d0f8bbea2a39e6148008fd05e44",
+            "95e9a0db962095adaebe9b2d6f0dbce2d499f112f2d2b7273fa6870e",
+        ]);
+        check_vectors::<Sha256>([
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+        ]);
+        check_vectors::<Sha384>([
+            "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59cfaea9ea9076ede7f4af152e8b2fa9cb6",
+            "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e8e2240ca5e69e2c78b3239ecfab21649",
+            "4ece084485813e9088d2c63a041bc5b44f9ef1012a2b588f3cd11f05033ac4c60c2ef6ab4030fe8296248df163f44952",
+        ]);
+        check_vectors::<Sha512>([
+            "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cdedaa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+            "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+            "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f3526b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+        ]);
+    }
+
+    #[test]
+    fn test_hmac_sha3_224() {
+        // The inputs of RFC 4231 with SHA3-224, as published in the HMAC-SHA3
+        // test vectors; 131 bytes are still shorter than the 144-byte rate
+        check_vectors::<Sha3_224>([
+            "3b16546bbc7be2706a031dcafd56373d9884367641d8c59af3c860f7",
+            "7fdb8dd88bd2f60d1b798634ad386811c2cfc85bfaf5d52bbace5e66",
+            "b4a1f04c00287a9b7f6075b313d279b833bc8f75124352d05fb9995f",
+        ]);
+    }
+
+    #[test]
+    fn test_hmac_streaming_and_verify() {
+        let message: Vec<u8> = (0..=255).collect();
+        let expected = hmac::<Sha256>(b"key", &message);
+        for piece in [1, 7, 64, 100] {
+            let mut mac = Hmac::<Sha256>::new(b"key");
+            message.chunks(piece).for_each(|chunk| mac.update(chunk));
+            assert_eq!(mac.finalize(), expected, "pieces of {}", piece);
+        }
+
+        let verify = |message: &[u8], tag: &[u8]| {
+            let mut mac = Hmac::<Sha256>::new(b"key");
+            mac.update(message);
+            mac.verify(tag)
+        };
+        assert_eq!(verify(&message, &expected), Ok(()));
+        assert_eq!(verify(&message, &expected[..16]), Ok(()));
+        assert_eq!(verify(&message[1..], &expected), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(verify(&message, &[]), Err(CipherModeError::AuthenticationFailed));
+        assert_ne!(hmac::<Sha256>(b"Key", &message), expected);
+    }
+}
@@ -0,0 +1,647 @@
+//! ECB, CBC, OFB and CTR
+//!
+//! ECB and CBC encrypt whole blocks and take a [`Padding`] that fills the
+//! last one; decryption removes it again and reports malformed padding as
+//! [`CipherModeError::InvalidPadding`]. OFB and CTR turn the block cipher into
+//! a stream cipher and work on data of any length without padding.
+
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use secret::Secret;
+
+use crate::ctr::CtrLayout;
+use crate::padding::Padding;
+#[cfg(feature = "parallel")]
+use crate::utils::add_to_counter;
+use crate::utils::{increment_counter, xor_blocks_in_place};
+use crate::{BlockCipher, CipherModeError, block_size_of};
+
+/// Length of the big-endian block counter in [`CipherModes::ctr_encrypt_with_nonce`]
+const NONCE_COUNTER_LENGTH: usize = 4;
+
+/// Namespace for the mode functions
+pub struct CipherModes;
+
+/// A mode and its parameters, for choosing the mode at run time
+///
+/// [`CipherModes::encrypt`] and [`CipherModes::decrypt`] dispatch on it, so
+/// a mode read from a configuration file or command line needs no `match`
+/// of its own. Each variant behaves exactly like the function of the same
+/// name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mode {
+    Ecb {
+        padding: Padding,
+    },
+    Cbc {
+        #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+        iv: Vec<u8>,
+        padding: Padding,
+    },
+    Ofb {
+        #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+        iv: Vec<u8>,
+    },
+    /// The whole block is the counter
+    Ctr {
+        #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+        counter: Vec<u8>,
+    },
+    /// Counter block = nonce || 4-byte big-endian counter from 0
+    CtrWithNonce {
+        #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+        nonce: Vec<u8>,
+    },
+    /// Counter block built from nonce and counter as `layout` describes
+    CtrWithLayout {
+        layout: CtrLayout,
+        #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+        nonce: Vec<u8>,
+    },
+}
+
+/// A mode for [`CipherModes::seal`], which chooses the IV itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SealMode {
+    Cbc { padding: Padding },
+    Ofb,
+    /// The random block is the initial counter
+    Ctr,
+}
+
+impl SealMode {
+    fn with_iv(self, iv: Vec<u8>) -> Mode {
+        match self {
+            SealMode::Cbc { padding } => Mode::Cbc { iv, padding },
+            SealMode::Ofb => Mode::Ofb { iv },
+            SealMode::Ctr => Mode::Ctr { counter: iv },
+        }
+    }
+}
+
+impl CipherModes {
+    /// Electronic Code Book: every block is encrypted on its own
+    pub fn ecb_encrypt<C: BlockCipher>(cipher: &C, plaintext: &[u8], padding: Padding) -> Result<Vec<u8>, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        let mut data = padding.pad(plaintext, block_size)?;
+        Self::ecb_encrypt_in_place(cipher, &mut data)?;
+        Ok(data)
+    }
+
+    pub fn ecb_decrypt<C: BlockCipher>(cipher: &C, ciphertext: &[u8], padding: Padding) -> Result<Vec<u8>, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        let mut data = ciphertext.to_vec();
+        Self::ecb_decrypt_in_place(cipher, &mut data)?;
+        padding.unpad(&data, block_size)
+    }
+
+    /// Cipher Block Chaining: c_i = E(p_i ⊕ c_(i-1)) with c_0 = IV
+    pub fn cbc_encrypt<C: BlockCipher>(cipher: &C, iv: &[u8], plaintext: &[u8], padding: Padding) -> Result<Vec<u8>, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        let mut data = padding.pad(plaintext, block_size)?;
+        Self::cbc_encrypt_in_place(cipher, iv, &mut data)?;
+        Ok(data)
+    }
+
+    /// p_i = D(c_i) ⊕ c_(i-1)
+    ///
+    /// Telling a sender whether the padding was valid turns the receiver into
+    /// a padding oracle that decrypts any ciphertext byte by byte. Only use
+    /// this on ciphertexts whose integrity has been checked, e.g. with a MAC.
+    pub fn cbc_decrypt<C: BlockCipher>(cipher: &C, iv: &[u8], ciphertext: &[u8], padding: Padding) -> Result<Vec<u8>, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        let mut data = ciphertext.to_vec();
+        Self::cbc_decrypt_in_place(cipher, iv, &mut data)?;
+        padding.unpad(&data, block_size)
+    }
+
+    /// Output Feedback: the keystream is E(IV), E(E(IV)), …
+    pub fn ofb_encrypt<C: BlockCipher>(cipher: &C, iv: &[u8], data: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        let mut data = data.to_vec();
+        Self::ofb_encrypt_in_place(cipher, iv, &mut data)?;
+        Ok(data)
+    }
+
+    /// OFB is symmetric, decryption generates the same keystream
+    pub fn ofb_decrypt<C: BlockCipher>(cipher: &C, iv: &[u8], data: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        Self::ofb_encrypt(cipher, iv, data)
+    }
+
+    /// Counter mode: the keystream is E(ctr), E(ctr + 1), …
+    ///
+    /// The whole block is the counter and is incremented as a big-endian integer.
+    pub fn ctr_encrypt<C: BlockCipher>(cipher: &C, counter: &[u8], data: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        let mut data = data.to_vec();
+        Self::ctr_encrypt_in_place(cipher, counter, &mut data)?;
+        Ok(data)
+    }
+
+    /// CTR is symmetric, decryption generates the same keystream
+    pub fn ctr_decrypt<C: BlockCipher>(cipher: &C, counter: &[u8], data: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        Self::ctr_encrypt(cipher, counter, data)
+    }
+
+    /// Counter mode with a nonce: counter block = nonce || 4-byte big-endian counter from 0
+    ///
+    /// The nonce must be `C::BLOCK_SIZE - 4` bytes long.
+    pub fn ctr_encrypt_with_nonce<C: BlockCipher>(cipher: &C, nonce: &[u8], data: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        if block_size <= NONCE_COUNTER_LENGTH {
+            return Err(CipherModeError::InvalidBlockSize);
+        }
+        check_iv(nonce, block_size - NONCE_COUNTER_LENGTH)?;
+
+        let mut counter = nonce.to_vec();
+        counter.extend_from_slice(&[0; NONCE_COUNTER_LENGTH]);
+        Self::ctr_encrypt(cipher, &counter, data)
+    }
+
+    /// Counter mode with nonce and counter arranged as `layout` describes
+    ///
+    /// Unlike [`CipherModes::ctr_encrypt`] the counter wraps around within
+    /// its width and never carries into the nonce.
+    pub fn ctr_encrypt_with_layout<C: BlockCipher>(cipher: &C, layout: &CtrLayout, nonce: &[u8], data: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        layout.check(nonce, block_size)?;
+
+        let mut output = data.to_vec();
+        let mut keystream = Secret::new(vec![0; block_size]);
+        for (index, block) in (0..).zip(output.chunks_mut(block_size)) {
+            layout.counter_block(nonce, index, &mut keystream);
+            cipher.encrypt_block(&mut keystream);
+            xor_blocks_in_place(block, &keystream);
+        }
+        Ok(output)
+    }
+
+    /// CTR is symmetric, decryption generates the same keystream
+    pub fn ctr_decrypt_with_layout<C: BlockCipher>(cipher: &C, layout: &CtrLayout, nonce: &[u8], data: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        Self::ctr_encrypt_with_layout(cipher, layout, nonce, data)
+    }
+
+    /// Encrypts in the given mode
+    pub fn encrypt<C: BlockCipher>(cipher: &C, mode: &Mode, data: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        match mode {
+            Mode::Ecb { padding } => Self::ecb_encrypt(cipher, data, *padding),
+            Mode::Cbc { iv, padding } => Self::cbc_encrypt(cipher, iv, data, *padding),
+            Mode::Ofb { iv } => Self::ofb_encrypt(cipher, iv, data),
+            Mode::Ctr { counter } => Self::ctr_encrypt(cipher, counter, data),
+            Mode::CtrWithNonce { nonce } => Self::ctr_encrypt_with_nonce(cipher, nonce, data),
+            Mode::CtrWithLayout { layout, nonce } => Self::ctr_encrypt_with_layout(cipher, layout, nonce, data),
+        }
+    }
+
+    /// Decrypts in the given mode
+    pub fn decrypt<C: BlockCipher>(cipher: &C, mode: &Mode, data: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        match mode {
+            Mode::Ecb { padding } => Self::ecb_decrypt(cipher, data, *padding),
+            Mode::Cbc { iv, padding } => Self::cbc_decrypt(cipher, iv, data, *padding),
+            Mode::Ofb { .. } | Mode::Ctr { .. } | Mode::CtrWithNonce { .. } | Mode::CtrWithLayout { .. } => {
+                Self::encrypt(cipher, mode, data)
+            }
+        }
+    }
+
+    /// Encrypts under a random IV from `rng` and puts the IV in front of the
+    /// ciphertext
+    ///
+    /// The IV is one block long and travels with the ciphertext, so
+    /// [`CipherModes::open`] only needs the key. Like the other modes this
+    /// does not authenticate: there is no GCM yet, use [`eax_encrypt`] or
+    /// [`chacha20_poly1305_encrypt`] if changes must be detected.
+    ///
+    /// [`eax_encrypt`]: crate::eax_encrypt
+    /// [`chacha20_poly1305_encrypt`]: crate::chacha20_poly1305_encrypt
+    pub fn seal<C: BlockCipher>(cipher: &C, mode: SealMode, plaintext: &[u8], rng: &mut (impl RngCore + CryptoRng)) -> Result<Vec<u8>, CipherModeError> {
+        let mut iv = vec![0; block_size_of::<C>()];
+        rng.fill_bytes(&mut iv);
+        let ciphertext = Self::encrypt(cipher, &mode.with_iv(iv.clone()), plaintext)?;
+        iv.extend(ciphertext);
+        Ok(iv)
+    }
+
+    /// Splits off the IV that [`CipherModes::seal`] put in front and decrypts
+    /// the rest
+    ///
+    /// [`CipherModeError::DecryptionError`] if `sealed` is shorter than a
+    /// block, not whole blocks or badly padded. `open` takes what arrives
+    /// over the wire, and telling these apart would give whoever sent it a
+    /// padding oracle.
+    pub fn open<C: BlockCipher>(cipher: &C, mode: SealMode, sealed: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        if sealed.len() < block_size {
+            return Err(CipherModeError::DecryptionError);
+        }
+        let (iv, ciphertext) = sealed.split_at(block_size);
+        Self::decrypt(cipher, &mode.with_iv(iv.to_vec()), ciphertext).map_err(|error| match error {
+            CipherModeError::InvalidInputLength { .. } | CipherModeError::InvalidPadding => CipherModeError::DecryptionError,
+            error => error,
+        })
+    }
+}
+
+/// The modes on a buffer that is overwritten with the result
+///
+/// Apart from a block or two of state per call nothing is allocated, the
+/// cipher works in place as well. ECB and CBC take whole blocks only and
+/// leave the padding to the caller, see [`Padding::pad`].
+impl CipherModes {
+    pub fn ecb_encrypt_in_place<C: BlockCipher>(cipher: &C, data: &mut [u8]) -> Result<(), CipherModeError> {
+        let block_size = block_size_of::<C>();
+        check_whole_blocks(data, block_size)?;
+
+        data.chunks_mut(block_size).for_each(|block| cipher.encrypt_block(block));
+        Ok(())
+    }
+
+    pub fn ecb_decrypt_in_place<C: BlockCipher>(cipher: &C, data: &mut [u8]) -> Result<(), CipherModeError> {
+        let block_size = block_size_of::<C>();
+        check_whole_blocks(data, block_size)?;
+
+        data.chunks_mut(block_size).for_each(|block| cipher.decrypt_block(block));
+        Ok(())
+    }
+
+    pub fn cbc_encrypt_in_place<C: BlockCipher>(cipher: &C, iv: &[u8], data: &mut [u8]) -> Result<(), CipherModeError> {
+        let block_size = block_size_of::<C>();
+        check_whole_blocks(data, block_size)?;
+        check_iv(iv, block_size)?;
+
+        let mut previous = iv;
+        for block in data.chunks_mut(block_size) {
+            xor_blocks_in_place(block, previous);
+            cipher.encrypt_block(block);
+            previous = block;
+        }
+        Ok(())
+    }
+
+    pub fn cbc_decrypt_in_place<C: BlockCipher>(cipher: &C, iv: &[u8], data: &mut [u8]) -> Result<(), CipherModeError> {
+        let block_size = block_size_of::<C>();
+        check_whole_blocks(data, block_size)?;
+        check_iv(iv, block_size)?;
+
+        // The ciphertext block is needed for the next one but gets overwritten
+        let mut previous = iv.to_vec();
+        let mut current = vec![0; block_size];
+        for block in data.chunks_mut(block_size) {
+            current.copy_from_slice(block);
+            cipher.decrypt_block(block);
+            xor_blocks_in_place(block, &previous);
+            std::mem::swap(&mut previous, &mut current);
+        }
+        Ok(())
+    }
+
+    pub fn ofb_encrypt_in_place<C: BlockCipher>(cipher: &C, iv: &[u8], data: &mut [u8]) -> Result<(), CipherModeError> {
+        let block_size = block_size_of::<C>();
+        check_iv(iv, block_size)?;
+
+        let mut keystream = Secret::new(iv.to_vec());
+        for block in data.chunks_mut(block_size) {
+            cipher.encrypt_block(&mut keystream);
+            xor_blocks_in_place(block, &keystream);
+        }
+        Ok(())
+    }
+
+    pub fn ofb_decrypt_in_place<C: BlockCipher>(cipher: &C, iv: &[u8], data: &mut [u8]) -> Result<(), CipherModeError> {
+        Self::ofb_encrypt_in_place(cipher, iv, data)
+    }
+
+    pub fn ctr_encrypt_in_place<C: BlockCipher>(cipher: &C, counter: &[u8], data: &mut [u8]) -> Result<(), CipherModeError> {
+        let block_size = block_size_of::<C>();
+        check_iv(counter, block_size)?;
+
+        let mut counter = counter.to_vec();
+        let mut keystream = Secret::new(vec![0; block_size]);
+        for block in data.chunks_mut(block_size) {
+            keystream.copy_from_slice(&counter);
+            cipher.encrypt_block(&mut keystream);
+            xor_blocks_in_place(block, &keystream);
+            increment_counter(&mut counter);
+        }
+        Ok(())
+    }
+
+    pub fn ctr_decrypt_in_place<C: BlockCipher>(cipher: &C, counter: &[u8], data: &mut [u8]) -> Result<(), CipherModeError> {
+        Self::ctr_encrypt_in_place(cipher, counter, data)
+    }
+}
+
+/// Blocks per task of the parallel functions, large enough to keep the
+/// scheduling overhead small
+#[cfg(feature = "parallel")]
+pub const PARALLEL_CHUNK_BLOCKS: usize = 1024;
+
+/// The modes whose blocks do not depend on each other, spread over all cores
+///
+/// The output is identical to the sequential functions; only inputs of
+/// more than [`PARALLEL_CHUNK_BLOCKS`] blocks are split at all. The
+/// `parallel` example compares their throughput with the sequential ones.
+#[cfg(feature = "parallel")]
+impl CipherModes {
+    /// [`CipherModes::ecb_encrypt`] on all cores
+    pub fn ecb_encrypt_parallel<C: BlockCipher + Sync>(cipher: &C, plaintext: &[u8], padding: Padding) -> Result<Vec<u8>, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        let mut data = padding.pad(plaintext, block_size)?;
+        data.par_chunks_mut(block_size * PARALLEL_CHUNK_BLOCKS)
+            .try_for_each(|chunk| Self::ecb_encrypt_in_place(cipher, chunk))?;
+        Ok(data)
+    }
+
+    /// [`CipherModes::ecb_decrypt`] on all cores
+    pub fn ecb_decrypt_parallel<C: BlockCipher + Sync>(cipher: &C, ciphertext: &[u8], padding: Padding) -> Result<Vec<u8>, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        check_whole_blocks(ciphertext, block_size)?;
+        let mut data = ciphertext.to_vec();
+        data.par_chunks_mut(block_size * PARALLEL_CHUNK_BLOCKS)
+            .try_for_each(|chunk| Self::ecb_decrypt_in_place(cipher, chunk))?;
+        padding.unpad(&data, block_size)
+    }
+
+    /// [`CipherModes::ctr_encrypt`] on all cores
+    ///
+    /// Chunk i starts with the counter advanced by i · [`PARALLEL_CHUNK_BLOCKS`],
+    /// so the keystream is the same as if one thread had produced it.
+    pub fn ctr_encrypt_parallel<C: BlockCipher + Sync>(cipher: &C, counter: &[u8], data: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        check_iv(counter, block_size)?;
+
+        let chunk_size = block_size * PARALLEL_CHUNK_BLOCKS;
+        let mut output = data.to_vec();
+        output.par_chunks_mut(chunk_size).enumerate().try_for_each(|(i, chunk)| {
+            let mut counter = counter.to_vec();
+            add_to_counter(&mut counter, (i * PARALLEL_CHUNK_BLOCKS) as u64);
+            Self::ctr_encrypt_in_place(cipher, &counter, chunk)
+        })?;
+        Ok(output)
+    }
+
+    /// CTR is symmetric, decryption generates the same keystream
+    pub fn ctr_decrypt_parallel<C: BlockCipher + Sync>(cipher: &C, counter: &[u8], data: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+        Self::ctr_encrypt_parallel(cipher, counter, data)
+    }
+}
+
+fn check_whole_blocks(data: &[u8], block_size: usize) -> Result<(), CipherModeError> {
+    if !data.len().is_multiple_of(block_size) {
+        return Err(CipherModeError::InvalidInputLength { block_size, actual: data.len() });
+    }
+    Ok(())
+}
+
+fn check_iv(iv: &[u8], expected: usize) -> Result<(), CipherModeError> {
+    if iv.len() != expected {
+        return Err(CipherModeError::InvalidIvLength { expected, actual: iv.len() });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use csprng::{Algorithm, Drbg};
+
+    use super::*;
+    use crate::ctr::{CounterPosition, Endianness};
+    use crate::utils::xor_blocks;
+    use crate::{DummyCipher, KeyInit};
+
+    const KEY: &[u8] = b"secret key 16 by";
+    const IV: &[u8] = b"initial vector!!";
+
+    #[test]
+    fn test_roundtrips() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let plaintext = b"The quick brown fox jumps over the lazy dog";
+
+        let ecb = CipherModes::ecb_encrypt(&cipher, plaintext, Padding::Pkcs7).unwrap();
+        assert_eq!(ecb.len(), 48);
+        assert_eq!(CipherModes::ecb_decrypt(&cipher, &ecb, Padding::Pkcs7).unwrap(), plaintext);
+
+        let cbc = CipherModes::cbc_encrypt(&cipher, IV, plaintext, Padding::Pkcs7).unwrap();
+        assert_eq!(CipherModes::cbc_decrypt(&cipher, IV, &cbc, Padding::Pkcs7).unwrap(), plaintext);
+
+        let ofb = CipherModes::ofb_encrypt(&cipher, IV, plaintext).unwrap();
+        assert_eq!(ofb.len(), plaintext.len());
+        assert_eq!(CipherModes::ofb_decrypt(&cipher, IV, &ofb).unwrap(), plaintext);
+
+        let ctr = CipherModes::ctr_encrypt(&cipher, IV, plaintext).unwrap();
+        assert_eq!(CipherModes::ctr_decrypt(&cipher, IV, &ctr).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn test_pkcs7_padding() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let plaintext = b"ends in zeros.\0\0";
+
+        // Zero bytes at the end of the plaintext survive, a full block of padding is added
+        let ecb = CipherModes::ecb_encrypt(&cipher, plaintext, Padding::Pkcs7).unwrap();
+        assert_eq!(ecb.len(), 32);
+        assert_eq!(CipherModes::ecb_decrypt(&cipher, &ecb, Padding::Pkcs7).unwrap(), plaintext);
+        let ciphertext = CipherModes::cbc_encrypt(&cipher, IV, plaintext, Padding::Pkcs7).unwrap();
+        assert_eq!(ciphertext.len(), 32);
+        assert_eq!(CipherModes::cbc_decrypt(&cipher, IV, &ciphertext, Padding::Pkcs7).unwrap(), plaintext);
+
+        // Flipping the last byte of the previous block corrupts the padding
+        let mut tampered = ciphertext.clone();
+        tampered[15] ^= 0x10;
+        assert_eq!(CipherModes::cbc_decrypt(&cipher, IV, &tampered, Padding::Pkcs7), Err(CipherModeError::InvalidPadding));
+        assert_eq!(CipherModes::ecb_decrypt(&cipher, &ecb[..16], Padding::Pkcs7), Err(CipherModeError::InvalidPadding));
+        let wide = DummyCipher::<256>::new(KEY).unwrap();
+        assert_eq!(CipherModes::cbc_encrypt(&wide, &[0; 256], b"", Padding::Pkcs7), Err(CipherModeError::InvalidBlockSize));
+    }
+
+    #[test]
+    fn test_padding_schemes() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let plaintext = b"ends in zeros.\0\0";
+
+        for padding in [Padding::Pkcs7, Padding::AnsiX923, Padding::Iso7816] {
+            let ciphertext = CipherModes::cbc_encrypt(&cipher, IV, plaintext, padding).unwrap();
+            assert_eq!(ciphertext.len(), 32, "{:?}", padding);
+            assert_eq!(CipherModes::cbc_decrypt(&cipher, IV, &ciphertext, padding).unwrap(), plaintext);
+        }
+
+        // Zero padding adds nothing to whole blocks and strips the plaintext's own zeros
+        let ciphertext = CipherModes::ecb_encrypt(&cipher, plaintext, Padding::Zero).unwrap();
+        assert_eq!(ciphertext.len(), 16);
+        assert_eq!(CipherModes::ecb_decrypt(&cipher, &ciphertext, Padding::Zero).unwrap(), b"ends in zeros.");
+
+        let ciphertext = CipherModes::ecb_encrypt(&cipher, plaintext, Padding::None).unwrap();
+        assert_eq!(CipherModes::ecb_decrypt(&cipher, &ciphertext, Padding::None).unwrap(), plaintext);
+        assert_eq!(
+            CipherModes::cbc_encrypt(&cipher, IV, &plaintext[..15], Padding::None),
+            Err(CipherModeError::InvalidInputLength { block_size: 16, actual: 15 })
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let data: Vec<u8> = (0..=255).cycle().take(16 * PARALLEL_CHUNK_BLOCKS * 3 + 5).collect();
+        // The counter wraps around inside the second chunk
+        let counter = [[0xff; 14].as_slice(), &[0xfb, 0x00]].concat();
+
+        let ctr = CipherModes::ctr_encrypt_parallel(&cipher, &counter, &data).unwrap();
+        assert_eq!(ctr, CipherModes::ctr_encrypt(&cipher, &counter, &data).unwrap());
+        assert_eq!(CipherModes::ctr_decrypt_parallel(&cipher, &counter, &ctr).unwrap(), data);
+
+        let ecb = CipherModes::ecb_encrypt_parallel(&cipher, &data, Padding::Pkcs7).unwrap();
+        assert_eq!(ecb, CipherModes::ecb_encrypt(&cipher, &data, Padding::Pkcs7).unwrap());
+        assert_eq!(CipherModes::ecb_decrypt_parallel(&cipher, &ecb, Padding::Pkcs7).unwrap(), data);
+    }
+
+    #[test]
+    fn test_in_place_matches_allocating() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let plaintext: Vec<u8> = (0..48).collect();
+
+        type InPlace = fn(&DummyCipher, &[u8], &mut [u8]) -> Result<(), CipherModeError>;
+        let modes: [(InPlace, InPlace, Vec<u8>); 3] = [
+            (CipherModes::cbc_encrypt_in_place, CipherModes::cbc_decrypt_in_place, CipherModes::cbc_encrypt(&cipher, IV, &plaintext, Padding::None).unwrap()),
+            (CipherModes::ofb_encrypt_in_place, CipherModes::ofb_decrypt_in_place, CipherModes::ofb_encrypt(&cipher, IV, &plaintext[..40]).unwrap()),
+            (CipherModes::ctr_encrypt_in_place, CipherModes::ctr_decrypt_in_place, CipherModes::ctr_encrypt(&cipher, IV, &plaintext[..40]).unwrap()),
+        ];
+        for (encrypt, decrypt, expected) in modes {
+            let mut data = plaintext[..expected.len()].to_vec();
+            encrypt(&cipher, IV, &mut data).unwrap();
+            assert_eq!(data, expected);
+            decrypt(&cipher, IV, &mut data).unwrap();
+            assert_eq!(data, &plaintext[..expected.len()]);
+        }
+
+        let mut data = plaintext.clone();
+        CipherModes::ecb_encrypt_in_place(&cipher, &mut data).unwrap();
+        assert_eq!(data, CipherModes::ecb_encrypt(&cipher, &plaintext, Padding::None).unwrap());
+        CipherModes::ecb_decrypt_in_place(&cipher, &mut data).unwrap();
+        assert_eq!(data, plaintext);
+        assert_eq!(
+            CipherModes::cbc_encrypt_in_place(&cipher, IV, &mut data[..40]),
+            Err(CipherModeError::InvalidInputLength { block_size: 16, actual: 40 })
+        );
+    }
+
+    #[test]
+    fn test_ctr_with_nonce() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let nonce = &IV[..12];
+
+        let mut counter = nonce.to_vec();
+        counter.extend_from_slice(&[0, 0, 0, 0]);
+        assert_eq!(
+            CipherModes::ctr_encrypt_with_nonce(&cipher, nonce, b"data").unwrap(),
+            CipherModes::ctr_encrypt(&cipher, &counter, b"data").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_ctr_with_layout() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let data: Vec<u8> = (0..40).collect();
+
+        // Without wrap-around the default layout is ctr_encrypt_with_nonce
+        assert_eq!(
+            CipherModes::ctr_encrypt_with_layout(&cipher, &CtrLayout::new(12, 4), &IV[..12], &data).unwrap(),
+            CipherModes::ctr_encrypt_with_nonce(&cipher, &IV[..12], &data).unwrap()
+        );
+
+        // 64-bit little-endian counter in front, counting in steps of 2 from 0xff…ff
+        let layout = CtrLayout {
+            endianness: Endianness::Little,
+            position: CounterPosition::Start,
+            step: 2,
+            initial_counter: u64::MAX,
+            ..CtrLayout::new(8, 8)
+        };
+        let counters: Vec<u8> = [u64::MAX, 1, 3]
+            .iter()
+            .flat_map(|counter| [counter.to_le_bytes().as_slice(), &IV[8..]].concat())
+            .collect();
+        let keystream = CipherModes::ecb_encrypt(&cipher, &counters, Padding::None).unwrap();
+        let ciphertext = CipherModes::ctr_encrypt_with_layout(&cipher, &layout, &IV[8..], &data).unwrap();
+        assert_eq!(ciphertext, xor_blocks(&data, &keystream));
+        assert_eq!(CipherModes::ctr_decrypt_with_layout(&cipher, &layout, &IV[8..], &ciphertext).unwrap(), data);
+
+        assert_eq!(
+            CipherModes::ctr_encrypt_with_layout(&cipher, &CtrLayout::new(12, 8), &IV[..12], &data),
+            Err(CipherModeError::InvalidBlockSize)
+        );
+    }
+
+    #[test]
+    fn test_mode_dispatch() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let plaintext = b"chosen at run time, not at compile time";
+        let modes = [
+            (Mode::Ecb { padding: Padding::Pkcs7 }, CipherModes::ecb_encrypt(&cipher, plaintext, Padding::Pkcs7)),
+            (Mode::Cbc { iv: IV.to_vec(), padding: Padding::Iso7816 }, CipherModes::cbc_encrypt(&cipher, IV, plaintext, Padding::Iso7816)),
+            (Mode::Ofb { iv: IV.to_vec() }, CipherModes::ofb_encrypt(&cipher, IV, plaintext)),
+            (Mode::Ctr { counter: IV.to_vec() }, CipherModes::ctr_encrypt(&cipher, IV, plaintext)),
+            (Mode::CtrWithNonce { nonce: IV[..12].to_vec() }, CipherModes::ctr_encrypt_with_nonce(&cipher, &IV[..12], plaintext)),
+            (
+                Mode::CtrWithLayout { layout: CtrLayout::new(8, 8), nonce: IV[..8].to_vec() },
+                CipherModes::ctr_encrypt_with_layout(&cipher, &CtrLayout::new(8, 8), &IV[..8], plaintext),
+            ),
+        ];
+        for (mode, expected) in modes {
+            let ciphertext = CipherModes::encrypt(&cipher, &mode, plaintext).unwrap();
+            assert_eq!(ciphertext, expected.unwrap(), "{:?}", mode);
+            assert_eq!(CipherModes::decrypt(&cipher, &mode, &ciphertext).unwrap(), plaintext, "{:?}", mode);
+        }
+        assert_eq!(
+            CipherModes::encrypt(&cipher, &Mode::Cbc { iv: vec![], padding: Padding::Pkcs7 }, plaintext),
+            Err(CipherModeError::InvalidIvLength { expected: 16, actual: 0 })
+        );
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        assert_eq!(
+            CipherModes::cbc_encrypt(&cipher, &IV[..8], b"data", Padding::Pkcs7),
+            Err(CipherModeError::InvalidIvLength { expected: 16, actual: 8 })
+        );
+        assert_eq!(
+            CipherModes::ecb_decrypt(&cipher, &[0; 17], Padding::Pkcs7),
+            Err(CipherModeError::InvalidInputLength { block_size: 16, actual: 17 })
+        );
+        assert_eq!(
+            CipherModes::ctr_encrypt_with_nonce(&cipher, IV, b"data"),
+            Err(CipherModeError::InvalidIvLength { expected: 12, actual: 16 })
+        );
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"seal test");
+        let plaintext = b"The IV travels with the ciphertext";
+        for mode in [SealMode::Cbc { padding: Padding::Pkcs7 }, SealMode::Ofb, SealMode::Ctr] {
+            let sealed = CipherModes::seal(&cipher, mode, plaintext, &mut rng).unwrap();
+            let (iv, ciphertext) = sealed.split_at(16);
+            assert_eq!(CipherModes::decrypt(&cipher, &mode.with_iv(iv.to_vec()), ciphertext).unwrap(), plaintext, "{:?}", mode);
+            assert_eq!(CipherModes::open(&cipher, mode, &sealed).unwrap(), plaintext, "{:?}", mode);
+
+            // A fresh IV every time
+            let again = CipherModes::seal(&cipher, mode, plaintext, &mut rng).unwrap();
+            assert_ne!(again[..16], sealed[..16], "{:?}", mode);
+            assert_ne!(again[16..], sealed[16..], "{:?}", mode);
+        }
+
+        assert_eq!(CipherModes::open(&cipher, SealMode::Ctr, &[0; 16]).unwrap(), b"");
+        assert_eq!(CipherModes::open(&cipher, SealMode::Ofb, &[0; 15]), Err(CipherModeError::DecryptionError));
+
+        // Bad padding and a ragged length look the same
+        let cbc = SealMode::Cbc { padding: Padding::Pkcs7 };
+        let mut sealed = CipherModes::seal(&cipher, cbc, plaintext, &mut rng).unwrap();
+        assert_eq!(CipherModes::open(&cipher, cbc, &sealed[..sealed.len() - 1]), Err(CipherModeError::DecryptionError));
+        let last = sealed.len() - 1;
+        sealed[last - 16] ^= 0xff;
+        assert_eq!(CipherModes::open(&cipher, cbc, &sealed), Err(CipherModeError::DecryptionError));
+    }
+}
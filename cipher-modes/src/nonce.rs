@@ -0,0 +1,167 @@
+//! Nonces and counter blocks that do not repeat
+//!
+//! CTR, OFB and the AEADs built on them turn key and nonce into a
+//! keystream. Two messages under the same key and nonce share it, and the
+//! XOR of the ciphertexts is the XOR of the plaintexts. [`NonceManager`]
+//! hands out nonces for one key and refuses any it has handed out or seen
+//! before:
+//!
+//! - [`NonceManager::next_counter`] gives CTR counter blocks and skips the
+//!   blocks the message uses, so the keystreams do not overlap either,
+//! - [`NonceManager::next_sequential`] counts up by one, for nonces that are
+//!   followed by a block counter of their own, as in
+//!   [`CipherModes::ctr_encrypt_with_nonce`], EAX or ChaCha20-Poly1305,
+//! - [`NonceManager::next_random`] draws them from a random number generator,
+//! - [`NonceManager::register`] records nonces chosen elsewhere, for example
+//!   those of received messages.
+//!
+//! Counters start at zero and go up, so the used ones are everything below
+//! the next; random and registered nonces are kept in a set. Both are only
+//! remembered as long as the manager lives: a new session with the same key
+//! needs a new key or the state of the old manager.
+//!
+//! [`CipherModes::ctr_encrypt_with_nonce`]: crate::CipherModes::ctr_encrypt_with_nonce
+
+use std::collections::HashSet;
+
+use rand_core::{CryptoRng, RngCore};
+
+use crate::CipherModeError;
+use crate::utils::add_to_counter;
+
+/// Nonces of one length for one key, each handed out at most once
+#[derive(Debug, Clone)]
+pub struct NonceManager {
+    size: usize,
+    /// The next counter as a big-endian integer, `None` once they are used up
+    counter: Option<Vec<u8>>,
+    /// Random and registered nonces
+    used: HashSet<Vec<u8>>,
+}
+
+impl NonceManager {
+    /// A manager for nonces of `size` bytes; the block size for counter blocks
+    pub fn new(size: usize) -> Self {
+        NonceManager { size, counter: Some(vec![0; size]), used: HashSet::new() }
+    }
+
+    /// The counter block for a message of `length` bytes
+    ///
+    /// The next call continues after the last block of the message, at least
+    /// one block further. [`CipherModeError::NonceReused`] once the counter
+    /// would wrap around or run into a random or registered nonce.
+    pub fn next_counter(&mut self, length: usize) -> Result<Vec<u8>, CipherModeError> {
+        let blocks = length.div_ceil(self.size.max(1)).max(1);
+        self.advance(blocks as u64)
+    }
+
+    /// The nonce after the previous one, starting at zero
+    pub fn next_sequential(&mut self) -> Result<Vec<u8>, CipherModeError> {
+        self.advance(1)
+    }
+
+    /// A nonce from `rng`
+    ///
+    /// [`CipherModeError::NonceReused`] if it was handed out before, which
+    /// with nonces of 12 bytes or more means that `rng` is broken.
+    pub fn next_random(&mut self, rng: &mut (impl RngCore + CryptoRng)) -> Result<Vec<u8>, CipherModeError> {
+        let mut nonce = vec![0; self.size];
+        rng.fill_bytes(&mut nonce);
+        self.register(&nonce)?;
+        Ok(nonce)
+    }
+
+    /// Records a nonce chosen elsewhere; [`CipherModeError::NonceReused`] if
+    /// it was used before
+    pub fn register(&mut self, nonce: &[u8]) -> Result<(), CipherModeError> {
+        if nonce.len() != self.size {
+            return Err(CipherModeError::InvalidIvLength { expected: self.size, actual: nonce.len() });
+        }
+        if self.is_used(nonce) {
+            return Err(CipherModeError::NonceReused);
+        }
+        self.used.insert(nonce.to_vec());
+        Ok(())
+    }
+
+    /// Whether `nonce` was handed out or registered
+    pub fn is_used(&self, nonce: &[u8]) -> bool {
+        match &self.counter {
+            Some(counter) => nonce < counter.as_slice() || self.used.contains(nonce),
+            None => true,
+        }
+    }
+
+    /// Hands out the current counter and reserves `n` values from it
+    fn advance(&mut self, n: u64) -> Result<Vec<u8>, CipherModeError> {
+        let current = self.counter.clone().ok_or(CipherModeError::NonceReused)?;
+        let mut next = current.clone();
+        add_to_counter(&mut next, n);
+        let wrapped = (self.size < 8 && n >> (8 * self.size) != 0) || next <= current;
+        if wrapped {
+            self.counter = None;
+            return Err(CipherModeError::NonceReused);
+        }
+        if self.used.iter().any(|nonce| *nonce >= current && *nonce < next) {
+            return Err(CipherModeError::NonceReused);
+        }
+        self.counter = Some(next);
+        Ok(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use csprng::{Algorithm, Drbg};
+
+    use super::*;
+    use crate::{CipherModes, DummyCipher, KeyInit};
+
+    #[test]
+    fn test_counters_do_not_overlap() {
+        let cipher = DummyCipher::<16>::new(b"secret key 16 by").unwrap();
+        let mut nonces = NonceManager::new(16);
+        let mut blocks = HashSet::new();
+        for length in [40, 0, 16, 1] {
+            let counter = nonces.next_counter(length).unwrap();
+            let keystream = CipherModes::ctr_encrypt(&cipher, &counter, &vec![0; length.next_multiple_of(16)]).unwrap();
+            blocks.extend(keystream.chunks(16).map(<[u8]>::to_vec));
+        }
+        assert_eq!(blocks.len(), 5);
+        // 3 + 1 + 1 + 1 blocks reserved, the empty message takes one as well
+        assert_eq!(nonces.next_sequential().unwrap(), [[0; 15].as_slice(), &[6]].concat());
+    }
+
+    #[test]
+    fn test_rejects_reuse() {
+        let mut nonces = NonceManager::new(12);
+        let first = nonces.next_sequential().unwrap();
+        assert_eq!(nonces.register(&first), Err(CipherModeError::NonceReused));
+
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"nonce test");
+        let random = nonces.next_random(&mut rng).unwrap();
+        assert!(nonces.is_used(&random));
+        assert_eq!(nonces.register(&random), Err(CipherModeError::NonceReused));
+        assert_eq!(nonces.register(&[0; 8]), Err(CipherModeError::InvalidIvLength { expected: 12, actual: 8 }));
+
+        // The counter does not run into a registered nonce
+        let mut ahead = [0; 12];
+        ahead[11] = 3;
+        nonces.register(&ahead).unwrap();
+        assert_eq!(nonces.next_counter(24).unwrap()[11], 1);
+        assert_eq!(nonces.next_counter(1), Err(CipherModeError::NonceReused));
+    }
+
+    #[test]
+    fn test_counter_exhausted() {
+        let mut nonces = NonceManager::new(1);
+        for expected in 0..255 {
+            assert_eq!(nonces.next_sequential().unwrap(), [expected]);
+        }
+        // 0xff would be followed by 0x00 again
+        assert_eq!(nonces.next_sequential(), Err(CipherModeError::NonceReused));
+        assert_eq!(nonces.next_sequential(), Err(CipherModeError::NonceReused));
+        assert!(nonces.is_used(&[0xff]));
+        assert_eq!(NonceManager::new(1).next_counter(256), Err(CipherModeError::NonceReused));
+    }
+}
@@ -0,0 +1,197 @@
+//! Padding schemes for ECB and CBC
+//!
+//! Both modes encrypt whole blocks only, so the plaintext is padded before
+//! encryption and the padding removed after decryption. The schemes differ
+//! in what the added bytes look like and in whether they can be removed
+//! unambiguously:
+//!
+//! ```text
+//! Pkcs7      … 04 04 04 04     n bytes of value n
+//! AnsiX923   … 00 00 00 04     n - 1 zero bytes, then n
+//! Iso7816    … 80 00 00 00     0x80, then zero bytes
+//! Zero       … 00 00 00 00     zero bytes, none for whole blocks
+//! None                         whole blocks only
+//! ```
+//!
+//! The first three always add at least one byte and are removed exactly.
+//! Zero padding cannot tell its own bytes from zero bytes at the end of the
+//! plaintext and strips both; it is only there for old formats and exercises.
+
+use crate::CipherModeError;
+
+/// How [`CipherModes`](crate::CipherModes) fills the last block in ECB and CBC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Padding {
+    /// PKCS#7 (RFC 5652): n bytes of value n, 1 ≤ n ≤ block size
+    #[default]
+    Pkcs7,
+    /// ANSI X9.23: zero bytes, the last one holds the padding length
+    AnsiX923,
+    /// ISO/IEC 7816-4 (also ISO/IEC 9797-1 method 2): 0x80 followed by zero bytes
+    Iso7816,
+    /// Zero bytes up to the block boundary; lossy for plaintexts ending in 0x00
+    Zero,
+    /// No padding, the plaintext must already fill whole blocks
+    None,
+}
+
+impl Padding {
+    /// Pads `data` to a multiple of `block_size`
+    ///
+    /// PKCS#7 and ANSI X9.23 store the length in one byte and need a block
+    /// size of at most 255, otherwise [`CipherModeError::InvalidBlockSize`].
+    /// Without padding, data that does not fill whole blocks is
+    /// [`CipherModeError::InvalidInputLength`].
+    pub fn pad(self, data: &[u8], block_size: usize) -> Result<Vec<u8>, CipherModeError> {
+        self.check_block_size(block_size)?;
+        let missing = block_size - data.len() % block_size;
+        let mut padded = data.to_vec();
+        match self {
+            Padding::Pkcs7 => padded.resize(data.len() + missing, missing as u8),
+            Padding::AnsiX923 => {
+                padded.resize(data.len() + missing - 1, 0);
+                padded.push(missing as u8);
+            }
+            Padding::Iso7816 => {
+                padded.push(0x80);
+                padded.resize(data.len() + missing, 0);
+            }
+            Padding::Zero => padded.resize(data.len().next_multiple_of(block_size), 0),
+            Padding::None if missing != block_size => {
+                return Err(CipherModeError::InvalidInputLength { block_size, actual: data.len() });
+            }
+            Padding::None => {}
+        }
+        Ok(padded)
+    }
+
+    /// Removes the padding; [`CipherModeError::InvalidPadding`] if it is malformed
+    ///
+    /// PKCS#7, ANSI X9.23 and ISO/IEC 7816-4 check the whole last block
+    /// without stopping at the first bad byte, so the time taken does not
+    /// reveal where the padding went wrong.
+    pub fn unpad(self, data: &[u8], block_size: usize) -> Result<Vec<u8>, CipherModeError> {
+        self.check_block_size(block_size)?;
+        let tail = &data[data.len().saturating_sub(block_size)..];
+        let (padding, invalid) = match self {
+            Padding::Pkcs7 => length_byte_padding(tail, |_, byte, padding| byte != padding),
+            Padding::AnsiX923 => length_byte_padding(tail, |i, byte, _| i > 0 && byte != 0),
+            Padding::Iso7816 => iso7816_padding(tail),
+            Padding::Zero => (tail.iter().rev().take_while(|&&byte| byte == 0).count(), false),
+            Padding::None => (0, false),
+        };
+        if std::hint::black_box(invalid) {
+            return Err(CipherModeError::InvalidPadding);
+        }
+        Ok(data[..data.len() - padding].to_vec())
+    }
+
+    pub(crate) fn check_block_size(self, block_size: usize) -> Result<(), CipherModeError> {
+        let max = match self {
+            Padding::Pkcs7 | Padding::AnsiX923 => 255,
+            _ => usize::MAX,
+        };
+        if block_size == 0 || block_size > max {
+            return Err(CipherModeError::InvalidBlockSize);
+        }
+        Ok(())
+    }
+}
+
+/// Padding length from the last byte; `bad(i, byte, length)` tells whether
+/// the i-th byte from the end breaks the padding
+fn length_byte_padding(tail: &[u8], bad: impl Fn(usize, u8, u8) -> bool) -> (usize, bool) {
+    let Some(&length) = tail.last() else {
+        return (0, true);
+    };
+    let mut invalid = u8::from(length == 0) | u8::from(usize::from(length) > tail.len());
+    for (i, &byte) in tail.iter().rev().enumerate() {
+        invalid |= u8::from(i < usize::from(length)) & u8::from(bad(i, byte, length));
+    }
+    (usize::from(length), invalid != 0)
+}
+
+/// Padding length up to the last non-zero byte, which must be 0x80
+fn iso7816_padding(tail: &[u8]) -> (usize, bool) {
+    let mut seen = 0u8;
+    let mut invalid = 0u8;
+    let mut length = 0;
+    for (i, &byte) in tail.iter().rev().enumerate() {
+        let first = u8::from(seen == 0) & u8::from(byte != 0);
+        invalid |= first & u8::from(byte != 0x80);
+        length |= (i + 1) * usize::from(first);
+        seen |= first;
+    }
+    (length, (invalid | u8::from(seen == 0)) != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pkcs7() {
+        let pkcs7 = Padding::Pkcs7;
+        assert_eq!(pkcs7.pad(b"abc", 4).unwrap(), b"abc\x01");
+        assert_eq!(pkcs7.pad(b"abcd", 4).unwrap(), b"abcd\x04\x04\x04\x04");
+        assert_eq!(pkcs7.unpad(b"abc\x01", 4).unwrap(), b"abc");
+        assert_eq!(pkcs7.unpad(b"abcd\x04\x04\x04\x04", 4).unwrap(), b"abcd");
+        assert_eq!(pkcs7.unpad(b"ab\x01\x02", 4), Err(CipherModeError::InvalidPadding));
+        assert_eq!(pkcs7.unpad(b"abc\x00", 4), Err(CipherModeError::InvalidPadding));
+        assert_eq!(pkcs7.unpad(b"abc\x05", 4), Err(CipherModeError::InvalidPadding));
+        assert_eq!(pkcs7.unpad(b"", 4), Err(CipherModeError::InvalidPadding));
+        assert_eq!(pkcs7.unpad(b"\x02\x02", 4).unwrap(), b"");
+        assert_eq!(pkcs7.unpad(b"\x03\x03", 4), Err(CipherModeError::InvalidPadding));
+        assert_eq!(pkcs7.pad(b"", 256), Err(CipherModeError::InvalidBlockSize));
+    }
+
+    #[test]
+    fn test_ansi_x923() {
+        let x923 = Padding::AnsiX923;
+        assert_eq!(x923.pad(b"abc", 4).unwrap(), b"abc\x01");
+        assert_eq!(x923.pad(b"ab", 4).unwrap(), b"ab\x00\x02");
+        assert_eq!(x923.pad(b"abcd", 4).unwrap(), b"abcd\x00\x00\x00\x04");
+        assert_eq!(x923.unpad(b"ab\x00\x02", 4).unwrap(), b"ab");
+        assert_eq!(x923.unpad(b"abcd\x00\x00\x00\x04", 4).unwrap(), b"abcd");
+        assert_eq!(x923.unpad(b"a\x01\x00\x03", 4), Err(CipherModeError::InvalidPadding));
+        assert_eq!(x923.unpad(b"abc\x00", 4), Err(CipherModeError::InvalidPadding));
+        assert_eq!(x923.unpad(b"abc\x05", 4), Err(CipherModeError::InvalidPadding));
+    }
+
+    #[test]
+    fn test_iso7816() {
+        let iso = Padding::Iso7816;
+        assert_eq!(iso.pad(b"abc", 4).unwrap(), b"abc\x80");
+        assert_eq!(iso.pad(b"a", 4).unwrap(), b"a\x80\x00\x00");
+        assert_eq!(iso.pad(b"abcd", 4).unwrap(), b"abcd\x80\x00\x00\x00");
+        assert_eq!(iso.unpad(b"a\x00\x80\x00", 4).unwrap(), b"a\x00");
+        assert_eq!(iso.unpad(b"abcd\x80\x00\x00\x00", 4).unwrap(), b"abcd");
+        assert_eq!(iso.unpad(b"abc\x01", 4), Err(CipherModeError::InvalidPadding));
+        assert_eq!(iso.unpad(b"\x00\x00\x00\x00", 4), Err(CipherModeError::InvalidPadding));
+        assert_eq!(iso.unpad(b"", 4), Err(CipherModeError::InvalidPadding));
+    }
+
+    #[test]
+    fn test_zero_and_none() {
+        assert_eq!(Padding::Zero.pad(b"abc", 4).unwrap(), b"abc\0");
+        assert_eq!(Padding::Zero.pad(b"abcd", 4).unwrap(), b"abcd");
+        // The zero byte of the plaintext is lost
+        assert_eq!(Padding::Zero.unpad(b"ab\0\0", 4).unwrap(), b"ab");
+        assert_eq!(Padding::None.pad(b"abcd", 4).unwrap(), b"abcd");
+        assert_eq!(Padding::None.pad(b"abc", 4), Err(CipherModeError::InvalidInputLength { block_size: 4, actual: 3 }));
+        assert_eq!(Padding::None.unpad(b"ab\0\0", 4).unwrap(), b"ab\0\0");
+    }
+
+    #[test]
+    #[ignore = "measures run time, run with cargo test -- --ignored"]
+    fn test_unpad_timing() {
+        // Only the first or only the second-to-last padding byte is wrong
+        let mut early = vec![0x10; 16];
+        early[0] = 0x0f;
+        let mut late = vec![0x10; 16];
+        late[14] = 0x0f;
+        let comparison = secret::ct::timing::test_inputs(20000, [early, late], |block| Padding::Pkcs7.unpad(block, 16));
+        assert!(!comparison.leaks(), "{}", comparison);
+    }
+}
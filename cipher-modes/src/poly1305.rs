@@ -0,0 +1,209 @@
+//! Poly1305, a one-time MAC (RFC 8439)
+//!
+//! The message is split into 16-byte blocks, each read as a little-endian
+//! number with a 1 appended, and evaluated as a polynomial at the secret
+//! point r modulo the prime 2^130 - 5; the secret s is added at the end:
+//!
+//! ```text
+//! h = (…((m_1·r + m_2)·r + m_3)·r …)·r  mod 2^130 - 5
+//! tag = (h + s) mod 2^128
+//! ```
+//!
+//! The key (r, s) must never authenticate two messages: from two tags under
+//! the same key r can be solved for. ChaCha20-Poly1305 therefore derives a
+//! fresh key from the keystream of every nonce.
+//!
+//! The arithmetic works on five limbs of 26 bits, so products fit into 64
+//! bits and nothing branches on secret data.
+
+use secret::Secret;
+
+use crate::CipherModeError;
+
+/// Key length in bytes, r followed by s
+pub const KEY_SIZE: usize = 32;
+
+/// Tag length in bytes
+pub const TAG_SIZE: usize = 16;
+
+const BLOCK: usize = 16;
+const MASK: u32 = 0x3ff_ffff;
+
+/// Poly1305 over a message fed in pieces
+pub struct Poly1305 {
+    /// r, clamped, in 26-bit limbs
+    r: Secret<[u32; 5]>,
+    s: Secret<[u32; 4]>,
+    /// The accumulator in 26-bit limbs, not fully reduced
+    h: [u32; 5],
+    /// Up to one block that is not processed yet
+    pending: Vec<u8>,
+}
+
+impl Poly1305 {
+    /// [`CipherModeError::InvalidKeyLength`] unless the key is 32 bytes long
+    pub fn new(key: &[u8]) -> Result<Self, CipherModeError> {
+        if key.len() != KEY_SIZE {
+            return Err(CipherModeError::InvalidKeyLength { expected: KEY_SIZE, got: key.len() });
+        }
+        // Clamping clears the top four bits of every fourth byte and the
+        // bottom two bits of the bytes after, which the masks include
+        let r = [
+            le32(&key[0..]) & 0x3ff_ffff,
+            (le32(&key[3..]) >> 2) & 0x3ff_ff03,
+            (le32(&key[6..]) >> 4) & 0x3ff_c0ff,
+            (le32(&key[9..]) >> 6) & 0x3f0_3fff,
+            (le32(&key[12..]) >> 8) & 0x00f_ffff,
+        ];
+        let s = std::array::from_fn(|i| le32(&key[16 + 4 * i..]));
+        Ok(Poly1305 { r: Secret::new(r), s: Secret::new(s), h: [0; 5], pending: Vec::with_capacity(BLOCK) })
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let take = data.len().min(BLOCK - self.pending.len());
+            self.pending.extend_from_slice(&data[..take]);
+            data = &data[take..];
+            if self.pending.len() == BLOCK {
+                let block: [u8; BLOCK] = self.pending[..].try_into().expect("the block is complete");
+                self.block(&block, 1 << 24);
+                self.pending.clear();
+            }
+        }
+    }
+
+    /// The tag, 16 bytes
+    pub fn finalize(mut self) -> [u8; TAG_SIZE] {
+        if !self.pending.is_empty() {
+            // The appended 1 goes right after the data instead of above 2^128
+            let mut block = [0; BLOCK];
+            block[..self.pending.len()].copy_from_slice(&self.pending);
+            block[self.pending.len()] = 1;
+            self.block(&block, 0);
+        }
+
+        let [mut h0, mut h1, mut h2, mut h3, mut h4] = self.h;
+        let mut c;
+        c = h1 >> 26; h1 &= MASK; h2 += c;
+        c = h2 >> 26; h2 &= MASK; h3 += c;
+        c = h3 >> 26; h3 &= MASK; h4 += c;
+        c = h4 >> 26; h4 &= MASK; h0 += c * 5;
+        c = h0 >> 26; h0 &= MASK; h1 += c;
+
+        // h - p, taken instead of h if it does not go negative
+        let mut g0 = h0 + 5; c = g0 >> 26; g0 &= MASK;
+        let mut g1 = h1 + c; c = g1 >> 26; g1 &= MASK;
+        let mut g2 = h2 + c; c = g2 >> 26; g2 &= MASK;
+        let mut g3 = h3 + c; c = g3 >> 26; g3 &= MASK;
+        let g4 = (h4 + c).wrapping_sub(1 << 26);
+        let take_g = (g4 >> 31).wrapping_sub(1);
+        h0 = (h0 & !take_g) | (g0 & take_g);
+        h1 = (h1 & !take_g) | (g1 & take_g);
+        h2 = (h2 & !take_g) | (g2 & take_g);
+        h3 = (h3 & !take_g) | (g3 & take_g);
+        h4 = (h4 & !take_g) | (g4 & take_g);
+
+        // Back to four 32-bit words, plus s
+        let words = [h0 | h1 << 26, h1 >> 6 | h2 << 20, h2 >> 12 | h3 << 14, h3 >> 18 | h4 << 8];
+        let mut tag = [0; TAG_SIZE];
+        let mut carry = 0u64;
+        for (i, chunk) in tag.chunks_exact_mut(4).enumerate() {
+            let sum = u64::from(words[i]) + u64::from(self.s[i]) + carry;
+            chunk.copy_from_slice(&(sum as u32).to_le_bytes());
+            carry = sum >> 32;
+        }
+        tag
+    }
+
+    /// Compares the tag with `tag` in constant time
+    pub fn verify(self, tag: &[u8]) -> Result<(), CipherModeError> {
+        if !secret::ct::eq(&self.finalize(), tag) {
+            return Err(CipherModeError::AuthenticationFailed);
+        }
+        Ok(())
+    }
+
+    /// h = (h + m)·r, with `high_bit` the 2^128 of a complete block
+    fn block(&mut self, block: &[u8; BLOCK], high_bit: u32) {
+        let [r0, r1, r2, r3, r4] = (*self.r).map(u64::from);
+        let [s1, s2, s3, s4] = [r1 * 5, r2 * 5, r3 * 5, r4 * 5];
+
+        let h = &mut self.h;
+        let h0 = u64::from(h[0] + (le32(&block[0..]) & MASK));
+        let h1 = u64::from(h[1] + ((le32(&block[3..]) >> 2) & MASK));
+        let h2 = u64::from(h[2] + ((le32(&block[6..]) >> 4) & MASK));
+        let h3 = u64::from(h[3] + ((le32(&block[9..]) >> 6) & MASK));
+        let h4 = u64::from(h[4] + ((le32(&block[12..]) >> 8) | high_bit));
+
+        // 2^130 ≡ 5, so limbs that overflow the fifth come back times 5
+        let d0 = h0 * r0 + h1 * s4 + h2 * s3 + h3 * s2 + h4 * s1;
+        let mut d1 = h0 * r1 + h1 * r0 + h2 * s4 + h3 * s3 + h4 * s2;
+        let mut d2 = h0 * r2 + h1 * r1 + h2 * r0 + h3 * s4 + h4 * s3;
+        let mut d3 = h0 * r3 + h1 * r2 + h2 * r1 + h3 * r0 + h4 * s4;
+        let mut d4 = h0 * r4 + h1 * r3 + h2 * r2 + h3 * r1 + h4 * r0;
+
+        let mask = u64::from(MASK);
+        d1 += d0 >> 26;
+        d2 += d1 >> 26;
+        d3 += d2 >> 26;
+        d4 += d3 >> 26;
+        let mut l0 = (d0 & mask) + (d4 >> 26) * 5;
+        let l1 = (d1 & mask) + (l0 >> 26);
+        l0 &= mask;
+        *h = [l0, l1, d2 & mask, d3 & mask, d4 & mask].map(|limb| limb as u32);
+    }
+}
+
+/// Poly1305 of a whole message
+pub fn poly1305(key: &[u8], message: &[u8]) -> Result<[u8; TAG_SIZE], CipherModeError> {
+    let mut mac = Poly1305::new(key)?;
+    mac.update(message);
+    Ok(mac.finalize())
+}
+
+/// The little-endian word at the start of `bytes`
+fn le32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes[..4].try_into().expect("4 bytes for a word"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_rfc8439() {
+        // RFC 8439, section 2.5.2
+        let key = codec::hex::decode("85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b").unwrap();
+        let tag = poly1305(&key, b"Cryptographic Forum Research Group").unwrap();
+        assert_eq!(tag.to_vec(), codec::hex::decode("a8061dc1305136c6c22b8baf0c0127a9").unwrap());
+
+        // Fed in pieces
+        let mut mac = Poly1305::new(&key).unwrap();
+        for piece in [&b"Cryptographic "[..], b"Forum Research", b" Group"] {
+            mac.update(piece);
+        }
+        assert_eq!(mac.verify(&tag), Ok(()));
+    }
+
+    #[test]
+    fn test_reduction_edge_cases() {
+        // RFC 8439, appendix A.3, test vectors 5 and 7: h ends just above p
+        let key = codec::hex::decode("0200000000000000000000000000000000000000000000000000000000000000").unwrap();
+        assert_eq!(poly1305(&key, &[0xff; 16]).unwrap().to_vec(), codec::hex::decode("03000000000000000000000000000000").unwrap());
+
+        let key = codec::hex::decode("0100000000000000000000000000000000000000000000000000000000000000").unwrap();
+        let message = codec::hex::decode("fffffffffffffffffffffffffffffffff0ffffffffffffffffffffffffffffff11000000000000000000000000000000").unwrap();
+        assert_eq!(poly1305(&key, &message).unwrap().to_vec(), codec::hex::decode("05000000000000000000000000000000").unwrap());
+    }
+
+    #[test]
+    fn test_rejects_wrong_tag_and_key() {
+        let key = [7; 32];
+        let mut tag = poly1305(&key, b"message").unwrap();
+        tag[15] ^= 1;
+        let mut mac = Poly1305::new(&key).unwrap();
+        mac.update(b"message");
+        assert_eq!(mac.verify(&tag), Err(CipherModeError::AuthenticationFailed));
+        assert!(Poly1305::new(&[0; 16]).is_err());
+    }
+}
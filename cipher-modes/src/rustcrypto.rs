@@ -0,0 +1,177 @@
+//! Adapters between [`BlockCipher`] and the block cipher traits of RustCrypto
+//!
+//! The `cipher` crate of RustCrypto describes block ciphers with
+//! `BlockEncrypt` and `BlockDecrypt`; `aes`, `des`, `blowfish` and the other
+//! cipher crates on crates.io implement them. [`FromRustCrypto`] wraps such
+//! a cipher so that the modes here accept it, [`ToRustCrypto`] goes the
+//! other way and lets RustCrypto's mode crates (`cbc`, `ctr`, …) run a
+//! cipher of this crate:
+//!
+//! ```text
+//! let cipher = FromRustCrypto::<aes::Aes128>::new(&key)?;
+//! CipherModes::cbc_encrypt(&cipher, &iv, plaintext, Padding::Pkcs7)?;
+//!
+//! let cipher = ToRustCrypto::<_, U16>::new(Aes128::new(&key)?)?;
+//! let encryptor = cbc::Encryptor::inner_iv_init(cipher, iv.as_slice().into());
+//! ```
+//!
+//! RustCrypto gives the block size as a `typenum` type instead of a
+//! constant, so [`ToRustCrypto`] takes it as a second parameter and checks
+//! it against [`BlockCipher::BLOCK_SIZE`] once, in `new`.
+
+use std::marker::PhantomData;
+use std::ops::RangeInclusive;
+
+use cipher::consts::U1;
+use cipher::generic_array::{ArrayLength, GenericArray};
+use cipher::inout::InOut;
+use cipher::typenum::Unsigned;
+use cipher::{Block, BlockBackend, BlockClosure, BlockDecrypt, BlockEncrypt, BlockSizeUser, KeySizeUser, ParBlocksSizeUser};
+
+use crate::{BlockCipher, CipherModeError, KeyInit, block_size_of};
+
+/// A RustCrypto block cipher as a [`BlockCipher`]
+#[derive(Debug, Clone)]
+pub struct FromRustCrypto<C>(pub C);
+
+impl<C: BlockEncrypt + BlockDecrypt + KeySizeUser> BlockCipher for FromRustCrypto<C> {
+    const BLOCK_SIZE: usize = C::BlockSize::USIZE;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        self.0.encrypt_block(GenericArray::from_mut_slice(block));
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        self.0.decrypt_block(GenericArray::from_mut_slice(block));
+    }
+
+    /// RustCrypto ciphers take keys of exactly `C::KeySize` bytes
+    fn valid_key_lengths() -> RangeInclusive<usize> {
+        C::KeySize::USIZE..=C::KeySize::USIZE
+    }
+}
+
+impl<C: cipher::KeyInit> KeyInit for FromRustCrypto<C> {
+    /// [`CipherModeError::InvalidKeyLength`] if the cipher rejects the key
+    fn new(key: &[u8]) -> Result<Self, CipherModeError> {
+        C::new_from_slice(key)
+            .map(FromRustCrypto)
+            .map_err(|_| CipherModeError::InvalidKeyLength { expected: C::KeySize::USIZE, got: key.len() })
+    }
+}
+
+/// A [`BlockCipher`] as a RustCrypto block cipher with blocks of `N` bytes
+#[derive(Debug, Clone)]
+pub struct ToRustCrypto<C, N> {
+    cipher: C,
+    block_size: PhantomData<N>,
+}
+
+impl<C: BlockCipher, N: ArrayLength<u8>> ToRustCrypto<C, N> {
+    /// [`CipherModeError::InvalidBlockSize`] unless `N` is the cipher's block size
+    pub fn new(cipher: C) -> Result<Self, CipherModeError> {
+        if N::USIZE != block_size_of::<C>() {
+            return Err(CipherModeError::InvalidBlockSize);
+        }
+        Ok(ToRustCrypto { cipher, block_size: PhantomData })
+    }
+
+    pub fn into_inner(self) -> C {
+        self.cipher
+    }
+}
+
+impl<C: BlockCipher, N: ArrayLength<u8>> BlockSizeUser for ToRustCrypto<C, N> {
+    type BlockSize = N;
+}
+
+impl<C: BlockCipher, N: ArrayLength<u8>> cipher::BlockCipher for ToRustCrypto<C, N> {}
+
+impl<C: BlockCipher, N: ArrayLength<u8>> BlockEncrypt for ToRustCrypto<C, N> {
+    fn encrypt_with_backend(&self, f: impl BlockClosure<BlockSize = N>) {
+        f.call(&mut Backend { cipher: &self.cipher, decrypt: false, block_size: PhantomData });
+    }
+}
+
+impl<C: BlockCipher, N: ArrayLength<u8>> BlockDecrypt for ToRustCrypto<C, N> {
+    fn decrypt_with_backend(&self, f: impl BlockClosure<BlockSize = N>) {
+        f.call(&mut Backend { cipher: &self.cipher, decrypt: true, block_size: PhantomData });
+    }
+}
+
+/// What RustCrypto's modes call per block, one block at a time
+struct Backend<'a, C, N> {
+    cipher: &'a C,
+    decrypt: bool,
+    block_size: PhantomData<N>,
+}
+
+impl<C: BlockCipher, N: ArrayLength<u8>> BlockSizeUser for Backend<'_, C, N> {
+    type BlockSize = N;
+}
+
+impl<C: BlockCipher, N: ArrayLength<u8>> ParBlocksSizeUser for Backend<'_, C, N> {
+    type ParBlocksSize = U1;
+}
+
+impl<C: BlockCipher, N: ArrayLength<u8>> BlockBackend for Backend<'_, C, N> {
+    fn proc_block(&mut self, mut block: InOut<'_, '_, Block<Self>>) {
+        let mut data = block.clone_in();
+        if self.decrypt {
+            self.cipher.decrypt_block(&mut data);
+        } else {
+            self.cipher.encrypt_block(&mut data);
+        }
+        *block.get_out() = data;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cipher::consts::{U8, U16};
+
+    use super::*;
+    use crate::{Aes128, CipherModes, DummyCipher, Padding};
+
+    const KEY: &[u8] = b"secret key 16 by";
+
+    #[test]
+    fn test_both_adapters_match_our_aes() {
+        // RustCrypto's AES in our modes gives the same ciphertext as our AES
+        let wrapped = FromRustCrypto::<aes::Aes128>::new(KEY).unwrap();
+        let plaintext = b"The quick brown fox jumps over the lazy dog";
+        let expected = CipherModes::cbc_encrypt(&Aes128::new(KEY).unwrap(), &[7; 16], plaintext, Padding::Pkcs7).unwrap();
+        let ciphertext = CipherModes::cbc_encrypt(&wrapped, &[7; 16], plaintext, Padding::Pkcs7).unwrap();
+        assert_eq!(ciphertext, expected);
+        assert_eq!(CipherModes::cbc_decrypt(&wrapped, &[7; 16], &ciphertext, Padding::Pkcs7).unwrap(), plaintext);
+
+        // Our AES behind RustCrypto's traits encrypts blocks like theirs
+        let cipher = ToRustCrypto::<_, U16>::new(Aes128::new(KEY).unwrap()).unwrap();
+        let mut block = GenericArray::clone_from_slice(&[0x42; 16]);
+        let mut reference = block;
+        cipher.encrypt_block(&mut block);
+        wrapped.0.encrypt_block(&mut reference);
+        assert_eq!(block, reference);
+        cipher.decrypt_block(&mut block);
+        assert_eq!(block.as_slice(), [0x42; 16]);
+    }
+
+    #[test]
+    fn test_valid_key_lengths() {
+        assert_eq!(FromRustCrypto::<aes::Aes128>::valid_key_lengths(), 16..=16);
+        assert_eq!(FromRustCrypto::<aes::Aes256>::valid_key_lengths(), 32..=32);
+        assert_eq!(
+            FromRustCrypto::<aes::Aes128>::check_key_length(&[0; 24]),
+            Err(CipherModeError::InvalidKeyLength { expected: 16, got: 24 })
+        );
+    }
+
+    #[test]
+    fn test_rejects_wrong_block_size() {
+        assert_eq!(
+            ToRustCrypto::<_, U8>::new(DummyCipher::<16>::new(KEY).unwrap()).map(|_| ()),
+            Err(CipherModeError::InvalidBlockSize)
+        );
+        assert!(ToRustCrypto::<_, U8>::new(DummyCipher::<8>::new(KEY).unwrap()).is_ok());
+    }
+}
@@ -0,0 +1,161 @@
+//! Encrypted messages as a value with named parts
+//!
+//! [`CipherModes::seal`] and the AEAD functions return one byte string,
+//! IV ‖ ciphertext or ciphertext ‖ tag. [`SealedMessage`] takes it apart
+//! and records how it was made, so that the receiver knows what to call.
+//! With the `serde` feature it can be written as JSON, CBOR or any other
+//! serde format; byte strings become hex in text formats:
+//!
+//! ```text
+//! {"scheme":"ChaCha20Poly1305","nonce":"070000004041424344454647",
+//!  "ciphertext":"d31a8d34…","tag":"1ae10b594f09e26a7e902ecbd0600691"}
+//! ```
+//!
+//! The key is not part of it, and neither is the associated data.
+
+use crate::modes::SealMode;
+use crate::{BlockCipher, CipherModeError, block_size_of};
+
+/// Tag length of the AEADs, for EAX with a 16-byte block cipher
+const TAG_SIZE: usize = 16;
+
+/// The function a [`SealedMessage`] comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Scheme {
+    /// [`CipherModes::seal`](crate::CipherModes::seal), without a tag
+    Seal(SealMode),
+    /// [`eax_encrypt`](crate::eax_encrypt) with a 16-byte block cipher
+    Eax,
+    /// [`chacha20_poly1305_encrypt`](crate::chacha20_poly1305_encrypt)
+    ChaCha20Poly1305,
+    /// [`ascon128_encrypt`](crate::ascon128_encrypt)
+    Ascon128,
+}
+
+impl Scheme {
+    /// Length of the tag in bytes, 0 for [`Scheme::Seal`]
+    pub fn tag_size(self) -> usize {
+        match self {
+            Scheme::Seal(_) => 0,
+            Scheme::Eax | Scheme::ChaCha20Poly1305 | Scheme::Ascon128 => TAG_SIZE,
+        }
+    }
+}
+
+/// IV or nonce, ciphertext and tag of one message
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SealedMessage {
+    pub scheme: Scheme,
+    /// The IV for [`Scheme::Seal`], the nonce otherwise
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex", alias = "iv"))]
+    pub nonce: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub ciphertext: Vec<u8>,
+    /// Empty for [`Scheme::Seal`]
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex", default))]
+    pub tag: Vec<u8>,
+}
+
+impl SealedMessage {
+    /// Splits the output of an AEAD function, ciphertext ‖ tag
+    ///
+    /// For [`Scheme::Seal`] `output` is the ciphertext after the IV.
+    /// [`CipherModeError::AuthenticationFailed`] if it is shorter than the
+    /// tag, as the decryption would report.
+    pub fn new(scheme: Scheme, nonce: &[u8], output: &[u8]) -> Result<Self, CipherModeError> {
+        let Some(split) = output.len().checked_sub(scheme.tag_size()) else {
+            return Err(CipherModeError::AuthenticationFailed);
+        };
+        let (ciphertext, tag) = output.split_at(split);
+        Ok(SealedMessage { scheme, nonce: nonce.to_vec(), ciphertext: ciphertext.to_vec(), tag: tag.to_vec() })
+    }
+
+    /// Splits the output of [`CipherModes::seal`](crate::CipherModes::seal)
+    /// with cipher `C` into IV and ciphertext
+    pub fn from_seal<C: BlockCipher>(mode: SealMode, sealed: &[u8]) -> Result<Self, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        if sealed.len() < block_size {
+            return Err(CipherModeError::InvalidInputLength { block_size, actual: sealed.len() });
+        }
+        let (iv, ciphertext) = sealed.split_at(block_size);
+        Self::new(Scheme::Seal(mode), iv, ciphertext)
+    }
+
+    /// What the decryption takes: IV ‖ ciphertext for
+    /// [`CipherModes::open`](crate::CipherModes::open), ciphertext ‖ tag for
+    /// the AEADs, which get the nonce separately
+    pub fn payload(&self) -> Vec<u8> {
+        match self.scheme {
+            Scheme::Seal(_) => [&self.nonce[..], &self.ciphertext].concat(),
+            _ => [&self.ciphertext[..], &self.tag].concat(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use csprng::{Algorithm, Drbg};
+
+    use super::*;
+    use crate::{Aes128, CipherModes, KeyInit, Padding, chacha20_poly1305_decrypt, chacha20_poly1305_encrypt};
+
+    #[test]
+    fn test_split_and_join() {
+        let cipher = Aes128::new(&[0x42; 16]).unwrap();
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"sealed message test");
+        let mode = SealMode::Cbc { padding: Padding::Pkcs7 };
+        let sealed = CipherModes::seal(&cipher, mode, b"attack at dawn", &mut rng).unwrap();
+        let message = SealedMessage::from_seal::<Aes128>(mode, &sealed).unwrap();
+        assert_eq!(message.nonce, sealed[..16]);
+        assert!(message.tag.is_empty());
+        assert_eq!(CipherModes::open(&cipher, mode, &message.payload()).unwrap(), b"attack at dawn");
+
+        let output = chacha20_poly1305_encrypt(&[7; 32], &[1; 12], b"", b"attack at dawn").unwrap();
+        let message = SealedMessage::new(Scheme::ChaCha20Poly1305, &[1; 12], &output).unwrap();
+        assert_eq!(message.ciphertext.len(), 14);
+        assert_eq!(chacha20_poly1305_decrypt(&[7; 32], &message.nonce, b"", &message.payload()).unwrap(), b"attack at dawn");
+
+        assert_eq!(SealedMessage::new(Scheme::Eax, &[], &[0; 15]), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(
+            SealedMessage::from_seal::<Aes128>(mode, &[0; 8]),
+            Err(CipherModeError::InvalidInputLength { block_size: 16, actual: 8 })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let message = SealedMessage {
+            scheme: Scheme::Seal(SealMode::Cbc { padding: Padding::Iso7816 }),
+            nonce: vec![0x00, 0x01, 0xfe, 0xff],
+            ciphertext: vec![0x42; 3],
+            tag: vec![],
+        };
+        let json = serde_json::to_string(&message).unwrap();
+        assert_eq!(
+            json,
+            r#"{"scheme":{"Seal":{"Cbc":{"padding":"Iso7816"}}},"nonce":"0001feff","ciphertext":"424242","tag":""}"#
+        );
+        assert_eq!(serde_json::from_str::<SealedMessage>(&json).unwrap(), message);
+
+        // "iv" for "nonce", a missing tag and numbers instead of hex
+        let json = r#"{"scheme":"Ascon128","iv":[1,2],"ciphertext":"0A0b"}"#;
+        let parsed: SealedMessage = serde_json::from_str(json).unwrap();
+        assert_eq!((parsed.scheme, parsed.nonce, parsed.ciphertext, parsed.tag), (Scheme::Ascon128, vec![1, 2], vec![10, 11], vec![]));
+        assert!(serde_json::from_str::<SealedMessage>(r#"{"scheme":"Eax","nonce":"0g","ciphertext":""}"#).is_err());
+
+        // Byte strings in CBOR
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&message, &mut cbor).unwrap();
+        assert!(cbor.windows(5).any(|window| window == [0x44, 0x00, 0x01, 0xfe, 0xff]));
+        assert_eq!(ciborium::from_reader::<SealedMessage, _>(&cbor[..]).unwrap(), message);
+
+        // The parameters of CipherModes::encrypt
+        let mode = crate::Mode::CtrWithLayout { layout: crate::CtrLayout::new(12, 4), nonce: vec![0xab; 12] };
+        let json = serde_json::to_string(&mode).unwrap();
+        assert!(json.contains(r#""nonce":"abababababababababababab""#));
+        assert_eq!(serde_json::from_str::<crate::Mode>(&json).unwrap(), mode);
+    }
+}
@@ -0,0 +1,145 @@
+//! SIV, deterministic authenticated encryption (RFC 5297)
+//!
+//! The IV is not chosen by the caller but computed from everything that is
+//! authenticated: S2V, a CMAC over a list of strings, turns the associated
+//! data headers and the plaintext into a synthetic IV V, which then serves
+//! as the CTR counter:
+//!
+//! ```text
+//! D = CMAC(0)
+//! D = 2·D ⊕ CMAC(H_i)                      for every header H_1 … H_m
+//! V = CMAC(P xorend D)                     for |P| ≥ 16
+//! V = CMAC(2·D ⊕ P‖10…0)                   otherwise
+//! C = CTR(V with bits 31 and 63 cleared, P)
+//! ```
+//!
+//! The output is V followed by C. A nonce is passed as one of the headers,
+//! by convention the last. If it repeats, or is left out, SIV only reveals
+//! whether the same message was encrypted twice under the same headers;
+//! EAX or GCM would lose confidentiality instead.
+//!
+//! RFC 5297 uses two keys: K1 for S2V and K2 for CTR, split from a key of
+//! twice the cipher's key length. SIV needs a cipher with 16-byte blocks.
+
+use crate::mac::{Cmac, cmac, double};
+use crate::modes::CipherModes;
+use crate::utils::{xor_blocks, xor_blocks_in_place};
+use crate::{BlockCipher, CipherModeError, block_size_of};
+
+/// Block size SIV is defined for
+const BLOCK: usize = 16;
+
+/// Encrypts and authenticates `plaintext` and authenticates the `headers`
+///
+/// `mac_cipher` is keyed with K1, `ctr_cipher` with K2. Returns V ‖ C.
+pub fn siv_encrypt<C: BlockCipher>(mac_cipher: &C, ctr_cipher: &C, headers: &[&[u8]], plaintext: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    check_block_size::<C>()?;
+    let v = s2v(mac_cipher, headers, plaintext)?;
+    let ciphertext = CipherModes::ctr_encrypt(ctr_cipher, &counter(&v), plaintext)?;
+    Ok([v, ciphertext].concat())
+}
+
+/// Decrypts and checks V; [`CipherModeError::AuthenticationFailed`] if the
+/// ciphertext or a header was modified
+///
+/// The plaintext has to be decrypted before V can be recomputed, but it is
+/// only returned if V matches.
+pub fn siv_decrypt<C: BlockCipher>(mac_cipher: &C, ctr_cipher: &C, headers: &[&[u8]], ciphertext: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    check_block_size::<C>()?;
+    if ciphertext.len() < BLOCK {
+        return Err(CipherModeError::AuthenticationFailed);
+    }
+    let (v, ciphertext) = ciphertext.split_at(BLOCK);
+    let plaintext = CipherModes::ctr_decrypt(ctr_cipher, &counter(v), ciphertext)?;
+    if !secret::ct::eq(&s2v(mac_cipher, headers, &plaintext)?, v) {
+        return Err(CipherModeError::AuthenticationFailed);
+    }
+    Ok(plaintext)
+}
+
+/// S2V over the headers and the plaintext as last string
+fn s2v<C: BlockCipher>(cipher: &C, headers: &[&[u8]], plaintext: &[u8]) -> Result<Vec<u8>, CipherModeError> {
+    let mut d = cmac(cipher, &[0; BLOCK])?;
+    for header in headers {
+        d = double(&d)?;
+        xor_blocks_in_place(&mut d, &cmac(cipher, header)?);
+    }
+
+    let mut mac = Cmac::new(cipher)?;
+    if plaintext.len() >= BLOCK {
+        // xorend: D is XORed into the last 16 bytes
+        let (head, tail) = plaintext.split_at(plaintext.len() - BLOCK);
+        mac.update(head);
+        mac.update(&xor_blocks(tail, &d));
+    } else {
+        let mut last = plaintext.to_vec();
+        last.push(0x80);
+        last.resize(BLOCK, 0);
+        xor_blocks_in_place(&mut last, &double(&d)?);
+        mac.update(&last);
+    }
+    Ok(mac.finalize())
+}
+
+/// V with bits 31 and 63 cleared, so that CTR implementations with a 32- or
+/// 64-bit counter produce the same keystream
+fn counter(v: &[u8]) -> Vec<u8> {
+    let mut counter = v.to_vec();
+    counter[8] &= 0x7f;
+    counter[12] &= 0x7f;
+    counter
+}
+
+fn check_block_size<C: BlockCipher>() -> Result<(), CipherModeError> {
+    if block_size_of::<C>() != BLOCK {
+        return Err(CipherModeError::InvalidBlockSize);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DummyCipher, KeyInit};
+
+    fn ciphers() -> (DummyCipher, DummyCipher) {
+        (DummyCipher::new(b"mac key 16 bytes").unwrap(), DummyCipher::new(b"ctr key 16 bytes").unwrap())
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let (mac, ctr) = ciphers();
+        for headers in [&[][..], &[&b"header"[..]], &[b"header", b"", b"nonce"]] {
+            for length in [0, 1, 15, 16, 17, 40] {
+                let plaintext: Vec<u8> = (0..length as u8).collect();
+                let ciphertext = siv_encrypt(&mac, &ctr, headers, &plaintext).unwrap();
+                assert_eq!(ciphertext.len(), length + 16);
+                assert_eq!(siv_decrypt(&mac, &ctr, headers, &ciphertext).unwrap(), plaintext);
+            }
+        }
+    }
+
+    #[test]
+    fn test_deterministic() {
+        // The same input gives the same output, a different header a different one
+        let (mac, ctr) = ciphers();
+        let first = siv_encrypt(&mac, &ctr, &[b"nonce"], b"attack at dawn").unwrap();
+        assert_eq!(siv_encrypt(&mac, &ctr, &[b"nonce"], b"attack at dawn").unwrap(), first);
+        assert_ne!(siv_encrypt(&mac, &ctr, &[b"Nonce"], b"attack at dawn").unwrap(), first);
+    }
+
+    #[test]
+    fn test_rejects_tampering() {
+        let (mac, ctr) = ciphers();
+        let headers: &[&[u8]] = &[b"header", b"nonce"];
+        let ciphertext = siv_encrypt(&mac, &ctr, headers, b"attack at dawn").unwrap();
+        // Changes to V or C go unnoticed with the linear XOR cipher; the AES tests cover them
+        assert_eq!(siv_decrypt(&mac, &ctr, &[b"header", b"Nonce"], &ciphertext), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(siv_decrypt(&mac, &ctr, &[b"nonce", b"header"], &ciphertext), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(siv_decrypt(&mac, &ctr, &[b"header"], &ciphertext), Err(CipherModeError::AuthenticationFailed));
+        assert_eq!(siv_decrypt(&mac, &ctr, headers, &ciphertext[..15]), Err(CipherModeError::AuthenticationFailed));
+
+        let cipher = DummyCipher::<8>::new(b"key").unwrap();
+        assert_eq!(siv_encrypt(&cipher, &cipher, &[], b"data"), Err(CipherModeError::InvalidBlockSize));
+    }
+}
@@ -0,0 +1,107 @@
+//! CTR as a [`StreamCipher`]
+//!
+//! The keystream of CTR is E(ctr), E(ctr + 1), …, so block i can be
+//! computed without the ones before it and [`CtrStream`] seeks in constant
+//! time. OFB has no such shortcut, every keystream block is the encryption
+//! of the previous one; it stays with [`Encryptor`](crate::Encryptor).
+
+use secret::Secret;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::utils::{add_to_counter, xor_blocks_in_place};
+use crate::{BlockCipher, CipherModeError, StreamCipher, block_size_of};
+
+/// CTR over a block cipher, the same keystream as [`CipherModes::ctr_encrypt`](crate::CipherModes::ctr_encrypt)
+pub struct CtrStream<C: BlockCipher> {
+    cipher: C,
+    /// Counter block of block 0
+    initial: Vec<u8>,
+    position: u64,
+    /// The keystream block that contains `position`, if computed
+    keystream: Secret<Vec<u8>>,
+    block: Option<u64>,
+}
+
+impl<C: BlockCipher> CtrStream<C> {
+    /// [`CipherModeError::InvalidIvLength`] unless `counter` is one block long
+    pub fn new(cipher: C, counter: &[u8]) -> Result<Self, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        if counter.len() != block_size {
+            return Err(CipherModeError::InvalidIvLength { expected: block_size, actual: counter.len() });
+        }
+        Ok(CtrStream { cipher, initial: counter.to_vec(), position: 0, keystream: Secret::new(vec![0; block_size]), block: None })
+    }
+}
+
+/// Wipes the cipher and the buffered keystream block
+#[cfg(feature = "zeroize")]
+impl<C: BlockCipher + Zeroize> Zeroize for CtrStream<C> {
+    fn zeroize(&mut self) {
+        self.cipher.zeroize();
+        self.keystream.as_mut_slice().zeroize();
+        self.block = None;
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<C: BlockCipher + ZeroizeOnDrop> ZeroizeOnDrop for CtrStream<C> {}
+
+impl<C: BlockCipher> StreamCipher for CtrStream<C> {
+    fn apply_keystream(&mut self, mut data: &mut [u8]) {
+        let block_size = block_size_of::<C>() as u64;
+        while !data.is_empty() {
+            let block = self.position / block_size;
+            if self.block != Some(block) {
+                self.keystream.copy_from_slice(&self.initial);
+                add_to_counter(&mut self.keystream, block);
+                self.cipher.encrypt_block(&mut self.keystream);
+                self.block = Some(block);
+            }
+            let offset = (self.position % block_size) as usize;
+            let take = data.len().min(self.keystream.len() - offset);
+            let (now, rest) = data.split_at_mut(take);
+            xor_blocks_in_place(now, &self.keystream[offset..]);
+            self.position += take as u64;
+            data = rest;
+        }
+    }
+
+    fn seek(&mut self, position: u64) {
+        self.position = position;
+    }
+
+    fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CipherModes, DummyCipher, KeyInit};
+
+    #[test]
+    fn test_matches_ctr_encrypt() {
+        let cipher = DummyCipher::<16>::new(b"secret key 16 by").unwrap();
+        let counter = [0xff; 16];
+        let data: Vec<u8> = (0..100).collect();
+        let expected = CipherModes::ctr_encrypt(&cipher, &counter, &data).unwrap();
+
+        let mut stream = CtrStream::new(&cipher, &counter).unwrap();
+        let mut output = data.clone();
+        let (first, rest) = output.split_at_mut(7);
+        stream.apply_keystream(first);
+        stream.apply_keystream(rest);
+        assert_eq!(output, expected);
+
+        // Decrypt only the end, starting in the middle of a block
+        stream.seek(37);
+        let mut tail = expected[37..].to_vec();
+        stream.apply_keystream(&mut tail);
+        assert_eq!(tail, data[37..]);
+        assert_eq!(stream.position(), 100);
+
+        assert!(CtrStream::new(&cipher, &counter[..8]).is_err());
+    }
+}
@@ -0,0 +1,392 @@
+//! Incremental encryption and decryption for data that does not fit in memory
+//!
+//! [`Encryptor`] and [`Decryptor`] keep the chaining value, counter or
+//! keystream between calls, so a file can be fed in chunks of any size and
+//! the output is the same as from the one-shot functions of
+//! [`CipherModes`](crate::CipherModes):
+//!
+//! ```text
+//! let mut encryptor = Encryptor::cbc(Aes128::new(&key)?, &iv, Padding::Pkcs7)?;
+//! for chunk in input { output.write_all(&encryptor.update(&chunk))?; }
+//! output.write_all(&encryptor.finalize()?)?;
+//! ```
+//!
+//! ECB and CBC hold back an incomplete block until more data arrives. The
+//! decryptor also holds back the last complete block, because only
+//! `finalize` knows that it carries the padding.
+
+use secret::Secret;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::padding::Padding;
+use crate::utils::{increment_counter, xor_blocks, xor_blocks_in_place};
+use crate::{BlockCipher, CipherModeError, block_size_of};
+
+/// Chaining state of the mode
+enum Mode {
+    Ecb,
+    /// The previous ciphertext block, the IV at the start
+    Cbc { previous: Secret<Vec<u8>> },
+    /// The last keystream block and how much of it is used up
+    Ofb { keystream: Secret<Vec<u8>>, used: usize },
+    /// The next counter block and the unused end of the current keystream block
+    Ctr { counter: Secret<Vec<u8>>, keystream: Secret<Vec<u8>>, used: usize },
+}
+
+/// What [`Encryptor`] and [`Decryptor`] share
+struct Core<C: BlockCipher> {
+    cipher: C,
+    mode: Mode,
+    padding: Padding,
+    /// Input not processed yet, less than a block for ECB and CBC (plus the
+    /// held-back block when decrypting)
+    pending: Secret<Vec<u8>>,
+    received: usize,
+}
+
+impl<C: BlockCipher> Core<C> {
+    fn new(cipher: C, mode: Mode, padding: Padding) -> Self {
+        Core { cipher, mode, padding, pending: Secret::default(), received: 0 }
+    }
+
+    fn ecb(cipher: C, padding: Padding) -> Result<Self, CipherModeError> {
+        padding.check_block_size(block_size_of::<C>())?;
+        Ok(Self::new(cipher, Mode::Ecb, padding))
+    }
+
+    fn cbc(cipher: C, iv: &[u8], padding: Padding) -> Result<Self, CipherModeError> {
+        padding.check_block_size(block_size_of::<C>())?;
+        check_iv(iv, block_size_of::<C>())?;
+        Ok(Self::new(cipher, Mode::Cbc { previous: Secret::new(iv.to_vec()) }, padding))
+    }
+
+    fn ofb(cipher: C, iv: &[u8]) -> Result<Self, CipherModeError> {
+        check_iv(iv, block_size_of::<C>())?;
+        let used = iv.len();
+        Ok(Self::new(cipher, Mode::Ofb { keystream: Secret::new(iv.to_vec()), used }, Padding::None))
+    }
+
+    fn ctr(cipher: C, counter: &[u8]) -> Result<Self, CipherModeError> {
+        check_iv(counter, block_size_of::<C>())?;
+        Ok(Self::new(cipher, Mode::Ctr { counter: Secret::new(counter.to_vec()), keystream: Secret::default(), used: 0 }, Padding::None))
+    }
+
+    /// XORs `data` with the next keystream bytes; `None` for ECB and CBC
+    fn apply_keystream(&mut self, mut data: &[u8]) -> Option<Vec<u8>> {
+        if matches!(self.mode, Mode::Ecb | Mode::Cbc { .. }) {
+            return None;
+        }
+        let mut output = Vec::with_capacity(data.len());
+        while !data.is_empty() {
+            let (keystream, used) = match &mut self.mode {
+                Mode::Ofb { keystream, used } => {
+                    if *used == keystream.len() {
+                        self.cipher.encrypt_block(keystream);
+                        *used = 0;
+                    }
+                    (keystream, used)
+                }
+                Mode::Ctr { counter, keystream, used } => {
+                    if *used == keystream.len() {
+                        keystream.clone_from(counter);
+                        self.cipher.encrypt_block(keystream);
+                        increment_counter(counter);
+                        *used = 0;
+                    }
+                    (keystream, used)
+                }
+                Mode::Ecb | Mode::Cbc { .. } => unreachable!("block modes return above"),
+            };
+            let take = data.len().min(keystream.len() - *used);
+            output.extend(xor_blocks(&data[..take], &keystream[*used..]));
+            *used += take;
+            data = &data[take..];
+        }
+        Some(output)
+    }
+
+    fn encrypt_block(&mut self, block: &[u8]) -> Vec<u8> {
+        match &mut self.mode {
+            Mode::Cbc { previous } => {
+                xor_blocks_in_place(previous, block);
+                self.cipher.encrypt_block(previous);
+                previous.to_vec()
+            }
+            _ => {
+                let mut block = block.to_vec();
+                self.cipher.encrypt_block(&mut block);
+                block
+            }
+        }
+    }
+
+    fn decrypt_block(&mut self, block: &[u8]) -> Vec<u8> {
+        match &mut self.mode {
+            Mode::Cbc { previous } => {
+                let mut plaintext = block.to_vec();
+                self.cipher.decrypt_block(&mut plaintext);
+                xor_blocks_in_place(&mut plaintext, previous);
+                previous.copy_from_slice(block);
+                plaintext
+            }
+            _ => {
+                let mut block = block.to_vec();
+                self.cipher.decrypt_block(&mut block);
+                block
+            }
+        }
+    }
+
+    /// Processes all complete blocks of `pending` except the last `keep` ones
+    fn process_blocks(&mut self, keep: usize, decrypt: bool) -> Vec<u8> {
+        let block_size = block_size_of::<C>();
+        let blocks = (self.pending.len() / block_size).saturating_sub(keep);
+        let rest = self.pending.split_off(blocks * block_size);
+        let input = Secret::new(std::mem::replace(&mut *self.pending, rest));
+        input.chunks(block_size)
+            .flat_map(|block| if decrypt { self.decrypt_block(block) } else { self.encrypt_block(block) })
+            .collect()
+    }
+}
+
+/// Wipes the cipher, the chaining state and the data held back
+#[cfg(feature = "zeroize")]
+impl<C: BlockCipher + Zeroize> Zeroize for Core<C> {
+    fn zeroize(&mut self) {
+        self.cipher.zeroize();
+        match &mut self.mode {
+            Mode::Ecb => {}
+            Mode::Cbc { previous } => previous.as_mut_slice().zeroize(),
+            Mode::Ofb { keystream, .. } => keystream.as_mut_slice().zeroize(),
+            Mode::Ctr { counter, keystream, .. } => {
+                counter.as_mut_slice().zeroize();
+                keystream.as_mut_slice().zeroize();
+            }
+        }
+        self.pending.zeroize();
+    }
+}
+
+/// Encrypts a message fed in pieces
+pub struct Encryptor<C: BlockCipher>(Core<C>);
+
+impl<C: BlockCipher> Encryptor<C> {
+    /// ECB with the given padding
+    pub fn ecb(cipher: C, padding: Padding) -> Result<Self, CipherModeError> {
+        Core::ecb(cipher, padding).map(Encryptor)
+    }
+
+    /// CBC with the given padding; the IV must be one block long
+    pub fn cbc(cipher: C, iv: &[u8], padding: Padding) -> Result<Self, CipherModeError> {
+        Core::cbc(cipher, iv, padding).map(Encryptor)
+    }
+
+    /// OFB; the IV must be one block long
+    pub fn ofb(cipher: C, iv: &[u8]) -> Result<Self, CipherModeError> {
+        Core::ofb(cipher, iv).map(Encryptor)
+    }
+
+    /// CTR; the whole block is the counter, as in [`CipherModes::ctr_encrypt`](crate::CipherModes::ctr_encrypt)
+    pub fn ctr(cipher: C, counter: &[u8]) -> Result<Self, CipherModeError> {
+        Core::ctr(cipher, counter).map(Encryptor)
+    }
+
+    /// Encrypts the next piece; ECB and CBC return whole blocks only
+    pub fn update(&mut self, data: &[u8]) -> Vec<u8> {
+        self.0.received += data.len();
+        if let Some(output) = self.0.apply_keystream(data) {
+            return output;
+        }
+        self.0.pending.extend_from_slice(data);
+        self.0.process_blocks(0, false)
+    }
+
+    /// Pads and encrypts what is left
+    ///
+    /// Without padding the message must have filled whole blocks, otherwise
+    /// [`CipherModeError::InvalidInputLength`].
+    pub fn finalize(mut self) -> Result<Vec<u8>, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        let padded = self.0.padding.pad(&self.0.pending, block_size)
+            .map_err(|_| CipherModeError::InvalidInputLength { block_size, actual: self.0.received })?;
+        self.0.pending = Secret::new(padded);
+        Ok(self.0.process_blocks(0, false))
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<C: BlockCipher + Zeroize> Zeroize for Encryptor<C> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<C: BlockCipher + ZeroizeOnDrop> ZeroizeOnDrop for Encryptor<C> {}
+
+/// Decrypts a message fed in pieces
+pub struct Decryptor<C: BlockCipher>(Core<C>);
+
+impl<C: BlockCipher> Decryptor<C> {
+    /// ECB with the given padding
+    pub fn ecb(cipher: C, padding: Padding) -> Result<Self, CipherModeError> {
+        Core::ecb(cipher, padding).map(Decryptor)
+    }
+
+    /// CBC with the given padding; the IV must be one block long
+    pub fn cbc(cipher: C, iv: &[u8], padding: Padding) -> Result<Self, CipherModeError> {
+        Core::cbc(cipher, iv, padding).map(Decryptor)
+    }
+
+    /// OFB; the IV must be one block long
+    pub fn ofb(cipher: C, iv: &[u8]) -> Result<Self, CipherModeError> {
+        Core::ofb(cipher, iv).map(Decryptor)
+    }
+
+    /// CTR; the whole block is the counter
+    pub fn ctr(cipher: C, counter: &[u8]) -> Result<Self, CipherModeError> {
+        Core::ctr(cipher, counter).map(Decryptor)
+    }
+
+    /// Decrypts the next piece; ECB and CBC hold back the last complete block
+    pub fn update(&mut self, data: &[u8]) -> Vec<u8> {
+        self.0.received += data.len();
+        if let Some(output) = self.0.apply_keystream(data) {
+            return output;
+        }
+        self.0.pending.extend_from_slice(data);
+        // A whole pending block is only released once more data follows it
+        let keep = usize::from(self.0.pending.len().is_multiple_of(block_size_of::<C>()));
+        self.0.process_blocks(keep, true)
+    }
+
+    /// Decrypts the held-back block and removes the padding
+    ///
+    /// [`CipherModeError::InvalidInputLength`] if the ciphertext was not a
+    /// whole number of blocks, [`CipherModeError::InvalidPadding`] if the
+    /// padding is malformed. The plaintext returned by earlier calls to
+    /// `update` is only trustworthy once this succeeded, and even then only
+    /// if the ciphertext was authenticated.
+    pub fn finalize(mut self) -> Result<Vec<u8>, CipherModeError> {
+        let block_size = block_size_of::<C>();
+        if !self.0.pending.len().is_multiple_of(block_size) {
+            return Err(CipherModeError::InvalidInputLength { block_size, actual: self.0.received });
+        }
+        let last = self.0.process_blocks(0, true);
+        self.0.padding.unpad(&last, block_size)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<C: BlockCipher + Zeroize> Zeroize for Decryptor<C> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<C: BlockCipher + ZeroizeOnDrop> ZeroizeOnDrop for Decryptor<C> {}
+
+fn check_iv(iv: &[u8], expected: usize) -> Result<(), CipherModeError> {
+    if iv.len() != expected {
+        return Err(CipherModeError::InvalidIvLength { expected, actual: iv.len() });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CipherModes, DummyCipher, KeyInit};
+
+    const KEY: &[u8] = b"secret key 16 by";
+    const IV: &[u8] = b"initial vector!!";
+
+    fn encrypt_in_pieces(mut encryptor: Encryptor<&DummyCipher>, data: &[u8], piece: usize) -> Vec<u8> {
+        let mut output: Vec<u8> = data.chunks(piece).flat_map(|chunk| encryptor.update(chunk)).collect();
+        output.extend(encryptor.finalize().unwrap());
+        output
+    }
+
+    fn decrypt_in_pieces(mut decryptor: Decryptor<&DummyCipher>, data: &[u8], piece: usize) -> Result<Vec<u8>, CipherModeError> {
+        let mut output: Vec<u8> = data.chunks(piece).flat_map(|chunk| decryptor.update(chunk)).collect();
+        output.extend(decryptor.finalize()?);
+        Ok(output)
+    }
+
+    #[test]
+    fn test_matches_one_shot() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let plaintext: Vec<u8> = (0..100).collect();
+        for length in [0, 1, 16, 17, 32, 100] {
+            let plaintext = &plaintext[..length];
+            let ecb = CipherModes::ecb_encrypt(&cipher, plaintext, Padding::Pkcs7).unwrap();
+            let cbc = CipherModes::cbc_encrypt(&cipher, IV, plaintext, Padding::Iso7816).unwrap();
+            let ofb = CipherModes::ofb_encrypt(&cipher, IV, plaintext).unwrap();
+            let ctr = CipherModes::ctr_encrypt(&cipher, IV, plaintext).unwrap();
+            for piece in [1, 7, 16, 33] {
+                let context = format!("length {}, pieces of {}", length, piece);
+                assert_eq!(encrypt_in_pieces(Encryptor::ecb(&cipher, Padding::Pkcs7).unwrap(), plaintext, piece), ecb, "{}", context);
+                assert_eq!(encrypt_in_pieces(Encryptor::cbc(&cipher, IV, Padding::Iso7816).unwrap(), plaintext, piece), cbc, "{}", context);
+                assert_eq!(encrypt_in_pieces(Encryptor::ofb(&cipher, IV).unwrap(), plaintext, piece), ofb, "{}", context);
+                assert_eq!(encrypt_in_pieces(Encryptor::ctr(&cipher, IV).unwrap(), plaintext, piece), ctr, "{}", context);
+
+                assert_eq!(decrypt_in_pieces(Decryptor::ecb(&cipher, Padding::Pkcs7).unwrap(), &ecb, piece).unwrap(), plaintext, "{}", context);
+                assert_eq!(decrypt_in_pieces(Decryptor::cbc(&cipher, IV, Padding::Iso7816).unwrap(), &cbc, piece).unwrap(), plaintext, "{}", context);
+                assert_eq!(decrypt_in_pieces(Decryptor::ofb(&cipher, IV).unwrap(), &ofb, piece).unwrap(), plaintext, "{}", context);
+                assert_eq!(decrypt_in_pieces(Decryptor::ctr(&cipher, IV).unwrap(), &ctr, piece).unwrap(), plaintext, "{}", context);
+            }
+        }
+    }
+
+    #[test]
+    fn test_update_output() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let mut encryptor = Encryptor::cbc(&cipher, IV, Padding::Pkcs7).unwrap();
+        assert!(encryptor.update(&[0; 15]).is_empty());
+        assert_eq!(encryptor.update(&[0; 2]).len(), 16);
+        assert_eq!(encryptor.finalize().unwrap().len(), 16);
+
+        // The decryptor keeps the last whole block for finalize
+        let mut decryptor = Decryptor::cbc(&cipher, IV, Padding::Pkcs7).unwrap();
+        assert!(decryptor.update(&[0; 16]).is_empty());
+        assert_eq!(decryptor.update(&[0; 1]).len(), 16);
+    }
+
+    #[test]
+    fn test_errors() {
+        let cipher = DummyCipher::<16>::new(KEY).unwrap();
+        let mut encryptor = Encryptor::cbc(&cipher, IV, Padding::None).unwrap();
+        encryptor.update(&[0; 20]);
+        assert_eq!(encryptor.finalize(), Err(CipherModeError::InvalidInputLength { block_size: 16, actual: 20 }));
+
+        let ciphertext = CipherModes::cbc_encrypt(&cipher, IV, b"attack at dawn", Padding::Pkcs7).unwrap();
+        let decryptor = || Decryptor::cbc(&cipher, IV, Padding::Pkcs7).unwrap();
+        assert_eq!(decrypt_in_pieces(decryptor(), &ciphertext[..15], 4), Err(CipherModeError::InvalidInputLength { block_size: 16, actual: 15 }));
+        assert_eq!(decrypt_in_pieces(decryptor(), &[], 4), Err(CipherModeError::InvalidPadding));
+        let mut tampered = ciphertext.clone();
+        tampered[15] ^= 1;
+        assert_eq!(decrypt_in_pieces(decryptor(), &tampered, 4), Err(CipherModeError::InvalidPadding));
+
+        assert!(Encryptor::cbc(&cipher, &IV[..8], Padding::Pkcs7).is_err());
+        assert!(Decryptor::ecb(DummyCipher::<256>::new(KEY).unwrap(), Padding::Pkcs7).is_err());
+        assert!(Encryptor::ctr(&cipher, &IV[..8]).is_err());
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize() {
+        // Key, counter and keystream are all zeros afterwards, so with the
+        // XOR cipher the data comes out unchanged
+        let mut encryptor = Encryptor::ctr(DummyCipher::<16>::new(KEY).unwrap(), IV).unwrap();
+        assert_ne!(encryptor.update(b"first part"), b"first part");
+        encryptor.zeroize();
+        assert_eq!(encryptor.update(b"second part, two blocks long"), b"second part, two blocks long");
+
+        let mut decryptor = Decryptor::cbc(DummyCipher::<16>::new(KEY).unwrap(), IV, Padding::None).unwrap();
+        decryptor.update(&[0x42; 20]);
+        decryptor.zeroize();
+        assert_eq!(decryptor.finalize(), Ok(Vec::new()));
+    }
+}
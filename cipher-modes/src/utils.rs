@@ -0,0 +1,75 @@
+//! Helpers shared by the modes
+
+/// XORs two blocks, the result is as long as the shorter one
+pub fn xor_blocks(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter().zip(b).map(|(x, y)| x ^ y).collect()
+}
+
+/// XORs `b` into `a`; bytes of `a` beyond the length of `b` stay unchanged
+pub fn xor_blocks_in_place(a: &mut [u8], b: &[u8]) {
+    a.iter_mut().zip(b).for_each(|(x, y)| *x ^= y);
+}
+
+/// Increments a counter block as a big-endian integer, wrapping around
+pub fn increment_counter(counter: &mut [u8]) {
+    for byte in counter.iter_mut().rev() {
+        let (value, overflow) = byte.overflowing_add(1);
+        *byte = value;
+        if !overflow {
+            break;
+        }
+    }
+}
+
+/// Adds `n` to a counter block as a big-endian integer, wrapping around
+///
+/// The same as calling [`increment_counter`] `n` times.
+pub fn add_to_counter(counter: &mut [u8], n: u64) {
+    let mut carry = u128::from(n);
+    for byte in counter.iter_mut().rev() {
+        if carry == 0 {
+            break;
+        }
+        let sum = u128::from(*byte) + (carry & 0xff);
+        *byte = sum as u8;
+        carry = (carry >> 8) + (sum >> 8);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xor_blocks_in_place() {
+        let mut block = [0x0f, 0xf0, 0xaa];
+        xor_blocks_in_place(&mut block, &[0xff, 0xff]);
+        assert_eq!(block, [0xf0, 0x0f, 0xaa]);
+        assert_eq!(xor_blocks(&[0x0f, 0xf0, 0xaa], &[0xff, 0xff]), &block[..2]);
+    }
+
+    #[test]
+    fn test_increment_counter() {
+        let mut counter = [0x00, 0xff, 0xff];
+        increment_counter(&mut counter);
+        assert_eq!(counter, [0x01, 0x00, 0x00]);
+
+        let mut counter = [0xff, 0xff];
+        increment_counter(&mut counter);
+        assert_eq!(counter, [0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_add_to_counter() {
+        for (start, n) in [([0x00, 0xfe, 0xff], 2), ([0xff, 0xff, 0xf0], 0x20), ([0x12, 0x34, 0x56], 0x01_0000)] {
+            let mut added = start;
+            add_to_counter(&mut added, n);
+            let mut incremented = start;
+            (0..n).for_each(|_| increment_counter(&mut incremented));
+            assert_eq!(added, incremented);
+        }
+        let mut counter = [0xff; 2];
+        add_to_counter(&mut counter, u64::MAX);
+        assert_eq!(counter, [0xff, 0xfe]);
+    }
+}
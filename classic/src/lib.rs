@@ -0,0 +1,129 @@
+//! Toolbox for the simple monoalphabetic ciphers that show up in puzzles
+//! and CTF challenges.
+//!
+//! Operations are applied from left to right, e.g. `--ops atbash,rot13,shift:5`
+//! first mirrors the alphabet, then rotates by 13 and finally shifts by 5.
+
+use std::str::FromStr;
+
+use clap::Parser;
+use logging::{English, Verbosity};
+use tool_error::ToolError;
+use classical::Alphabet;
+
+/// Command-line arguments for the classic cipher toolbox.
+#[derive(Parser, Debug)]
+struct Cli {
+    /// Path to the input file
+    #[arg(short, long, help = "Path to the input file")]
+    file: String,
+
+    /// Path to the output file, the result is printed if omitted
+    #[arg(short, long, help = "Path to the output file (default: stdout)")]
+    output: Option<String>,
+
+    /// Operations applied in order
+    #[arg(long, required = true, value_delimiter = ',', help = "Comma-separated operations: atbash, rot13, shift:N (N may be negative)")]
+    ops: Vec<Operation>,
+
+    /// Alphabet to operate on
+    #[arg(short, long, default_value = "latin", help = "Alphabet: latin, german, alnum, printable or a literal list of symbols")]
+    alphabet: String,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+/// A single step of the pipeline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operation {
+    /// Mirror the alphabet (A <-> Z)
+    Atbash,
+    /// Shift by 13 positions
+    Rot13,
+    /// Shift by an arbitrary number of positions
+    Shift(i64),
+}
+
+impl FromStr for Operation {
+    type Err = String;
+
+    fn from_str(op: &str) -> Result<Self, Self::Err> {
+        match op.trim().to_lowercase().as_str() {
+            "atbash" => Ok(Operation::Atbash),
+            "rot13" => Ok(Operation::Rot13),
+            other => match other.strip_prefix("shift:") {
+                Some(amount) => amount.parse()
+                    .map(Operation::Shift)
+                    .map_err(|_| format!("invalid shift amount '{}'", amount)),
+                None => Err(format!("unknown operation '{}' (expected atbash, rot13 or shift:N)", op)),
+            },
+        }
+    }
+}
+
+/// Main entry point for the classic cipher toolbox.
+///
+/// The first argument is the program name, as with `std::env::args_os`.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+    let alphabet = Alphabet::parse(&cli.alphabet).map_err(ToolError::usage)?;
+
+    let content: String = tool_error::fs::read_to_string(&cli.file)?;
+    let result = apply_all(&content, &cli.ops, &alphabet);
+
+    match &cli.output {
+        Some(path) => tool_error::fs::write(path, result)?,
+        None => print!("{}", result),
+    }
+    Ok(())
+}
+
+/// Applies a single operation to every character of the text.
+fn apply(text: &str, operation: Operation, alphabet: &Alphabet) -> String {
+    match operation {
+        Operation::Atbash => text.chars().map(|c| alphabet.reflect(c)).collect(),
+        Operation::Rot13 => apply(text, Operation::Shift(13), alphabet),
+        Operation::Shift(amount) => {
+            let shift = amount.rem_euclid(alphabet.len() as i64) as usize;
+            text.chars().map(|c| alphabet.shift(c, shift)).collect()
+        }
+    }
+}
+
+/// Applies all operations from left to right.
+fn apply_all(text: &str, operations: &[Operation], alphabet: &Alphabet) -> String {
+    operations.iter()
+        .fold(text.to_string(), |text, &operation| apply(&text, operation, alphabet))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_operations() {
+        assert_eq!("atbash".parse(), Ok(Operation::Atbash));
+        assert_eq!(" ROT13".parse(), Ok(Operation::Rot13));
+        assert_eq!("shift:-3".parse(), Ok(Operation::Shift(-3)));
+        assert!("shift:x".parse::<Operation>().is_err());
+        assert!("vigenere".parse::<Operation>().is_err());
+    }
+
+    #[test]
+    fn test_pipeline() {
+        let alphabet = Alphabet::latin();
+        assert_eq!(apply_all("Hello, World!", &[Operation::Atbash], &alphabet), "Svool, Dliow!");
+        assert_eq!(apply_all("Hello", &[Operation::Rot13, Operation::Rot13], &alphabet), "Hello");
+        assert_eq!(apply_all("abc", &[Operation::Shift(-1)], &alphabet), "zab");
+        assert_eq!(
+            apply_all("abc", &[Operation::Atbash, Operation::Rot13, Operation::Shift(5)], &alphabet),
+            "rqp"
+        );
+    }
+}
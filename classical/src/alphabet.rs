@@ -0,0 +1,220 @@
+/// An ordered set of symbols that the classical ciphers operate on.
+///
+/// Characters outside the alphabet are passed through unchanged. If the
+/// alphabet only contains the uppercase form of a letter, the lowercase form
+/// is mapped onto it and the case is restored on output, so `latin()` behaves
+/// exactly like the original hardcoded A-Z handling.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alphabet {
+    symbols: Vec<char>,
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Self::latin()
+    }
+}
+
+impl Alphabet {
+    /// Creates an alphabet from the given symbols.
+    ///
+    /// The alphabet needs at least two distinct symbols.
+    pub fn new(symbols: &str) -> Result<Self, String> {
+        let symbols: Vec<char> = symbols.chars().collect();
+
+        if symbols.len() < 2 {
+            return Err("Alphabet must contain at least two symbols".to_string());
+        }
+        for (i, c) in symbols.iter().enumerate() {
+            if symbols[..i].contains(c) {
+                return Err(format!("Alphabet contains '{}' more than once", c));
+            }
+        }
+
+        Ok(Alphabet { symbols })
+    }
+
+    /// The standard alphabet A-Z.
+    pub fn latin() -> Self {
+        Alphabet { symbols: ('A'..='Z').collect() }
+    }
+
+    /// Parses a preset name or a literal list of symbols.
+    ///
+    /// Presets:
+    /// * `latin` - A-Z
+    /// * `german` - A-Z plus ÄÖÜẞ (lowercase ß is folded onto the capital ẞ)
+    /// * `alnum` - A-Z plus the digits 0-9
+    /// * `printable` - the printable ASCII range from space to `~`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        match spec {
+            "latin" => Ok(Self::latin()),
+            "german" => Self::new("ABCDEFGHIJKLMNOPQRSTUVWXYZÄÖÜẞ"),
+            "alnum" => Self::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789"),
+            "printable" => Self::new(&(' '..='~').collect::<String>()),
+            literal => Self::new(literal),
+        }
+    }
+
+    /// Number of symbols in the alphabet.
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Always false, an alphabet has at least two symbols.
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Returns the symbol at the given position.
+    pub fn symbol(&self, index: usize) -> char {
+        self.symbols[index]
+    }
+
+    /// Looks up a character and reports whether it was case-folded.
+    fn lookup(&self, c: char) -> Option<(usize, bool)> {
+        if let Some(index) = self.symbols.iter().position(|&s| s == c) {
+            return Some((index, false));
+        }
+
+        if c.is_lowercase() {
+            let upper = fold_case(c);
+            return self.symbols.iter().position(|&s| s == upper).map(|index| (index, true));
+        }
+
+        None
+    }
+
+    /// Returns the position of a character, folding lowercase letters onto
+    /// their uppercase symbol if necessary.
+    pub fn index_of(&self, c: char) -> Option<usize> {
+        self.lookup(c).map(|(index, _)| index)
+    }
+
+    /// Replaces a character by the symbol at the index computed by `f`,
+    /// restoring the lowercase form if the character was case-folded.
+    /// Characters outside the alphabet are returned unchanged.
+    fn map_symbol(&self, c: char, f: impl Fn(usize) -> usize) -> char {
+        match self.lookup(c) {
+            Some((index, folded)) => {
+                let mapped = self.symbols[f(index)];
+                if folded {
+                    let mut lower = mapped.to_lowercase();
+                    match (lower.next(), lower.next()) {
+                        (Some(l), None) => l,
+                        _ => mapped,
+                    }
+                } else {
+                    mapped
+                }
+            }
+            None => c,
+        }
+    }
+
+    /// Shifts a character forward by `shift` positions, preserving its case.
+    /// Characters outside the alphabet are returned unchanged.
+    pub fn shift(&self, c: char, shift: usize) -> char {
+        self.map_symbol(c, |index| (index + shift) % self.len())
+    }
+
+    /// Shifts a character backward by `shift` positions.
+    pub fn unshift(&self, c: char, shift: usize) -> char {
+        self.shift(c, self.len() - shift % self.len())
+    }
+
+    /// Mirrors a character within the alphabet (Atbash): the first symbol
+    /// becomes the last one and vice versa.
+    pub fn reflect(&self, c: char) -> char {
+        self.map_symbol(c, |index| self.len() - 1 - index)
+    }
+
+    /// Converts a key string into shift values.
+    pub fn key_shifts(&self, key: &str) -> Result<Vec<usize>, String> {
+        if key.is_empty() {
+            return Err("Key must not be empty".to_string());
+        }
+
+        key.chars()
+            .map(|c| self.index_of(c).ok_or_else(|| format!("Key character '{}' is not part of the alphabet", c)))
+            .collect()
+    }
+
+    /// Keeps only characters of the alphabet, in their canonical (case-folded) form.
+    pub fn normalize(&self, text: &str) -> String {
+        text.chars()
+            .filter_map(|c| self.index_of(c).map(|index| self.symbols[index]))
+            .collect()
+    }
+}
+
+/// Uppercases a letter if it has a single-character uppercase form.
+///
+/// `ß` uppercases to "SS" by default; it is mapped to the capital `ẞ`
+/// instead so that it keeps a distinct position in the alphabet.
+pub(crate) fn fold_case(c: char) -> char {
+    if c == 'ß' {
+        return 'ẞ';
+    }
+
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(u), None) => u,
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latin_preserves_case() {
+        let alphabet = Alphabet::latin();
+        assert_eq!(alphabet.shift('a', 3), 'd');
+        assert_eq!(alphabet.shift('Z', 1), 'A');
+        assert_eq!(alphabet.unshift('c', 3), 'z');
+        assert_eq!(alphabet.shift('ä', 1), 'ä');
+    }
+
+    #[test]
+    fn test_german_alphabet() {
+        let alphabet = Alphabet::parse("german").unwrap();
+        assert_eq!(alphabet.len(), 30);
+        assert_eq!(alphabet.shift('Z', 1), 'Ä');
+        assert_eq!(alphabet.shift('ü', 1), 'ß');
+        assert_eq!(alphabet.shift('ß', 1), 'a');
+        assert_eq!(alphabet.unshift('ß', 1), 'ü');
+        assert_eq!(alphabet.normalize("Straße"), "STRAẞE");
+    }
+
+    #[test]
+    fn test_reflect() {
+        let alphabet = Alphabet::latin();
+        assert_eq!(alphabet.reflect('A'), 'Z');
+        assert_eq!(alphabet.reflect('m'), 'n');
+        assert_eq!(alphabet.reflect('!'), '!');
+    }
+
+    #[test]
+    fn test_printable_alphabet_is_case_sensitive() {
+        let alphabet = Alphabet::parse("printable").unwrap();
+        assert_eq!(alphabet.len(), 95);
+        assert_eq!(alphabet.shift('~', 1), ' ');
+        assert_ne!(alphabet.index_of('a'), alphabet.index_of('A'));
+    }
+
+    #[test]
+    fn test_rejects_invalid_alphabets() {
+        assert!(Alphabet::new("A").is_err());
+        assert!(Alphabet::new("ABA").is_err());
+    }
+
+    #[test]
+    fn test_key_shifts_and_normalize() {
+        let alphabet = Alphabet::latin();
+        assert_eq!(alphabet.key_shifts("Key").unwrap(), vec![10, 4, 24]);
+        assert!(alphabet.key_shifts("K3Y").is_err());
+        assert_eq!(alphabet.normalize("Hello, World!"), "HELLOWORLD");
+    }
+}
@@ -0,0 +1,123 @@
+use crate::Alphabet;
+
+/// Counts how often each symbol of the alphabet occurs in the text.
+///
+/// Characters outside the alphabet are ignored; lowercase letters are
+/// counted together with their uppercase symbol.
+pub fn count_frequencies(text: &str, alphabet: &Alphabet) -> Vec<u32> {
+    let mut frequencies = vec![0u32; alphabet.len()];
+
+    for c in text.chars() {
+        if let Some(index) = alphabet.index_of(c) {
+            frequencies[index] += 1;
+        }
+    }
+
+    frequencies
+}
+
+/// Calculates the Index of Coincidence from symbol counts.
+///
+/// Returns 0.0 if fewer than two symbols were counted.
+pub fn index_of_coincidence(frequencies: &[u32]) -> f64 {
+    let total: u64 = frequencies.iter().map(|&f| f as u64).sum();
+    if total < 2 {
+        return 0.0;
+    }
+
+    // IC = sum(f_i * (f_i - 1)) / (N * (N - 1))
+    let numerator: u64 = frequencies.iter()
+        .map(|&f| f as u64 * (f as u64).saturating_sub(1))
+        .sum();
+    numerator as f64 / (total * (total - 1)) as f64
+}
+
+/// Friedman (kappa) test: estimates the period of a polyalphabetic cipher
+/// from the IC of the whole ciphertext.
+///
+/// `reference_ic` is the IC of the plaintext language, `alphabet_len`
+/// the number of symbols and `text_len` the number of counted symbols.
+/// Returns `None` if the text is too short or too uniform for an estimate.
+pub fn friedman_key_length(ic: f64, reference_ic: f64, alphabet_len: usize, text_len: usize) -> Option<f64> {
+    let random_ic = 1.0 / alphabet_len as f64;
+    let n = text_len as f64;
+
+    // L = N (kp - kr) / ((N - 1) ko - N kr + kp)
+    let denominator = (n - 1.0) * ic - n * random_ic + reference_ic;
+    if text_len < 2 || denominator <= 0.0 {
+        return None;
+    }
+    Some(n * (reference_ic - random_ic) / denominator)
+}
+
+/// Chi-square statistic of the observed counts against the expected
+/// distribution, assuming the text was shifted by `shift` positions.
+///
+/// Symbols with an expected frequency of 0 are skipped.
+pub fn chi_squared(observed: &[u32], expected: &[f64], shift: usize) -> f64 {
+    let n = observed.len();
+    let total: f64 = observed.iter().map(|&count| count as f64).sum();
+    let mut chi_squared = 0.0;
+
+    for (i, &probability) in expected.iter().enumerate() {
+        let expected_count = probability * total;
+        if expected_count > 0.0 {
+            let observed_count = observed[(i + shift) % n] as f64;
+            chi_squared += (observed_count - expected_count).powi(2) / expected_count;
+        }
+    }
+
+    chi_squared
+}
+
+/// Scores every possible shift and returns `(shift, chi_squared)` pairs,
+/// best (lowest) first.
+pub fn rank_shifts(observed: &[u32], expected: &[f64]) -> Vec<(usize, f64)> {
+    let mut ranking: Vec<(usize, f64)> = (0..observed.len())
+        .map(|shift| (shift, chi_squared(observed, expected, shift)))
+        .collect();
+    ranking.sort_by(|a, b| a.1.total_cmp(&b.1));
+    ranking
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_frequencies_folds_case() {
+        let counts = count_frequencies("AaB-c!", &Alphabet::latin());
+        assert_eq!(&counts[..3], &[2, 1, 1]);
+        assert_eq!(counts.iter().sum::<u32>(), 4);
+    }
+
+    #[test]
+    fn test_friedman_key_length() {
+        // Plaintext IC gives a period of about one
+        let estimate = friedman_key_length(0.0667, 0.0667, 26, 1000).unwrap();
+        assert!((estimate - 1.0).abs() < 0.01);
+
+        // IC halfway to random corresponds to a period of about two
+        let estimate = friedman_key_length((0.0667 + 1.0 / 26.0) / 2.0, 0.0667, 26, 1000).unwrap();
+        assert!((estimate - 2.0).abs() < 0.05);
+
+        assert!(friedman_key_length(0.0, 0.0667, 26, 1000).is_none());
+    }
+
+    #[test]
+    fn test_rank_shifts_finds_caesar_shift() {
+        let expected = [0.5, 0.3, 0.2, 0.0];
+        // Distribution 50/30/20 rotated by one position
+        let observed = [0, 50, 30, 20];
+        let ranking = rank_shifts(&observed, &expected);
+        assert_eq!(ranking[0], (1, 0.0));
+        assert_eq!(ranking.len(), 4);
+    }
+
+    #[test]
+    fn test_index_of_coincidence() {
+        assert_eq!(index_of_coincidence(&[4, 0, 0]), 1.0);
+        assert_eq!(index_of_coincidence(&[1, 1, 1]), 0.0);
+        assert_eq!(index_of_coincidence(&[1]), 0.0);
+    }
+}
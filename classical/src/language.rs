@@ -0,0 +1,206 @@
+use clap::ValueEnum;
+
+use crate::alphabet::fold_case;
+use crate::Alphabet;
+
+/// Built-in languages with known letter frequency distributions
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Language {
+    /// German
+    De,
+    /// English
+    En,
+    /// French (accents folded onto the base letter)
+    Fr,
+    /// Spanish (accents folded onto the base letter)
+    Es,
+    /// Italian (accents folded onto the base letter)
+    It,
+}
+
+/// German letter frequencies for frequency analysis
+const GERMAN_FREQUENCIES: [f64; 26] = [
+    0.0558, 0.0196, 0.0316, 0.0498, 0.1693, 0.0149, 0.0302, 0.0498,
+    0.0802, 0.0024, 0.0132, 0.0360, 0.0255, 0.1053, 0.0224, 0.0067,
+    0.0002, 0.0689, 0.0642, 0.0579, 0.0383, 0.0084, 0.0178, 0.0005,
+    0.0005, 0.0121
+];
+
+/// English letter frequencies
+const ENGLISH_FREQUENCIES: [f64; 26] = [
+    0.0817, 0.0149, 0.0278, 0.0425, 0.1270, 0.0223, 0.0202, 0.0609,
+    0.0697, 0.0015, 0.0077, 0.0403, 0.0241, 0.0675, 0.0751, 0.0193,
+    0.0010, 0.0599, 0.0633, 0.0906, 0.0276, 0.0098, 0.0236, 0.0015,
+    0.0197, 0.0007
+];
+
+/// French letter frequencies
+const FRENCH_FREQUENCIES: [f64; 26] = [
+    0.0764, 0.0090, 0.0326, 0.0367, 0.1472, 0.0107, 0.0087, 0.0074,
+    0.0753, 0.0061, 0.0007, 0.0546, 0.0297, 0.0710, 0.0580, 0.0252,
+    0.0136, 0.0669, 0.0795, 0.0724, 0.0631, 0.0184, 0.0005, 0.0043,
+    0.0013, 0.0033
+];
+
+/// Spanish letter frequencies
+const SPANISH_FREQUENCIES: [f64; 26] = [
+    0.1153, 0.0222, 0.0402, 0.0501, 0.1218, 0.0069, 0.0177, 0.0070,
+    0.0625, 0.0049, 0.0001, 0.0497, 0.0316, 0.0671, 0.0868, 0.0251,
+    0.0088, 0.0687, 0.0798, 0.0463, 0.0293, 0.0114, 0.0002, 0.0022,
+    0.0101, 0.0047
+];
+
+/// Italian letter frequencies
+const ITALIAN_FREQUENCIES: [f64; 26] = [
+    0.1175, 0.0093, 0.0450, 0.0374, 0.1179, 0.0115, 0.0164, 0.0064,
+    0.1014, 0.0001, 0.0001, 0.0651, 0.0251, 0.0688, 0.0983, 0.0306,
+    0.0051, 0.0637, 0.0498, 0.0562, 0.0301, 0.0210, 0.0003, 0.0001,
+    0.0002, 0.0118
+];
+
+impl Language {
+    /// Returns the letter frequency table for this language
+    pub fn table(self) -> FrequencyTable {
+        let frequencies = match self {
+            Language::De => GERMAN_FREQUENCIES,
+            Language::En => ENGLISH_FREQUENCIES,
+            Language::Fr => FRENCH_FREQUENCIES,
+            Language::Es => SPANISH_FREQUENCIES,
+            Language::It => ITALIAN_FREQUENCIES,
+        };
+        let entries = ('A'..='Z').zip(frequencies).collect();
+        FrequencyTable::from_entries(entries).expect("built-in tables are non-empty")
+    }
+}
+
+/// A normalized symbol frequency distribution.
+///
+/// Tables are keyed by character so that they can be projected onto any
+/// alphabet; symbols missing from the table are expected with frequency 0.
+#[derive(Clone, Debug)]
+pub struct FrequencyTable {
+    entries: Vec<(char, f64)>,
+}
+
+impl FrequencyTable {
+    /// Builds a table from raw weights and scales it so that the weights sum to 1
+    fn from_entries(entries: Vec<(char, f64)>) -> Option<Self> {
+        let total: f64 = entries.iter().map(|(_, f)| f).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let entries = entries.into_iter().map(|(c, f)| (c, f / total)).collect();
+        Some(FrequencyTable { entries })
+    }
+
+    /// Parses a custom frequency distribution.
+    ///
+    /// Each non-empty line holds a symbol followed by its weight, e.g. `E 17.4`.
+    /// Lines starting with `#` are ignored. Weights may be given as fractions,
+    /// percentages or raw counts; the table is normalized to sum to 1.
+    /// Letters are stored in uppercase, matching the case folding of `Alphabet`.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut entries: Vec<(char, f64)> = Vec::new();
+
+        for (line_number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let symbol = parts.next().unwrap_or_default();
+            let weight = parts.next()
+                .ok_or_else(|| format!("Line {}: missing frequency value", line_number + 1))?;
+
+            let mut symbol_chars = symbol.chars();
+            let c = match (symbol_chars.next(), symbol_chars.next()) {
+                (Some(c), None) => fold_case(c),
+                _ => return Err(format!("Line {}: '{}' is not a single symbol", line_number + 1, symbol)),
+            };
+
+            let weight: f64 = weight.parse()
+                .map_err(|_| format!("Line {}: invalid frequency value '{}'", line_number + 1, weight))?;
+            if !weight.is_finite() || weight < 0.0 {
+                return Err(format!("Line {}: frequency must be a non-negative number", line_number + 1));
+            }
+
+            match entries.iter_mut().find(|(s, _)| *s == c) {
+                Some(entry) => entry.1 = weight,
+                None => entries.push((c, weight)),
+            }
+        }
+
+        Self::from_entries(entries).ok_or_else(|| "Frequency table contains no positive values".to_string())
+    }
+
+    /// Frequency of a single symbol (0 if the table does not list it)
+    pub fn frequency(&self, c: char) -> f64 {
+        self.entries.iter()
+            .find(|(s, _)| *s == c)
+            .map_or(0.0, |(_, f)| *f)
+    }
+
+    /// Expected frequencies for each symbol of the alphabet, in alphabet order.
+    ///
+    /// The result is renormalized over the alphabet so that table entries for
+    /// symbols outside the alphabet do not distort chi-square scores.
+    pub fn expected(&self, alphabet: &Alphabet) -> Vec<f64> {
+        let projected: Vec<f64> = (0..alphabet.len())
+            .map(|i| self.frequency(alphabet.symbol(i)))
+            .collect();
+        let total: f64 = projected.iter().sum();
+        if total <= 0.0 {
+            return projected;
+        }
+        projected.into_iter().map(|f| f / total).collect()
+    }
+
+    /// Expected Index of Coincidence of plaintext over the given alphabet: the sum
+    /// of squared symbol probabilities (about 0.076 for German, 0.066 for English).
+    pub fn reference_ic(&self, alphabet: &Alphabet) -> f64 {
+        self.expected(alphabet).iter().map(|f| f * f).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_ic() {
+        let latin = Alphabet::latin();
+        let german = Language::De.table().reference_ic(&latin);
+        let english = Language::En.table().reference_ic(&latin);
+        assert!((german - 0.076).abs() < 0.002);
+        assert!((english - 0.066).abs() < 0.002);
+    }
+
+    #[test]
+    fn test_parse_frequency_table() {
+        let table = FrequencyTable::parse("# custom\nE 3\na 1\n").unwrap();
+        assert_eq!(table.frequency('E'), 0.75);
+        assert_eq!(table.frequency('A'), 0.25);
+        assert_eq!(table.frequency('B'), 0.0);
+    }
+
+    #[test]
+    fn test_parse_frequency_table_rejects_invalid_lines() {
+        assert!(FrequencyTable::parse("AB 1").is_err());
+        assert!(FrequencyTable::parse("E").is_err());
+        assert!(FrequencyTable::parse("E x").is_err());
+        assert!(FrequencyTable::parse("E 0").is_err());
+    }
+
+    #[test]
+    fn test_expected_projects_onto_alphabet() {
+        let table = FrequencyTable::parse("E 2\nÄ 1\nß 1").unwrap();
+        let expected = table.expected(&Alphabet::latin());
+        assert_eq!(expected.len(), 26);
+        assert_eq!(expected[4], 1.0);
+
+        let german = table.expected(&Alphabet::parse("german").unwrap());
+        assert_eq!(german[26], 0.25);
+        assert_eq!(german[29], 0.25);
+    }
+}
@@ -0,0 +1,13 @@
+//! Shared building blocks for the classical cipher tools: configurable
+//! alphabets, letter frequency tables for several languages, n-gram
+//! language scoring and the basic statistics used by the decryptors.
+
+pub mod alphabet;
+pub mod frequency;
+pub mod language;
+pub mod ngram;
+
+pub use alphabet::Alphabet;
+pub use frequency::{chi_squared, count_frequencies, friedman_key_length, index_of_coincidence, rank_shifts};
+pub use language::{FrequencyTable, Language};
+pub use ngram::NgramModel;
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+
+use crate::alphabet::fold_case;
+use crate::{Alphabet, Language};
+
+/// Approximate relative frequencies (percent) of the most common bigrams.
+const GERMAN_BIGRAMS: &[(&str, f64)] = &[
+    ("ER", 4.09), ("EN", 4.00), ("CH", 2.42), ("DE", 2.27), ("EI", 1.93), ("TE", 1.85),
+    ("IN", 1.68), ("ND", 1.62), ("IE", 1.58), ("GE", 1.47), ("ST", 1.21), ("NE", 1.19),
+    ("BE", 1.17), ("ES", 1.16), ("UN", 1.14), ("RE", 1.12), ("AN", 1.02), ("HE", 1.02),
+    ("AU", 0.95), ("NG", 0.91), ("SE", 0.90), ("IT", 0.89), ("DI", 0.88), ("IC", 0.87),
+    ("SC", 0.85), ("LE", 0.82), ("DA", 0.78), ("NS", 0.76), ("IS", 0.74), ("RA", 0.73),
+];
+
+const ENGLISH_BIGRAMS: &[(&str, f64)] = &[
+    ("TH", 3.56), ("HE", 3.07), ("IN", 2.43), ("ER", 2.05), ("AN", 1.99), ("RE", 1.85),
+    ("ON", 1.76), ("AT", 1.49), ("EN", 1.45), ("ND", 1.35), ("TI", 1.34), ("ES", 1.34),
+    ("OR", 1.28), ("TE", 1.20), ("OF", 1.17), ("ED", 1.17), ("IS", 1.13), ("IT", 1.12),
+    ("AL", 1.09), ("AR", 1.07), ("ST", 1.05), ("TO", 1.04), ("NT", 1.04), ("NG", 0.95),
+    ("SE", 0.93), ("HA", 0.93), ("AS", 0.87), ("OU", 0.87), ("IO", 0.83), ("LE", 0.83),
+];
+
+const FRENCH_BIGRAMS: &[(&str, f64)] = &[
+    ("ES", 3.15), ("LE", 2.22), ("DE", 2.17), ("EN", 2.12), ("RE", 2.09), ("NT", 1.97),
+    ("ON", 1.64), ("ER", 1.63), ("TE", 1.63), ("EL", 1.54), ("AN", 1.53), ("SE", 1.40),
+    ("ET", 1.37), ("LA", 1.37), ("AI", 1.28), ("IT", 1.20), ("ME", 1.17), ("OU", 1.15),
+    ("EM", 1.13), ("IE", 1.10), ("NE", 1.05), ("UR", 1.02), ("QU", 0.99), ("NS", 0.98),
+];
+
+const SPANISH_BIGRAMS: &[(&str, f64)] = &[
+    ("DE", 2.57), ("ES", 2.37), ("EN", 2.37), ("EL", 2.02), ("LA", 1.99), ("OS", 1.95),
+    ("UE", 1.67), ("AR", 1.53), ("RA", 1.51), ("RE", 1.49), ("ER", 1.46), ("AS", 1.37),
+    ("ON", 1.35), ("ST", 1.27), ("AD", 1.24), ("AL", 1.22), ("OR", 1.21), ("TA", 1.20),
+    ("CO", 1.18), ("SE", 1.17), ("AN", 1.12), ("NT", 1.08), ("QU", 1.02), ("DO", 0.98),
+];
+
+const ITALIAN_BIGRAMS: &[(&str, f64)] = &[
+    ("ER", 1.77), ("RE", 1.70), ("ON", 1.60), ("TO", 1.60), ("DI", 1.50), ("LA", 1.50),
+    ("IN", 1.50), ("EN", 1.40), ("ES", 1.30), ("AN", 1.30), ("CO", 1.30), ("TA", 1.30),
+    ("NE", 1.30), ("TE", 1.30), ("EL", 1.20), ("AL", 1.20), ("RA", 1.20), ("AT", 1.20),
+    ("NT", 1.20), ("CH", 1.00), ("LE", 1.00), ("IO", 0.95), ("NO", 0.95), ("OR", 0.90),
+];
+
+/// Most common quadgrams, most frequent first. Without real counts the
+/// built-in models weight them by rank (Zipf's law); a statistics file
+/// gives better results and can be loaded with `NgramModel::parse`.
+const GERMAN_QUADGRAMS: &[&str] = &[
+    "ICHT", "EINE", "SCHE", "CHEN", "NDER", "LICH", "ISCH", "UNDE", "ENDE", "NDIE",
+    "DIES", "SICH", "NGEN", "UNGE", "RUNG", "HEIT", "AUCH", "NACH", "CHTE", "STEN",
+    "ERDE", "SEIN", "ICHE", "DASS", "KEIT", "WERD", "IGEN", "TION", "ERST", "ENEN",
+    "LLEN", "TSCH", "ESCH", "EDER", "ERUN", "ACHT", "INDE", "DERS", "ENDI", "IEDE",
+    "TEIN", "GENE", "CHER", "NDEN", "REND", "ERGE", "TERS", "BERE", "UBER", "INEN",
+];
+
+const ENGLISH_QUADGRAMS: &[&str] = &[
+    "TION", "NTHE", "THER", "THAT", "OFTH", "FTHE", "THES", "WITH", "INTH", "ATIO",
+    "OTHE", "TTHE", "DTHE", "INGT", "ETHE", "SAND", "STHE", "HERE", "THEC", "MENT",
+    "THEM", "RTHE", "THEP", "FROM", "THIS", "TING", "THEI", "NGTH", "IONS", "ANDT",
+    "EDTH", "ANDE", "ERES", "OULD", "EVER", "HAVE", "NDTH", "THEA", "ONTH", "ALLY",
+    "ENTH", "HATT", "THEY", "WHIC", "HICH", "OUNT", "IGHT", "ATTH", "TERS", "ANCE",
+];
+
+/// Log-probability model over n-grams of a fixed length.
+///
+/// Text is scored by summing the log10 probabilities of all overlapping
+/// n-grams; unknown n-grams get a floor value well below the rarest entry.
+/// The absolute score only matters when comparing texts of the same length.
+#[derive(Clone, Debug)]
+pub struct NgramModel {
+    n: usize,
+    log_probs: HashMap<String, f64>,
+    floor: f64,
+}
+
+impl NgramModel {
+    /// Builds a model from raw n-gram counts or frequencies.
+    pub fn from_counts(entries: Vec<(String, f64)>) -> Result<Self, String> {
+        let n = match entries.first() {
+            Some((ngram, _)) => ngram.chars().count(),
+            None => return Err("N-gram table is empty".to_string()),
+        };
+        if n == 0 || entries.iter().any(|(ngram, _)| ngram.chars().count() != n) {
+            return Err("All n-grams must have the same, non-zero length".to_string());
+        }
+
+        let total: f64 = entries.iter().map(|(_, count)| count).sum();
+        if total <= 0.0 {
+            return Err("N-gram table contains no positive values".to_string());
+        }
+
+        let log_probs: HashMap<String, f64> = entries.into_iter()
+            .filter(|(_, count)| *count > 0.0)
+            .map(|(ngram, count)| (ngram.chars().map(fold_case).collect(), (count / total).log10()))
+            .collect();
+        let min = log_probs.values().copied().fold(f64::INFINITY, f64::min);
+
+        Ok(NgramModel { n, log_probs, floor: min - 1.0 })
+    }
+
+    /// Parses an n-gram table with one `NGRAM COUNT` pair per line.
+    ///
+    /// This is the format of the widely used quadgram statistics files.
+    /// Lines starting with `#` are ignored.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut entries = Vec::new();
+
+        for (line_number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let ngram = parts.next().unwrap_or_default();
+            let count = parts.next()
+                .ok_or_else(|| format!("Line {}: missing count", line_number + 1))?;
+            let count: f64 = count.parse()
+                .map_err(|_| format!("Line {}: invalid count '{}'", line_number + 1, count))?;
+            if !count.is_finite() || count < 0.0 {
+                return Err(format!("Line {}: count must be a non-negative number", line_number + 1));
+            }
+
+            entries.push((ngram.to_string(), count));
+        }
+
+        Self::from_counts(entries)
+    }
+
+    /// Built-in bigram model for a language.
+    pub fn bigrams(language: Language) -> Self {
+        let table = match language {
+            Language::De => GERMAN_BIGRAMS,
+            Language::En => ENGLISH_BIGRAMS,
+            Language::Fr => FRENCH_BIGRAMS,
+            Language::Es => SPANISH_BIGRAMS,
+            Language::It => ITALIAN_BIGRAMS,
+        };
+        let entries = table.iter().map(|&(ngram, f)| (ngram.to_string(), f)).collect();
+        Self::from_counts(entries).expect("built-in tables are valid")
+    }
+
+    /// Built-in quadgram model, available for German and English.
+    pub fn quadgrams(language: Language) -> Option<Self> {
+        let table = match language {
+            Language::De => GERMAN_QUADGRAMS,
+            Language::En => ENGLISH_QUADGRAMS,
+            Language::Fr | Language::Es | Language::It => return None,
+        };
+        let entries = table.iter()
+            .enumerate()
+            .map(|(rank, &ngram)| (ngram.to_string(), 1.0 / (rank + 1) as f64))
+            .collect();
+        Some(Self::from_counts(entries).expect("built-in tables are valid"))
+    }
+
+    /// Length of the n-grams in this model.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Scores a text; higher (less negative) means more language-like.
+    ///
+    /// Only symbols of the alphabet are considered.
+    pub fn score(&self, text: &str, alphabet: &Alphabet) -> f64 {
+        let symbols: Vec<char> = alphabet.normalize(text).chars().collect();
+        if symbols.len() < self.n {
+            return self.floor;
+        }
+
+        let mut ngram = String::with_capacity(self.n * 4);
+        symbols.windows(self.n)
+            .map(|window| {
+                ngram.clear();
+                ngram.extend(window);
+                self.log_probs.get(&ngram).copied().unwrap_or(self.floor)
+            })
+            .sum()
+    }
+
+    /// Average log probability per n-gram, comparable across text lengths.
+    pub fn score_per_ngram(&self, text: &str, alphabet: &Alphabet) -> f64 {
+        let count = alphabet.normalize(text).chars().count();
+        if count < self.n {
+            return self.floor;
+        }
+        self.score(text, alphabet) / (count - self.n + 1) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plaintext_scores_higher_than_shuffled_text() {
+        let model = NgramModel::bigrams(Language::En);
+        let alphabet = Alphabet::latin();
+        let plain = model.score("the weather is nice and there is no rain", &alphabet);
+        let shuffled = model.score("tew ehtaher si icen adn htree si on ainr", &alphabet);
+        assert!(plain > shuffled);
+    }
+
+    #[test]
+    fn test_builtin_quadgrams() {
+        let model = NgramModel::quadgrams(Language::De).unwrap();
+        assert_eq!(model.n(), 4);
+        let alphabet = Alphabet::latin();
+        assert!(model.score("nicht", &alphabet) > model.score("thcin", &alphabet));
+        assert!(NgramModel::quadgrams(Language::It).is_none());
+    }
+
+    #[test]
+    fn test_parse_ngram_table() {
+        let model = NgramModel::parse("# quadgrams\nTION 3\nnthe 1\n").unwrap();
+        assert_eq!(model.n(), 4);
+        let alphabet = Alphabet::latin();
+        assert!(model.score("tion", &alphabet) > model.score("nthe", &alphabet));
+        assert!(model.score("nthe", &alphabet) > model.score("xxxx", &alphabet));
+    }
+
+    #[test]
+    fn test_parse_rejects_mixed_lengths() {
+        assert!(NgramModel::parse("TH 1\nTHE 1").is_err());
+        assert!(NgramModel::parse("").is_err());
+        assert!(NgramModel::parse("TH x").is_err());
+    }
+}
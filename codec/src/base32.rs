@@ -0,0 +1,103 @@
+//! Base32-Kodierung nach RFC 4648 (Standardalphabet mit Auffüllung)
+//!
+//! Übliches Format für OTP-Geheimnisse in Authenticator-Apps. Diese lassen
+//! die Auffüllung meist weg und schreiben gelegentlich Kleinbuchstaben,
+//! beides akzeptiert der Dekodierer.
+
+use crate::{CodecError, significant_chars};
+
+const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Kodiert Bytes als Base32, aufgefüllt mit `=` auf ein Vielfaches von 8 Zeichen
+pub fn encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(5) * 8);
+
+    for chunk in bytes.chunks(5) {
+        let group = chunk.iter()
+            .enumerate()
+            .fold(0u64, |group, (i, &b)| group | u64::from(b) << (32 - 8 * i));
+
+        // n Bytes ergeben ⌈8n / 5⌉ Zeichen, der Rest wird aufgefüllt
+        let symbols = (chunk.len() * 8).div_ceil(5);
+        for i in 0..8 {
+            if i < symbols {
+                encoded.push(ALPHABET[(group >> (35 - 5 * i)) as usize & 0x1f] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Dekodiert Base32 ohne Rücksicht auf Groß- und Kleinschreibung
+///
+/// Whitespace wird ignoriert, die Auffüllung darf fehlen.
+pub fn decode(text: &str) -> Result<Vec<u8>, CodecError> {
+    let chars: Vec<(usize, char)> = significant_chars(text).collect();
+
+    let padding = chars.iter().rev().take_while(|(_, c)| *c == '=').count();
+    let symbols = &chars[..chars.len() - padding];
+    if symbols.iter().any(|(_, c)| *c == '=') || (padding > 0 && !chars.len().is_multiple_of(8)) {
+        return Err(CodecError::InvalidPadding);
+    }
+    // Ein letzter Block mit 1, 3 oder 6 Zeichen entsteht aus keiner Byteanzahl
+    if matches!(symbols.len() % 8, 1 | 3 | 6) {
+        return Err(CodecError::InvalidLength { encoding: "Base32", length: chars.len() });
+    }
+
+    let mut decoded = Vec::with_capacity(symbols.len() * 5 / 8);
+    for group in symbols.chunks(8) {
+        let mut value = 0u64;
+        for &(position, character) in group {
+            let digit = ALPHABET.iter()
+                .position(|&a| a as char == character.to_ascii_uppercase())
+                .ok_or(CodecError::InvalidCharacter { character, position })?;
+            value = (value << 5) | digit as u64;
+        }
+        value <<= 5 * (8 - group.len());
+
+        decoded.extend_from_slice(&value.to_be_bytes()[3..3 + group.len() * 5 / 8]);
+    }
+
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Testvektoren aus RFC 4648, Abschnitt 10
+    const VECTORS: [(&str, &str); 7] = [
+        ("", ""),
+        ("f", "MY======"),
+        ("fo", "MZXQ===="),
+        ("foo", "MZXW6==="),
+        ("foob", "MZXW6YQ="),
+        ("fooba", "MZXW6YTB"),
+        ("foobar", "MZXW6YTBOI======"),
+    ];
+
+    #[test]
+    fn test_rfc_vectors() {
+        for (plain, encoded) in VECTORS {
+            assert_eq!(encode(plain.as_bytes()), encoded);
+            assert_eq!(decode(encoded).unwrap(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_decode_authenticator_style() {
+        assert_eq!(decode("mzxw 6ytb oi").unwrap(), b"foobar");
+        assert_eq!(decode("MZXW6YQ").unwrap(), b"foob");
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(decode("MZXW6Y"), Err(CodecError::InvalidLength { encoding: "Base32", length: 6 }));
+        assert_eq!(decode("MZ=W6YQ="), Err(CodecError::InvalidPadding));
+        assert_eq!(decode("MZXW6=="), Err(CodecError::InvalidPadding));
+        assert_eq!(decode("MZXW1YQ="), Err(CodecError::InvalidCharacter { character: '1', position: 4 }));
+    }
+}
@@ -0,0 +1,137 @@
+//! Base58-Kodierung mit dem Bitcoin-Alphabet
+//!
+//! Das Alphabet lässt die leicht verwechselbaren Zeichen `0`, `O`, `I` und `l`
+//! weg. Die Bytes werden als eine große Zahl zur Basis 58 geschrieben; jedes
+//! führende Nullbyte wird als `1` kodiert.
+//!
+//! Base58Check hängt vor dem Kodieren die ersten vier Bytes von
+//! SHA-256(SHA-256(Daten)) an, sodass fast jeder Tippfehler auffällt.
+
+use crate::{CodecError, significant_chars};
+use sha2::{Digest, Sha256};
+
+const CHECKSUM_LENGTH: usize = 4;
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Kodiert Bytes als Base58
+pub fn encode(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+
+    // Ziffern zur Basis 58, niederwertigste zuerst
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for &byte in &bytes[zeros..] {
+        let mut carry = u32::from(byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    std::iter::repeat_n('1', zeros)
+        .chain(digits.iter().rev().map(|&d| ALPHABET[d as usize] as char))
+        .collect()
+}
+
+/// Dekodiert Base58, Whitespace wird ignoriert
+pub fn decode(text: &str) -> Result<Vec<u8>, CodecError> {
+    let mut zeros = 0;
+    let mut leading = true;
+
+    // Bytes der Zahl, niederwertigstes zuerst
+    let mut bytes: Vec<u8> = Vec::new();
+    for (position, character) in significant_chars(text) {
+        let value = ALPHABET.iter()
+            .position(|&a| a as char == character)
+            .ok_or(CodecError::InvalidCharacter { character, position })?;
+
+        if leading && value == 0 {
+            zeros += 1;
+            continue;
+        }
+        leading = false;
+
+        let mut carry = value as u32;
+        for byte in bytes.iter_mut() {
+            carry += u32::from(*byte) * 58;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push((carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+
+    Ok(std::iter::repeat_n(0, zeros).chain(bytes.into_iter().rev()).collect())
+}
+
+fn checksum(payload: &[u8]) -> [u8; CHECKSUM_LENGTH] {
+    let hash = Sha256::digest(Sha256::digest(payload));
+    hash[..CHECKSUM_LENGTH].try_into().expect("vier Bytes")
+}
+
+/// Kodiert Bytes mit angehängter Prüfsumme als Base58Check
+pub fn encode_check(payload: &[u8]) -> String {
+    encode(&[payload, &checksum(payload)].concat())
+}
+
+/// Dekodiert Base58Check und gibt die Daten ohne Prüfsumme zurück
+pub fn decode_check(text: &str) -> Result<Vec<u8>, CodecError> {
+    let mut bytes = decode(text)?;
+    if bytes.len() < CHECKSUM_LENGTH {
+        return Err(CodecError::InvalidLength { encoding: "Base58Check", length: text.trim().chars().count() });
+    }
+    let expected = bytes.split_off(bytes.len() - CHECKSUM_LENGTH);
+    if checksum(&bytes) != expected[..] {
+        return Err(CodecError::InvalidChecksum { encoding: "Base58Check" });
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Testvektoren aus draft-msporny-base58
+    #[test]
+    fn test_known_vectors() {
+        assert_eq!(encode(b"Hello World!"), "2NEpo7TZRRrLZSi2U");
+        assert_eq!(encode(b"The quick brown fox jumps over the lazy dog."), "USm3fpXnKG5EUBx2ndxBDMPVciP5hGey2Jh4NDv6gmeo1LkMeiKrLJUUBk6Z");
+        assert_eq!(encode(&[0x00, 0x00, 0x28, 0x7f, 0xb4, 0xcd]), "11233QC4");
+        assert_eq!(decode("11233QC4").unwrap(), [0x00, 0x00, 0x28, 0x7f, 0xb4, 0xcd]);
+        assert_eq!(decode("2NEpo7TZRRrLZSi2U").unwrap(), b"Hello World!");
+    }
+
+    /// Bitcoin-Adressen: Versionsbyte 0x00 und RIPEMD-160-Hash
+    #[test]
+    fn test_check_vectors() {
+        assert_eq!(encode_check(&[0; 21]), "1111111111111111111114oLvT2");
+        let mut payload = vec![0x00];
+        payload.extend(crate::hex::decode("010966776006953D5567439E5E39F86A0D273BEE").unwrap());
+        assert_eq!(encode_check(&payload), "16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvM");
+        assert_eq!(decode_check("16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvM").unwrap(), payload);
+        assert_eq!(decode_check(&encode_check(b"")).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_check_detects_typos() {
+        // Zwei Zeichen vertauscht bzw. eines geändert
+        assert_eq!(decode_check("16UwLL9Risc3QfPqBUvKofHmBQ7wMtjMv"), Err(CodecError::InvalidChecksum { encoding: "Base58Check" }));
+        assert_eq!(decode_check("16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvN"), Err(CodecError::InvalidChecksum { encoding: "Base58Check" }));
+        assert_eq!(decode_check("2g"), Err(CodecError::InvalidLength { encoding: "Base58Check", length: 2 }));
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert_eq!(encode(&[]), "");
+        assert_eq!(encode(&[0, 0]), "11");
+        assert_eq!(decode("11").unwrap(), [0, 0]);
+        assert_eq!(decode("1O"), Err(CodecError::InvalidCharacter { character: 'O', position: 1 }));
+    }
+}
@@ -0,0 +1,96 @@
+//! Base64-Kodierung nach RFC 4648 (Standardalphabet mit Auffüllung)
+
+use crate::{CodecError, significant_chars};
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Kodiert Bytes als Base64, aufgefüllt mit `=` auf ein Vielfaches von 4 Zeichen
+pub fn encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter()
+            .enumerate()
+            .fold(0u32, |group, (i, &b)| group | u32::from(b) << (16 - 8 * i));
+
+        // n Bytes ergeben n + 1 Zeichen, der Rest wird aufgefüllt
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Dekodiert Base64, Whitespace (z. B. Zeilenumbrüche in PEM) wird ignoriert
+pub fn decode(text: &str) -> Result<Vec<u8>, CodecError> {
+    let chars: Vec<(usize, char)> = significant_chars(text).collect();
+    if !chars.len().is_multiple_of(4) {
+        return Err(CodecError::InvalidLength { encoding: "Base64", length: chars.len() });
+    }
+
+    let padding = chars.iter().rev().take_while(|(_, c)| *c == '=').count();
+    if padding > 2 || chars[..chars.len() - padding].iter().any(|(_, c)| *c == '=') {
+        return Err(CodecError::InvalidPadding);
+    }
+
+    let mut decoded = Vec::with_capacity(chars.len() / 4 * 3);
+    for (index, group) in chars.chunks(4).enumerate() {
+        let is_last = index == chars.len() / 4 - 1;
+        let symbols = if is_last { 4 - padding } else { 4 };
+
+        let mut value = 0u32;
+        for &(position, character) in &group[..symbols] {
+            let digit = ALPHABET.iter()
+                .position(|&a| a as char == character)
+                .ok_or(CodecError::InvalidCharacter { character, position })?;
+            value = (value << 6) | digit as u32;
+        }
+        value <<= 6 * (4 - symbols);
+
+        decoded.extend_from_slice(&value.to_be_bytes()[1..symbols]);
+    }
+
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Testvektoren aus RFC 4648, Abschnitt 10
+    const VECTORS: [(&str, &str); 7] = [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+
+    #[test]
+    fn test_rfc_vectors() {
+        for (plain, encoded) in VECTORS {
+            assert_eq!(encode(plain.as_bytes()), encoded);
+            assert_eq!(decode(encoded).unwrap(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_decode_ignores_line_breaks() {
+        assert_eq!(decode("Zm9v\nYmFy\n").unwrap(), b"foobar");
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(decode("Zm9"), Err(CodecError::InvalidLength { encoding: "Base64", length: 3 }));
+        assert_eq!(decode("Z=9v"), Err(CodecError::InvalidPadding));
+        assert_eq!(decode("Z==="), Err(CodecError::InvalidPadding));
+        assert_eq!(decode("Zm9-"), Err(CodecError::InvalidCharacter { character: '-', position: 3 }));
+    }
+}
@@ -0,0 +1,209 @@
+//! Bech32 und Bech32m nach BIP 173 bzw. BIP 350
+//!
+//! Eine Zeichenkette besteht aus einem lesbaren Präfix (HRP), dem
+//! Trennzeichen `1`, den Daten in 5-Bit-Gruppen und sechs Prüfzeichen. Die
+//! BCH-Prüfsumme erkennt bis zu vier falsche Zeichen sicher. Bech32m
+//! unterscheidet sich nur in der Konstante, mit der die Prüfsumme endet.
+
+use crate::{CodecError, significant_chars};
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+const CHECKSUM_LENGTH: usize = 6;
+const MAX_LENGTH: usize = 90;
+
+/// Variante der Prüfsumme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Variant {
+    fn constant(self) -> u32 {
+        match self {
+            Variant::Bech32 => 1,
+            Variant::Bech32m => 0x2bc830a3,
+        }
+    }
+}
+
+fn malformed(reason: &'static str) -> CodecError {
+    CodecError::Malformed { encoding: "Bech32", reason }
+}
+
+fn polymod(values: impl IntoIterator<Item = u8>) -> u32 {
+    values.into_iter().fold(1u32, |checksum, value| {
+        let top = checksum >> 25;
+        let mut checksum = (checksum & 0x1ffffff) << 5 ^ u32::from(value);
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+        checksum
+    })
+}
+
+/// Präfix in die Form bringen, die in die Prüfsumme eingeht
+fn expand_hrp(hrp: &str) -> impl Iterator<Item = u8> + '_ {
+    hrp.bytes().map(|b| b >> 5).chain([0]).chain(hrp.bytes().map(|b| b & 0x1f))
+}
+
+/// Bytes in 5-Bit-Gruppen zerlegen, die letzte wird mit Nullbits aufgefüllt
+fn to_words(bytes: &[u8]) -> Vec<u8> {
+    let mut words = Vec::with_capacity((bytes.len() * 8).div_ceil(5));
+    let (mut buffer, mut bits) = (0u32, 0);
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            words.push((buffer >> bits) as u8 & 0x1f);
+        }
+    }
+    if bits > 0 {
+        words.push((buffer << (5 - bits)) as u8 & 0x1f);
+    }
+    words
+}
+
+/// Umkehrung von [`to_words`]; überzählige Bits müssen Null sein
+fn from_words(words: &[u8]) -> Result<Vec<u8>, CodecError> {
+    let mut bytes = Vec::with_capacity(words.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for &word in words {
+        buffer = (buffer << 5) | u32::from(word);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    if bits >= 5 || buffer & ((1 << bits) - 1) != 0 {
+        return Err(malformed("Auffüllung ungleich Null"));
+    }
+    Ok(bytes)
+}
+
+/// Kodiert Bytes unter dem Präfix `hrp`, das Ergebnis ist kleingeschrieben
+///
+/// Der Aufrufer wählt ein Präfix aus druckbaren ASCII-Zeichen; Zeichenketten
+/// über 90 Zeichen lehnt [`decode`] ab.
+pub fn encode(hrp: &str, bytes: &[u8], variant: Variant) -> String {
+    let hrp = hrp.to_ascii_lowercase();
+    let words = to_words(bytes);
+    let checksum = polymod(expand_hrp(&hrp).chain(words.iter().copied()).chain([0; CHECKSUM_LENGTH])) ^ variant.constant();
+
+    let mut encoded = String::with_capacity(hrp.len() + 1 + words.len() + CHECKSUM_LENGTH);
+    encoded.push_str(&hrp);
+    encoded.push('1');
+    encoded.extend(words.iter().map(|&w| CHARSET[w as usize] as char));
+    encoded.extend((0..CHECKSUM_LENGTH).map(|i| CHARSET[(checksum >> (5 * (5 - i))) as usize & 0x1f] as char));
+    encoded
+}
+
+/// Dekodiert Bech32 oder Bech32m und liefert Präfix, Daten und Variante
+///
+/// Whitespace wird ignoriert. Groß- oder Kleinschreibung ist erlaubt, aber
+/// nicht gemischt; das Präfix wird kleingeschrieben zurückgegeben.
+pub fn decode(text: &str) -> Result<(String, Vec<u8>, Variant), CodecError> {
+    let chars: Vec<(usize, char)> = significant_chars(text).collect();
+    if chars.len() > MAX_LENGTH {
+        return Err(CodecError::InvalidLength { encoding: "Bech32", length: chars.len() });
+    }
+    if let Some(&(position, character)) = chars.iter().find(|(_, c)| !matches!(c, '!'..='~')) {
+        return Err(CodecError::InvalidCharacter { character, position });
+    }
+    if chars.iter().any(|(_, c)| c.is_ascii_lowercase()) && chars.iter().any(|(_, c)| c.is_ascii_uppercase()) {
+        return Err(malformed("Groß- und Kleinbuchstaben gemischt"));
+    }
+
+    let separator = chars.iter().rposition(|(_, c)| *c == '1').ok_or(malformed("Trennzeichen '1' fehlt"))?;
+    if separator == 0 {
+        return Err(malformed("Präfix fehlt"));
+    }
+    if chars.len() - separator - 1 < CHECKSUM_LENGTH {
+        return Err(malformed("Prüfsumme zu kurz"));
+    }
+
+    let hrp: String = chars[..separator].iter().map(|(_, c)| c.to_ascii_lowercase()).collect();
+    let values = chars[separator + 1..].iter()
+        .map(|&(position, character)| {
+            CHARSET.iter()
+                .position(|&a| a as char == character.to_ascii_lowercase())
+                .map(|digit| digit as u8)
+                .ok_or(CodecError::InvalidCharacter { character, position })
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+
+    let variant = match polymod(expand_hrp(&hrp).chain(values.iter().copied())) {
+        c if c == Variant::Bech32.constant() => Variant::Bech32,
+        c if c == Variant::Bech32m.constant() => Variant::Bech32m,
+        _ => return Err(CodecError::InvalidChecksum { encoding: "Bech32" }),
+    };
+    let bytes = from_words(&values[..values.len() - CHECKSUM_LENGTH])?;
+    Ok((hrp, bytes, variant))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Gültige Beispiele aus BIP 173 und BIP 350
+    #[test]
+    fn test_valid_vectors() {
+        for (text, variant) in [
+            ("A12UEL5L", Variant::Bech32),
+            ("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw", Variant::Bech32),
+            ("A1LQFN3A", Variant::Bech32m),
+            ("abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx", Variant::Bech32m),
+        ] {
+            let (_, _, decoded) = decode(text).unwrap();
+            assert_eq!(decoded, variant, "{}", text);
+        }
+        assert_eq!(decode("A12UEL5L").unwrap(), ("a".to_string(), vec![], Variant::Bech32));
+    }
+
+    #[test]
+    fn test_invalid_vectors() {
+        // Präfix fehlt, ungültiges Datenzeichen, Prüfsumme zu kurz, kein Trennzeichen
+        assert_eq!(decode("10a06t8"), Err(malformed("Präfix fehlt")));
+        assert_eq!(decode("x1b4n0q5v"), Err(CodecError::InvalidCharacter { character: 'b', position: 2 }));
+        assert_eq!(decode("li1dgmt3"), Err(malformed("Prüfsumme zu kurz")));
+        assert_eq!(decode("pzry9x0s0muk"), Err(malformed("Trennzeichen '1' fehlt")));
+        assert_eq!(decode("A1G7SGD8"), Err(CodecError::InvalidChecksum { encoding: "Bech32" }));
+        assert_eq!(decode("a12UEL5L"), Err(malformed("Groß- und Kleinbuchstaben gemischt")));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for bytes in [&b""[..], b"\x00", b"\xff\xff", b"Schluessel-Fingerabdruck", &[0x5a; 32]] {
+            for variant in [Variant::Bech32, Variant::Bech32m] {
+                let encoded = encode("fp", bytes, variant);
+                assert_eq!(decode(&encoded).unwrap(), ("fp".to_string(), bytes.to_vec(), variant));
+                assert_eq!(decode(&encoded.to_uppercase()).unwrap().1, bytes);
+            }
+        }
+    }
+
+    #[test]
+    fn test_detects_typos() {
+        let encoded = encode("fp", b"Fingerabdruck", Variant::Bech32m);
+        let mut typo = encoded.clone().into_bytes();
+        typo[5] = if typo[5] == b'q' { b'p' } else { b'q' };
+        assert_eq!(decode(std::str::from_utf8(&typo).unwrap()), Err(CodecError::InvalidChecksum { encoding: "Bech32" }));
+        assert_eq!(decode(&format!("{} \n", encoded)).unwrap().1, b"Fingerabdruck");
+    }
+
+    #[test]
+    fn test_limits() {
+        let long = encode("x", &[0; 60], Variant::Bech32);
+        assert!(long.len() > MAX_LENGTH);
+        assert_eq!(decode(&long), Err(CodecError::InvalidLength { encoding: "Bech32", length: long.len() }));
+        // Gesetzte Auffüllbits bzw. fünf und mehr überzählige Bits
+        assert_eq!(from_words(&[0, 0]), Ok(vec![0]));
+        assert_eq!(from_words(&[0, 1]), Err(malformed("Auffüllung ungleich Null")));
+        assert_eq!(from_words(&[0, 0, 0]), Err(malformed("Auffüllung ungleich Null")));
+    }
+}
@@ -0,0 +1,57 @@
+//! Lesen und Schreiben von Dateien mit einheitlichen Fehlermeldungen
+
+use std::path::Path;
+
+use crate::{CodecError, hex};
+
+/// Liest eine Binärdatei vollständig ein
+pub fn read_binary(path: impl AsRef<Path>) -> Result<Vec<u8>, CodecError> {
+    let path = path.as_ref();
+    std::fs::read(path).map_err(|e| io_error(path, e))
+}
+
+/// Schreibt Bytes in eine Datei, eine vorhandene Datei wird ersetzt
+pub fn write_binary(path: impl AsRef<Path>, data: &[u8]) -> Result<(), CodecError> {
+    let path = path.as_ref();
+    std::fs::write(path, data).map_err(|e| io_error(path, e))
+}
+
+/// Liest eine Textdatei vollständig ein
+pub fn read_text(path: impl AsRef<Path>) -> Result<String, CodecError> {
+    let path = path.as_ref();
+    std::fs::read_to_string(path).map_err(|e| io_error(path, e))
+}
+
+/// Liest eine Datei mit Hexadezimalziffern und dekodiert sie
+pub fn read_hex(path: impl AsRef<Path>) -> Result<Vec<u8>, CodecError> {
+    hex::decode(&read_text(path)?)
+}
+
+/// Schreibt Bytes als Hexadezimalziffern in Kleinbuchstaben
+pub fn write_hex(path: impl AsRef<Path>, data: &[u8]) -> Result<(), CodecError> {
+    write_binary(path, hex::encode(data).as_bytes())
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> CodecError {
+    CodecError::Io { path: path.display().to_string(), message: error.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_file_roundtrip() {
+        let path = std::env::temp_dir().join(format!("codec_test_{}.hex", std::process::id()));
+        write_hex(&path, b"\x00\x01\xfe").unwrap();
+        assert_eq!(read_text(&path).unwrap(), "0001fe");
+        assert_eq!(read_hex(&path).unwrap(), b"\x00\x01\xfe");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let error = read_binary("/nicht/vorhanden").unwrap_err();
+        assert!(matches!(error, CodecError::Io { ref path, .. } if path == "/nicht/vorhanden"));
+    }
+}
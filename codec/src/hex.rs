@@ -0,0 +1,84 @@
+//! Hexadezimalkodierung
+
+use crate::{CodecError, significant_chars};
+
+/// Kodiert Bytes als Hexadezimalziffern in Kleinbuchstaben
+pub fn encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Kodiert Bytes als Hexadezimalziffern in Großbuchstaben
+pub fn encode_upper(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// Dekodiert Hexadezimalziffern, Whitespace wird ignoriert
+///
+/// Groß- und Kleinschreibung sind gleichwertig. Eine ungerade Anzahl an
+/// Ziffern ist ein Fehler.
+pub fn decode(text: &str) -> Result<Vec<u8>, CodecError> {
+    let digits = digits(text)?;
+    if !digits.len().is_multiple_of(2) {
+        return Err(CodecError::InvalidLength { encoding: "Hex", length: digits.len() });
+    }
+
+    Ok(digits.chunks(2).map(|pair| (pair[0] << 4) | pair[1]).collect())
+}
+
+/// Dekodiert Hexadezimalziffern zu 16-Bit-Blöcken (je 4 Ziffern, Big Endian)
+///
+/// Ein unvollständiger letzter Block wird rechts mit Nullen aufgefüllt,
+/// wie es die SPN-Werkzeuge im ECB-Modus erwarten.
+pub fn decode_words(text: &str) -> Result<Vec<u16>, CodecError> {
+    let mut digits = digits(text)?;
+    while !digits.len().is_multiple_of(4) {
+        digits.push(0);
+    }
+
+    Ok(digits.chunks(4)
+        .map(|block| block.iter().fold(0u16, |word, &digit| (word << 4) | u16::from(digit)))
+        .collect())
+}
+
+/// Kodiert 16-Bit-Blöcke als je 4 Hexadezimalziffern in Großbuchstaben
+pub fn encode_words(words: &[u16]) -> String {
+    words.iter().map(|word| format!("{:04X}", word)).collect()
+}
+
+/// Wert jeder Hexadezimalziffer des Textes
+fn digits(text: &str) -> Result<Vec<u8>, CodecError> {
+    significant_chars(text)
+        .map(|(position, character)| {
+            character.to_digit(16)
+                .map(|digit| digit as u8)
+                .ok_or(CodecError::InvalidCharacter { character, position })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let bytes = decode("48656c6C6f 00ff").unwrap();
+        assert_eq!(bytes, b"Hello\x00\xff");
+        assert_eq!(encode(&bytes), "48656c6c6f00ff");
+        assert_eq!(encode_upper(&bytes), "48656C6C6F00FF");
+        assert_eq!(decode("").unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(decode("abc"), Err(CodecError::InvalidLength { encoding: "Hex", length: 3 }));
+        assert_eq!(decode("a g0"), Err(CodecError::InvalidCharacter { character: 'g', position: 2 }));
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(decode_words("1234 ABCD 5").unwrap(), vec![0x1234, 0xABCD, 0x5000]);
+        assert_eq!(encode_words(&[0x1234, 0x00AB]), "123400AB");
+        assert!(decode_words("12X4").is_err());
+    }
+}
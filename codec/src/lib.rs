@@ -0,0 +1,59 @@
+//! Gemeinsame Kodierungen für die Kommandozeilenwerkzeuge
+//!
+//! Schlüssel, Klartexte und Hashwerte werden zwischen den Werkzeugen als
+//! Hexadezimal-, Base32-, Base64- oder Base58-Text ausgetauscht. Für
+//! Fingerabdrücke und Adressen, die Menschen abtippen, gibt es zusätzlich
+//! Base58Check und Bech32 mit Prüfsumme. Alle Dekodierer ignorieren
+//! Whitespace und melden Fehler über denselben Typ [`CodecError`], damit die
+//! Fehlermeldungen in allen Werkzeugen gleich aussehen.
+
+use std::error::Error;
+use std::fmt;
+
+pub mod base32;
+pub mod base58;
+pub mod base64;
+pub mod bech32;
+pub mod file;
+pub mod hex;
+
+/// Fehler beim Dekodieren oder beim Dateizugriff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodecError {
+    /// Zeichen gehört nicht zum Alphabet der Kodierung
+    InvalidCharacter { character: char, position: usize },
+    /// Anzahl der Zeichen passt nicht zur Kodierung
+    InvalidLength { encoding: &'static str, length: usize },
+    /// Base64-Auffüllung (`=`) an falscher Stelle oder in falscher Anzahl
+    InvalidPadding,
+    /// Prüfsumme passt nicht zu den Daten, meist ein Tippfehler
+    InvalidChecksum { encoding: &'static str },
+    /// Aufbau verletzt die Regeln der Kodierung, etwa ein fehlendes Trennzeichen
+    Malformed { encoding: &'static str, reason: &'static str },
+    /// Datei konnte nicht gelesen oder geschrieben werden
+    Io { path: String, message: String },
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CodecError::InvalidCharacter { character, position } => {
+                write!(f, "Ungültiges Zeichen '{}' an Position {}", character.escape_default(), position)
+            }
+            CodecError::InvalidLength { encoding, length } => {
+                write!(f, "Ungültige Länge für {}: {} Zeichen", encoding, length)
+            }
+            CodecError::InvalidPadding => write!(f, "Ungültige Base64-Auffüllung"),
+            CodecError::InvalidChecksum { encoding } => write!(f, "{}-Prüfsumme stimmt nicht", encoding),
+            CodecError::Malformed { encoding, reason } => write!(f, "Ungültiges {}: {}", encoding, reason),
+            CodecError::Io { path, message } => write!(f, "Dateifehler bei '{}': {}", path, message),
+        }
+    }
+}
+
+impl Error for CodecError {}
+
+/// Zeichen ohne Whitespace, jeweils mit ihrer Position im ursprünglichen Text
+fn significant_chars(text: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    text.chars().enumerate().filter(|(_, c)| !c.is_whitespace())
+}
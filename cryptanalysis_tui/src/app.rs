@@ -0,0 +1,268 @@
+//! State of an interactive Vigenère/additive cryptanalysis session.
+//!
+//! The key is kept as one shift per column. Columns the user locked are never
+//! touched by the automatic solver, so manual corrections survive changes of
+//! the key length and re-runs of the frequency analysis.
+
+use classical::{count_frequencies, index_of_coincidence, rank_shifts, Alphabet};
+
+/// Largest key length the initial estimate considers
+const MAX_KEY_LENGTH: usize = 20;
+
+/// The shortest key length whose average IC reaches this share of the
+/// language's IC is taken; longer keys inflate the IC of short columns
+const KEY_LENGTH_IC_RATIO: f64 = 0.85;
+
+pub struct App {
+    alphabet: Alphabet,
+    expected: Vec<f64>,
+    ciphertext: String,
+    clean: Vec<char>,
+    shifts: Vec<usize>,
+    locked: Vec<bool>,
+    selected: usize,
+    fixed_length: bool,
+    pub status: String,
+}
+
+impl App {
+    /// Starts a session and makes a first key guess.
+    ///
+    /// Without an explicit key length it is estimated from the column IC.
+    /// With `fixed_length` the key length can't be changed (additive cipher).
+    pub fn new(ciphertext: String, alphabet: Alphabet, expected: Vec<f64>, key_length: Option<usize>, fixed_length: bool) -> Self {
+        let clean: Vec<char> = alphabet.normalize(&ciphertext).chars().collect();
+        let mut app = App {
+            alphabet,
+            expected,
+            ciphertext,
+            clean,
+            shifts: Vec::new(),
+            locked: Vec::new(),
+            selected: 0,
+            fixed_length,
+            status: String::new(),
+        };
+
+        let key_length = key_length.unwrap_or_else(|| app.estimate_key_length());
+        app.set_key_length(key_length);
+        app
+    }
+
+    /// Picks the shortest key length whose average column IC is close to the
+    /// language's IC, or the one with the best IC if none is.
+    fn estimate_key_length(&self) -> usize {
+        let ics: Vec<(usize, f64)> = (1..=MAX_KEY_LENGTH.min(self.clean.len().max(1)))
+            .map(|length| {
+                let total: f64 = (0..length)
+                    .map(|column| index_of_coincidence(&count_frequencies(&self.column(column, length), &self.alphabet)))
+                    .sum();
+                (length, total / length as f64)
+            })
+            .collect();
+
+        let reference_ic: f64 = self.expected.iter().map(|p| p * p).sum();
+        ics.iter()
+            .find(|&&(_, ic)| ic >= reference_ic * KEY_LENGTH_IC_RATIO)
+            .or_else(|| ics.iter().max_by(|a, b| a.1.total_cmp(&b.1)))
+            .map(|&(length, _)| length)
+            .unwrap_or(1)
+    }
+
+    /// Every `length`-th symbol of the cleaned ciphertext, starting at `column`.
+    fn column(&self, column: usize, length: usize) -> String {
+        self.clean.iter().skip(column).step_by(length).collect()
+    }
+
+    pub fn key_length(&self) -> usize {
+        self.shifts.len()
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn is_locked(&self, column: usize) -> bool {
+        self.locked[column]
+    }
+
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    pub fn expected(&self) -> &[f64] {
+        &self.expected
+    }
+
+    /// The current key guess.
+    pub fn key(&self) -> String {
+        self.shifts.iter().map(|&shift| self.alphabet.symbol(shift)).collect()
+    }
+
+    /// Changes the key length; locked letters keep their position, all
+    /// other columns are solved again.
+    pub fn set_key_length(&mut self, length: usize) {
+        if length == 0 || (self.fixed_length && !self.shifts.is_empty()) {
+            return;
+        }
+
+        self.shifts.resize(length, 0);
+        self.locked.resize(length, false);
+        self.selected = self.selected.min(length - 1);
+        self.auto_solve();
+    }
+
+    /// Sets every unlocked column to the shift with the lowest chi-square.
+    pub fn auto_solve(&mut self) {
+        let length = self.key_length();
+        for column in 0..length {
+            if !self.locked[column] {
+                let counts = count_frequencies(&self.column(column, length), &self.alphabet);
+                self.shifts[column] = rank_shifts(&counts, &self.expected)[0].0;
+            }
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.key_length();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = (self.selected + self.key_length() - 1) % self.key_length();
+    }
+
+    /// Moves the key letter of the selected column by `delta` positions.
+    pub fn adjust(&mut self, delta: isize) {
+        if self.locked[self.selected] {
+            self.status = format!("Column {} is locked", self.selected + 1);
+            return;
+        }
+
+        let n = self.alphabet.len() as isize;
+        let shift = self.shifts[self.selected] as isize + delta;
+        self.shifts[self.selected] = shift.rem_euclid(n) as usize;
+    }
+
+    /// Sets the key letter of the selected column directly.
+    pub fn set_letter(&mut self, c: char) {
+        match self.alphabet.index_of(c) {
+            Some(_) if self.locked[self.selected] => {
+                self.status = format!("Column {} is locked", self.selected + 1);
+            }
+            Some(shift) => {
+                self.shifts[self.selected] = shift;
+                self.select_next();
+            }
+            None => self.status = format!("'{}' is not part of the alphabet", c),
+        }
+    }
+
+    pub fn toggle_lock(&mut self) {
+        self.locked[self.selected] = !self.locked[self.selected];
+    }
+
+    /// Relative frequencies of the selected column after decryption, in
+    /// alphabet order, together with its chi-square against the language.
+    pub fn column_histogram(&self) -> (Vec<f64>, f64) {
+        let length = self.key_length();
+        let counts = count_frequencies(&self.column(self.selected, length), &self.alphabet);
+        let shift = self.shifts[self.selected];
+        let total: u32 = counts.iter().sum();
+        let n = counts.len();
+
+        let observed = (0..n)
+            .map(|i| if total == 0 { 0.0 } else { counts[(i + shift) % n] as f64 / total as f64 })
+            .collect();
+        (observed, classical::chi_squared(&counts, &self.expected, shift))
+    }
+
+    /// Decrypts the full input with the current key, keeping its formatting.
+    pub fn plaintext(&self) -> String {
+        let mut position = 0;
+        self.ciphertext.chars()
+            .map(|c| {
+                if self.alphabet.index_of(c).is_some() {
+                    let shift = self.shifts[position % self.shifts.len()];
+                    position += 1;
+                    self.alphabet.unshift(c, shift)
+                } else {
+                    c
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use classical::Language;
+
+    fn encrypt(text: &str, key: &str) -> String {
+        let alphabet = Alphabet::latin();
+        let shifts = alphabet.key_shifts(key).unwrap();
+        let mut position = 0;
+        text.chars()
+            .map(|c| match alphabet.index_of(c) {
+                Some(_) => {
+                    position += 1;
+                    alphabet.shift(c, shifts[(position - 1) % shifts.len()])
+                }
+                None => c,
+            })
+            .collect()
+    }
+
+    const TEXT: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+        it was the season of light, it was the season of darkness, it was the spring of hope";
+
+    fn app(key_length: Option<usize>) -> App {
+        let alphabet = Alphabet::latin();
+        let expected = Language::En.table().expected(&alphabet);
+        App::new(encrypt(TEXT, "KEY"), alphabet, expected, key_length, false)
+    }
+
+    #[test]
+    fn test_initial_guess() {
+        let app = app(None);
+        assert_eq!(app.key(), "KEY");
+        assert_eq!(app.plaintext(), TEXT);
+    }
+
+    #[test]
+    fn test_locked_letters_survive_length_changes() {
+        let mut app = app(Some(3));
+        app.adjust(1);
+        app.toggle_lock();
+        assert_eq!(app.key(), "LEY");
+
+        app.set_key_length(6);
+        assert_eq!(app.key(), "LEYKEY");
+        app.auto_solve();
+        assert_eq!(app.key(), "LEYKEY");
+
+        app.adjust(-1);
+        assert_eq!(app.key(), "LEYKEY");
+    }
+
+    #[test]
+    fn test_set_letter_advances_selection() {
+        let mut app = app(Some(3));
+        app.set_letter('a');
+        assert_eq!(app.key(), "AEY");
+        assert_eq!(app.selected(), 1);
+        app.set_letter('!');
+        assert_eq!(app.key(), "AEY");
+    }
+
+    #[test]
+    fn test_column_histogram_follows_shift() {
+        let app = app(Some(3));
+        let (observed, chi_squared) = app.column_histogram();
+        assert!((observed.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        // In English plaintext T and E are among the most common letters
+        assert!(observed[4] > 0.05 || observed[19] > 0.05);
+        assert!(chi_squared < 100.0);
+    }
+}
@@ -0,0 +1,136 @@
+//! Interactive cryptanalysis of Vigenère and additive (Caesar) ciphertexts.
+//!
+//! The terminal shows the current key guess, the letter histogram of the
+//! selected key column after decryption and a live preview of the plaintext.
+//! Key letters can be adjusted and locked by hand; the automatic frequency
+//! analysis only ever changes unlocked columns.
+
+use clap::{Parser, ValueEnum};
+use logging::{English, Verbosity};
+use tool_error::ToolError;
+use classical::{Alphabet, Language};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::DefaultTerminal;
+
+use app::App;
+
+mod app;
+mod ui;
+
+/// Command-line arguments for the interactive cryptanalysis program.
+#[derive(Parser, Debug)]
+struct Cli {
+    /// Path to the input file containing encrypted text
+    #[arg(short, long, help = "Path to the input file containing encrypted text")]
+    file: String,
+
+    /// Where the plaintext is saved with Ctrl-S
+    #[arg(short, long, help = "Path to the output file for decrypted text")]
+    output: Option<String>,
+
+    /// Cipher the text was encrypted with
+    #[arg(short, long, value_enum, default_value = "vigenere", help = "Cipher (vigenere/additive)")]
+    mode: CipherMode,
+
+    /// Initial key length, estimated from the IC if omitted
+    #[arg(short, long, help = "Initial key length (default: estimated)")]
+    key_length: Option<usize>,
+
+    /// Language of the plaintext, selects the built-in frequency table
+    #[arg(short, long, value_enum, default_value = "de", help = "Plaintext language (de, en, fr, es, it)")]
+    language: Language,
+
+    /// Alphabet the ciphertext was encrypted over
+    #[arg(short, long, default_value = "latin", help = "Alphabet: latin, german, alnum, printable or a literal list of symbols")]
+    alphabet: String,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+/// Enum representing the cipher under analysis.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CipherMode {
+    /// Vigenère cipher, the key length can be changed
+    Vigenere,
+    /// Additive cipher, a Vigenère key of length 1
+    Additive,
+}
+
+/// Runs the tool with the given command-line arguments
+///
+/// The first argument is the program name, as with `std::env::args_os`,
+/// so the tool can also be started from `cryptolab`.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+    let ciphertext: String = tool_error::fs::read_to_string(&cli.file)?;
+
+    let alphabet = Alphabet::parse(&cli.alphabet).map_err(ToolError::usage)?;
+    if alphabet.normalize(&ciphertext).is_empty() {
+        return Err(ToolError::data("Input contains no symbols of the alphabet"));
+    }
+
+    let expected = cli.language.table().expected(&alphabet);
+    let (key_length, fixed_length) = match cli.mode {
+        CipherMode::Vigenere => (cli.key_length, false),
+        CipherMode::Additive => (Some(1), true),
+    };
+    let mut app = App::new(ciphertext, alphabet, expected, key_length, fixed_length);
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app, cli.output.as_deref());
+    ratatui::restore();
+
+    result?;
+
+    // Print the final key so it can be used with the cipher tools
+    println!("{}", app.key());
+    Ok(())
+}
+
+/// Event loop: redraws after every key press until the user quits.
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App, output: Option<&str>) -> std::io::Result<()> {
+    loop {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        app.status.clear();
+
+        match key.code {
+            KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => save(app, output),
+            KeyCode::Left => app.select_previous(),
+            KeyCode::Right => app.select_next(),
+            KeyCode::Up => app.adjust(1),
+            KeyCode::Down => app.adjust(-1),
+            KeyCode::Char(' ') => app.toggle_lock(),
+            KeyCode::Char('+') => app.set_key_length(app.key_length() + 1),
+            KeyCode::Char('-') => app.set_key_length(app.key_length() - 1),
+            KeyCode::Tab => app.auto_solve(),
+            KeyCode::Char(c) => app.set_letter(c),
+            _ => {}
+        }
+    }
+}
+
+/// Writes the current plaintext to the output file, if one was given.
+fn save(app: &mut App, output: Option<&str>) {
+    app.status = match output {
+        Some(path) => match std::fs::write(path, app.plaintext()) {
+            Ok(()) => format!("Saved plaintext to {}", path),
+            Err(e) => format!("Failed to write {}: {}", path, e),
+        },
+        None => "No output file given (use --output)".to_string(),
+    };
+}
@@ -0,0 +1,5 @@
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    tool_error::report(cryptanalysis_tui::run(std::env::args_os()))
+}
@@ -0,0 +1,88 @@
+//! Rendering of the cryptanalysis session.
+
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Borders, Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::app::App;
+
+/// Help line shown at the bottom of the screen
+const HELP: &str = "←/→ column  ↑/↓ shift  letter: set key  Space lock  +/- length  Tab auto-solve  Ctrl-S save  Esc quit";
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(14),
+            Constraint::Min(5),
+            Constraint::Length(1),
+        ])
+        .split(frame.area());
+
+    frame.render_widget(key_line(app), chunks[0]);
+    frame.render_widget(histogram(app), chunks[1]);
+
+    let preview = Paragraph::new(app.plaintext())
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL).title("Decrypted preview"));
+    frame.render_widget(preview, chunks[2]);
+
+    let footer = if app.status.is_empty() { HELP.to_string() } else { app.status.clone() };
+    frame.render_widget(Paragraph::new(footer).style(Style::default().fg(Color::DarkGray)), chunks[3]);
+}
+
+/// The key with the selected column highlighted and locked letters marked.
+fn key_line(app: &App) -> Paragraph<'static> {
+    let mut spans = Vec::new();
+    for (column, letter) in app.key().chars().enumerate() {
+        let mut style = Style::default();
+        if app.is_locked(column) {
+            style = style.fg(Color::Yellow).add_modifier(Modifier::UNDERLINED);
+        }
+        if column == app.selected() {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        spans.push(Span::styled(letter.to_string(), style));
+    }
+
+    let title = format!("Key (length {}, column {})", app.key_length(), app.selected() + 1);
+    Paragraph::new(Line::from(spans))
+        .block(Block::default().borders(Borders::ALL).title(title))
+}
+
+/// Observed frequencies of the selected column next to the expected ones.
+fn histogram(app: &App) -> BarChart<'static> {
+    let (observed, chi_squared) = app.column_histogram();
+    let expected = app.expected();
+
+    let mut chart = BarChart::default()
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Column {}: observed (cyan) vs. expected (gray), chi-square {:.1}",
+            app.selected() + 1,
+            chi_squared
+        )))
+        .bar_width(1)
+        .bar_gap(0)
+        .group_gap(1);
+
+    for (i, (&observed, &expected)) in observed.iter().zip(expected).enumerate() {
+        // Bars need integer values, use tenths of a percent
+        let bars = [
+            Bar::default()
+                .value((observed * 1000.0).round() as u64)
+                .text_value(String::new())
+                .style(Style::default().fg(Color::Cyan)),
+            Bar::default()
+                .value((expected * 1000.0).round() as u64)
+                .text_value(String::new())
+                .style(Style::default().fg(Color::Gray)),
+        ];
+        let label = Line::from(app.alphabet().symbol(i).to_string());
+        chart = chart.data(BarGroup::default().label(label).bars(&bars));
+    }
+
+    chart
+}
@@ -0,0 +1,256 @@
+//! Faktorisierung: Probedivision, Pollard-Rho, Pollard p - 1 und Fermat
+//!
+//! Jedes Verfahren sucht einen echten Teiler einer zusammengesetzten Zahl n
+//! und ist für eine andere Schwäche gebaut:
+//!
+//! - Probedivision findet kleine Faktoren bis zu einer Schranke.
+//! - Pollard-Rho braucht etwa √p Schritte für den kleinsten Primfaktor p,
+//!   unabhängig von n.
+//! - Pollard p - 1 findet p, wenn p - 1 nur kleine Primfaktoren hat.
+//! - Fermat findet p und q, wenn beide nah bei √n liegen.
+//!
+//! Gegen RSA-Moduln aus zwei zufälligen, gleich großen Primzahlen hilft
+//! keines davon. Dafür gibt es das [`quadratic_sieve`], dessen Laufzeit
+//! nur von der Größe von n abhängt; in dieser einfachen Form reicht es bis
+//! [`QS_MAX_BITS`] Bit. Die langen Verfahren brechen zu einer Frist
+//! `deadline` ab und liefern dann `None`.
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use std::time::Instant;
+
+use crate::{exact_root, gcd, integer_root, mod_pow};
+
+mod qs;
+
+pub use qs::{MAX_BITS as QS_MAX_BITS, quadratic_sieve};
+
+/// Schritte zwischen zwei Blicken auf die Uhr und zwei ggT-Berechnungen
+const BATCH: u64 = 128;
+
+/// Kleinster Primfaktor bis `bound`, sofern er kleiner als n ist
+pub fn trial_division(n: &BigUint, bound: u32) -> Option<BigUint> {
+    let mut divisor = 2u32;
+    while divisor <= bound && BigUint::from(divisor) * divisor <= *n {
+        if (n % divisor).is_zero() {
+            return Some(BigUint::from(divisor));
+        }
+        divisor += if divisor == 2 { 1 } else { 2 };
+    }
+    None
+}
+
+/// |a - b|
+fn distance(a: &BigUint, b: &BigUint) -> BigUint {
+    if a > b { a - b } else { b - a }
+}
+
+/// Pollard-Rho mit Brents Zyklensuche
+///
+/// Die Folge x ↦ x² + c mod n wiederholt sich modulo eines Primfaktors p
+/// nach etwa √p Schritten, lange bevor sie es modulo n tut; ggT(x - y, n)
+/// deckt p dann auf. Die Differenzen werden gesammelt multipliziert, damit
+/// nur jeder `BATCH`-te Schritt einen ggT kostet.
+pub fn pollard_rho(n: &BigUint, deadline: Instant) -> Option<BigUint> {
+    if n <= &BigUint::from(3u32) {
+        return None;
+    }
+    if (n % 2u32).is_zero() {
+        return Some(BigUint::from(2u32));
+    }
+
+    let one = BigUint::one();
+    for c in 1u32.. {
+        let f = |x: &BigUint| (x * x + c) % n;
+        let mut y = BigUint::from(2u32);
+        let mut x = y.clone();
+        let mut saved = y.clone();
+        let mut product = one.clone();
+        let mut divisor = one.clone();
+        let mut length = 1u64;
+        while divisor.is_one() {
+            x = y.clone();
+            for _ in 0..length {
+                y = f(&y);
+            }
+            let mut done = 0;
+            while done < length && divisor.is_one() {
+                if Instant::now() > deadline {
+                    return None;
+                }
+                saved = y.clone();
+                for _ in 0..BATCH.min(length - done) {
+                    y = f(&y);
+                    product = product * distance(&x, &y) % n;
+                }
+                divisor = gcd(&product, n);
+                done += BATCH;
+            }
+            length *= 2;
+        }
+
+        // Ein ganzer Block ist über das Ziel hinaus: einzeln nachrechnen
+        if divisor == *n {
+            loop {
+                saved = f(&saved);
+                divisor = gcd(&distance(&x, &saved), n);
+                if !divisor.is_one() {
+                    break;
+                }
+            }
+        }
+        if divisor != *n {
+            return Some(divisor);
+        }
+    }
+    unreachable!("c durchläuft alle u32")
+}
+
+/// Pollard p - 1, erste Stufe
+///
+/// Nach Fermat ist a^k ≡ 1 (mod p), sobald p - 1 ein Teiler von k ist. Mit
+/// k = bound! reicht es, wenn alle Primpotenzen in p - 1 höchstens `bound`
+/// sind; ggT(a^k - 1, n) enthält dann p. Fallen alle Primfaktoren bei
+/// demselben k heraus, versucht es die nächste Basis aus [`P_MINUS_1_BASES`].
+pub fn pollard_p_minus_1(n: &BigUint, bound: u64, deadline: Instant) -> Option<BigUint> {
+    if n <= &BigUint::from(3u32) {
+        return None;
+    }
+    for base in P_MINUS_1_BASES {
+        let base = BigUint::from(base);
+        // Eine Basis mit gemeinsamem Teiler verrät ihn direkt, etwa 2 bei geradem n
+        let divisor = gcd(&base, n);
+        if divisor == *n {
+            continue;
+        }
+        if !divisor.is_one() {
+            return Some(divisor);
+        }
+        match p_minus_1_with_base(n, base, bound, deadline) {
+            Some(divisor) if divisor == *n => continue,
+            result => return result,
+        }
+    }
+    None
+}
+
+/// Basen für [`pollard_p_minus_1`], der Reihe nach
+const P_MINUS_1_BASES: [u32; 4] = [2, 3, 5, 7];
+
+/// Pollard p - 1 mit einer Basis teilerfremd zu n; n selbst, wenn alle
+/// Primfaktoren bei demselben Exponenten herausfallen
+fn p_minus_1_with_base(n: &BigUint, base: BigUint, bound: u64, deadline: Instant) -> Option<BigUint> {
+    let mut a = base;
+    // a vor dem ersten Exponenten des laufenden Blocks
+    let mut checkpoint = (a.clone(), 2);
+    for k in 2..=bound {
+        a = mod_pow(&a, &BigUint::from(k), n);
+        if k.is_multiple_of(BATCH) || k == bound {
+            let divisor = gcd(&(&a - 1u32), n);
+            if divisor == *n {
+                // Ein ganzer Block ist über das Ziel hinaus: einzeln nachrechnen
+                let (mut a, first) = checkpoint;
+                return (first..=k).find_map(|k| {
+                    a = mod_pow(&a, &BigUint::from(k), n);
+                    let divisor = gcd(&(&a - 1u32), n);
+                    (!divisor.is_one()).then_some(divisor)
+                });
+            }
+            if !divisor.is_one() {
+                return Some(divisor);
+            }
+            if Instant::now() > deadline {
+                return None;
+            }
+            checkpoint = (a.clone(), k + 1);
+        }
+    }
+    None
+}
+
+/// Fermat: n = a² - b² = (a - b)(a + b) für a ab ⌈√n⌉
+///
+/// Für n = p·q sind a = (p + q)/2 und b = (q - p)/2; liegen p und q nah
+/// beieinander, ist a nur wenig größer als √n.
+pub fn fermat(n: &BigUint, deadline: Instant) -> Option<BigUint> {
+    if n <= &BigUint::from(3u32) {
+        return None;
+    }
+    if (n % 2u32).is_zero() {
+        return Some(BigUint::from(2u32));
+    }
+    let root = integer_root(n, 2);
+    let mut a = if &root * &root == *n { return Some(root) } else { root + 1u32 };
+    let one = BigUint::one();
+    let mut steps = 0u64;
+    loop {
+        if let Some(b) = exact_root(&(&a * &a - n), 2) {
+            let divisor = &a - b;
+            // a - b = 1 heißt n = 1·n, dann ist n prim
+            return (divisor != one).then_some(divisor);
+        }
+        if steps.is_multiple_of(BATCH) && Instant::now() > deadline {
+            return None;
+        }
+        a += 1u32;
+        steps += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn big(n: u64) -> BigUint {
+        BigUint::from(n)
+    }
+
+    fn soon() -> Instant {
+        Instant::now() + Duration::from_secs(30)
+    }
+
+    #[test]
+    fn test_trial_division() {
+        assert_eq!(trial_division(&big(1_000_003 * 101), 1000), Some(big(101)));
+        assert_eq!(trial_division(&big(1_000_003 * 1_000_033), 1000), None);
+        assert_eq!(trial_division(&big(1_000_003), 1 << 20), None);
+        assert_eq!(trial_division(&big(64), 10), Some(big(2)));
+    }
+
+    #[test]
+    fn test_pollard_rho() {
+        // Zwei 32-Bit-Primzahlen: etwa 2^16 Schritte
+        let n = big(4_294_967_291) * big(4_294_967_279);
+        let p = pollard_rho(&n, soon()).unwrap();
+        assert!(p == big(4_294_967_291) || p == big(4_294_967_279));
+        assert!(matches!(pollard_rho(&big(91), soon()), Some(p) if p == big(7) || p == big(13)));
+        assert_eq!(pollard_rho(&big(1 << 20), soon()), Some(big(2)));
+        // Eine Primzahl zerfällt nicht, die Frist beendet die Suche
+        assert_eq!(pollard_rho(&big(1_000_003), Instant::now()), None);
+    }
+
+    #[test]
+    fn test_pollard_p_minus_1() {
+        // 1_299_709 - 1 = 2^2 · 3^2 · 79 · 457, 2_147_483_659 - 1 = 2 · 3 · 149 · 2402107
+        let n = big(1_299_709) * big(2_147_483_659);
+        assert_eq!(pollard_p_minus_1(&n, 500, soon()), Some(big(1_299_709)));
+        assert_eq!(pollard_p_minus_1(&n, 400, soon()), None);
+
+        // Gerade Zahlen, und beide Faktoren im selben Block von BATCH Exponenten
+        assert_eq!(pollard_p_minus_1(&big(4), 100, soon()), Some(big(2)));
+        assert_eq!(pollard_p_minus_1(&big(1 << 20), 100, soon()), Some(big(2)));
+        assert!(matches!(pollard_p_minus_1(&big(101 * 103), 1000, soon()), Some(p) if p == big(101) || p == big(103)));
+        assert_eq!(pollard_p_minus_1(&big(25), 1000, soon()), Some(big(5)));
+        assert_eq!(pollard_p_minus_1(&big(10_007), 1000, soon()), None);
+    }
+
+    #[test]
+    fn test_fermat() {
+        let (p, q) = (big(4_294_967_291), big(4_294_967_311));
+        assert_eq!(fermat(&(&p * &q), soon()), Some(p.clone()));
+        assert_eq!(fermat(&(&p * &p), soon()), Some(p));
+        assert_eq!(fermat(&big(10_007), soon()), None);
+        assert_eq!(fermat(&big(1_000_003 * 3), Instant::now()), None);
+    }
+}
@@ -0,0 +1,293 @@
+//! Quadratisches Sieb (Pomerance, 1981), einfache Variante mit einem Polynom
+//!
+//! Gesucht sind x und y mit x² ≡ y² (mod n), aber x ≢ ±y; dann ist
+//! ggT(x - y, n) ein echter Teiler. Mit m = ⌈√n⌉ und Q(t) = (t + m)² - n gilt
+//! (t + m)² ≡ Q(t) (mod n). Das Sieb sucht t, für die Q(t) nur Primfaktoren
+//! aus einer Faktorbasis hat (eine Relation). Die Exponentenvektoren modulo 2
+//! sind Vektoren über GF(2); sobald es mehr Relationen als Primzahlen gibt,
+//! ist eine Teilmenge linear abhängig. Das Produkt ihrer Q(t) ist dann ein
+//! Quadrat y², das Produkt der (t + m) liefert x.
+//!
+//! Gesiebt wird mit Logarithmen: p teilt Q(t) genau dann, wenn t + m eine
+//! der beiden Wurzeln von n modulo p ist. Jede Primzahl addiert log p an
+//! diesen Stellen; wo die Summe fast log Q(t) erreicht, lohnt die
+//! Probedivision. Die Faktorbasis enthält nur p mit (n/p) = 1, die anderen
+//! teilen Q(t) nie.
+//!
+//! Gerechnet wird in `u128`, das reicht für n bis [`MAX_BITS`] Bit. Für
+//! größere n bräuchte es mehrere Polynome und große Primfaktoren.
+
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive};
+use std::time::Instant;
+
+use crate::{gcd, integer_root};
+
+/// Größte Eingabe in Bit, damit (t + m)² in ein `u128` passt
+pub const MAX_BITS: u64 = 120;
+
+/// Siebintervall pro Durchgang
+const BLOCK: usize = 1 << 16;
+
+/// Relationen über die Größe der Faktorbasis hinaus, für mehrere Abhängigkeiten
+const EXTRA_RELATIONS: usize = 16;
+
+fn mul_mod(a: u64, b: u64, p: u64) -> u64 {
+    (u128::from(a) * u128::from(b) % u128::from(p)) as u64
+}
+
+fn pow_mod(mut base: u64, mut exponent: u64, p: u64) -> u64 {
+    let mut result = 1 % p;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, p);
+        }
+        base = mul_mod(base, base, p);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Wurzel von a modulo der Primzahl p (Tonelli-Shanks), `None` für Nichtreste
+fn sqrt_mod(a: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+    if p == 2 || a == 0 {
+        return Some(a);
+    }
+    // Euler-Kriterium
+    if pow_mod(a, (p - 1) / 2, p) != 1 {
+        return None;
+    }
+    // p - 1 = q · 2^s mit q ungerade
+    let s = (p - 1).trailing_zeros();
+    let q = (p - 1) >> s;
+    let z = (2..p).find(|&z| pow_mod(z, (p - 1) / 2, p) == p - 1)?;
+    let (mut m, mut c, mut t, mut r) = (s, pow_mod(z, q, p), pow_mod(a, q, p), pow_mod(a, q.div_ceil(2), p));
+    while t != 1 {
+        // Kleinstes i mit t^(2^i) = 1
+        let mut i = 0;
+        let mut power = t;
+        while power != 1 {
+            power = mul_mod(power, power, p);
+            i += 1;
+        }
+        let b = pow_mod(c, 1 << (m - i - 1), p);
+        m = i;
+        c = mul_mod(b, b, p);
+        t = mul_mod(t, c, p);
+        r = mul_mod(r, b, p);
+    }
+    Some(r)
+}
+
+/// Alle Primzahlen bis `limit` (Sieb des Eratosthenes)
+fn primes_up_to(limit: u64) -> Vec<u64> {
+    let mut composite = vec![false; limit as usize + 1];
+    let mut primes = Vec::new();
+    for n in 2..=limit {
+        if !composite[n as usize] {
+            primes.push(n);
+            (n * n..=limit).step_by(n as usize).for_each(|k| composite[k as usize] = true);
+        }
+    }
+    primes
+}
+
+/// Schranke der Faktorbasis, etwa L(n)^(1/2) mit L(n) = exp(√(ln n · ln ln n))
+fn factor_base_bound(bits: u64) -> u64 {
+    let ln = bits as f64 * std::f64::consts::LN_2;
+    ((0.5 * (ln * ln.ln()).sqrt()).exp() * 2.0).clamp(100.0, 1e6) as u64
+}
+
+/// Primzahl der Faktorbasis mit den Stellen t mod p, an denen sie Q(t) teilt
+struct Prime {
+    p: u64,
+    log: u8,
+    offsets: Vec<u64>,
+}
+
+/// t + m und die Zerlegung von Q(t) als (Index in der Faktorbasis, Exponent)
+struct Relation {
+    a: u128,
+    exponents: Vec<(usize, u32)>,
+}
+
+/// Teilmengen der Relationen, deren Exponentenvektoren modulo 2 null ergeben
+///
+/// Gauß-Elimination über GF(2); jede Zeile merkt sich, aus welchen
+/// ursprünglichen Zeilen sie entstanden ist.
+fn dependencies(relations: &[Relation], columns: usize) -> Vec<Vec<usize>> {
+    let words = |bits: usize| bits.div_ceil(64);
+    let mut rows: Vec<(Vec<u64>, Vec<u64>)> = relations
+        .iter()
+        .enumerate()
+        .map(|(index, relation)| {
+            let mut bits = vec![0u64; words(columns)];
+            for &(column, exponent) in &relation.exponents {
+                bits[column / 64] ^= u64::from(exponent & 1) << (column % 64);
+            }
+            let mut history = vec![0u64; words(relations.len())];
+            history[index / 64] |= 1 << (index % 64);
+            (bits, history)
+        })
+        .collect();
+
+    let mut pivot = vec![false; rows.len()];
+    for column in 0..columns {
+        let bit = |row: &(Vec<u64>, Vec<u64>)| row.0[column / 64] >> (column % 64) & 1 == 1;
+        let Some(chosen) = (0..rows.len()).find(|&r| !pivot[r] && bit(&rows[r])) else {
+            continue;
+        };
+        pivot[chosen] = true;
+        let (pivot_bits, pivot_history) = rows[chosen].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r != chosen && bit(row) {
+                row.0.iter_mut().zip(&pivot_bits).for_each(|(word, pivot)| *word ^= pivot);
+                row.1.iter_mut().zip(&pivot_history).for_each(|(word, pivot)| *word ^= pivot);
+            }
+        }
+    }
+
+    rows.iter()
+        .zip(&pivot)
+        .filter(|((bits, _), is_pivot)| !**is_pivot && bits.iter().all(|&word| word == 0))
+        .map(|((_, history), _)| (0..relations.len()).filter(|&i| history[i / 64] >> (i % 64) & 1 == 1).collect())
+        .collect()
+}
+
+/// Echter Teiler von n mit dem quadratischen Sieb
+///
+/// `None` für n über [`MAX_BITS`] Bit, für Primzahlpotenzen und wenn die
+/// Frist abläuft.
+pub fn quadratic_sieve(n: &BigUint, deadline: Instant) -> Option<BigUint> {
+    if n.bits() > MAX_BITS || *n < BigUint::from(4u32) {
+        return None;
+    }
+    let value = n.to_u128()?;
+    if value.is_multiple_of(2) {
+        return Some(BigUint::from(2u32));
+    }
+    let root = integer_root(n, 2).to_u128()?;
+    if root * root == value {
+        return Some(BigUint::from(root));
+    }
+    let m = root + 1;
+
+    let bound = factor_base_bound(n.bits());
+    let mut base = Vec::new();
+    for p in primes_up_to(bound) {
+        let residue = (value % u128::from(p)) as u64;
+        if residue == 0 {
+            return (u128::from(p) != value).then(|| BigUint::from(p));
+        }
+        if let Some(r) = sqrt_mod(residue, p) {
+            // t + m ≡ ±r (mod p)
+            let m_mod = (m % u128::from(p)) as u64;
+            let mut offsets = vec![(r + p - m_mod) % p, (2 * p - r - m_mod) % p];
+            offsets.dedup();
+            base.push(Prime { p, log: (p as f64).log2().round() as u8, offsets });
+        }
+    }
+
+    // Relationen sammeln
+    let needed = base.len() + EXTRA_RELATIONS;
+    let slack = (bound as f64).log2() as u8 + 4;
+    let mut relations = Vec::with_capacity(needed);
+    let mut sieve = vec![0u8; BLOCK];
+    let mut start = 0u128;
+    while relations.len() < needed {
+        if Instant::now() > deadline {
+            return None;
+        }
+        sieve.fill(0);
+        for prime in &base {
+            let position = (start % u128::from(prime.p)) as u64;
+            for &offset in &prime.offsets {
+                let first = ((offset + prime.p - position) % prime.p) as usize;
+                for entry in sieve.iter_mut().skip(first).step_by(prime.p as usize) {
+                    *entry = entry.saturating_add(prime.log);
+                }
+            }
+        }
+
+        let end = start + BLOCK as u128;
+        let threshold = ((end + m) * (end + m) - value).ilog2().saturating_sub(u32::from(slack)) as u8;
+        for (i, _) in sieve.iter().enumerate().filter(|&(_, &sum)| sum >= threshold) {
+            let a = start + i as u128 + m;
+            let mut q = a * a - value;
+            let mut exponents = Vec::new();
+            for (index, prime) in base.iter().enumerate() {
+                let p = u128::from(prime.p);
+                let mut exponent = 0;
+                while q.is_multiple_of(p) {
+                    q /= p;
+                    exponent += 1;
+                }
+                if exponent > 0 {
+                    exponents.push((index, exponent));
+                }
+            }
+            if q == 1 && relations.len() < needed {
+                relations.push(Relation { a, exponents });
+            }
+        }
+        start = end;
+    }
+
+    // Kongruenzen von Quadraten aus den Abhängigkeiten
+    for dependency in dependencies(&relations, base.len()) {
+        let mut x = BigUint::one();
+        let mut totals = vec![0u32; base.len()];
+        for &r in &dependency {
+            x = x * BigUint::from(relations[r].a) % n;
+            for &(index, exponent) in &relations[r].exponents {
+                totals[index] += exponent;
+            }
+        }
+        let y = totals.iter().zip(&base).fold(BigUint::one(), |y, (&total, prime)| {
+            y * BigUint::from(prime.p).modpow(&BigUint::from(total / 2), n) % n
+        });
+        let divisor = gcd(&((x + n - y) % n), n);
+        if !divisor.is_one() && divisor != *n {
+            return Some(divisor);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_sqrt_mod() {
+        for p in [2u64, 3, 5, 13, 17, 41, 65537, 1_000_003] {
+            for a in 0..50 {
+                match sqrt_mod(a, p) {
+                    Some(r) => assert_eq!(mul_mod(r, r, p), a % p, "a = {}, p = {}", a, p),
+                    None => assert_eq!(pow_mod(a, (p - 1) / 2, p), p - 1),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dependencies() {
+        // Q-Werte 2·3, 3·5, 2·5 und 7: nur die ersten drei ergeben zusammen ein Quadrat
+        let relation = |exponents: Vec<(usize, u32)>| Relation { a: 0, exponents };
+        let relations = [relation(vec![(0, 1), (1, 1)]), relation(vec![(1, 1), (2, 1)]), relation(vec![(0, 1), (2, 1)]), relation(vec![(3, 1)])];
+        assert_eq!(dependencies(&relations, 4), [vec![0, 1, 2]]);
+    }
+
+    #[test]
+    fn test_quadratic_sieve() {
+        let soon = || Instant::now() + Duration::from_secs(60);
+        // 2^31 - 1 und 2^32 - 5
+        let (p, q) = (BigUint::from(2_147_483_647u64), BigUint::from(4_294_967_291u64));
+        let divisor = quadratic_sieve(&(&p * &q), soon()).unwrap();
+        assert!(divisor == p || divisor == q);
+        assert_eq!(quadratic_sieve(&BigUint::from(1_000_003u64 * 1_000_003), soon()), Some(BigUint::from(1_000_003u64)));
+        assert_eq!(quadratic_sieve(&(BigUint::one() << 121), soon()), None);
+    }
+}
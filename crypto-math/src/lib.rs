@@ -0,0 +1,412 @@
+//! Zahlentheoretische Grundlagen für RSA, DSA und Diffie-Hellman
+//!
+//! Modulare Exponentiation (auch mit gleichem Ablauf für geheime
+//! Exponenten), Miller-Rabin-Primzahltest, erweiterter
+//! euklidischer Algorithmus und modulares Inverses. Für die Angriffe auf
+//! RSA kommen Chinesischer Restsatz, ganzzahlige k-te Wurzeln und
+//! Kettenbrüche hinzu, für Faktorisierungsangriffe das Modul [`factor`].
+//! Die Werkzeuge nutzen diese gemeinsame Implementierung, statt jeweils eine
+//! eigene Variante mitzubringen.
+
+use num_bigint::{BigInt, BigUint, RandBigInt};
+use num_traits::{One, Signed, Zero};
+use secret::ct;
+
+pub mod factor;
+
+/// Modulare Exponentiation: berechnet (base^exp) mod modulus
+///
+/// Square-and-Multiply über die Bits des Exponenten, vom niedrigsten zum
+/// höchsten. Für `modulus` = 1 ist das Ergebnis 0.
+///
+/// Multipliziert wird nur bei gesetzten Bits, die Laufzeit verrät also den
+/// Exponenten. Für geheime Exponenten gibt es [`mod_pow_ct`].
+pub fn mod_pow(base: &BigUint, exp: &BigUint, modulus: &BigUint) -> BigUint {
+    square_and_multiply(base, exp, modulus, || {})
+}
+
+/// [`mod_pow`], ruft aber nach jedem Bit des Exponenten `after_bit` auf
+///
+/// Damit misst die Zeitangriffs-Demo, wie lange die einzelnen Schritte dauern.
+pub fn square_and_multiply(base: &BigUint, exp: &BigUint, modulus: &BigUint, mut after_bit: impl FnMut()) -> BigUint {
+    if modulus.is_one() {
+        return BigUint::zero();
+    }
+
+    let mut result = BigUint::one();
+    let mut base = base % modulus;
+
+    for i in 0..exp.bits() {
+        if exp.bit(i) {
+            result = (result * &base) % modulus;
+        }
+        base = (&base * &base) % modulus;
+        after_bit();
+    }
+
+    result
+}
+
+/// Modulare Exponentiation mit gleichem Ablauf für jedes Bit des Exponenten
+///
+/// Montgomery-Leiter: Jeder Schritt multipliziert und quadriert genau einmal,
+/// welches der beiden Zwischenergebnisse wohin gehört, entscheidet ein
+/// Tausch in konstanter Zeit. Es werden mindestens so viele Bits bearbeitet,
+/// wie der Modul hat, sodass auch führende Nullen des Exponenten nicht
+/// auffallen.
+///
+/// `num-bigint` selbst rechnet nicht in konstanter Zeit; Zwischenergebnisse
+/// mit führenden Nullziffern sind etwas schneller. Der Unterschied hängt aber
+/// nicht mehr an den Bits des Exponenten.
+pub fn mod_pow_ct(base: &BigUint, exp: &BigUint, modulus: &BigUint) -> BigUint {
+    montgomery_ladder(base, exp, modulus, || {})
+}
+
+/// [`mod_pow_ct`], ruft aber nach jedem Bit des Exponenten `after_bit` auf
+pub fn montgomery_ladder(base: &BigUint, exp: &BigUint, modulus: &BigUint, mut after_bit: impl FnMut()) -> BigUint {
+    if modulus.is_one() {
+        return BigUint::zero();
+    }
+
+    let mut low = BigUint::one();
+    let mut high = base % modulus;
+
+    for i in (0..exp.bits().max(modulus.bits())).rev() {
+        // Bei gesetztem Bit vertauscht, damit beide Fälle dieselben Schritte gehen
+        let bit = exp.bit(i);
+        let (a, b) = (ct::select_biguint(bit, &high, &low), ct::select_biguint(bit, &low, &high));
+        high = (&a * &b) % modulus;
+        low = (&a * &a) % modulus;
+        (low, high) = (ct::select_biguint(bit, &high, &low), ct::select_biguint(bit, &low, &high));
+        after_bit();
+    }
+
+    low
+}
+
+/// Miller-Rabin Primzahltest
+///
+/// Probabilistischer Test mit `rounds` zufälligen Basen. Eine Primzahl wird
+/// immer erkannt, eine zusammengesetzte Zahl fälschlich mit höchstens
+/// (1/4)^rounds als prim eingestuft.
+pub fn is_probable_prime(n: &BigUint, rounds: u32) -> bool {
+    let two = BigUint::from(2u32);
+    if n < &two {
+        return false;
+    }
+    if n == &two || n == &BigUint::from(3u32) {
+        return true;
+    }
+    if !n.bit(0) {
+        return false;
+    }
+
+    // Schreibe n-1 = 2^s * d mit d ungerade
+    let n_minus_1 = n - 1u32;
+    let s = n_minus_1.trailing_zeros().expect("n - 1 ist nicht 0");
+    let d = &n_minus_1 >> s;
+
+    let mut rng = rand::thread_rng();
+
+    'rounds: for _ in 0..rounds {
+        // Zufällige Basis a ∈ [2, n-2]
+        let a = rng.gen_biguint_range(&two, &n_minus_1);
+        let mut x = mod_pow(&a, &d, n);
+
+        if x.is_one() || x == n_minus_1 {
+            continue;
+        }
+
+        // Bis zu s-1 mal quadrieren, bis -1 erscheint
+        for _ in 1..s {
+            x = (&x * &x) % n;
+            if x == n_minus_1 {
+                continue 'rounds;
+            }
+        }
+
+        return false; // a ist Zeuge: n ist zusammengesetzt
+    }
+
+    true // Wahrscheinlich prim
+}
+
+/// Größter gemeinsamer Teiler, ggT(0, 0) = 0
+pub fn gcd(a: &BigUint, b: &BigUint) -> BigUint {
+    let (mut a, mut b) = (a.clone(), b.clone());
+    while !b.is_zero() {
+        (a, b) = (b.clone(), a % b);
+    }
+    a
+}
+
+/// Erweiterter euklidischer Algorithmus
+///
+/// Liefert (g, x, y) mit a·x + b·y = g = ggT(a, b) und g ≥ 0. Die
+/// Koeffizienten sind im Allgemeinen negativ und werden daher als `BigInt`
+/// geführt; auch negative Eingaben sind erlaubt.
+pub fn extended_gcd(a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
+    // Invariante: old_r = a·old_x + b·old_y und r = a·x + b·y
+    let (mut old_r, mut r) = (a.clone(), b.clone());
+    let (mut old_x, mut x) = (BigInt::one(), BigInt::zero());
+    let (mut old_y, mut y) = (BigInt::zero(), BigInt::one());
+
+    while !r.is_zero() {
+        let quotient = &old_r / &r;
+        (old_r, r) = (r.clone(), &old_r - &quotient * &r);
+        (old_x, x) = (x.clone(), &old_x - &quotient * &x);
+        (old_y, y) = (y.clone(), &old_y - &quotient * &y);
+    }
+
+    if old_r.is_negative() {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// Modulares Inverses von a modulo m
+///
+/// `None`, wenn a und m nicht teilerfremd sind oder m < 2 ist.
+pub fn mod_inverse(a: &BigUint, m: &BigUint) -> Option<BigUint> {
+    if m < &BigUint::from(2u32) {
+        return None;
+    }
+
+    let m = BigInt::from(m.clone());
+    let (gcd, x, _) = extended_gcd(&BigInt::from(a.clone()), &m);
+    if !gcd.is_one() {
+        return None;
+    }
+
+    // x auf den Bereich [0, m) normalisieren
+    (((x % &m) + &m) % &m).to_biguint()
+}
+
+/// Chinesischer Restsatz für paarweise teilerfremde Moduln
+///
+/// Aus Kongruenzen x ≡ r_i (mod m_i), gegeben als Paare (r_i, m_i), wird
+/// das eindeutige x in [0, M) mit M = ∏ m_i berechnet. Liefert (x, M), oder
+/// `None`, wenn ein Modul null ist oder zwei Moduln einen gemeinsamen
+/// Teiler haben.
+pub fn crt(congruences: &[(BigUint, BigUint)]) -> Option<(BigUint, BigUint)> {
+    let mut x = BigUint::zero();
+    let mut modulus = BigUint::one();
+    for (residue, m) in congruences {
+        if m.is_zero() {
+            return None;
+        }
+        if m.is_one() {
+            continue;
+        }
+        // x + modulus·t ≡ residue (mod m)  ⇒  t = (residue - x)·modulus⁻¹ mod m
+        let inverse = mod_inverse(&(&modulus % m), m)?;
+        let difference = (residue % m + m - &x % m) % m;
+        let t = difference * inverse % m;
+        x += &modulus * t;
+        modulus *= m;
+    }
+    Some((x, modulus))
+}
+
+/// Ganzzahlige k-te Wurzel, abgerundet: das größte r mit r^k ≤ n
+///
+/// Newton-Verfahren r ← ((k-1)·r + n / r^(k-1)) / k, beginnend oberhalb
+/// der Wurzel; die Folge fällt dann streng, bis sie die Wurzel erreicht.
+pub fn integer_root(n: &BigUint, k: u32) -> BigUint {
+    assert!(k > 0, "Die 0-te Wurzel ist nicht definiert");
+    if n.is_zero() || k == 1 {
+        return n.clone();
+    }
+
+    // 2^⌈bits/k⌉ liegt sicher über der Wurzel
+    let mut root = BigUint::one() << n.bits().div_ceil(u64::from(k));
+    loop {
+        let next = (&root * (k - 1) + n / root.pow(k - 1)) / k;
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+/// Exakte k-te Wurzel, falls n eine k-te Potenz ist
+pub fn exact_root(n: &BigUint, k: u32) -> Option<BigUint> {
+    let root = integer_root(n, k);
+    (&root.pow(k) == n).then_some(root)
+}
+
+/// Kettenbruchentwicklung [a_0; a_1, a_2, …] des Bruchs numerator/denominator
+///
+/// Die Teilnenner sind die Quotienten des euklidischen Algorithmus. Für
+/// denominator = 0 ist die Entwicklung leer.
+pub fn continued_fraction(numerator: &BigUint, denominator: &BigUint) -> Vec<BigUint> {
+    let (mut a, mut b) = (numerator.clone(), denominator.clone());
+    let mut terms = Vec::new();
+    while !b.is_zero() {
+        terms.push(&a / &b);
+        (a, b) = (b.clone(), &a % &b);
+    }
+    terms
+}
+
+/// Näherungsbrüche (p_i, q_i) eines Kettenbruchs
+///
+/// p_i = a_i·p_(i-1) + p_(i-2) und ebenso für q_i. Der Satz von Wiener
+/// nutzt, dass k/d unter diesen Näherungen von e/n vorkommt, wenn d klein
+/// genug ist.
+pub fn convergents(terms: &[BigUint]) -> Vec<(BigUint, BigUint)> {
+    let (mut p, mut previous_p) = (BigUint::one(), BigUint::zero());
+    let (mut q, mut previous_q) = (BigUint::zero(), BigUint::one());
+    terms
+        .iter()
+        .map(|a| {
+            (p, previous_p) = (a * &p + &previous_p, p.clone());
+            (q, previous_q) = (a * &q + &previous_q, q.clone());
+            (p.clone(), q.clone())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mod_pow() {
+        let big = |n: u32| BigUint::from(n);
+        assert_eq!(mod_pow(&big(2), &big(10), &big(1000)), big(24)); // 1024 mod 1000
+        assert_eq!(mod_pow(&big(7), &big(0), &big(13)), big(1));
+        assert_eq!(mod_pow(&big(7), &big(5), &big(1)), big(0));
+        assert_eq!(mod_pow(&big(123456), &big(65537), &big(1000003)), big(123456).modpow(&big(65537), &big(1000003)));
+    }
+
+    #[test]
+    fn test_mod_pow_ct() {
+        let big = |n: u32| BigUint::from(n);
+        assert_eq!(mod_pow_ct(&big(2), &big(10), &big(1000)), big(24));
+        assert_eq!(mod_pow_ct(&big(7), &big(0), &big(13)), big(1));
+        assert_eq!(mod_pow_ct(&big(7), &big(5), &big(1)), big(0));
+        assert_eq!(mod_pow_ct(&big(1000), &big(3), &big(13)), big(1000).modpow(&big(3), &big(13)));
+
+        let modulus: BigUint = "191677726143854315674598428926330859363608329588293420380333240985455824521179813035337110510248831071087008746466405096838035678566522676019644319734501401554761112169596389".parse().unwrap();
+        let base = BigUint::from(0xC0FFEEu32);
+        for exp in [big(1), big(65537), &modulus - 2u32, &modulus << 40] {
+            assert_eq!(mod_pow_ct(&base, &exp, &modulus), base.modpow(&exp, &modulus));
+        }
+    }
+
+    #[test]
+    fn test_steps() {
+        let big = |n: u32| BigUint::from(n);
+        let mut steps = 0;
+        square_and_multiply(&big(3), &big(0b10110), &big(1000), || steps += 1);
+        assert_eq!(steps, 5);
+        // Die Leiter läuft mindestens über alle Bits des Moduls
+        steps = 0;
+        montgomery_ladder(&big(3), &big(0b10110), &big(1000), || steps += 1);
+        assert_eq!(steps, 10);
+    }
+
+    #[test]
+    fn test_is_probable_prime() {
+        for prime in [2u32, 3, 5, 17, 97, 7919, 1_000_003] {
+            assert!(is_probable_prime(&BigUint::from(prime), 20), "{} ist prim", prime);
+        }
+        // 561 und 41041 sind Carmichael-Zahlen, die den Fermat-Test bestehen
+        for composite in [0u32, 1, 4, 15, 21, 561, 41041, 1_000_001] {
+            assert!(!is_probable_prime(&BigUint::from(composite), 20), "{} ist zusammengesetzt", composite);
+        }
+
+        // Mersenne-Primzahl 2^127 - 1
+        let mersenne = (BigUint::one() << 127) - 1u32;
+        assert!(is_probable_prime(&mersenne, 20));
+    }
+
+    #[test]
+    fn test_gcd() {
+        let big = |n: u32| BigUint::from(n);
+        assert_eq!(gcd(&big(240), &big(46)), big(2));
+        assert_eq!(gcd(&big(46), &big(240)), big(2));
+        assert_eq!(gcd(&big(17), &big(0)), big(17));
+        assert_eq!(gcd(&big(0), &big(0)), big(0));
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        let cases = [(240, 46), (46, 240), (-240, 46), (240, -46), (-240, -46), (17, 0), (0, -17), (0, 0)];
+        for (a, b) in cases {
+            let (a, b) = (BigInt::from(a), BigInt::from(b));
+            let (gcd, x, y) = extended_gcd(&a, &b);
+            assert!(!gcd.is_negative());
+            assert_eq!(&a * &x + &b * &y, gcd, "a = {}, b = {}", a, b);
+        }
+        assert_eq!(extended_gcd(&BigInt::from(240), &BigInt::from(46)).0, BigInt::from(2));
+        assert_eq!(extended_gcd(&BigInt::from(-240), &BigInt::from(46)).0, BigInt::from(2));
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        let big = |n: u32| BigUint::from(n);
+        assert_eq!(mod_inverse(&big(3), &big(11)), Some(big(4)));
+        assert_eq!(mod_inverse(&big(65537), &big(3120)), Some(big(2753)));
+        // Koeffizient x ist hier negativ: 10·(-5) + 17·3 = 1
+        assert_eq!(mod_inverse(&big(10), &big(17)), Some(big(12)));
+        assert_eq!(mod_inverse(&big(27), &big(10)), Some(big(3)));
+        assert_eq!(mod_inverse(&big(6), &big(9)), None);
+        assert_eq!(mod_inverse(&big(5), &big(1)), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        let big = |n: u32| BigUint::from(n);
+        // Sunzi: x ≡ 2 (mod 3), x ≡ 3 (mod 5), x ≡ 2 (mod 7)
+        let congruences = [(big(2), big(3)), (big(3), big(5)), (big(2), big(7))];
+        assert_eq!(crt(&congruences), Some((big(23), big(105))));
+        // Reste dürfen größer als ihr Modul sein
+        assert_eq!(crt(&[(big(17), big(4)), (big(1), big(9))]), Some((big(1), big(36))));
+        assert_eq!(crt(&[]), Some((big(0), big(1))));
+        assert_eq!(crt(&[(big(1), big(4)), (big(3), big(6))]), None);
+        assert_eq!(crt(&[(big(1), big(0))]), None);
+
+        // Håstad: m³ ist größer als jeder einzelne Modul, aber kleiner als ihr Produkt
+        let m = BigUint::from(0xC0_FFEE_BEEFu64);
+        let moduli = [big(1_000_003) * big(1_000_033), big(1_000_037) * big(1_000_039), big(1_000_081) * big(1_000_099)];
+        let congruences: Vec<_> = moduli.iter().map(|n| (m.pow(3) % n, n.clone())).collect();
+        let (cube, _) = crt(&congruences).unwrap();
+        assert_eq!(exact_root(&cube, 3), Some(m));
+    }
+
+    #[test]
+    fn test_integer_root() {
+        let big = |n: u32| BigUint::from(n);
+        assert_eq!(integer_root(&big(0), 3), big(0));
+        assert_eq!(integer_root(&big(1), 5), big(1));
+        assert_eq!(integer_root(&big(26), 3), big(2));
+        assert_eq!(integer_root(&big(27), 3), big(3));
+        assert_eq!(integer_root(&big(28), 3), big(3));
+        assert_eq!(integer_root(&big(99), 1), big(99));
+        assert_eq!(integer_root(&big(1_000_000), 2), big(1000));
+
+        let r: BigUint = "123456789012345678901234567890".parse().unwrap();
+        for k in [2, 3, 7] {
+            let power = r.pow(k);
+            assert_eq!(integer_root(&power, k), r);
+            assert_eq!(integer_root(&(&power - 1u32), k), &r - 1u32);
+            assert_eq!(exact_root(&power, k), Some(r.clone()));
+            assert_eq!(exact_root(&(&power + 1u32), k), None);
+        }
+    }
+
+    #[test]
+    fn test_continued_fraction() {
+        let big = |n: u32| BigUint::from(n);
+        // 415/93 = [4; 2, 6, 7]
+        let terms = continued_fraction(&big(415), &big(93));
+        assert_eq!(terms, [big(4), big(2), big(6), big(7)]);
+        assert_eq!(convergents(&terms), [(big(4), big(1)), (big(9), big(2)), (big(58), big(13)), (big(415), big(93))]);
+        assert_eq!(continued_fraction(&big(3), &big(7)), [big(0), big(2), big(3)]);
+        assert!(continued_fraction(&big(1), &big(0)).is_empty());
+        assert!(convergents(&[]).is_empty());
+    }
+}
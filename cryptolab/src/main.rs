@@ -0,0 +1,154 @@
+//! One binary for all tools of the workspace.
+//!
+//! Every tool is still its own crate with its own command-line interface;
+//! `cryptolab` only picks the tool from the leading words of the command line
+//! and hands the remaining arguments to it unchanged:
+//!
+//! ```text
+//! cryptolab aes --help
+//! cryptolab rsa keygen -b 2048
+//! cryptolab vigenere crack -f cipher.txt
+//! ```
+//!
+//! The program name seen by the tool is `cryptolab <words>`, so usage lines
+//! and error messages show the command that was actually typed.
+
+use std::ffi::OsString;
+use std::process::ExitCode;
+
+use tool_error::ExitStatus;
+
+type Runner = fn(Vec<OsString>) -> tool_error::Result<()>;
+
+/// A subcommand and the tool that handles it
+struct Tool {
+    /// Words that select the tool, e.g. `["rsa", "keygen"]`
+    path: &'static [&'static str],
+    about: &'static str,
+    run: Runner,
+}
+
+const TOOLS: &[Tool] = &[
+    Tool { path: &["additive"], about: "Additive (Caesar) cipher with brute force", run: additive_cipher::run },
+    Tool { path: &["additive", "crack"], about: "Break an additive cipher by German letter frequencies", run: german_freq_decryptor::run },
+    Tool { path: &["adfgvx"], about: "ADFGVX cipher", run: adfgvx::run },
+    Tool { path: &["aes"], about: "AES-128 with several modes of operation", run: aes_128::run },
+    Tool { path: &["birthday"], about: "Birthday attack on truncated SHA3-224/SHA-256 hashes", run: birthday::run },
+    Tool { path: &["classic"], about: "Simple monoalphabetic ciphers (Atbash, affine, ...)", run: classic::run },
+    Tool { path: &["dh", "exchange"], about: "Diffie-Hellman key exchange", run: dh_exchange::run },
+    Tool { path: &["dh", "params"], about: "Generate Diffie-Hellman parameters", run: dh_params::run },
+    Tool { path: &["dlog"], about: "Discrete logarithms with baby-step giant-step, rho and Pohlig-Hellman", run: dlog::run },
+    Tool { path: &["dsa", "keygen"], about: "Generate DSA parameters and keys", run: dsa_keygen::run },
+    Tool { path: &["dsa", "sign"], about: "Create a DSA signature", run: dsa_sign::run },
+    Tool { path: &["dsa", "verify"], about: "Verify a DSA signature", run: dsa_verify::run },
+    Tool { path: &["ecb-detect"], about: "Detect ECB mode by repeated ciphertext blocks", run: ecb_detect::run },
+    Tool { path: &["entropy"], about: "Byte entropy, chi-square and serial correlation of a file", run: entropy::run },
+    Tool { path: &["factor"], about: "Factor integers and benchmark the factoring methods", run: factor::run },
+    Tool { path: &["handshake"], about: "TLS-style handshake and encrypted echo over TCP", run: handshake::run },
+    Tool { path: &["homophonic"], about: "Homophonic substitution with a hill-climbing solver", run: homophonic::run },
+    Tool { path: &["keystore"], about: "Passphrase-protected keystore for key files", run: keystore::run },
+    Tool { path: &["mss"], about: "Merkle signature scheme with Winternitz one-time signatures", run: mss::run },
+    Tool { path: &["otp"], about: "One-time pad", run: otp::run },
+    Tool { path: &["otp-token"], about: "HOTP/TOTP one-time passwords", run: otp_token::run },
+    Tool { path: &["padding-oracle"], about: "CBC padding-oracle attack demo", run: padding_oracle::run },
+    Tool { path: &["power-analysis"], about: "Correlation power analysis on simulated AES traces", run: power_analysis::run },
+    Tool { path: &["prng-crack"], about: "Predict LCG, Mersenne Twister and LFSR outputs", run: prng_crack::run },
+    Tool { path: &["pwcrack"], about: "Dictionary attack on pwhash password hashes", run: pwcrack::run },
+    Tool { path: &["pwhash"], about: "Password hashing with Argon2id, scrypt and PBKDF2", run: pwhash::run },
+    Tool { path: &["rail-fence"], about: "Rail-fence transposition cipher", run: rail_fence::run },
+    Tool { path: &["randtest"], about: "NIST SP 800-22 randomness tests", run: randtest::run },
+    Tool { path: &["ratchet"], about: "Double-ratchet messaging demo with X25519 and AES-GCM", run: ratchet::run },
+    Tool { path: &["rsa"], about: "Textbook RSA encryption and decryption", run: rsa::run },
+    Tool { path: &["rsa", "keygen"], about: "Generate RSA keys", run: rsa_keygen::run },
+    Tool { path: &["seal"], about: "Signed and encrypted message containers", run: seal::run },
+    Tool { path: &["sha3"], about: "SHA3-224 hash", run: sha3::run },
+    Tool { path: &["spn"], about: "SPN cipher from the lecture", run: lineare_analysis::run },
+    Tool { path: &["spn", "bruteforce"], about: "Exhaustive key search for the SPN", run: spn_bruteforce::run },
+    Tool { path: &["spn", "linear"], about: "Linear cryptanalysis of the SPN", run: linear_approximation::run },
+    Tool { path: &["timing-attack"], about: "Timing attack demo against square-and-multiply RSA", run: timing_attack::run },
+    Tool { path: &["tui"], about: "Interactive cryptanalysis of Vigenere and additive ciphers", run: cryptanalysis_tui::run },
+    Tool { path: &["vigenere"], about: "Vigenere cipher", run: vigenere::run },
+    Tool { path: &["vigenere", "crack"], about: "Break a Vigenere cipher", run: vigenere_decrypter::run },
+    Tool { path: &["x509"], about: "Minimal X.509 certificates and chain verification", run: x509_lite::run },
+    Tool { path: &["xor", "crack"], about: "Break repeating-key XOR", run: xor_decrypter::run },
+];
+
+/// Finds the tool whose path is the longest prefix of `args`
+///
+/// `args` excludes the program name. Returns the tool and the number of
+/// words its path consumed.
+fn resolve(args: &[OsString]) -> Option<&'static Tool> {
+    TOOLS.iter()
+        .filter(|tool| {
+            tool.path.len() <= args.len()
+                && tool.path.iter().zip(args).all(|(word, arg)| arg.to_str() == Some(*word))
+        })
+        .max_by_key(|tool| tool.path.len())
+}
+
+/// Builds the argument list for the tool: `cryptolab <path>` as program name
+/// followed by everything after the path
+fn tool_args(tool: &Tool, args: Vec<OsString>) -> Vec<OsString> {
+    let program = format!("cryptolab {}", tool.path.join(" "));
+    std::iter::once(OsString::from(program))
+        .chain(args.into_iter().skip(tool.path.len()))
+        .collect()
+}
+
+fn usage() -> String {
+    let mut text = String::from("Usage: cryptolab <tool> [ARGS]...\n\nTools:\n");
+    let width = TOOLS.iter().map(|tool| tool.path.join(" ").len()).max().unwrap_or(0);
+    for tool in TOOLS {
+        text.push_str(&format!("  {:width$}  {}\n", tool.path.join(" "), tool.about));
+    }
+    text.push_str("\nRun 'cryptolab <tool> --help' for the options of a tool.");
+    text
+}
+
+fn main() -> ExitCode {
+    let args: Vec<OsString> = std::env::args_os().skip(1).collect();
+
+    let first = args.first().and_then(|arg| arg.to_str());
+    if matches!(first, None | Some("help" | "--help" | "-h")) {
+        println!("{}", usage());
+        return ExitCode::SUCCESS;
+    }
+
+    let Some(tool) = resolve(&args) else {
+        eprintln!("error: unknown tool '{}'\n\n{}", args[0].to_string_lossy(), usage());
+        return ExitStatus::Usage.into();
+    };
+
+    tool_error::report((tool.run)(tool_args(tool, args)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<OsString> {
+        line.split_whitespace().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn test_resolve_longest_path() {
+        assert_eq!(resolve(&args("rsa -k key.txt")).unwrap().path, ["rsa"]);
+        assert_eq!(resolve(&args("rsa keygen -b 512")).unwrap().path, ["rsa", "keygen"]);
+        assert_eq!(resolve(&args("vigenere crack")).unwrap().path, ["vigenere", "crack"]);
+        assert!(resolve(&args("dsa")).is_none());
+        assert!(resolve(&args("enigma")).is_none());
+    }
+
+    #[test]
+    fn test_tool_args() {
+        let tool = resolve(&args("dsa sign -m hello")).unwrap();
+        assert_eq!(tool_args(tool, args("dsa sign -m hello")), ["cryptolab dsa sign", "-m", "hello"]);
+    }
+
+    #[test]
+    fn test_paths_are_unique() {
+        for (index, tool) in TOOLS.iter().enumerate() {
+            assert!(TOOLS[index + 1..].iter().all(|other| other.path != tool.path));
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! ChaCha20 als Zufallsbitgenerator
+//!
+//! Der Schlüssel ist SHA-256 des Seeds, der Zähler beginnt bei 0. Die
+//! Ausgabe ist der ChaCha20-Schlüsselstrom in der Wortreihenfolge von
+//! `rand_chacha`, also nicht dieselbe wie bei einem ChaCha20-Chiffrat mit
+//! gleichem Schlüssel und Nonce 0.
+
+use rand_chacha::ChaCha20Rng;
+use rand_core::{RngCore, SeedableRng};
+use secret::Secret;
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone)]
+pub struct ChaChaDrbg {
+    rng: ChaCha20Rng,
+}
+
+impl ChaChaDrbg {
+    /// Leitet den Schlüssel aus beliebig langem Seed-Material ab
+    pub fn new(seed: &[u8]) -> Self {
+        let key = Secret::new(<[u8; 32]>::from(Sha256::digest(seed)));
+        ChaChaDrbg { rng: ChaCha20Rng::from_seed(*key) }
+    }
+
+    pub fn fill_bytes(&mut self, output: &mut [u8]) {
+        self.rng.fill_bytes(output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_is_hashed() {
+        // Gleicher Seed, gleiche Ausgabe; ein Bit mehr im Seed ändert alles
+        let (mut a, mut b, mut c) = ([0u8; 32], [0u8; 32], [0u8; 32]);
+        ChaChaDrbg::new(b"seed").fill_bytes(&mut a);
+        ChaChaDrbg::new(b"seed").fill_bytes(&mut b);
+        ChaChaDrbg::new(b"seee").fill_bytes(&mut c);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+}
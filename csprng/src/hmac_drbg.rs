@@ -0,0 +1,111 @@
+//! HMAC_DRBG mit SHA-256 nach NIST SP 800-90A, Abschnitt 10.1.2
+//!
+//! Der Zustand besteht nur aus dem Schlüssel K und dem Wert V, beide werden
+//! beim Freigeben überschrieben. Nach jeder Anfrage werden K und V mit
+//! `update` weitergedreht, frühere Ausgaben lassen sich aus dem Zustand
+//! also nicht zurückrechnen.
+
+use hmac::{Hmac, Mac};
+use secret::Secret;
+use sha2::Sha256;
+
+const OUTLEN: usize = 32;
+
+/// Höchstens so viele Bytes pro Anfrage (2^19 Bit laut SP 800-90A)
+pub const MAX_REQUEST: usize = 1 << 16;
+
+#[derive(Debug, Clone)]
+pub struct HmacDrbg {
+    key: Secret<[u8; OUTLEN]>,
+    value: Secret<[u8; OUTLEN]>,
+}
+
+impl HmacDrbg {
+    /// Instantiate: Zustand aus Entropie, Nonce und Personalisierung
+    pub fn new(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Self {
+        let mut drbg = HmacDrbg {
+            key: Secret::new([0x00; OUTLEN]),
+            value: Secret::new([0x01; OUTLEN]),
+        };
+        drbg.update(&[entropy, nonce, personalization]);
+        drbg
+    }
+
+    /// Mischt frische Entropie in den Zustand
+    pub fn reseed(&mut self, entropy: &[u8], additional: &[u8]) {
+        self.update(&[entropy, additional]);
+    }
+
+    /// Füllt `output` mit Zufallsbytes, höchstens [`MAX_REQUEST`] auf einmal
+    pub fn generate(&mut self, output: &mut [u8], additional: &[u8]) {
+        assert!(output.len() <= MAX_REQUEST, "Anfrage zu groß für HMAC_DRBG");
+
+        if !additional.is_empty() {
+            self.update(&[additional]);
+        }
+        for chunk in output.chunks_mut(OUTLEN) {
+            let value = self.hmac(&[&*self.value]);
+            *self.value = value;
+            chunk.copy_from_slice(&self.value[..chunk.len()]);
+        }
+        self.update(&[additional]);
+    }
+
+    /// HMAC_DRBG_Update, `data` ist die Verkettung der Teile
+    fn update(&mut self, data: &[&[u8]]) {
+        let empty = data.iter().all(|part| part.is_empty());
+        for round in [0x00u8, 0x01] {
+            if round == 0x01 && empty {
+                break;
+            }
+            let mut input: Vec<&[u8]> = vec![&*self.value, std::slice::from_ref(&round)];
+            input.extend_from_slice(data);
+            *self.key = self.hmac(&input);
+            *self.value = self.hmac(&[&*self.value]);
+        }
+    }
+
+    fn hmac(&self, parts: &[&[u8]]) -> [u8; OUTLEN] {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&*self.key).expect("HMAC akzeptiert jede Schlüssellänge");
+        for part in parts {
+            mac.update(part);
+        }
+        mac.finalize().into_bytes().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// CAVP HMAC_DRBG.rsp, SHA-256, ohne Prediction Resistance, COUNT = 0
+    #[test]
+    fn test_nist_vector() {
+        let entropy = codec::hex::decode("ca851911349384bffe89de1cbdc46e6831e44d34a4fb935ee285dd14b71a7488").unwrap();
+        let nonce = codec::hex::decode("659ba96c601dc69fc902940805ec0ca8").unwrap();
+        let expected = codec::hex::decode(concat!(
+            "e528e9abf2dece54d47c7e75e5fe302149f817ea9fb4bee6f4199697d04d5b89",
+            "d54fbb978a15b5c443c9ec21036d2460b6f73ebad0dc2aba6e624abf07745bc1",
+            "07694bb7547bb0995f70de25d6b29e2d3011bb19d27676c07162c8b5ccde0668",
+            "961df86803482cb37ed6d5c0bb8d50cf1f50d476aa0458bdaba806f48be9dcb8",
+        )).unwrap();
+
+        let mut drbg = HmacDrbg::new(&entropy, &nonce, &[]);
+        let mut output = [0u8; 128];
+        drbg.generate(&mut output, &[]);
+        drbg.generate(&mut output, &[]);
+        assert_eq!(output.to_vec(), expected);
+    }
+
+    #[test]
+    fn test_reseed_changes_output() {
+        let mut a = HmacDrbg::new(b"entropie", b"", b"");
+        let mut b = a.clone();
+        b.reseed(b"mehr entropie", b"");
+
+        let (mut x, mut y) = ([0u8; 16], [0u8; 16]);
+        a.generate(&mut x, &[]);
+        b.generate(&mut y, &[]);
+        assert_ne!(x, y);
+    }
+}
@@ -0,0 +1,210 @@
+//! Kryptographisch sichere Zufallszahlen für Schlüssel, Nonces und Primzahlen
+//!
+//! [`Drbg`] ist ein deterministischer Zufallsbitgenerator: entweder ChaCha20
+//! oder HMAC_DRBG mit SHA-256 (NIST SP 800-90A). Im Normalfall kommt der
+//! Seed vom Betriebssystem; mit einem festen Seed liefern die Werkzeuge bei
+//! jedem Lauf dieselben Schlüssel, was für Tests und Übungsaufgaben praktisch,
+//! für echte Schlüssel aber fatal ist.
+//!
+//! Werkzeuge binden [`RngArgs`] mit `#[command(flatten)]` ein und erhalten
+//! mit [`RngArgs::rng`] ihren Generator. `Drbg` implementiert `RngCore` und
+//! `CryptoRng` und lässt sich überall statt `thread_rng()` verwenden, etwa
+//! mit `RandBigInt` aus `num-bigint`.
+//!
+//! Zu Lehrzwecken gibt es mit `--drbg insecure-mt19937` auch den Mersenne
+//! Twister. Er ist nicht kryptographisch sicher; `prng_crack` sagt nach 624
+//! Ausgaben alle weiteren voraus, auch die Schlüssel.
+
+use clap::{Args, ValueEnum};
+use logging::warn;
+use rand_core::{CryptoRng, OsRng, RngCore};
+use secret::Secret;
+
+pub mod chacha;
+pub mod hmac_drbg;
+pub mod mt19937;
+
+use chacha::ChaChaDrbg;
+use hmac_drbg::HmacDrbg;
+use mt19937::Mt19937;
+
+/// Personalisierung von HMAC_DRBG, trennt diese Instanzen von anderen Anwendungen
+const PERSONALIZATION: &[u8] = b"hackthesys csprng";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Algorithm {
+    #[default]
+    #[value(name = "chacha20")]
+    ChaCha20,
+    #[value(name = "hmac-sha256")]
+    HmacSha256,
+    /// Mersenne Twister, nur für Demonstrationen
+    #[value(name = "insecure-mt19937")]
+    Mt19937,
+}
+
+#[derive(Debug, Clone)]
+pub enum Drbg {
+    ChaCha20(Box<ChaChaDrbg>),
+    HmacSha256(HmacDrbg),
+    Mt19937(Box<Mt19937>),
+}
+
+impl Drbg {
+    /// Generator mit Seed aus dem Betriebssystem
+    pub fn from_entropy(algorithm: Algorithm) -> Self {
+        let mut seed = Secret::new([0u8; 48]);
+        OsRng.fill_bytes(&mut *seed);
+        match algorithm {
+            Algorithm::ChaCha20 => Drbg::ChaCha20(Box::new(ChaChaDrbg::new(&*seed))),
+            // Die ersten 32 Bytes als Entropie, der Rest als Nonce
+            Algorithm::HmacSha256 => Drbg::HmacSha256(HmacDrbg::new(&seed[..32], &seed[32..], PERSONALIZATION)),
+            // Wie in den meisten Bibliotheken nur 32 Bit Seed
+            Algorithm::Mt19937 => Drbg::Mt19937(Box::new(Mt19937::new(u32::from_le_bytes([seed[0], seed[1], seed[2], seed[3]])))),
+        }
+    }
+
+    /// Reproduzierbarer Generator: gleicher Seed, gleiche Ausgabe
+    pub fn from_seed(algorithm: Algorithm, seed: &[u8]) -> Self {
+        match algorithm {
+            Algorithm::ChaCha20 => Drbg::ChaCha20(Box::new(ChaChaDrbg::new(seed))),
+            Algorithm::HmacSha256 => Drbg::HmacSha256(HmacDrbg::new(seed, &[], PERSONALIZATION)),
+            Algorithm::Mt19937 => {
+                let key: Vec<u32> = seed.chunks(4).map(|c| c.iter().rev().fold(0, |word, &b| word << 8 | u32::from(b))).collect();
+                Drbg::Mt19937(Box::new(Mt19937::from_key(&key)))
+            }
+        }
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        match self {
+            Drbg::ChaCha20(_) => Algorithm::ChaCha20,
+            Drbg::HmacSha256(_) => Algorithm::HmacSha256,
+            Drbg::Mt19937(_) => Algorithm::Mt19937,
+        }
+    }
+
+    /// Unabhängiger Generator, geseedet aus diesem, z. B. einer pro Thread
+    pub fn fork(&mut self) -> Drbg {
+        let mut seed = Secret::new([0u8; 32]);
+        self.fill_bytes(&mut *seed);
+        Drbg::from_seed(self.algorithm(), &*seed)
+    }
+}
+
+impl RngCore for Drbg {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            Drbg::ChaCha20(drbg) => drbg.fill_bytes(dest),
+            Drbg::HmacSha256(drbg) => {
+                for chunk in dest.chunks_mut(hmac_drbg::MAX_REQUEST) {
+                    drbg.generate(chunk, &[]);
+                }
+            }
+            Drbg::Mt19937(mt) => mt.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Gilt nicht für [`Algorithm::Mt19937`], das nur zur Demonstration existiert
+impl CryptoRng for Drbg {}
+
+/// Gemeinsame Schalter für Werkzeuge, die Schlüssel oder Nonces erzeugen
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RngArgs {
+    /// Fester Seed für reproduzierbare Läufe (nur für Tests, nie für echte Schlüssel)
+    #[arg(long, value_name = "SEED")]
+    seed: Option<String>,
+
+    /// Zufallsbitgenerator
+    #[arg(long, value_enum, default_value_t = Algorithm::ChaCha20)]
+    drbg: Algorithm,
+}
+
+impl RngArgs {
+    pub fn is_seeded(&self) -> bool {
+        self.seed.is_some()
+    }
+
+    /// Generator nach den Schaltern; mit `--seed` oder MT19937 wird gewarnt
+    pub fn rng(&self) -> Drbg {
+        if self.drbg == Algorithm::Mt19937 {
+            warn!("MT19937 ist nicht kryptographisch sicher: 624 Ausgaben verraten alle weiteren");
+        }
+        match &self.seed {
+            Some(seed) => {
+                warn!("Fester Seed: alle Zufallswerte sind reproduzierbar und damit nicht geheim");
+                Drbg::from_seed(self.drbg, seed.as_bytes())
+            }
+            None => Drbg::from_entropy(self.drbg),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        rng: RngArgs,
+    }
+
+    fn output(mut drbg: Drbg) -> [u8; 64] {
+        let mut bytes = [0u8; 64];
+        drbg.fill_bytes(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn test_seeded_is_reproducible() {
+        for algorithm in [Algorithm::ChaCha20, Algorithm::HmacSha256, Algorithm::Mt19937] {
+            assert_eq!(output(Drbg::from_seed(algorithm, b"42")), output(Drbg::from_seed(algorithm, b"42")));
+            assert_ne!(output(Drbg::from_seed(algorithm, b"42")), output(Drbg::from_seed(algorithm, b"43")));
+            assert_ne!(output(Drbg::from_entropy(algorithm)), output(Drbg::from_entropy(algorithm)));
+        }
+        assert_ne!(output(Drbg::from_seed(Algorithm::ChaCha20, b"42")), output(Drbg::from_seed(Algorithm::HmacSha256, b"42")));
+    }
+
+    #[test]
+    fn test_fork() {
+        let mut parent = Drbg::from_seed(Algorithm::HmacSha256, b"42");
+        let first = parent.fork();
+        let second = parent.fork();
+        assert_eq!(first.algorithm(), Algorithm::HmacSha256);
+        assert_ne!(output(first), output(second));
+    }
+
+    #[test]
+    fn test_args() {
+        let cli = Cli::parse_from(["test", "--seed", "42", "--drbg", "hmac-sha256"]);
+        assert!(cli.rng.is_seeded());
+        assert_eq!(output(cli.rng.rng()), output(Drbg::from_seed(Algorithm::HmacSha256, b"42")));
+        assert!(!Cli::parse_from(["test"]).rng.is_seeded());
+    }
+
+    #[test]
+    fn test_insecure_mt19937() {
+        let cli = Cli::parse_from(["test", "--seed", "42", "--drbg", "insecure-mt19937"]);
+        let mut drbg = cli.rng.rng();
+        assert_eq!(drbg.algorithm(), Algorithm::Mt19937);
+        // Jedes next_u32 ist genau ein Wort des Generators; "42" = 0x3234 als Schlüsselwort
+        let mut mt = Mt19937::from_key(&[0x3234]);
+        assert_eq!(drbg.next_u32(), mt.next_u32());
+    }
+}
@@ -0,0 +1,131 @@
+//! Mersenne Twister MT19937, absichtlich unsicher
+//!
+//! Der Standardgenerator vieler Sprachen (Python `random`, C++ `std::mt19937`,
+//! PHP `mt_rand`) hat eine riesige Periode und besteht statistische Tests,
+//! ist aber linear über GF(2): Aus 624 aufeinanderfolgenden Ausgaben lässt
+//! sich der gesamte Zustand zurückrechnen. Er ist nur vorhanden, damit
+//! `prng_crack` zeigen kann, was mit Schlüsseln aus so einem Generator passiert.
+//!
+//! Die Bytes einer Ausgabe sind die 32-Bit-Wörter in Little-Endian, eine
+//! Ausgabe von `next_u32` ist also genau ein Wort des Generators.
+
+/// Anzahl der Zustandswörter
+pub const N: usize = 624;
+const M: usize = 397;
+const MATRIX_A: u32 = 0x9908_b0df;
+const UPPER_MASK: u32 = 0x8000_0000;
+const LOWER_MASK: u32 = 0x7fff_ffff;
+
+#[derive(Debug, Clone)]
+pub struct Mt19937 {
+    state: [u32; N],
+    index: usize,
+}
+
+/// Tempering: macht die Ausgabe gleichmäßiger, ist aber umkehrbar
+pub fn temper(mut y: u32) -> u32 {
+    y ^= y >> 11;
+    y ^= (y << 7) & 0x9d2c_5680;
+    y ^= (y << 15) & 0xefc6_0000;
+    y ^ (y >> 18)
+}
+
+impl Mt19937 {
+    /// `init_genrand` der Referenzimplementierung
+    pub fn new(seed: u32) -> Self {
+        let mut state = [0u32; N];
+        state[0] = seed;
+        for i in 1..N {
+            state[i] = 1_812_433_253u32.wrapping_mul(state[i - 1] ^ (state[i - 1] >> 30)).wrapping_add(i as u32);
+        }
+        Mt19937 { state, index: N }
+    }
+
+    /// `init_by_array` der Referenzimplementierung
+    pub fn from_key(key: &[u32]) -> Self {
+        let mut mt = Mt19937::new(19_650_218);
+        let s = &mut mt.state;
+        let (mut i, mut j) = (1, 0);
+        for _ in 0..N.max(key.len()) {
+            s[i] = (s[i] ^ (s[i - 1] ^ (s[i - 1] >> 30)).wrapping_mul(1_664_525)).wrapping_add(key.get(j).copied().unwrap_or(0)).wrapping_add(j as u32);
+            i += 1;
+            j += 1;
+            if i >= N {
+                s[0] = s[N - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..N - 1 {
+            s[i] = (s[i] ^ (s[i - 1] ^ (s[i - 1] >> 30)).wrapping_mul(1_566_083_941)).wrapping_sub(i as u32);
+            i += 1;
+            if i >= N {
+                s[0] = s[N - 1];
+                i = 1;
+            }
+        }
+        s[0] = 0x8000_0000;
+        mt
+    }
+
+    /// Generator, dessen nächste Ausgaben aus dem Zustand `state` folgen
+    ///
+    /// `state` sind die ungetemperten Wörter der letzten 624 Ausgaben.
+    pub fn from_state(state: [u32; N]) -> Self {
+        Mt19937 { state, index: N }
+    }
+
+    /// Berechnet die nächsten 624 Zustandswörter
+    fn twist(&mut self) {
+        for i in 0..N {
+            let y = (self.state[i] & UPPER_MASK) | (self.state[(i + 1) % N] & LOWER_MASK);
+            self.state[i] = self.state[(i + M) % N] ^ (y >> 1) ^ (MATRIX_A & 0u32.wrapping_sub(y & 1));
+        }
+        self.index = 0;
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        if self.index >= N {
+            self.twist();
+        }
+        let y = self.state[self.index];
+        self.index += 1;
+        temper(y)
+    }
+
+    pub fn fill_bytes(&mut self, output: &mut [u8]) {
+        for chunk in output.chunks_mut(4) {
+            chunk.copy_from_slice(&self.next_u32().to_le_bytes()[..chunk.len()]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_outputs() {
+        // Erste Ausgaben von mt19937ar.c
+        let mut mt = Mt19937::new(5489);
+        assert_eq!(mt.next_u32(), 3_499_211_612);
+        assert_eq!(mt.next_u32(), 581_869_302);
+        let mut mt = Mt19937::from_key(&[0x123, 0x234, 0x345, 0x456]);
+        assert_eq!([mt.next_u32(), mt.next_u32(), mt.next_u32()], [1_067_595_299, 955_945_823, 477_289_528]);
+        // C++: das 10000. Ergebnis von std::mt19937 mit Standard-Seed
+        let mut mt = Mt19937::new(5489);
+        assert_eq!((0..10000).map(|_| mt.next_u32()).last(), Some(4_123_659_995));
+    }
+
+    #[test]
+    fn test_fill_bytes() {
+        let mut words = Mt19937::new(1);
+        let mut bytes = Mt19937::new(1);
+        let mut output = [0u8; 6];
+        bytes.fill_bytes(&mut output);
+        assert_eq!(output[..4], words.next_u32().to_le_bytes());
+        assert_eq!(output[4..], words.next_u32().to_le_bytes()[..2]);
+    }
+}
@@ -0,0 +1,92 @@
+//! Schlüsselableitung aus dem gemeinsamen DH-Geheimnis
+//!
+//! Das rohe Geheimnis g^ab mod p ist nicht gleichverteilt und darf nicht direkt
+//! als Schlüssel verwendet werden. Es wird daher mit HKDF-SHA256 (RFC 5869)
+//! in einen symmetrischen Schlüssel umgewandelt.
+
+use clap::ValueEnum;
+use hkdf::Hkdf;
+use num_bigint::BigUint;
+use secret::Secret;
+use sha2::Sha256;
+use std::error::Error;
+
+/// Unterstützte Zielschlüssel
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum KeyKind {
+    /// 128-Bit Schlüssel für das aes-128 Programm
+    Aes128,
+}
+
+impl KeyKind {
+    /// Länge des abgeleiteten Schlüssels in Bytes
+    pub fn key_len(self) -> usize {
+        match self {
+            KeyKind::Aes128 => 16,
+        }
+    }
+
+    /// Kontextinformation für HKDF-Expand, trennt Schlüssel verschiedener Verwendungszwecke
+    fn info(self) -> &'static [u8] {
+        match self {
+            KeyKind::Aes128 => b"dh_exchange aes128 key",
+        }
+    }
+}
+
+/// Kodiert das Geheimnis mit fester Länge (Länge von p), wie in RFC 2631 / TLS üblich
+pub fn encode_secret(shared_secret: &BigUint, p: &BigUint) -> Secret<Vec<u8>> {
+    let len = p.bits().div_ceil(8) as usize;
+    let bytes = Secret::new(shared_secret.to_bytes_be());
+    let mut encoded = Secret::new(Vec::with_capacity(len.max(bytes.len())));
+    encoded.resize(len.saturating_sub(bytes.len()), 0);
+    encoded.extend_from_slice(&bytes);
+    encoded
+}
+
+/// HKDF-SHA256 Extract-and-Expand (RFC 5869)
+///
+/// Die Ausgabelänge ist auf 255 * 32 Bytes begrenzt; größere Werte sind ein Programmierfehler.
+pub fn hkdf_sha256(ikm: &[u8], salt: &[u8], info: &[u8], len: usize) -> Secret<Vec<u8>> {
+    let hkdf = Hkdf::<Sha256>::new(Some(salt), ikm);
+    let mut okm = Secret::new(vec![0u8; len]);
+    hkdf.expand(info, &mut okm)
+        .expect("HKDF: angeforderte Schlüssellänge zu groß");
+    okm
+}
+
+/// Leitet einen symmetrischen Schlüssel mit HKDF-SHA256 ab
+pub fn derive_key(shared_secret: &BigUint, p: &BigUint, salt: &[u8], kind: KeyKind) -> Result<Secret<Vec<u8>>, Box<dyn Error>> {
+    let ikm = encode_secret(shared_secret, p);
+    Ok(hkdf_sha256(&ikm, salt, kind.info(), kind.key_len()))
+}
+
+/// Schreibt den Schlüssel als Hexadezimalstring (Format der aes-128 Schlüsseldatei)
+pub fn write_key_file(path: &str, key: &[u8]) -> tool_error::Result<()> {
+    tool_error::fs::write(path, codec::hex::encode(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_secret_pads_to_modulus_length() {
+        let p = BigUint::from(0x00FF_FFFFu32);
+        assert_eq!(*encode_secret(&BigUint::from(5u32), &p), vec![0, 0, 5]);
+    }
+
+    #[test]
+    fn test_derive_key_depends_on_salt() {
+        let p = BigUint::from(0xFFFF_FFFBu32);
+        let secret = BigUint::from(123_456u32);
+
+        let key1 = derive_key(&secret, &p, b"salt-1", KeyKind::Aes128).unwrap();
+        let key2 = derive_key(&secret, &p, b"salt-1", KeyKind::Aes128).unwrap();
+        let key3 = derive_key(&secret, &p, b"salt-2", KeyKind::Aes128).unwrap();
+
+        assert_eq!(key1.len(), 16);
+        assert_eq!(key1, key2);
+        assert_ne!(key1, key3);
+    }
+}
@@ -0,0 +1,409 @@
+use clap::Parser;
+use logging::{Verbosity, info};
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::{Zero, One};
+use rand::Rng;
+use crypto_math::mod_pow;
+use csprng::RngArgs;
+use key_formats::{DhParameters, KeyFile};
+use secret::Secret;
+use std::io::{self, BufRead};
+use tool_error::{Context, ToolError};
+
+pub mod kdf;
+mod multi;
+pub mod net;
+
+/// DH-Schlüsselaustausch: Führt Diffie-Hellman Schlüsselaustausch durch
+#[derive(Parser)]
+#[command(name = "dh_exchange")]
+#[command(about = "Führt Diffie-Hellman Schlüsselaustausch durch")]
+#[command(version = "1.0")]
+struct Args {
+    /// Verwende vorgegebene private Schlüssel für Tests (unsicher!)
+    #[arg(long = "test-mode")]
+    test_mode: bool,
+    
+    /// Alices privater Schlüssel (nur im Test-Modus)
+    #[arg(long = "alice-key")]
+    alice_key: Option<String>,
+    
+    /// Bobs privater Schlüssel (nur im Test-Modus mit --simulate)
+    #[arg(long = "bob-key")]
+    bob_key: Option<String>,
+    
+    /// Simuliert Alice und Bob lokal und prüft, ob beide dasselbe Geheimnis erhalten
+    #[arg(long = "simulate")]
+    simulate: bool,
+    
+    /// Simuliert eine Variante mit statischen und ephemeren Schlüsseln (z.B. X3DH)
+    #[arg(long = "protocol", value_enum, value_name = "VARIANTE", conflicts_with_all = ["simulate", "listen", "connect"])]
+    protocol: Option<multi::Protocol>,
+    
+    /// Wartet auf einen Partner an diesem Port (Alice, liest p und g von stdin)
+    #[arg(long = "listen", value_name = "PORT", conflicts_with_all = ["connect", "simulate"])]
+    listen: Option<u16>,
+    
+    /// Verbindet sich mit einem wartenden Partner (Bob, erhält p und g über das Netz)
+    #[arg(long = "connect", value_name = "HOST:PORT", conflicts_with = "simulate")]
+    connect: Option<String>,
+    
+    /// Leitet aus dem Geheimnis per HKDF-SHA256 einen symmetrischen Schlüssel ab
+    #[arg(long = "derive-key", value_enum, value_name = "TYP", requires = "out")]
+    derive_key: Option<kdf::KeyKind>,
+    
+    /// Ausgabedatei für den abgeleiteten Schlüssel (Hexadezimal, für aes-128 --key-file)
+    #[arg(long = "out", value_name = "DATEI")]
+    out: Option<String>,
+    
+    /// Liest p und g aus einer Parameterdatei von dh_params statt von stdin
+    #[arg(long = "params", value_name = "DATEI", conflicts_with = "connect")]
+    params: Option<String>,
+    
+    /// Erwartet nach g eine weitere Eingabezeile mit der Untergruppenordnung q
+    #[arg(long = "with-q", conflicts_with = "connect")]
+    with_q: bool,
+    
+    /// Vereinbarter Salt in Hexadezimal (stdin- und Simulationsmodus; im Netzwerk wird er ausgetauscht)
+    #[arg(long = "salt", value_name = "HEX", conflicts_with_all = ["listen", "connect"])]
+    salt: Option<String>,
+
+    #[command(flatten)]
+    rng: RngArgs,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+/// Ergebnis einer lokal simulierten Zwei-Parteien-Ausführung
+struct Simulation {
+    alice_public: BigUint,
+    bob_public: BigUint,
+    alice_secret: Secret<BigUint>,
+    bob_secret: Secret<BigUint>,
+}
+
+/// Generiert sicheren privaten Schlüssel für Diffie-Hellman
+/// Der private Schlüssel liegt im Bereich [2, p-2] und hat ausreichende Bitlänge
+pub fn generate_private_key(p: &BigUint, rng: &mut impl Rng) -> Secret<BigUint> {
+    
+    // Generiere Schlüssel im Bereich [2, p-2]
+    // Verwende mindestens 160 Bits für Sicherheit
+    let min_bits = std::cmp::min(160, p.bits() - 2);
+    let random = Secret::new(rng.gen_biguint(min_bits as u64));
+    
+    // Stelle sicher, dass der Schlüssel im gültigen Bereich liegt
+    Secret::new(&*random % (p - 2u32) + 2u32)
+}
+
+/// Liest eine Zeile von stdin und konvertiert sie zu BigUint
+fn read_biguint_from_stdin() -> tool_error::Result<BigUint> {
+    let stdin = io::stdin();
+    let mut line = String::new();
+    stdin.lock().read_line(&mut line)?;
+    
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return Err(ToolError::data("Leere Eingabe erhalten"));
+    }
+    
+    trimmed.parse::<BigUint>()
+        .map_err(|e| ToolError::data(format!("Ungültige Zahl '{}': {}", trimmed, e)))
+}
+
+/// Validiert DH-Parameter auf grundlegende Sicherheitseigenschaften
+pub fn validate_dh_params(p: &BigUint, g: &BigUint) -> tool_error::Result<()> {
+    // p sollte mindestens 512 Bits haben
+    if p.bits() < 512 {
+        return Err(ToolError::data(format!("Warnung: p hat nur {} Bits, empfohlen sind mindestens 1024", p.bits())));
+    }
+    
+    // g sollte im Bereich [2, p-2] liegen
+    if g < &BigUint::from(2u32) || g >= &(p - 1u32) {
+        return Err(ToolError::data("g muss im Bereich [2, p-2] liegen"));
+    }
+    
+    // p sollte ungerade sein
+    if p % 2u32 == BigUint::zero() {
+        return Err(ToolError::data("p sollte eine ungerade Primzahl sein"));
+    }
+    
+    Ok(())
+}
+
+/// Prüft, dass q die Gruppenordnung p-1 teilt und g eine Untergruppe der Ordnung q erzeugt
+fn validate_subgroup(p: &BigUint, g: &BigUint, q: &BigUint) -> tool_error::Result<()> {
+    if q <= &BigUint::one() || !((p - 1u32) % q).is_zero() {
+        return Err(ToolError::data("q muss ein Teiler von p-1 sein"));
+    }
+    
+    if !mod_pow(g, q, p).is_one() {
+        return Err(ToolError::data("g erzeugt keine Untergruppe der Ordnung q (g^q mod p != 1)"));
+    }
+    
+    Ok(())
+}
+
+/// Führt den Austausch für beide Parteien lokal durch
+/// 
+/// Alice: A = g^a mod p, S_A = B^a mod p
+/// Bob:   B = g^b mod p, S_B = A^b mod p
+fn simulate_exchange(p: &BigUint, g: &BigUint, alice_private: &BigUint, bob_private: &BigUint) -> Simulation {
+    let alice_public = mod_pow(g, alice_private, p);
+    let bob_public = mod_pow(g, bob_private, p);
+    
+    Simulation {
+        alice_secret: Secret::new(mod_pow(&bob_public, alice_private, p)),
+        bob_secret: Secret::new(mod_pow(&alice_public, bob_private, p)),
+        alice_public,
+        bob_public,
+    }
+}
+
+/// Liest einen privaten Schlüssel aus den Argumenten (Test-Modus) oder erzeugt einen neuen
+fn private_key_or_generate(test_mode: bool, key: Option<String>, p: &BigUint, rng: &mut impl Rng) -> Result<Secret<BigUint>, Box<dyn std::error::Error>> {
+    match (test_mode, key) {
+        (true, Some(key)) => Ok(Secret::new(key.parse::<BigUint>()
+            .map_err(|e| ToolError::usage(format!("Ungültiger privater Schlüssel: {}", e)))?)),
+        _ => Ok(generate_private_key(p, rng)),
+    }
+}
+
+/// Validiert einen öffentlichen Schlüssel des Gegenübers
+/// 
+/// Die Werte 0, 1 und p-1 liegen in trivialen Untergruppen und werden immer abgelehnt.
+/// Ist q bekannt, muss zusätzlich B^q ≡ 1 mod p gelten, sonst könnte ein Angreifer
+/// über Elemente kleiner Ordnung Bits des privaten Schlüssels erfahren.
+pub fn validate_public_key(public_key: &BigUint, p: &BigUint, q: Option<&BigUint>) -> tool_error::Result<()> {
+    let p_minus_1 = p - 1u32;
+    if public_key < &BigUint::from(2u32) || public_key >= &p_minus_1 {
+        return Err(ToolError::data("Öffentlicher Schlüssel des Gegenübers ist ungültig (0, 1 oder >= p-1)"));
+    }
+    
+    if let Some(q) = q && !mod_pow(public_key, q, p).is_one() {
+        return Err(ToolError::data("Öffentlicher Schlüssel des Gegenübers liegt nicht in der Untergruppe der Ordnung q"));
+    }
+    
+    Ok(())
+}
+
+/// Parst einen Salt aus Hexadezimalziffern
+fn parse_salt(hex: &str) -> tool_error::Result<Vec<u8>> {
+    codec::hex::decode(hex)
+        .map_err(|e| ToolError::usage(format!("Salt muss aus einer geraden Anzahl Hexadezimalziffern bestehen: {}", e)))
+}
+
+/// Leitet bei Bedarf einen Schlüssel aus dem Geheimnis ab und schreibt ihn in die Ausgabedatei
+fn write_derived_key(args: &Args, shared_secret: &BigUint, p: &BigUint, salt: &[u8]) -> tool_error::Result<()> {
+    if let (Some(kind), Some(out)) = (args.derive_key, &args.out) {
+        let key = kdf::derive_key(shared_secret, p, salt, kind)?;
+        kdf::write_key_file(out, &key)?;
+        info!("Abgeleiteter Schlüssel geschrieben nach: {}", out);
+    }
+    Ok(())
+}
+
+/// Gibt das Ergebnis eines Netzwerkaustauschs im gleichen Format wie der stdin-Modus aus
+fn print_exchange(exchange: &net::Exchange) {
+    info!("Öffentlicher Schlüssel des Gegenübers: {}", exchange.peer_public);
+    println!("{}", exchange.own_public);
+    println!("{}", *exchange.shared_secret);
+}
+
+/// Startet das Werkzeug mit den übergebenen Kommandozeilenargumenten
+///
+/// Das erste Argument ist wie bei `std::env::args_os` der Programmname;
+/// so lässt sich das Werkzeug auch aus `cryptolab` heraus aufrufen.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+    let mut rng = args.rng.rng();
+    
+    // Client-Modus: p und g kommen vom Server, nicht von stdin
+    if let Some(addr) = &args.connect {
+        let test_mode = args.test_mode;
+        let bob_key = args.bob_key.clone();
+        let mut p = BigUint::zero();
+        let mut key_rng = rng.fork();
+        let exchange = net::connect(addr, &mut rng, |received_p| {
+            p = received_p.clone();
+            private_key_or_generate(test_mode, bob_key, received_p, &mut key_rng)
+        })?;
+        print_exchange(&exchange);
+        write_derived_key(&args, &exchange.shared_secret, &p, &exchange.salt)?;
+        return Ok(());
+    }
+    
+    let salt = match &args.salt {
+        Some(hex) => parse_salt(hex)?,
+        None => Vec::new(),
+    };
+    
+    // Schritt 1: Lese Parameter p und g aus der Parameterdatei oder von stdin
+    let (p, g) = match &args.params {
+        Some(path) => {
+            let params = DhParameters::read(path).context("Parameterdatei")?;
+            (params.p, params.g)
+        }
+        None => (read_biguint_from_stdin()?, read_biguint_from_stdin()?),
+    };
+    
+    let q = if args.with_q {
+        Some(read_biguint_from_stdin()?)
+    } else {
+        None
+    };
+    
+    // Validiere Parameter
+    validate_dh_params(&p, &g)?;
+    if let Some(q) = &q {
+        validate_subgroup(&p, &g, q)?;
+    }
+    
+    // Variante mit mehreren DH-Anteilen: alle Schlüssel werden lokal erzeugt
+    if let Some(protocol) = args.protocol {
+        let session = multi::simulate(protocol, &p, &g, &mut rng);
+        
+        println!("DH-Anteile:               {}", session.dh_count);
+        println!("Alice Sitzungsschlüssel:  {}", codec::hex::encode(&session.alice_key));
+        println!("Bob Sitzungsschlüssel:    {}", codec::hex::encode(&session.bob_key));
+        
+        if session.alice_key != session.bob_key {
+            return Err(ToolError::failed("Sitzungsschlüssel stimmen nicht überein"));
+        }
+        println!("Sitzungsschlüssel stimmen überein");
+        return Ok(());
+    }
+    
+    // Schritt 2: Generiere Alices private und öffentliche Schlüssel
+    let alice_private = private_key_or_generate(args.test_mode, args.alice_key.clone(), &p, &mut rng)?;
+    
+    // Simulationsmodus: Bob wird lokal mitgerechnet, kein weiterer stdin-Input nötig
+    if args.simulate {
+        let bob_private = private_key_or_generate(args.test_mode, args.bob_key.clone(), &p, &mut rng)?;
+        let simulation = simulate_exchange(&p, &g, &alice_private, &bob_private);
+        
+        println!("Alice öffentlich: {}", simulation.alice_public);
+        println!("Bob öffentlich:   {}", simulation.bob_public);
+        println!("Alice Geheimnis:  {}", *simulation.alice_secret);
+        println!("Bob Geheimnis:    {}", *simulation.bob_secret);
+        
+        if simulation.alice_secret != simulation.bob_secret {
+            return Err(ToolError::failed("Simulation fehlgeschlagen: Geheimnisse stimmen nicht überein"));
+        }
+        println!("Geheimnisse stimmen überein");
+        write_derived_key(&args, &simulation.alice_secret, &p, &salt)?;
+        return Ok(());
+    }
+    
+    // Server-Modus: Bobs Schlüssel kommt über das Netz statt von stdin
+    if let Some(port) = args.listen {
+        let exchange = net::listen(port, &p, &g, q.as_ref(), &alice_private, &mut rng)?;
+        print_exchange(&exchange);
+        write_derived_key(&args, &exchange.shared_secret, &p, &exchange.salt)?;
+        return Ok(());
+    }
+    
+    // Berechne Alices öffentlichen Schlüssel: A = g^a mod p
+    let alice_public = mod_pow(&g, &alice_private, &p);
+    
+    // Schritt 3: Ausgabe von Alices öffentlichem Schlüssel
+    println!("{}", alice_public);
+    
+    // Schritt 4: Lese Bobs öffentlichen Schlüssel
+    let bob_public = read_biguint_from_stdin()?;
+    
+    // Validiere Bobs öffentlichen Schlüssel
+    validate_public_key(&bob_public, &p, q.as_ref())?;
+    
+    // Schritt 5: Berechne gemeinsames Geheimnis
+    // Alice berechnet: S = B^a mod p
+    let shared_secret = Secret::new(mod_pow(&bob_public, &alice_private, &p));
+    
+    // Schritt 6: Ausgabe des gemeinsamen Geheimnisses
+    println!("{}", *shared_secret);
+    
+    // Schritt 7 (optional): Schlüssel ableiten statt das rohe Geheimnis zu verwenden
+    write_derived_key(&args, &shared_secret, &p, &salt)?;
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_dh_exchange() {
+        // Verwende kleine Werte für Test
+        let p = BigUint::from(23u32);
+        let g = BigUint::from(5u32);
+        let alice_private = BigUint::from(6u32);
+        let bob_private = BigUint::from(15u32);
+        
+        // Alice berechnet A = g^a mod p
+        let alice_public = mod_pow(&g, &alice_private, &p);
+        
+        // Bob berechnet B = g^b mod p  
+        let bob_public = mod_pow(&g, &bob_private, &p);
+        
+        // Beide berechnen gemeinsames Geheimnis
+        let alice_secret = mod_pow(&bob_public, &alice_private, &p);
+        let bob_secret = mod_pow(&alice_public, &bob_private, &p);
+        
+        assert_eq!(alice_secret, bob_secret);
+    }
+    
+    #[test]
+    fn test_simulate_exchange() {
+        let p = BigUint::from(23u32);
+        let g = BigUint::from(5u32);
+        
+        let simulation = simulate_exchange(&p, &g, &BigUint::from(6u32), &BigUint::from(15u32));
+        
+        assert_eq!(simulation.alice_public, BigUint::from(8u32));
+        assert_eq!(simulation.bob_public, BigUint::from(19u32));
+        assert_eq!(*simulation.alice_secret, BigUint::from(2u32));
+        assert_eq!(simulation.bob_secret, simulation.alice_secret);
+    }
+    
+    #[test]
+    fn test_validate_public_key_rejects_trivial_values() {
+        let p = BigUint::from(23u32);
+        for value in [0u32, 1, 22, 23] {
+            assert!(validate_public_key(&BigUint::from(value), &p, None).is_err());
+        }
+        assert!(validate_public_key(&BigUint::from(5u32), &p, None).is_ok());
+    }
+    
+    #[test]
+    fn test_validate_public_key_checks_subgroup() {
+        // g = 2 erzeugt in Z_23* die Untergruppe der Ordnung q = 11 (quadratische Reste)
+        let p = BigUint::from(23u32);
+        let g = BigUint::from(2u32);
+        let q = BigUint::from(11u32);
+        assert!(validate_subgroup(&p, &g, &q).is_ok());
+        
+        assert!(validate_public_key(&BigUint::from(4u32), &p, Some(&q)).is_ok());
+        // 5 ist kein quadratischer Rest und hat Ordnung 22
+        assert!(validate_public_key(&BigUint::from(5u32), &p, Some(&q)).is_err());
+    }
+    
+    #[test]
+    fn test_validate_subgroup_rejects_wrong_q() {
+        let p = BigUint::from(23u32);
+        assert!(validate_subgroup(&p, &BigUint::from(2u32), &BigUint::from(7u32)).is_err());
+        assert!(validate_subgroup(&p, &BigUint::from(5u32), &BigUint::from(11u32)).is_err());
+    }
+    
+    #[test]
+    fn test_parse_salt() {
+        assert_eq!(parse_salt("00ff 10").unwrap(), vec![0x00, 0xff, 0x10]);
+        assert!(parse_salt("abc").is_err());
+        assert!(parse_salt("zz").is_err());
+    }
+}
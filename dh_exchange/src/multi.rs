@@ -0,0 +1,195 @@
+//! Austauschvarianten mit statischen und ephemeren Schlüsseln
+//!
+//! Moderne Messaging-Protokolle (z.B. Signal/X3DH) kombinieren mehrere
+//! DH-Ergebnisse aus langlebigen Identitätsschlüsseln und kurzlebigen
+//! (ephemeren) Schlüsseln und leiten daraus per KDF einen Sitzungsschlüssel ab:
+//!
+//! | Variante          | DH-Anteile                                          |
+//! |-------------------|-----------------------------------------------------|
+//! | Ephemeral-Static  | DH(EK_A, IK_B)                                      |
+//! | Triple-DH         | DH(IK_A, SPK_B), DH(EK_A, IK_B), DH(EK_A, SPK_B)    |
+//! | X3DH              | Triple-DH + DH(EK_A, OPK_B)                         |
+//!
+//! IK = Identitätsschlüssel, EK = ephemerer Schlüssel,
+//! SPK = signierter Prekey, OPK = Einmal-Prekey.
+
+use clap::ValueEnum;
+use num_bigint::BigUint;
+use rand::Rng;
+use secret::Secret;
+
+use crate::{generate_private_key, kdf, mod_pow};
+
+/// Länge des abgeleiteten Sitzungsschlüssels in Bytes
+const SESSION_KEY_LEN: usize = 32;
+
+/// Unterstützte Austauschvarianten
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Protocol {
+    /// Nur Alice ist ephemer, Bob statisch (einseitig authentisiert)
+    EphemeralStatic,
+    /// Drei DH-Ergebnisse aus Identitäts-, ephemerem und Prekey-Schlüssel
+    TripleDh,
+    /// Triple-DH plus Einmal-Prekey für zusätzliche Vorwärtssicherheit
+    X3dh,
+}
+
+impl Protocol {
+    /// Kontextinformation für HKDF, trennt die Varianten voneinander
+    fn info(self) -> &'static [u8] {
+        match self {
+            Protocol::EphemeralStatic => b"dh_exchange ephemeral-static",
+            Protocol::TripleDh => b"dh_exchange triple-dh",
+            Protocol::X3dh => b"dh_exchange x3dh",
+        }
+    }
+}
+
+/// Privater und öffentlicher Schlüssel einer Partei
+pub struct KeyPair {
+    pub private: Secret<BigUint>,
+    pub public: BigUint,
+}
+
+impl KeyPair {
+    pub fn from_private(private: Secret<BigUint>, p: &BigUint, g: &BigUint) -> Self {
+        let public = mod_pow(g, &private, p);
+        KeyPair { private, public }
+    }
+
+    pub fn generate(p: &BigUint, g: &BigUint, rng: &mut impl Rng) -> Self {
+        Self::from_private(generate_private_key(p, rng), p, g)
+    }
+}
+
+/// Schlüssel des Initiators Alice
+pub struct AliceKeys {
+    pub identity: KeyPair,
+    pub ephemeral: KeyPair,
+}
+
+/// Schlüssel des Empfängers Bob (Prekeys werden vorab veröffentlicht)
+pub struct BobKeys {
+    pub identity: KeyPair,
+    pub signed_prekey: KeyPair,
+    pub one_time_prekey: KeyPair,
+}
+
+/// Ergebnis einer lokal simulierten Ausführung
+pub struct Session {
+    pub dh_count: usize,
+    pub alice_key: Secret<Vec<u8>>,
+    pub bob_key: Secret<Vec<u8>>,
+}
+
+/// DH-Anteile aus Alices Sicht (ihre privaten Schlüssel, Bobs öffentliche)
+fn alice_dh_outputs(protocol: Protocol, p: &BigUint, alice: &AliceKeys, bob: &BobKeys) -> Vec<Secret<BigUint>> {
+    let ek = &alice.ephemeral.private;
+    match protocol {
+        Protocol::EphemeralStatic => vec![Secret::new(mod_pow(&bob.identity.public, ek, p))],
+        Protocol::TripleDh | Protocol::X3dh => {
+            let mut outputs = vec![
+                Secret::new(mod_pow(&bob.signed_prekey.public, &alice.identity.private, p)),
+                Secret::new(mod_pow(&bob.identity.public, ek, p)),
+                Secret::new(mod_pow(&bob.signed_prekey.public, ek, p)),
+            ];
+            if protocol == Protocol::X3dh {
+                outputs.push(Secret::new(mod_pow(&bob.one_time_prekey.public, ek, p)));
+            }
+            outputs
+        }
+    }
+}
+
+/// DH-Anteile aus Bobs Sicht (seine privaten Schlüssel, Alices öffentliche)
+fn bob_dh_outputs(protocol: Protocol, p: &BigUint, alice: &AliceKeys, bob: &BobKeys) -> Vec<Secret<BigUint>> {
+    let ek = &alice.ephemeral.public;
+    match protocol {
+        Protocol::EphemeralStatic => vec![Secret::new(mod_pow(ek, &bob.identity.private, p))],
+        Protocol::TripleDh | Protocol::X3dh => {
+            let mut outputs = vec![
+                Secret::new(mod_pow(&alice.identity.public, &bob.signed_prekey.private, p)),
+                Secret::new(mod_pow(ek, &bob.identity.private, p)),
+                Secret::new(mod_pow(ek, &bob.signed_prekey.private, p)),
+            ];
+            if protocol == Protocol::X3dh {
+                outputs.push(Secret::new(mod_pow(ek, &bob.one_time_prekey.private, p)));
+            }
+            outputs
+        }
+    }
+}
+
+/// Leitet den Sitzungsschlüssel aus der Verkettung aller DH-Anteile ab
+///
+/// Jeder Anteil wird mit fester Länge kodiert, damit die Verkettung eindeutig ist.
+pub fn session_key(protocol: Protocol, p: &BigUint, dh_outputs: &[Secret<BigUint>]) -> Secret<Vec<u8>> {
+    let mut ikm = Secret::new(Vec::new());
+    for dh in dh_outputs {
+        ikm.extend_from_slice(&kdf::encode_secret(dh, p));
+    }
+    kdf::hkdf_sha256(&ikm, &[], protocol.info(), SESSION_KEY_LEN)
+}
+
+/// Führt die gewählte Variante für beide Parteien mit gegebenen Schlüsseln durch
+pub fn run(protocol: Protocol, p: &BigUint, alice: &AliceKeys, bob: &BobKeys) -> Session {
+    let alice_dh = alice_dh_outputs(protocol, p, alice, bob);
+    let bob_dh = bob_dh_outputs(protocol, p, alice, bob);
+
+    Session {
+        dh_count: alice_dh.len(),
+        alice_key: session_key(protocol, p, &alice_dh),
+        bob_key: session_key(protocol, p, &bob_dh),
+    }
+}
+
+/// Erzeugt alle benötigten Schlüssel zufällig und simuliert den Austausch
+pub fn simulate(protocol: Protocol, p: &BigUint, g: &BigUint, rng: &mut impl Rng) -> Session {
+    let alice = AliceKeys {
+        identity: KeyPair::generate(p, g, rng),
+        ephemeral: KeyPair::generate(p, g, rng),
+    };
+    let bob = BobKeys {
+        identity: KeyPair::generate(p, g, rng),
+        signed_prekey: KeyPair::generate(p, g, rng),
+        one_time_prekey: KeyPair::generate(p, g, rng),
+    };
+    run(protocol, p, &alice, &bob)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_keys(p: &BigUint, g: &BigUint) -> (AliceKeys, BobKeys) {
+        let pair = |x: u32| KeyPair::from_private(Secret::new(BigUint::from(x)), p, g);
+        let alice = AliceKeys { identity: pair(3), ephemeral: pair(7) };
+        let bob = BobKeys { identity: pair(11), signed_prekey: pair(13), one_time_prekey: pair(17) };
+        (alice, bob)
+    }
+
+    #[test]
+    fn test_all_protocols_agree() {
+        let p = BigUint::from(1_000_000_007u32);
+        let g = BigUint::from(5u32);
+        let (alice, bob) = fixed_keys(&p, &g);
+
+        for (protocol, count) in [(Protocol::EphemeralStatic, 1), (Protocol::TripleDh, 3), (Protocol::X3dh, 4)] {
+            let session = run(protocol, &p, &alice, &bob);
+            assert_eq!(session.dh_count, count);
+            assert_eq!(session.alice_key.len(), SESSION_KEY_LEN);
+            assert_eq!(session.alice_key, session.bob_key);
+        }
+    }
+
+    #[test]
+    fn test_protocols_yield_different_keys() {
+        let p = BigUint::from(1_000_000_007u32);
+        let g = BigUint::from(5u32);
+        let (alice, bob) = fixed_keys(&p, &g);
+
+        let triple = run(Protocol::TripleDh, &p, &alice, &bob);
+        let x3dh = run(Protocol::X3dh, &p, &alice, &bob);
+        assert_ne!(triple.alice_key, x3dh.alice_key);
+    }
+}
@@ -0,0 +1,213 @@
+//! Netzwerkmodus für den Diffie-Hellman-Austausch über TCP
+//!
+//! Drahtformat: Jede Zahl wird als 4-Byte Längenpräfix (Big-Endian) gefolgt
+//! von den Big-Endian Bytes der Zahl übertragen.
+//!
+//! Ablauf:
+//! 1. Server (`--listen`) sendet p, g, seinen öffentlichen Schlüssel A und einen Salt-Anteil
+//! 2. Client (`--connect`) prüft die Parameter und antwortet mit B und seinem Salt-Anteil
+//! 3. Beide berechnen das gemeinsame Geheimnis; der Salt für die Schlüsselableitung
+//!    ist Salt(Server) || Salt(Client)
+
+use logging::info;
+use num_bigint::BigUint;
+use rand::{Rng, RngCore};
+use secret::Secret;
+use std::error::Error;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+use crate::{mod_pow, validate_dh_params, validate_public_key};
+
+/// Maximale Länge einer einzelnen Nachricht auf der Leitung (schützt vor Speicherangriffen)
+const MAX_MESSAGE_LEN: usize = 64 * 1024;
+
+/// Länge des Salt-Anteils jeder Partei in Bytes
+const SALT_SHARE_LEN: usize = 16;
+
+/// Ergebnis eines Austauschs aus Sicht einer Partei
+pub struct Exchange {
+    pub own_public: BigUint,
+    pub peer_public: BigUint,
+    pub shared_secret: Secret<BigUint>,
+    /// Gemeinsamer Salt für die Schlüsselableitung (Server-Anteil || Client-Anteil)
+    pub salt: Vec<u8>,
+}
+
+/// Schreibt eine Bytefolge im längenpräfixierten Drahtformat
+pub fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+    writer.write_all(&(bytes.len() as u32).to_be_bytes())?;
+    writer.write_all(bytes)?;
+    Ok(())
+}
+
+/// Liest eine Bytefolge im längenpräfixierten Drahtformat
+pub fn read_bytes<R: Read>(reader: &mut R) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut len_bytes = [0u8; 4];
+    reader.read_exact(&mut len_bytes)?;
+    let len = u32::from_be_bytes(len_bytes) as usize;
+
+    if len == 0 || len > MAX_MESSAGE_LEN {
+        return Err(format!("Ungültige Nachrichtenlänge: {} Bytes", len).into());
+    }
+
+    let mut bytes = vec![0u8; len];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Schreibt eine Zahl im längenpräfixierten Drahtformat
+pub fn write_biguint<W: Write>(writer: &mut W, value: &BigUint) -> Result<(), Box<dyn Error>> {
+    write_bytes(writer, &value.to_bytes_be())
+}
+
+/// Liest eine Zahl im längenpräfixierten Drahtformat
+pub fn read_biguint<R: Read>(reader: &mut R) -> Result<BigUint, Box<dyn Error>> {
+    Ok(BigUint::from_bytes_be(&read_bytes(reader)?))
+}
+
+/// Erzeugt einen zufälligen Salt-Anteil
+fn generate_salt_share(rng: &mut impl RngCore) -> Vec<u8> {
+    let mut share = vec![0u8; SALT_SHARE_LEN];
+    rng.fill_bytes(&mut share);
+    share
+}
+
+/// Liest einen Salt-Anteil und prüft dessen Länge
+fn read_salt_share<R: Read>(reader: &mut R) -> Result<Vec<u8>, Box<dyn Error>> {
+    let share = read_bytes(reader)?;
+    if share.len() != SALT_SHARE_LEN {
+        return Err(format!("Salt-Anteil muss {} Bytes lang sein", SALT_SHARE_LEN).into());
+    }
+    Ok(share)
+}
+
+/// Wartet auf genau eine Verbindung und führt den Austausch als Server durch
+///
+/// Ist q bekannt, wird Bobs öffentlicher Schlüssel zusätzlich auf Zugehörigkeit
+/// zur Untergruppe der Ordnung q geprüft.
+pub fn listen(port: u16, p: &BigUint, g: &BigUint, q: Option<&BigUint>, private_key: &BigUint, rng: &mut impl Rng) -> Result<Exchange, Box<dyn Error>> {
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    info!("Warte auf Verbindung an Port {}...", port);
+
+    let (stream, peer) = listener.accept()?;
+    info!("Verbunden mit {}", peer);
+
+    serve(stream, p, g, q, private_key, rng)
+}
+
+/// Verbindet sich mit einem Server und führt den Austausch als Client durch
+///
+/// Der private Schlüssel wird erst erzeugt, wenn p bekannt ist.
+pub fn connect<F>(addr: &str, rng: &mut impl Rng, private_key: F) -> Result<Exchange, Box<dyn Error>>
+where
+    F: FnOnce(&BigUint) -> Result<Secret<BigUint>, Box<dyn Error>>,
+{
+    let stream = TcpStream::connect(addr)?;
+    info!("Verbunden mit {}", addr);
+
+    respond(stream, rng, private_key)
+}
+
+/// Serverseite des Protokolls: sendet p, g, A, Salt und empfängt B, Salt
+fn serve<S: Read + Write>(mut stream: S, p: &BigUint, g: &BigUint, q: Option<&BigUint>, private_key: &BigUint, rng: &mut impl Rng) -> Result<Exchange, Box<dyn Error>> {
+    let own_public = mod_pow(g, private_key, p);
+    let mut salt = generate_salt_share(rng);
+
+    write_biguint(&mut stream, p)?;
+    write_biguint(&mut stream, g)?;
+    write_biguint(&mut stream, &own_public)?;
+    write_bytes(&mut stream, &salt)?;
+    stream.flush()?;
+
+    let peer_public = read_biguint(&mut stream)?;
+    validate_public_key(&peer_public, p, q)?;
+    salt.extend(read_salt_share(&mut stream)?);
+
+    let shared_secret = Secret::new(mod_pow(&peer_public, private_key, p));
+    Ok(Exchange { own_public, peer_public, shared_secret, salt })
+}
+
+/// Clientseite des Protokolls: empfängt p, g, A und sendet B
+fn respond<S, F>(mut stream: S, rng: &mut impl Rng, private_key: F) -> Result<Exchange, Box<dyn Error>>
+where
+    S: Read + Write,
+    F: FnOnce(&BigUint) -> Result<Secret<BigUint>, Box<dyn Error>>,
+{
+    let p = read_biguint(&mut stream)?;
+    let g = read_biguint(&mut stream)?;
+    let peer_public = read_biguint(&mut stream)?;
+    let mut salt = read_salt_share(&mut stream)?;
+
+    // Parameter vom Gegenüber sind nicht vertrauenswürdig
+    validate_dh_params(&p, &g)?;
+    validate_public_key(&peer_public, &p, None)?;
+
+    let private_key = private_key(&p)?;
+    let own_public = mod_pow(&g, &private_key, &p);
+
+    let own_salt = generate_salt_share(rng);
+    write_biguint(&mut stream, &own_public)?;
+    write_bytes(&mut stream, &own_salt)?;
+    stream.flush()?;
+    salt.extend(own_salt);
+
+    let shared_secret = Secret::new(mod_pow(&peer_public, &private_key, &p));
+    Ok(Exchange { own_public, peer_public, shared_secret, salt })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::thread;
+
+    /// RFC 2409 Oakley Group 1 (768 Bit) ist groß genug für validate_dh_params
+    fn test_group() -> (BigUint, BigUint) {
+        let p = BigUint::parse_bytes(
+            b"FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74\
+              020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437\
+              4FE1356D6D51C245E485B576625E7EC6F44C42E9A63A3620FFFFFFFFFFFFFFFF",
+            16,
+        ).unwrap();
+        (p, BigUint::from(2u32))
+    }
+
+    #[test]
+    fn test_wire_format_roundtrip() {
+        let value = BigUint::from(0x0102_0304_0506u64);
+        let mut buffer = Vec::new();
+        write_biguint(&mut buffer, &value).unwrap();
+
+        assert_eq!(&buffer[..4], &[0, 0, 0, 6]);
+        assert_eq!(read_biguint(&mut Cursor::new(buffer)).unwrap(), value);
+    }
+
+    #[test]
+    fn test_wire_format_rejects_oversized_length() {
+        let buffer = (MAX_MESSAGE_LEN as u32 + 1).to_be_bytes().to_vec();
+        assert!(read_biguint(&mut Cursor::new(buffer)).is_err());
+    }
+
+    #[test]
+    fn test_exchange_over_tcp() {
+        let (p, g) = test_group();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        let server_p = p.clone();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            serve(stream, &server_p, &g, None, &BigUint::from(123_456_789u32), &mut rand::thread_rng()).unwrap()
+        });
+
+        let client = connect(&addr, &mut rand::thread_rng(), |_| Ok(Secret::new(BigUint::from(987_654_321u32)))).unwrap();
+        let server = server.join().unwrap();
+
+        assert_eq!(client.shared_secret, server.shared_secret);
+        assert_eq!(client.peer_public, server.own_public);
+        assert_eq!(server.peer_public, client.own_public);
+        assert_eq!(client.salt.len(), 2 * SALT_SHARE_LEN);
+        assert_eq!(client.salt, server.salt);
+    }
+}
@@ -0,0 +1,289 @@
+use clap::Parser;
+use logging::Verbosity;
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::{One, ToPrimitive};
+use crypto_math::{is_probable_prime, mod_pow};
+use csprng::{Drbg, RngArgs};
+use key_formats::{DhParameters, Format, KeyFile};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+use tool_error::ToolError;
+
+mod pem;
+
+/// DH-Parametergenerierung: Generiert sichere Parameter für Diffie-Hellman-Schlüsselaustausch
+#[derive(Parser)]
+#[command(name = "dh_params")]
+#[command(about = "Generiert Diffie-Hellman Parameter (Primzahl p und Generator g)")]
+#[command(version = "1.0")]
+struct Args {
+    /// Bitlänge der Primzahl (ungefähr)
+    #[arg(value_name = "BITLENGTH")]
+    bit_length: usize,
+    
+    /// Anzahl der Miller-Rabin Runden für Primzahltest (Standard: 40)
+    #[arg(short = 'r', long = "rounds", default_value = "40")]
+    miller_rabin_rounds: u32,
+    
+    /// Anzahl paralleler Such-Threads (Standard: Anzahl CPU-Kerne, mit --seed 1)
+    ///
+    /// Mit mehreren Threads hängt das Ergebnis davon ab, welcher zuerst fündig
+    /// wird; reproduzierbar ist nur die Suche mit einem Thread.
+    #[arg(short = 't', long = "threads")]
+    threads: Option<usize>,
+    
+    /// Ausgabe als OpenSSL-kompatibles PEM (DH PARAMETERS) statt zwei Dezimalzeilen
+    #[arg(long = "pem", conflicts_with = "format")]
+    pem: bool,
+    
+    /// Ausgabeformat: zeilen (p und g, Standard), json oder toml
+    #[arg(long = "format", default_value = "zeilen")]
+    format: Format,
+
+    #[command(flatten)]
+    rng: RngArgs,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+/// Obergrenze für die kleinen Primzahlen des Vorsiebs
+const SIEVE_LIMIT: u32 = 2000;
+
+/// Berechnet alle ungeraden Primzahlen kleiner als `limit` (Sieb des Eratosthenes)
+fn small_primes(limit: u32) -> Vec<u32> {
+    let limit = limit as usize;
+    let mut is_prime = vec![true; limit];
+    let mut primes = Vec::new();
+    
+    for i in 2..limit {
+        if is_prime[i] {
+            if i > 2 {
+                primes.push(i as u32);
+            }
+            for multiple in (i * i..limit).step_by(i) {
+                is_prime[multiple] = false;
+            }
+        }
+    }
+    
+    primes
+}
+
+/// Vorsieb für Safe-Prime-Kandidaten
+/// Verwirft q, wenn q oder p = 2q + 1 durch eine kleine Primzahl r teilbar ist.
+/// Aus q ≡ m (mod r) folgt p ≡ 2m + 1 (mod r), daher genügt ein Rest pro Primzahl.
+fn passes_sieve(q: &BigUint, primes: &[u32]) -> bool {
+    for &r in primes {
+        let m = (q % r).to_u32().expect("Rest ist kleiner als r");
+        
+        // q = r bzw. p = r sind selbst prim und dürfen nicht verworfen werden
+        if m == 0 && q != &BigUint::from(r) {
+            return false;
+        }
+        if (2 * m + 1).is_multiple_of(r) && (q * 2u32 + 1u32) != BigUint::from(r) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Generiert eine sichere Primzahl q und prüft ob p = 2q + 1 auch prim ist
+/// Dies erzeugt eine "sichere Primzahl" (safe prime), die für DH geeignet ist
+/// 
+/// Die Suche läuft parallel auf `threads` Threads. Jeder Kandidat durchläuft zuerst
+/// das Vorsieb für q und p gleichzeitig, erst danach den teuren Miller-Rabin-Test.
+/// Jeder Thread erhält einen eigenen, aus `rng` abgeleiteten Generator.
+fn generate_safe_prime(bit_length: usize, k: u32, rng: &mut Drbg, threads: usize, progress: bool) -> (BigUint, BigUint) {
+    let primes = small_primes(SIEVE_LIMIT);
+    let found = AtomicBool::new(false);
+    let candidates = AtomicU64::new(0);
+    let sieved = AtomicU64::new(0);
+    let (sender, receiver) = mpsc::channel();
+    
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            let sender = sender.clone();
+            let (primes, found, candidates, sieved) = (&primes, &found, &candidates, &sieved);
+            let mut rng = rng.fork();
+            
+            scope.spawn(move || {
+                
+                while !found.load(Ordering::Relaxed) {
+                    // Generiere zufällige ungerade Zahl q mit gewünschter Bitlänge
+                    // Konvertiere usize zu u64 für gen_biguint
+                    let mut q = rng.gen_biguint((bit_length - 1) as u64);
+                    q |= BigUint::one(); // Stelle sicher, dass q ungerade ist
+                    q |= BigUint::one() << (bit_length - 2); // Setze höchstes Bit für korrekte Bitlänge
+                    candidates.fetch_add(1, Ordering::Relaxed);
+                    
+                    if !passes_sieve(&q, primes) {
+                        continue;
+                    }
+                    sieved.fetch_add(1, Ordering::Relaxed);
+                    
+                    // Prüfe ob q prim ist
+                    if !is_probable_prime(&q, k) {
+                        continue;
+                    }
+                    
+                    // Berechne p = 2q + 1 und prüfe ob p auch prim ist
+                    let p = &q * 2u32 + 1u32;
+                    if is_probable_prime(&p, k) && !found.swap(true, Ordering::Relaxed) {
+                        let _ = sender.send((p, q));
+                    }
+                }
+            });
+        }
+        drop(sender);
+        
+        // Fortschrittsanzeige auf stderr, bis ein Thread fündig wird
+        loop {
+            match receiver.recv_timeout(Duration::from_millis(500)) {
+                Ok(result) => {
+                    if progress {
+                        eprintln!();
+                    }
+                    return result;
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    if progress {
+                        eprint!(
+                            "\rKandidaten: {}, nach Sieb: {}",
+                            candidates.load(Ordering::Relaxed),
+                            sieved.load(Ordering::Relaxed)
+                        );
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => unreachable!("Suche endet erst mit einem Ergebnis"),
+            }
+        }
+    })
+}
+
+/// Findet einen Generator (oder Beinahe-Generator) für Z*_p
+/// Für sichere Primzahlen p = 2q + 1 testen wir einfache Kandidaten
+fn find_generator(p: &BigUint, q: &BigUint) -> BigUint {
+    let candidates = [2u32, 3u32, 5u32, 7u32, 11u32, 13u32, 17u32, 19u32, 23u32, 29u32];
+    
+    for &candidate in &candidates {
+        let g = BigUint::from(candidate);
+        
+        // g muss in [2, p-2] liegen
+        if g >= p - 1u32 {
+            continue;
+        }
+        
+        // Prüfe ob g^q ≢ 1 (mod p) und g^2 ≢ 1 (mod p)
+        // Dies stellt sicher, dass g eine große Ordnung hat
+        let g_to_q = mod_pow(&g, q, p);
+        let g_to_2 = mod_pow(&g, &BigUint::from(2u32), p);
+        
+        if g_to_q != BigUint::one() && g_to_2 != BigUint::one() {
+            return g;
+        }
+    }
+    
+    // Fallback: verwende g = 2
+    BigUint::from(2u32)
+}
+
+/// Startet das Werkzeug mit den übergebenen Kommandozeilenargumenten
+///
+/// Das erste Argument ist wie bei `std::env::args_os` der Programmname;
+/// so lässt sich das Werkzeug auch aus `cryptolab` heraus aufrufen.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+    
+    // Validiere Eingabe
+    if args.bit_length < 8 {
+        return Err(ToolError::usage("Bitlänge muss mindestens 8 sein"));
+    }
+    
+    // Generiere sichere Primzahl p = 2q + 1
+    let threads = match args.threads {
+        Some(threads) => threads,
+        None if args.rng.is_seeded() => 1,
+        None => thread::available_parallelism().map_or(1, |n| n.get()),
+    };
+    let mut rng = args.rng.rng();
+    let (p, q) = generate_safe_prime(args.bit_length, args.miller_rabin_rounds, &mut rng, threads, !args.verbosity.is_quiet());
+    
+    // Finde Generator g
+    let g = find_generator(&p, &q);
+    
+    // Ausgabe in gewünschtem Format
+    if args.pem {
+        print!("{}", pem::to_pem(&p, &g));
+        return Ok(());
+    }
+    // Zeilenformat: erste Zeile Primzahl p, zweite Zeile Generator g
+    let params = DhParameters { p, g };
+    print!("{}", params.encode(args.format)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::Algorithm;
+    
+    #[test]
+    fn test_small_primes() {
+        assert_eq!(small_primes(30), vec![3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    }
+    
+    #[test]
+    fn test_sieve_filters_q_and_p() {
+        let primes = small_primes(SIEVE_LIMIT);
+        // 15 = 3 * 5 ist zusammengesetzt
+        assert!(!passes_sieve(&BigUint::from(15u32), &primes));
+        // q = 7 ist prim, aber p = 15 nicht
+        assert!(!passes_sieve(&BigUint::from(7u32), &primes));
+        // q = 11, p = 23 ist ein Safe-Prime-Paar
+        assert!(passes_sieve(&BigUint::from(11u32), &primes));
+    }
+    
+    #[test]
+    fn test_generate_safe_prime_parallel() {
+        let mut rng = Drbg::from_entropy(Algorithm::ChaCha20);
+        let (p, q) = generate_safe_prime(64, 20, &mut rng, 2, false);
+        assert_eq!(p, &q * 2u32 + 1u32);
+        assert_eq!(p.bits(), 64);
+        assert!(is_probable_prime(&p, 20));
+        assert!(is_probable_prime(&q, 20));
+    }
+    
+    #[test]
+    fn test_safe_prime_resists_pohlig_hellman() {
+        use dlog::group::Group;
+        use dlog::pohlig_hellman::{factor_order, largest_factor, pohlig_hellman};
+
+        // Pohlig-Hellman kostet etwa die Wurzel des größten Primfaktors von p - 1
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"pohlig-hellman");
+        let (p, q) = generate_safe_prime(64, 20, &mut rng, 1, false);
+        let factors = factor_order(&(&p - 1u32), 1 << 16).unwrap();
+        assert_eq!(largest_factor(&factors), Some(&q));
+        assert_eq!(q.bits(), 63);
+
+        // Eine gleich große Primzahl mit glattem p - 1 fällt sofort
+        let smooth = Group::smooth(64, 12, &mut rng);
+        let factors = factor_order(&smooth.order, 1 << 16).unwrap();
+        assert!(largest_factor(&factors).unwrap().bits() <= 12);
+        let x = rng.gen_biguint_below(&smooth.order);
+        assert_eq!(pohlig_hellman(&smooth, &smooth.power(&x), &factors), Some(x));
+    }
+
+    #[test]
+    fn test_seeded_search_is_reproducible() {
+        let search = || generate_safe_prime(48, 20, &mut Drbg::from_seed(Algorithm::HmacSha256, b"dh"), 1, false);
+        assert_eq!(search(), search());
+    }
+}
@@ -0,0 +1,50 @@
+//! PEM-Ausgabe der DH-Parameter im OpenSSL-Format
+//!
+//! OpenSSL erwartet die Struktur aus PKCS #3:
+//!
+//! ```text
+//! DHParameter ::= SEQUENCE {
+//!     prime INTEGER,  -- p
+//!     base  INTEGER   -- g
+//! }
+//! ```
+//!
+//! DER-kodiert, Base64-kodiert und zwischen
+//! `-----BEGIN DH PARAMETERS-----` / `-----END DH PARAMETERS-----` eingebettet.
+
+use num_bigint::BigUint;
+
+/// DER-Kodierung der DHParameter-Struktur
+pub fn encode_dh_parameters(p: &BigUint, g: &BigUint) -> Vec<u8> {
+    asn1::sequence(&[asn1::integer(p), asn1::integer(g)])
+}
+
+/// PEM-Kodierung der DHParameter-Struktur (64 Zeichen pro Zeile)
+pub fn to_pem(p: &BigUint, g: &BigUint) -> String {
+    let encoded = codec::base64::encode(&encode_dh_parameters(p, g));
+
+    let mut pem = String::from("-----BEGIN DH PARAMETERS-----\n");
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).expect("Base64 ist ASCII"));
+        pem.push('\n');
+    }
+    pem.push_str("-----END DH PARAMETERS-----\n");
+    pem
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_small_parameters() {
+        let der = encode_dh_parameters(&BigUint::from(23u32), &BigUint::from(5u32));
+        assert_eq!(der, vec![0x30, 0x06, 0x02, 0x01, 0x17, 0x02, 0x01, 0x05]);
+    }
+
+    #[test]
+    fn test_pem_armor() {
+        let pem = to_pem(&BigUint::from(23u32), &BigUint::from(5u32));
+        assert_eq!(pem, "-----BEGIN DH PARAMETERS-----\nMAYCARcCAQU=\n-----END DH PARAMETERS-----\n");
+    }
+}
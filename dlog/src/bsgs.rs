@@ -0,0 +1,87 @@
+//! Baby-step giant-step (Shanks, 1971)
+//!
+//! Write the unknown x = i·m + j with 0 ≤ j < m. The baby steps store g^j
+//! for every j in a table; the giant steps compute h·g^(-i·m) for i = 0, 1, …
+//! until one of them is in the table. With m ≈ √n for a group of order n
+//! both phases take √n steps: a 2k-bit group order costs 2^k operations and
+//! 2^k table entries. A smaller table trades memory for more giant steps.
+
+use crypto_math::{integer_root, mod_inverse, mod_pow};
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+use std::collections::HashMap;
+
+use crate::group::Group;
+
+/// Number of baby steps: ⌈√order⌉, but at most `max_table`
+pub fn table_size(order: &BigUint, max_table: u64) -> u64 {
+    let root = integer_root(order, 2);
+    let ceil = if &root * &root == *order { root } else { root + 1u32 };
+    ceil.to_u64().unwrap_or(u64::MAX).min(max_table).max(1)
+}
+
+/// x with g^x = h, or `None` if h is not in the subgroup generated by g
+///
+/// Uses a table of at most `max_table` entries. The result is reduced
+/// modulo the order of the group.
+pub fn bsgs(group: &Group, h: &BigUint, max_table: u64) -> Option<BigUint> {
+    let Group { p, g, order } = group;
+    let m = table_size(order, max_table);
+
+    // Baby steps: g^j → j, keeping the smallest j for repeated values
+    let mut table = HashMap::with_capacity(m as usize);
+    let mut power = BigUint::from(1u32) % p;
+    for j in 0..m {
+        table.entry(power.clone()).or_insert(j);
+        power = power * g % p;
+    }
+
+    // Giant steps: h · (g^-m)^i
+    let factor = mod_inverse(&mod_pow(g, &BigUint::from(m), p), p)?;
+    let giant_steps = order / m + 1u32;
+    let mut gamma = h % p;
+    let mut i = BigUint::zero();
+    while i <= giant_steps {
+        if let Some(&j) = table.get(&gamma) {
+            return Some((&i * m + j) % order);
+        }
+        gamma = gamma * &factor % p;
+        i += 1u32;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1019 = 2·509 + 1 is a safe prime, 4 generates the subgroup of order 509
+    fn small_group() -> Group {
+        Group::new(BigUint::from(1019u32), BigUint::from(4u32), BigUint::from(509u32))
+    }
+
+    #[test]
+    fn test_table_size() {
+        assert_eq!(table_size(&BigUint::from(509u32), 1 << 20), 23);
+        assert_eq!(table_size(&BigUint::from(529u32), 1 << 20), 23);
+        assert_eq!(table_size(&BigUint::from(509u32), 5), 5);
+        assert_eq!(table_size(&BigUint::from(1u32), 5), 1);
+    }
+
+    #[test]
+    fn test_bsgs() {
+        let group = small_group();
+        for x in [0u32, 1, 2, 123, 508] {
+            let h = group.power(&BigUint::from(x));
+            assert_eq!(bsgs(&group, &h, 1 << 20), Some(BigUint::from(x)));
+            // A small table needs more giant steps but finds the same x
+            assert_eq!(bsgs(&group, &h, 3), Some(BigUint::from(x)));
+        }
+    }
+
+    #[test]
+    fn test_not_in_subgroup() {
+        // 2 is a quadratic non-residue mod 1019, the powers of 4 are all residues
+        assert_eq!(bsgs(&small_group(), &BigUint::from(2u32), 1 << 20), None);
+    }
+}
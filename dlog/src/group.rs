@@ -0,0 +1,169 @@
+//! Cyclic subgroups of Z*_p as used by Diffie-Hellman and DSA
+//!
+//! A discrete-log attack works in the subgroup generated by g, so its cost
+//! depends on the order of g, not on the size of p. Diffie-Hellman with a
+//! safe prime p = 2q + 1 uses a subgroup of order q ≈ p/2; DSA uses a small
+//! subgroup of prime order q inside a much larger p. A prime whose p - 1 has
+//! only small factors is what `dh_params` avoids: see [`crate::pohlig_hellman`].
+
+use crypto_math::{is_probable_prime, mod_pow};
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::One;
+use rand::Rng;
+
+/// Miller-Rabin rounds for the small primes generated here
+pub(crate) const ROUNDS: u32 = 40;
+
+/// Generator `g` of a subgroup of Z*_p with the given order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    pub p: BigUint,
+    pub g: BigUint,
+    pub order: BigUint,
+}
+
+/// Random prime of exactly `bits` bits
+fn random_prime(bits: u64, rng: &mut impl Rng) -> BigUint {
+    loop {
+        let candidate = rng.gen_biguint(bits) | BigUint::one() << (bits - 1) | BigUint::one();
+        if is_probable_prime(&candidate, ROUNDS) {
+            return candidate;
+        }
+    }
+}
+
+/// All primes below `limit` (sieve of Eratosthenes)
+fn primes_below(limit: u64) -> Vec<u64> {
+    let mut composite = vec![false; limit as usize];
+    let mut primes = Vec::new();
+    for n in 2..limit {
+        if !composite[n as usize] {
+            primes.push(n);
+            (n * n..limit).step_by(n as usize).for_each(|m| composite[m as usize] = true);
+        }
+    }
+    primes
+}
+
+/// Random prime of exactly `bits` bits from a sorted table
+fn pick_prime(primes: &[u64], bits: u64, rng: &mut impl Rng) -> BigUint {
+    let range = primes.partition_point(|&q| q < 1 << (bits - 1))..primes.partition_point(|&q| q < 1 << bits);
+    BigUint::from(primes[rng.gen_range(range)])
+}
+
+/// Element of order `order`, raising random elements to the cofactor
+fn subgroup_generator(p: &BigUint, order: &BigUint, rng: &mut impl Rng) -> BigUint {
+    let cofactor = (p - 1u32) / order;
+    loop {
+        let g = mod_pow(&rng.gen_biguint_range(&BigUint::from(2u32), &(p - 1u32)), &cofactor, p);
+        if !g.is_one() {
+            return g;
+        }
+    }
+}
+
+impl Group {
+    pub fn new(p: BigUint, g: BigUint, order: BigUint) -> Self {
+        Group { p, g, order }
+    }
+
+    /// Diffie-Hellman group like `dh_params`: safe prime of `bits` bits, g of prime order (p - 1)/2
+    pub fn safe_prime(bits: u64, rng: &mut impl Rng) -> Self {
+        assert!(bits >= 4, "safe primes need at least 4 bits");
+        loop {
+            let q = random_prime(bits - 1, rng);
+            let p = &q * 2u32 + 1u32;
+            if is_probable_prime(&p, ROUNDS) {
+                let g = subgroup_generator(&p, &q, rng);
+                return Group { p, g, order: q };
+            }
+        }
+    }
+
+    /// DSA group like `dsa-keygen`: q of `q_bits` bits dividing p - 1, p of `p_bits` bits
+    pub fn dsa(p_bits: u64, q_bits: u64, rng: &mut impl Rng) -> Self {
+        assert!(p_bits > q_bits && q_bits >= 2, "p must be longer than q");
+        let q = random_prime(q_bits, rng);
+        let low = (BigUint::one() << (p_bits - 1)) / &q + 1u32;
+        let high = (BigUint::one() << p_bits) / &q;
+        let p = loop {
+            let p = rng.gen_biguint_range(&low, &high) * &q + 1u32;
+            if p.bits() == p_bits && is_probable_prime(&p, ROUNDS) {
+                break p;
+            }
+        };
+        let g = subgroup_generator(&p, &q, rng);
+        Group { p, g, order: q }
+    }
+
+    /// Group of order p - 1 where p has `p_bits` bits and p - 1 = 2 · q_1 · q_2 · …
+    /// has only prime factors of at most `factor_bits` bits
+    pub fn smooth(p_bits: u64, factor_bits: u64, rng: &mut impl Rng) -> Self {
+        assert!((2..=32).contains(&factor_bits) && p_bits > factor_bits + 1, "p must be longer than its factors");
+        let primes = primes_below(1 << factor_bits);
+        loop {
+            let mut factors = vec![BigUint::from(2u32)];
+            let mut order = BigUint::from(2u32);
+            while order.bits() + factor_bits <= p_bits {
+                let q = pick_prime(&primes, factor_bits, rng);
+                order *= &q;
+                factors.push(q);
+            }
+            // The last factor fills up the missing bits
+            let rest = p_bits - order.bits();
+            if rest >= 2 {
+                let q = pick_prime(&primes, rest, rng);
+                order *= &q;
+                factors.push(q);
+            }
+            let p = &order + 1u32;
+            if p.bits() != p_bits || !is_probable_prime(&p, ROUNDS) {
+                continue;
+            }
+            // A generator of the whole group is no q-th power for any q
+            let g = loop {
+                let g = rng.gen_biguint_range(&BigUint::from(2u32), &order);
+                if factors.iter().all(|q| !mod_pow(&g, &(&order / q), &p).is_one()) {
+                    break g;
+                }
+            };
+            return Group { p, g, order };
+        }
+    }
+
+    /// g^x mod p
+    pub fn power(&self, x: &BigUint) -> BigUint {
+        mod_pow(&self.g, x, &self.p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::{Algorithm, Drbg};
+
+    #[test]
+    fn test_generated_groups() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"group");
+        let dh = Group::safe_prime(40, &mut rng);
+        assert_eq!(dh.p.bits(), 40);
+        assert_eq!(dh.order, (&dh.p - 1u32) / 2u32);
+
+        let dsa = Group::dsa(128, 24, &mut rng);
+        assert_eq!((dsa.p.bits(), dsa.order.bits()), (128, 24));
+        assert_eq!(((&dsa.p - 1u32) % &dsa.order), BigUint::ZERO);
+
+        let smooth = Group::smooth(128, 12, &mut rng);
+        assert_eq!(smooth.p.bits(), 128);
+        assert_eq!(smooth.order, &smooth.p - 1u32);
+        assert!(smooth.power(&smooth.order).is_one());
+        assert!(!smooth.power(&(&smooth.order / 2u32)).is_one());
+        assert_eq!(primes_below(30), [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+
+        for group in [dh, dsa] {
+            assert!(is_probable_prime(&group.order, ROUNDS));
+            assert!(group.power(&group.order).is_one());
+            assert!(!group.g.is_one());
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! Computes discrete logarithms in small Diffie-Hellman and DSA groups.
+//!
+//! Given g and h = g^x mod p, the attacker wants x. Generic algorithms such
+//! as baby-step giant-step or Pollard's rho need about √n steps for a group
+//! of order n, so every two bits of group order double the work. `--scale`
+//! measures this: it attacks ever larger groups and extrapolates the time to
+//! real sizes.
+//!
+//! Pohlig–Hellman only pays for the largest prime factor of n. With
+//! `--group smooth` it breaks a large p whose p - 1 has only small factors,
+//! which is why `dh_params` insists on safe primes.
+//!
+//! Without `-p` the tool generates a group like `dh_params` (safe prime),
+//! `dsa-keygen` (small subgroup of a large p) or a smooth one, and a random
+//! secret to recover.
+
+use clap::{Parser, ValueEnum};
+use crypto_math::is_probable_prime;
+use csprng::RngArgs;
+use logging::{English, Verbosity, info};
+use num_bigint::{BigUint, RandBigInt};
+use std::time::{Duration, Instant};
+use tool_error::ToolError;
+
+pub mod bsgs;
+pub mod group;
+pub mod pohlig_hellman;
+pub mod rho;
+
+use group::Group;
+
+/// Trial division bound for factoring the group order
+const TRIAL_BOUND: u32 = 1 << 20;
+
+/// Kind of group the demo generates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum GroupKind {
+    /// Safe prime p = 2q + 1 as from dh_params, order q
+    Dh,
+    /// Subgroup of prime order q in a larger p as from dsa-keygen
+    Dsa,
+    /// p - 1 with only small prime factors, order p - 1
+    Smooth,
+}
+
+/// Algorithm for the logarithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Method {
+    /// Baby-step giant-step, √n time and memory
+    Bsgs,
+    /// Pollard's rho, √n time and constant memory, prime order only
+    Rho,
+    /// Pohlig–Hellman, √q for the largest prime factor q of the order
+    PohligHellman,
+}
+
+/// Command-line arguments for the discrete-log solver.
+#[derive(Parser, Debug)]
+#[command(name = "dlog", about = "Discrete logarithms in small or smooth DH and DSA groups")]
+#[command(after_help = "Exit codes: 0 logarithm found, 1 no logarithm (h not generated by g), 2 invalid arguments")]
+struct Cli {
+    /// Bits of the group order in the demo
+    #[arg(short, long, default_value_t = 32, value_parser = clap::value_parser!(u64).range(4..=96), help = "Bits of the group order to attack")]
+    bits: u64,
+
+    /// Kind of group in the demo
+    #[arg(long, value_enum, default_value = "dh", help = "Group to generate")]
+    group: GroupKind,
+
+    /// Size of p for DSA and smooth groups
+    #[arg(long, default_value_t = 512, help = "Bits of p for --group dsa and smooth")]
+    p_bits: u64,
+
+    /// Size of the factors of p - 1 for smooth groups
+    #[arg(long, default_value_t = 16, value_parser = clap::value_parser!(u64).range(2..=20), help = "Bits of the prime factors of p - 1 for --group smooth")]
+    factor_bits: u64,
+
+    /// Algorithm
+    #[arg(long, value_enum, default_value = "bsgs", help = "Algorithm for the logarithm")]
+    method: Method,
+
+    /// Largest baby-step table
+    #[arg(short, long, default_value_t = 1 << 22, value_parser = clap::value_parser!(u64).range(1..), help = "Largest number of table entries (memory bound)")]
+    max_table: u64,
+
+    /// Attack every size from 16 bits up to --bits
+    #[arg(long, help = "Attack growing groups from 16 bits up to --bits and extrapolate")]
+    scale: bool,
+
+    /// Modulus of a given instance
+    #[arg(short, long, requires_all = ["generator", "target"], conflicts_with = "scale", help = "Solve this instance: prime modulus p")]
+    prime: Option<BigUint>,
+
+    /// Generator of a given instance
+    #[arg(short, long, requires = "prime", help = "Generator g")]
+    generator: Option<BigUint>,
+
+    /// Target h = g^x of a given instance
+    #[arg(short = 'y', long, requires = "prime", help = "Target h = g^x, e.g. a public key")]
+    target: Option<BigUint>,
+
+    /// Order of g, if known
+    #[arg(short, long, requires = "prime", help = "Order of g (default: p - 1)")]
+    order: Option<BigUint>,
+
+    #[command(flatten)]
+    rng: RngArgs,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+/// Result of one attack
+struct Solution {
+    x: Option<BigUint>,
+    /// Bits of the largest group the method had to attack generically
+    hardest: u64,
+    time: Duration,
+}
+
+/// Solves one instance with the chosen method
+fn solve(group: &Group, h: &BigUint, method: Method, max_table: u64) -> tool_error::Result<Solution> {
+    let start = Instant::now();
+    let (x, hardest) = match method {
+        Method::Bsgs => {
+            info!("Baby-step giant-step with {} table entries", bsgs::table_size(&group.order, max_table));
+            (bsgs::bsgs(group, h, max_table), group.order.bits())
+        }
+        Method::Rho if !is_probable_prime(&group.order, group::ROUNDS) => {
+            return Err(ToolError::usage("rho needs a group of prime order, use --method pohlig-hellman"));
+        }
+        Method::Rho => (rho::rho(group, h), group.order.bits()),
+        Method::PohligHellman => {
+            let factors = pohlig_hellman::factor_order(&group.order, TRIAL_BOUND)
+                .ok_or_else(|| ToolError::failed(format!("order has several prime factors above {}", TRIAL_BOUND)))?;
+            let text: Vec<String> = factors.iter().map(|(q, e)| if *e == 1 { q.to_string() } else { format!("{}^{}", q, e) }).collect();
+            info!("Order {}", text.join(" · "));
+            let largest = pohlig_hellman::largest_factor(&factors).map_or(0, |q| q.bits());
+            (pohlig_hellman::pohlig_hellman(group, h, &factors), largest)
+        }
+    };
+    Ok(Solution { x, hardest, time: start.elapsed() })
+}
+
+fn generate(cli: &Cli, bits: u64, rng: &mut impl rand::Rng) -> tool_error::Result<Group> {
+    match cli.group {
+        GroupKind::Dh => Ok(Group::safe_prime(bits + 1, rng)),
+        GroupKind::Dsa if cli.p_bits > bits => Ok(Group::dsa(cli.p_bits, bits, rng)),
+        GroupKind::Dsa => Err(ToolError::usage("--p-bits must be larger than --bits")),
+        GroupKind::Smooth if cli.p_bits > cli.factor_bits + 1 => Ok(Group::smooth(cli.p_bits, cli.factor_bits, rng)),
+        GroupKind::Smooth => Err(ToolError::usage("--p-bits must be larger than --factor-bits")),
+    }
+}
+
+/// log2 of the years a √n attack on an order of `target` bits would take
+fn extrapolate(seconds: f64, bits: u64, target: u64) -> f64 {
+    const SECONDS_PER_YEAR: f64 = 365.25 * 24.0 * 3600.0;
+    seconds.log2() + (target as f64 - bits as f64) / 2.0 - SECONDS_PER_YEAR.log2()
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+
+    if let (Some(p), Some(g), Some(h)) = (&cli.prime, &cli.generator, &cli.target) {
+        let order = cli.order.clone().unwrap_or_else(|| p - 1u32);
+        let group = Group::new(p.clone(), g.clone(), order);
+        let solution = solve(&group, h, cli.method, cli.max_table)?;
+        let x = solution.x.ok_or_else(|| ToolError::failed("h is not a power of g"))?;
+        println!("x = {} ({:.3} s)", x, solution.time.as_secs_f64());
+        return Ok(());
+    }
+    if cli.scale && cli.group == GroupKind::Smooth {
+        return Err(ToolError::usage("--scale grows the order, which --group smooth fixes by --p-bits"));
+    }
+
+    let mut rng = cli.rng.rng();
+    let sizes: Vec<u64> = if cli.scale { (16..=cli.bits).step_by(4).collect() } else { vec![cli.bits] };
+    println!("Order bits  Largest attacked  p bits  Time");
+    let mut last = (0, 0.0);
+    for bits in sizes {
+        let group = generate(&cli, bits, &mut rng)?;
+        let x = rng.gen_biguint_below(&group.order);
+        let h = group.power(&x);
+        let solution = solve(&group, &h, cli.method, cli.max_table)?;
+        if solution.x.as_ref() != Some(&x) {
+            return Err(ToolError::failed(format!("secret of the {}-bit group not recovered", group.order.bits())));
+        }
+        let seconds = solution.time.as_secs_f64();
+        println!("{:>10}  {:>16}  {:>6}  {:.3} s", group.order.bits(), solution.hardest, group.p.bits(), seconds);
+        last = (solution.hardest, seconds.max(1e-6));
+    }
+
+    let (bits, seconds) = last;
+    if cli.group == GroupKind::Smooth {
+        println!("Every secret recovered: Pohlig–Hellman never attacked more than {} bits.", bits);
+        println!("With a safe prime from dh_params the largest factor has {} bits.", cli.p_bits - 1);
+        return Ok(());
+    }
+    println!("Every secret recovered. At this speed a √n attack would need");
+    println!("  2^{:.0} years for a 160-bit DSA subgroup", extrapolate(seconds, bits, 160));
+    println!("  2^{:.0} years for 2048-bit Diffie-Hellman with a safe prime", extrapolate(seconds, bits, 2047));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_demo() {
+        assert!(run(["dlog", "--bits", "24", "--seed", "dlog"]).is_ok());
+        assert!(run(["dlog", "--bits", "20", "--group", "dsa", "--p-bits", "128", "--scale", "--seed", "dlog"]).is_ok());
+        assert!(run(["dlog", "--bits", "20", "--group", "dsa", "--p-bits", "16"]).is_err());
+    }
+
+    #[test]
+    fn test_methods() {
+        assert!(run(["dlog", "--bits", "32", "--group", "dsa", "--p-bits", "256", "--method", "rho", "--seed", "dlog"]).is_ok());
+        assert!(run(["dlog", "--group", "smooth", "--p-bits", "256", "--method", "pohlig-hellman", "--seed", "dlog"]).is_ok());
+        // Rho needs prime order, the smooth group has order p - 1
+        assert!(run(["dlog", "--group", "smooth", "--p-bits", "128", "--method", "rho", "--seed", "dlog"]).is_err());
+        assert!(run(["dlog", "--group", "smooth", "--scale"]).is_err());
+        // 1458 = 2·3^6
+        assert!(run(["dlog", "-p", "1459", "-g", "3", "-y", "1000", "--method", "pohlig-hellman"]).is_ok());
+    }
+
+    #[test]
+    fn test_given_instance() {
+        assert!(run(["dlog", "-p", "1019", "-g", "4", "-y", "504", "--order", "509"]).is_ok());
+        assert!(run(["dlog", "-p", "1019", "-g", "4", "-y", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        // Twice the bits of order, square root of the work: 20 more bits, 2^10 times longer
+        assert!((extrapolate(1.0, 40, 60) - (10.0 - (365.25f64 * 86400.0).log2())).abs() < 1e-9);
+    }
+}
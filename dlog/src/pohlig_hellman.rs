@@ -0,0 +1,129 @@
+//! Pohlig–Hellman (1978)
+//!
+//! If the group order n = ∏ q_i^e_i, the map y ↦ y^(n / q_i^e_i) sends the
+//! problem into the subgroup of order q_i^e_i, where x mod q_i^e_i is found
+//! one base-q_i digit at a time with a logarithm in a group of order q_i.
+//! The Chinese remainder theorem puts the pieces back together. The cost is
+//! about Σ e_i·√q_i, dominated by the largest prime factor: a 2048-bit group
+//! whose order has only 20-bit factors falls in seconds. A safe prime
+//! p = 2q + 1 leaves q ≈ p/2 as the largest factor, so nothing is gained.
+
+use crypto_math::{crt, is_probable_prime, mod_inverse, mod_pow};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+use crate::group::{Group, ROUNDS};
+use crate::rho;
+
+/// Prime factors with exponents, as (q, e) for each q^e
+pub type Factors = Vec<(BigUint, u32)>;
+
+/// Factors `order` by trial division up to `bound`
+///
+/// `None` if what is left after trial division is neither 1 nor prime.
+pub fn factor_order(order: &BigUint, bound: u32) -> Option<Factors> {
+    let mut n = order.clone();
+    let mut factors = Vec::new();
+    let mut divisor = 2u32;
+    while divisor <= bound && BigUint::from(divisor) * divisor <= n {
+        let mut exponent = 0;
+        while (&n % divisor).is_zero() {
+            n /= divisor;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((BigUint::from(divisor), exponent));
+        }
+        divisor += if divisor == 2 { 1 } else { 2 };
+    }
+    if !n.is_one() {
+        if !is_probable_prime(&n, ROUNDS) {
+            return None;
+        }
+        factors.push((n, 1));
+    }
+    Some(factors)
+}
+
+/// Largest prime factor, the one that decides the cost of the attack
+pub fn largest_factor(factors: &[(BigUint, u32)]) -> Option<&BigUint> {
+    factors.iter().map(|(q, _)| q).max()
+}
+
+/// x with g^x = h, given the factorization of the group order
+///
+/// The subproblems of prime order are solved with [`rho::rho`]. `None` if h
+/// is not a power of g.
+pub fn pohlig_hellman(group: &Group, h: &BigUint, factors: &[(BigUint, u32)]) -> Option<BigUint> {
+    let Group { p, g, order } = group;
+    debug_assert_eq!(factors.iter().map(|(q, e)| q.pow(*e)).product::<BigUint>(), *order);
+
+    let mut congruences = Vec::with_capacity(factors.len());
+    for (q, e) in factors {
+        // Move g and h into the subgroup of order q^e
+        let q_e = q.pow(*e);
+        let cofactor = order / &q_e;
+        let g_e = mod_pow(g, &cofactor, p);
+        let h_e = mod_pow(h, &cofactor, p);
+        let g_e_inverse = mod_inverse(&g_e, p)?;
+        let digits = Group::new(p.clone(), mod_pow(&g_e, &q.pow(e - 1), p), q.clone());
+
+        // x mod q^e = d_0 + d_1·q + … with each digit a logarithm of order q
+        let mut x = BigUint::zero();
+        let mut place = BigUint::one();
+        for k in 0..*e {
+            let remaining = h_e.clone() * mod_pow(&g_e_inverse, &x, p) % p;
+            let digit = rho::rho(&digits, &mod_pow(&remaining, &q.pow(e - 1 - k), p))?;
+            x += digit * &place;
+            place *= q;
+        }
+        congruences.push((x, q_e));
+    }
+
+    let (x, _) = crt(&congruences)?;
+    (group.power(&x) == h % p).then_some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::{Algorithm, Drbg};
+    use num_bigint::RandBigInt;
+
+    #[test]
+    fn test_factor_order() {
+        let factors = factor_order(&BigUint::from(2u32 * 2 * 2 * 3 * 1019 * 1019), 1 << 10).unwrap();
+        assert_eq!(factors, [(2u32, 3), (3, 1), (1019, 2)].map(|(q, e)| (BigUint::from(q), e)));
+        // One prime above the bound is fine, two are not
+        let big = BigUint::from(1_000_003u32);
+        assert_eq!(factor_order(&(&big * 6u32), 1 << 10).unwrap().last(), Some(&(big.clone(), 1)));
+        assert_eq!(factor_order(&(&big * &big), 1 << 10), None);
+    }
+
+    #[test]
+    fn test_smooth_group() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"pohlig-hellman");
+        let group = Group::smooth(256, 16, &mut rng);
+        let factors = factor_order(&group.order, 1 << 16).unwrap();
+        assert!(largest_factor(&factors).unwrap().bits() <= 16);
+        for _ in 0..3 {
+            let x = rng.gen_biguint_below(&group.order);
+            assert_eq!(pohlig_hellman(&group, &group.power(&x), &factors), Some(x));
+        }
+    }
+
+    #[test]
+    fn test_prime_powers() {
+        // 1458 = 2·3^6, 3 is a primitive root mod 1459
+        let group = Group::new(BigUint::from(1459u32), BigUint::from(3u32), BigUint::from(1458u32));
+        let factors = factor_order(&group.order, 100).unwrap();
+        assert_eq!(factors, [(BigUint::from(2u32), 1), (BigUint::from(3u32), 6)]);
+        for x in [0u32, 1, 728, 1000, 1457] {
+            assert_eq!(pohlig_hellman(&group, &group.power(&BigUint::from(x)), &factors), Some(BigUint::from(x)));
+        }
+        // The squares form the subgroup of order 729, which does not contain 3
+        let squares = Group::new(BigUint::from(1459u32), BigUint::from(9u32), BigUint::from(729u32));
+        let factors = factor_order(&squares.order, 100).unwrap();
+        assert_eq!(pohlig_hellman(&squares, &BigUint::from(3u32), &factors), None);
+    }
+}
@@ -0,0 +1,94 @@
+//! Pollard's rho for discrete logarithms (1978)
+//!
+//! A pseudo-random walk through elements g^a·h^b, where the next step depends
+//! only on the current element, must eventually repeat. By the birthday
+//! paradox that happens after about √n steps for a group of order n. Floyd's
+//! cycle finding detects the repetition with two walkers and no table, and a
+//! collision g^a1·h^b1 = g^a2·h^b2 gives x = (a1 - a2) / (b2 - b1) mod n.
+//! Same time as baby-step giant-step, constant memory.
+
+use crypto_math::{mod_inverse, mod_pow};
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive};
+
+use crate::bsgs;
+use crate::group::Group;
+
+/// Orders below this are solved with a table; tiny groups leave the walk no room
+const MIN_ORDER: u32 = 1 << 10;
+
+/// Starting points to try before giving up
+const ATTEMPTS: u32 = 16;
+
+/// Position of the walk: element x = g^a·h^b with the exponents a and b
+type Point = (BigUint, BigUint, BigUint);
+
+/// One step of the walk, split into three classes by x mod 3
+fn step(group: &Group, h: &BigUint, (x, a, b): Point) -> Point {
+    let Group { p, g, order } = group;
+    match (&x % 3u32).to_u32() {
+        Some(0) => (x * g % p, (a + 1u32) % order, b),
+        Some(1) => (&x * &x % p, a * 2u32 % order, b * 2u32 % order),
+        _ => (x * h % p, a, (b + 1u32) % order),
+    }
+}
+
+/// x with g^x = h in a group of prime order, or `None` if h is not in it
+pub fn rho(group: &Group, h: &BigUint) -> Option<BigUint> {
+    let Group { p, order, .. } = group;
+    if *order < BigUint::from(MIN_ORDER) {
+        return bsgs::bsgs(group, h, u64::MAX);
+    }
+    // Outside the subgroup the walk still collides, but the collision means nothing
+    if !mod_pow(h, order, p).is_one() {
+        return None;
+    }
+
+    let h = h % p;
+    for attempt in 1..=ATTEMPTS {
+        let (a, b) = (BigUint::from(attempt), BigUint::one());
+        let start = (group.power(&a) * &h % p, a, b);
+        let mut tortoise = step(group, &h, start.clone());
+        let mut hare = step(group, &h, tortoise.clone());
+        while tortoise.0 != hare.0 {
+            tortoise = step(group, &h, tortoise);
+            hare = step(group, &h, step(group, &h, hare));
+        }
+
+        let ((_, a1, b1), (_, a2, b2)) = (tortoise, hare);
+        // b2 = b1 gives no equation for x; start again elsewhere
+        let Some(inverse) = mod_inverse(&((b2 + order - b1) % order), order) else {
+            continue;
+        };
+        let x = (a1 + order - a2) * inverse % order;
+        if group.power(&x) == h {
+            return Some(x);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::{Algorithm, Drbg};
+    use num_bigint::RandBigInt;
+
+    #[test]
+    fn test_rho() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"rho");
+        let group = Group::dsa(128, 28, &mut rng);
+        for _ in 0..4 {
+            let x = rng.gen_biguint_below(&group.order);
+            assert_eq!(rho(&group, &group.power(&x)), Some(x));
+        }
+        // A non-residue is not a power of g
+        assert_eq!(rho(&group, &(&group.p - 1u32)), None);
+    }
+
+    #[test]
+    fn test_small_order() {
+        let group = Group::new(BigUint::from(1019u32), BigUint::from(4u32), BigUint::from(509u32));
+        assert_eq!(rho(&group, &group.power(&BigUint::from(321u32))), Some(BigUint::from(321u32)));
+    }
+}
@@ -0,0 +1,135 @@
+use std::error::Error;
+use clap::Parser;
+use logging::Verbosity;
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::One;
+use rand::Rng;
+use crypto_math::{is_probable_prime, mod_pow};
+use csprng::RngArgs;
+use key_formats::{DsaParameters, DsaPrivateKey, DsaPublicKey, Format, KeyFile};
+use secret::Secret;
+use tool_error::ToolError;
+
+/// DSA-Schlüsselgenerator - Parameter (L, N) = (1024, 160)
+#[derive(Parser)]
+#[command(name = "dsa_keygen")]
+#[command(about = "Generiert DSA Parameter und Schlüssel")]
+#[command(version = "1.0")]
+struct Args {
+    /// Dateiname für den öffentlichen Schlüssel
+    #[arg(long, default_value = "public_key.pem")]
+    public_key_file: String,
+    
+    /// Dateiname für den privaten Schlüssel
+    #[arg(long, default_value = "private_key.pem")]    
+    private_key_file: String,
+    
+    /// Bitlänge der großen Primzahl p (Standard: 1024)
+    #[arg(long, default_value = "1024")]
+    p_length: usize,
+    
+    /// Bitlänge der kleinen Primzahl q (Standard: 160)
+    #[arg(long, default_value = "160")]
+    q_length: usize,
+    
+    /// Format der Schlüsseldateien: zeilen, json oder toml
+    #[arg(long, default_value = "zeilen")]
+    format: Format,
+
+    #[command(flatten)]
+    rng: RngArgs,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+/// Startet das Werkzeug mit den übergebenen Kommandozeilenargumenten
+///
+/// Das erste Argument ist wie bei `std::env::args_os` der Programmname;
+/// so lässt sich das Werkzeug auch aus `cryptolab` heraus aufrufen.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+    
+    validate_parameters(args.p_length, args.q_length)?;
+    
+    let mut rng = args.rng.rng();
+    let params = generate_dsa_parameters(args.p_length, args.q_length, &mut rng)?;
+    let keys = generate_dsa_keys(&params, &mut rng)?;
+    
+    let public_key = DsaPublicKey { params: params.clone(), y: keys.public_key };
+    let private_key = DsaPrivateKey { params, x: keys.private_key };
+    public_key.write(&args.public_key_file, args.format)?;
+    private_key.write(&args.private_key_file, args.format)?;
+    
+    Ok(())
+}
+
+/// DSA-Schlüsselpaar  
+#[derive(Debug)]
+struct DSAKeys {
+    private_key: Secret<BigUint>,  // x
+    public_key: BigUint,   // y = g^x mod p
+}
+
+fn validate_parameters(l_bits: usize, n_bits: usize) -> tool_error::Result<()> {
+    if l_bits < n_bits {
+        return Err(ToolError::usage("L muss größer als N sein"));
+    }
+    Ok(())
+}
+
+fn generate_dsa_parameters(l_bits: usize, n_bits: usize, rng: &mut impl Rng) -> Result<DsaParameters, Box<dyn Error>> {
+    // Konvertiere zu u64 für gen_biguint
+    let n_bits_u64 = n_bits as u64;
+    let l_bits_u64 = l_bits as u64;
+    
+    // Generiere q (160 Bits)
+    let q = loop {
+        let mut candidate = rng.gen_biguint(n_bits_u64);
+        candidate |= BigUint::one() << (n_bits - 1);
+        candidate |= BigUint::one();
+        
+        if is_probable_prime(&candidate, 40) {
+            break candidate;
+        }
+    };
+    
+    // Generiere p (1024 Bits) mit p = k*q + 1
+    let p = loop {
+        let min_k = (BigUint::one() << (l_bits - 1)) / &q;
+        let max_k = ((BigUint::one() << l_bits) - BigUint::one()) / &q;
+        
+        let k = rng.gen_biguint_range(&min_k, &max_k);
+        let p_candidate = &k * &q + BigUint::one();
+        
+        // Korrigierter Typvergleich
+        if p_candidate.bits() == l_bits_u64 && is_probable_prime(&p_candidate, 40) {
+            break p_candidate;
+        }
+    };
+    
+    // Generiere Generator g
+    let exponent = (&p - BigUint::one()) / &q;
+    let g = loop {
+        let h = rng.gen_biguint_range(&BigUint::from(2u32), &p);
+        let g_candidate = mod_pow(&h, &exponent, &p);
+        
+        if g_candidate != BigUint::one() {
+            break g_candidate;
+        }
+    };
+    
+    Ok(DsaParameters { p, q, g })
+}
+
+fn generate_dsa_keys(params: &DsaParameters, rng: &mut impl Rng) -> Result<DSAKeys, Box<dyn Error>> {
+    let private_key = Secret::new(rng.gen_biguint_range(&BigUint::from(2u32), &params.q));
+    let public_key = mod_pow(&params.g, &private_key, &params.p);
+    
+    Ok(DSAKeys { private_key, public_key })
+}
@@ -0,0 +1,148 @@
+use std::error::Error;
+use clap::Parser;
+use logging::Verbosity;
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::Zero;
+use rand::Rng;
+use sha2::{Sha224, Digest};
+use crypto_math::{mod_inverse, mod_pow};
+use csprng::RngArgs;
+use key_formats::{DsaParameters, DsaPrivateKey, DsaSignature, Format, KeyFile};
+use secret::Secret;
+use tool_error::{Context, ToolError};
+
+/// DSA-Signatur-Programm mit SHA-224
+#[derive(Parser)]
+#[command(name = "dsa_sign")]
+#[command(about = "Erstellt DSA-Signatur für eine Nachricht")]
+#[command(version = "1.0")]
+struct Args {
+    /// Datei mit privatem Schlüssel
+    #[arg(long, help = "Pfad zur privaten Schlüsseldatei (p, q, g, x)")]
+    private_key_file: String,
+    
+    /// Datei mit der zu signierenden Nachricht
+    #[arg(long, help = "Pfad zur Nachrichtendatei")]
+    message_file: String,
+    
+    /// Ausgabedatei für Signatur (optional, sonst stdout)
+    #[arg(short, long, help = "Ausgabedatei für Signatur (r, s)")]
+    output: Option<String>,
+    
+    /// Format der Signatur
+    #[arg(long, default_value = "zeilen", help = "Format der Signatur: zeilen, json oder toml")]
+    format: Format,
+
+    #[command(flatten)]
+    rng: RngArgs,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+/// Startet das Werkzeug mit den übergebenen Kommandozeilenargumenten
+///
+/// Das erste Argument ist wie bei `std::env::args_os` der Programmname;
+/// so lässt sich das Werkzeug auch aus `cryptolab` heraus aufrufen.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+    
+    let (params, private_key) = load_private_key(&args.private_key_file)?;
+    let message = tool_error::fs::read_to_string(&args.message_file)?;
+    
+    let signature = dsa_sign(message.as_bytes(), &params, &private_key, &mut args.rng.rng())?;
+    
+    // Ausgabe der Signatur
+    let signature_text = signature.encode(args.format)?;
+    
+    match args.output {
+        Some(output_file) => {
+            tool_error::fs::write(output_file, signature_text)?;
+        }
+        None => {
+            print!("{}", signature_text);
+        }
+    }
+    
+    Ok(())
+}
+
+/// Lädt privaten Schlüssel aus Datei
+/// 
+/// Erwartet Format (oder JSON/TOML mit denselben Feldnamen):
+/// Zeile 1: p (1024-Bit Primzahl)
+/// Zeile 2: q (160-Bit Primzahl) 
+/// Zeile 3: g (Generator)
+/// Zeile 4: x (privater Schlüssel)
+fn load_private_key(filename: &str) -> tool_error::Result<(DsaParameters, Secret<BigUint>)> {
+    let key = DsaPrivateKey::read(filename)
+        .with_context(|| format!("Kann private Schlüsseldatei '{}' nicht lesen", filename))?;
+    
+    // Validierung der Schlüsselparameter
+    if *key.x >= key.params.q {
+        return Err(ToolError::data("Privater Schlüssel x muss kleiner als q sein"));
+    }
+    
+    Ok((key.params, key.x))
+}
+
+/// SHA-224 Hashfunktion für DSA
+fn sha224_hash(input: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha224::new();
+    hasher.update(input);
+    hasher.finalize().to_vec()
+}
+
+/// Konvertiert SHA-224 Hash zu BigUint für DSA-Berechnung
+fn hash_to_bigint(hash_bytes: &[u8], q: &BigUint) -> BigUint {
+    let hash_int = BigUint::from_bytes_be(hash_bytes);
+    // SHA-224 erzeugt 224 Bits, q ist 160 Bits - Reduktion nötig
+    hash_int % q
+}
+
+/// DSA-Signatur erstellen
+/// 
+/// Algorithmus:
+/// 1. H(m) = SHA-224(message) mod q  
+/// 2. Wähle zufälliges k ∈ [1, q-1]
+/// 3. r = (g^k mod p) mod q
+/// 4. s = k^(-1) * (H(m) + x*r) mod q
+/// 5. Signatur = (r, s)
+///
+/// k und k^(-1) verraten zusammen mit der Signatur den privaten Schlüssel und
+/// werden deshalb wie x beim Freigeben überschrieben.
+pub fn dsa_sign(message: &[u8], params: &DsaParameters, private_key: &BigUint, rng: &mut impl Rng) -> Result<DsaSignature, Box<dyn Error>> {
+    // Schritt 1: Hash der Nachricht
+    let hash_bytes = sha224_hash(message);
+    let hash_int = hash_to_bigint(&hash_bytes, &params.q);
+    
+    // Schleife bis gültige Signatur gefunden
+    loop {
+        // Schritt 2: Zufälliges k wählen
+        let k = Secret::new(rng.gen_biguint_range(&BigUint::from(1u32), &params.q));
+        
+        // Schritt 3: r berechnen
+        let r = mod_pow(&params.g, &k, &params.p) % &params.q;
+        
+        if r == BigUint::zero() {
+            continue; // Ungültiges r, neue Runde
+        }
+        
+        // Schritt 4: s berechnen
+        let k_inv = mod_inverse(&k, &params.q)
+            .map(Secret::new)
+            .ok_or("Modulares Inverses existiert nicht - k und q sind nicht teilerfremd")?;
+        let xr = Secret::new(private_key * &r);
+        let s = (&*k_inv * (&hash_int + &*xr)) % &params.q;
+        
+        if s != BigUint::zero() {
+            return Ok(DsaSignature { r, s }); // Gültige Signatur gefunden
+        }
+        // Sonst: s = 0, neue Runde
+    }
+}
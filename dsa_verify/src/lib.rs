@@ -0,0 +1,187 @@
+use std::io::{self, Read};
+use std::error::Error;
+use std::time::Instant;
+use clap::Parser;
+use logging::Verbosity;
+use num_bigint::BigUint;
+use num_traits::Zero;
+use serde::Serialize;
+use sha2::{Sha224, Digest};
+use crypto_math::{mod_inverse, mod_pow};
+use key_formats::{DsaParameters, DsaPublicKey, DsaSignature, KeyFile};
+use tool_error::{Context, ExitStatus, ToolError};
+
+/// DSA-Verifikations-Programm mit SHA-224
+#[derive(Parser)]
+#[command(name = "dsa_verify")]
+#[command(about = "Verifiziert DSA-Signatur einer Nachricht")]
+#[command(version = "1.0")]
+#[command(after_help = "Exit Code 0: Signatur gültig, 1: Signatur ungültig, 65/74: Schlüssel, Signatur oder Datei fehlerhaft.\n\
+Mit -q entfällt auch die Ausgabe des Ergebnisses, es zählt nur der Exit Code.")]
+struct Args {
+    /// Datei mit öffentlichem Schlüssel
+    #[arg(long, help = "Pfad zur öffentlichen Schlüsseldatei (p, q, g, y)")]
+    public_key_file: String,
+    
+    /// Datei mit der Nachricht
+    #[arg(long, help = "Pfad zur Nachrichtendatei")]
+    message_file: String,
+    
+    /// Datei mit Signatur (optional, sonst stdin)
+    #[arg(short, long, help = "Signaturdatei (r, s) - falls nicht angegeben, wird von stdin gelesen")]
+    signature: Option<String>,
+    
+    /// Ergebnis als JSON ausgeben
+    #[arg(long, conflicts_with = "quiet", help = "Ergebnis und Laufzeit als JSON ausgeben (Exit Code wie ohne --json)")]
+    json: bool,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+/// Ergebnis der Verifikation für --json
+#[derive(Serialize)]
+struct VerificationReport {
+    valid: bool,
+    public_key_file: String,
+    message_file: String,
+    elapsed_ms: f64,
+}
+
+/// Startet das Werkzeug mit den übergebenen Kommandozeilenargumenten
+///
+/// Das erste Argument ist wie bei `std::env::args_os` der Programmname;
+/// so lässt sich das Werkzeug auch aus `cryptolab` heraus aufrufen.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let started = Instant::now();
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+    
+    let (params, public_key) = load_public_key(&args.public_key_file)?;
+    let message = tool_error::fs::read_to_string(&args.message_file)?;
+    
+    // Signatur laden
+    let signature = match args.signature {
+        Some(sig_file) => read_signature_from_file(&sig_file)?,
+        None => read_signature_from_stdin()?,
+    };
+    
+    let is_valid = dsa_verify(message.as_bytes(), &signature, &params, &public_key)?;
+    
+    if args.json {
+        let report = VerificationReport {
+            valid: is_valid,
+            public_key_file: args.public_key_file,
+            message_file: args.message_file,
+            elapsed_ms: started.elapsed().as_secs_f64() * 1000.0,
+        };
+        println!("{}", serde_json::to_string_pretty(&report).expect("Bericht ist serialisierbar"));
+    } else if !args.verbosity.is_quiet() {
+        if is_valid {
+            println!("Signatur gültig");
+        } else {
+            println!("Signatur ungültig");
+        }
+    }
+    
+    // Exit Code setzen, das Ergebnis ist bereits ausgegeben
+    if is_valid {
+        Ok(())
+    } else {
+        Err(ToolError::Silent(ExitStatus::Failure))
+    }
+}
+
+/// Lädt öffentlichen Schlüssel aus Datei
+/// 
+/// Erwartet Format (oder JSON/TOML mit denselben Feldnamen):
+/// Zeile 1: p (1024-Bit Primzahl)
+/// Zeile 2: q (160-Bit Primzahl)
+/// Zeile 3: g (Generator)
+/// Zeile 4: y (öffentlicher Schlüssel)
+fn load_public_key(filename: &str) -> tool_error::Result<(DsaParameters, BigUint)> {
+    let key = DsaPublicKey::read(filename)
+        .with_context(|| format!("Kann öffentliche Schlüsseldatei '{}' nicht lesen", filename))?;
+    
+    // Validierung der Schlüsselparameter
+    if key.y >= key.params.p {
+        return Err(ToolError::data("Öffentlicher Schlüssel y muss kleiner als p sein"));
+    }
+    
+    Ok((key.params, key.y))
+}
+
+/// Liest Signatur aus Datei
+fn read_signature_from_file(filename: &str) -> tool_error::Result<DsaSignature> {
+    DsaSignature::read(filename)
+        .with_context(|| format!("Kann Signaturdatei '{}' nicht lesen", filename))
+}
+
+/// Liest Signatur von stdin bis zum Ende der Eingabe
+fn read_signature_from_stdin() -> tool_error::Result<DsaSignature> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)
+        .context("Fehler beim Lesen der Signatur von stdin")?;
+    
+    DsaSignature::parse(&input)
+        .context("Ungültige Signatur von stdin")
+}
+
+/// SHA-224 Hashfunktion für DSA
+fn sha224_hash(input: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha224::new();
+    hasher.update(input);
+    hasher.finalize().to_vec()
+}
+
+/// Konvertiert SHA-224 Hash zu BigUint für DSA-Berechnung
+fn hash_to_bigint(hash_bytes: &[u8], q: &BigUint) -> BigUint {
+    let hash_int = BigUint::from_bytes_be(hash_bytes);
+    // SHA-224 erzeugt 224 Bits, q ist 160 Bits - Reduktion nötig
+    hash_int % q
+}
+
+/// DSA-Signatur verifizieren
+/// 
+/// Algorithmus:
+/// 1. Prüfe 0 < r < q und 0 < s < q
+/// 2. H(m) = SHA-224(message) mod q
+/// 3. w = s^(-1) mod q
+/// 4. u1 = H(m) * w mod q
+/// 5. u2 = r * w mod q  
+/// 6. v = ((g^u1 * y^u2) mod p) mod q
+/// 7. Signatur gültig ⟺ v = r
+pub fn dsa_verify(message: &[u8], signature: &DsaSignature, params: &DsaParameters, public_key: &BigUint) -> Result<bool, Box<dyn Error>> {
+    let DsaSignature { r, s } = signature;
+    
+    // Schritt 1: Signatur-Parameter validieren
+    if *r == BigUint::zero() || *r >= params.q || *s == BigUint::zero() || *s >= params.q {
+        return Ok(false);
+    }
+    
+    // Schritt 2: Hash der Nachricht
+    let hash_bytes = sha224_hash(message);
+    let hash_int = hash_to_bigint(&hash_bytes, &params.q);
+    
+    // Schritt 3: w = s^(-1) mod q
+    let w = mod_inverse(s, &params.q)
+        .ok_or("Modulares Inverses existiert nicht - s und q sind nicht teilerfremd")?;
+    
+    // Schritt 4: u1 = H(m) * w mod q
+    let u1 = (&hash_int * &w) % &params.q;
+    
+    // Schritt 5: u2 = r * w mod q
+    let u2 = (r * &w) % &params.q;
+    
+    // Schritt 6: v = ((g^u1 * y^u2) mod p) mod q
+    let g_u1 = mod_pow(&params.g, &u1, &params.p);
+    let y_u2 = mod_pow(public_key, &u2, &params.p);
+    let v = (&g_u1 * &y_u2) % &params.p % &params.q;
+    
+    // Schritt 7: Vergleich
+    Ok(v == *r)
+}
@@ -0,0 +1,177 @@
+//! Detects ECB mode by looking for repeated ciphertext blocks.
+//!
+//! ECB encrypts equal plaintext blocks to equal ciphertext blocks. Text with
+//! repeated phrases, padded records and above all images therefore leave
+//! visible repetitions in the ciphertext, while CBC, OFB and CTR produce no
+//! repeats at all unless a block of random-looking data happens to collide,
+//! which for 16-byte blocks practically never happens.
+//!
+//! The tool reports how many blocks are duplicates and, with `--ppm`, draws
+//! one colored square per block so the structure can be seen at a glance.
+
+use clap::Parser;
+use logging::{English, Verbosity, info, warn};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tool_error::ToolError;
+
+pub mod ppm;
+
+/// Number of most frequent blocks listed in the report
+const TOP_BLOCKS: usize = 5;
+
+/// Command-line arguments for the ECB detector.
+#[derive(Parser, Debug)]
+#[command(name = "ecb_detect", about = "Detects repeated ciphertext blocks (ECB mode) and renders a block-color map")]
+struct Cli {
+    /// Path to the ciphertext file
+    #[arg(short, long, help = "Path to the ciphertext file")]
+    file: PathBuf,
+
+    /// Input file contains hex digits instead of raw bytes
+    #[arg(long, help = "The input file is hex encoded (whitespace is ignored)")]
+    hex: bool,
+
+    /// Cipher block size in bytes
+    #[arg(short, long, default_value_t = 16, help = "Block size in bytes (16 for AES, 2 for the SPN)")]
+    block_size: usize,
+
+    /// Skip a header that was left unencrypted, e.g. of a bitmap
+    #[arg(long, default_value_t = 0, help = "Number of leading bytes to skip")]
+    skip: usize,
+
+    /// Write the block-color map to this file
+    #[arg(long, help = "Write a block-color map as binary PPM image")]
+    ppm: Option<PathBuf>,
+
+    /// Blocks per image row; the plaintext row length in blocks makes the picture recognizable
+    #[arg(short, long, help = "Blocks per row of the image (default: square)")]
+    width: Option<usize>,
+
+    /// Side length of one block in the image
+    #[arg(long, default_value_t = 4, help = "Pixels per block side in the image")]
+    scale: usize,
+
+    /// Draw blocks that occur only once in black
+    #[arg(long, help = "Only color repeated blocks")]
+    repeats_only: bool,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+/// Statistics about repeated blocks
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockReport {
+    pub blocks: usize,
+    pub unique: usize,
+    /// Blocks whose content also occurs elsewhere in the ciphertext
+    pub repeated: usize,
+    /// The most frequent repeated blocks with their block indices, most frequent first
+    pub top: Vec<(Vec<u8>, Vec<usize>)>,
+}
+
+impl BlockReport {
+    /// Share of blocks that repeat an earlier block
+    pub fn duplication_rate(&self) -> f64 {
+        if self.blocks == 0 {
+            return 0.0;
+        }
+        (self.blocks - self.unique) as f64 / self.blocks as f64
+    }
+}
+
+/// Counts repeated blocks; a trailing partial block is ignored
+pub fn analyze(data: &[u8], block_size: usize) -> BlockReport {
+    let mut positions: HashMap<&[u8], Vec<usize>> = HashMap::new();
+    for (index, block) in data.chunks_exact(block_size).enumerate() {
+        positions.entry(block).or_default().push(index);
+    }
+
+    let blocks = data.len() / block_size;
+    let unique = positions.len();
+    let mut top: Vec<(Vec<u8>, Vec<usize>)> = positions.into_iter()
+        .filter(|(_, indices)| indices.len() > 1)
+        .map(|(block, indices)| (block.to_vec(), indices))
+        .collect();
+    let repeated = top.iter().map(|(_, indices)| indices.len()).sum();
+    top.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.1[0].cmp(&b.1[0])));
+    top.truncate(TOP_BLOCKS);
+
+    BlockReport { blocks, unique, repeated, top }
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+
+    if cli.block_size == 0 || cli.scale == 0 || cli.width == Some(0) {
+        return Err(ToolError::usage("Block size, width and scale must be positive"));
+    }
+
+    let data = codec::file::read_binary(&cli.file).and_then(|raw| {
+        if cli.hex { codec::hex::decode(&String::from_utf8_lossy(&raw)) } else { Ok(raw) }
+    })?;
+    let data = data.get(cli.skip..).unwrap_or_default();
+    if !data.len().is_multiple_of(cli.block_size) {
+        warn!("Ignoring {} trailing bytes that do not fill a block", data.len() % cli.block_size);
+    }
+
+    let report = analyze(data, cli.block_size);
+    if report.blocks < 2 {
+        return Err(ToolError::data("Ciphertext needs at least two whole blocks"));
+    }
+
+    println!("Blocks:           {}", report.blocks);
+    println!("Unique blocks:    {}", report.unique);
+    println!("Repeated blocks:  {}", report.repeated);
+    println!("Duplication rate: {:.2}%", report.duplication_rate() * 100.0);
+    for (block, indices) in &report.top {
+        let shown: Vec<String> = indices.iter().take(8).map(|i| i.to_string()).collect();
+        let more = if indices.len() > 8 { ", ..." } else { "" };
+        println!("  {} x{} at blocks {}{}", codec::hex::encode(block), indices.len(), shown.join(", "), more);
+    }
+    if report.repeated > 0 {
+        println!("Repeated blocks found: the ciphertext was most likely encrypted in ECB mode");
+    } else {
+        println!("No repeated blocks: no evidence of ECB mode");
+    }
+
+    if let Some(path) = &cli.ppm {
+        let width = cli.width.unwrap_or_else(|| (report.blocks as f64).sqrt().ceil() as usize);
+        let image = ppm::block_map(data, cli.block_size, width, cli.scale, cli.repeats_only);
+        codec::file::write_binary(path, &image)?;
+        info!("Block map with {} blocks per row written to {}", width, path.display());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_blocks() {
+        let report = analyze(b"AAAABBBBAAAACCCCAAAABBBBx", 4);
+        assert_eq!(report.blocks, 6);
+        assert_eq!(report.unique, 3);
+        assert_eq!(report.repeated, 5);
+        assert_eq!(report.top[0], (b"AAAA".to_vec(), vec![0, 2, 4]));
+        assert_eq!(report.top[1], (b"BBBB".to_vec(), vec![1, 5]));
+        assert!((report.duplication_rate() - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_no_repeats() {
+        let data: Vec<u8> = (0..=255).collect();
+        let report = analyze(&data, 16);
+        assert_eq!(report.repeated, 0);
+        assert!(report.top.is_empty());
+        assert_eq!(report.duplication_rate(), 0.0);
+    }
+}
@@ -0,0 +1,169 @@
+//! entropy: Wie zufällig sind die Bytes einer Datei?
+//!
+//! Berechnet für jede Datei die Shannon-Entropie der Bytehäufigkeiten, den
+//! Chi-Quadrat-Test auf Gleichverteilung, den Mittelwert und die serielle
+//! Korrelation aufeinanderfolgender Bytes. Gedacht für Schlüsseldateien und
+//! One-Time-Pads: ein guter Pad hat nahezu 8 Bit Entropie pro Byte, einen
+//! unauffälligen p-Wert und eine Korrelation nahe 0. Text, komprimierte
+//! Daten und Zählerfolgen fallen dagegen deutlich auf.
+
+use clap::Parser;
+use logging::{Verbosity, info, warn};
+use randtest::special::igamc;
+use std::path::PathBuf;
+use tool_error::{ExitStatus, ToolError};
+
+#[derive(Parser)]
+#[command(name = "entropy")]
+#[command(about = "Schätzt Entropie, Gleichverteilung und serielle Korrelation der Bytes einer Datei")]
+#[command(version = "1.0")]
+#[command(after_help = "Exit-Codes: 0 Erfolg, 1 eine Datei liegt unter --min-entropy, 74 Datei nicht lesbar")]
+struct Args {
+    /// Zu untersuchende Dateien
+    #[arg(value_name = "FILE", required = true)]
+    files: Vec<PathBuf>,
+
+    /// Mindestentropie in Bit pro Byte; darunter endet das Programm mit Exit-Code 1
+    #[arg(long = "min-entropy", value_name = "BITS")]
+    min_entropy: Option<f64>,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+/// Kennzahlen einer Bytefolge
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statistics {
+    pub len: usize,
+    /// Shannon-Entropie in Bit pro Byte, zwischen 0 und 8
+    pub entropy: f64,
+    /// Chi-Quadrat-Statistik über die 256 Bytewerte (255 Freiheitsgrade)
+    pub chi_squared: f64,
+    /// Wahrscheinlichkeit, dass gleichverteilte Bytes ein größeres χ² ergeben
+    pub p_value: f64,
+    /// Arithmetisches Mittel, für Zufallsdaten etwa 127,5
+    pub mean: f64,
+    /// Korrelation jedes Bytes mit seinem Nachfolger (zyklisch), `None` bei konstanten Daten
+    pub serial_correlation: Option<f64>,
+}
+
+/// Berechnet alle Kennzahlen; `bytes` darf nicht leer sein
+pub fn analyze(bytes: &[u8]) -> Statistics {
+    assert!(!bytes.is_empty(), "leere Eingabe");
+
+    let mut counts = [0u64; 256];
+    for &byte in bytes {
+        counts[byte as usize] += 1;
+    }
+
+    let n = bytes.len() as f64;
+    let entropy = -counts.iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / n;
+            p * p.log2()
+        })
+        .sum::<f64>();
+
+    let expected = n / 256.0;
+    let chi_squared: f64 = counts.iter().map(|&count| (count as f64 - expected).powi(2) / expected).sum();
+    let p_value = igamc(255.0 / 2.0, chi_squared / 2.0);
+
+    let sum: f64 = bytes.iter().map(|&byte| byte as f64).sum();
+
+    // Korrelationskoeffizient nach Knuth, TAOCP Bd. 2, 3.3.2 K
+    let sum_squares: f64 = bytes.iter().map(|&byte| (byte as f64).powi(2)).sum();
+    let sum_products: f64 = bytes.iter()
+        .zip(bytes.iter().cycle().skip(1))
+        .map(|(&a, &b)| a as f64 * b as f64)
+        .sum();
+    let denominator = n * sum_squares - sum * sum;
+    let serial_correlation = (denominator != 0.0).then(|| (n * sum_products - sum * sum) / denominator);
+
+    Statistics { len: bytes.len(), entropy, chi_squared, p_value, mean: sum / n, serial_correlation }
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+
+    if let Some(min) = args.min_entropy
+        && !(0.0..=8.0).contains(&min)
+    {
+        return Err(ToolError::usage("--min-entropy muss zwischen 0 und 8 liegen"));
+    }
+
+    let mut below_minimum = false;
+    for path in &args.files {
+        let bytes = tool_error::fs::read(path)?;
+        if bytes.is_empty() {
+            warn!("{}: Datei ist leer", path.display());
+            below_minimum |= args.min_entropy.is_some();
+            continue;
+        }
+        if bytes.len() < 2560 {
+            // Erwartungswert unter 10 pro Bytewert: die χ²-Näherung ist unzuverlässig
+            warn!("{}: nur {} Bytes, der Chi-Quadrat-Test ist wenig aussagekräftig", path.display(), bytes.len());
+        }
+
+        let stats = analyze(&bytes);
+        info!("{}: χ² = {:.2} bei 255 Freiheitsgraden", path.display(), stats.chi_squared);
+        let correlation = match stats.serial_correlation {
+            Some(value) => format!("{:+.6}", value),
+            None => "undefiniert".to_string(),
+        };
+        println!(
+            "{}: {} Bytes, Entropie {:.6} Bit/Byte, χ²-p-Wert {:.6}, Mittelwert {:.4}, serielle Korrelation {}",
+            path.display(), stats.len, stats.entropy, stats.p_value, stats.mean, correlation,
+        );
+
+        if let Some(min) = args.min_entropy
+            && stats.entropy < min
+        {
+            warn!("{}: Entropie {:.4} liegt unter {}", path.display(), stats.entropy, min);
+            below_minimum = true;
+        }
+    }
+
+    if below_minimum {
+        Err(ToolError::Silent(ExitStatus::Failure))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uniform_bytes() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let stats = analyze(&bytes);
+        assert!((stats.entropy - 8.0).abs() < 1e-12);
+        assert_eq!(stats.chi_squared, 0.0);
+        assert_eq!(stats.p_value, 1.0);
+        assert_eq!(stats.mean, 127.5);
+        // Eine aufsteigende Folge ist stark mit ihrem Nachfolger korreliert
+        assert!(stats.serial_correlation.unwrap() > 0.9);
+    }
+
+    #[test]
+    fn test_constant_bytes() {
+        let stats = analyze(&[0x41; 1000]);
+        assert_eq!(stats.entropy, 0.0);
+        assert!(stats.p_value < 1e-10);
+        assert_eq!(stats.serial_correlation, None);
+    }
+
+    #[test]
+    fn test_two_symbols() {
+        let stats = analyze(b"abababab");
+        assert!((stats.entropy - 1.0).abs() < 1e-12);
+        assert!((stats.serial_correlation.unwrap() + 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,103 @@
+//! Every method against numbers with one weakness each
+//!
+//! Each row is a product n = p·q of the requested size whose factors have
+//! one of the weaknesses a method exploits, plus an RSA-like row without
+//! any. The table shows which method wins where, and that none of them
+//! gets anywhere against balanced random primes.
+
+use crypto_math::is_probable_prime;
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::One;
+use rand::Rng;
+
+use crate::{Limits, Method, ROUNDS, split};
+
+/// Bits of the small factor in the trial-division row
+const SMALL_FACTOR_BITS: u64 = 16;
+
+/// Random prime of exactly `bits` bits
+fn random_prime(bits: u64, rng: &mut impl Rng) -> BigUint {
+    loop {
+        let candidate = rng.gen_biguint(bits) | BigUint::one() << (bits - 1) | BigUint::one();
+        if is_probable_prime(&candidate, ROUNDS) {
+            return candidate;
+        }
+    }
+}
+
+/// Prime of about `bits` bits whose p - 1 has only prime factors below `bound`
+fn smooth_prime(bits: u64, bound: u64, rng: &mut impl Rng) -> BigUint {
+    let bound = bound.max(3);
+    loop {
+        let mut p = BigUint::from(2u32);
+        while p.bits() < bits {
+            let q = rng.gen_range(2..bound);
+            if is_probable_prime(&BigUint::from(q), ROUNDS) {
+                p *= q;
+            }
+        }
+        p += 1u32;
+        if is_probable_prime(&p, ROUNDS) {
+            return p;
+        }
+    }
+}
+
+/// Smallest prime ≥ n
+fn next_prime(mut n: BigUint) -> BigUint {
+    n |= BigUint::one();
+    while !is_probable_prime(&n, ROUNDS) {
+        n += 2u32;
+    }
+    n
+}
+
+/// Numbers of `bits` bits, one per weakness, with a description
+fn instances(bits: u64, limits: &Limits, rng: &mut impl Rng) -> Vec<(&'static str, BigUint)> {
+    let half = bits / 2;
+    let p = random_prime(half, rng);
+    let close = next_prime(&p + rng.gen_biguint(bits / 4));
+    let smooth = smooth_prime(half, limits.p1_bound, rng);
+    vec![
+        ("small factor", random_prime(SMALL_FACTOR_BITS, rng) * random_prime(bits - SMALL_FACTOR_BITS, rng)),
+        ("smooth p - 1", random_prime(bits - smooth.bits(), rng) * smooth),
+        ("close primes", &p * close),
+        ("balanced (RSA)", p * random_prime(bits - half, rng)),
+    ]
+}
+
+/// Prints the time every method needs for every instance
+pub(crate) fn run(bits: u64, methods: &[Method], limits: &Limits, rng: &mut impl Rng) {
+    let header: Vec<String> = methods.iter().map(|m| format!("{:>9}", m.name())).collect();
+    println!("{:<15} {:>4} {}", "Number", "bits", header.join(" "));
+    for (name, n) in instances(bits, limits, rng) {
+        let cells: Vec<String> = methods
+            .iter()
+            .map(|&method| match split(method, &n, limits) {
+                (Some(_), time) => format!("{:>7.3} s", time.as_secs_f64()),
+                (None, _) => format!("{:>9}", "-"),
+            })
+            .collect();
+        println!("{:<15} {:>4} {}", name, n.bits(), cells.join(" "));
+    }
+    println!("- no factor within {:.1} s (or the method's bound)", limits.time.as_secs_f64());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::{Algorithm, Drbg};
+
+    #[test]
+    fn test_instances() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"benchmark");
+        let limits = Limits { time: std::time::Duration::from_secs(1), trial_bound: 1 << 16, p1_bound: 1000 };
+        let smooth = smooth_prime(40, 1000, &mut rng);
+        assert!(is_probable_prime(&smooth, ROUNDS));
+        assert!(crypto_math::factor::trial_division(&(&smooth - 1u32), 1000).is_some());
+        for (name, n) in instances(64, &limits, &mut rng) {
+            assert!((63..=65).contains(&n.bits()), "{}: {} bits", name, n.bits());
+            assert!(!is_probable_prime(&n, ROUNDS));
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! Factors integers by trying the methods of `crypto_math::factor` in turn.
+//!
+//! Each method targets a different weakness: trial division finds small
+//! factors, Pollard's rho any factor up to about 2^40 in seconds, Pollard's
+//! p - 1 a prime p whose p - 1 is smooth, and Fermat two primes close to √n.
+//! The quadratic sieve needs no weakness at all, only a small n: it splits
+//! RSA moduli of up to 120 bits within seconds. The tool tries the methods in this
+//! order, each with its own time limit, and splits every composite factor
+//! again until only primes are left. Every split reports which method found
+//! it and how long that took.
+//!
+//! `--key` breaks a public key from `rsa-keygen --insecure`: it factors n,
+//! computes d and writes the private key that `rsa` decrypts with.
+//!
+//! `--benchmark BITS` builds numbers with each weakness (and one without) and
+//! runs every method on every number, a benchmark harness for the math crate.
+
+use clap::{Parser, ValueEnum};
+use crypto_math::{factor, is_probable_prime, mod_inverse};
+use csprng::RngArgs;
+use key_formats::{Format, KeyFile, RsaPrivateKey, RsaPublicKey};
+use logging::{English, Verbosity, info};
+use num_bigint::BigUint;
+use secret::Secret;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tool_error::{Context, ToolError};
+
+pub mod benchmark;
+
+/// Miller-Rabin rounds for deciding when to stop splitting
+const ROUNDS: u32 = 40;
+
+/// Factoring method, in the order the dispatcher tries them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum Method {
+    /// Trial division up to --trial-bound
+    Trial,
+    /// Pollard's rho with Brent's cycle finding
+    Rho,
+    /// Pollard's p - 1 with bound --p1-bound
+    #[value(name = "p-1")]
+    PMinus1,
+    /// Fermat's difference of squares
+    Fermat,
+    /// Quadratic sieve, for n up to 120 bits
+    Qs,
+}
+
+impl Method {
+    fn name(self) -> &'static str {
+        match self {
+            Method::Trial => "trial",
+            Method::Rho => "rho",
+            Method::PMinus1 => "p-1",
+            Method::Fermat => "fermat",
+            Method::Qs => "qs",
+        }
+    }
+}
+
+/// Bounds and limits shared by all methods
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    time: Duration,
+    trial_bound: u32,
+    p1_bound: u64,
+}
+
+/// Proper divisor of the composite `n` and the time taken, or `None` within the limit
+fn split(method: Method, n: &BigUint, limits: &Limits) -> (Option<BigUint>, Duration) {
+    let start = Instant::now();
+    let deadline = start + limits.time;
+    let divisor = match method {
+        Method::Trial => factor::trial_division(n, limits.trial_bound),
+        Method::Rho => factor::pollard_rho(n, deadline),
+        Method::PMinus1 => factor::pollard_p_minus_1(n, limits.p1_bound, deadline),
+        Method::Fermat => factor::fermat(n, deadline),
+        Method::Qs => factor::quadratic_sieve(n, deadline),
+    };
+    (divisor, start.elapsed())
+}
+
+/// Command-line arguments for the factoring tool.
+#[derive(Parser, Debug)]
+#[command(name = "factor", about = "Factors integers with trial division, Pollard rho, Pollard p - 1, Fermat and the quadratic sieve")]
+#[command(after_help = "Exit codes: 0 fully factored, 1 a composite factor is left, 2 invalid arguments")]
+struct Cli {
+    /// Numbers to factor
+    #[arg(required_unless_present_any = ["benchmark", "key"], help = "Numbers to factor")]
+    numbers: Vec<BigUint>,
+
+    /// Public RSA key whose modulus to factor
+    #[arg(short, long, value_name = "FILE", conflicts_with = "numbers", help = "Factor the modulus of this public RSA key (e, n)")]
+    key: Option<PathBuf>,
+
+    /// Where to write the recovered private key
+    #[arg(short, long, value_name = "FILE", requires = "key", help = "Write the recovered private key (d, n) here")]
+    private_output: Option<PathBuf>,
+
+    /// Methods to try, in this order
+    #[arg(short, long, value_enum, value_delimiter = ',', default_values = ["trial", "rho", "p-1", "fermat", "qs"], help = "Methods to try, in this order")]
+    methods: Vec<Method>,
+
+    /// Time limit per method and number
+    #[arg(short, long, default_value_t = 10.0, value_parser = parse_seconds, help = "Seconds each method may spend on a number")]
+    time_limit: f64,
+
+    /// Largest trial divisor
+    #[arg(long, default_value_t = 1 << 20, help = "Largest divisor for trial division")]
+    trial_bound: u32,
+
+    /// Smoothness bound of Pollard's p - 1
+    #[arg(long, default_value_t = 100_000, value_parser = clap::value_parser!(u64).range(2..), help = "Bound B for p - 1: finds p when p - 1 divides B!")]
+    p1_bound: u64,
+
+    /// Run the benchmark instead of factoring
+    #[arg(long, value_name = "BITS", conflicts_with_all = ["numbers", "key"], value_parser = clap::value_parser!(u64).range(24..=512), help = "Benchmark every method on BITS-bit numbers with and without weaknesses")]
+    benchmark: Option<u64>,
+
+    #[command(flatten)]
+    rng: RngArgs,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+fn parse_seconds(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(seconds) if seconds > 0.0 && seconds.is_finite() => Ok(seconds),
+        _ => Err(format!("'{}' is not a positive number of seconds", value)),
+    }
+}
+
+/// Prime factors of `n` in ascending order and the composite factors no method could split
+fn factorize(n: &BigUint, methods: &[Method], limits: &Limits) -> (Vec<BigUint>, Vec<BigUint>) {
+    let (mut primes, mut composites) = (Vec::new(), Vec::new());
+    let mut pending = vec![n.clone()];
+    'numbers: while let Some(m) = pending.pop() {
+        if m <= BigUint::from(1u32) {
+            continue;
+        }
+        if is_probable_prime(&m, ROUNDS) {
+            primes.push(m);
+            continue;
+        }
+        for &method in methods {
+            info!("{} on {}", method.name(), m);
+            let (divisor, time) = split(method, &m, limits);
+            if let Some(divisor) = divisor {
+                println!("{:<7} {:>9.3} s  {} = {} · {}", method.name(), time.as_secs_f64(), m, divisor, &m / &divisor);
+                pending.push(&m / &divisor);
+                pending.push(divisor);
+                continue 'numbers;
+            }
+        }
+        composites.push(m);
+    }
+    primes.sort();
+    composites.sort();
+    (primes, composites)
+}
+
+/// Private key for `key` from the factors of its modulus
+fn break_key(key: &RsaPublicKey, methods: &[Method], limits: &Limits) -> tool_error::Result<RsaPrivateKey> {
+    if key.n < BigUint::from(2u32) {
+        return Err(ToolError::data("modulus must be at least 2"));
+    }
+    let (primes, composites) = factorize(&key.n, methods, limits);
+    if !composites.is_empty() {
+        return Err(ToolError::failed(format!("modulus not factored, {} is left", composites[0])));
+    }
+    // φ(n) for n = p·q, or more generally for squarefree n
+    if primes.windows(2).any(|pair| pair[0] == pair[1]) {
+        return Err(ToolError::data("modulus has a repeated prime factor, not an RSA key"));
+    }
+    let phi = Secret::new(primes.iter().map(|p| p - 1u32).product::<BigUint>());
+    let d = mod_inverse(&key.e, &phi).ok_or_else(|| ToolError::data("e is not invertible modulo φ(n)"))?;
+    Ok(RsaPrivateKey { d: Secret::new(d), n: key.n.clone() })
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+
+    let limits = Limits { time: Duration::from_secs_f64(cli.time_limit), trial_bound: cli.trial_bound, p1_bound: cli.p1_bound };
+    if let Some(bits) = cli.benchmark {
+        benchmark::run(bits, &cli.methods, &limits, &mut cli.rng.rng());
+        return Ok(());
+    }
+    if let Some(path) = &cli.key {
+        let key = RsaPublicKey::read(path).with_context(|| format!("key file {}", path.display()))?;
+        let private_key = break_key(&key, &cli.methods, &limits)?;
+        println!("d = {}", *private_key.d);
+        if let Some(output) = &cli.private_output {
+            private_key.write(output, Format::Lines)?;
+        }
+        return Ok(());
+    }
+    if cli.numbers.iter().any(|n| *n < BigUint::from(2u32)) {
+        return Err(ToolError::usage("numbers to factor must be at least 2"));
+    }
+
+    let mut unfactored = 0;
+    for n in &cli.numbers {
+        let (primes, composites) = factorize(n, &cli.methods, &limits);
+        let factors: Vec<String> = primes.iter().map(|p| p.to_string()).chain(composites.iter().map(|c| format!("[{}]", c))).collect();
+        println!("{} = {}", n, factors.join(" · "));
+        unfactored += composites.len();
+    }
+    if unfactored > 0 {
+        return Err(ToolError::failed(format!("{} composite factors in brackets withstood every method", unfactored)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> Limits {
+        Limits { time: Duration::from_secs(30), trial_bound: 1000, p1_bound: 1000 }
+    }
+
+    #[test]
+    fn test_factorize() {
+        let methods = [Method::Trial, Method::Rho, Method::PMinus1, Method::Fermat, Method::Qs];
+        let n = BigUint::from(2u64 * 2 * 3 * 101 * 1_000_003 * 1_000_033);
+        let (primes, composites) = factorize(&n, &methods, &limits());
+        assert_eq!(primes, [2u32, 2, 3, 101, 1_000_003, 1_000_033].map(BigUint::from));
+        assert!(composites.is_empty());
+
+        // Trial division alone leaves the product of the large primes
+        let (primes, composites) = factorize(&n, &[Method::Trial], &limits());
+        assert_eq!(primes, [2u32, 2, 3, 101].map(BigUint::from));
+        assert_eq!(composites, [BigUint::from(1_000_003u64 * 1_000_033)]);
+    }
+
+    #[test]
+    fn test_run() {
+        assert!(run(["factor", "1000036000099", "97", "-t", "5"]).is_ok());
+        assert!(run(["factor", "1000036000099", "--methods", "trial", "--trial-bound", "1000"]).is_err());
+        assert!(run(["factor", "1"]).is_err());
+        assert!(run(["factor", "--benchmark", "40", "--time-limit", "2", "--p1-bound", "2000", "--seed", "factor"]).is_ok());
+    }
+
+    #[test]
+    fn test_break_rsa_keygen_key() {
+        let dir = std::env::temp_dir().join(format!("factor-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+        let keygen = ["rsa-keygen", "--length", "80", "--insecure", "--seed", "factor"];
+        let outputs = ["--private-output", &path("private"), "--public-output", &path("public"), "--primes-output", &path("primes")];
+        rsa_keygen::run(keygen.iter().chain(&outputs)).unwrap();
+        std::fs::write(path("message"), "1234567890").unwrap();
+        rsa::run(["rsa", "--operation", "encrypt", "--file", &path("message"), "--key", &path("public"), "--output", &path("ciphertext")]).unwrap();
+
+        // Only the public key and the ciphertext are needed from here on
+        run(["factor", "--key", &path("public"), "--private-output", &path("recovered"), "--methods", "qs"]).unwrap();
+        rsa::run(["rsa", "--operation", "decrypt", "--file", &path("ciphertext"), "--key", &path("recovered"), "--output", &path("decrypted")]).unwrap();
+        assert_eq!(std::fs::read_to_string(path("decrypted")).unwrap(), "1234567890");
+        assert_eq!(RsaPrivateKey::read(path("recovered")).unwrap(), RsaPrivateKey::read(path("private")).unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,115 @@
+
+use clap::Parser;
+use logging::{English, Verbosity};
+use tool_error::ToolError;
+use classical::{count_frequencies, rank_shifts, Alphabet, Language};
+
+/// Command-line arguments for the German frequency decryptor program.
+#[derive(Parser, Debug)]
+struct Cli {
+    /// Path to the input file containing encrypted text
+    #[arg(short, long, help = "Path to the input file containing encrypted text")]
+    file: String,
+
+    /// Path to the output file where decrypted text will be saved
+    #[arg(short, long, help = "Path to the output file for decrypted text")]
+    output: String,
+
+    /// Alphabet the ciphertext was encrypted over
+    #[arg(short, long, default_value = "latin", help = "Alphabet: latin, german, alnum, printable or a literal list of symbols")]
+    alphabet: String,
+
+    /// Language of the plaintext, selects the reference distribution
+    #[arg(short, long, value_enum, default_value = "de", help = "Plaintext language (de, en, fr, es, it)")]
+    language: Language,
+
+    /// Number of ranked candidate plaintexts to print
+    #[arg(short, long, default_value_t = 1, help = "Print the N best candidate keys with a plaintext preview")]
+    top: usize,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+
+/// Main entry point for the German frequency decryptor.
+///
+/// The first argument is the program name, as with `std::env::args_os`.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    // Parse command-line arguments
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+    
+    // Read the encrypted content from the input file
+    let content: String = tool_error::fs::read_to_string(&cli.file)?;
+    
+    let alphabet = Alphabet::parse(&cli.alphabet).map_err(ToolError::usage)?;
+    
+    // Analyze character frequencies in the encrypted text
+    let frequencies: Vec<u32> = count_frequencies(&content, &alphabet);
+    let expected: Vec<f64> = cli.language.table().expected(&alphabet);
+    
+    // Rank all keys by chi-square against the language distribution
+    let ranking = rank_shifts(&frequencies, &expected);
+    let key = ranking[0].0;
+    println!("Detected cipher key: {}", key);
+    
+    if cli.top > 1 {
+        println!("{:>4} {:>12}  Preview", "Key", "Chi-square");
+        for &(candidate, chi_squared) in ranking.iter().take(cli.top) {
+            let preview: String = decrypt(&content, candidate, &alphabet)
+                .chars()
+                .take(60)
+                .map(|c| if c.is_whitespace() { ' ' } else { c })
+                .collect();
+            println!("{:>4} {:>12.2}  {}", candidate, chi_squared, preview);
+        }
+    }
+    
+    // Decrypt the content using the discovered key
+    let decrypted: String = decrypt(&content, key, &alphabet);
+    
+    // Write the decrypted text to the output file
+    tool_error::fs::write(&cli.output, decrypted)
+}
+
+/// Decrypts the given content using a simple additive cipher.
+///
+/// # Arguments
+///
+/// * `content` - The input string to be decrypted.
+/// * `key` - The decryption key.
+/// * `alphabet` - The alphabet the shift is applied in.
+///
+/// # Returns
+///
+/// A `String` containing the decrypted content.
+fn decrypt(content: &str, key: usize, alphabet: &Alphabet) -> String {
+    // Apply reverse shift; characters outside the alphabet remain unchanged
+    content
+        .chars()
+        .map(|c| alphabet.unshift(c, key))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_text_without_dominant_e() {
+        // "Zwei Boxkaempfer jagen Viktor quer ueber den Sylter Deich", shifted by 7
+        let alphabet = Alphabet::latin();
+        let plaintext = "Zwei Boxkaempfer jagen Viktor quer ueber den Sylter Deich";
+        let ciphertext: String = plaintext.chars().map(|c| alphabet.shift(c, 7)).collect();
+
+        let frequencies = count_frequencies(&ciphertext, &alphabet);
+        let ranking = rank_shifts(&frequencies, &Language::De.table().expected(&alphabet));
+        assert_eq!(ranking[0].0, 7);
+        assert_eq!(decrypt(&ciphertext, ranking[0].0, &alphabet), plaintext);
+    }
+}
@@ -0,0 +1,106 @@
+//! GF(2^128) in der Bitreihenfolge von GCM
+//!
+//! GCM rechnet modulo x^128 + x^7 + x^2 + x + 1 und liest einen Block
+//! „verkehrt herum“: Das höchstwertige Bit des ersten Bytes ist der
+//! Koeffizient von x^0. Ein Element ist hier der Block als `u128` in
+//! Big-Endian, das Einselement also `1 << 127`. Für die Multiplikation
+//! werden die Bits gespiegelt, carry-less multipliziert und reduziert.
+
+use crate::poly;
+
+/// Blockgröße von GHASH in Bytes
+pub const BLOCK_SIZE: usize = 16;
+
+/// Einselement in GCM-Bitreihenfolge
+pub const ONE: u128 = 1 << 127;
+
+/// x^128 ≡ x^7 + x^2 + x + 1
+const REDUCTION: u128 = 0x87;
+
+pub fn from_block(block: &[u8; BLOCK_SIZE]) -> u128 {
+    u128::from_be_bytes(*block)
+}
+
+pub fn to_block(element: u128) -> [u8; BLOCK_SIZE] {
+    element.to_be_bytes()
+}
+
+/// Reduziert das 256-Bit-Produkt (hoch, niedrig) in gewöhnlicher Bitreihenfolge
+fn reduce((high, low): (u128, u128)) -> u128 {
+    // high · x^128 ≡ high · 0x87; der Überlauf hat höchstens Grad 6
+    let (overflow, folded) = poly::clmul128(high, REDUCTION);
+    low ^ folded ^ poly::clmul(overflow as u64, REDUCTION as u64)
+}
+
+/// Produkt zweier Elemente
+pub fn mul(x: u128, y: u128) -> u128 {
+    reduce(poly::clmul128(x.reverse_bits(), y.reverse_bits())).reverse_bits()
+}
+
+/// `x^n`, mit x^0 = 1
+pub fn pow(x: u128, n: u128) -> u128 {
+    (0..128).rev().fold(ONE, |result, i| {
+        let square = mul(result, result);
+        if n >> i & 1 == 1 { mul(square, x) } else { square }
+    })
+}
+
+/// Multiplikatives Inverses mit 0 ↦ 0, über x^(2^128 - 2)
+pub fn inverse(x: u128) -> u128 {
+    pow(x, u128::MAX - 1)
+}
+
+/// GHASH nach NIST SP 800-38D über zusätzliche Daten und Chiffretext
+///
+/// Beide Eingaben werden mit Nullen auf volle Blöcke aufgefüllt, zum
+/// Schluss folgt ein Block mit ihren Längen in Bits.
+pub fn ghash(h: &[u8; BLOCK_SIZE], aad: &[u8], ciphertext: &[u8]) -> [u8; BLOCK_SIZE] {
+    let h = from_block(h);
+    let mut y = 0;
+    for data in [aad, ciphertext] {
+        for chunk in data.chunks(BLOCK_SIZE) {
+            let mut block = [0u8; BLOCK_SIZE];
+            block[..chunk.len()].copy_from_slice(chunk);
+            y = mul(y ^ from_block(&block), h);
+        }
+    }
+    let lengths = ((aad.len() as u128 * 8) << 64) | (ciphertext.len() as u128 * 8);
+    to_block(mul(y ^ lengths, h))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(hex: &str) -> [u8; BLOCK_SIZE] {
+        u128::from_str_radix(hex, 16).unwrap().to_be_bytes()
+    }
+
+    #[test]
+    fn test_mul() {
+        let h = from_block(&block("66e94bd4ef8a2c3b884cfa59ca342b2e"));
+        assert_eq!(mul(h, ONE), h);
+        assert_eq!(mul(h, 0), 0);
+        // Testfall 2 aus dem GCM-Spezifikationsdokument: X1 = C · H
+        let c = from_block(&block("0388dace60b6a392f328c2b971b2fe78"));
+        assert_eq!(to_block(mul(c, h)), block("5e2ec746917062882c85b0685353deb7"));
+        // x · x^127 = x^128 ≡ x^7 + x^2 + x + 1
+        assert_eq!(mul(ONE >> 1, 1), 0xe1 << 120);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let h = from_block(&block("66e94bd4ef8a2c3b884cfa59ca342b2e"));
+        assert_eq!(mul(h, inverse(h)), ONE);
+        assert_eq!(inverse(ONE), ONE);
+        assert_eq!(inverse(0), 0);
+    }
+
+    #[test]
+    fn test_ghash() {
+        let h = block("66e94bd4ef8a2c3b884cfa59ca342b2e");
+        let c = block("0388dace60b6a392f328c2b971b2fe78");
+        assert_eq!(ghash(&h, &[], &c), block("f38cbb1ad69223dcc3457ae5b6b0f885"));
+        assert_eq!(ghash(&h, &[], &[]), [0; BLOCK_SIZE]);
+    }
+}
@@ -0,0 +1,125 @@
+//! GF(2^8) mit dem AES-Polynom x^8 + x^4 + x^3 + x + 1
+//!
+//! [`mul`] rechnet carry-less und ohne Verzweigung an den Operanden, wie es
+//! MixColumns braucht. [`mul_table`] nimmt stattdessen Logarithmus- und
+//! Exponententafeln zum Erzeugenden 0x03; das ist schneller, aber die
+//! Speicherzugriffe hängen von den Werten ab.
+
+/// Irreduzibles Polynom des AES-Körpers
+pub const MODULUS: u16 = 0x11b;
+
+/// Multiplikation mit x, in AES `xtime` genannt
+pub fn xtime(a: u8) -> u8 {
+    (a << 1) ^ (0x1b & 0u8.wrapping_sub(a >> 7))
+}
+
+/// Produkt in GF(2^8)
+pub fn mul(a: u8, b: u8) -> u8 {
+    let (mut a, mut result) = (a, 0);
+    for i in 0..8 {
+        result ^= a & 0u8.wrapping_sub(b >> i & 1);
+        a = xtime(a);
+    }
+    result
+}
+
+/// (Exponenten, Logarithmen) zum Erzeugenden 0x03
+const fn tables() -> ([u8; 256], [u8; 256]) {
+    let (mut exp, mut log) = ([0u8; 256], [0u8; 256]);
+    let mut value: u8 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = value;
+        log[value as usize] = i as u8;
+        // value · 3 = value · x + value
+        value ^= (value << 1) ^ (0x1b & 0u8.wrapping_sub(value >> 7));
+        i += 1;
+    }
+    // 3^255 = 1, so bleibt `exp` für Indizes bis 255 gültig
+    exp[255] = 1;
+    (exp, log)
+}
+
+const TABLES: ([u8; 256], [u8; 256]) = tables();
+
+/// Potenzen des Erzeugenden: `EXP[i]` = 3^i
+pub const EXP: [u8; 256] = TABLES.0;
+
+/// Diskreter Logarithmus zur Basis 3; `LOG[0]` ist bedeutungslos
+pub const LOG: [u8; 256] = TABLES.1;
+
+/// Produkt über die Tafeln
+pub fn mul_table(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    EXP[(usize::from(LOG[a as usize]) + usize::from(LOG[b as usize])) % 255]
+}
+
+/// `a^n`, mit 0^0 = 1
+pub fn pow(a: u8, n: u32) -> u8 {
+    (0..32).rev().fold(1, |result, i| {
+        let square = mul(result, result);
+        if n >> i & 1 == 1 { mul(square, a) } else { square }
+    })
+}
+
+/// Multiplikatives Inverses, wie in der AES-S-Box mit 0 ↦ 0
+pub fn inverse(a: u8) -> u8 {
+    // a^254 = a^(-1), denn die Gruppe hat 255 Elemente; 0^254 = 0
+    pow(a, 254)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly;
+
+    #[test]
+    fn test_known_products() {
+        // FIPS 197, Abschnitt 4.2
+        assert_eq!(mul(0x57, 0x83), 0xc1);
+        assert_eq!(mul(0x57, 0x13), 0xfe);
+        assert_eq!(xtime(0x57), 0xae);
+        assert_eq!(xtime(0x80), 0x1b);
+        assert_eq!(mul(0x02, 0x80), 0x1b);
+        assert_eq!(mul(0x03, 0x01), 0x03);
+    }
+
+    #[test]
+    fn test_implementations_agree() {
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                let expected = poly::rem(poly::clmul(a.into(), b.into()), MODULUS.into()) as u8;
+                assert_eq!(mul(a, b), expected);
+                assert_eq!(mul_table(a, b), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tables() {
+        assert_eq!(EXP[0], 1);
+        assert_eq!(EXP[1], 3);
+        let mut seen = [false; 256];
+        for &value in &EXP[..255] {
+            assert!(!seen[value as usize], "3 erzeugt die Gruppe");
+            seen[value as usize] = true;
+        }
+        assert_eq!(LOG[3], 1);
+    }
+
+    #[test]
+    fn test_inverse_and_pow() {
+        assert_eq!(inverse(0), 0);
+        assert_eq!(inverse(1), 1);
+        // FIPS 197, Abschnitt 4.4: {53}·{ca} = {01}
+        assert_eq!(inverse(0x53), 0xca);
+        for a in 1..=255u8 {
+            assert_eq!(mul(a, inverse(a)), 1);
+        }
+        assert_eq!(pow(0x03, 255), 1);
+        assert_eq!(pow(0x57, 2), mul(0x57, 0x57));
+        assert_eq!(pow(0, 0), 1);
+    }
+}
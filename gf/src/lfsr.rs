@@ -0,0 +1,163 @@
+//! Linear rückgekoppelte Schieberegister (LFSR) über GF(2)
+//!
+//! Ein LFSR der Länge L mit Verbindungspolynom C(x) = 1 + c_1·x + … + c_L·x^L
+//! erzeugt die Folge s_n = c_1·s_(n-1) + … + c_L·s_(n-L). Polynome sind wie in
+//! [`crate::poly`] Bitmuster, Bit i ist c_i. Als Stromchiffre wird der
+//! Schlüsselstrom auf den Klartext addiert; der Schlüssel ist der
+//! Anfangszustand, oft auch das Polynom.
+//!
+//! Das ist unsicher: Nach Berlekamp und Massey genügen 2L Bits Schlüsselstrom,
+//! um das kürzeste erzeugende LFSR samt Polynom zu rekonstruieren. Diese Bits
+//! liefert schon ein bekannter Dateikopf.
+
+use crate::poly;
+
+/// Größte Länge, die in ein `u128` passt
+pub const MAX_LENGTH: u32 = 127;
+
+/// Fibonacci-LFSR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lfsr {
+    connection: u128,
+    length: u32,
+    /// Bit i ist s_(n+i), Bit 0 wird als Nächstes ausgegeben
+    state: u128,
+    /// Bit L - i gesetzt, wenn c_i gesetzt ist
+    taps: u128,
+}
+
+impl Lfsr {
+    /// LFSR der Länge `length` mit Verbindungspolynom `connection` und den
+    /// ersten `length` Ausgabebits `initial` (Bit i wird als i-tes ausgegeben)
+    pub fn new(connection: u128, length: u32, initial: u128) -> Self {
+        assert!(length <= MAX_LENGTH, "LFSR länger als {} Bits", MAX_LENGTH);
+        assert!(connection & 1 == 1, "Verbindungspolynom muss 1 + … sein");
+        assert!(poly::degree(connection).is_some_and(|d| d <= length), "Polynom hat höheren Grad als das Register");
+        let mask = (1u128 << length) - 1;
+        let taps = (1..=length).filter(|&i| connection >> i & 1 == 1).fold(0, |taps, i| taps | 1 << (length - i));
+        Lfsr { connection, length, state: initial & mask, taps }
+    }
+
+    /// Kürzestes LFSR, das mit `bits` beginnt; `None` ab Länge über [`MAX_LENGTH`]
+    pub fn from_sequence(bits: &[u8]) -> Option<Self> {
+        let (connection, length) = berlekamp_massey(bits)?;
+        let initial = bits.iter().take(length as usize).enumerate().fold(0, |state, (i, &bit)| state | u128::from(bit & 1) << i);
+        Some(Lfsr::new(connection, length, initial))
+    }
+
+    pub fn connection(&self) -> u128 {
+        self.connection
+    }
+
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    pub fn next_bit(&mut self) -> u8 {
+        let output = (self.state & 1) as u8;
+        if self.length > 0 {
+            let feedback = (self.state & self.taps).count_ones() as u128 & 1;
+            self.state = (self.state >> 1) | feedback << (self.length - 1);
+        }
+        output
+    }
+
+    /// Nächstes Byte des Schlüsselstroms, höchstwertiges Bit zuerst
+    pub fn next_byte(&mut self) -> u8 {
+        (0..8).fold(0, |byte, _| byte << 1 | self.next_bit())
+    }
+
+    /// Ver- und Entschlüsseln: XOR mit dem Schlüsselstrom
+    pub fn apply(&mut self, data: &[u8]) -> Vec<u8> {
+        data.iter().map(|&b| b ^ self.next_byte()).collect()
+    }
+}
+
+/// Berlekamp-Massey: (Verbindungspolynom, Länge) des kürzesten LFSR für `bits`
+///
+/// Die Länge ist die lineare Komplexität der Folge. Eindeutig ist das
+/// Ergebnis, sobald mindestens doppelt so viele Bits wie die Länge vorliegen.
+/// `None`, wenn die Länge [`MAX_LENGTH`] übersteigt.
+pub fn berlekamp_massey(bits: &[u8]) -> Option<(u128, u32)> {
+    let (mut c, mut b) = (1u128, 1u128);
+    let (mut length, mut shift) = (0u32, 1u32);
+    for n in 0..bits.len() {
+        // Abweichung zwischen Vorhersage und tatsächlichem Bit
+        let discrepancy = (1..=length as usize).fold(bits[n] & 1, |d, i| d ^ ((c >> i) as u8 & bits[n - i] & 1));
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+        if poly::degree(b).unwrap_or(0) + shift > MAX_LENGTH {
+            return None;
+        }
+        let previous = c;
+        c ^= b << shift;
+        if 2 * length as usize <= n {
+            length = n as u32 + 1 - length;
+            b = previous;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+        if length > MAX_LENGTH {
+            return None;
+        }
+    }
+    Some((c, length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequence(lfsr: &mut Lfsr, count: usize) -> Vec<u8> {
+        (0..count).map(|_| lfsr.next_bit()).collect()
+    }
+
+    #[test]
+    fn test_lfsr() {
+        // 1 + x³ + x⁴ ist primitiv: Periode 2^4 - 1 = 15
+        let mut lfsr = Lfsr::new(0b11001, 4, 0b0001);
+        let bits = sequence(&mut lfsr, 30);
+        assert_eq!(bits[..15], bits[15..]);
+        assert!((1..15).all(|p| bits[..15] != bits[p..15 + p]));
+        assert_eq!(bits[..8], [1, 0, 0, 0, 1, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_stream_cipher() {
+        let message = b"Angriff im Morgengrauen";
+        let ciphertext = Lfsr::new(0b11001, 4, 0b1011).apply(message);
+        assert_ne!(ciphertext, message);
+        assert_eq!(Lfsr::new(0b11001, 4, 0b1011).apply(&ciphertext), message);
+    }
+
+    #[test]
+    fn test_berlekamp_massey() {
+        let connection = (1 << 31) | (1 << 3) | 1;
+        let mut lfsr = Lfsr::new(connection, 31, 0x1234_5678);
+        let bits = sequence(&mut lfsr, 62);
+        assert_eq!(berlekamp_massey(&bits), Some((connection, 31)));
+
+        // Das Ergebnis setzt die Folge fort
+        let mut clone = Lfsr::from_sequence(&bits).unwrap();
+        assert_eq!(sequence(&mut clone, 62), bits);
+        assert_eq!(sequence(&mut clone, 100), sequence(&mut lfsr, 100));
+    }
+
+    #[test]
+    fn test_linear_complexity() {
+        assert_eq!(berlekamp_massey(&[]), Some((1, 0)));
+        assert_eq!(berlekamp_massey(&[0; 20]), Some((1, 0)));
+        // Eine einzelne Eins am Ende braucht ein Register über die ganze Länge
+        let mut bits = [0; 10];
+        bits[9] = 1;
+        assert_eq!(berlekamp_massey(&bits).map(|(_, length)| length), Some(10));
+        // Konstante Einsen: s_n = s_(n-1)
+        assert_eq!(berlekamp_massey(&[1; 20]), Some((0b11, 1)));
+        let mut bits = vec![0; 300];
+        bits[299] = 1;
+        assert_eq!(berlekamp_massey(&bits), None);
+    }
+}
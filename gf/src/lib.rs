@@ -0,0 +1,84 @@
+//! Endliche Körper der Charakteristik 2
+//!
+//! AES rechnet in GF(2^8), GHASH in GF(2^128), Codes wie Reed-Muller über
+//! Polynomen in GF(2). Statt diese Arithmetik in jedem Werkzeug neu zu
+//! schreiben, liegt sie hier an einer Stelle:
+//!
+//! - [`poly`]: Polynome über GF(2) als Bitmuster, carry-less Multiplikation,
+//!   Division mit Rest und Irreduzibilitätstest
+//! - [`gf256`]: der AES-Körper, carry-less und über Tafeln
+//! - [`gf128`]: der GCM-Körper samt GHASH
+//! - [`lfsr`]: Schieberegister als Stromchiffre und Berlekamp-Massey
+//! - [`Field`]: beliebige Körper GF(2^m) bis m = 64 für Experimente
+
+pub mod gf128;
+pub mod gf256;
+pub mod lfsr;
+pub mod poly;
+
+/// Körper GF(2^m) = GF(2)[x] / (p) zu einem irreduziblen Polynom p
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field {
+    modulus: u128,
+}
+
+impl Field {
+    /// Körper zum Polynom `modulus`, `None` wenn es nicht irreduzibel ist
+    /// oder nicht Grad 1 bis 64 hat
+    pub fn new(modulus: u128) -> Option<Self> {
+        poly::is_irreducible(modulus).then_some(Field { modulus })
+    }
+
+    pub fn modulus(&self) -> u128 {
+        self.modulus
+    }
+
+    /// Grad m des Körpers über GF(2)
+    pub fn degree(&self) -> u32 {
+        poly::degree(self.modulus).unwrap_or(0)
+    }
+
+    /// Anzahl der Elemente ungleich null, 2^m - 1
+    pub fn group_order(&self) -> u128 {
+        (1 << self.degree()) - 1
+    }
+
+    pub fn mul(&self, a: u128, b: u128) -> u128 {
+        poly::mul_mod(a, b, self.modulus)
+    }
+
+    pub fn pow(&self, a: u128, n: u128) -> u128 {
+        poly::pow_mod(a, n, self.modulus)
+    }
+
+    /// Multiplikatives Inverses mit 0 ↦ 0, über a^(2^m - 2)
+    pub fn inverse(&self, a: u128) -> u128 {
+        self.pow(a, self.group_order() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_matches_aes() {
+        let field = Field::new(gf256::MODULUS.into()).unwrap();
+        assert_eq!(field.degree(), 8);
+        assert_eq!(field.group_order(), 255);
+        for a in 0..=255u8 {
+            assert_eq!(field.mul(a.into(), 0x57), gf256::mul(a, 0x57).into());
+            assert_eq!(field.inverse(a.into()), gf256::inverse(a).into());
+        }
+    }
+
+    #[test]
+    fn test_field_rejects_reducible() {
+        assert_eq!(Field::new(0x100), None);
+        assert_eq!(Field::new(1), None);
+        // GF(2^64) zu x^64 + x^4 + x^3 + x + 1
+        let field = Field::new((1 << 64) | 0b11011).unwrap();
+        let a = 0x0123_4567_89ab_cdef;
+        assert_eq!(field.mul(a, field.inverse(a)), 1);
+    }
+}
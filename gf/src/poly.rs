@@ -0,0 +1,143 @@
+//! Polynome über GF(2)
+//!
+//! Ein Polynom ist ein Bitmuster: Bit i ist der Koeffizient von x^i, also
+//! steht 0x11b für x^8 + x^4 + x^3 + x + 1. Addition ist XOR, Multiplikation
+//! die „carry-less“ Multiplikation ohne Überträge. Die Schleifen verzweigen
+//! nicht an den Bits der Operanden, nur an ihrer festen Breite.
+
+/// Carry-less Produkt zweier Polynome vom Grad unter 64
+pub fn clmul(a: u64, b: u64) -> u128 {
+    let a = u128::from(a);
+    (0..64).fold(0u128, |product, i| product ^ (a << i) & 0u128.wrapping_sub(u128::from(b >> i & 1)))
+}
+
+/// Carry-less Produkt zweier Polynome vom Grad unter 128, als (hohe, niedrige) Hälfte
+///
+/// Karatsuba über die 64-Bit-Hälften: drei statt vier Teilprodukte.
+pub fn clmul128(a: u128, b: u128) -> (u128, u128) {
+    let (a1, a0) = ((a >> 64) as u64, a as u64);
+    let (b1, b0) = ((b >> 64) as u64, b as u64);
+    let high = clmul(a1, b1);
+    let low = clmul(a0, b0);
+    let middle = clmul(a1 ^ a0, b1 ^ b0) ^ high ^ low;
+    (high ^ (middle >> 64), low ^ (middle << 64))
+}
+
+/// Grad des Polynoms, `None` für das Nullpolynom
+pub fn degree(p: u128) -> Option<u32> {
+    p.checked_ilog2()
+}
+
+/// Rest von `a` bei Division durch `m`; `m` darf nicht null sein
+pub fn rem(mut a: u128, m: u128) -> u128 {
+    let m_degree = degree(m).expect("Division durch das Nullpolynom");
+    while let Some(a_degree) = degree(a).filter(|&d| d >= m_degree) {
+        a ^= m << (a_degree - m_degree);
+    }
+    a
+}
+
+/// Größter gemeinsamer Teiler nach Euklid
+pub fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, rem(a, b));
+    }
+    a
+}
+
+/// `a · b mod m` für einen Modul vom Grad höchstens 64
+pub fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    assert!(degree(m).is_some_and(|d| d <= 64), "Modul muss Grad 1 bis 64 haben");
+    rem(clmul(rem(a, m) as u64, rem(b, m) as u64), m)
+}
+
+/// `a^n mod m` durch Square-and-Multiply
+pub fn pow_mod(a: u128, mut n: u128, m: u128) -> u128 {
+    let mut result = rem(1, m);
+    let mut base = rem(a, m);
+    while n > 0 {
+        if n & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        n >>= 1;
+    }
+    result
+}
+
+/// Irreduzibilitätstest nach Ben-Or für Polynome vom Grad 1 bis 64
+///
+/// `p` ist genau dann irreduzibel, wenn es mit keinem x^(2^i) - x für
+/// i ≤ deg(p)/2 einen echten gemeinsamen Teiler hat.
+pub fn is_irreducible(p: u128) -> bool {
+    let Some(n) = degree(p).filter(|&d| (1..=64).contains(&d)) else {
+        return false;
+    };
+    let x = 0b10;
+    let mut power = rem(x, p);
+    for _ in 0..n / 2 {
+        power = mul_mod(power, power, p);
+        if gcd(p, power ^ rem(x, p)) != 1 {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clmul() {
+        // (x + 1)² = x² + 1 über GF(2)
+        assert_eq!(clmul(0b11, 0b11), 0b101);
+        assert_eq!(clmul(u64::MAX, 1), u128::from(u64::MAX));
+        assert_eq!(clmul(1 << 63, 1 << 63), 1 << 126);
+        assert_eq!(clmul(0, 0xdead_beef), 0);
+    }
+
+    #[test]
+    fn test_clmul128() {
+        let schoolbook = |a: u128, b: u128| {
+            (0..128).filter(|i| b >> i & 1 == 1).fold((0u128, 0u128), |(high, low), i| {
+                (high ^ if i == 0 { 0 } else { a >> (128 - i) }, low ^ (a << i))
+            })
+        };
+        for (a, b) in [(3, 3), (u128::MAX, u128::MAX), (1 << 127, 1 << 127), (0x0123_4567_89ab_cdef_fedc_ba98_7654_3210, 0xdead_beef_0bad_f00d_1234)] {
+            assert_eq!(clmul128(a, b), schoolbook(a, b));
+        }
+    }
+
+    #[test]
+    fn test_rem_and_gcd() {
+        assert_eq!(degree(0), None);
+        assert_eq!(degree(0x11b), Some(8));
+        // x^8 ≡ x^4 + x^3 + x + 1 mod 0x11b
+        assert_eq!(rem(0x100, 0x11b), 0x1b);
+        // (x + 1)(x² + x + 1) und (x + 1)(x³ + x + 1)
+        assert_eq!(gcd(clmul(0b11, 0b111), clmul(0b11, 0b1011)), 0b11);
+    }
+
+    #[test]
+    fn test_pow_mod() {
+        // Die multiplikative Gruppe von GF(2^8) hat 255 Elemente
+        for a in [1, 2, 3, 0x53, 0xca] {
+            assert_eq!(pow_mod(a, 255, 0x11b), 1);
+        }
+        assert_eq!(pow_mod(0, 0, 0x11b), 1);
+    }
+
+    #[test]
+    fn test_is_irreducible() {
+        for p in [0b10, 0b11, 0b111, 0b1011, 0x11b, 0x11d, (1 << 64) | 0b11011] {
+            assert!(is_irreducible(p), "{:#x}", p);
+        }
+        // Konstanten, x², x² + 1 = (x + 1)², x^4 + x² + 1 = (x² + x + 1)², x^8
+        for p in [0, 1, 0b100, 0b101, 0b10101, 0x100] {
+            assert!(!is_irreducible(p), "{:#x}", p);
+        }
+        // Genau 30 irreduzible Polynome vom Grad 8
+        assert_eq!((0x100..0x200).filter(|&p| is_irreducible(p)).count(), 30);
+    }
+}
@@ -0,0 +1,126 @@
+//! Authentisierung des Servers durch eine Signatur über den Transkript-Hash
+//!
+//! Signiert wird wie bei TLS 1.3 eine feste Kontextzeichenkette gefolgt vom
+//! Hash, damit die Signatur nicht in einem anderen Protokoll wiederverwendet
+//! werden kann. DSA verwendet `dsa_sign`/`dsa_verify` (SHA-224 intern) mit
+//! der Signatur als DER `SEQUENCE { r, s }`, RSA PKCS #1 v1.5 mit SHA-256 aus
+//! `rsa::pkcs1`.
+
+use clap::ValueEnum;
+use key_formats::{DsaPrivateKey, DsaPublicKey, DsaSignature, KeyFile, RsaPrivateKey, RsaPublicKey};
+use rand::{CryptoRng, Rng};
+use std::error::Error;
+use tool_error::Context;
+
+const CONTEXT: &[u8] = b"cryptolab handshake, Signatur des Servers\0";
+
+/// Signaturverfahren des Servers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Method {
+    Dsa,
+    /// RSA mit PKCS #1 v1.5 und SHA-256, Modul mindestens 496 Bit
+    Rsa,
+}
+
+/// Privater Schlüssel des Servers
+pub enum SigningKey {
+    Dsa(DsaPrivateKey),
+    Rsa(RsaPrivateKey),
+}
+
+/// Öffentlicher Schlüssel des Servers, den der Client vorab kennt
+pub enum VerifyingKey {
+    Dsa(DsaPublicKey),
+    Rsa(RsaPublicKey),
+}
+
+fn signed_data(transcript_hash: &[u8]) -> Vec<u8> {
+    [CONTEXT, transcript_hash].concat()
+}
+
+impl SigningKey {
+    pub fn read(method: Method, path: &str) -> tool_error::Result<Self> {
+        let context = || format!("Kann Serverschlüssel '{}' nicht lesen", path);
+        Ok(match method {
+            Method::Dsa => SigningKey::Dsa(DsaPrivateKey::read(path).with_context(context)?),
+            Method::Rsa => SigningKey::Rsa(RsaPrivateKey::read(path).with_context(context)?),
+        })
+    }
+
+    pub fn sign(&self, transcript_hash: &[u8], rng: &mut (impl Rng + CryptoRng)) -> Result<Vec<u8>, Box<dyn Error>> {
+        let data = signed_data(transcript_hash);
+        match self {
+            SigningKey::Dsa(key) => Ok(dsa_sign::dsa_sign(&data, &key.params, &key.x, rng)?.to_der()),
+            SigningKey::Rsa(key) => Ok(rsa::pkcs1::sign(&data, key).ok_or("RSA-Modul ist zu kurz für PKCS #1 mit SHA-256")?),
+        }
+    }
+}
+
+impl VerifyingKey {
+    pub fn read(method: Method, path: &str) -> tool_error::Result<Self> {
+        let context = || format!("Kann öffentlichen Serverschlüssel '{}' nicht lesen", path);
+        Ok(match method {
+            Method::Dsa => VerifyingKey::Dsa(DsaPublicKey::read(path).with_context(context)?),
+            Method::Rsa => VerifyingKey::Rsa(RsaPublicKey::read(path).with_context(context)?),
+        })
+    }
+
+    pub fn verify(&self, transcript_hash: &[u8], signature: &[u8]) -> bool {
+        let data = signed_data(transcript_hash);
+        match self {
+            VerifyingKey::Dsa(key) => DsaSignature::from_der(signature)
+                .is_ok_and(|signature| dsa_verify::dsa_verify(&data, &signature, &key.params, &key.y).unwrap_or(false)),
+            VerifyingKey::Rsa(key) => rsa::pkcs1::verify(&data, signature, key),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crypto_math::mod_pow;
+    use csprng::{Algorithm, Drbg};
+    use key_formats::DsaParameters;
+    use num_bigint::BigUint;
+    use secret::Secret;
+    use sha2::{Digest, Sha256};
+
+    /// RSA-Schlüssel mit 576 Bit, gerade groß genug für PKCS #1 mit SHA-256
+    pub(crate) fn rsa_keys() -> (SigningKey, VerifyingKey) {
+        let n: BigUint = "191677726143854315674598428926330859363608329588293420380333240985455824521179813035337110510248831071087008746466405096838035678566522676019644319734501401554761112169596389".parse().unwrap();
+        let d: BigUint = "190852953591363861167675061104226928723808864416804051862591294085873931201147271001432259701997425425908853217310742424346992958524465651074542509542066127393363380985475733".parse().unwrap();
+        (
+            SigningKey::Rsa(RsaPrivateKey { d: Secret::new(d), n: n.clone() }),
+            VerifyingKey::Rsa(RsaPublicKey { e: BigUint::from(65537u32), n }),
+        )
+    }
+
+    pub(crate) fn dsa_keys() -> (SigningKey, VerifyingKey) {
+        let params = DsaParameters { p: 283u32.into(), q: 47u32.into(), g: 64u32.into() };
+        let y = mod_pow(&params.g, &BigUint::from(24u32), &params.p);
+        (
+            SigningKey::Dsa(DsaPrivateKey { params: params.clone(), x: Secret::new(24u32.into()) }),
+            VerifyingKey::Dsa(DsaPublicKey { params, y }),
+        )
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let hash = Sha256::digest(b"Transkript");
+        for (signing, verifying) in [rsa_keys(), dsa_keys()] {
+            let signature = signing.sign(&hash, &mut Drbg::from_seed(Algorithm::ChaCha20, b"auth")).unwrap();
+            assert!(verifying.verify(&hash, &signature));
+            assert!(!verifying.verify(&Sha256::digest(b"anderes Transkript"), &signature));
+        }
+    }
+
+    #[test]
+    fn test_rsa_rejects_modified_signature() {
+        let (signing, verifying) = rsa_keys();
+        let hash = Sha256::digest(b"Transkript");
+        let mut signature = signing.sign(&hash, &mut Drbg::from_seed(Algorithm::ChaCha20, b"auth")).unwrap();
+        signature[40] ^= 1;
+        assert!(!verifying.verify(&hash, &signature));
+        assert!(!verifying.verify(&hash, &signature[1..]));
+    }
+}
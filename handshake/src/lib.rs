@@ -0,0 +1,168 @@
+//! handshake: TLS-artiger Verbindungsaufbau über TCP
+//!
+//! Der Server schickt DH-Parameter und seinen Schlüsselanteil, der Client
+//! antwortet mit seinem Anteil, der Server signiert das Transkript mit DSA
+//! oder RSA. Aus dem gemeinsamen Geheimnis leitet HKDF-SHA256 je Richtung
+//! einen AES-128- und einen HMAC-Schlüssel ab; danach schickt der Client
+//! Zeilen von STDIN als geschützte Datensätze, der Server gibt sie aus und
+//! sendet sie als Echo zurück.
+//!
+//! Nur eine Demonstration: Der Client muss den öffentlichen Schlüssel des
+//! Servers vorab kennen, es gibt keine Zertifikate, keine Aushandlung von
+//! Verfahren und keine Authentisierung des Clients.
+
+mod auth;
+mod protocol;
+mod record;
+
+pub use auth::{Method, SigningKey, VerifyingKey};
+pub use protocol::{Session, connect, serve};
+pub use record::{RecordLayer, Role};
+
+use clap::{Parser, Subcommand};
+use csprng::RngArgs;
+use key_formats::{DhParameters, KeyFile};
+use logging::{Verbosity, info};
+use num_bigint::BigUint;
+use std::io::BufRead;
+use std::net::{TcpListener, TcpStream};
+use tool_error::Context;
+
+/// RFC 3526 Gruppe 14 (2048 Bit, g = 2), wenn keine Parameterdatei angegeben ist
+const DEFAULT_PRIME: &[u8] = b"FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74\
+020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437\
+4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED\
+EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05\
+98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB\
+9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B\
+E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF695581718\
+3995497CEA956AE515D2261898FA051015728E5A8AACAA68FFFFFFFFFFFFFFFF";
+
+#[derive(Parser)]
+#[command(name = "handshake")]
+#[command(about = "TLS-artiger Handshake über TCP: DH, HKDF, signiertes Transkript, AES-CTR und HMAC")]
+#[command(version = "1.0")]
+#[command(after_help = "Beispiel:\n  handshake server --key dsa_privat.txt\n  handshake client --connect localhost:4433 --server-key dsa_oeffentlich.txt")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Wartet auf einen Client und gibt dessen Nachrichten als Echo zurück
+    Server {
+        /// TCP-Port
+        #[arg(short, long, default_value_t = 4433, help = "Port, an dem der Server wartet")]
+        port: u16,
+
+        /// DH-Parameter von dh_params
+        #[arg(long, value_name = "DATEI", help = "DH-Parameter (p, g) von dh_params, Standard: RFC 3526 Gruppe 14")]
+        params: Option<String>,
+
+        /// Privater Signaturschlüssel des Servers
+        #[arg(short, long, help = "Privater DSA- bzw. RSA-Schlüssel des Servers")]
+        key: String,
+
+        /// Signaturverfahren
+        #[arg(short, long, value_enum, default_value_t = Method::Dsa)]
+        auth: Method,
+
+        #[command(flatten)]
+        rng: RngArgs,
+    },
+    /// Verbindet sich, prüft den Server und sendet Zeilen von STDIN
+    Client {
+        /// Adresse des Servers
+        #[arg(short, long, value_name = "HOST:PORT", help = "Adresse des Servers")]
+        connect: String,
+
+        /// Öffentlicher Signaturschlüssel des Servers
+        #[arg(short = 'k', long, help = "Öffentlicher DSA- bzw. RSA-Schlüssel des Servers")]
+        server_key: String,
+
+        /// Signaturverfahren
+        #[arg(short, long, value_enum, default_value_t = Method::Dsa)]
+        auth: Method,
+
+        #[command(flatten)]
+        rng: RngArgs,
+    },
+}
+
+fn default_params() -> DhParameters {
+    DhParameters {
+        p: BigUint::parse_bytes(DEFAULT_PRIME, 16).expect("Konstante ist hexadezimal"),
+        g: BigUint::from(2u32),
+    }
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+
+    match args.command {
+        Command::Server { port, params, key, auth, rng } => {
+            let key = SigningKey::read(auth, &key)?;
+            let params = match params {
+                Some(path) => DhParameters::read(&path).with_context(|| format!("Kann DH-Parameter '{}' nicht lesen", path))?,
+                None => default_params(),
+            };
+            dh_exchange::validate_dh_params(&params.p, &params.g)?;
+
+            let listener = TcpListener::bind(("0.0.0.0", port)).context("Kann Port nicht öffnen")?;
+            info!("Warte auf Verbindung an Port {}...", port);
+            let (mut stream, peer) = listener.accept().context("Fehler beim Annehmen der Verbindung")?;
+            info!("Verbunden mit {}", peer);
+
+            let mut session = serve(&mut stream, &params, &key, &mut rng.rng()).context("Handshake fehlgeschlagen")?;
+            info!("Handshake abgeschlossen, {}-Bit-Gruppe", params.p.bits());
+            loop {
+                let message = session.records.receive(&mut stream).context("Fehler beim Empfangen")?;
+                if message.is_empty() {
+                    info!("Client hat die Verbindung beendet");
+                    return Ok(());
+                }
+                println!("{}", String::from_utf8_lossy(&message));
+                session.records.send(&mut stream, &message).context("Fehler beim Senden")?;
+            }
+        }
+        Command::Client { connect: addr, server_key, auth, rng } => {
+            let server_key = VerifyingKey::read(auth, &server_key)?;
+            let mut stream = TcpStream::connect(&addr).with_context(|| format!("Kann keine Verbindung zu {} aufbauen", addr))?;
+
+            let mut session = connect(&mut stream, &server_key, &mut rng.rng()).context("Handshake fehlgeschlagen")?;
+            info!("Handshake abgeschlossen, Transkript-Hash {}", codec::hex::encode(&session.transcript_hash));
+
+            for line in std::io::stdin().lock().lines() {
+                let line = line.context("Fehler beim Lesen von stdin")?;
+                if line.is_empty() {
+                    continue;
+                }
+                session.records.send(&mut stream, line.as_bytes()).context("Fehler beim Senden")?;
+                let echo = session.records.receive(&mut stream).context("Fehler beim Empfangen")?;
+                println!("{}", String::from_utf8_lossy(&echo));
+            }
+            session.records.send(&mut stream, b"").context("Fehler beim Beenden der Verbindung")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_params() {
+        let params = default_params();
+        assert_eq!(params.p.bits(), 2048);
+        assert!(dh_exchange::validate_dh_params(&params.p, &params.g).is_ok());
+    }
+}
@@ -0,0 +1,223 @@
+//! Ablauf des Handshakes
+//!
+//! ```text
+//! Client                                  Server
+//! ClientHello: client_random      -->
+//!                                 <--     ServerHello: server_random, p, g, A
+//! KeyShare: B                     -->
+//!                                 <--     Signatur über den Transkript-Hash
+//! ```
+//!
+//! Jede Nachricht ist ein längenpräfixierter Rahmen wie bei `dh_exchange`.
+//! Der Transkript-Hash ist SHA-256 über alle Rahmen bis einschließlich B.
+//! Er geht in die Signatur des Servers ein, sodass der Client jede
+//! Veränderung von Zufallswerten, Parametern oder Schlüsselanteilen bemerkt,
+//! und dient als Salt für HKDF, sodass jede Sitzung eigene Schlüssel hat.
+
+use dh_exchange::kdf::{encode_secret, hkdf_sha256};
+use dh_exchange::net::{read_bytes, write_bytes};
+use dh_exchange::{generate_private_key, validate_dh_params, validate_public_key};
+use crypto_math::mod_pow;
+use key_formats::DhParameters;
+use logging::debug;
+use num_bigint::BigUint;
+use rand::{CryptoRng, Rng};
+use secret::Secret;
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::io::{Read, Write};
+
+use crate::auth::{SigningKey, VerifyingKey};
+use crate::record::{KEY_MATERIAL_LEN, RecordLayer, Role};
+
+const RANDOM_LEN: usize = 32;
+const KDF_INFO: &[u8] = b"cryptolab handshake v1 Schluessel";
+
+/// Ergebnis eines erfolgreichen Handshakes
+pub struct Session {
+    pub transcript_hash: [u8; 32],
+    pub records: RecordLayer,
+}
+
+/// Überträgt Rahmen und hasht sie für das Transkript mit
+struct Transcript(Sha256);
+
+impl Transcript {
+    fn new() -> Self {
+        Transcript(Sha256::new())
+    }
+
+    fn add(&mut self, frame: &[u8]) {
+        self.0.update((frame.len() as u32).to_be_bytes());
+        self.0.update(frame);
+    }
+
+    fn send<W: Write>(&mut self, writer: &mut W, frame: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.add(frame);
+        write_bytes(writer, frame)
+    }
+
+    fn receive<R: Read>(&mut self, reader: &mut R) -> Result<Vec<u8>, Box<dyn Error>> {
+        let frame = read_bytes(reader)?;
+        self.add(&frame);
+        Ok(frame)
+    }
+
+    fn hash(&self) -> [u8; 32] {
+        self.0.clone().finalize().into()
+    }
+}
+
+fn random_bytes(rng: &mut impl Rng) -> [u8; RANDOM_LEN] {
+    let mut random = [0u8; RANDOM_LEN];
+    rng.fill_bytes(&mut random);
+    random
+}
+
+fn session(shared_secret: &BigUint, p: &BigUint, transcript_hash: [u8; 32], role: Role) -> Session {
+    let material = hkdf_sha256(&encode_secret(shared_secret, p), &transcript_hash, KDF_INFO, KEY_MATERIAL_LEN);
+    Session { transcript_hash, records: RecordLayer::new(&material, role) }
+}
+
+/// Serverseite: sendet die DH-Parameter und signiert das Transkript
+pub fn serve<S: Read + Write>(stream: &mut S, params: &DhParameters, key: &SigningKey, rng: &mut (impl Rng + CryptoRng)) -> Result<Session, Box<dyn Error>> {
+    let mut transcript = Transcript::new();
+    let client_random = transcript.receive(stream)?;
+    if client_random.len() != RANDOM_LEN {
+        return Err(format!("ClientHello muss {} Zufallsbytes enthalten", RANDOM_LEN).into());
+    }
+
+    let private_key = generate_private_key(&params.p, rng);
+    let own_public = mod_pow(&params.g, &private_key, &params.p);
+    transcript.send(stream, &random_bytes(rng))?;
+    transcript.send(stream, &params.p.to_bytes_be())?;
+    transcript.send(stream, &params.g.to_bytes_be())?;
+    transcript.send(stream, &own_public.to_bytes_be())?;
+    stream.flush()?;
+
+    let peer_public = BigUint::from_bytes_be(&transcript.receive(stream)?);
+    validate_public_key(&peer_public, &params.p, None)?;
+
+    let transcript_hash = transcript.hash();
+    write_bytes(stream, &key.sign(&transcript_hash, rng)?)?;
+    stream.flush()?;
+    debug!("Transkript-Hash {}", codec::hex::encode(&transcript_hash));
+
+    let shared_secret = Secret::new(mod_pow(&peer_public, &private_key, &params.p));
+    Ok(session(&shared_secret, &params.p, transcript_hash, Role::Server))
+}
+
+/// Clientseite: prüft Parameter und Signatur, bevor Schlüssel abgeleitet werden
+pub fn connect<S: Read + Write>(stream: &mut S, server_key: &VerifyingKey, rng: &mut impl Rng) -> Result<Session, Box<dyn Error>> {
+    let mut transcript = Transcript::new();
+    transcript.send(stream, &random_bytes(rng))?;
+    stream.flush()?;
+
+    let server_random = transcript.receive(stream)?;
+    if server_random.len() != RANDOM_LEN {
+        return Err(format!("ServerHello muss {} Zufallsbytes enthalten", RANDOM_LEN).into());
+    }
+    let p = BigUint::from_bytes_be(&transcript.receive(stream)?);
+    let g = BigUint::from_bytes_be(&transcript.receive(stream)?);
+    let peer_public = BigUint::from_bytes_be(&transcript.receive(stream)?);
+
+    // Noch ist nichts signiert, die Werte stammen möglicherweise von einem Angreifer
+    validate_dh_params(&p, &g)?;
+    validate_public_key(&peer_public, &p, None)?;
+
+    let private_key = generate_private_key(&p, rng);
+    let own_public = mod_pow(&g, &private_key, &p);
+    transcript.send(stream, &own_public.to_bytes_be())?;
+    stream.flush()?;
+
+    let transcript_hash = transcript.hash();
+    let signature = read_bytes(stream)?;
+    if !server_key.verify(&transcript_hash, &signature) {
+        return Err("Signatur des Servers über das Transkript ist ungültig".into());
+    }
+    debug!("Transkript-Hash {}", codec::hex::encode(&transcript_hash));
+
+    let shared_secret = Secret::new(mod_pow(&peer_public, &private_key, &p));
+    Ok(session(&shared_secret, &p, transcript_hash, Role::Client))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::tests::{dsa_keys, rsa_keys};
+    use csprng::{Algorithm, Drbg};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+
+    /// RFC 2409 Oakley Group 1 (768 Bit) ist groß genug für validate_dh_params
+    fn test_group() -> DhParameters {
+        let p = BigUint::parse_bytes(
+            b"FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74\
+              020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437\
+              4FE1356D6D51C245E485B576625E7EC6F44C42E9A63A3620FFFFFFFFFFFFFFFF",
+            16,
+        ).unwrap();
+        DhParameters { p, g: BigUint::from(2u32) }
+    }
+
+    fn rng(seed: &[u8]) -> Drbg {
+        Drbg::from_seed(Algorithm::ChaCha20, seed)
+    }
+
+    #[test]
+    fn test_handshake_over_tcp() {
+        let (signing, verifying) = dsa_keys();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut session = serve(&mut stream, &test_group(), &signing, &mut rng(b"server")).unwrap();
+            let request = session.records.receive(&mut stream).unwrap();
+            session.records.send(&mut stream, &[b"Echo: ".as_slice(), &request].concat()).unwrap();
+            session.transcript_hash
+        });
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let mut session = connect(&mut stream, &verifying, &mut rng(b"client")).unwrap();
+        session.records.send(&mut stream, b"Hallo").unwrap();
+        assert_eq!(session.records.receive(&mut stream).unwrap(), b"Echo: Hallo");
+        assert_eq!(server.join().unwrap(), session.transcript_hash);
+    }
+
+    #[test]
+    fn test_client_detects_modified_key_share() {
+        // Mit dem winzigen q der DSA-Testschlüssel gelänge eine Fälschung mit Wahrscheinlichkeit 1/47
+        let (signing, verifying) = rsa_keys();
+        let server_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let server_addr = server_listener.local_addr().unwrap();
+        let proxy_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let proxy_addr = proxy_listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = server_listener.accept().unwrap();
+            serve(&mut stream, &test_group(), &signing, &mut rng(b"server")).is_ok()
+        });
+
+        // Angreifer in der Mitte ersetzt A durch g und leitet den Rest unverändert weiter
+        let proxy = thread::spawn(move || {
+            let (mut client, _) = proxy_listener.accept().unwrap();
+            let mut server = TcpStream::connect(server_addr).unwrap();
+            let forward = |from: &mut TcpStream, to: &mut TcpStream| write_bytes(to, &read_bytes(from).unwrap()).unwrap();
+            forward(&mut client, &mut server);
+            for _ in 0..3 {
+                forward(&mut server, &mut client);
+            }
+            read_bytes(&mut server).unwrap();
+            write_bytes(&mut client, &[2]).unwrap();
+            forward(&mut client, &mut server);
+            forward(&mut server, &mut client);
+        });
+
+        let mut stream = TcpStream::connect(proxy_addr).unwrap();
+        let error = connect(&mut stream, &verifying, &mut rng(b"client")).err().unwrap();
+        assert!(error.to_string().contains("Signatur"), "{}", error);
+        proxy.join().unwrap();
+        assert!(server.join().unwrap());
+    }
+}
@@ -0,0 +1,158 @@
+//! Datensätze nach dem Handshake: AES-128-CTR und HMAC-SHA256
+//!
+//! Jede Richtung hat eigene Schlüssel und eine eigene Sequenznummer. Die
+//! Sequenznummer bildet die Nonce für CTR und geht in den HMAC ein; ein
+//! wiederholter, vertauschter oder verworfener Datensatz fällt dadurch auf.
+//!
+//! ```text
+//! Datensatz = AES-CTR(k_enc, Nonce = 0^4 || seq, Klartext) || HMAC(k_mac, seq || Geheimtext)
+//! ```
+//!
+//! Ein leerer Klartext beendet die Verbindung.
+
+use aes_128::Aes128;
+use cipher_modes::{CipherModes, KeyInit};
+use dh_exchange::net::{read_bytes, write_bytes};
+use hmac::{Hmac, Mac};
+use secret::Secret;
+use sha2::Sha256;
+use std::error::Error;
+use std::io::{Read, Write};
+
+const BLOCK_SIZE: usize = 16;
+const ENCRYPTION_KEY_LEN: usize = 16;
+const MAC_KEY_LEN: usize = 32;
+const TAG_LEN: usize = 32;
+/// Schlüsselmaterial für beide Richtungen
+pub const KEY_MATERIAL_LEN: usize = 2 * (ENCRYPTION_KEY_LEN + MAC_KEY_LEN);
+
+/// Seite der Verbindung, bestimmt welche Schlüssel zum Senden dienen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Client,
+    Server,
+}
+
+struct Direction {
+    encryption: Aes128,
+    mac: Secret<Vec<u8>>,
+    sequence: u64,
+}
+
+impl Direction {
+    fn new(material: &[u8]) -> Self {
+        Direction {
+            encryption: Aes128::new(&material[..ENCRYPTION_KEY_LEN]).expect("AES-128 braucht 16 Bytes"),
+            mac: Secret::new(material[ENCRYPTION_KEY_LEN..].to_vec()),
+            sequence: 0,
+        }
+    }
+
+    fn nonce(&self) -> [u8; BLOCK_SIZE - 4] {
+        let mut nonce = [0u8; BLOCK_SIZE - 4];
+        nonce[4..].copy_from_slice(&self.sequence.to_be_bytes());
+        nonce
+    }
+
+    fn keystream(&self, data: &[u8]) -> Vec<u8> {
+        CipherModes::ctr_encrypt_with_nonce(&self.encryption, &self.nonce(), data)
+            .expect("Blockgröße und Nonce passen zu AES")
+    }
+
+    fn mac(&self, ciphertext: &[u8]) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.mac).expect("HMAC akzeptiert jede Schlüssellänge");
+        mac.update(&self.sequence.to_be_bytes());
+        mac.update(ciphertext);
+        mac
+    }
+}
+
+/// Verschlüsselt und authentisiert die Anwendungsdaten einer Verbindung
+pub struct RecordLayer {
+    sending: Direction,
+    receiving: Direction,
+}
+
+impl RecordLayer {
+    /// Teilt das Schlüsselmaterial auf: zuerst Client → Server, dann Server → Client
+    pub fn new(material: &[u8], role: Role) -> Self {
+        assert_eq!(material.len(), KEY_MATERIAL_LEN, "Länge des Schlüsselmaterials");
+        let (client, server) = material.split_at(KEY_MATERIAL_LEN / 2);
+        let (client, server) = (Direction::new(client), Direction::new(server));
+        match role {
+            Role::Client => RecordLayer { sending: client, receiving: server },
+            Role::Server => RecordLayer { sending: server, receiving: client },
+        }
+    }
+
+    pub fn seal(&mut self, plaintext: &[u8]) -> Vec<u8> {
+        let mut record = self.sending.keystream(plaintext);
+        let tag = self.sending.mac(&record).finalize().into_bytes();
+        record.extend_from_slice(&tag);
+        self.sending.sequence += 1;
+        record
+    }
+
+    pub fn open(&mut self, record: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let split = record.len().checked_sub(TAG_LEN).ok_or("Datensatz ist kürzer als der HMAC")?;
+        let (ciphertext, tag) = record.split_at(split);
+        self.receiving.mac(ciphertext)
+            .verify_slice(tag)
+            .map_err(|_| format!("HMAC von Datensatz {} ungültig, Verbindung wird abgebrochen", self.receiving.sequence))?;
+        let plaintext = self.receiving.keystream(ciphertext);
+        self.receiving.sequence += 1;
+        Ok(plaintext)
+    }
+
+    pub fn send<W: Write>(&mut self, writer: &mut W, plaintext: &[u8]) -> Result<(), Box<dyn Error>> {
+        write_bytes(writer, &self.seal(plaintext))?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn receive<R: Read>(&mut self, reader: &mut R) -> Result<Vec<u8>, Box<dyn Error>> {
+        self.open(&read_bytes(reader)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair() -> (RecordLayer, RecordLayer) {
+        let material: Vec<u8> = (0..KEY_MATERIAL_LEN as u8).collect();
+        (RecordLayer::new(&material, Role::Client), RecordLayer::new(&material, Role::Server))
+    }
+
+    #[test]
+    fn test_both_directions() {
+        let (mut client, mut server) = pair();
+        let record = client.seal(b"GET /");
+        assert_ne!(&record[..5], b"GET /");
+        assert_eq!(server.open(&record).unwrap(), b"GET /");
+        assert_eq!(client.open(&server.seal(b"200 OK")).unwrap(), b"200 OK");
+        assert_eq!(server.open(&client.seal(b"")).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_same_plaintext_differs_per_record() {
+        let (mut client, _) = pair();
+        assert_ne!(client.seal(b"ping"), client.seal(b"ping"));
+    }
+
+    #[test]
+    fn test_rejects_tampering_and_replay() {
+        let (mut client, mut server) = pair();
+        let first = client.seal(b"Betrag 10");
+        let mut modified = first.clone();
+        modified[7] ^= 1;
+        assert!(server.open(&modified).is_err());
+
+        assert!(server.open(&first).is_ok());
+        assert!(server.open(&first).is_err());
+        // Der Server lauscht nicht auf seine eigenen Datensätze
+        let (_, mut other_server) = pair();
+        let echo = other_server.seal(b"Echo");
+        assert!(other_server.open(&echo).is_err());
+    }
+}
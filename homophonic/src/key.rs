@@ -0,0 +1,213 @@
+//! Homophonic keys and the numeric ciphertext format.
+//!
+//! A key assigns every letter A-Z a set of numeric symbols. The number of
+//! symbols per letter follows the letter frequency of the language, so every
+//! symbol of the ciphertext occurs about equally often.
+//!
+//! Key files contain one line per letter: the letter followed by its symbols,
+//! e.g. `E 03 17 42 58`.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use classical::Alphabet;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct HomophonicKey {
+    /// Symbols of every letter, indexed like the latin alphabet
+    homophones: Vec<Vec<u16>>,
+}
+
+impl HomophonicKey {
+    /// Generates a random key with `symbols` symbols in total.
+    ///
+    /// Every letter gets at least one symbol; the remaining ones are handed out
+    /// in proportion to the expected letter frequencies (largest remainder).
+    pub fn generate(frequencies: &[f64], symbols: usize, rng: &mut impl Rng) -> Result<Self, String> {
+        let letters = frequencies.len();
+        if symbols < letters || symbols > u16::MAX as usize {
+            return Err(format!("Number of symbols must be between {} and {}", letters, u16::MAX));
+        }
+
+        let extra = (symbols - letters) as f64;
+        let shares: Vec<f64> = frequencies.iter().map(|p| p * extra).collect();
+        let mut counts: Vec<usize> = shares.iter().map(|share| 1 + share.floor() as usize).collect();
+
+        let mut by_remainder: Vec<usize> = (0..letters).collect();
+        by_remainder.sort_by(|&a, &b| (shares[b] - shares[b].floor()).total_cmp(&(shares[a] - shares[a].floor())));
+        let missing = symbols - counts.iter().sum::<usize>();
+        for &letter in by_remainder.iter().cycle().take(missing) {
+            counts[letter] += 1;
+        }
+
+        let mut pool: Vec<u16> = (0..symbols as u16).collect();
+        pool.shuffle(rng);
+        let mut pool = pool.into_iter();
+        let homophones = counts.iter()
+            .map(|&count| {
+                let mut symbols: Vec<u16> = pool.by_ref().take(count).collect();
+                symbols.sort_unstable();
+                symbols
+            })
+            .collect();
+
+        Ok(HomophonicKey { homophones })
+    }
+
+    /// Parses a key file.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let alphabet = Alphabet::latin();
+        let mut homophones = vec![Vec::new(); alphabet.len()];
+        let mut seen = HashMap::new();
+
+        for (line_number, line) in content.lines().enumerate() {
+            let mut parts = line.split_whitespace();
+            let Some(letter) = parts.next() else {
+                continue;
+            };
+
+            let mut chars = letter.chars();
+            let index = match (chars.next().and_then(|c| alphabet.index_of(c)), chars.next()) {
+                (Some(index), None) => index,
+                _ => return Err(format!("Line {}: invalid letter '{}'", line_number + 1, letter)),
+            };
+
+            for symbol in parts {
+                let symbol: u16 = symbol.parse()
+                    .map_err(|_| format!("Line {}: invalid symbol '{}'", line_number + 1, symbol))?;
+                if let Some(other) = seen.insert(symbol, index) {
+                    return Err(format!(
+                        "Symbol {} is assigned to both {} and {}",
+                        symbol, alphabet.symbol(other), alphabet.symbol(index)
+                    ));
+                }
+                homophones[index].push(symbol);
+            }
+        }
+
+        if let Some(index) = homophones.iter().position(|symbols| symbols.is_empty()) {
+            return Err(format!("Letter {} has no symbols", alphabet.symbol(index)));
+        }
+        Ok(HomophonicKey { homophones })
+    }
+
+    /// Total number of symbols.
+    pub fn symbol_count(&self) -> usize {
+        self.homophones.iter().map(Vec::len).sum()
+    }
+
+    /// Number of digits used to write a symbol.
+    pub fn symbol_width(&self) -> usize {
+        let max = self.homophones.iter().flatten().copied().max().unwrap_or(0);
+        max.to_string().len().max(2)
+    }
+
+    /// Encrypts the letters of a text, choosing a random homophone for each one.
+    /// All other characters are dropped.
+    pub fn encrypt(&self, text: &str, rng: &mut impl Rng) -> Vec<u16> {
+        let alphabet = Alphabet::latin();
+        alphabet.normalize(text)
+            .chars()
+            .map(|c| {
+                let index = alphabet.index_of(c).expect("normalized text");
+                *self.homophones[index].choose(rng).expect("every letter has a symbol")
+            })
+            .collect()
+    }
+
+    /// Decrypts a sequence of symbols.
+    pub fn decrypt(&self, symbols: &[u16]) -> Result<String, String> {
+        let alphabet = Alphabet::latin();
+        let table: HashMap<u16, char> = self.homophones.iter()
+            .enumerate()
+            .flat_map(|(index, symbols)| {
+                let letter = alphabet.symbol(index);
+                symbols.iter().map(move |&symbol| (symbol, letter))
+            })
+            .collect();
+
+        symbols.iter()
+            .map(|symbol| table.get(symbol).copied().ok_or_else(|| format!("Symbol {} is not part of the key", symbol)))
+            .collect()
+    }
+}
+
+impl fmt::Display for HomophonicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let alphabet = Alphabet::latin();
+        let width = self.symbol_width();
+        for (index, symbols) in self.homophones.iter().enumerate() {
+            write!(f, "{}", alphabet.symbol(index))?;
+            for symbol in symbols {
+                write!(f, " {:0width$}", symbol, width = width)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes symbols as zero-padded numbers, 20 per line.
+pub fn format_ciphertext(symbols: &[u16], width: usize) -> String {
+    symbols.chunks(20)
+        .map(|line| {
+            line.iter()
+                .map(|symbol| format!("{:0width$}", symbol, width = width))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
+
+/// Reads whitespace-separated numeric symbols.
+pub fn parse_ciphertext(content: &str) -> Result<Vec<u16>, String> {
+    content.split_whitespace()
+        .map(|symbol| symbol.parse().map_err(|_| format!("Invalid ciphertext symbol '{}'", symbol)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use classical::Language;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_generate_follows_frequencies() {
+        let frequencies = Language::En.table().expected(&Alphabet::latin());
+        let key = HomophonicKey::generate(&frequencies, 100, &mut StdRng::seed_from_u64(1)).unwrap();
+
+        assert_eq!(key.symbol_count(), 100);
+        // E (12.7%) gets far more symbols than Z (0.07%), which still gets one
+        assert!(key.homophones[4].len() >= 10);
+        assert_eq!(key.homophones[25].len(), 1);
+        assert!(HomophonicKey::generate(&frequencies, 20, &mut StdRng::seed_from_u64(1)).is_err());
+    }
+
+    #[test]
+    fn test_roundtrip_through_files() {
+        let frequencies = Language::De.table().expected(&Alphabet::latin());
+        let mut rng = StdRng::seed_from_u64(7);
+        let key = HomophonicKey::generate(&frequencies, 60, &mut rng).unwrap();
+        let key = HomophonicKey::parse(&key.to_string()).unwrap();
+
+        let ciphertext = format_ciphertext(&key.encrypt("Angriff im Morgengrauen!", &mut rng), key.symbol_width());
+        let plaintext = key.decrypt(&parse_ciphertext(&ciphertext).unwrap()).unwrap();
+        assert_eq!(plaintext, "ANGRIFFIMMORGENGRAUEN");
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_keys() {
+        let mut content: String = ('B'..='Z').map(|c| format!("{} {}\n", c, c as u16)).collect();
+        assert!(HomophonicKey::parse(&content).is_err());
+        content.push_str("A 66\n");
+        assert!(HomophonicKey::parse(&content).is_err());
+        content = content.replace("A 66", "A 1");
+        assert!(HomophonicKey::parse(&content).is_ok());
+    }
+}
@@ -0,0 +1,175 @@
+//! Homophonic substitution cipher.
+//!
+//! Every plaintext letter is replaced by one of several numeric symbols, with
+//! frequent letters getting more symbols than rare ones. The symbol frequencies
+//! of the ciphertext are therefore nearly uniform, which defeats the frequency
+//! analysis used against simple substitution. The `solve` command shows how
+//! the cipher still falls to a search guided by n-gram statistics.
+
+use clap::{Parser, Subcommand};
+use logging::{English, Verbosity};
+use classical::{count_frequencies, index_of_coincidence, Alphabet, Language, NgramModel};
+use key::HomophonicKey;
+use rand::rngs::{OsRng, StdRng};
+use rand::SeedableRng;
+use solver::Solver;
+use tool_error::fs::{read_to_string as read, write};
+use tool_error::{Context, ToolError};
+
+mod key;
+mod solver;
+
+/// Command-line arguments for the homophonic substitution program.
+#[derive(Parser, Debug)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Seed for the random number generator, for reproducible results
+    #[arg(long, global = true, help = "Seed for reproducible keys, ciphertexts and solver runs")]
+    seed: Option<u64>,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Generate a random key weighted by the letter frequencies of a language
+    Generate {
+        #[arg(short, long, help = "Path of the key file to create")]
+        key: String,
+
+        /// Total number of symbols, at least 26
+        #[arg(short, long, default_value_t = 100, help = "Total number of symbols")]
+        symbols: usize,
+
+        #[arg(short, long, value_enum, default_value = "de", help = "Plaintext language (de, en, fr, es, it)")]
+        language: Language,
+    },
+    /// Encrypt a text, choosing a random homophone for every letter
+    Encrypt {
+        #[arg(short, long, help = "Path to the key file")]
+        key: String,
+
+        #[arg(short, long, help = "Path to the input file")]
+        input: String,
+
+        #[arg(short, long, help = "Path to the output file")]
+        output: String,
+    },
+    /// Decrypt a ciphertext with a known key
+    Decrypt {
+        #[arg(short, long, help = "Path to the key file")]
+        key: String,
+
+        #[arg(short, long, help = "Path to the input file")]
+        input: String,
+
+        #[arg(short, long, help = "Path to the output file")]
+        output: String,
+    },
+    /// Recover the plaintext without the key
+    Solve {
+        #[arg(short, long, help = "Path to the input file")]
+        input: String,
+
+        #[arg(short, long, help = "Path to the output file")]
+        output: String,
+
+        #[arg(short, long, value_enum, default_value = "de", help = "Plaintext language (de, en, fr, es, it)")]
+        language: Language,
+
+        /// N-gram statistics; the built-in tables are small, a real quadgram file works much better
+        #[arg(long, help = "Path to an n-gram statistics file (one 'NGRAM COUNT' pair per line)")]
+        ngram_file: Option<String>,
+
+        #[arg(long, default_value_t = 50_000, help = "Annealing steps per restart")]
+        iterations: usize,
+
+        #[arg(long, default_value_t = 10, help = "Number of independent restarts")]
+        restarts: usize,
+    },
+}
+
+/// Runs the tool with the given command-line arguments
+///
+/// The first argument is the program name, as with `std::env::args_os`,
+/// so the tool can also be started from `cryptolab`.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+    let mut rng = match cli.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_rng(OsRng).map_err(|e| ToolError::Other(e.into()))?,
+    };
+
+    match cli.command {
+        Command::Generate { key, symbols, language } => {
+            let frequencies = language.table().expected(&Alphabet::latin());
+            let homophonic_key = HomophonicKey::generate(&frequencies, symbols, &mut rng)
+                .map_err(ToolError::usage)?;
+            write(&key, homophonic_key.to_string())?;
+            println!("Generated key with {} symbols: {}", homophonic_key.symbol_count(), key);
+        }
+        Command::Encrypt { key, input, output } => {
+            let key = load_key(&key)?;
+            let plaintext = read(&input)?;
+            let ciphertext = key.encrypt(&plaintext, &mut rng);
+            write(&output, key::format_ciphertext(&ciphertext, key.symbol_width()))?;
+
+            // Show how much flatter the symbol distribution is
+            let alphabet = Alphabet::latin();
+            println!("Plaintext letter IC:   {:.4}", index_of_coincidence(&count_frequencies(&plaintext, &alphabet)));
+            println!("Ciphertext symbol IC:  {:.4}", symbol_ic(&ciphertext));
+        }
+        Command::Decrypt { key, input, output } => {
+            let key = load_key(&key)?;
+            let ciphertext = key::parse_ciphertext(&read(&input)?).map_err(ToolError::data)?;
+            write(&output, key.decrypt(&ciphertext).map_err(ToolError::data)?)?;
+        }
+        Command::Solve { input, output, language, ngram_file, iterations, restarts } => {
+            let ciphertext = key::parse_ciphertext(&read(&input)?).map_err(ToolError::data)?;
+            if ciphertext.is_empty() {
+                return Err(ToolError::data("Ciphertext is empty"));
+            }
+
+            let model = match ngram_file {
+                Some(path) => NgramModel::parse(&read(&path)?)
+                    .map_err(ToolError::data)
+                    .with_context(|| format!("Invalid n-gram file '{}'", path))?,
+                None => NgramModel::quadgrams(language).unwrap_or_else(|| NgramModel::bigrams(language)),
+            };
+
+            let expected = language.table().expected(&Alphabet::latin());
+            let solution = Solver::new(&model, &expected).solve(&ciphertext, iterations, restarts, &mut rng);
+            for (symbol, letter) in solution.symbols.iter().zip(&solution.letters) {
+                println!("{:>5} -> {}", symbol, letter);
+            }
+            println!("Score: {:.2}", solution.score);
+            write(&output, &solution.plaintext)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Index of coincidence over the ciphertext symbols.
+fn symbol_ic(symbols: &[u16]) -> f64 {
+    let max = symbols.iter().copied().max().unwrap_or(0) as usize;
+    let mut counts = vec![0u32; max + 1];
+    for &symbol in symbols {
+        counts[symbol as usize] += 1;
+    }
+    index_of_coincidence(&counts)
+}
+
+fn load_key(path: &str) -> tool_error::Result<HomophonicKey> {
+    HomophonicKey::parse(&read(path)?)
+        .map_err(ToolError::data)
+        .with_context(|| format!("Invalid key file '{}'", path))
+}
@@ -0,0 +1,187 @@
+//! Ciphertext-only attack on homophonic substitution.
+//!
+//! Simple frequency analysis fails because the homophones flatten the
+//! symbol distribution. The solver instead searches the space of
+//! symbol-to-letter assignments with simulated annealing, scoring every
+//! candidate plaintext with n-gram statistics. A penalty on the letter
+//! distribution keeps the search from mapping most symbols onto the few
+//! letters that dominate the n-gram table.
+
+use classical::{Alphabet, NgramModel};
+use rand::Rng;
+
+/// Starting temperature of the annealing schedule per occurrence of a
+/// symbol, in log10 score units; changing a frequent symbol changes the
+/// score more, so the temperature scales with the average symbol count
+const START_TEMPERATURE: f64 = 1.0;
+
+/// Weight of the chi-square letter distribution penalty
+const FREQUENCY_WEIGHT: f64 = 0.5;
+
+/// Result of a solver run.
+pub struct Solution {
+    /// Distinct ciphertext symbols in ascending order
+    pub symbols: Vec<u16>,
+    /// Recovered letter for every symbol
+    pub letters: Vec<char>,
+    pub plaintext: String,
+    pub score: f64,
+}
+
+pub struct Solver {
+    n: usize,
+    letters: Vec<char>,
+    expected: Vec<f64>,
+    /// Log probability of every n-gram, indexed in base `letters.len()`
+    table: Vec<f64>,
+}
+
+impl Solver {
+    /// Precomputes a dense n-gram table so a candidate is scored without
+    /// building strings. `expected` are the letter frequencies of the language.
+    pub fn new(model: &NgramModel, expected: &[f64]) -> Self {
+        let alphabet = Alphabet::latin();
+        let letters: Vec<char> = (0..alphabet.len()).map(|i| alphabet.symbol(i)).collect();
+        let n = model.n();
+
+        let size = letters.len().pow(n as u32);
+        let mut ngram = String::with_capacity(n);
+        let table = (0..size)
+            .map(|mut index| {
+                ngram.clear();
+                for _ in 0..n {
+                    ngram.insert(0, letters[index % letters.len()]);
+                    index /= letters.len();
+                }
+                model.score(&ngram, &alphabet)
+            })
+            .collect();
+
+        Solver { n, letters, expected: expected.to_vec(), table }
+    }
+
+    /// Scores a plaintext given as letter indices together with its letter counts.
+    fn score(&self, plaintext: &[usize], counts: &[u32]) -> f64 {
+        let ngrams: f64 = plaintext.windows(self.n)
+            .map(|window| self.table[window.iter().fold(0, |index, &letter| index * self.letters.len() + letter)])
+            .sum();
+        ngrams - FREQUENCY_WEIGHT * classical::chi_squared(counts, &self.expected, 0)
+    }
+
+    /// Runs `restarts` independent annealing runs of `iterations` steps each
+    /// and returns the best assignment found.
+    pub fn solve(&self, ciphertext: &[u16], iterations: usize, restarts: usize, rng: &mut impl Rng) -> Solution {
+        let mut symbols: Vec<u16> = ciphertext.to_vec();
+        symbols.sort_unstable();
+        symbols.dedup();
+        let positions: Vec<usize> = ciphertext.iter()
+            .map(|symbol| symbols.binary_search(symbol).expect("symbol is listed"))
+            .collect();
+        let mut occurrences = vec![0u32; symbols.len()];
+        for &symbol in &positions {
+            occurrences[symbol] += 1;
+        }
+
+        let start_temperature = START_TEMPERATURE * ciphertext.len() as f64 / symbols.len() as f64;
+        let mut best_mapping = vec![0; symbols.len()];
+        let mut best_score = f64::NEG_INFINITY;
+
+        for _ in 0..restarts.max(1) {
+            let mut mapping: Vec<usize> = (0..symbols.len()).map(|_| rng.gen_range(0..self.letters.len())).collect();
+            let mut plaintext: Vec<usize> = positions.iter().map(|&symbol| mapping[symbol]).collect();
+            let mut counts = vec![0u32; self.letters.len()];
+            for &letter in &plaintext {
+                counts[letter] += 1;
+            }
+            let mut score = self.score(&plaintext, &counts);
+
+            for step in 0..iterations {
+                let temperature = start_temperature * (1.0 - step as f64 / iterations as f64);
+                let symbol = rng.gen_range(0..symbols.len());
+                let old_letter = mapping[symbol];
+                let new_letter = rng.gen_range(0..self.letters.len());
+                if new_letter == old_letter {
+                    continue;
+                }
+
+                mapping[symbol] = new_letter;
+                counts[old_letter] -= occurrences[symbol];
+                counts[new_letter] += occurrences[symbol];
+                for (letter, &s) in plaintext.iter_mut().zip(&positions) {
+                    if s == symbol {
+                        *letter = new_letter;
+                    }
+                }
+
+                let new_score = self.score(&plaintext, &counts);
+                let accept = new_score >= score
+                    || (temperature > 0.0 && rng.gen::<f64>() < ((new_score - score) / temperature).exp());
+                if accept {
+                    score = new_score;
+                } else {
+                    mapping[symbol] = old_letter;
+                    counts[new_letter] -= occurrences[symbol];
+                    counts[old_letter] += occurrences[symbol];
+                    for (letter, &s) in plaintext.iter_mut().zip(&positions) {
+                        if s == symbol {
+                            *letter = old_letter;
+                        }
+                    }
+                }
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_mapping = mapping;
+            }
+        }
+
+        Solution {
+            plaintext: positions.iter().map(|&symbol| self.letters[best_mapping[symbol]]).collect(),
+            letters: best_mapping.iter().map(|&letter| self.letters[letter]).collect(),
+            symbols,
+            score: best_score,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key::HomophonicKey;
+    use classical::Language;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const TEXT: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+        it was the season of light, it was the season of darkness, it was the spring of hope, \
+        it was the winter of despair, we had everything before us, we had nothing before us";
+
+    #[test]
+    fn test_solver_recovers_plaintext() {
+        let alphabet = Alphabet::latin();
+        let plaintext = alphabet.normalize(TEXT);
+
+        // Trigram and letter statistics of the text itself stand in for a real corpus
+        let chars: Vec<char> = plaintext.chars().collect();
+        let letter_frequencies: Vec<f64> = classical::count_frequencies(&plaintext, &alphabet)
+            .iter()
+            .map(|&count| count as f64 / chars.len() as f64)
+            .collect();
+        let mut counts = std::collections::HashMap::new();
+        for window in chars.windows(3) {
+            *counts.entry(window.iter().collect::<String>()).or_insert(0.0) += 1.0;
+        }
+        let model = NgramModel::from_counts(counts.into_iter().collect()).unwrap();
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let frequencies = Language::En.table().expected(&alphabet);
+        let key = HomophonicKey::generate(&frequencies, 30, &mut rng).unwrap();
+        let ciphertext = key.encrypt(TEXT, &mut rng);
+
+        let solution = Solver::new(&model, &letter_frequencies).solve(&ciphertext, 20_000, 2, &mut rng);
+        let correct = solution.plaintext.chars().zip(plaintext.chars()).filter(|(a, b)| a == b).count();
+        assert!(correct * 10 >= plaintext.len() * 9, "only {} of {} letters correct", correct, plaintext.len());
+    }
+}
@@ -0,0 +1,221 @@
+//! AES-128 from `aes-128` in the modes of `cipher-modes`, EAX, key wrap and
+//! the adapters to RustCrypto's cipher traits included
+//!
+//! ECB and CBC are checked without padding on whole blocks and with PKCS #7
+//! on the full message, in both directions: each side decrypts what the
+//! other encrypted. RustCrypto also has the ANSI X9.23 and ISO/IEC 7816-4
+//! paddings and EAX, OpenSSL has neither.
+
+use aes_128::Aes128;
+use cipher_modes::{BlockCipher, CipherModes, KeyInit, Padding};
+use csprng::{Algorithm, Drbg};
+
+use crate::{CASES, random_bytes, random_message};
+
+const BLOCK: usize = 16;
+
+/// Random key, IV and message, and the message cut to whole blocks
+struct Case {
+    key: Vec<u8>,
+    cipher: Aes128,
+    iv: Vec<u8>,
+    message: Vec<u8>,
+    blocks: Vec<u8>,
+}
+
+/// The first case uses the IV FF…FF, so the CTR counter wraps around to zero
+fn cases(seed: &[u8]) -> Vec<Case> {
+    let mut rng = Drbg::from_seed(Algorithm::ChaCha20, seed);
+    (0..CASES)
+        .map(|i| {
+            let key = random_bytes(&mut rng, BLOCK);
+            let iv = if i == 0 { vec![0xff; BLOCK] } else { random_bytes(&mut rng, BLOCK) };
+            let message = random_message(&mut rng);
+            let blocks = message[..message.len() / BLOCK * BLOCK].to_vec();
+            let cipher = Aes128::new(&key).unwrap();
+            Case { key, cipher, iv, message, blocks }
+        })
+        .collect()
+}
+
+#[cfg(feature = "rustcrypto")]
+mod rustcrypto {
+    use super::*;
+    use aes::cipher::block_padding::{AnsiX923, Iso7816, NoPadding, Pkcs7};
+    use aes::cipher::{BlockDecryptMut, BlockEncryptMut, KeyInit, KeyIvInit, StreamCipher};
+
+    #[test]
+    fn test_ecb() {
+        for case in cases(b"ecb rustcrypto") {
+            let encryptor = || ecb::Encryptor::<aes::Aes128>::new_from_slice(&case.key).unwrap();
+            let expected = encryptor().encrypt_padded_vec_mut::<NoPadding>(&case.blocks);
+            assert_eq!(CipherModes::ecb_encrypt(&case.cipher, &case.blocks, Padding::None).unwrap(), expected);
+            let mut decrypted = expected.clone();
+            decrypted.chunks_mut(BLOCK).for_each(|block| case.cipher.decrypt_block(block));
+            assert_eq!(decrypted, case.blocks);
+
+            let expected = encryptor().encrypt_padded_vec_mut::<Pkcs7>(&case.message);
+            let ciphertext = CipherModes::ecb_encrypt(&case.cipher, &case.message, Padding::Pkcs7).unwrap();
+            assert_eq!(ciphertext, expected);
+            let decryptor = ecb::Decryptor::<aes::Aes128>::new_from_slice(&case.key).unwrap();
+            assert_eq!(decryptor.decrypt_padded_vec_mut::<Pkcs7>(&ciphertext).unwrap(), case.message);
+            assert_eq!(CipherModes::ecb_decrypt(&case.cipher, &expected, Padding::Pkcs7).unwrap(), case.message);
+        }
+    }
+
+    #[test]
+    fn test_cbc() {
+        for case in cases(b"cbc rustcrypto") {
+            let encryptor = || cbc::Encryptor::<aes::Aes128>::new_from_slices(&case.key, &case.iv).unwrap();
+            let expected = encryptor().encrypt_padded_vec_mut::<NoPadding>(&case.blocks);
+            assert_eq!(CipherModes::cbc_encrypt(&case.cipher, &case.iv, &case.blocks, Padding::None).unwrap(), expected);
+
+            let expected = encryptor().encrypt_padded_vec_mut::<Pkcs7>(&case.message);
+            let ciphertext = CipherModes::cbc_encrypt(&case.cipher, &case.iv, &case.message, Padding::Pkcs7).unwrap();
+            assert_eq!(ciphertext, expected);
+            let decryptor = cbc::Decryptor::<aes::Aes128>::new_from_slices(&case.key, &case.iv).unwrap();
+            assert_eq!(decryptor.decrypt_padded_vec_mut::<Pkcs7>(&ciphertext).unwrap(), case.message);
+            assert_eq!(CipherModes::cbc_decrypt(&case.cipher, &case.iv, &expected, Padding::Pkcs7).unwrap(), case.message);
+
+            let expected = encryptor().encrypt_padded_vec_mut::<AnsiX923>(&case.message);
+            assert_eq!(CipherModes::cbc_encrypt(&case.cipher, &case.iv, &case.message, Padding::AnsiX923).unwrap(), expected);
+            assert_eq!(CipherModes::cbc_decrypt(&case.cipher, &case.iv, &expected, Padding::AnsiX923).unwrap(), case.message);
+            let expected = encryptor().encrypt_padded_vec_mut::<Iso7816>(&case.message);
+            assert_eq!(CipherModes::cbc_encrypt(&case.cipher, &case.iv, &case.message, Padding::Iso7816).unwrap(), expected);
+            assert_eq!(CipherModes::cbc_decrypt(&case.cipher, &case.iv, &expected, Padding::Iso7816).unwrap(), case.message);
+        }
+    }
+
+    #[test]
+    fn test_ctr() {
+        for case in cases(b"ctr rustcrypto") {
+            let mut expected = case.message.clone();
+            ctr::Ctr128BE::<aes::Aes128>::new_from_slices(&case.key, &case.iv).unwrap().apply_keystream(&mut expected);
+            assert_eq!(CipherModes::ctr_encrypt(&case.cipher, &case.iv, &case.message).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_ctr_layouts() {
+        use cipher_modes::{CounterPosition, CtrLayout, Endianness};
+
+        for case in cases(b"ctr layouts rustcrypto") {
+            // Ctr32BE: nonce ‖ 32-bit big-endian counter, starting at the value in the IV
+            let mut expected = case.message.clone();
+            ctr::Ctr32BE::<aes::Aes128>::new_from_slices(&case.key, &case.iv).unwrap().apply_keystream(&mut expected);
+            let initial_counter = u32::from_be_bytes(case.iv[12..].try_into().unwrap()).into();
+            let layout = CtrLayout { initial_counter, ..CtrLayout::new(12, 4) };
+            assert_eq!(CipherModes::ctr_encrypt_with_layout(&case.cipher, &layout, &case.iv[..12], &case.message).unwrap(), expected);
+
+            // Ctr64LE: 64-bit little-endian counter ‖ nonce
+            let mut expected = case.message.clone();
+            ctr::Ctr64LE::<aes::Aes128>::new_from_slices(&case.key, &case.iv).unwrap().apply_keystream(&mut expected);
+            let layout = CtrLayout {
+                endianness: Endianness::Little,
+                position: CounterPosition::Start,
+                initial_counter: u64::from_le_bytes(case.iv[..8].try_into().unwrap()),
+                ..CtrLayout::new(8, 8)
+            };
+            assert_eq!(CipherModes::ctr_encrypt_with_layout(&case.cipher, &layout, &case.iv[8..], &case.message).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_eax() {
+        use eax::aead::{Aead, Payload};
+
+        // The IV doubles as nonce, the whole-block prefix of the message as associated data
+        for case in cases(b"eax rustcrypto") {
+            let eax = eax::Eax::<aes::Aes128>::new_from_slice(&case.key).unwrap();
+            let payload = Payload { msg: &case.message, aad: &case.blocks };
+            let expected = eax.encrypt(case.iv.as_slice().into(), payload).unwrap();
+            let ciphertext = cipher_modes::eax_encrypt(&case.cipher, &case.iv, &case.blocks, &case.message).unwrap();
+            assert_eq!(ciphertext, expected);
+            assert_eq!(cipher_modes::eax_decrypt(&case.cipher, &case.iv, &case.blocks, &expected).unwrap(), case.message);
+        }
+    }
+
+    #[test]
+    fn test_key_wrap() {
+        // Message and whole blocks are the keys to wrap, RFC 3394 needs at least 16 bytes
+        for case in cases(b"key wrap rustcrypto") {
+            let kek = aes_kw::KekAes128::try_from(case.key.as_slice()).unwrap();
+            if !case.blocks.is_empty() {
+                let expected = kek.wrap_vec(&case.blocks).unwrap();
+                assert_eq!(cipher_modes::key_wrap(&case.cipher, &case.blocks).unwrap(), expected);
+                assert_eq!(cipher_modes::key_unwrap(&case.cipher, &expected).unwrap(), case.blocks);
+            }
+            if !case.message.is_empty() {
+                let expected = kek.wrap_with_padding_vec(&case.message).unwrap();
+                assert_eq!(cipher_modes::key_wrap_padded(&case.cipher, &case.message).unwrap(), expected);
+                assert_eq!(cipher_modes::key_unwrap_padded(&case.cipher, &expected).unwrap(), case.message);
+            }
+        }
+    }
+
+    #[test]
+    fn test_adapters() {
+        // `aes::Aes128` in the modes of `cipher-modes`, and AES from `aes-128` in the `cbc` crate
+        use aes::cipher::InnerIvInit;
+        use aes::cipher::consts::U16;
+        use cipher_modes::rustcrypto::{FromRustCrypto, ToRustCrypto};
+
+        for case in cases(b"adapters rustcrypto") {
+            let expected = CipherModes::cbc_encrypt(&case.cipher, &case.iv, &case.message, Padding::Pkcs7).unwrap();
+            let reference = <FromRustCrypto<aes::Aes128> as cipher_modes::KeyInit>::new(&case.key).unwrap();
+            assert_eq!(CipherModes::cbc_encrypt(&reference, &case.iv, &case.message, Padding::Pkcs7).unwrap(), expected);
+
+            let adapted = ToRustCrypto::<_, U16>::new(case.cipher.clone()).unwrap();
+            let encryptor = cbc::Encryptor::inner_iv_slice_init(adapted, &case.iv).unwrap();
+            assert_eq!(encryptor.encrypt_padded_vec_mut::<Pkcs7>(&case.message), expected);
+        }
+    }
+}
+
+#[cfg(feature = "openssl")]
+mod openssl {
+    use super::*;
+    use crate::openssl::enc;
+
+    #[test]
+    fn test_ecb() {
+        for case in cases(b"ecb openssl") {
+            let expected = enc("-aes-128-ecb", &case.key, None, &case.blocks, false, false);
+            assert_eq!(CipherModes::ecb_encrypt(&case.cipher, &case.blocks, Padding::None).unwrap(), expected);
+
+            let ciphertext = CipherModes::ecb_encrypt(&case.cipher, &case.message, Padding::Pkcs7).unwrap();
+            assert_eq!(enc("-aes-128-ecb", &case.key, None, &ciphertext, true, true), case.message);
+            let expected = enc("-aes-128-ecb", &case.key, None, &case.message, false, true);
+            assert_eq!(CipherModes::ecb_decrypt(&case.cipher, &expected, Padding::Pkcs7).unwrap(), case.message);
+        }
+    }
+
+    #[test]
+    fn test_cbc() {
+        for case in cases(b"cbc openssl") {
+            let expected = enc("-aes-128-cbc", &case.key, Some(&case.iv), &case.blocks, false, false);
+            assert_eq!(CipherModes::cbc_encrypt(&case.cipher, &case.iv, &case.blocks, Padding::None).unwrap(), expected);
+
+            let ciphertext = CipherModes::cbc_encrypt(&case.cipher, &case.iv, &case.message, Padding::Pkcs7).unwrap();
+            assert_eq!(enc("-aes-128-cbc", &case.key, Some(&case.iv), &ciphertext, true, true), case.message);
+            let expected = enc("-aes-128-cbc", &case.key, Some(&case.iv), &case.message, false, true);
+            assert_eq!(CipherModes::cbc_decrypt(&case.cipher, &case.iv, &expected, Padding::Pkcs7).unwrap(), case.message);
+        }
+    }
+
+    #[test]
+    fn test_ofb() {
+        for case in cases(b"ofb openssl") {
+            let expected = enc("-aes-128-ofb", &case.key, Some(&case.iv), &case.message, false, false);
+            assert_eq!(CipherModes::ofb_encrypt(&case.cipher, &case.iv, &case.message).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_ctr() {
+        for case in cases(b"ctr openssl") {
+            let expected = enc("-aes-128-ctr", &case.key, Some(&case.iv), &case.message, false, false);
+            assert_eq!(CipherModes::ctr_encrypt(&case.cipher, &case.iv, &case.message).unwrap(), expected);
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! Blowfish from `cipher_modes::cipher` in ECB and CBC, with keys of random length
+//!
+//! OpenSSL 3 only offers Blowfish with the legacy provider, so only
+//! RustCrypto's `blowfish` crate serves as reference.
+
+use aes::cipher::block_padding::{NoPadding, Pkcs7};
+use aes::cipher::{BlockDecryptMut, BlockEncryptMut, KeyInit as _, KeyIvInit};
+use cipher_modes::cipher::blowfish::{BLOCK_SIZE, MAX_KEY_SIZE, MIN_KEY_SIZE};
+use cipher_modes::{Blowfish, CipherModes, KeyInit, Padding};
+use csprng::{Algorithm, Drbg};
+use rand::Rng;
+
+use crate::{CASES, random_bytes, random_message};
+
+#[test]
+fn test_ecb_and_cbc() {
+    let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"blowfish rustcrypto");
+    for _ in 0..CASES {
+        let length = rng.gen_range(MIN_KEY_SIZE..=MAX_KEY_SIZE);
+        let key = random_bytes(&mut rng, length);
+        let iv = random_bytes(&mut rng, BLOCK_SIZE);
+        let message = random_message(&mut rng);
+        let blocks = &message[..message.len() / BLOCK_SIZE * BLOCK_SIZE];
+        let cipher = Blowfish::new(&key).unwrap();
+
+        let expected = ecb::Encryptor::<blowfish::Blowfish>::new_from_slice(&key).unwrap().encrypt_padded_vec_mut::<NoPadding>(blocks);
+        assert_eq!(CipherModes::ecb_encrypt(&cipher, blocks, Padding::None).unwrap(), expected);
+
+        let expected = cbc::Encryptor::<blowfish::Blowfish>::new_from_slices(&key, &iv).unwrap().encrypt_padded_vec_mut::<Pkcs7>(&message);
+        let ciphertext = CipherModes::cbc_encrypt(&cipher, &iv, &message, Padding::Pkcs7).unwrap();
+        assert_eq!(ciphertext, expected);
+        let decryptor = cbc::Decryptor::<blowfish::Blowfish>::new_from_slices(&key, &iv).unwrap();
+        assert_eq!(decryptor.decrypt_padded_vec_mut::<Pkcs7>(&ciphertext).unwrap(), message);
+    }
+}
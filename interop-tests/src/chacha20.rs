@@ -0,0 +1,51 @@
+//! ChaCha20 from `cipher_modes::cipher`, from the start and after seeking,
+//! and ChaCha20-Poly1305 on top of it
+
+use ::chacha20::cipher::{KeyIvInit, StreamCipher as _, StreamCipherSeek};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use cipher_modes::cipher::chacha20::{KEY_SIZE, NONCE_SIZE};
+use cipher_modes::{ChaCha20, StreamCipher, chacha20_poly1305_decrypt, chacha20_poly1305_encrypt};
+use csprng::{Algorithm, Drbg};
+use rand::Rng;
+
+use crate::{CASES, random_bytes, random_message};
+
+#[test]
+fn test_chacha20_rustcrypto() {
+    let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"chacha20 rustcrypto");
+    for _ in 0..CASES {
+        let key = random_bytes(&mut rng, KEY_SIZE);
+        let nonce = random_bytes(&mut rng, NONCE_SIZE);
+        let message = random_message(&mut rng);
+        let position = rng.gen_range(0..1000);
+
+        let mut reference = ::chacha20::ChaCha20::new(key.as_slice().into(), nonce.as_slice().into());
+        reference.seek(position);
+        let mut expected = message.clone();
+        reference.apply_keystream(&mut expected);
+
+        let mut cipher = ChaCha20::new(&key, &nonce).unwrap();
+        cipher.seek(position);
+        let mut output = message.clone();
+        cipher.apply_keystream(&mut output);
+        assert_eq!(output, expected);
+    }
+}
+
+#[test]
+fn test_chacha20_poly1305_rustcrypto() {
+    let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"chacha20-poly1305 rustcrypto");
+    for _ in 0..CASES {
+        let key = random_bytes(&mut rng, KEY_SIZE);
+        let nonce = random_bytes(&mut rng, NONCE_SIZE);
+        let aad = random_message(&mut rng);
+        let message = random_message(&mut rng);
+
+        let reference = chacha20poly1305::ChaCha20Poly1305::new(key.as_slice().into());
+        let expected = reference.encrypt(nonce.as_slice().into(), Payload { msg: &message, aad: &aad }).unwrap();
+
+        let ciphertext = chacha20_poly1305_encrypt(&key, &nonce, &aad, &message).unwrap();
+        assert_eq!(ciphertext, expected);
+        assert_eq!(chacha20_poly1305_decrypt(&key, &nonce, &aad, &expected).unwrap(), message);
+    }
+}
@@ -0,0 +1,50 @@
+//! AES-CMAC from `cipher_modes::mac`, in one call and fed in random pieces
+
+use aes_128::Aes128;
+use cipher_modes::{Cmac, KeyInit};
+use csprng::{Algorithm, Drbg};
+use rand::Rng;
+
+use crate::{CASES, random_bytes, random_message};
+
+/// Random key and message, and the tag of the message fed in random pieces
+fn cases(seed: &[u8]) -> Vec<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+    let mut rng = Drbg::from_seed(Algorithm::ChaCha20, seed);
+    (0..CASES)
+        .map(|_| {
+            let key = random_bytes(&mut rng, 16);
+            let message = random_message(&mut rng);
+            let mut mac = Cmac::new(Aes128::new(&key).unwrap()).unwrap();
+            let mut rest = message.as_slice();
+            while !rest.is_empty() {
+                let (piece, tail) = rest.split_at(rng.gen_range(1..=rest.len()));
+                mac.update(piece);
+                rest = tail;
+            }
+            let tag = mac.finalize();
+            (key, message, tag)
+        })
+        .collect()
+}
+
+#[cfg(feature = "rustcrypto")]
+#[test]
+fn test_cmac_rustcrypto() {
+    use cmac::Mac;
+
+    for (key, message, tag) in cases(b"cmac rustcrypto") {
+        let mut reference = <cmac::Cmac<aes::Aes128> as Mac>::new_from_slice(&key).unwrap();
+        reference.update(&message);
+        assert_eq!(tag, reference.finalize().into_bytes().to_vec());
+    }
+}
+
+#[cfg(feature = "openssl")]
+#[test]
+fn test_cmac_openssl() {
+    for (key, message, tag) in cases(b"cmac openssl") {
+        let key = format!("hexkey:{}", codec::hex::encode(&key));
+        let expected = crate::openssl::run(&["mac", "-cipher", "AES-128-CBC", "-macopt", &key, "-binary", "CMAC"], &message);
+        assert_eq!(tag, expected);
+    }
+}
@@ -0,0 +1,62 @@
+//! HMAC from `cipher_modes::mac` with SHA-256, SHA-512 and SHA3-224, fed in
+//! random pieces, with keys shorter and longer than a block
+
+use cipher_modes::hash::{Sha3_224, Sha256, Sha512};
+use cipher_modes::{Hash, Hmac};
+use csprng::{Algorithm, Drbg};
+use rand::Rng;
+
+use crate::{CASES, random_bytes, random_message};
+
+/// Random key and message, and the tag of the message fed in random pieces
+fn cases<H: Hash>(seed: &[u8]) -> Vec<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+    let mut rng = Drbg::from_seed(Algorithm::ChaCha20, seed);
+    (0..CASES)
+        .map(|_| {
+            let key_length = rng.gen_range(0..=2 * H::BLOCK_SIZE);
+            let key = random_bytes(&mut rng, key_length);
+            let message = random_message(&mut rng);
+            let mut mac = Hmac::<H>::new(&key);
+            let mut rest = message.as_slice();
+            while !rest.is_empty() {
+                let (piece, tail) = rest.split_at(rng.gen_range(1..=rest.len()));
+                mac.update(piece);
+                rest = tail;
+            }
+            let tag = mac.finalize();
+            (key, message, tag)
+        })
+        .collect()
+}
+
+#[cfg(feature = "rustcrypto")]
+#[test]
+fn test_hmac_rustcrypto() {
+    use hmac::{Mac, SimpleHmac};
+
+    fn check<H: Hash, R: sha2::Digest + hmac::digest::core_api::BlockSizeUser>(seed: &[u8]) {
+        for (key, message, tag) in cases::<H>(seed) {
+            let mut reference = SimpleHmac::<R>::new_from_slice(&key).unwrap();
+            reference.update(&message);
+            assert_eq!(tag, reference.finalize().into_bytes().to_vec());
+        }
+    }
+    check::<Sha256, sha2::Sha256>(b"hmac-sha256 rustcrypto");
+    check::<Sha512, sha2::Sha512>(b"hmac-sha512 rustcrypto");
+    check::<Sha3_224, sha3_reference::Sha3_224>(b"hmac-sha3-224 rustcrypto");
+}
+
+#[cfg(feature = "openssl")]
+#[test]
+fn test_hmac_openssl() {
+    fn check<H: Hash>(seed: &[u8], digest: &str) {
+        for (key, message, tag) in cases::<H>(seed) {
+            let key = format!("hexkey:{}", codec::hex::encode(&key));
+            let expected = crate::openssl::run(&["mac", "-digest", digest, "-macopt", &key, "-binary", "HMAC"], &message);
+            assert_eq!(tag, expected);
+        }
+    }
+    check::<Sha256>(b"hmac-sha256 openssl", "SHA256");
+    check::<Sha512>(b"hmac-sha512 openssl", "SHA512");
+    check::<Sha3_224>(b"hmac-sha3-224 openssl", "SHA3-224");
+}
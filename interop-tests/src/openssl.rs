@@ -0,0 +1,63 @@
+//! Runs the `openssl` command-line tool.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Runs `openssl` with `args`, feeds `input` on stdin and returns stdout
+///
+/// Panics if the tool is missing or fails, with its error output: in a test
+/// either means the comparison could not be made.
+pub fn run(args: &[&str], input: &[u8]) -> Vec<u8> {
+    let mut child = Command::new("openssl")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("openssl must be on the PATH for the `openssl` feature");
+    // Write from a thread, large inputs would otherwise fill the pipes in both directions
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_vec();
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+    let output = child.wait_with_output().expect("openssl runs");
+    writer.join().expect("writer thread").expect("openssl reads its input");
+    assert!(output.status.success(), "openssl {}: {}", args.join(" "), String::from_utf8_lossy(&output.stderr));
+    output.stdout
+}
+
+/// `openssl enc` with a hex key and IV, without salt or key derivation
+pub fn enc(cipher: &str, key: &[u8], iv: Option<&[u8]>, data: &[u8], decrypt: bool, pad: bool) -> Vec<u8> {
+    let key = codec::hex::encode(key);
+    let iv = iv.map(codec::hex::encode);
+    let mut args = vec!["enc", if decrypt { "-d" } else { "-e" }, cipher, "-K", key.as_str()];
+    if let Some(iv) = &iv {
+        args.extend(["-iv", iv.as_str()]);
+    }
+    if !pad {
+        args.push("-nopad");
+    }
+    run(&args, data)
+}
+
+/// Directory for files the tool needs, removed when dropped
+pub struct TempDir(pub PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("interop-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&path).expect("temporary directory");
+        TempDir(path)
+    }
+
+    /// Path of `name` inside the directory, as the tool expects it
+    pub fn file(&self, name: &str) -> String {
+        self.0.join(name).to_str().expect("temporary paths are UTF-8").to_string()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
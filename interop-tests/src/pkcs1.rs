@@ -0,0 +1,46 @@
+//! RSA signatures with PKCS #1 v1.5 and SHA-256 from `rsa::pkcs1`
+//!
+//! The encoding is deterministic, so a signature from the same key must be
+//! identical byte for byte. OpenSSL generates the key; its PKCS #1 DER
+//! form is read with `asn1`.
+
+use asn1::Reader;
+use csprng::{Algorithm, Drbg};
+use key_formats::{RsaPrivateKey, RsaPublicKey};
+use secret::Secret;
+
+use crate::openssl::{TempDir, run};
+use crate::{CASES, random_message};
+
+const KEY_BITS: &str = "rsa_keygen_bits:2048";
+
+/// `RSAPrivateKey ::= SEQUENCE { version, n, e, d, p, q, … }` (RFC 8017, A.1.2)
+fn read_key(der: &[u8]) -> (RsaPrivateKey, RsaPublicKey) {
+    let mut key = Reader::new(der).sequence().unwrap();
+    let _version = key.integer().unwrap();
+    let (n, e, d) = (key.integer().unwrap(), key.integer().unwrap(), key.integer().unwrap());
+    (RsaPrivateKey { d: Secret::new(d), n: n.clone() }, RsaPublicKey { e, n })
+}
+
+#[test]
+fn test_sign_and_verify() {
+    let dir = TempDir::new("pkcs1");
+    let (key, public, signature) = (dir.file("key.pem"), dir.file("public.pem"), dir.file("signature"));
+    run(&["genpkey", "-algorithm", "RSA", "-pkeyopt", KEY_BITS, "-out", &key], &[]);
+    run(&["pkey", "-in", &key, "-pubout", "-out", &public], &[]);
+    let (private_key, public_key) = read_key(&run(&["rsa", "-in", &key, "-traditional", "-outform", "DER"], &[]));
+
+    let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"pkcs1 openssl");
+    for _ in 0..CASES {
+        let message = random_message(&mut rng);
+        let expected = run(&["dgst", "-sha256", "-sign", &key], &message);
+        let ours = rsa::pkcs1::sign(&message, &private_key).unwrap();
+        assert_eq!(ours, expected);
+
+        // OpenSSL accepts ours (run panics on "Verification failure"), we accept OpenSSL's
+        std::fs::write(&signature, &ours).unwrap();
+        run(&["dgst", "-sha256", "-verify", &public, "-signature", &signature], &message);
+        assert!(rsa::pkcs1::verify(&message, &expected, &public_key));
+        assert!(!rsa::pkcs1::verify(&[&message[..], b"!"].concat(), &expected, &public_key));
+    }
+}
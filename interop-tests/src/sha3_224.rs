@@ -0,0 +1,48 @@
+//! SHA3-224 from `sha3`, in one call and fed in random pieces
+//!
+//! Messages up to [`MAX_LENGTH`](crate::MAX_LENGTH) bytes span two rate
+//! blocks of 144 bytes, so the padding lands in every position of a block.
+
+use csprng::{Algorithm, Drbg};
+use rand::Rng;
+use sha3::{Sha3_224, sha3_224};
+
+use crate::{CASES, random_message};
+
+/// Hashes `message` with `Sha3_224::update` in pieces of random length
+fn hash_in_pieces(message: &[u8], rng: &mut impl Rng) -> Vec<u8> {
+    let mut hasher = Sha3_224::new();
+    let mut rest = message;
+    while !rest.is_empty() {
+        let (piece, tail) = rest.split_at(rng.gen_range(1..=rest.len()));
+        hasher.update(piece);
+        rest = tail;
+    }
+    hasher.finalize()
+}
+
+#[cfg(feature = "rustcrypto")]
+#[test]
+fn test_sha3_224_rustcrypto() {
+    use sha3_reference::Digest;
+
+    let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"sha3 rustcrypto");
+    for _ in 0..CASES {
+        let message = random_message(&mut rng);
+        let expected = sha3_reference::Sha3_224::digest(&message).to_vec();
+        assert_eq!(sha3_224(&message), expected);
+        assert_eq!(hash_in_pieces(&message, &mut rng), expected);
+    }
+}
+
+#[cfg(feature = "openssl")]
+#[test]
+fn test_sha3_224_openssl() {
+    let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"sha3 openssl");
+    for _ in 0..CASES {
+        let message = random_message(&mut rng);
+        let expected = crate::openssl::run(&["dgst", "-sha3-224", "-binary"], &message);
+        assert_eq!(sha3_224(&message), expected);
+        assert_eq!(hash_in_pieces(&message, &mut rng), expected);
+    }
+}
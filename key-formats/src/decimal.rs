@@ -0,0 +1,32 @@
+//! serde-Darstellung von `BigUint` als Dezimalstring
+//!
+//! Für Felder mit `#[serde(with = "key_formats::decimal")]`, auch für
+//! geheime Werte vom Typ `Secret<BigUint>`. Beim Lesen werden auch kleine
+//! Zahlen ohne Anführungszeichen akzeptiert, damit sich Testschlüssel bequem
+//! von Hand schreiben lassen.
+
+use std::borrow::Borrow;
+
+use num_bigint::BigUint;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::Serializer;
+
+pub fn serialize<S: Serializer, T: Borrow<BigUint>>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value.borrow())
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: From<BigUint>>(deserializer: D) -> Result<T, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Decimal {
+        Text(String),
+        Number(u64),
+    }
+
+    let value = match Decimal::deserialize(deserializer)? {
+        Decimal::Text(text) => text.trim().parse()
+            .map_err(|_| de::Error::custom(format!("ungültige Dezimalzahl '{}'", text)))?,
+        Decimal::Number(number) => BigUint::from(number),
+    };
+    Ok(value.into())
+}
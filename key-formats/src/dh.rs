@@ -0,0 +1,31 @@
+//! Diffie-Hellman-Parameter von `dh_params`, gelesen von `dh_exchange`
+
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+
+use crate::{decimal, key_file};
+
+/// Primzahl p und Generator g, Zeilenformat: p, g
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DhParameters {
+    #[serde(with = "decimal")]
+    pub p: BigUint,
+    #[serde(with = "decimal")]
+    pub g: BigUint,
+}
+
+key_file!(DhParameters { p, g });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Format, KeyFile};
+
+    #[test]
+    fn test_roundtrip() {
+        let params = DhParameters { p: BigUint::from(23u32), g: BigUint::from(5u32) };
+        for format in [Format::Lines, Format::Json, Format::Toml] {
+            assert_eq!(DhParameters::parse(&params.encode(format).unwrap()).unwrap(), params, "{}", format);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! DSA-Schlüssel von `dsa-keygen` und Signaturen von `dsa_sign`
+//!
+//! Beide Schlüssel enthalten die Domainparameter p, q, g. In JSON und TOML
+//! stehen sie als gewöhnliche Felder neben y bzw. x.
+
+use num_bigint::BigUint;
+use secret::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{KeyFile, decimal, key_file};
+
+/// Domainparameter: Primzahlen p und q mit q | p - 1 und Generator g der Ordnung q
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DsaParameters {
+    #[serde(with = "decimal")]
+    pub p: BigUint,
+    #[serde(with = "decimal")]
+    pub q: BigUint,
+    #[serde(with = "decimal")]
+    pub g: BigUint,
+}
+
+/// Öffentlicher Schlüssel y = g^x mod p, Zeilenformat: p, q, g, y
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DsaPublicKey {
+    #[serde(flatten)]
+    pub params: DsaParameters,
+    #[serde(with = "decimal")]
+    pub y: BigUint,
+}
+
+/// Privater Schlüssel x, Zeilenformat: p, q, g, x; x wird beim Freigeben überschrieben
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DsaPrivateKey {
+    #[serde(flatten)]
+    pub params: DsaParameters,
+    #[serde(with = "decimal")]
+    pub x: Secret<BigUint>,
+}
+
+/// Signatur (r, s), Zeilenformat: r, s
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DsaSignature {
+    #[serde(with = "decimal")]
+    pub r: BigUint,
+    #[serde(with = "decimal")]
+    pub s: BigUint,
+}
+
+key_file!(DsaParameters { p, q, g });
+key_file!(DsaSignature { r, s });
+
+impl DsaSignature {
+    /// DER-Kodierung `SEQUENCE { r INTEGER, s INTEGER }` wie in X.509 (RFC 3279)
+    pub fn to_der(&self) -> Vec<u8> {
+        asn1::sequence(&[asn1::integer(&self.r), asn1::integer(&self.s)])
+    }
+
+    pub fn from_der(bytes: &[u8]) -> Result<Self, asn1::DerError> {
+        let mut outer = asn1::Reader::new(bytes);
+        let mut numbers = outer.sequence()?;
+        outer.finish()?;
+        let signature = DsaSignature { r: numbers.integer()?, s: numbers.integer()? };
+        numbers.finish()?;
+        Ok(signature)
+    }
+}
+
+impl KeyFile for DsaPublicKey {
+    const FIELDS: &'static [&'static str] = &["p", "q", "g", "y"];
+
+    fn to_values(&self) -> Vec<&BigUint> {
+        vec![&self.params.p, &self.params.q, &self.params.g, &self.y]
+    }
+
+    fn from_values(mut values: Vec<BigUint>) -> Self {
+        let y = values.pop().expect("Anzahl der Zeilen ist geprüft");
+        DsaPublicKey { params: DsaParameters::from_values(values), y }
+    }
+}
+
+impl KeyFile for DsaPrivateKey {
+    const FIELDS: &'static [&'static str] = &["p", "q", "g", "x"];
+
+    fn to_values(&self) -> Vec<&BigUint> {
+        vec![&self.params.p, &self.params.q, &self.params.g, &*self.x]
+    }
+
+    fn from_values(mut values: Vec<BigUint>) -> Self {
+        let x = values.pop().expect("Anzahl der Zeilen ist geprüft");
+        DsaPrivateKey { params: DsaParameters::from_values(values), x: x.into() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Format;
+
+    fn key() -> DsaPrivateKey {
+        let params = DsaParameters { p: BigUint::from(23u32), q: BigUint::from(11u32), g: BigUint::from(4u32) };
+        DsaPrivateKey { params, x: BigUint::from(7u32).into() }
+    }
+
+    #[test]
+    fn test_flat_fields() {
+        assert_eq!(key().encode(Format::Lines).unwrap(), "23\n11\n4\n7\n");
+        assert_eq!(key().encode(Format::Toml).unwrap(), "p = \"23\"\nq = \"11\"\ng = \"4\"\nx = \"7\"\n");
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for format in [Format::Lines, Format::Json, Format::Toml] {
+            assert_eq!(DsaPrivateKey::parse(&key().encode(format).unwrap()).unwrap(), key(), "{}", format);
+        }
+
+        let signature = DsaSignature { r: BigUint::from(3u32), s: BigUint::from(5u32) };
+        assert_eq!(DsaSignature::parse("3\n5").unwrap(), signature);
+    }
+
+    #[test]
+    fn test_signature_der() {
+        let signature = DsaSignature { r: BigUint::from(3u32), s: BigUint::from(200u32) };
+        let der = signature.to_der();
+        assert_eq!(der, [0x30, 0x07, 0x02, 0x01, 0x03, 0x02, 0x02, 0x00, 0xc8]);
+        assert_eq!(DsaSignature::from_der(&der).unwrap(), signature);
+        assert!(DsaSignature::from_der(&der[..der.len() - 1]).is_err());
+        assert!(DsaSignature::from_der(&[der.as_slice(), &[0]].concat()).is_err());
+        assert!(DsaSignature::from_der(&[0x30, 0x03, 0x02, 0x01, 0x03]).is_err());
+    }
+
+    #[test]
+    fn test_wrong_key_type() {
+        let public = key().encode(Format::Json).unwrap().replace("\"x\"", "\"y\"");
+        assert!(DsaPrivateKey::parse(&public).is_err());
+        assert_eq!(DsaPublicKey::parse(&public).unwrap().y, BigUint::from(7u32));
+    }
+}
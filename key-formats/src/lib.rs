@@ -0,0 +1,237 @@
+//! Schlüssel- und Parameterdateien für RSA, DSA und Diffie-Hellman
+//!
+//! Alle Werkzeuge lesen und schreiben Schlüssel über die Typen dieses Crates.
+//! Jede Datei kann in einem von drei Formaten vorliegen:
+//!
+//! - `zeilen`: eine Dezimalzahl pro Zeile in fester Reihenfolge, das
+//!   ursprüngliche Format der Werkzeuge (z.B. RSA: Exponent, Modulus)
+//! - `json`: ein Objekt mit den Feldnamen als Schlüssel
+//! - `toml`: eine Tabelle mit den Feldnamen als Schlüssel
+//!
+//! ```text
+//! 65537              {                       e = "65537"
+//! 3233                 "e": "65537",         n = "3233"
+//!                      "n": "3233"
+//!                    }
+//! ```
+//!
+//! Große Zahlen stehen in JSON und TOML als Dezimalstrings, weil beide
+//! Formate keine beliebig großen Ganzzahlen kennen. Beim Lesen wird das
+//! Format am Inhalt erkannt, sodass vorhandene Zeilendateien weiter
+//! funktionieren und kein Werkzeug ein Formatargument zum Lesen braucht.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use num_bigint::BigUint;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+pub mod decimal;
+mod dh;
+mod dsa;
+mod rsa;
+
+pub use dh::DhParameters;
+pub use dsa::{DsaParameters, DsaPrivateKey, DsaPublicKey, DsaSignature};
+pub use rsa::{RsaPrimes, RsaPrivateKey, RsaPublicKey};
+
+/// Dateiformat einer Schlüsseldatei
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Eine Dezimalzahl pro Zeile
+    Lines,
+    Json,
+    Toml,
+}
+
+impl Format {
+    /// Erkennt das Format am ersten Zeichen, das kein Leerzeichen ist
+    pub fn detect(text: &str) -> Format {
+        match text.trim_start().chars().next() {
+            Some('{') => Format::Json,
+            Some(c) if c.is_ascii_digit() => Format::Lines,
+            None => Format::Lines,
+            Some(_) => Format::Toml,
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_lowercase().as_str() {
+            "zeilen" | "lines" => Ok(Format::Lines),
+            "json" => Ok(Format::Json),
+            "toml" => Ok(Format::Toml),
+            _ => Err(format!("Unbekanntes Format '{}' (erlaubt: zeilen, json, toml)", name)),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Format::Lines => write!(f, "zeilen"),
+            Format::Json => write!(f, "json"),
+            Format::Toml => write!(f, "toml"),
+        }
+    }
+}
+
+/// Fehler beim Lesen oder Schreiben einer Schlüsseldatei
+#[derive(Debug)]
+pub enum KeyFileError {
+    Io { path: PathBuf, source: std::io::Error },
+    Json(serde_json::Error),
+    Toml(String),
+    Lines(String),
+}
+
+impl fmt::Display for KeyFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyFileError::Io { path, source } => write!(f, "Datei '{}': {}", path.display(), source),
+            KeyFileError::Json(e) => write!(f, "Ungültiges JSON: {}", e),
+            KeyFileError::Toml(e) => write!(f, "Ungültiges TOML: {}", e),
+            KeyFileError::Lines(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for KeyFileError {}
+
+/// Schlüssel oder Parameter, die als Datei gespeichert werden
+///
+/// JSON und TOML kommen aus den serde-Implementierungen, das Zeilenformat
+/// aus [`KeyFile::FIELDS`] und den beiden Umwandlungen in eine Zahlenliste.
+pub trait KeyFile: Serialize + DeserializeOwned {
+    /// Feldnamen in der Reihenfolge des Zeilenformats
+    const FIELDS: &'static [&'static str];
+
+    fn to_values(&self) -> Vec<&BigUint>;
+
+    /// Baut den Wert aus genau `FIELDS.len()` Zahlen
+    fn from_values(values: Vec<BigUint>) -> Self;
+
+    fn encode(&self, format: Format) -> Result<String, KeyFileError> {
+        match format {
+            Format::Lines => Ok(self.to_values().iter().map(|value| format!("{}\n", value)).collect()),
+            Format::Json => serde_json::to_string_pretty(self)
+                .map(|json| json + "\n")
+                .map_err(KeyFileError::Json),
+            Format::Toml => toml::to_string(self).map_err(|e| KeyFileError::Toml(e.to_string())),
+        }
+    }
+
+    fn decode(text: &str, format: Format) -> Result<Self, KeyFileError>
+    where
+        Self: Sized,
+    {
+        match format {
+            Format::Lines => parse_lines(text, Self::FIELDS).map(Self::from_values),
+            Format::Json => serde_json::from_str(text).map_err(KeyFileError::Json),
+            Format::Toml => toml::from_str(text).map_err(|e| KeyFileError::Toml(e.to_string())),
+        }
+    }
+
+    /// Liest den Wert in dem Format, das [`Format::detect`] erkennt
+    fn parse(text: &str) -> Result<Self, KeyFileError>
+    where
+        Self: Sized,
+    {
+        Self::decode(text, Format::detect(text))
+    }
+
+    fn read(path: impl AsRef<Path>) -> Result<Self, KeyFileError>
+    where
+        Self: Sized,
+    {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|source| KeyFileError::Io { path: path.to_path_buf(), source })?;
+        Self::parse(&text)
+    }
+
+    fn write(&self, path: impl AsRef<Path>, format: Format) -> Result<(), KeyFileError> {
+        let path = path.as_ref();
+        fs::write(path, self.encode(format)?)
+            .map_err(|source| KeyFileError::Io { path: path.to_path_buf(), source })
+    }
+}
+
+/// Liest eine Dezimalzahl pro nichtleerer Zeile
+fn parse_lines(text: &str, fields: &[&str]) -> Result<Vec<BigUint>, KeyFileError> {
+    let lines: Vec<&str> = text.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    if lines.len() != fields.len() {
+        return Err(KeyFileError::Lines(format!(
+            "Erwartet {} Zeilen ({}), gefunden: {}",
+            fields.len(), fields.join(", "), lines.len()
+        )));
+    }
+
+    lines.iter().zip(fields).enumerate()
+        .map(|(index, (line, field))| {
+            line.parse().map_err(|_| KeyFileError::Lines(format!(
+                "Zeile {}: ungültige Dezimalzahl für {}: '{}'", index + 1, field, line
+            )))
+        })
+        .collect()
+}
+
+/// Implementiert [`KeyFile`] für eine Struktur aus `BigUint`-Feldern
+macro_rules! key_file {
+    ($name:ident { $($field:ident),+ }) => {
+        impl $crate::KeyFile for $name {
+            const FIELDS: &'static [&'static str] = &[$(stringify!($field)),+];
+
+            fn to_values(&self) -> Vec<&num_bigint::BigUint> {
+                vec![$(::std::borrow::Borrow::borrow(&self.$field)),+]
+            }
+
+            fn from_values(values: Vec<num_bigint::BigUint>) -> Self {
+                let mut values = values.into_iter();
+                $name { $($field: values.next().expect("Anzahl der Zeilen ist geprüft").into()),+ }
+            }
+        }
+    };
+}
+
+pub(crate) use key_file;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(Format::detect("65537\n3233\n"), Format::Lines);
+        assert_eq!(Format::detect("  {\"e\": \"3\"}"), Format::Json);
+        assert_eq!(Format::detect("e = \"3\"\n"), Format::Toml);
+        assert_eq!(Format::detect("# Kommentar\ne = \"3\"\n"), Format::Toml);
+    }
+
+    #[test]
+    fn test_format_names() {
+        for format in [Format::Lines, Format::Json, Format::Toml] {
+            assert_eq!(format.to_string().parse(), Ok(format));
+        }
+        assert_eq!("JSON".parse(), Ok(Format::Json));
+        assert!("yaml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn test_parse_lines_errors() {
+        let error = parse_lines("1\n", &["e", "n"]).unwrap_err();
+        assert_eq!(error.to_string(), "Erwartet 2 Zeilen (e, n), gefunden: 1");
+
+        let error = parse_lines("1\nx\n", &["e", "n"]).unwrap_err();
+        assert_eq!(error.to_string(), "Zeile 2: ungültige Dezimalzahl für n: 'x'");
+
+        // Leerzeilen und Leerzeichen am Rand werden ignoriert
+        assert_eq!(parse_lines("\n 3 \n\n33\n", &["e", "n"]).unwrap(), vec![BigUint::from(3u32), BigUint::from(33u32)]);
+    }
+}
@@ -0,0 +1,72 @@
+//! RSA-Schlüssel von `rsa-keygen`, gelesen von `rsa`
+
+use num_bigint::BigUint;
+use secret::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{decimal, key_file};
+
+/// Öffentlicher Schlüssel, Zeilenformat: e, n
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RsaPublicKey {
+    #[serde(with = "decimal")]
+    pub e: BigUint,
+    #[serde(with = "decimal")]
+    pub n: BigUint,
+}
+
+/// Privater Schlüssel, Zeilenformat: d, n; d wird beim Freigeben überschrieben
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RsaPrivateKey {
+    #[serde(with = "decimal")]
+    pub d: Secret<BigUint>,
+    #[serde(with = "decimal")]
+    pub n: BigUint,
+}
+
+/// Die beiden geheimen Primfaktoren von n, Zeilenformat: p, q
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RsaPrimes {
+    #[serde(with = "decimal")]
+    pub p: Secret<BigUint>,
+    #[serde(with = "decimal")]
+    pub q: Secret<BigUint>,
+}
+
+key_file!(RsaPublicKey { e, n });
+key_file!(RsaPrivateKey { d, n });
+key_file!(RsaPrimes { p, q });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Format, KeyFile};
+
+    fn key() -> RsaPublicKey {
+        RsaPublicKey { e: BigUint::from(65537u32), n: "3233".parse().unwrap() }
+    }
+
+    #[test]
+    fn test_encodings() {
+        assert_eq!(key().encode(Format::Lines).unwrap(), "65537\n3233\n");
+        assert_eq!(key().encode(Format::Json).unwrap(), "{\n  \"e\": \"65537\",\n  \"n\": \"3233\"\n}\n");
+        assert_eq!(key().encode(Format::Toml).unwrap(), "e = \"65537\"\nn = \"3233\"\n");
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let big = RsaPrivateKey { d: Secret::new((BigUint::from(1u32) << 2048) - 1u32), n: (BigUint::from(1u32) << 2047) + 1u32 };
+        for format in [Format::Lines, Format::Json, Format::Toml] {
+            assert_eq!(RsaPrivateKey::parse(&big.encode(format).unwrap()).unwrap(), big, "{}", format);
+        }
+    }
+
+    #[test]
+    fn test_parse_handwritten() {
+        assert_eq!(RsaPublicKey::parse("{\"e\": 65537, \"n\": \"3233\"}").unwrap(), key());
+        // Dateien von rsa-keygen enden ohne Zeilenumbruch
+        assert_eq!(RsaPublicKey::parse("65537\n3233").unwrap(), key());
+        assert!(RsaPublicKey::parse("{\"e\": \"65537\"}").is_err());
+        assert!(RsaPublicKey::parse("e = \"abc\"\nn = \"3233\"\n").is_err());
+    }
+}
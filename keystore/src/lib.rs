@@ -0,0 +1,158 @@
+//! keystore: Schlüsselbund mit Passphrase
+//!
+//! Sammelt die Schlüsseldateien der übrigen Werkzeuge (RSA, DSA, X25519,
+//! AES) in einer einzigen Datei, verschlüsselt mit AES-256-GCM unter einem
+//! Schlüssel, den PBKDF2-HMAC-SHA256 aus der Passphrase ableitet. `export`
+//! gibt einen Eintrag wieder als gewöhnliche Schlüsseldatei aus, die die
+//! Werkzeuge direkt lesen.
+
+mod store;
+
+pub use store::{DEFAULT_ROUNDS, Entry, Keystore, Kind, MAX_ROUNDS, StoreError};
+
+use clap::{Parser, Subcommand};
+use csprng::RngArgs;
+use logging::{Verbosity, info};
+use secret::Secret;
+use std::io::BufRead;
+use std::path::Path;
+use tool_error::{Context, ToolError};
+
+#[derive(Parser)]
+#[command(name = "keystore")]
+#[command(about = "Schlüsselbund: Schlüsseldateien verschlüsselt mit PBKDF2 und AES-256-GCM aufbewahren")]
+#[command(version = "1.0")]
+#[command(after_help = "Die Passphrase wird aus --password-file oder als erste Zeile von STDIN gelesen.\n\
+Beispiel:\n  keystore add server rsa_privat.txt --kind rsa-private\n  keystore list\n  keystore export server -o rsa_privat.txt\n\
+Exit-Codes: 0 Erfolg, 1 Passphrase falsch oder Datei verändert, 65 Datei oder Schlüssel unlesbar")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Datei des Schlüsselbunds
+    #[arg(short, long, global = true, default_value = "schluesselbund.ks", help = "Datei des Schlüsselbunds")]
+    store: String,
+
+    /// Datei, deren erste Zeile die Passphrase ist
+    #[arg(long, global = true, help = "Passphrase aus der ersten Zeile dieser Datei lesen")]
+    password_file: Option<String>,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Schlüsseldatei aufnehmen, legt den Schlüsselbund bei Bedarf an
+    Add {
+        /// Name des Eintrags
+        name: String,
+
+        /// Schlüsseldatei eines der Werkzeuge
+        file: String,
+
+        /// Art des Schlüssels
+        #[arg(short, long, value_enum, help = "Art des Schlüssels (ec und aes: Hex)")]
+        kind: Kind,
+
+        /// PBKDF2-Runden beim Speichern
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..=MAX_ROUNDS as i64), help = "PBKDF2-Runden, höchstens 9600000 (Standard: bisheriger Wert bzw. 600000)")]
+        rounds: Option<u32>,
+
+        #[command(flatten)]
+        rng: RngArgs,
+    },
+    /// Einträge auflisten
+    List,
+    /// Eintrag als Schlüsseldatei ausgeben
+    Export {
+        /// Name des Eintrags
+        name: String,
+
+        /// Ausgabedatei
+        #[arg(short, long, help = "Ausgabedatei (Standard: STDOUT)")]
+        output: Option<String>,
+    },
+}
+
+fn read_password(path: Option<&str>) -> tool_error::Result<Secret<String>> {
+    let mut line = String::new();
+    match path {
+        Some(path) => line.push_str(tool_error::fs::read_to_string(path)?.lines().next().unwrap_or_default()),
+        None => {
+            std::io::stdin().lock().read_line(&mut line).context("Fehler beim Lesen der Passphrase von stdin")?;
+        }
+    }
+    let password = Secret::new(line.trim_end_matches(['\r', '\n']).to_string());
+    line.clear();
+    if password.is_empty() {
+        return Err(ToolError::usage("Leere Passphrase"));
+    }
+    Ok(password)
+}
+
+fn open(bytes: &[u8], passphrase: &Secret<String>) -> tool_error::Result<Keystore> {
+    Keystore::open(bytes, passphrase.as_bytes()).map_err(|e| match e {
+        StoreError::Authentication => ToolError::failed(e.to_string()),
+        StoreError::Malformed(_) => ToolError::data(e),
+    })
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+
+    match args.command {
+        Command::Add { name, file, kind, rounds, rng } => {
+            let data = Secret::new(tool_error::fs::read_to_string(&file)?);
+            let summary = kind.describe(&data)
+                .map_err(|e| ToolError::data(format!("'{}' ist kein Schlüssel der Art {}: {}", file, kind, e)))?;
+
+            let passphrase = read_password(args.password_file.as_deref())?;
+            let (mut keystore, stored_rounds) = if Path::new(&args.store).exists() {
+                let bytes = tool_error::fs::read(&args.store)?;
+                (open(&bytes, &passphrase)?, store::rounds(&bytes))
+            } else {
+                info!("Lege neuen Schlüsselbund '{}' an", args.store);
+                (Keystore::default(), None)
+            };
+            let rounds = rounds.or(stored_rounds).unwrap_or(DEFAULT_ROUNDS);
+
+            if !keystore.insert(Entry { name: name.clone(), kind, data }) {
+                return Err(ToolError::usage(format!("Eintrag '{}' existiert bereits", name)));
+            }
+            tool_error::fs::write(&args.store, keystore.seal(passphrase.as_bytes(), rounds, &mut rng.rng()))?;
+            info!("'{}' ({}, {}) gespeichert, {} PBKDF2-Runden", name, kind, summary, rounds);
+            Ok(())
+        }
+        Command::List => {
+            let passphrase = read_password(args.password_file.as_deref())?;
+            let keystore = open(&tool_error::fs::read(&args.store)?, &passphrase)?;
+            if keystore.entries.is_empty() {
+                println!("Schlüsselbund ist leer");
+            }
+            for entry in &keystore.entries {
+                let summary = entry.kind.describe(&entry.data).unwrap_or_else(|e| format!("unlesbar: {}", e));
+                println!("{:<20} {:<12} {}", entry.name, entry.kind, summary);
+            }
+            Ok(())
+        }
+        Command::Export { name, output } => {
+            let passphrase = read_password(args.password_file.as_deref())?;
+            let keystore = open(&tool_error::fs::read(&args.store)?, &passphrase)?;
+            let entry = keystore.get(&name)
+                .ok_or_else(|| ToolError::usage(format!("Kein Eintrag '{}' im Schlüsselbund", name)))?;
+            match output {
+                Some(path) => tool_error::fs::write(&path, entry.data.as_bytes()),
+                None => {
+                    print!("{}", *entry.data);
+                    Ok(())
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,318 @@
+//! Verschlüsselte Datei mit benannten Schlüsseln
+//!
+//! ```text
+//! "CLKS" 0x01
+//! u32 PBKDF2-Runden || Salz (16 Bytes) || Nonce (12 Bytes)
+//! AES-256-GCM(Einträge), Kopf als zusätzliche Daten
+//! ```
+//!
+//! Ein Eintrag ist `u16 Länge || Name`, ein Byte für die Art und
+//! `u32 Länge || Inhalt`. Der Inhalt ist der Text der Schlüsseldatei, so wie
+//! ihn die Werkzeuge schreiben und lesen. Bei jedem Speichern werden Salz
+//! und Nonce neu gewählt.
+
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use clap::ValueEnum;
+use key_formats::{DsaPrivateKey, DsaPublicKey, KeyFile, RsaPrivateKey, RsaPublicKey};
+use rand_core::{CryptoRng, RngCore};
+use secret::Secret;
+use sha2::Sha256;
+use std::fmt;
+
+const MAGIC: &[u8] = b"CLKS\x01";
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const HEADER_LENGTH: usize = MAGIC.len() + 4 + SALT_LENGTH + NONCE_LENGTH;
+
+/// PBKDF2-HMAC-SHA256-Runden für neue Dateien (OWASP-Empfehlung)
+pub const DEFAULT_ROUNDS: u32 = 600_000;
+
+/// Obergrenze beim Öffnen; die Runden stehen ungeschützt im Kopf, und eine
+/// gefälschte Datei mit 2^32 Runden hielte `open` sonst stundenlang auf
+pub const MAX_ROUNDS: u32 = 16 * DEFAULT_ROUNDS;
+
+/// Art eines gespeicherten Schlüssels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Kind {
+    RsaPublic,
+    RsaPrivate,
+    DsaPublic,
+    DsaPrivate,
+    /// X25519-Schlüssel, 32 Bytes als Hex
+    Ec,
+    /// AES-Schlüssel, 16, 24 oder 32 Bytes als Hex
+    Aes,
+}
+
+impl Kind {
+    const ALL: [Kind; 6] = [Kind::RsaPublic, Kind::RsaPrivate, Kind::DsaPublic, Kind::DsaPrivate, Kind::Ec, Kind::Aes];
+
+    fn to_byte(self) -> u8 {
+        Kind::ALL.iter().position(|&kind| kind == self).expect("jede Art steht in ALL") as u8
+    }
+
+    fn from_byte(byte: u8) -> Option<Kind> {
+        Kind::ALL.get(byte as usize).copied()
+    }
+
+    /// Prüft, ob der Text ein Schlüssel dieser Art ist, und beschreibt ihn kurz
+    pub fn describe(self, text: &str) -> Result<String, String> {
+        match self {
+            Kind::RsaPublic => RsaPublicKey::parse(text).map(|key| format!("{} Bit", key.n.bits())).map_err(|e| e.to_string()),
+            Kind::RsaPrivate => RsaPrivateKey::parse(text).map(|key| format!("{} Bit", key.n.bits())).map_err(|e| e.to_string()),
+            Kind::DsaPublic => DsaPublicKey::parse(text).map(|key| format!("{} Bit", key.params.p.bits())).map_err(|e| e.to_string()),
+            Kind::DsaPrivate => DsaPrivateKey::parse(text).map(|key| format!("{} Bit", key.params.p.bits())).map_err(|e| e.to_string()),
+            Kind::Ec => match hex_key(text)?.len() {
+                32 => Ok("X25519".to_string()),
+                length => Err(format!("X25519-Schlüssel hat 32 Bytes, nicht {}", length)),
+            },
+            Kind::Aes => match hex_key(text)?.len() {
+                length @ (16 | 24 | 32) => Ok(format!("AES-{}", length * 8)),
+                length => Err(format!("AES-Schlüssel hat 16, 24 oder 32 Bytes, nicht {}", length)),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = self.to_possible_value().expect("keine Art ist versteckt");
+        write!(f, "{}", name.get_name())
+    }
+}
+
+fn hex_key(text: &str) -> Result<Secret<Vec<u8>>, String> {
+    codec::hex::decode(text).map(Secret::new).map_err(|e| e.to_string())
+}
+
+/// Fehler beim Öffnen der Datei
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreError {
+    /// Keine Schlüsselbund-Datei oder abgeschnitten
+    Malformed(String),
+    /// Passphrase falsch oder Datei verändert
+    Authentication,
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StoreError::Malformed(reason) => write!(f, "Datei ist kein Schlüsselbund: {}", reason),
+            StoreError::Authentication => write!(f, "Passphrase falsch oder Datei verändert"),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub kind: Kind,
+    pub data: Secret<String>,
+}
+
+/// Entschlüsselter Inhalt der Datei
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Keystore {
+    pub entries: Vec<Entry>,
+}
+
+impl Keystore {
+    pub fn get(&self, name: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    /// Fügt einen Eintrag hinzu; `false`, wenn der Name schon vergeben ist
+    pub fn insert(&mut self, entry: Entry) -> bool {
+        if self.get(&entry.name).is_some() {
+            return false;
+        }
+        self.entries.push(entry);
+        true
+    }
+
+    fn encode_entries(&self) -> Secret<Vec<u8>> {
+        let mut bytes = Secret::new(Vec::new());
+        for entry in &self.entries {
+            bytes.extend_from_slice(&(entry.name.len() as u16).to_be_bytes());
+            bytes.extend_from_slice(entry.name.as_bytes());
+            bytes.push(entry.kind.to_byte());
+            bytes.extend_from_slice(&(entry.data.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(entry.data.as_bytes());
+        }
+        bytes
+    }
+
+    fn decode_entries(bytes: &[u8]) -> Result<Self, StoreError> {
+        let mut reader = Reader(bytes);
+        let mut entries = Vec::new();
+        while !reader.0.is_empty() {
+            let name_length = u16::from_be_bytes(reader.array()?) as usize;
+            let name = text(reader.take(name_length)?)?;
+            let kind = Kind::from_byte(reader.array::<1>()?[0])
+                .ok_or_else(|| StoreError::Malformed("unbekannte Schlüsselart".to_string()))?;
+            let data_length = u32::from_be_bytes(reader.array()?) as usize;
+            let data = Secret::new(text(reader.take(data_length)?)?);
+            entries.push(Entry { name, kind, data });
+        }
+        Ok(Keystore { entries })
+    }
+
+    /// Verschlüsselt alle Einträge mit einem aus der Passphrase abgeleiteten Schlüssel
+    pub fn seal(&self, passphrase: &[u8], rounds: u32, rng: &mut (impl RngCore + CryptoRng)) -> Vec<u8> {
+        let mut header = MAGIC.to_vec();
+        header.extend_from_slice(&rounds.to_be_bytes());
+        let mut random = [0u8; SALT_LENGTH + NONCE_LENGTH];
+        rng.fill_bytes(&mut random);
+        header.extend_from_slice(&random);
+
+        let (salt, nonce) = random.split_at(SALT_LENGTH);
+        let cipher = cipher(passphrase, salt, rounds);
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(nonce), Payload { msg: &self.encode_entries(), aad: &header })
+            .expect("AES-GCM verschlüsselt beliebig lange Klartexte dieser Größe");
+        [header, ciphertext].concat()
+    }
+
+    pub fn open(bytes: &[u8], passphrase: &[u8]) -> Result<Self, StoreError> {
+        if bytes.len() < HEADER_LENGTH {
+            return Err(StoreError::Malformed("Datei ist abgeschnitten".to_string()));
+        }
+        let (header, ciphertext) = bytes.split_at(HEADER_LENGTH);
+        let mut reader = Reader(header);
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(StoreError::Malformed("Kennung oder Version falsch".to_string()));
+        }
+        let rounds = u32::from_be_bytes(reader.array()?);
+        if rounds == 0 {
+            return Err(StoreError::Malformed("null PBKDF2-Runden".to_string()));
+        }
+        if rounds > MAX_ROUNDS {
+            return Err(StoreError::Malformed(format!("{} PBKDF2-Runden, höchstens {} erlaubt", rounds, MAX_ROUNDS)));
+        }
+        let salt = reader.take(SALT_LENGTH)?;
+        let nonce = reader.take(NONCE_LENGTH)?;
+
+        let plaintext = cipher(passphrase, salt, rounds)
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: header })
+            .map(Secret::new)
+            .map_err(|_| StoreError::Authentication)?;
+        Keystore::decode_entries(&plaintext)
+    }
+}
+
+/// Die Runden stehen im Kopf, damit ältere Dateien lesbar bleiben, wenn der Standard steigt
+pub fn rounds(bytes: &[u8]) -> Option<u32> {
+    let field = bytes.strip_prefix(MAGIC)?.get(..4)?;
+    Some(u32::from_be_bytes(field.try_into().expect("vier Bytes")))
+}
+
+fn cipher(passphrase: &[u8], salt: &[u8], rounds: u32) -> Aes256Gcm {
+    let mut key = Secret::new([0u8; 32]);
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase, salt, rounds, &mut *key);
+    Aes256Gcm::new((&*key).into())
+}
+
+fn text(bytes: &[u8]) -> Result<String, StoreError> {
+    String::from_utf8(bytes.to_vec()).map_err(|_| StoreError::Malformed("Eintrag ist kein UTF-8".to_string()))
+}
+
+/// Liest Felder nacheinander aus einem Byte-Slice
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], StoreError> {
+        if self.0.len() < length {
+            return Err(StoreError::Malformed("Eintrag ist abgeschnitten".to_string()));
+        }
+        let (head, rest) = self.0.split_at(length);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], StoreError> {
+        Ok(self.take(N)?.try_into().expect("Länge ist geprüft"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::{Algorithm, Drbg};
+
+    const ROUNDS: u32 = 1000;
+
+    fn keystore() -> Keystore {
+        let mut store = Keystore::default();
+        assert!(store.insert(Entry { name: "rsa".to_string(), kind: Kind::RsaPublic, data: Secret::new("65537\n3233\n".to_string()) }));
+        assert!(store.insert(Entry { name: "sitzung".to_string(), kind: Kind::Aes, data: Secret::new("00112233445566778899aabbccddeeff\n".to_string()) }));
+        store
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"keystore");
+        let sealed = keystore().seal(b"geheim", ROUNDS, &mut rng);
+        assert_eq!(rounds(&sealed), Some(ROUNDS));
+        assert_eq!(Keystore::open(&sealed, b"geheim").unwrap(), keystore());
+        assert_eq!(Keystore::open(&Keystore::default().seal(b"geheim", ROUNDS, &mut rng), b"geheim").unwrap(), Keystore::default());
+
+        // Neues Salz und neue Nonce bei jedem Speichern
+        assert_ne!(keystore().seal(b"geheim", ROUNDS, &mut rng), sealed);
+    }
+
+    #[test]
+    fn test_wrong_passphrase_and_tampering() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"keystore");
+        let sealed = keystore().seal(b"geheim", ROUNDS, &mut rng);
+        assert_eq!(Keystore::open(&sealed, b"Geheim"), Err(StoreError::Authentication));
+
+        // Niedrigstes Byte der Runden: 1000 ^ 1 = 1001
+        for index in [MAGIC.len() + 3, MAGIC.len() + 4, HEADER_LENGTH - 1, sealed.len() - 1] {
+            let mut tampered = sealed.clone();
+            tampered[index] ^= 1;
+            assert!(Keystore::open(&tampered, b"geheim").is_err(), "Byte {}", index);
+        }
+    }
+
+    #[test]
+    fn test_malformed() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"keystore");
+        let sealed = keystore().seal(b"geheim", ROUNDS, &mut rng);
+        assert!(matches!(Keystore::open(&sealed[..HEADER_LENGTH - 1], b"geheim"), Err(StoreError::Malformed(_))));
+        assert!(matches!(Keystore::open(&sealed[1..], b"geheim"), Err(StoreError::Malformed(_))));
+
+        // Zu viele Runden werden vor PBKDF2 abgewiesen
+        let mut expensive = sealed.clone();
+        expensive[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&(MAX_ROUNDS + 1).to_be_bytes());
+        assert!(matches!(Keystore::open(&expensive, b"geheim"), Err(StoreError::Malformed(_))));
+        expensive[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(matches!(Keystore::open(&expensive, b"geheim"), Err(StoreError::Malformed(_))));
+        assert!(matches!(Keystore::decode_entries(&[0, 3, b'a']), Err(StoreError::Malformed(_))));
+        assert!(matches!(Keystore::decode_entries(&[0, 1, b'a', 99, 0, 0, 0, 0]), Err(StoreError::Malformed(_))));
+    }
+
+    #[test]
+    fn test_duplicate_names() {
+        let mut store = keystore();
+        assert!(!store.insert(Entry { name: "rsa".to_string(), kind: Kind::Ec, data: Secret::new(String::new()) }));
+        assert_eq!(store.entries.len(), 2);
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(Kind::RsaPublic.describe("65537\n3233\n").unwrap(), "12 Bit");
+        assert_eq!(Kind::DsaPrivate.describe("283\n47\n64\n24\n").unwrap(), "9 Bit");
+        assert_eq!(Kind::Aes.describe("000102030405060708090a0b0c0d0e0f").unwrap(), "AES-128");
+        assert_eq!(Kind::Ec.describe(&"ab".repeat(32)).unwrap(), "X25519");
+        assert!(Kind::Aes.describe("0001").is_err());
+        assert!(Kind::Ec.describe("xyz").is_err());
+        assert!(Kind::RsaPrivate.describe("1\n").is_err());
+        for kind in Kind::ALL {
+            assert_eq!(Kind::from_byte(kind.to_byte()), Some(kind));
+        }
+        assert_eq!(Kind::RsaPublic.to_string(), "rsa-public");
+    }
+}
@@ -0,0 +1,129 @@
+//! Darstellung aller Teilschlüsselkandidaten mit ihrem Bias
+//!
+//! Die Kandidaten werden als Matrix angeordnet: die Spalte ist der Wert des
+//! letzten aktiven Nibbles, die Zeile der Wert aller vorherigen. Bei zwei
+//! aktiven Nibbles wie in der Vorlesung ergibt das 16×16 Einträge mit L1 als
+//! Zeile und L2 als Spalte. Der richtige Teilschlüssel sollte als einzelner
+//! heller Punkt herausstechen.
+
+use std::fmt::Write;
+
+/// Helligkeitsstufen der ASCII-Darstellung, von niedrigem zu hohem Bias
+const SHADES: &[u8] = b" .:-=+*#%@";
+
+/// Bias aller Kandidaten als Matrix
+pub struct BiasMatrix {
+    /// Beschriftung jeder Zeile (Hexadezimalziffern der vorderen Nibbles)
+    row_labels: Vec<String>,
+    rows: Vec<[f64; 16]>,
+}
+
+impl BiasMatrix {
+    /// Ordnet die Ergebnisse der Teilschlüsselsuche an
+    ///
+    /// `results` enthält für jeden Kandidaten den Teilschlüssel an seiner
+    /// Position im Rundenschlüssel und den gemessenen Bias.
+    pub fn new(results: &[(u16, f64)], nibbles: &[usize]) -> Self {
+        let (&column_nibble, row_nibbles) = nibbles.split_last().expect("mindestens ein aktives Nibble");
+        let row_count = 1 << (4 * row_nibbles.len());
+
+        let nibble_value = |key: u16, nibble: usize| ((key >> (4 * nibble)) & 0xF) as usize;
+        let row_index = |key: u16| {
+            row_nibbles.iter().fold(0, |index, &nibble| (index << 4) | nibble_value(key, nibble))
+        };
+
+        let mut rows = vec![[0.0; 16]; row_count];
+        for &(key, bias) in results {
+            rows[row_index(key)][nibble_value(key, column_nibble)] = bias;
+        }
+
+        // Bei nur einem aktiven Nibble gibt es eine einzige, unbeschriftete Zeile
+        let row_labels = if row_nibbles.is_empty() {
+            vec!["-".to_string()]
+        } else {
+            (0..row_count)
+                .map(|row| format!("{:0width$X}", row, width = row_nibbles.len()))
+                .collect()
+        };
+
+        BiasMatrix { row_labels, rows }
+    }
+
+    /// CSV mit einer Kopfzeile der Spaltenwerte und einer Zeile pro Zeilenwert
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("kandidat");
+        for column in 0..16 {
+            let _ = write!(csv, ",{:X}", column);
+        }
+        csv.push('\n');
+
+        for (label, row) in self.row_labels.iter().zip(&self.rows) {
+            csv.push_str(label);
+            for bias in row {
+                let _ = write!(csv, ",{:.6}", bias);
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// ASCII-Heatmap, jede Zelle ist relativ zum größten Bias schattiert
+    pub fn to_ascii(&self) -> String {
+        let max = self.rows.iter().flatten().copied().fold(0.0, f64::max);
+        let width = self.row_labels[0].len();
+
+        let mut output = format!("{:width$} ", "");
+        for column in 0..16 {
+            let _ = write!(output, "{:X}", column);
+        }
+        output.push('\n');
+
+        for (label, row) in self.row_labels.iter().zip(&self.rows) {
+            let _ = write!(output, "{:>width$} ", label);
+            for &bias in row {
+                let level = if max > 0.0 { (bias / max * (SHADES.len() - 1) as f64).round() as usize } else { 0 };
+                output.push(SHADES[level] as char);
+            }
+            output.push('\n');
+        }
+        let _ = write!(output, "Skala: '{}' = 0 bis '{}' = {:.6}", SHADES[0] as char, SHADES[SHADES.len() - 1] as char, max);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_nibble_matrix() {
+        // Alle 256 Kandidaten für die Nibbles 1 und 3, Maximum bei L1 = E, L2 = B
+        let results: Vec<(u16, f64)> = (0..256u16)
+            .map(|value| {
+                let key = ((value >> 4) << 4) | ((value & 0xF) << 12);
+                let bias = if key == 0xB0E0 { 0.05 } else { 0.01 };
+                (key, bias)
+            })
+            .collect();
+
+        let matrix = BiasMatrix::new(&results, &[1, 3]);
+        let csv = matrix.to_csv();
+        assert_eq!(csv.lines().count(), 17);
+        assert!(csv.starts_with("kandidat,0,1,2,3,4,5,6,7,8,9,A,B,C,D,E,F\n"));
+        let row_e: Vec<&str> = csv.lines().nth(15).unwrap().split(',').collect();
+        assert_eq!(row_e[0], "E");
+        assert_eq!(row_e[12], "0.050000");
+
+        let ascii = matrix.to_ascii();
+        let row_e = ascii.lines().nth(15).unwrap();
+        assert_eq!(row_e, "E :::::::::::@::::");
+    }
+
+    #[test]
+    fn test_single_nibble_matrix() {
+        let results: Vec<(u16, f64)> = (0..16u16).map(|value| (value << 8, value as f64)).collect();
+        let matrix = BiasMatrix::new(&results, &[2]);
+        assert_eq!(matrix.to_csv().lines().nth(1).unwrap().split(',').nth(6), Some("5.000000"));
+        assert!(matrix.to_ascii().lines().nth(1).unwrap().ends_with('@'));
+    }
+}
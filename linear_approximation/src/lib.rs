@@ -0,0 +1,486 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use logging::{Verbosity, debug, info, warn};
+use spn_core::pairs::{PairFile, Selection, parse_pairs};
+use spn_core::statistics::{self, Distinguisher};
+use spn_core::{PERMUTATION, ROUNDS, S_BOX, SpnCipher, parse_key};
+use std::time::Instant;
+use tool_error::{Context, ToolError};
+use trail::Approximation;
+
+mod heatmap;
+mod report;
+mod trail;
+
+/// Lineare Kryptoanalyse für SPN
+/// 
+/// Implementiert Teilschlüsselsuche für gegebene lineare Approximation【22-1】【22-2】
+/// Die Klartext-Kryptotextpaare erzeugt der Befehl `generate-pairs`
+/// In der Theorie sind es ca tε⁻² ≈ t·1000 für kleines t (in VL t = 8)【10-2】
+/// Ausgabe der Teilschlüssel in Standardoutput als Hexadezimalzahl
+#[derive(Parser)]
+#[command(author, version, about, long_about = None, subcommand_negates_reqs = true)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+    
+    /// Paardatei im Format von `generate-pairs` (siehe spn_core::pairs)
+    #[arg(short = 'f', long, required_unless_present = "plaintexts", conflicts_with_all = ["plaintexts", "ciphertexts"], help = "Paardatei aus 'generate-pairs'")]
+    pairs_file: Option<String>,
+    
+    /// Datei mit Klartexten (Hexadezimal)
+    #[arg(short, long, requires = "ciphertexts", help = "Datei mit Klartexten (Hexadezimal)")]
+    plaintexts: Option<String>,
+    
+    /// Datei mit entsprechenden Kryptotexten (Hexadezimal)
+    #[arg(short, long, requires = "plaintexts", help = "Datei mit entsprechenden Kryptotexten (Hexadezimal)")]
+    ciphertexts: Option<String>,
+    
+    /// Bekannter Schlüssel zur Kontrolle; ohne Angabe der aus der Paardatei, falls vorhanden
+    #[arg(short, long, help = "Bekannter Schlüssel zur Kontrolle des Ergebnisses")]
+    test_key: Option<String>,
+    
+    /// Beste lineare Spur automatisch suchen statt der Approximation aus der Vorlesung
+    #[arg(short, long, help = "Beste lineare Spur per Branch-and-Bound suchen")]
+    auto_trail: bool,
+    
+    /// Höchstzahl aktiver S-Boxen in der letzten Runde (= geratene Teilschlüssel-Nibbles)
+    #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u8).range(1..=4), help = "Maximal aktive S-Boxen der letzten Runde (bei --auto-trail)")]
+    max_active: u8,
+    
+    /// Bias aller Kandidaten als CSV-Matrix speichern (Zeile: vordere Nibbles, Spalte: letztes Nibble)
+    #[arg(long, help = "Bias aller Teilschlüsselkandidaten als CSV speichern")]
+    csv: Option<String>,
+    
+    /// Bias aller Kandidaten als ASCII-Heatmap auf STDERR ausgeben
+    #[arg(long, help = "ASCII-Heatmap des Bias auf STDERR ausgeben")]
+    heatmap: bool,
+    
+    /// Ergebnis als JSON auf STDOUT statt der Hexadezimalzeile
+    #[arg(long, help = "Ergebnis, Kandidaten und Laufzeit als JSON ausgeben")]
+    json: bool,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Klartexte wählen, verschlüsseln und als Paardatei speichern
+    GeneratePairs {
+        /// Ausgabedatei für die Paare
+        #[arg(short, long, help = "Ausgabedatei für die Paare")]
+        output: String,
+        
+        /// Schlüssel für die Verschlüsselung
+        #[arg(short, long, default_value = "2D55", value_parser = parse_key, help = "Schlüssel als 4 Hexadezimalziffern")]
+        key: u16,
+        
+        /// Anzahl der Klartexte, im Modus difference Anzahl der Klartextpaare
+        #[arg(short = 'n', long, default_value_t = 8000, help = "Anzahl der Klartexte bzw. Klartextpaare")]
+        count: usize,
+        
+        /// Auswahl der Klartexte
+        #[arg(short, long, value_enum, default_value_t = SelectionMode::Random, help = "Auswahl der Klartexte")]
+        mode: SelectionMode,
+        
+        /// Erster Klartext im Modus counter
+        #[arg(long, default_value = "0000", value_parser = parse_block, help = "Erster Klartext (bei --mode counter)")]
+        start: u16,
+        
+        /// XOR-Differenz der Klartextpaare im Modus difference
+        #[arg(short, long, required_if_eq("mode", "difference"), value_parser = parse_block, help = "Differenz der Klartextpaare (bei --mode difference)")]
+        difference: Option<u16>,
+        
+        /// Schlüssel zur Kontrolle späterer Angriffe in die Datei schreiben
+        #[arg(long, help = "Schlüssel in die Paardatei schreiben")]
+        record_key: bool,
+    },
+    /// Benötigte Paare oder Erfolgswahrscheinlichkeit eines Angriffs abschätzen
+    Estimate {
+        /// Güte ε einer linearen Approximation
+        #[arg(short, long, required_unless_present = "probability", conflicts_with = "probability", value_parser = parse_bias, help = "Güte ε der linearen Approximation")]
+        bias: Option<f64>,
+        
+        /// Wahrscheinlichkeit p einer differentiellen Charakteristik
+        #[arg(short = 'q', long, value_parser = parse_probability, help = "Wahrscheinlichkeit p der differentiellen Charakteristik")]
+        probability: Option<f64>,
+        
+        /// Gewünschte Erfolgswahrscheinlichkeit, daraus folgt die Anzahl der Paare
+        #[arg(short, long, required_unless_present = "pairs", conflicts_with = "pairs", value_parser = parse_probability, help = "Gewünschte Erfolgswahrscheinlichkeit (z. B. 0.95)")]
+        success: Option<f64>,
+        
+        /// Vorhandene Paare, daraus folgt die Erfolgswahrscheinlichkeit
+        #[arg(short = 'n', long, help = "Anzahl vorhandener Paare")]
+        pairs: Option<usize>,
+        
+        /// Anzahl der geratenen Teilschlüsselbits (4 pro aktiver S-Box der letzten Runde)
+        #[arg(short, long, default_value_t = 8, value_parser = clap::value_parser!(u32).range(1..=16), help = "Anzahl der geratenen Teilschlüsselbits")]
+        key_bits: u32,
+    },
+}
+
+/// Auswahl der Klartexte für `generate-pairs`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum SelectionMode {
+    /// Zufällige Klartexte (Known Plaintext)
+    Random,
+    /// Aufeinanderfolgende Klartexte
+    Counter,
+    /// Klartextpaare mit fester Differenz (Chosen Plaintext)
+    Difference,
+}
+
+/// Erfolgswahrscheinlichkeit, unter der vor zu wenigen Paaren gewarnt wird
+const SUFFICIENT_SUCCESS: f64 = 0.9;
+
+/// Güte der Approximation aus der Vorlesung
+const LECTURE_BIAS: f64 = 1.0 / 32.0;
+
+/// Lineare Approximation aus der Vorlesung【22-1】【22-2】
+/// X₅ ⊕ X₇ ⊕ X₈ ⊕ U₄₆ ⊕ U₄₈ ⊕ U₄₁₄ ⊕ U₄₁₆ = 0
+/// 
+/// Diese Approximation hat Güte ε ≈ 1/32【22-2】【22-4】
+const LECTURE_APPROXIMATION: Approximation = Approximation {
+    // X₅, X₇, X₈ (0-basiert: 4, 6, 7)
+    input_mask: 0x00D0,
+    // U₄₆, U₄₈, U₄₁₄, U₄₁₆ (0-basiert: 5, 7, 13, 15)
+    output_mask: 0xA0A0,
+};
+
+/// Rekonstruiert u4-Bits aus Kryptotext und Teilschlüsselkandidaten【10-7】
+/// Für die lineare Analyse müssen wir die relevanten Bits von u4 rekonstruieren
+fn reconstruct_u4_from_ciphertext(ciphertext: u16, key_guess: u16, cipher: &SpnCipher) -> u16 {
+    // Hypothetische finale Schlüsseladdition rückgängig machen; der
+    // Kandidat belegt nur die Nibbles, die in die Approximation eingehen
+    let v4 = ciphertext ^ key_guess;
+    
+    // Inverse S-Box anwenden um u4 zu erhalten
+    cipher.apply_inverse_sbox(v4)
+}
+
+/// Alle Teilschlüsselkandidaten für die aktiven Nibbles, an ihrer Position im Rundenschlüssel
+fn key_candidates(nibbles: &[usize]) -> Vec<u16> {
+    (0..1u32 << (4 * nibbles.len()))
+        .map(|value| {
+            nibbles.iter()
+                .enumerate()
+                .fold(0u16, |key, (i, &nibble)| key | (((value >> (4 * i)) & 0xF) as u16) << (4 * nibble))
+        })
+        .collect()
+}
+
+/// Formatiert die aktiven Nibbles eines Kandidaten, niedrigstes Nibble zuerst
+fn format_subkey(key_guess: u16, nibbles: &[usize]) -> String {
+    nibbles.iter()
+        .map(|&nibble| format!("{:X}", (key_guess >> (4 * nibble)) & 0xF))
+        .collect()
+}
+
+/// Führt die Teilschlüsselsuche durch【10-7】
+///
+/// Liefert alle Kandidaten mit ihrem gemessenen Bias, den besten zuerst.
+fn subkey_search(plaintexts: &[u16], ciphertexts: &[u16], approximation: &Approximation) -> Vec<(u16, f64)> {
+    let total_pairs = plaintexts.len();
+    let cipher = SpnCipher::new(0); // Nur für inverse S-Box Operationen
+    
+    let mut results: Vec<(u16, f64)> = key_candidates(&approximation.active_nibbles())
+        .into_iter()
+        .map(|key_guess| {
+            // Teste lineare Approximation für jeden Teilschlüsselkandidaten
+            let count_approximation_holds = plaintexts.iter()
+                .zip(ciphertexts.iter())
+                .filter(|&(&plaintext, &ciphertext)| {
+                    let u4_reconstructed = reconstruct_u4_from_ciphertext(ciphertext, key_guess, &cipher);
+                    approximation.evaluate(plaintext, u4_reconstructed) == 0
+                })
+                .count();
+            
+            // Berechne Wahrscheinlichkeit und Bias
+            let probability = count_approximation_holds as f64 / total_pairs as f64;
+            (key_guess, (probability - 0.5).abs())
+        })
+        .collect();
+    
+    // Sortiere nach Bias (absteigende Reihenfolge)
+    results.sort_by(|a, b| b.1.total_cmp(&a.1));
+    
+    results
+}
+
+/// Parst einen 16-Bit-Block aus 4 Hexadezimalziffern
+fn parse_block(value: &str) -> Result<u16, String> {
+    if value.len() != 4 {
+        return Err("Block muss genau 4 Hexadezimalziffern haben".to_string());
+    }
+    u16::from_str_radix(value, 16).map_err(|_| format!("'{}' ist keine Hexadezimalzahl", value))
+}
+
+/// Parst eine Güte ε ∈ (0, 1/2]
+fn parse_bias(value: &str) -> Result<f64, String> {
+    let bias: f64 = value.parse().map_err(|_| format!("'{}' ist keine Zahl", value))?;
+    if bias > 0.0 && bias <= 0.5 {
+        Ok(bias)
+    } else {
+        Err("Güte muss in (0, 0.5] liegen".to_string())
+    }
+}
+
+/// Parst eine Wahrscheinlichkeit in (0, 1)
+fn parse_probability(value: &str) -> Result<f64, String> {
+    let probability: f64 = value.parse().map_err(|_| format!("'{}' ist keine Zahl", value))?;
+    if probability > 0.0 && probability < 1.0 {
+        Ok(probability)
+    } else {
+        Err("Wahrscheinlichkeit muss in (0, 1) liegen".to_string())
+    }
+}
+
+/// Gibt die Abschätzung des `estimate`-Befehls aus
+fn estimate(distinguisher: Distinguisher, success: Option<f64>, pairs: Option<usize>, key_bits: u32) {
+    match (success, pairs) {
+        (Some(success), _) => {
+            let pairs = statistics::required_pairs(distinguisher, success, key_bits);
+            println!("Benötigte Paare für {:.1}% Erfolgswahrscheinlichkeit: {}", success * 100.0, pairs);
+        }
+        (None, Some(pairs)) => {
+            let success = statistics::success_probability(distinguisher, pairs, key_bits);
+            println!("Erfolgswahrscheinlichkeit mit {} Paaren: {:.1}%", pairs, success * 100.0);
+        }
+        (None, None) => unreachable!("clap verlangt --success oder --pairs"),
+    }
+}
+
+/// Warnt, wenn die Paare für einen zuverlässigen Angriff nicht reichen
+fn warn_if_insufficient(bias: f64, pairs: usize, key_bits: u32) {
+    let distinguisher = Distinguisher::Linear { bias };
+    let success = statistics::success_probability(distinguisher, pairs, key_bits);
+    if success < SUFFICIENT_SUCCESS {
+        warn!("Mit {} Paaren gelingt der Angriff nur mit ≈ {:.1}% Wahrscheinlichkeit (ε = {:.6}, {} Schlüsselbits)",
+              pairs, success * 100.0, bias, key_bits);
+        warn!("Für {:.0}% werden ≈ {} Paare benötigt",
+              SUFFICIENT_SUCCESS * 100.0, statistics::required_pairs(distinguisher, SUFFICIENT_SUCCESS, key_bits));
+    }
+}
+
+/// Startet das Werkzeug mit den übergebenen Kommandozeilenargumenten
+///
+/// Das erste Argument ist wie bei `std::env::args_os` der Programmname;
+/// so lässt sich das Werkzeug auch aus `cryptolab` heraus aufrufen.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let started = Instant::now();
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+    
+    match args.command {
+        Some(Command::Estimate { bias, probability, success, pairs, key_bits }) => {
+            let distinguisher = match (bias, probability) {
+                (Some(bias), _) => Distinguisher::Linear { bias },
+                (None, Some(probability)) => Distinguisher::Differential { probability },
+                (None, None) => unreachable!("clap verlangt --bias oder --probability"),
+            };
+            estimate(distinguisher, success, pairs, key_bits);
+            return Ok(());
+        }
+        Some(Command::GeneratePairs { output, key, count, mode, start, difference, record_key }) => {
+            let selection = match mode {
+                SelectionMode::Random => Selection::Random,
+                SelectionMode::Counter => Selection::Counter { start },
+                SelectionMode::Difference => Selection::Difference {
+                    difference: difference.expect("clap verlangt --difference"),
+                },
+            };
+            
+            let mut file = PairFile::generate(&SpnCipher::new(key), selection, count);
+            if record_key {
+                file.key = Some(key);
+            }
+            return file.write(&output).context("Fehler beim Schreiben der Paare");
+        }
+        None => {}
+    }
+    
+    // Lade Paare aus der Paardatei oder aus zwei Hexadezimaldateien
+    let (plaintexts, ciphertexts, file_key) = if let Some(path) = &args.pairs_file {
+        let file = PairFile::read(path)
+            .map_err(ToolError::data)
+            .context("Fehler beim Laden der Paare")?;
+        (file.plaintexts, file.ciphertexts, file.key)
+    } else {
+        // Ohne Paardatei sind beide Dateien durch clap erzwungen
+        let plaintexts_path = args.plaintexts.as_deref().expect("Klartexte fehlen");
+        let ciphertexts_path = args.ciphertexts.as_deref().expect("Kryptotexte fehlen");
+        
+        let pt_data = tool_error::fs::read_to_string(plaintexts_path)
+            .context("Fehler beim Laden der Klartexte")?;
+        let ct_data = tool_error::fs::read_to_string(ciphertexts_path)
+            .context("Fehler beim Laden der Kryptotexte")?;
+        
+        let (plaintexts, ciphertexts) = parse_pairs(pt_data.trim(), ct_data.trim()).map_err(ToolError::data)?;
+        
+        (plaintexts, ciphertexts, None)
+    };
+    
+    if plaintexts.is_empty() {
+        return Err(ToolError::data("Keine Klartext-Kryptotext-Paare gefunden"));
+    }
+    
+    let test_key = args.test_key.as_deref().map(parse_key).transpose()
+        .map_err(ToolError::usage)
+        .context("Fehler beim Parsen des Testschlüssels")?
+        .or(file_key);
+    
+    // Wähle die Approximation: fest aus der Vorlesung oder automatisch gesucht
+    let (approximation, bias) = if args.auto_trail {
+        let Some(trail) = trail::search(&S_BOX, &PERMUTATION, ROUNDS - 1, args.max_active as usize) else {
+            return Err(ToolError::failed("Keine lineare Spur gefunden"));
+        };
+        
+        info!("Gefundene Spur:\n{}", trail);
+        (trail.approximation(), trail.bias)
+    } else {
+        (LECTURE_APPROXIMATION, LECTURE_BIAS)
+    };
+    let nibbles = approximation.active_nibbles();
+    warn_if_insufficient(bias, plaintexts.len(), 4 * nibbles.len() as u32);
+    
+    // Führe Teilschlüsselsuche durch
+    let results = subkey_search(&plaintexts, &ciphertexts, &approximation);
+    
+    // === AUSGABE DER TEILSCHLÜSSEL IN STANDARDOUTPUT ALS HEXADEZIMALZAHL ===【10-2】
+    let (best_key, _best_bias) = results[0];
+    
+    // Hauptausgabe: Teilschlüssel als Hexadezimalzahl
+    if !args.json {
+        println!("{}", format_subkey(best_key, &nibbles));
+    }
+    
+    // Vollständige Übersicht aller Kandidaten, STDOUT bleibt dem Teilschlüssel vorbehalten
+    if args.csv.is_some() || args.heatmap {
+        let matrix = heatmap::BiasMatrix::new(&results, &nibbles);
+        if let Some(path) = &args.csv {
+            tool_error::fs::write(path, matrix.to_csv()).context("Fehler beim Schreiben der CSV-Datei")?;
+        }
+        if args.heatmap {
+            eprintln!("{}", matrix.to_ascii());
+        }
+    }
+    
+    if args.json {
+        let expected_subkey = test_key.map(|key| format_subkey(key, &nibbles));
+        let report = report::AttackReport {
+            subkey: format_subkey(best_key, &nibbles),
+            active_nibbles: nibbles.clone(),
+            input_mask: format!("{:04X}", approximation.input_mask),
+            output_mask: format!("{:04X}", approximation.output_mask),
+            expected_bias: bias,
+            pairs: plaintexts.len(),
+            success_probability: statistics::success_probability(
+                Distinguisher::Linear { bias }, plaintexts.len(), 4 * nibbles.len() as u32,
+            ),
+            candidates: results.iter()
+                .take(report::REPORTED_CANDIDATES)
+                .map(|&(key_guess, bias)| report::Candidate { subkey: format_subkey(key_guess, &nibbles), bias })
+                .collect(),
+            success: expected_subkey.as_ref().map(|expected| *expected == format_subkey(best_key, &nibbles)),
+            expected_subkey,
+            elapsed_ms: started.elapsed().as_secs_f64() * 1000.0,
+        };
+        println!("{}", report.to_json());
+        return Ok(());
+    }
+    
+    // Auswertung gegen den Kontrollschlüssel (-v), Details mit -vv
+    debug!("Verwendete Paare: {}", plaintexts.len());
+    debug!("Aktive Nibbles: {:?}", nibbles);
+    for (i, (key_guess, bias)) in results.iter().take(5).enumerate() {
+        debug!("Kandidat {:2}: {} (Bias: {:.6})", i + 1, format_subkey(*key_guess, &nibbles), bias);
+    }
+    if let Some(key) = test_key {
+        let expected = format_subkey(key, &nibbles);
+        let found = format_subkey(best_key, &nibbles);
+        
+        debug!("Testschlüssel: {:04X}", key);
+        if found == expected {
+            info!("Angriff erfolgreich: Teilschlüssel {} stimmt mit dem Testschlüssel überein", found);
+        } else {
+            info!("Angriff fehlgeschlagen: {} statt {} gefunden - mehr Paare nötig", found, expected);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_linear_approximation() {
+        // Test der linearen Approximation
+        let plaintext = 0x1234;
+        let u4 = 0x5678;
+        
+        // Sollte deterministisch sein
+        let result1 = LECTURE_APPROXIMATION.evaluate(plaintext, u4);
+        let result2 = LECTURE_APPROXIMATION.evaluate(plaintext, u4);
+        assert_eq!(result1, result2);
+        
+        // Sollte nur 0 oder 1 zurückgeben
+        assert!(result1 == 0 || result1 == 1);
+    }
+    
+    #[test]
+    fn test_estimate_arguments() {
+        assert_eq!(parse_bias("0.03125").unwrap(), 1.0 / 32.0);
+        assert!(parse_bias("0").is_err());
+        assert!(parse_bias("0.6").is_err());
+        assert_eq!(parse_probability("0.95").unwrap(), 0.95);
+        assert!(parse_probability("1").is_err());
+        assert!(parse_probability("x").is_err());
+    }
+    
+    #[test]
+    fn test_subkey_search_with_known_key() {
+        let known_key = 0x2D55;
+        let cipher = SpnCipher::new(known_key);
+        
+        // Generiere wenige Testpaare
+        let (plaintexts, ciphertexts) = spn_core::pairs::generate_pairs(&cipher, 1000);
+        
+        // Führe Suche durch
+        let results = subkey_search(&plaintexts, &ciphertexts, &LECTURE_APPROXIMATION);
+        
+        // Sollte für jeden Kandidaten ein Ergebnis haben
+        assert_eq!(results.len(), 256);
+        
+        // Erwartete Teilschlüssel: Nibble 2 (L1) und Nibble 4 (L2)
+        let expected = known_key & 0xF0F0;
+        
+        // Prüfe ob erwarteter Teilschlüssel in Top-Kandidaten ist
+        let found = results.iter().take(10).any(|&(key_guess, _)| key_guess == expected);
+        
+        // Bei 1000 Paaren sollte es oft funktionieren (aber nicht immer)
+        // Daher nur Info-Ausgabe statt assert
+        println!("Erwartete Teilschlüssel ({}) in Top 10 gefunden: {}", 
+                 format_subkey(expected, &[1, 3]), found);
+    }
+    
+    #[test]
+    fn test_subkey_search_with_auto_trail() {
+        let known_key = 0x2D55;
+        let cipher = SpnCipher::new(known_key);
+        let trail = trail::search(&S_BOX, &PERMUTATION, ROUNDS - 1, 2).unwrap();
+        let approximation = trail.approximation();
+        
+        // Bei ε ≈ 1/17 reichen einige tausend Paare mit großem Abstand
+        let (plaintexts, ciphertexts) = spn_core::pairs::generate_pairs(&cipher, 5000);
+        let results = subkey_search(&plaintexts, &ciphertexts, &approximation);
+        
+        let nibbles = approximation.active_nibbles();
+        let mask = nibbles.iter().fold(0u16, |mask, &nibble| mask | 0xF << (4 * nibble));
+        assert_eq!(results[0].0, known_key & mask);
+    }
+}
@@ -0,0 +1,42 @@
+//! Ergebnis der Teilschlüsselsuche als JSON (`--json`)
+//!
+//! Für automatische Auswertungen: statt der Hexadezimalzeile auf STDOUT und
+//! der Diagnosen mit `-v` auf STDERR steht alles in einem einzigen JSON-Objekt.
+//! Teilschlüssel und Masken sind Hexadezimalstrings wie in der Textausgabe.
+
+use serde::Serialize;
+
+/// Anzahl der besten Kandidaten im Bericht
+pub const REPORTED_CANDIDATES: usize = 10;
+
+#[derive(Debug, Serialize)]
+pub struct AttackReport {
+    /// Bester Teilschlüssel, niedrigstes aktives Nibble zuerst
+    pub subkey: String,
+    pub active_nibbles: Vec<usize>,
+    pub input_mask: String,
+    pub output_mask: String,
+    /// Güte ε der Approximation (Vorlesung oder gefundene Spur)
+    pub expected_bias: f64,
+    pub pairs: usize,
+    /// Geschätzte Erfolgswahrscheinlichkeit für diese Anzahl Paare
+    pub success_probability: f64,
+    /// Die besten Kandidaten mit gemessenem Bias, bester zuerst
+    pub candidates: Vec<Candidate>,
+    /// Teilschlüssel des Kontrollschlüssels, falls einer bekannt ist
+    pub expected_subkey: Option<String>,
+    pub success: Option<bool>,
+    pub elapsed_ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Candidate {
+    pub subkey: String,
+    pub bias: f64,
+}
+
+impl AttackReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Bericht ist serialisierbar")
+    }
+}
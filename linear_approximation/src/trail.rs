@@ -0,0 +1,255 @@
+//! Automatische Suche nach linearen Spuren (Trails)
+//!
+//! Eine lineare Spur verknüpft über alle Runden bis auf die letzte je eine
+//! Approximation pro aktiver S-Box: die Ausgabemaske einer Runde wird durch
+//! die Permutation zur Eingabemaske der nächsten. Die Güte der gesamten
+//! Approximation folgt aus dem Piling-Up-Lemma:
+//!
+//! ε = 2^(n-1) · ε₁ · ε₂ · … · εₙ
+//!
+//! Die Suche ist ein Branch-and-Bound über alle Masken: ein Zweig wird
+//! verworfen, sobald er selbst mit der besten S-Box-Approximation in jeder
+//! verbleibenden Runde die bisher beste Spur nicht mehr schlagen kann.
+
+use std::fmt;
+
+/// Lineare Approximationstabelle (LAT) einer 4-Bit-S-Box
+///
+/// `lat[a][b]` ist die Anzahl der Eingaben x mit a·x = b·S(x), minus 8.
+/// Die Güte der Approximation (a, b) ist `lat[a][b] / 16`.
+pub fn lat(s_box: &[u8; 16]) -> [[i8; 16]; 16] {
+    let mut table = [[0i8; 16]; 16];
+    for (a, row) in table.iter_mut().enumerate() {
+        for (b, entry) in row.iter_mut().enumerate() {
+            let matches = (0..16)
+                .filter(|&x| parity((a & x) as u16) == parity((b & s_box[x] as usize) as u16))
+                .count();
+            *entry = matches as i8 - 8;
+        }
+    }
+    table
+}
+
+/// Lineare Approximation zwischen Klartext und der Eingabe der letzten S-Box-Schicht
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Approximation {
+    /// Maske auf den Klartextbits
+    pub input_mask: u16,
+    /// Maske auf u^N (vor der letzten S-Box-Schicht)
+    pub output_mask: u16,
+}
+
+impl Approximation {
+    /// Wert der Approximation (0 oder 1) für einen Klartext und u^N
+    pub fn evaluate(&self, plaintext: u16, u_last: u16) -> u16 {
+        parity(plaintext & self.input_mask) ^ parity(u_last & self.output_mask)
+    }
+
+    /// Indizes der Nibbles von u^N, die in die Approximation eingehen;
+    /// genau deren Teilschlüssel müssen geraten werden
+    pub fn active_nibbles(&self) -> Vec<usize> {
+        (0..4).filter(|&i| (self.output_mask >> (4 * i)) & 0xF != 0).collect()
+    }
+}
+
+/// Gefundene lineare Spur
+#[derive(Debug, Clone)]
+pub struct Trail {
+    /// Eingabemaske der S-Box-Schicht jeder Runde (Runde 1 = Klartextmaske)
+    pub input_masks: Vec<u16>,
+    /// Ausgabemaske der S-Box-Schicht jeder Runde
+    pub output_masks: Vec<u16>,
+    /// Maske auf u^N nach der letzten approximierten Runde
+    pub final_mask: u16,
+    /// Güte ε nach dem Piling-Up-Lemma
+    pub bias: f64,
+}
+
+impl Trail {
+    pub fn approximation(&self) -> Approximation {
+        Approximation { input_mask: self.input_masks[0], output_mask: self.final_mask }
+    }
+
+    /// Anzahl der aktiven S-Boxen über alle approximierten Runden
+    pub fn active_sboxes(&self) -> usize {
+        self.input_masks.iter().map(|&mask| nibble_count(mask)).sum()
+    }
+}
+
+impl fmt::Display for Trail {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (round, (input, output)) in self.input_masks.iter().zip(&self.output_masks).enumerate() {
+            writeln!(f, "Runde {}: u-Maske {:04X} -> v-Maske {:04X}", round + 1, input, output)?;
+        }
+        writeln!(f, "Maske auf u{}: {:04X}", self.input_masks.len() + 1, self.final_mask)?;
+        writeln!(f, "Aktive S-Boxen: {}", self.active_sboxes())?;
+        write!(f, "Güte ε = {:.6} (≈ 1/{:.0}), benötigte Paare ≈ {:.0}", self.bias, 1.0 / self.bias, self.bias.powi(-2))
+    }
+}
+
+/// Branch-and-Bound-Suche über alle Spuren durch `rounds` Runden
+struct Search<'a> {
+    /// Approximationen jeder Eingabemaske, nach |Korrelation| absteigend
+    choices: Vec<Vec<(u8, f64)>>,
+    permutation: &'a [usize; 16],
+    rounds: usize,
+    max_final_nibbles: usize,
+    max_correlation: f64,
+    best: Option<Trail>,
+    best_correlation: f64,
+    input_masks: Vec<u16>,
+    output_masks: Vec<u16>,
+}
+
+/// Sucht die Spur mit der größten Güte über `rounds` Runden.
+///
+/// Gerechnet wird mit Korrelationen c = 2ε, die sich einfach multiplizieren.
+/// `max_final_nibbles` begrenzt die Zahl der aktiven S-Boxen der letzten
+/// Runde und damit die Zahl der zu ratenden Teilschlüssel-Nibbles.
+pub fn search(s_box: &[u8; 16], permutation: &[usize; 16], rounds: usize, max_final_nibbles: usize) -> Option<Trail> {
+    let table = lat(s_box);
+    let choices: Vec<Vec<(u8, f64)>> = (0..16)
+        .map(|a| {
+            let mut row: Vec<(u8, f64)> = (1..16u8)
+                .filter(|&b| table[a][b as usize] != 0)
+                .map(|b| (b, table[a][b as usize] as f64 / 8.0))
+                .collect();
+            row.sort_by(|x, y| y.1.abs().total_cmp(&x.1.abs()));
+            row
+        })
+        .collect();
+    let max_correlation = choices[1..].iter()
+        .flatten()
+        .map(|(_, c)| c.abs())
+        .fold(0.0, f64::max);
+
+    let mut search = Search {
+        choices,
+        permutation,
+        rounds,
+        max_final_nibbles,
+        max_correlation,
+        best: None,
+        best_correlation: 0.0,
+        input_masks: Vec::with_capacity(rounds),
+        output_masks: Vec::with_capacity(rounds),
+    };
+
+    // Masken mit wenigen aktiven S-Boxen zuerst, sie liefern früh eine gute Schranke
+    let mut masks: Vec<u16> = (1..=u16::MAX).collect();
+    masks.sort_by_key(|&mask| nibble_count(mask));
+    for mask in masks {
+        search.round(0, mask, 1.0);
+    }
+
+    search.best
+}
+
+impl Search<'_> {
+    fn round(&mut self, round: usize, mask: u16, correlation: f64) {
+        if round == self.rounds {
+            if nibble_count(mask) <= self.max_final_nibbles && correlation.abs() > self.best_correlation {
+                self.best_correlation = correlation.abs();
+                self.best = Some(Trail {
+                    input_masks: self.input_masks.clone(),
+                    output_masks: self.output_masks.clone(),
+                    final_mask: mask,
+                    bias: correlation.abs() / 2.0,
+                });
+            }
+            return;
+        }
+
+        self.input_masks.push(mask);
+        self.sbox(round, mask, 0, 0, correlation);
+        self.input_masks.pop();
+    }
+
+    /// Wählt die Ausgabemaske der S-Box `nibble` und aller folgenden
+    fn sbox(&mut self, round: usize, mask: u16, nibble: usize, output: u16, correlation: f64) {
+        if nibble == 4 {
+            self.output_masks.push(output);
+            let next = permute_mask(self.permutation, output);
+            self.round(round + 1, next, correlation);
+            self.output_masks.pop();
+            return;
+        }
+
+        let input = ((mask >> (4 * nibble)) & 0xF) as usize;
+        if input == 0 {
+            self.sbox(round, mask, nibble + 1, output, correlation);
+            return;
+        }
+
+        // Jede weitere aktive S-Box dieser Runde und jede folgende Runde
+        // kann die Korrelation höchstens mit `max_correlation` multiplizieren
+        let later_sboxes = nibble_count(mask.checked_shr(4 * (nibble as u32 + 1)).unwrap_or(0)) + (self.rounds - round - 1);
+        let bound = self.max_correlation.powi(later_sboxes as i32);
+
+        for index in 0..self.choices[input].len() {
+            let (b, c) = self.choices[input][index];
+            let next = correlation * c;
+            if next.abs() * bound <= self.best_correlation {
+                break;
+            }
+            self.sbox(round, mask, nibble + 1, output | (b as u16) << (4 * nibble), next);
+        }
+    }
+}
+
+/// Eine Maske auf v wird zur Maske auf w = P(v): Bit i wandert an Position P[i]
+fn permute_mask(permutation: &[usize; 16], mask: u16) -> u16 {
+    permutation.iter()
+        .enumerate()
+        .fold(0, |result, (i, &target)| result | ((mask >> i) & 1) << target)
+}
+
+fn nibble_count(mask: u16) -> usize {
+    (0..4).filter(|&i| (mask >> (4 * i)) & 0xF != 0).count()
+}
+
+fn parity(value: u16) -> u16 {
+    (value.count_ones() & 1) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spn_core::{PERMUTATION, S_BOX};
+
+    #[test]
+    fn test_lat() {
+        let table = lat(&S_BOX);
+        // Triviale Approximation gilt immer
+        assert_eq!(table[0][0], 8);
+        // Jede Zeile und Spalte außer der ersten summiert sich zu ±8
+        // (Parseval), bei Heys' S-Box liegen alle Einträge in [-6, 6]
+        assert!(table.iter().skip(1).flatten().all(|&entry| entry.abs() <= 6));
+        assert!((1..16).all(|a| table[a][0] == 0 && table[0][a] == 0));
+    }
+
+    #[test]
+    fn test_search_finds_consistent_trail() {
+        let trail = search(&S_BOX, &PERMUTATION, 3, 2).unwrap();
+        assert!(trail.approximation().active_nibbles().len() <= 2);
+
+        // Die Spur muss zusammenhängen
+        for round in 1..3 {
+            assert_eq!(trail.input_masks[round], permute_mask(&PERMUTATION, trail.output_masks[round - 1]));
+        }
+        assert_eq!(trail.final_mask, permute_mask(&PERMUTATION, trail.output_masks[2]));
+
+        // Die Güte ist die per Piling-Up-Lemma aus der LAT berechnete
+        let table = lat(&S_BOX);
+        let correlation: f64 = trail.input_masks.iter()
+            .zip(&trail.output_masks)
+            .flat_map(|(&input, &output)| {
+                (0..4)
+                    .filter(move |&i| (input >> (4 * i)) & 0xF != 0)
+                    .map(move |i| table[((input >> (4 * i)) & 0xF) as usize][((output >> (4 * i)) & 0xF) as usize] as f64 / 8.0)
+            })
+            .product();
+        assert!((correlation.abs() / 2.0 - trail.bias).abs() < 1e-12);
+        assert!(trail.bias >= 1.0 / 32.0);
+    }
+}
@@ -0,0 +1,321 @@
+//! Export der SPN-Verschlüsselung als Gleichungssystem
+//!
+//! Für bekannte Klartext-Kryptotext-Paare werden alle Runden als Gleichungen
+//! über GF(2) aufgeschrieben; unbekannt sind die 16 Schlüsselbits und die
+//! Zwischenwerte u^r und v^r jeder Runde. Jede Lösung des Systems liefert
+//! einen Schlüssel, der alle Paare verschlüsselt, sodass sich die Schlüsselsuche
+//! einem SAT-Solver (DIMACS CNF) oder einem Gröbnerbasen-Werkzeug (ANF)
+//! übergeben lässt.
+//!
+//! Die Permutation ist nur eine Umbenennung der Bits und erzeugt keine
+//! eigenen Variablen: Eingang von Runde r+1 ist u^(r+1)_P(i) = v^r_i ⊕ k_P(i).
+
+use std::fmt::Write;
+
+use spn_core::{ROUNDS, SpnCipher};
+
+/// Variablen pro Paar: u^r und v^r für jede Runde
+const PAIR_VARIABLES: usize = ROUNDS * 32;
+
+/// Einzelne Bedingung des Gleichungssystems
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Constraint {
+    /// Die Summe der Variablen über GF(2) ist gleich `constant`
+    Xor { variables: Vec<usize>, constant: bool },
+    /// `output` = S(`input`), Bit 0 ist jeweils das niederwertigste
+    SBox { input: [usize; 4], output: [usize; 4] },
+}
+
+/// Gleichungssystem für eine Menge von Klartext-Kryptotext-Paaren
+pub struct EquationSystem {
+    s_box: [u8; 16],
+    pairs: usize,
+    constraints: Vec<Constraint>,
+}
+
+impl EquationSystem {
+    /// Stellt die Gleichungen für alle Paare auf, der Schlüssel ist allen gemeinsam
+    pub fn new(cipher: &SpnCipher, pairs: &[(u16, u16)]) -> Self {
+        let mut constraints = Vec::new();
+
+        for (pair, &(plaintext, ciphertext)) in pairs.iter().enumerate() {
+            // Runde 1: u^1 = x ⊕ K
+            for i in 0..16 {
+                constraints.push(Constraint::Xor {
+                    variables: vec![key(i), u(pair, 1, i)],
+                    constant: bit(plaintext, i),
+                });
+            }
+
+            for round in 1..=ROUNDS {
+                for nibble in 0..4 {
+                    let bits = |variable: fn(usize, usize, usize) -> usize| {
+                        std::array::from_fn(|j| variable(pair, round, 4 * nibble + j))
+                    };
+                    constraints.push(Constraint::SBox { input: bits(u), output: bits(v) });
+                }
+
+                // Runden 2 bis 4: u^(r+1) = P(v^r) ⊕ K
+                if round < ROUNDS {
+                    for (i, &target) in cipher.permutation.iter().enumerate() {
+                        constraints.push(Constraint::Xor {
+                            variables: vec![key(target), v(pair, round, i), u(pair, round + 1, target)],
+                            constant: false,
+                        });
+                    }
+                }
+            }
+
+            // Finale Schlüsseladdition: y = v^N ⊕ K
+            for i in 0..16 {
+                constraints.push(Constraint::Xor {
+                    variables: vec![key(i), v(pair, ROUNDS, i)],
+                    constant: bit(ciphertext, i),
+                });
+            }
+        }
+
+        EquationSystem { s_box: cipher.s_box, pairs: pairs.len(), constraints }
+    }
+
+    /// Gesamtzahl der Variablen
+    pub fn variable_count(&self) -> usize {
+        16 + self.pairs * PAIR_VARIABLES
+    }
+
+    /// Gleichungen als Polynome in algebraischer Normalform, je Zeile ein
+    /// Polynom p mit p = 0
+    pub fn to_anf(&self) -> String {
+        let mut output = String::new();
+        let _ = writeln!(output, "# SPN mit {} Paar(en), {} Variablen", self.pairs, self.variable_count());
+        let _ = writeln!(output, "# Jede Zeile ist ein Polynom über GF(2), das gleich 0 ist");
+
+        for constraint in &self.constraints {
+            match constraint {
+                Constraint::Xor { variables, constant } => {
+                    let mut terms: Vec<String> = variables.iter().map(|&var| self.name(var)).collect();
+                    if *constant {
+                        terms.push("1".to_string());
+                    }
+                    let _ = writeln!(output, "{}", terms.join(" + "));
+                }
+                Constraint::SBox { input, output: sbox_output } => {
+                    // v_j + f_j(u) = 0 mit f_j als ANF des j-ten Ausgangsbits
+                    for (j, &out) in sbox_output.iter().enumerate() {
+                        let mut terms = vec![self.name(out)];
+                        for monomial in anf(&self.s_box, j) {
+                            if monomial == 0 {
+                                terms.push("1".to_string());
+                            } else {
+                                let factors: Vec<String> = (0..4)
+                                    .filter(|&b| monomial >> b & 1 == 1)
+                                    .map(|b| self.name(input[b]))
+                                    .collect();
+                                terms.push(factors.join("*"));
+                            }
+                        }
+                        let _ = writeln!(output, "{}", terms.join(" + "));
+                    }
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Gleichungen als Klauseln im DIMACS-CNF-Format
+    ///
+    /// Die Schlüsselbits k0 bis k15 sind die Variablen 1 bis 16, sodass sich
+    /// der Schlüssel direkt aus der Belegung eines SAT-Solvers ablesen lässt.
+    pub fn to_dimacs(&self) -> String {
+        let clauses = self.clauses();
+
+        let mut output = String::new();
+        let _ = writeln!(output, "c SPN mit {} Paar(en)", self.pairs);
+        let _ = writeln!(output, "c Variablen 1-16: Schlüsselbits k0 (niederwertigstes) bis k15");
+        for pair in 0..self.pairs {
+            let _ = writeln!(output, "c Variablen {}-{}: Zwischenwerte von Paar {}",
+                u(pair, 1, 0) + 1, v(pair, ROUNDS, 15) + 1, pair + 1);
+        }
+        let _ = writeln!(output, "p cnf {} {}", self.variable_count(), clauses.len());
+
+        for clause in clauses {
+            for literal in clause {
+                let _ = write!(output, "{} ", literal);
+            }
+            let _ = writeln!(output, "0");
+        }
+
+        output
+    }
+
+    /// Alle Klauseln, Literale wie in DIMACS (Variable i+1, negativ für ¬)
+    fn clauses(&self) -> Vec<Vec<i64>> {
+        let mut clauses = Vec::new();
+
+        for constraint in &self.constraints {
+            match constraint {
+                Constraint::Xor { variables, constant } => {
+                    // Jede Belegung mit falscher Parität wird durch eine Klausel ausgeschlossen
+                    for assignment in 0..1u32 << variables.len() {
+                        if (assignment.count_ones() % 2 == 1) != *constant {
+                            clauses.push(forbid(variables, assignment));
+                        }
+                    }
+                }
+                Constraint::SBox { input, output } => {
+                    // Für jeden Eingabewert x: u = x ⇒ v_j = S(x)_j
+                    for x in 0..16u32 {
+                        let y = self.s_box[x as usize] as u32;
+                        for (j, &out) in output.iter().enumerate() {
+                            let mut clause = forbid(input, x);
+                            clause.push(literal(out, y >> j & 1 == 1));
+                            clauses.push(clause);
+                        }
+                    }
+                }
+            }
+        }
+
+        clauses
+    }
+
+    /// Lesbarer Name einer Variablen
+    fn name(&self, variable: usize) -> String {
+        if variable < 16 {
+            return format!("k{}", variable);
+        }
+        let offset = (variable - 16) % PAIR_VARIABLES;
+        let pair = (variable - 16) / PAIR_VARIABLES;
+        let round = offset / 32 + 1;
+        let kind = if offset % 32 < 16 { "u" } else { "v" };
+        let suffix = if self.pairs > 1 { format!("_{}", pair + 1) } else { String::new() };
+        format!("{}{}_{}{}", kind, round, offset % 16, suffix)
+    }
+}
+
+fn key(i: usize) -> usize {
+    i
+}
+
+fn u(pair: usize, round: usize, i: usize) -> usize {
+    16 + pair * PAIR_VARIABLES + (round - 1) * 32 + i
+}
+
+fn v(pair: usize, round: usize, i: usize) -> usize {
+    u(pair, round, i) + 16
+}
+
+fn bit(value: u16, i: usize) -> bool {
+    value >> i & 1 == 1
+}
+
+fn literal(variable: usize, positive: bool) -> i64 {
+    let index = variable as i64 + 1;
+    if positive { index } else { -index }
+}
+
+/// Klausel, die genau die angegebene Belegung der Variablen verbietet
+fn forbid(variables: &[usize], assignment: u32) -> Vec<i64> {
+    variables.iter()
+        .enumerate()
+        .map(|(b, &var)| literal(var, assignment >> b & 1 == 0))
+        .collect()
+}
+
+/// Monome der ANF von Ausgangsbit `j` der S-Box (Möbius-Transformation)
+///
+/// Ein Monom ist als Bitmaske der beteiligten Eingangsbits kodiert, 0 ist die Konstante 1.
+fn anf(s_box: &[u8; 16], j: usize) -> Vec<usize> {
+    let mut coefficients: Vec<u8> = s_box.iter().map(|&y| y >> j & 1).collect();
+    for b in 0..4 {
+        for x in 0..16 {
+            if x >> b & 1 == 1 {
+                coefficients[x] ^= coefficients[x ^ (1 << b)];
+            }
+        }
+    }
+    (0..16).filter(|&m| coefficients[m] == 1).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Belegung aller Variablen mit den tatsächlichen Zwischenwerten
+    fn assignment(cipher: &SpnCipher, key_value: u16, plaintexts: &[u16]) -> Vec<bool> {
+        let mut values: Vec<bool> = (0..16).map(|i| bit(key_value, i)).collect();
+        for &plaintext in plaintexts {
+            let mut w = plaintext;
+            for _round in 1..=ROUNDS {
+                let u = w ^ key_value;
+                let v = cipher.apply_sbox(u);
+                values.extend((0..16).map(|i| bit(u, i)));
+                values.extend((0..16).map(|i| bit(v, i)));
+                w = cipher.apply_permutation(v);
+            }
+        }
+        values
+    }
+
+    fn satisfies(clauses: &[Vec<i64>], values: &[bool]) -> bool {
+        clauses.iter().all(|clause| {
+            clause.iter().any(|&literal| values[literal.unsigned_abs() as usize - 1] == (literal > 0))
+        })
+    }
+
+    #[test]
+    fn test_sbox_anf() {
+        let cipher = SpnCipher::new(0);
+        for x in 0..16 {
+            for j in 0..4 {
+                let value = anf(&cipher.s_box, j).iter()
+                    .filter(|&&monomial| x & monomial == monomial)
+                    .count() % 2;
+                assert_eq!(value as u8, cipher.s_box[x] >> j & 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cnf_satisfied_by_real_key() {
+        let key_value = 0x2D55;
+        let cipher = SpnCipher::new(key_value);
+        let plaintexts = [0x1234, 0xBEEF];
+        let pairs: Vec<(u16, u16)> = plaintexts.iter().map(|&p| (p, cipher.encrypt_block(p))).collect();
+
+        let system = EquationSystem::new(&cipher, &pairs);
+        let clauses = system.clauses();
+        let mut values = assignment(&cipher, key_value, &plaintexts);
+        assert_eq!(values.len(), system.variable_count());
+        assert!(satisfies(&clauses, &values));
+
+        // Ein anderer Schlüssel mit denselben Zwischenwerten verletzt das System
+        values[0] = !values[0];
+        assert!(!satisfies(&clauses, &values));
+    }
+
+    #[test]
+    fn test_dimacs_format() {
+        let cipher = SpnCipher::new(0x2D55);
+        let system = EquationSystem::new(&cipher, &[(0x1234, cipher.encrypt_block(0x1234))]);
+        let dimacs = system.to_dimacs();
+
+        // 32 zweistellige XOR (je 2 Klauseln), 48 dreistellige (je 4), 16 S-Boxen (je 64)
+        assert!(dimacs.contains("p cnf 144 1280\n"));
+        assert_eq!(dimacs.lines().filter(|line| line.ends_with(" 0")).count(), 1280);
+    }
+
+    #[test]
+    fn test_anf_format() {
+        let cipher = SpnCipher::new(0x2D55);
+        let system = EquationSystem::new(&cipher, &[(0x0001, cipher.encrypt_block(0x0001))]);
+        let anf = system.to_anf();
+
+        // Klartextbit 0 ist gesetzt, Bit 1 nicht
+        assert!(anf.contains("\nk0 + u1_0 + 1\n"));
+        assert!(anf.contains("\nk1 + u1_1\n"));
+        // 16 + 48 + 16 lineare Gleichungen, 16 S-Boxen mit je 4 Polynomen
+        assert_eq!(anf.lines().filter(|line| !line.starts_with('#')).count(), 144);
+    }
+}
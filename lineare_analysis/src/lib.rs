@@ -0,0 +1,127 @@
+use clap::{Parser, ValueEnum};
+use logging::{Verbosity, debug, info};
+use spn_core::{SpnCipher, parse_key};
+use tool_error::{Context, ToolError};
+
+mod algebraic;
+
+/// SPN (Substitution-Permutation Network) Cipher
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Input: Folge an Hexadezimalziffern, je 4 ein Block (wie ECB)
+    #[arg(short, long, help = "Eingabe als Hexadezimalziffern (je 4 ein Block)")]
+    input: String,
+    
+    /// Schlüssel: 16 Bit / 4 Hexadezimalziffern (für jede Runde gleich)
+    #[arg(short, long, required_unless_present = "ciphertext", help = "Schlüssel als 16 Bit / 4 Hexadezimalziffern")]
+    key: Option<String>,
+    
+    /// Output-Datei für ver- bzw. entschlüsselte Daten
+    #[arg(short, long, help = "Ausgabedatei für ver- bzw. entschlüsselte Daten")]
+    output: String,
+
+    /// Entschlüsseln statt verschlüsseln (inverse S-Box und Permutation, Runden rückwärts)
+    #[arg(short, long, help = "Eingabe entschlüsseln statt verschlüsseln")]
+    decrypt: bool,
+
+    /// Verschlüsselungsgleichungen für die Klartextblöcke der Eingabe exportieren
+    #[arg(short, long, value_enum, conflicts_with = "decrypt", help = "Gleichungssystem exportieren (anf oder cnf)")]
+    export: Option<ExportFormat>,
+
+    /// Zu den Klartextblöcken gehörende Kryptotextblöcke; ohne sie wird mit dem Schlüssel verschlüsselt
+    #[arg(short, long, requires = "export", help = "Kryptotext als Hexadezimalziffern (bei --export)")]
+    ciphertext: Option<String>,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+/// Ausgabeformat des Gleichungssystems
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ExportFormat {
+    /// Polynome in algebraischer Normalform über GF(2)
+    Anf,
+    /// Klauseln im DIMACS-CNF-Format für SAT-Solver
+    Cnf,
+}
+
+/// Stellt die Verschlüsselungsgleichungen für Klartext-Kryptotext-Paare auf
+///
+/// Ohne Kryptotext werden die Klartextblöcke mit dem bekannten Schlüssel
+/// verschlüsselt, etwa um einen Solver an einem Beispiel zu testen.
+fn export(plaintext: &str, ciphertext: Option<&str>, key: Option<u16>, format: ExportFormat) -> Result<String, String> {
+    let plaintexts = codec::hex::decode_words(plaintext)
+        .map_err(|e| format!("Klartext enthält ungültige Hexadezimalzeichen: {}", e))?;
+    
+    let cipher = SpnCipher::new(key.unwrap_or(0));
+    let ciphertexts = match ciphertext {
+        Some(ciphertext) => codec::hex::decode_words(ciphertext)
+            .map_err(|e| format!("Kryptotext enthält ungültige Hexadezimalzeichen: {}", e))?,
+        None => spn_core::pairs::encrypt_all(&cipher, &plaintexts),
+    };
+    
+    if plaintexts.is_empty() || plaintexts.len() != ciphertexts.len() {
+        return Err(format!("Anzahl der Klartextblöcke ({}) und Kryptotextblöcke ({}) muss gleich und größer 0 sein",
+                           plaintexts.len(), ciphertexts.len()));
+    }
+    
+    let pairs: Vec<(u16, u16)> = plaintexts.into_iter().zip(ciphertexts).collect();
+    debug!("Gleichungssystem für {} Klartext-Kryptotext-Paare", pairs.len());
+    let system = algebraic::EquationSystem::new(&cipher, &pairs);
+    
+    Ok(match format {
+        ExportFormat::Anf => system.to_anf(),
+        ExportFormat::Cnf => system.to_dimacs(),
+    })
+}
+
+/// Startet das Werkzeug mit den übergebenen Kommandozeilenargumenten
+///
+/// Das erste Argument ist wie bei `std::env::args_os` der Programmname;
+/// so lässt sich das Werkzeug auch aus `cryptolab` heraus aufrufen.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+    
+    // Parze und validiere Schlüssel
+    let key = args.key.as_deref().map(parse_key).transpose()
+        .map_err(ToolError::usage)
+        .context("Fehler beim Parsen des Schlüssels")?;
+    
+    // Gleichungssystem statt Chiffrat ausgeben
+    if let Some(format) = args.export {
+        let system = export(&args.input, args.ciphertext.as_deref(), key, format)
+            .map_err(ToolError::data)
+            .context("Exportfehler")?;
+        tool_error::fs::write(&args.output, &system)?;
+        info!("Gleichungssystem ({:?}) nach '{}' geschrieben", format, args.output);
+        return Ok(());
+    }
+    
+    // Erstelle SPN-Cipher; ohne --export ist der Schlüssel durch clap erzwungen
+    let cipher = SpnCipher::new(key.expect("Schlüssel fehlt"));
+    
+    // Ver- bzw. entschlüssele Eingabe
+    let result = if args.decrypt {
+        cipher.decrypt(&args.input)
+    } else {
+        cipher.encrypt(&args.input)
+    };
+    let operation = if args.decrypt { "Entschlüsselungsfehler" } else { "Verschlüsselungsfehler" };
+    let processed = result.map_err(ToolError::data).context(operation)?;
+    debug!("Eingabe: {}", args.input);
+    debug!("Ausgabe: {}", processed);
+    
+    // Schreibe Ausgabe in Datei
+    tool_error::fs::write(&args.output, &processed)?;
+    
+    // KEIN OUTPUT AUF STDOUT - nur stille Verarbeitung, Diagnosen mit -v auf STDERR
+    info!("{} Blöcke {} nach '{}' geschrieben", processed.len() / 4,
+          if args.decrypt { "entschlüsselt" } else { "verschlüsselt" }, args.output);
+    Ok(())
+}
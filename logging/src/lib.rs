@@ -0,0 +1,153 @@
+//! Einheitliche Diagnoseausgabe für alle Werkzeuge
+//!
+//! Jedes Werkzeug bindet [`Verbosity`] mit `#[command(flatten)]` in seine
+//! Argumente ein und ruft zu Beginn [`Verbosity::init`] auf. Diagnosen
+//! laufen über die Makros von `tracing` und landen auf STDERR; die
+//! eigentlichen Ergebnisse bleiben auf STDOUT.
+//!
+//! Ohne Schalter erscheinen Warnungen und Fehler, `-q` lässt nur Fehler
+//! durch, jedes `-v` eine Stufe mehr (info, debug, trace).
+//!
+//! Die Hilfe zu `-v` und `-q` ist deutsch; Werkzeuge mit englischer Hilfe
+//! binden `Verbosity<English>` ein, damit `--help` in einer Sprache bleibt.
+
+use std::io::IsTerminal;
+use std::marker::PhantomData;
+
+use clap::{Arg, ArgAction, ArgMatches, Args, Command, FromArgMatches};
+use tracing::level_filters::LevelFilter;
+
+pub use tracing::{debug, error, info, trace, warn};
+
+/// Sprache der Hilfetexte von [`Verbosity`]
+pub trait HelpLanguage {
+    const VERBOSE: &'static str;
+    const QUIET: &'static str;
+}
+
+/// Deutsche Hilfetexte, der Standard
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct German;
+
+impl HelpLanguage for German {
+    const VERBOSE: &'static str = "Mehr Diagnosen auf STDERR (-v info, -vv debug, -vvv trace)";
+    const QUIET: &'static str = "Nur Fehler auf STDERR ausgeben";
+}
+
+/// Englische Hilfetexte
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct English;
+
+impl HelpLanguage for English {
+    const VERBOSE: &'static str = "More diagnostics on STDERR (-v info, -vv debug, -vvv trace)";
+    const QUIET: &'static str = "Only print errors on STDERR";
+}
+
+/// `-v` und `-q`, mit Hilfetexten in der Sprache `L`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Verbosity<L = German> {
+    verbose: u8,
+    quiet: bool,
+    language: PhantomData<L>,
+}
+
+impl<L: HelpLanguage> Args for Verbosity<L> {
+    fn augment_args(cmd: Command) -> Command {
+        cmd.arg(Arg::new("verbose").short('v').long("verbose").action(ArgAction::Count).help(L::VERBOSE))
+            .arg(Arg::new("quiet").short('q').long("quiet").action(ArgAction::SetTrue).conflicts_with("verbose").help(L::QUIET))
+    }
+
+    fn augment_args_for_update(cmd: Command) -> Command {
+        Self::augment_args(cmd)
+    }
+}
+
+impl<L> FromArgMatches for Verbosity<L> {
+    fn from_arg_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+        Ok(Verbosity { verbose: matches.get_count("verbose"), quiet: matches.get_flag("quiet"), language: PhantomData })
+    }
+
+    fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), clap::Error> {
+        *self = Self::from_arg_matches(matches)?;
+        Ok(())
+    }
+}
+
+impl<L> Verbosity<L> {
+    pub fn is_quiet(&self) -> bool {
+        self.quiet
+    }
+
+    /// Höchste Stufe, die noch ausgegeben wird
+    pub fn level(&self) -> LevelFilter {
+        if self.quiet {
+            return LevelFilter::ERROR;
+        }
+        match self.verbose {
+            0 => LevelFilter::WARN,
+            1 => LevelFilter::INFO,
+            2 => LevelFilter::DEBUG,
+            _ => LevelFilter::TRACE,
+        }
+    }
+
+    /// Richtet die Ausgabe auf STDERR ein
+    ///
+    /// Farben nur auf einem Terminal. Ein zweiter Aufruf im selben Prozess
+    /// (etwa in Tests) ändert nichts.
+    pub fn init(&self) {
+        let _ = tracing_subscriber::fmt()
+            .with_writer(std::io::stderr)
+            .with_ansi(std::io::stderr().is_terminal())
+            .with_max_level(self.level())
+            .with_target(false)
+            .without_time()
+            .try_init();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, Parser};
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        verbosity: Verbosity,
+    }
+
+    fn level(args: &[&str]) -> LevelFilter {
+        Cli::parse_from(std::iter::once("test").chain(args.iter().copied())).verbosity.level()
+    }
+
+    #[test]
+    fn test_levels() {
+        assert_eq!(level(&[]), LevelFilter::WARN);
+        assert_eq!(level(&["-q"]), LevelFilter::ERROR);
+        assert_eq!(level(&["-v"]), LevelFilter::INFO);
+        assert_eq!(level(&["-vv"]), LevelFilter::DEBUG);
+        assert_eq!(level(&["-v", "--verbose", "-v"]), LevelFilter::TRACE);
+    }
+
+    #[test]
+    fn test_quiet_conflicts_with_verbose() {
+        assert!(Cli::try_parse_from(["test", "-q", "-v"]).is_err());
+    }
+
+    #[test]
+    fn test_help_language() {
+        #[derive(Parser)]
+        struct EnglishCli {
+            #[command(flatten)]
+            verbosity: Verbosity<English>,
+        }
+
+        let help = |mut command: Command| command.render_help().to_string();
+        assert!(help(Cli::command()).contains("Nur Fehler auf STDERR ausgeben"));
+        let english = help(EnglishCli::command());
+        assert!(english.contains("Only print errors on STDERR"));
+        assert!(!english.contains("Diagnosen"));
+        assert_eq!(EnglishCli::parse_from(["test", "-vv"]).verbosity.level(), LevelFilter::DEBUG);
+    }
+}
@@ -0,0 +1,210 @@
+//! Dateiformate für Schlüssel und Signaturen
+//!
+//! Alle Dateien sind Text: eine Kennzeile `# mss-<art> 1`, danach Zeilen
+//! der Form `name: wert`. Hashwerte stehen als 64 Hexadezimalziffern, Folgen
+//! von Hashwerten als wiederholte Zeilen mit demselben Namen.
+//!
+//! ```text
+//! # mss-privat 1
+//! hoehe: 10
+//! index: 3
+//! seed: 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08
+//! ```
+//!
+//! Der private Schlüssel ist zustandsbehaftet: `index` ist das nächste
+//! unbenutzte Blatt. Wer eine ältere Kopie der Datei weiterverwendet,
+//! signiert mit einem Einmalschlüssel zweimal und gibt ihn damit preis.
+
+use std::fmt;
+
+use secret::Secret;
+
+use crate::wots::{Hash, LEN, N};
+
+const PRIVATE_MAGIC: &str = "# mss-privat 1";
+const PUBLIC_MAGIC: &str = "# mss-oeffentlich 1";
+const SIGNATURE_MAGIC: &str = "# mss-signatur 1";
+
+/// Privater Schlüssel: Höhe des Baums, nächstes freies Blatt und Seed aller Einmalschlüssel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivateKey {
+    pub height: u32,
+    pub next_index: u64,
+    pub seed: Secret<[u8; N]>,
+}
+
+/// Öffentlicher Schlüssel: die Wurzel des Merkle-Baums
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    pub height: u32,
+    pub root: Hash,
+}
+
+/// Signatur: Blattindex, W-OTS-Signatur und Authentifizierungspfad
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    pub index: u64,
+    pub wots: Vec<Hash>,
+    pub path: Vec<Hash>,
+}
+
+/// Die Zeilen `name: wert` einer Datei nach der Kennzeile
+struct Fields<'a> {
+    fields: Vec<(usize, &'a str, &'a str)>,
+}
+
+impl<'a> Fields<'a> {
+    fn parse(text: &'a str, magic: &str) -> Result<Self, String> {
+        let mut lines = text.lines().map(str::trim).enumerate();
+        if lines.next().map(|(_, line)| line) != Some(magic) {
+            return Err(format!("Erste Zeile muss '{}' lauten", magic));
+        }
+
+        let mut fields = Vec::new();
+        for (index, line) in lines {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, value) = line.split_once(':')
+                .ok_or_else(|| format!("Zeile {}: erwartet 'name: wert', gefunden '{}'", index + 1, line))?;
+            fields.push((index + 1, name.trim(), value.trim()));
+        }
+        Ok(Fields { fields })
+    }
+
+    fn all<'s>(&'s self, name: &'s str) -> impl Iterator<Item = (usize, &'a str)> + 's {
+        self.fields.iter().filter(move |(_, field, _)| *field == name).map(|&(line, _, value)| (line, value))
+    }
+
+    fn one(&self, name: &str) -> Result<(usize, &'a str), String> {
+        let mut values = self.all(name);
+        match (values.next(), values.next()) {
+            (Some(value), None) => Ok(value),
+            (None, _) => Err(format!("Feld '{}' fehlt", name)),
+            (Some(_), Some((line, _))) => Err(format!("Zeile {}: Feld '{}' doppelt", line, name)),
+        }
+    }
+
+    fn number<T: std::str::FromStr>(&self, name: &str) -> Result<T, String> {
+        let (line, value) = self.one(name)?;
+        value.parse().map_err(|_| format!("Zeile {}: '{}' ist keine gültige Zahl", line, value))
+    }
+
+    fn hash(&self, name: &str) -> Result<Hash, String> {
+        let (line, value) = self.one(name)?;
+        parse_hash(line, value)
+    }
+
+    fn hashes(&self, name: &str) -> Result<Vec<Hash>, String> {
+        self.all(name).map(|(line, value)| parse_hash(line, value)).collect()
+    }
+}
+
+fn parse_hash(line: usize, value: &str) -> Result<Hash, String> {
+    codec::hex::decode(value).ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| format!("Zeile {}: erwartet {} Hexadezimalziffern", line, 2 * N))
+}
+
+fn check_height(height: u32) -> Result<u32, String> {
+    if !(1..=crate::MAX_HEIGHT).contains(&height) {
+        return Err(format!("Höhe {} liegt nicht zwischen 1 und {}", height, crate::MAX_HEIGHT));
+    }
+    Ok(height)
+}
+
+impl PrivateKey {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let fields = Fields::parse(text, PRIVATE_MAGIC)?;
+        Ok(PrivateKey {
+            height: check_height(fields.number("hoehe")?)?,
+            next_index: fields.number("index")?,
+            seed: Secret::new(fields.hash("seed")?),
+        })
+    }
+
+    /// Anzahl der noch möglichen Signaturen
+    pub fn remaining(&self) -> u64 {
+        (1u64 << self.height).saturating_sub(self.next_index)
+    }
+}
+
+impl PublicKey {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let fields = Fields::parse(text, PUBLIC_MAGIC)?;
+        Ok(PublicKey { height: check_height(fields.number("hoehe")?)?, root: fields.hash("wurzel")? })
+    }
+}
+
+impl Signature {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let fields = Fields::parse(text, SIGNATURE_MAGIC)?;
+        let signature = Signature { index: fields.number("index")?, wots: fields.hashes("wots")?, path: fields.hashes("pfad")? };
+        if signature.wots.len() != LEN {
+            return Err(format!("Signatur enthält {} statt {} W-OTS-Werte", signature.wots.len(), LEN));
+        }
+        Ok(signature)
+    }
+}
+
+impl fmt::Display for PrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", PRIVATE_MAGIC)?;
+        writeln!(f, "hoehe: {}", self.height)?;
+        writeln!(f, "index: {}", self.next_index)?;
+        writeln!(f, "seed: {}", codec::hex::encode(&*self.seed))
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", PUBLIC_MAGIC)?;
+        writeln!(f, "hoehe: {}", self.height)?;
+        writeln!(f, "wurzel: {}", codec::hex::encode(&self.root))
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", SIGNATURE_MAGIC)?;
+        writeln!(f, "index: {}", self.index)?;
+        for value in &self.wots {
+            writeln!(f, "wots: {}", codec::hex::encode(value))?;
+        }
+        for node in &self.path {
+            writeln!(f, "pfad: {}", codec::hex::encode(node))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_private_key_roundtrip() {
+        let key = PrivateKey { height: 4, next_index: 3, seed: Secret::new([0xAB; N]) };
+        let text = key.to_string();
+        assert!(text.starts_with("# mss-privat 1\nhoehe: 4\nindex: 3\nseed: abab"));
+        assert_eq!(PrivateKey::parse(&text).unwrap(), key);
+        assert_eq!(key.remaining(), 13);
+    }
+
+    #[test]
+    fn test_signature_roundtrip() {
+        let signature = Signature { index: 5, wots: vec![[1; N]; LEN], path: vec![[2; N], [3; N]] };
+        assert_eq!(Signature::parse(&signature.to_string()).unwrap(), signature);
+
+        let truncated = signature.to_string().replacen("wots: ", "# wots: ", 1);
+        assert!(Signature::parse(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        assert!(PublicKey::parse("# mss-privat 1\nhoehe: 4\n").is_err());
+        assert!(PublicKey::parse("# mss-oeffentlich 1\nhoehe: 4\nwurzel: 1234\n").is_err());
+        assert!(PublicKey::parse("# mss-oeffentlich 1\nhoehe: 0\nwurzel: 1234\n").is_err());
+        assert!(PrivateKey::parse("# mss-privat 1\nhoehe: 4\nhoehe: 5\nindex: 0\n").is_err());
+    }
+}
@@ -0,0 +1,221 @@
+//! mss: Merkle-Signaturverfahren (zustandsbehaftete hashbasierte Signaturen)
+//!
+//! Ein Schlüsselpaar besteht aus 2^h Winternitz-Einmalschlüsseln (Modul
+//! [`wots`]), deren öffentliche Schlüssel die Blätter eines Merkle-Baums
+//! bilden (Modul [`merkle`]). Öffentlicher Schlüssel ist die Wurzel. Eine
+//! Signatur enthält den Blattindex, die Einmalsignatur und den
+//! Authentifizierungspfad vom Blatt zur Wurzel; die Sicherheit beruht nur
+//! auf der Hashfunktion, nicht auf Faktorisierung oder diskreten Logarithmen.
+//!
+//! Jedes Blatt darf nur einmal signieren. Der private Schlüssel merkt sich
+//! deshalb das nächste freie Blatt und wird vor der Ausgabe jeder Signatur
+//! mit dem erhöhten Index zurückgeschrieben. Alle Einmalschlüssel werden aus
+//! einem Seed abgeleitet; beim Signieren wird der Baum neu berechnet, was bei
+//! den hier zulässigen Höhen nur Sekunden dauert.
+
+use clap::{Parser, Subcommand};
+use csprng::RngArgs;
+use logging::{Verbosity, info, warn};
+use rand_core::RngCore;
+use secret::Secret;
+use sha2::{Digest, Sha256};
+use tool_error::{Context, ExitStatus, ToolError};
+
+pub mod keys;
+pub mod merkle;
+pub mod wots;
+
+use keys::{PrivateKey, PublicKey, Signature};
+use merkle::{MerkleTree, leaf_hash, root_from_path};
+use wots::{Hash, N};
+
+/// Größte Baumhöhe; 2^16 Blätter kosten beim Signieren etwa 70 Millionen Hashes
+pub const MAX_HEIGHT: u32 = 16;
+
+#[derive(Parser)]
+#[command(name = "mss")]
+#[command(about = "Merkle-Signaturverfahren mit Winternitz-Einmalsignaturen (SHA-256, w = 16)")]
+#[command(version = "1.0")]
+#[command(after_help = "Exit-Codes beim Prüfen: 0 Signatur gültig, 1 Signatur ungültig, 65/74 Schlüssel, Signatur oder Datei fehlerhaft")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Schlüsselpaar mit 2^HOEHE Einmalschlüsseln erzeugen
+    Keygen {
+        /// Höhe des Merkle-Baums, erlaubt 2^HOEHE Signaturen
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..=MAX_HEIGHT as i64), help = "Höhe des Merkle-Baums")]
+        height: u32,
+
+        /// Datei für den privaten Schlüssel
+        #[arg(long, help = "Ausgabedatei für den privaten Schlüssel")]
+        private: String,
+
+        /// Datei für den öffentlichen Schlüssel
+        #[arg(long, help = "Ausgabedatei für den öffentlichen Schlüssel")]
+        public: String,
+
+        #[command(flatten)]
+        rng: RngArgs,
+    },
+    /// Datei signieren; der private Schlüssel wird dabei fortgeschrieben
+    Sign {
+        #[arg(short, long, help = "Privater Schlüssel, wird mit neuem Index überschrieben")]
+        key: String,
+
+        #[arg(short, long, help = "Zu signierende Datei")]
+        input: String,
+
+        #[arg(short, long, help = "Ausgabedatei für die Signatur")]
+        output: String,
+    },
+    /// Signatur einer Datei prüfen
+    Verify {
+        #[arg(short, long, help = "Öffentlicher Schlüssel")]
+        key: String,
+
+        #[arg(short, long, help = "Signierte Datei")]
+        input: String,
+
+        #[arg(short, long, help = "Signaturdatei")]
+        signature: String,
+    },
+}
+
+fn message_hash(message: &[u8]) -> Hash {
+    Sha256::digest(message).into()
+}
+
+/// Alle Blätter des Baums zu einem Seed
+fn build_tree(seed: &[u8], height: u32) -> MerkleTree {
+    let leaves = (0..1u32 << height)
+        .map(|leaf| leaf_hash(&wots::public_key(&wots::secret_key(seed, leaf), leaf)))
+        .collect();
+    MerkleTree::new(leaves)
+}
+
+pub fn keygen(height: u32, rng: &mut impl RngCore) -> (PrivateKey, PublicKey) {
+    let mut seed = Secret::new([0u8; N]);
+    rng.fill_bytes(&mut *seed);
+    let root = build_tree(&*seed, height).root();
+    (PrivateKey { height, next_index: 0, seed }, PublicKey { height, root })
+}
+
+/// Signiert mit dem nächsten freien Blatt und erhöht den Index des Schlüssels
+pub fn sign(key: &mut PrivateKey, message: &[u8]) -> tool_error::Result<Signature> {
+    if key.remaining() == 0 {
+        return Err(ToolError::failed(format!("Alle {} Einmalschlüssel sind verbraucht", 1u64 << key.height)));
+    }
+    let index = key.next_index;
+    key.next_index += 1;
+
+    let leaf = index as u32;
+    let secret_key = wots::secret_key(&*key.seed, leaf);
+    let wots = wots::sign(&secret_key, leaf, &message_hash(message));
+    let path = build_tree(&*key.seed, key.height).authentication_path(index as usize);
+    Ok(Signature { index, wots, path })
+}
+
+pub fn verify(key: &PublicKey, message: &[u8], signature: &Signature) -> bool {
+    if signature.index >= 1u64 << key.height || signature.path.len() != key.height as usize {
+        return false;
+    }
+    let leaf = signature.index as u32;
+    let public_key = wots::public_key_from_signature(&signature.wots, leaf, &message_hash(message));
+    root_from_path(leaf_hash(&public_key), signature.index as usize, &signature.path) == key.root
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+
+    match args.command {
+        Command::Keygen { height, private, public, rng } => {
+            info!("Berechne {} Einmalschlüssel", 1u64 << height);
+            let (private_key, public_key) = keygen(height, &mut rng.rng());
+            tool_error::fs::write(&private, private_key.to_string())?;
+            tool_error::fs::write(&public, public_key.to_string())?;
+            println!("Wurzel: {}", codec::hex::encode(&public_key.root));
+            Ok(())
+        }
+        Command::Sign { key, input, output } => {
+            let mut private_key = PrivateKey::parse(&tool_error::fs::read_to_string(&key)?)
+                .map_err(ToolError::data)
+                .context("Fehler beim Laden des privaten Schlüssels")?;
+            let message = tool_error::fs::read(&input)?;
+            let signature = sign(&mut private_key, &message)?;
+
+            // Erst den verbrauchten Index sichern, dann die Signatur herausgeben
+            tool_error::fs::write(&key, private_key.to_string())?;
+            tool_error::fs::write(&output, signature.to_string())?;
+            info!("Blatt {} verwendet, {} Signaturen übrig", signature.index, private_key.remaining());
+            if private_key.remaining() == 0 {
+                warn!("Letzter Einmalschlüssel verbraucht, für weitere Signaturen ein neues Schlüsselpaar erzeugen");
+            }
+            Ok(())
+        }
+        Command::Verify { key, input, signature } => {
+            let public_key = PublicKey::parse(&tool_error::fs::read_to_string(&key)?)
+                .map_err(ToolError::data)
+                .context("Fehler beim Laden des öffentlichen Schlüssels")?;
+            let signature = Signature::parse(&tool_error::fs::read_to_string(&signature)?)
+                .map_err(ToolError::data)
+                .context("Fehler beim Laden der Signatur")?;
+            let message = tool_error::fs::read(&input)?;
+
+            if verify(&public_key, &message, &signature) {
+                println!("Signatur gültig (Blatt {})", signature.index);
+                Ok(())
+            } else {
+                println!("Signatur ungültig");
+                Err(ToolError::Silent(ExitStatus::Failure))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::{Algorithm, Drbg};
+
+    #[test]
+    fn test_sign_verify_all_leaves() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"mss");
+        let (mut private_key, public_key) = keygen(2, &mut rng);
+
+        for index in 0..4 {
+            let signature = sign(&mut private_key, b"Nachricht").unwrap();
+            assert_eq!(signature.index, index);
+            assert_eq!(signature.path.len(), 2);
+            assert!(verify(&public_key, b"Nachricht", &signature));
+            assert!(!verify(&public_key, b"Nachricht!", &signature));
+        }
+        assert!(sign(&mut private_key, b"Nachricht").is_err());
+    }
+
+    #[test]
+    fn test_rejects_moved_signature() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"mss");
+        let (mut private_key, public_key) = keygen(3, &mut rng);
+        let mut signature = sign(&mut private_key, b"Nachricht").unwrap();
+
+        signature.index = 1;
+        assert!(!verify(&public_key, b"Nachricht", &signature));
+        signature.index = 8;
+        assert!(!verify(&public_key, b"Nachricht", &signature));
+        signature.index = 0;
+        signature.path.pop();
+        assert!(!verify(&public_key, b"Nachricht", &signature));
+    }
+}
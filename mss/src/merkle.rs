@@ -0,0 +1,92 @@
+//! Merkle-Baum über die öffentlichen W-OTS-Schlüssel
+//!
+//! Ein Blatt ist der Hash eines öffentlichen Einmalschlüssels, ein innerer
+//! Knoten der Hash seiner beiden Kinder. Blätter und Knoten erhalten
+//! verschiedene Präfixe (0x00 und 0x01), damit sich ein Knoten nicht als
+//! Blatt ausgeben lässt. Die Wurzel ist der öffentliche Schlüssel des
+//! Signaturverfahrens; der Authentifizierungspfad enthält für jede Ebene den
+//! Geschwisterknoten und verbindet ein Blatt mit der Wurzel.
+
+use sha2::{Digest, Sha256};
+
+use crate::wots::Hash;
+
+pub fn leaf_hash(public_key: &[Hash]) -> Hash {
+    let mut hasher = Sha256::new().chain_update([0x00]);
+    for part in public_key {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    Sha256::new().chain_update([0x01]).chain_update(left).chain_update(right).finalize().into()
+}
+
+/// Vollständiger Baum, Ebene 0 sind die Blätter, die letzte Ebene die Wurzel
+pub struct MerkleTree {
+    levels: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    /// Baut den Baum; die Anzahl der Blätter muss eine Zweierpotenz sein
+    pub fn new(leaves: Vec<Hash>) -> Self {
+        assert!(leaves.len().is_power_of_two(), "Anzahl der Blätter muss eine Zweierpotenz sein");
+        let mut levels = vec![leaves];
+        while levels.last().expect("mindestens eine Ebene").len() > 1 {
+            let next = levels.last().expect("mindestens eine Ebene")
+                .chunks_exact(2)
+                .map(|pair| node_hash(&pair[0], &pair[1]))
+                .collect();
+            levels.push(next);
+        }
+        MerkleTree { levels }
+    }
+
+    pub fn root(&self) -> Hash {
+        self.levels.last().expect("mindestens eine Ebene")[0]
+    }
+
+    /// Geschwisterknoten auf dem Weg von Blatt `index` zur Wurzel, unten zuerst
+    pub fn authentication_path(&self, index: usize) -> Vec<Hash> {
+        self.levels[..self.levels.len() - 1].iter()
+            .enumerate()
+            .map(|(height, level)| level[(index >> height) ^ 1])
+            .collect()
+    }
+}
+
+/// Berechnet die Wurzel aus einem Blatt und seinem Authentifizierungspfad
+pub fn root_from_path(leaf: Hash, index: usize, path: &[Hash]) -> Hash {
+    path.iter().enumerate().fold(leaf, |node, (height, sibling)| {
+        if (index >> height) & 1 == 0 {
+            node_hash(&node, sibling)
+        } else {
+            node_hash(sibling, &node)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths_lead_to_root() {
+        let leaves: Vec<Hash> = (0..8u8).map(|i| [i; 32]).collect();
+        let tree = MerkleTree::new(leaves.clone());
+        for (index, &leaf) in leaves.iter().enumerate() {
+            let path = tree.authentication_path(index);
+            assert_eq!(path.len(), 3);
+            assert_eq!(root_from_path(leaf, index, &path), tree.root());
+            assert_ne!(root_from_path(leaf, index ^ 1, &path), tree.root());
+        }
+    }
+
+    #[test]
+    fn test_single_leaf() {
+        let tree = MerkleTree::new(vec![[7; 32]]);
+        assert_eq!(tree.root(), [7; 32]);
+        assert!(tree.authentication_path(0).is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+//! Winternitz-Einmalsignatur (W-OTS) mit w = 16 über SHA-256
+//!
+//! Der Hashwert der Nachricht wird in 64 Ziffern zur Basis 16 zerlegt, dazu
+//! kommen 3 Ziffern einer Prüfsumme. Für jede Ziffer b gibt es eine Kette
+//! x, F(x), F(F(x)), … der Länge 15: Der geheime Schlüssel ist der Anfang,
+//! der öffentliche das Ende jeder Kette, die Signatur das Glied an Position b.
+//! Wer die Signatur fälschen will, müsste eine Ziffer verkleinern, also F
+//! umkehren; die Prüfsumme sorgt dafür, dass dann eine andere Ziffer
+//! wachsen muss. Jeder Schlüssel darf nur eine einzige Nachricht signieren.
+//!
+//! Jede Anwendung von F hängt Blattindex, Kette und Position an, damit
+//! Glieder verschiedener Ketten und Schlüssel nie dieselbe Funktion nutzen.
+
+use sha2::{Digest, Sha256};
+
+/// Länge eines Hashwerts in Bytes
+pub const N: usize = 32;
+/// Winternitz-Parameter: Ziffern zur Basis W
+pub const W: usize = 16;
+/// Ziffern des Nachrichtenhashs
+pub const LEN1: usize = 2 * N;
+/// Ziffern der Prüfsumme: höchstens 64 · 15 = 960 < 16³
+pub const LEN2: usize = 3;
+/// Anzahl der Ketten
+pub const LEN: usize = LEN1 + LEN2;
+
+pub type Hash = [u8; N];
+
+/// Kettenfunktion F für Glied `step` der Kette `chain` im Schlüssel `leaf`
+fn chain_step(leaf: u32, chain: usize, step: usize, value: &Hash) -> Hash {
+    Sha256::new()
+        .chain_update(leaf.to_be_bytes())
+        .chain_update((chain as u16).to_be_bytes())
+        .chain_update([step as u8])
+        .chain_update(value)
+        .finalize()
+        .into()
+}
+
+/// Geht in Kette `chain` von Glied `start` aus `steps` Glieder weiter
+fn chain(leaf: u32, chain: usize, start: usize, steps: usize, value: &Hash) -> Hash {
+    (start..start + steps).fold(*value, |value, step| chain_step(leaf, chain, step, &value))
+}
+
+/// Ziffern zur Basis 16 von Nachrichtenhash und Prüfsumme
+pub fn digits(message_hash: &Hash) -> [usize; LEN] {
+    let mut digits = [0; LEN];
+    for (i, byte) in message_hash.iter().enumerate() {
+        digits[2 * i] = (byte >> 4) as usize;
+        digits[2 * i + 1] = (byte & 0xF) as usize;
+    }
+    let checksum: usize = digits[..LEN1].iter().map(|digit| W - 1 - digit).sum();
+    for i in 0..LEN2 {
+        digits[LEN1 + i] = (checksum >> (4 * (LEN2 - 1 - i))) & 0xF;
+    }
+    digits
+}
+
+/// Geheimer Schlüssel des Blatts `leaf`, abgeleitet aus dem Seed
+pub fn secret_key(seed: &[u8], leaf: u32) -> Vec<Hash> {
+    (0..LEN)
+        .map(|i| {
+            Sha256::new()
+                .chain_update(seed)
+                .chain_update(leaf.to_be_bytes())
+                .chain_update((i as u16).to_be_bytes())
+                .finalize()
+                .into()
+        })
+        .collect()
+}
+
+/// Öffentlicher Schlüssel: die Enden aller Ketten
+pub fn public_key(secret_key: &[Hash], leaf: u32) -> Vec<Hash> {
+    secret_key.iter().enumerate().map(|(i, sk)| chain(leaf, i, 0, W - 1, sk)).collect()
+}
+
+pub fn sign(secret_key: &[Hash], leaf: u32, message_hash: &Hash) -> Vec<Hash> {
+    digits(message_hash).iter()
+        .zip(secret_key)
+        .enumerate()
+        .map(|(i, (&digit, sk))| chain(leaf, i, 0, digit, sk))
+        .collect()
+}
+
+/// Rechnet aus einer Signatur den öffentlichen Schlüssel aus, unter dem sie gültig wäre
+pub fn public_key_from_signature(signature: &[Hash], leaf: u32, message_hash: &Hash) -> Vec<Hash> {
+    digits(message_hash).iter()
+        .zip(signature)
+        .enumerate()
+        .map(|(i, (&digit, sig))| chain(leaf, i, digit, W - 1 - digit, sig))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digits_and_checksum() {
+        let digits = digits(&[0; N]);
+        assert!(digits[..LEN1].iter().all(|&digit| digit == 0));
+        // Prüfsumme 64 · 15 = 960 = 0x3C0
+        assert_eq!(digits[LEN1..], [0x3, 0xC, 0x0]);
+        assert_eq!(super::digits(&[0xFF; N])[LEN1..], [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_sign_verify() {
+        let sk = secret_key(b"seed", 7);
+        let pk = public_key(&sk, 7);
+        let message: Hash = Sha256::digest(b"Nachricht").into();
+        let signature = sign(&sk, 7, &message);
+        assert_eq!(public_key_from_signature(&signature, 7, &message), pk);
+
+        let other: Hash = Sha256::digest(b"Andere Nachricht").into();
+        assert_ne!(public_key_from_signature(&signature, 7, &other), pk);
+        assert_ne!(public_key_from_signature(&signature, 8, &message), pk);
+    }
+}
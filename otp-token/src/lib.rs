@@ -0,0 +1,263 @@
+//! otp-token: Einmalpasswörter nach HOTP (RFC 4226) und TOTP (RFC 6238)
+//!
+//! Ein HOTP-Code ist ein auf wenige Dezimalstellen gekürzter HMAC über einen
+//! Zähler. TOTP nimmt als Zähler die Anzahl der Zeitschritte (meist 30 s)
+//! seit der Unix-Epoche. Das gemeinsame Geheimnis wird wie in
+//! Authenticator-Apps als Base32 angegeben.
+
+use clap::{Parser, Subcommand, ValueEnum};
+use csprng::RngArgs;
+use hmac::{Hmac, Mac};
+use logging::{Verbosity, info};
+use rand_core::RngCore;
+use secret::{Secret, ct};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tool_error::{Context, ExitStatus, ToolError};
+
+/// Hashfunktion des HMAC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Algorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl Algorithm {
+    fn mac(self, key: &[u8], message: &[u8]) -> Vec<u8> {
+        fn compute<M: Mac + hmac::digest::KeyInit>(key: &[u8], message: &[u8]) -> Vec<u8> {
+            let mut mac = <M as Mac>::new_from_slice(key).expect("HMAC akzeptiert jede Schlüssellänge");
+            mac.update(message);
+            mac.finalize().into_bytes().to_vec()
+        }
+
+        match self {
+            Algorithm::Sha1 => compute::<Hmac<Sha1>>(key, message),
+            Algorithm::Sha256 => compute::<Hmac<Sha256>>(key, message),
+            Algorithm::Sha512 => compute::<Hmac<Sha512>>(key, message),
+        }
+    }
+}
+
+/// HOTP-Wert für einen Zähler, `digits` Dezimalstellen (RFC 4226, Abschnitt 5.3)
+pub fn hotp(key: &[u8], counter: u64, digits: u32, algorithm: Algorithm) -> u32 {
+    let mac = algorithm.mac(key, &counter.to_be_bytes());
+
+    // Dynamische Kürzung: das letzte Nibble wählt vier Bytes, ohne Vorzeichenbit
+    let offset = (mac[mac.len() - 1] & 0x0f) as usize;
+    let value = u32::from_be_bytes(mac[offset..offset + 4].try_into().expect("vier Bytes")) & 0x7fff_ffff;
+    value % 10u32.pow(digits)
+}
+
+/// TOTP-Zähler: Anzahl der Zeitschritte seit der Unix-Epoche (RFC 6238, Abschnitt 4)
+pub fn time_step(unix_time: u64, period: u64) -> u64 {
+    unix_time / period
+}
+
+/// Code mit führenden Nullen, wie ihn der Benutzer eintippt
+pub fn format_code(code: u32, digits: u32) -> String {
+    format!("{:0width$}", code, width = digits as usize)
+}
+
+/// Sucht den Zähler, zu dem der Code passt
+///
+/// Probiert die Zähler in der angegebenen Reihenfolge; `None`, wenn keiner passt.
+pub fn verify(key: &[u8], code: &str, counters: impl IntoIterator<Item = u64>, digits: u32, algorithm: Algorithm) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != digits as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    counters.into_iter().find(|&counter| ct::eq(format_code(hotp(key, counter, digits, algorithm), digits).as_bytes(), code.as_bytes()))
+}
+
+#[derive(Parser)]
+#[command(name = "otp-token")]
+#[command(about = "Einmalpasswörter nach HOTP (RFC 4226) und TOTP (RFC 6238)")]
+#[command(version = "1.0")]
+#[command(after_help = "Ohne --counter wird TOTP mit der aktuellen Zeit verwendet.\n\
+Exit-Codes beim Prüfen: 0 Code gültig, 1 Code ungültig, 65 Geheimnis nicht lesbar")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Neues zufälliges Geheimnis als Base32 ausgeben
+    Secret {
+        /// Länge in Bytes, RFC 4226 empfiehlt mindestens 20
+        #[arg(short, long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(16..=64), help = "Länge des Geheimnisses in Bytes (16-64)")]
+        bytes: u32,
+
+        #[command(flatten)]
+        rng: RngArgs,
+    },
+    /// Aktuellen Code ausgeben
+    Generate {
+        #[command(flatten)]
+        token: TokenArgs,
+    },
+    /// Code prüfen
+    Verify {
+        /// Der eingegebene Code
+        code: String,
+
+        /// Zugelassene Abweichung in Zeitschritten (TOTP) bzw. Zählerwerten (HOTP)
+        #[arg(short, long, default_value_t = 1, help = "Toleranz: TOTP ±w Zeitschritte, HOTP w Zählerwerte voraus")]
+        window: u64,
+
+        #[command(flatten)]
+        token: TokenArgs,
+    },
+}
+
+#[derive(clap::Args)]
+struct TokenArgs {
+    /// Gemeinsames Geheimnis als Base32
+    #[arg(short, long, required_unless_present = "secret_file", conflicts_with = "secret_file", help = "Gemeinsames Geheimnis als Base32")]
+    secret: Option<String>,
+
+    /// Datei mit dem Geheimnis als Base32
+    #[arg(long, help = "Datei mit dem Geheimnis als Base32")]
+    secret_file: Option<String>,
+
+    /// HOTP mit diesem Zähler statt TOTP
+    #[arg(short, long, help = "HOTP mit diesem Zählerstand statt TOTP")]
+    counter: Option<u64>,
+
+    /// Länge eines TOTP-Zeitschritts in Sekunden
+    #[arg(short, long, default_value_t = 30, value_parser = clap::value_parser!(u64).range(1..), help = "Zeitschritt in Sekunden (TOTP)")]
+    period: u64,
+
+    /// Unix-Zeit für TOTP (Standard: jetzt)
+    #[arg(long, help = "Unix-Zeit statt der aktuellen Uhrzeit (TOTP)")]
+    time: Option<u64>,
+
+    /// Anzahl der Dezimalstellen
+    #[arg(short, long, default_value_t = 6, value_parser = clap::value_parser!(u32).range(6..=8), help = "Anzahl der Stellen (6-8)")]
+    digits: u32,
+
+    /// Hashfunktion des HMAC
+    #[arg(short, long, value_enum, default_value_t = Algorithm::Sha1)]
+    algorithm: Algorithm,
+}
+
+impl TokenArgs {
+    fn key(&self) -> tool_error::Result<Secret<Vec<u8>>> {
+        let text = match (&self.secret, &self.secret_file) {
+            (Some(secret), _) => Secret::new(secret.clone()),
+            (None, Some(path)) => Secret::new(tool_error::fs::read_to_string(path)?),
+            (None, None) => unreachable!("clap verlangt --secret oder --secret-file"),
+        };
+        let key = codec::base32::decode(&text).context("Geheimnis ist kein gültiges Base32")?;
+        if key.is_empty() {
+            return Err(ToolError::data("Geheimnis ist leer"));
+        }
+        Ok(Secret::new(key))
+    }
+
+    /// TOTP-Zeitschritt der angegebenen oder der aktuellen Zeit
+    fn current_step(&self) -> u64 {
+        let now = self.time.unwrap_or_else(|| {
+            SystemTime::now().duration_since(UNIX_EPOCH).expect("Systemuhr nach 1970").as_secs()
+        });
+        time_step(now, self.period)
+    }
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+
+    match args.command {
+        Command::Secret { bytes, rng } => {
+            let mut key = Secret::new(vec![0u8; bytes as usize]);
+            rng.rng().fill_bytes(&mut key);
+            println!("{}", codec::base32::encode(&key));
+            Ok(())
+        }
+        Command::Generate { token } => {
+            let key = token.key()?;
+            let counter = token.counter.unwrap_or_else(|| token.current_step());
+            info!("Zähler {}", counter);
+            println!("{}", format_code(hotp(&key, counter, token.digits, token.algorithm), token.digits));
+            Ok(())
+        }
+        Command::Verify { code, window, token } => {
+            let key = token.key()?;
+            let matched = match token.counter {
+                // Der Zähler des Tokens kann dem des Servers nur vorauslaufen
+                Some(counter) => verify(&key, &code, counter..=counter.saturating_add(window), token.digits, token.algorithm)
+                    .inspect(|&found| println!("Code gültig, nächster Zähler: {}", found + 1)),
+                None => {
+                    let step = token.current_step();
+                    let steps = step.saturating_sub(window)..=step.saturating_add(window);
+                    verify(&key, &code, steps, token.digits, token.algorithm)
+                        .inspect(|&found| println!("Code gültig (Zeitschritt {:+})", found as i64 - step as i64))
+                }
+            };
+
+            match matched {
+                Some(_) => Ok(()),
+                None => {
+                    println!("Code ungültig");
+                    Err(ToolError::Silent(ExitStatus::Failure))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED_SHA1: &[u8] = b"12345678901234567890";
+    const SEED_SHA256: &[u8] = b"12345678901234567890123456789012";
+    const SEED_SHA512: &[u8] = b"1234567890123456789012345678901234567890123456789012345678901234";
+
+    #[test]
+    fn test_hotp_rfc4226() {
+        // RFC 4226, Anhang D
+        let expected = [755224, 287082, 359152, 969429, 338314, 254676, 287922, 162583, 399871, 520489];
+        for (counter, code) in expected.into_iter().enumerate() {
+            assert_eq!(hotp(SEED_SHA1, counter as u64, 6, Algorithm::Sha1), code, "Zähler {}", counter);
+        }
+    }
+
+    #[test]
+    fn test_totp_rfc6238() {
+        // RFC 6238, Anhang B
+        let vectors: [(u64, [&str; 3]); 6] = [
+            (59, ["94287082", "46119246", "90693936"]),
+            (1111111109, ["07081804", "68084774", "25091201"]),
+            (1111111111, ["14050471", "67062674", "99943326"]),
+            (1234567890, ["89005924", "91819424", "93441116"]),
+            (2000000000, ["69279037", "90698825", "38618901"]),
+            (20000000000, ["65353130", "77737706", "47863826"]),
+        ];
+        let keys = [(SEED_SHA1, Algorithm::Sha1), (SEED_SHA256, Algorithm::Sha256), (SEED_SHA512, Algorithm::Sha512)];
+        for (time, codes) in vectors {
+            for ((key, algorithm), code) in keys.into_iter().zip(codes) {
+                assert_eq!(format_code(hotp(key, time_step(time, 30), 8, algorithm), 8), code, "{} {:?}", time, algorithm);
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        assert_eq!(verify(SEED_SHA1, "969429", 0..=5, 6, Algorithm::Sha1), Some(3));
+        assert_eq!(verify(SEED_SHA1, " 969429\n", 3..=3, 6, Algorithm::Sha1), Some(3));
+        assert_eq!(verify(SEED_SHA1, "969429", 4..=9, 6, Algorithm::Sha1), None);
+        assert_eq!(verify(SEED_SHA1, "96942", 0..=5, 6, Algorithm::Sha1), None);
+        assert_eq!(verify(SEED_SHA1, "+69429", 0..=5, 6, Algorithm::Sha1), None);
+    }
+}
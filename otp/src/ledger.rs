@@ -0,0 +1,152 @@
+//! Bookkeeping of consumed pad bytes.
+//!
+//! The ledger is a sidecar text file next to the pad (`<pad>.used`) with one
+//! half-open byte range `start end` per line. Ranges are kept sorted and merged.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use tool_error::ToolError;
+
+/// Set of pad byte ranges that must never be used again.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Ledger {
+    used: Vec<Range<u64>>,
+}
+
+impl Ledger {
+    /// Path of the sidecar file belonging to a pad.
+    pub fn path_for(pad: &Path) -> PathBuf {
+        let mut name = pad.as_os_str().to_owned();
+        name.push(".used");
+        PathBuf::from(name)
+    }
+
+    /// Loads the ledger of a pad; a missing sidecar file means nothing is used yet.
+    pub fn load(pad: &Path) -> tool_error::Result<Self> {
+        let path = Self::path_for(pad);
+        if !path.exists() {
+            return Ok(Ledger::default());
+        }
+
+        Self::parse(&tool_error::fs::read_to_string(&path)?)
+    }
+
+    /// Parses the sidecar format.
+    pub fn parse(content: &str) -> tool_error::Result<Self> {
+        let mut ledger = Ledger::default();
+
+        for (line_number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let bounds: Vec<u64> = line.split_whitespace()
+                .map(|part| part.parse::<u64>())
+                .collect::<Result<_, _>>()
+                .map_err(|_| ToolError::data(format!("Ledger line {}: invalid range '{}'", line_number + 1, line)))?;
+            match bounds[..] {
+                [start, end] if start < end => ledger.mark(start..end),
+                _ => return Err(ToolError::data(format!("Ledger line {}: invalid range '{}'", line_number + 1, line))),
+            }
+        }
+
+        Ok(ledger)
+    }
+
+    /// Writes the ledger back to the sidecar file of the pad.
+    pub fn save(&self, pad: &Path) -> tool_error::Result<()> {
+        let path = Self::path_for(pad);
+        let content: String = self.used.iter()
+            .map(|range| format!("{} {}\n", range.start, range.end))
+            .collect();
+        tool_error::fs::write(&path, content)
+    }
+
+    /// Returns the first used range that overlaps the given range, if any.
+    pub fn overlap(&self, range: &Range<u64>) -> Option<&Range<u64>> {
+        self.used.iter().find(|used| used.start < range.end && range.start < used.end)
+    }
+
+    /// Marks a range as used, merging it with adjacent or overlapping ranges.
+    pub fn mark(&mut self, range: Range<u64>) {
+        if range.is_empty() {
+            return;
+        }
+
+        self.used.push(range);
+        self.used.sort_by_key(|r| r.start);
+
+        let mut merged: Vec<Range<u64>> = Vec::with_capacity(self.used.len());
+        for range in self.used.drain(..) {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        self.used = merged;
+    }
+
+    /// Finds the lowest offset at which `len` unused bytes are available.
+    pub fn first_free(&self, len: u64, pad_len: u64) -> Option<u64> {
+        let mut start = 0;
+        for used in &self.used {
+            if used.start >= start + len {
+                break;
+            }
+            start = start.max(used.end);
+        }
+        (start + len <= pad_len).then_some(start)
+    }
+
+    /// Total number of consumed bytes.
+    pub fn used_bytes(&self) -> u64 {
+        self.used.iter().map(|range| range.end - range.start).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mark_merges_ranges() {
+        let mut ledger = Ledger::default();
+        ledger.mark(10..20);
+        ledger.mark(0..5);
+        ledger.mark(5..10);
+        ledger.mark(30..40);
+        assert_eq!(ledger.used, vec![0..20, 30..40]);
+        assert_eq!(ledger.used_bytes(), 30);
+    }
+
+    #[test]
+    fn test_overlap_detection() {
+        let mut ledger = Ledger::default();
+        ledger.mark(10..20);
+        assert!(ledger.overlap(&(0..10)).is_none());
+        assert!(ledger.overlap(&(20..30)).is_none());
+        assert_eq!(ledger.overlap(&(15..25)), Some(&(10..20)));
+        assert_eq!(ledger.overlap(&(0..100)), Some(&(10..20)));
+    }
+
+    #[test]
+    fn test_first_free_skips_used_ranges() {
+        let mut ledger = Ledger::default();
+        ledger.mark(0..10);
+        ledger.mark(15..20);
+        assert_eq!(ledger.first_free(5, 100), Some(10));
+        assert_eq!(ledger.first_free(6, 100), Some(20));
+        assert_eq!(ledger.first_free(81, 100), None);
+    }
+
+    #[test]
+    fn test_parse_roundtrip() {
+        let ledger = Ledger::parse("0 10\n15 20\n").unwrap();
+        assert_eq!(ledger.used, vec![0..10, 15..20]);
+        assert!(Ledger::parse("10 5").is_err());
+        assert!(Ledger::parse("1 2 3").is_err());
+        assert!(Ledger::parse("a b").is_err());
+    }
+}
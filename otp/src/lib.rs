@@ -0,0 +1,293 @@
+//! One-time pad utility.
+//!
+//! Pads are generated from the operating system's CSPRNG. Every encryption
+//! consumes a fresh range of pad bytes; consumed ranges are recorded in a
+//! sidecar ledger and the tool refuses to use any pad byte twice.
+//!
+//! Ciphertext files start with the magic `OTP1` and the pad offset as a
+//! 64-bit big-endian integer, followed by the XOR-encrypted message.
+
+use clap::{Parser, Subcommand};
+use ledger::Ledger;
+use logging::{English, Verbosity};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use secret::Secret;
+use std::path::Path;
+use tool_error::ToolError;
+
+mod ledger;
+
+/// Magic bytes at the start of every ciphertext file
+const MAGIC: &[u8; 4] = b"OTP1";
+
+/// Header length: magic plus 8-byte pad offset
+const HEADER_LEN: usize = MAGIC.len() + 8;
+
+/// Command-line arguments for the one-time pad program.
+#[derive(Parser, Debug)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Generate a new pad file from a CSPRNG
+    Generate {
+        /// Path of the pad file to create
+        #[arg(short, long, help = "Path of the pad file to create")]
+        pad: String,
+
+        /// Pad size in bytes (suffixes K and M are accepted)
+        #[arg(short, long, value_parser = parse_size, help = "Pad size in bytes, e.g. 4096, 64K or 1M")]
+        size: u64,
+    },
+    /// Encrypt a message with unused pad bytes
+    Encrypt {
+        #[arg(short, long, help = "Path to the pad file")]
+        pad: String,
+
+        #[arg(short, long, help = "Path to the input file")]
+        input: String,
+
+        #[arg(short, long, help = "Path to the output file")]
+        output: String,
+
+        /// Explicit pad offset; by default the first free range is used
+        #[arg(long, help = "Pad offset to use (default: first unused range)")]
+        offset: Option<u64>,
+    },
+    /// Decrypt a ciphertext produced by `encrypt`
+    Decrypt {
+        #[arg(short, long, help = "Path to the pad file")]
+        pad: String,
+
+        #[arg(short, long, help = "Path to the input file")]
+        input: String,
+
+        #[arg(short, long, help = "Path to the output file")]
+        output: String,
+    },
+    /// Show how much of a pad is still unused
+    Status {
+        #[arg(short, long, help = "Path to the pad file")]
+        pad: String,
+    },
+}
+
+/// Runs the tool with the given command-line arguments
+///
+/// The first argument is the program name, as with `std::env::args_os`,
+/// so the tool can also be started from `cryptolab`.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+
+    match cli.command {
+        Command::Generate { pad, size } => generate(Path::new(&pad), size),
+        Command::Encrypt { pad, input, output, offset } => {
+            encrypt_file(Path::new(&pad), &input, &output, offset)
+        }
+        Command::Decrypt { pad, input, output } => decrypt_file(Path::new(&pad), &input, &output),
+        Command::Status { pad } => status(Path::new(&pad)),
+    }
+}
+
+/// Parses a byte count with an optional K (KiB) or M (MiB) suffix.
+fn parse_size(value: &str) -> Result<u64, String> {
+    let (digits, factor) = match value.chars().last() {
+        Some('K' | 'k') => (&value[..value.len() - 1], 1024),
+        Some('M' | 'm') => (&value[..value.len() - 1], 1024 * 1024),
+        _ => (value, 1),
+    };
+
+    let size: u64 = digits.parse().map_err(|_| format!("invalid size '{}'", value))?;
+    match size.checked_mul(factor) {
+        Some(0) => Err("size must be greater than 0".to_string()),
+        Some(size) => Ok(size),
+        None => Err(format!("size '{}' is too large", value)),
+    }
+}
+
+/// Creates a new pad file and an empty ledger.
+fn generate(pad: &Path, size: u64) -> tool_error::Result<()> {
+    if pad.exists() {
+        return Err(ToolError::usage(format!("Pad '{}' already exists, refusing to overwrite it", pad.display())));
+    }
+
+    let mut bytes = Secret::new(vec![0u8; size as usize]);
+    OsRng.fill_bytes(&mut bytes);
+    tool_error::fs::write(pad, &*bytes)?;
+    Ledger::default().save(pad)?;
+
+    println!("Generated {} byte pad: {}", size, pad.display());
+    Ok(())
+}
+
+/// XORs data with pad bytes; the same operation encrypts and decrypts.
+fn xor_with_pad(data: &[u8], pad: &[u8]) -> Vec<u8> {
+    data.iter().zip(pad).map(|(d, p)| d ^ p).collect()
+}
+
+/// Reads a pad and returns the requested slice; both are wiped when dropped.
+fn read_pad_range(pad: &Path, offset: u64, len: u64) -> tool_error::Result<Secret<Vec<u8>>> {
+    let bytes = Secret::new(tool_error::fs::read(pad)?);
+
+    let end = offset.checked_add(len).filter(|&end| end <= bytes.len() as u64)
+        .ok_or_else(|| ToolError::data(format!("Pad range {}..{} exceeds the pad size of {} bytes", offset, offset.saturating_add(len), bytes.len())))?;
+    Ok(Secret::new(bytes[offset as usize..end as usize].to_vec()))
+}
+
+/// Encrypts a message with an unused pad range and records that range.
+fn encrypt_file(pad: &Path, input: &str, output: &str, offset: Option<u64>) -> tool_error::Result<()> {
+    let message = tool_error::fs::read(input)?;
+    let len = message.len() as u64;
+    let pad_len = std::fs::metadata(pad)
+        .map_err(|e| ToolError::io(pad, e))?
+        .len();
+
+    let mut ledger = Ledger::load(pad)?;
+    let offset = match offset {
+        Some(offset) => offset,
+        None => ledger.first_free(len, pad_len)
+            .ok_or_else(|| ToolError::failed(format!("Pad has no unused range of {} bytes left", len)))?,
+    };
+
+    let end = offset.checked_add(len)
+        .ok_or_else(|| ToolError::usage(format!("Pad offset {} plus {} message bytes exceeds the largest possible offset", offset, len)))?;
+    let range = offset..end;
+    if let Some(used) = ledger.overlap(&range) {
+        return Err(ToolError::usage(format!(
+            "Pad bytes {}..{} were already used ({}..{}), refusing to reuse them",
+            range.start, range.end, used.start, used.end
+        )));
+    }
+
+    let key = read_pad_range(pad, offset, len)?;
+    let mut ciphertext = Vec::with_capacity(HEADER_LEN + message.len());
+    ciphertext.extend_from_slice(MAGIC);
+    ciphertext.extend_from_slice(&offset.to_be_bytes());
+    ciphertext.extend(xor_with_pad(&message, &key));
+
+    // Record the range before writing the ciphertext, so a failed write never leads to reuse
+    ledger.mark(range);
+    ledger.save(pad)?;
+    tool_error::fs::write(output, ciphertext)?;
+
+    println!("Encrypted {} bytes using pad offset {}", len, offset);
+    Ok(())
+}
+
+/// Splits a ciphertext file into pad offset and encrypted data.
+fn parse_ciphertext(bytes: &[u8]) -> tool_error::Result<(u64, &[u8])> {
+    if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(ToolError::data("Input is not a one-time pad ciphertext (missing OTP1 header)"));
+    }
+
+    let offset = u64::from_be_bytes(bytes[MAGIC.len()..HEADER_LEN].try_into().expect("8 bytes"));
+    Ok((offset, &bytes[HEADER_LEN..]))
+}
+
+/// Decrypts a ciphertext and marks the pad range as consumed on this side too.
+fn decrypt_file(pad: &Path, input: &str, output: &str) -> tool_error::Result<()> {
+    let bytes = tool_error::fs::read(input)?;
+    let (offset, ciphertext) = parse_ciphertext(&bytes)?;
+    let len = ciphertext.len() as u64;
+
+    let key = read_pad_range(pad, offset, len)?;
+    let message = xor_with_pad(ciphertext, &key);
+
+    let mut ledger = Ledger::load(pad)?;
+    ledger.mark(offset..offset + len);
+    ledger.save(pad)?;
+
+    tool_error::fs::write(output, message)?;
+
+    println!("Decrypted {} bytes using pad offset {}", len, offset);
+    Ok(())
+}
+
+/// Prints the pad usage.
+fn status(pad: &Path) -> tool_error::Result<()> {
+    let pad_len = std::fs::metadata(pad)
+        .map_err(|e| ToolError::io(pad, e))?
+        .len();
+    let used = Ledger::load(pad)?.used_bytes();
+
+    println!("Pad size:  {} bytes", pad_len);
+    println!("Used:      {} bytes", used);
+    println!("Remaining: {} bytes", pad_len.saturating_sub(used));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("4096"), Ok(4096));
+        assert_eq!(parse_size("64K"), Ok(65536));
+        assert_eq!(parse_size("1m"), Ok(1048576));
+        assert!(parse_size("0").is_err());
+        assert!(parse_size("K").is_err());
+    }
+
+    #[test]
+    fn test_xor_roundtrip() {
+        let pad = [0x13, 0x37, 0xFF, 0x00];
+        let ciphertext = xor_with_pad(b"Hey!", &pad);
+        assert_eq!(xor_with_pad(&ciphertext, &pad), b"Hey!");
+    }
+
+    #[test]
+    fn test_parse_ciphertext() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&42u64.to_be_bytes());
+        bytes.extend_from_slice(b"data");
+
+        let (offset, data) = parse_ciphertext(&bytes).unwrap();
+        assert_eq!(offset, 42);
+        assert_eq!(data, b"data");
+        assert!(parse_ciphertext(b"OTP0\0\0\0\0\0\0\0\0").is_err());
+    }
+
+    #[test]
+    fn test_encrypt_refuses_pad_reuse() {
+        let dir = std::env::temp_dir().join(format!("otp-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let pad = dir.join("pad.bin");
+        let message = dir.join("message.txt");
+        let ciphertext = dir.join("message.otp");
+        let decrypted = dir.join("message.out");
+        std::fs::write(&message, b"attack at dawn").unwrap();
+        let _ = std::fs::remove_file(&pad);
+
+        generate(&pad, 32).unwrap();
+        let path = |p: &Path| p.to_str().unwrap().to_string();
+        encrypt_file(&pad, &path(&message), &path(&ciphertext), None).unwrap();
+        decrypt_file(&pad, &path(&ciphertext), &path(&decrypted)).unwrap();
+        assert_eq!(std::fs::read(&decrypted).unwrap(), b"attack at dawn");
+
+        // Explicitly reusing offset 0 must fail, the automatic choice moves on
+        assert!(encrypt_file(&pad, &path(&message), &path(&ciphertext), Some(0)).is_err());
+        encrypt_file(&pad, &path(&message), &path(&ciphertext), None).unwrap();
+        assert_eq!(parse_ciphertext(&std::fs::read(&ciphertext).unwrap()).unwrap().0, 14);
+
+        // 28 of 32 bytes are used now
+        assert!(encrypt_file(&pad, &path(&message), &path(&ciphertext), None).is_err());
+
+        // An offset whose end does not fit in a u64 is refused, not wrapped around
+        let error = encrypt_file(&pad, &path(&message), &path(&ciphertext), Some(u64::MAX - 3)).unwrap_err();
+        assert!(matches!(error, ToolError::Usage(_)));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
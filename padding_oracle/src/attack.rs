@@ -0,0 +1,119 @@
+//! The attacker: decrypts a CBC ciphertext using only the oracle's answers
+//!
+//! CBC decrypts a block as p = D(c) ⊕ c', where c' is the previous block or
+//! the IV. The attacker sends the two-block message (c'', c) with a forged
+//! c'' and learns whether D(c) ⊕ c'' ends in valid padding. Starting with the
+//! last byte, it tries all 256 values until the padding is 0x01; then the
+//! last byte of D(c) is that value ⊕ 0x01. Knowing the last k bytes of D(c),
+//! it sets them to produce k + 1 and searches the next byte, and so on. At
+//! most 256 queries per byte recover D(c), and D(c) ⊕ c' is the plaintext.
+
+use std::fmt;
+
+use logging::debug;
+
+use crate::oracle::Message;
+
+/// No forged byte was accepted, so the oracle does not leak padding errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttackError {
+    pub block: usize,
+    pub byte: usize,
+}
+
+impl fmt::Display for AttackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no forged value accepted for byte {} of block {}: the oracle leaks no padding information", self.byte, self.block)
+    }
+}
+
+impl std::error::Error for AttackError {}
+
+/// Recovers the padded plaintext of `message` block by block
+///
+/// `accepts` is asked whether a forged message would be accepted. A tag is
+/// copied unchanged into every forgery, as the attacker cannot compute one.
+pub fn decrypt(message: &Message, block_size: usize, mut accepts: impl FnMut(&Message) -> bool) -> Result<Vec<u8>, AttackError> {
+    let mut previous = message.iv.as_slice();
+    let mut plaintext = Vec::with_capacity(message.ciphertext.len());
+
+    for (block_index, block) in message.ciphertext.chunks_exact(block_size).enumerate() {
+        let intermediate = decrypt_block(block, block_index, block_size, message.tag.as_ref(), &mut accepts)?;
+        plaintext.extend(intermediate.iter().zip(previous).map(|(i, p)| i ^ p));
+        debug!("Block {} recovered", block_index);
+        previous = block;
+    }
+
+    Ok(plaintext)
+}
+
+/// Finds D(block) one byte at a time, starting with the last
+fn decrypt_block(
+    block: &[u8],
+    block_index: usize,
+    block_size: usize,
+    tag: Option<&Vec<u8>>,
+    accepts: &mut impl FnMut(&Message) -> bool,
+) -> Result<Vec<u8>, AttackError> {
+    let mut intermediate = vec![0u8; block_size];
+    let mut forged = Message { iv: vec![0u8; block_size], ciphertext: block.to_vec(), tag: tag.cloned() };
+
+    for padding in 1..=block_size {
+        let position = block_size - padding;
+        for (forged_byte, known) in forged.iv.iter_mut().zip(&intermediate).skip(position + 1) {
+            *forged_byte = known ^ padding as u8;
+        }
+
+        let found = (0..=255u8).find(|&guess| {
+            forged.iv[position] = guess;
+            if !accepts(&forged) {
+                return false;
+            }
+            // For the last byte, padding 02 02 or 03 03 03 would also be valid;
+            // changing the byte before it only keeps a real 01 valid
+            if padding == 1 && position > 0 {
+                forged.iv[position - 1] ^= 0xff;
+                let confirmed = accepts(&forged);
+                forged.iv[position - 1] ^= 0xff;
+                return confirmed;
+            }
+            true
+        });
+
+        let guess = found.ok_or(AttackError { block: block_index, byte: position })?;
+        intermediate[position] = guess ^ padding as u8;
+    }
+
+    Ok(intermediate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracle::{BLOCK_SIZE, Mode, Oracle};
+    use cipher_modes::Padding;
+    use csprng::{Algorithm, Drbg};
+
+    #[test]
+    fn test_recovers_plaintext() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"padding oracle");
+        let mut oracle = Oracle::new(Mode::Vulnerable, &mut rng);
+        let plaintext = b"The password is hunter2";
+        let message = oracle.encrypt(plaintext, &mut rng);
+
+        let recovered = decrypt(&message, BLOCK_SIZE, |forged| oracle.accepts(forged)).unwrap();
+        assert_eq!(Padding::Pkcs7.unpad(&recovered, BLOCK_SIZE).unwrap(), plaintext);
+        assert!(oracle.queries() <= 2 * BLOCK_SIZE * 257);
+    }
+
+    #[test]
+    fn test_fails_against_authenticated_oracle() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"padding oracle");
+        let mut oracle = Oracle::new(Mode::Authenticated, &mut rng);
+        let message = oracle.encrypt(b"The password is hunter2", &mut rng);
+
+        let result = decrypt(&message, BLOCK_SIZE, |forged| oracle.accepts(forged));
+        assert_eq!(result, Err(AttackError { block: 0, byte: BLOCK_SIZE - 1 }));
+        assert_eq!(oracle.queries(), 256);
+    }
+}
@@ -0,0 +1,83 @@
+//! Demonstrates the CBC padding-oracle attack and how authentication stops it.
+//!
+//! A server encrypts a secret message with AES-128 in CBC mode and PKCS#7
+//! padding. It later accepts or rejects messages sent to it, and rejects
+//! exactly those whose padding is invalid. The attacker intercepts the
+//! ciphertext and, without the key, recovers the whole plaintext from the
+//! accept/reject answers alone (Vaudenay, 2002).
+//!
+//! With `--authenticated` the server appends an HMAC-SHA256 tag
+//! (encrypt-then-MAC) and checks it in constant time before decrypting.
+//! Every forgery is then rejected for the same reason and the attack fails
+//! at the first byte.
+
+use clap::Parser;
+use cipher_modes::Padding;
+use csprng::RngArgs;
+use logging::{English, Verbosity, info};
+use tool_error::ToolError;
+
+pub mod attack;
+pub mod oracle;
+
+use oracle::{BLOCK_SIZE, Mode, Oracle};
+
+/// Command-line arguments for the padding-oracle demo.
+#[derive(Parser, Debug)]
+#[command(name = "padding_oracle", about = "CBC padding-oracle attack demo against AES-128 with PKCS#7 padding")]
+#[command(after_help = "Exit codes: 0 plaintext recovered, 1 attack failed (expected with --authenticated)")]
+struct Cli {
+    /// Secret message the server encrypts
+    #[arg(short, long, default_value = "Transfer 1000 EUR to account DE02 1203 0000 0000 2020 51", help = "Secret message the server encrypts")]
+    message: String,
+
+    /// Read the secret message from a file instead
+    #[arg(short, long, conflicts_with = "message", help = "Read the secret message from a file")]
+    file: Option<String>,
+
+    /// Protect the ciphertext with encrypt-then-MAC
+    #[arg(long, help = "Server checks an HMAC tag in constant time before decrypting")]
+    authenticated: bool,
+
+    #[command(flatten)]
+    rng: RngArgs,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+
+    let secret = match &cli.file {
+        Some(path) => codec::file::read_binary(path)?,
+        None => cli.message.clone().into_bytes(),
+    };
+
+    let mut rng = cli.rng.rng();
+    let mode = if cli.authenticated { Mode::Authenticated } else { Mode::Vulnerable };
+    let mut oracle = Oracle::new(mode, &mut rng);
+    let message = oracle.encrypt(&secret, &mut rng);
+
+    println!("Server mode:  {:?}", oracle.mode());
+    println!("IV:           {}", codec::hex::encode(&message.iv));
+    println!("Ciphertext:   {}", codec::hex::encode(&message.ciphertext));
+    if let Some(tag) = &message.tag {
+        println!("Tag:          {}", codec::hex::encode(tag));
+    }
+    info!("Attacking {} blocks", message.ciphertext.len() / BLOCK_SIZE);
+
+    let result = attack::decrypt(&message, BLOCK_SIZE, |forged| oracle.accepts(forged));
+    println!("Queries:      {}", oracle.queries());
+    let padded = result.map_err(|e| ToolError::failed(e.to_string()))?;
+    let plaintext = Padding::Pkcs7.unpad(&padded, BLOCK_SIZE)
+        .map_err(|_| ToolError::failed("recovered plaintext has invalid padding"))?;
+    println!("Recovered:    {}", String::from_utf8_lossy(&plaintext));
+
+    Ok(())
+}
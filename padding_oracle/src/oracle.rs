@@ -0,0 +1,139 @@
+//! The server side: decrypts messages and tells the sender whether they were accepted
+//!
+//! In [`Mode::Vulnerable`] the server decrypts AES-128-CBC and checks the
+//! PKCS#7 padding; a padding error is the only reason to reject a message,
+//! so every answer leaks one bit about the plaintext. In
+//! [`Mode::Authenticated`] the server uses encrypt-then-MAC: it first checks
+//! an HMAC-SHA256 tag over IV and ciphertext with a constant-time
+//! comparison and only decrypts authentic messages. Forged ciphertexts are
+//! all rejected the same way, before the padding is ever looked at.
+
+use aes_128::Aes128;
+use cipher_modes::{CipherModes, KeyInit, Padding};
+use hmac::{Hmac, Mac};
+use rand_core::{CryptoRng, RngCore};
+use secret::Secret;
+use sha2::Sha256;
+
+/// AES block size in bytes
+pub const BLOCK_SIZE: usize = 16;
+
+/// How the server protects its ciphertexts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// CBC with PKCS#7, rejects messages with invalid padding
+    Vulnerable,
+    /// Encrypt-then-MAC, rejects every message with an invalid tag
+    Authenticated,
+}
+
+/// A message as it travels over the network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub iv: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+    /// HMAC-SHA256 over IV and ciphertext, only in authenticated mode
+    pub tag: Option<Vec<u8>>,
+}
+
+/// The server with its secret keys and a counter of answered queries
+pub struct Oracle {
+    mode: Mode,
+    cipher: Aes128,
+    mac_key: Secret<[u8; 32]>,
+    queries: usize,
+}
+
+impl Oracle {
+    /// Creates a server with fresh random keys
+    pub fn new(mode: Mode, rng: &mut (impl RngCore + CryptoRng)) -> Self {
+        let mut key = Secret::new([0; BLOCK_SIZE]);
+        let mut mac_key = Secret::new([0; 32]);
+        rng.fill_bytes(&mut *key);
+        rng.fill_bytes(&mut *mac_key);
+        let cipher = Aes128::new(&*key).expect("the key has 16 bytes");
+        Oracle { mode, cipher, mac_key, queries: 0 }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Number of messages the server has checked so far
+    pub fn queries(&self) -> usize {
+        self.queries
+    }
+
+    /// Encrypts a message with a random IV, as the legitimate sender would
+    pub fn encrypt(&self, plaintext: &[u8], rng: &mut (impl RngCore + CryptoRng)) -> Message {
+        let mut iv = vec![0; BLOCK_SIZE];
+        rng.fill_bytes(&mut iv);
+        let ciphertext = CipherModes::cbc_encrypt(&self.cipher, &iv, plaintext, Padding::Pkcs7)
+            .expect("IV and block size are valid");
+        let tag = match self.mode {
+            Mode::Vulnerable => None,
+            Mode::Authenticated => Some(self.mac(&iv, &ciphertext).finalize().into_bytes().to_vec()),
+        };
+        Message { iv, ciphertext, tag }
+    }
+
+    /// Whether the server accepts the message; this single bit is all the attacker sees
+    pub fn accepts(&mut self, message: &Message) -> bool {
+        self.queries += 1;
+        if message.iv.len() != BLOCK_SIZE || message.ciphertext.is_empty() {
+            return false;
+        }
+
+        if self.mode == Mode::Authenticated {
+            let Some(tag) = &message.tag else {
+                return false;
+            };
+            // verify_slice compares in constant time
+            if self.mac(&message.iv, &message.ciphertext).verify_slice(tag).is_err() {
+                return false;
+            }
+        }
+        CipherModes::cbc_decrypt(&self.cipher, &message.iv, &message.ciphertext, Padding::Pkcs7).is_ok()
+    }
+
+    fn mac(&self, iv: &[u8], ciphertext: &[u8]) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&*self.mac_key).expect("HMAC accepts any key length");
+        mac.update(iv);
+        mac.update(ciphertext);
+        mac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::{Algorithm, Drbg};
+
+    #[test]
+    fn test_vulnerable_oracle_leaks_padding() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"oracle");
+        let mut oracle = Oracle::new(Mode::Vulnerable, &mut rng);
+        let message = oracle.encrypt(b"sixteen byte msg", &mut rng);
+        assert_eq!(message.ciphertext.len(), 32);
+        assert!(oracle.accepts(&message));
+
+        // The last block decrypts to 16 bytes of 0x10; changing the IV of that block breaks them
+        let mut tampered = message.clone();
+        tampered.ciphertext[15] ^= 0x01;
+        assert!(!oracle.accepts(&tampered));
+        assert_eq!(oracle.queries(), 2);
+    }
+
+    #[test]
+    fn test_authenticated_oracle_rejects_forgeries() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"oracle");
+        let mut oracle = Oracle::new(Mode::Authenticated, &mut rng);
+        let message = oracle.encrypt(b"attack at dawn", &mut rng);
+        assert!(oracle.accepts(&message));
+
+        let mut tampered = message.clone();
+        tampered.iv[0] ^= 0x01;
+        assert!(!oracle.accepts(&tampered));
+        assert!(!oracle.accepts(&Message { tag: None, ..message }));
+    }
+}
@@ -0,0 +1,129 @@
+//! The attacker: correlation power analysis on the first-round S-box output
+//!
+//! For one key byte there are only 256 candidates. For each candidate g the
+//! attacker predicts the leakage HW(S(p_i ⊕ g)) of every recorded trace and
+//! computes Pearson's correlation between that prediction and every sample
+//! of the traces. Only the right guess predicts the SubBytes sample; wrong
+//! guesses yield values that look unrelated because the S-box is nonlinear.
+//! The guess with the highest correlation anywhere in the trace wins
+//! (Brier, Clavier, Olivier, 2004).
+//!
+//! The attacker needs the plaintexts and the traces, but neither the
+//! ciphertexts nor the position of the S-box in the trace.
+
+use aes_128::S_BOX;
+use logging::debug;
+
+use crate::device::{BLOCK_SIZE, hamming_weight};
+
+/// Best guess for one key byte
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Guess {
+    pub key: u8,
+    /// Largest absolute correlation of this guess over all samples
+    pub correlation: f64,
+    /// Sample where that correlation occurs
+    pub point: usize,
+    /// Largest correlation of any other guess, for comparison
+    pub runner_up: f64,
+}
+
+/// Values minus their mean, with the Euclidean norm of the result
+fn centre(values: impl Iterator<Item = f64>) -> (Vec<f64>, f64) {
+    let values: Vec<f64> = values.collect();
+    let mean = values.iter().sum::<f64>() / values.len().max(1) as f64;
+    let centred: Vec<f64> = values.iter().map(|v| v - mean).collect();
+    let norm = centred.iter().map(|v| v * v).sum::<f64>().sqrt();
+    (centred, norm)
+}
+
+fn correlation((x, x_norm): &(Vec<f64>, f64), (y, y_norm): &(Vec<f64>, f64)) -> f64 {
+    if *x_norm == 0.0 || *y_norm == 0.0 {
+        return 0.0;
+    }
+    x.iter().zip(y).map(|(a, b)| a * b).sum::<f64>() / (x_norm * y_norm)
+}
+
+/// Pearson's correlation coefficient, 0 if either series is constant
+pub fn pearson(x: &[f64], y: &[f64]) -> f64 {
+    correlation(&centre(x.iter().copied()), &centre(y.iter().copied()))
+}
+
+/// Traces as one centred column per sample, computed once for all guesses
+pub struct Samples(Vec<(Vec<f64>, f64)>);
+
+impl Samples {
+    pub fn new(traces: &[Vec<f64>]) -> Self {
+        let length = traces.iter().map(Vec::len).min().unwrap_or(0);
+        Samples((0..length).map(|point| centre(traces.iter().map(|trace| trace[point]))).collect())
+    }
+}
+
+/// Recovers key byte `byte` from the plaintexts and the matching samples
+pub fn attack_byte(plaintexts: &[[u8; BLOCK_SIZE]], samples: &Samples, byte: usize) -> Guess {
+    let mut scores: Vec<(u8, f64, usize)> = (0..=255u8)
+        .map(|guess| {
+            let prediction = centre(plaintexts.iter().map(|p| hamming_weight(S_BOX[(p[byte] ^ guess) as usize])));
+            samples.0.iter()
+                .enumerate()
+                .map(|(point, column)| (guess, correlation(&prediction, column).abs(), point))
+                .fold((guess, 0.0, 0), |best, score| if score.1 > best.1 { score } else { best })
+        })
+        .collect();
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let (key, correlation, point) = scores[0];
+    debug!("Byte {:2}: {:02x} with ρ = {:.3} at sample {}, runner-up {:02x} with ρ = {:.3}", byte, key, correlation, point, scores[1].0, scores[1].1);
+    Guess { key, correlation, point, runner_up: scores[1].1 }
+}
+
+/// Recovers all 16 key bytes
+pub fn attack(plaintexts: &[[u8; BLOCK_SIZE]], traces: &[Vec<f64>]) -> Vec<Guess> {
+    let samples = Samples::new(traces);
+    (0..BLOCK_SIZE).map(|byte| attack_byte(plaintexts, &samples, byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::{Device, POINTS_PER_BYTE};
+    use csprng::{Algorithm, Drbg};
+    use rand::Rng;
+    use secret::Secret;
+
+    #[test]
+    fn test_pearson() {
+        assert!((pearson(&[1.0, 2.0, 3.0], &[2.0, 4.0, 6.0]) - 1.0).abs() < 1e-12);
+        assert!((pearson(&[1.0, 2.0, 3.0], &[3.0, 2.0, 1.0]) + 1.0).abs() < 1e-12);
+        assert_eq!(pearson(&[1.0, 1.0, 1.0], &[1.0, 2.0, 3.0]), 0.0);
+    }
+
+    fn record(noise: f64, count: usize, seed: &[u8]) -> ([u8; BLOCK_SIZE], Vec<[u8; BLOCK_SIZE]>, Vec<Vec<f64>>) {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, seed);
+        let key: [u8; BLOCK_SIZE] = rng.r#gen();
+        let device = Device::new(Secret::new(key), noise);
+        let plaintexts: Vec<[u8; BLOCK_SIZE]> = (0..count).map(|_| rng.r#gen()).collect();
+        let traces = plaintexts.iter().map(|p| device.trace(p, &mut rng)).collect();
+        (key, plaintexts, traces)
+    }
+
+    #[test]
+    fn test_recovers_key() {
+        let (key, plaintexts, traces) = record(1.0, 300, b"cpa");
+        let guesses = attack(&plaintexts, &traces);
+        let recovered: Vec<u8> = guesses.iter().map(|g| g.key).collect();
+        assert_eq!(recovered, key);
+        for (byte, guess) in guesses.iter().enumerate() {
+            // The peak sits on the SubBytes sample of that byte
+            assert_eq!(guess.point, POINTS_PER_BYTE * byte + 2);
+            assert!(guess.correlation > guess.runner_up);
+        }
+    }
+
+    #[test]
+    fn test_too_few_traces() {
+        let (key, plaintexts, traces) = record(4.0, 10, b"cpa");
+        let recovered: Vec<u8> = attack(&plaintexts, &traces).iter().map(|g| g.key).collect();
+        assert_ne!(recovered, key);
+    }
+}
@@ -0,0 +1,91 @@
+//! The device: an AES-128 implementation whose power draw is simulated
+//!
+//! CMOS logic draws current when bits flip. A common model for a value
+//! moved over a bus or written to a register is its Hamming weight, the
+//! number of set bits. The simulated device processes the 16 bytes of the
+//! first round one after the other and leaks three values for each byte i:
+//!
+//! | sample  | operation        | leaked value            |
+//! |---------|------------------|-------------------------|
+//! | 3i      | load plaintext   | HW(p_i)                 |
+//! | 3i + 1  | AddRoundKey      | HW(p_i ⊕ k_i)           |
+//! | 3i + 2  | SubBytes         | HW(S(p_i ⊕ k_i))        |
+//!
+//! Every sample carries Gaussian measurement noise with standard deviation
+//! `noise`, measured in bits of Hamming weight.
+
+use aes_128::S_BOX;
+use rand::Rng;
+use secret::Secret;
+
+/// AES-128 key and block size in bytes
+pub const BLOCK_SIZE: usize = 16;
+
+/// Samples recorded for every byte of the state
+pub const POINTS_PER_BYTE: usize = 3;
+
+/// Samples in one trace
+pub const TRACE_LENGTH: usize = BLOCK_SIZE * POINTS_PER_BYTE;
+
+/// Number of set bits, as leaked by the device
+pub fn hamming_weight(byte: u8) -> f64 {
+    f64::from(byte.count_ones())
+}
+
+/// Standard normal sample by the Box-Muller transform
+fn gaussian(rng: &mut impl Rng) -> f64 {
+    // 1 - u lies in (0, 1], so the logarithm stays finite
+    let u: f64 = 1.0 - rng.r#gen::<f64>();
+    let v: f64 = rng.r#gen();
+    (-2.0 * u.ln()).sqrt() * (2.0 * std::f64::consts::PI * v).cos()
+}
+
+/// The device with its secret key and the noise of the measurement setup
+pub struct Device {
+    key: Secret<[u8; BLOCK_SIZE]>,
+    noise: f64,
+}
+
+impl Device {
+    pub fn new(key: Secret<[u8; BLOCK_SIZE]>, noise: f64) -> Self {
+        Device { key, noise }
+    }
+
+    /// Power trace of the first round for one plaintext
+    pub fn trace(&self, plaintext: &[u8; BLOCK_SIZE], rng: &mut impl Rng) -> Vec<f64> {
+        let mut trace = Vec::with_capacity(TRACE_LENGTH);
+        for (&p, &k) in plaintext.iter().zip(self.key.iter()) {
+            let state = p ^ k;
+            for value in [p, state, S_BOX[state as usize]] {
+                trace.push(hamming_weight(value) + self.noise * gaussian(rng));
+            }
+        }
+        trace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::{Algorithm, Drbg};
+
+    #[test]
+    fn test_noiseless_trace() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"device");
+        let device = Device::new(Secret::new([0x2b; BLOCK_SIZE]), 0.0);
+        let trace = device.trace(&[0x00; BLOCK_SIZE], &mut rng);
+        assert_eq!(trace.len(), TRACE_LENGTH);
+        // S(0x2b) = 0xf1
+        assert_eq!(trace[..3], [0.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn test_gaussian() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"gauss");
+        let samples: Vec<f64> = (0..20000).map(|_| gaussian(&mut rng)).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+        assert!(mean.abs() < 0.05, "{}", mean);
+        assert!((variance - 1.0).abs() < 0.05, "{}", variance);
+    }
+}
@@ -0,0 +1,97 @@
+//! A toy side-channel lab: correlation power analysis against AES-128.
+//!
+//! A simulated device encrypts random plaintexts with a secret key. Every
+//! encryption yields a power trace in which the first AES round leaks the
+//! Hamming weight of the values it handles, blurred by measurement noise.
+//! The attacker records the plaintexts and traces and recovers the key one
+//! byte at a time by correlating them with predicted S-box outputs.
+//!
+//! More noise needs more traces: the correlation of the right guess shrinks
+//! with the noise, and the spread of the wrong ones with the square root of
+//! the number of traces.
+
+use aes_128::Aes128;
+use cipher_modes::{BlockCipher, KeyInit};
+use clap::Parser;
+use csprng::RngArgs;
+use logging::{English, Verbosity, info};
+use rand::Rng;
+use secret::Secret;
+use tool_error::ToolError;
+
+pub mod cpa;
+pub mod device;
+
+use device::{BLOCK_SIZE, Device};
+
+/// Command-line arguments for the power-analysis lab.
+#[derive(Parser, Debug)]
+#[command(name = "power_analysis", about = "Correlation power analysis on simulated AES-128 power traces")]
+#[command(after_help = "Exit codes: 0 key recovered, 1 attack failed (too few traces for the noise), 2 invalid key")]
+struct Cli {
+    /// Number of traces the attacker records
+    #[arg(short = 'n', long, default_value_t = 500, value_parser = clap::value_parser!(u64).range(2..), help = "Number of traces to record")]
+    traces: u64,
+
+    /// Standard deviation of the measurement noise
+    #[arg(long, default_value_t = 2.0, help = "Noise standard deviation in bits of Hamming weight")]
+    noise: f64,
+
+    /// Key of the device, random if not given
+    #[arg(short, long, help = "AES-128 key of the device as 32 hex digits (default: random)")]
+    key: Option<String>,
+
+    #[command(flatten)]
+    rng: RngArgs,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+
+    if !(cli.noise.is_finite() && cli.noise >= 0.0) {
+        return Err(ToolError::usage("noise must be a non-negative number"));
+    }
+    let mut rng = cli.rng.rng();
+    let key: Secret<[u8; BLOCK_SIZE]> = match &cli.key {
+        Some(hex) => {
+            let bytes = Secret::new(codec::hex::decode(hex).map_err(|e| ToolError::usage(e.to_string()))?);
+            Secret::new(bytes.as_slice().try_into().map_err(|_| ToolError::usage(format!("key must be {} bytes, not {}", BLOCK_SIZE, bytes.len())))?)
+        }
+        None => Secret::new(rng.r#gen()),
+    };
+
+    // One known plaintext-ciphertext pair to check the recovered key against
+    let known: [u8; BLOCK_SIZE] = rng.r#gen();
+    let mut known_ciphertext = known;
+    Aes128::new(&*key).expect("the key has 16 bytes").encrypt_block(&mut known_ciphertext);
+
+    info!("Recording {} traces with noise σ = {}", cli.traces, cli.noise);
+    let device = Device::new(key, cli.noise);
+    let plaintexts: Vec<[u8; BLOCK_SIZE]> = (0..cli.traces).map(|_| rng.r#gen()).collect();
+    let traces: Vec<Vec<f64>> = plaintexts.iter().map(|p| device.trace(p, &mut rng)).collect();
+
+    let guesses = cpa::attack(&plaintexts, &traces);
+    println!("Traces: {}, noise σ = {}", cli.traces, cli.noise);
+    println!("Byte  Guess  Correlation  Runner-up  Sample");
+    for (byte, guess) in guesses.iter().enumerate() {
+        println!("{:4}     {:02x}  {:11.3}  {:9.3}  {:6}", byte, guess.key, guess.correlation, guess.runner_up, guess.point);
+    }
+
+    let recovered: Vec<u8> = guesses.iter().map(|g| g.key).collect();
+    println!("Recovered key: {}", codec::hex::encode(&recovered));
+    let mut check = known;
+    Aes128::new(&recovered).expect("one guess per key byte").encrypt_block(&mut check);
+    if check != known_ciphertext {
+        return Err(ToolError::failed("recovered key does not match the known ciphertext, record more traces"));
+    }
+    println!("The key encrypts the known plaintext correctly.");
+    Ok(())
+}
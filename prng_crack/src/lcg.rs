@@ -0,0 +1,166 @@
+//! Linear congruential generators and how to predict them
+//!
+//! An LCG steps its state by x' = (a·x + c) mod m. Consecutive differences
+//! t_i = x_(i+1) - x_i satisfy t_(i+1) ≡ a·t_i, so every
+//! t_(i+2)·t_i - t_(i+1)² is a multiple of m. The gcd of a few of them is m
+//! itself; a and c then follow from two linear congruences. Five outputs
+//! give two multiples of m, every further output one more.
+//!
+//! Generators such as `java.util.Random` hide the low bits of their state.
+//! If the parameters are public, the missing bits can simply be searched:
+//! every candidate is stepped forward and compared with the next outputs.
+
+use crypto_math::{extended_gcd, mod_inverse};
+use num_bigint::{BigInt, BigUint};
+use num_traits::{ToPrimitive, Zero};
+
+/// Multiplier, increment and modulus of an LCG
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Params {
+    pub multiplier: u64,
+    pub increment: u64,
+    pub modulus: u64,
+}
+
+impl Params {
+    /// The example `rand()` from the C standard, used by many old libcs
+    pub const POSIX: Params = Params { multiplier: 1_103_515_245, increment: 12345, modulus: 1 << 31 };
+    /// Park and Miller's "minimal standard" generator
+    pub const MINSTD: Params = Params { multiplier: 16807, increment: 0, modulus: (1 << 31) - 1 };
+    /// `java.util.Random`, which outputs bits 47 to 16 of its state
+    pub const JAVA: Params = Params { multiplier: 0x5_deec_e66d, increment: 0xb, modulus: 1 << 48 };
+
+    /// The state following `state`
+    pub fn step(&self, state: u64) -> u64 {
+        ((u128::from(self.multiplier) * u128::from(state) + u128::from(self.increment)) % u128::from(self.modulus)) as u64
+    }
+}
+
+/// An LCG together with its current state
+#[derive(Debug, Clone)]
+pub struct Lcg {
+    params: Params,
+    state: u64,
+}
+
+impl Lcg {
+    pub fn new(params: Params, seed: u64) -> Self {
+        Lcg { params, state: seed % params.modulus }
+    }
+
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Steps the generator and returns the new state
+    pub fn next_state(&mut self) -> u64 {
+        self.state = self.params.step(self.state);
+        self.state
+    }
+}
+
+/// `value mod m` in [0, m)
+fn reduce(value: &BigInt, m: &BigInt) -> BigUint {
+    (((value % m) + m) % m).to_biguint().expect("remainder is not negative")
+}
+
+/// Fewest outputs [`recover`] accepts
+///
+/// Four outputs fit some LCG whatever they are, and with five the gcd is
+/// often still a multiple of the modulus.
+pub const MIN_OUTPUTS: usize = 6;
+
+/// Recovers the parameters from consecutive full outputs
+///
+/// `None` if there are fewer than [`MIN_OUTPUTS`] or no LCG explains them.
+/// The more outputs, the less likely the gcd is a multiple of the modulus.
+pub fn recover(outputs: &[u64]) -> Option<Params> {
+    if outputs.len() < MIN_OUTPUTS {
+        return None;
+    }
+    let x: Vec<BigInt> = outputs.iter().map(|&v| BigInt::from(v)).collect();
+    let t: Vec<BigInt> = x.windows(2).map(|w| &w[1] - &w[0]).collect();
+    let modulus = t
+        .windows(3)
+        .map(|w| &w[2] * &w[0] - &w[1] * &w[1])
+        .fold(BigInt::zero(), |gcd, multiple| extended_gcd(&gcd, &multiple).0);
+    if modulus <= BigInt::from(*outputs.iter().max()?) {
+        return None;
+    }
+
+    // a = t_(i+1) / t_i for the first difference that is invertible mod m
+    let m = modulus.to_biguint()?;
+    let multiplier = t.windows(2).find_map(|w| Some(reduce(&w[1], &modulus) * mod_inverse(&reduce(&w[0], &modulus), &m)? % &m))?;
+    let increment = reduce(&(&x[1] - BigInt::from(multiplier.clone()) * &x[0]), &modulus);
+    let params = Params { multiplier: multiplier.to_u64()?, increment: increment.to_u64()?, modulus: m.to_u64()? };
+
+    let mut lcg = Lcg::new(params, outputs[0]);
+    outputs[1..].iter().all(|&output| lcg.next_state() == output).then_some(params)
+}
+
+/// Recovers the full state behind outputs that drop the lowest `shift` bits
+///
+/// Tries all 2^`shift` completions of the first output and keeps the first
+/// one whose successors match every further output. Returns the state
+/// belonging to the last output, from which the attacker steps on.
+pub fn recover_truncated(params: Params, shift: u32, outputs: &[u64]) -> Option<u64> {
+    assert!(shift <= 24, "Searching more than 2^24 hidden states takes too long");
+    let (&first, rest) = outputs.split_first()?;
+    (0..1u64 << shift).find_map(|low| {
+        let mut state = (first << shift) | low;
+        if state >= params.modulus {
+            return None;
+        }
+        for &output in rest {
+            state = params.step(state);
+            if state >> shift != output {
+                return None;
+            }
+        }
+        Some(state)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outputs(params: Params, seed: u64, count: usize) -> Vec<u64> {
+        let mut lcg = Lcg::new(params, seed);
+        (0..count).map(|_| lcg.next_state()).collect()
+    }
+
+    #[test]
+    fn test_known_sequence() {
+        // minstd from seed 1: 16807, 16807², ...
+        assert_eq!(outputs(Params::MINSTD, 1, 3), [16807, 282_475_249, 1_622_650_073]);
+    }
+
+    #[test]
+    fn test_recover() {
+        for (params, seed) in [(Params::POSIX, 0xC0FFEE), (Params::MINSTD, 42), (Params::JAVA, 0x1234_5678_9abc)] {
+            assert_eq!(recover(&outputs(params, seed, 10)), Some(params), "{:?}", params);
+        }
+        let custom = Params { multiplier: 1_664_525, increment: 1_013_904_223, modulus: 1 << 32 };
+        assert_eq!(recover(&outputs(custom, 7, 10)), Some(custom));
+    }
+
+    #[test]
+    fn test_recover_needs_enough_outputs() {
+        assert_eq!(recover(&outputs(Params::POSIX, 1, MIN_OUTPUTS - 1)), None);
+        assert_eq!(recover(&outputs(Params::POSIX, 1, MIN_OUTPUTS)), Some(Params::POSIX));
+        assert_eq!(recover(&[]), None);
+        // Doubling is an LCG for any modulus, so there is nothing to find
+        assert_eq!(recover(&[1, 2, 4, 8, 16, 32, 64, 128]), None);
+        // The digits of π are not generated by an LCG
+        assert_eq!(recover(&[3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7]), None);
+    }
+
+    #[test]
+    fn test_recover_truncated() {
+        let states = outputs(Params::JAVA, 0xdead_beef_cafe, 4);
+        let observed: Vec<u64> = states.iter().map(|s| s >> 16).collect();
+        assert_eq!(recover_truncated(Params::JAVA, 16, &observed), Some(states[3]));
+        assert_eq!(recover_truncated(Params::JAVA, 16, &[observed[0], observed[2]]), None);
+    }
+}
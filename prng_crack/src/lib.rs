@@ -0,0 +1,286 @@
+//! Predicts non-cryptographic random number generators from their output.
+//!
+//! Fast generators made for simulations and games are not designed to keep
+//! their state secret. A handful of outputs is enough to reconstruct it, and
+//! from then on every "random" value, including any key, nonce or token drawn
+//! from the generator, is known to the attacker. Keys must come from a
+//! cryptographically secure generator such as the `csprng` DRBGs.
+//!
+//! `lcg` attacks linear congruential generators: it either cracks outputs
+//! given on the command line or runs a demo against a well-known generator
+//! seeded at random. `mt` clones the Mersenne Twister from 624 outputs; its
+//! demo attacks a key drawn with `--drbg insecure-mt19937`, the insecure
+//! mode that every keygen tool offers through `csprng`. `lfsr` breaks the
+//! LFSR stream cipher of the `gf` crate with a known-plaintext attack: 2L
+//! bits of keystream reveal a register of length L through Berlekamp-Massey.
+
+use clap::{Parser, Subcommand, ValueEnum};
+use csprng::{Algorithm, Drbg, RngArgs};
+use gf::lfsr::{self, Lfsr};
+use logging::{English, Verbosity, info};
+use rand::Rng;
+use rand_core::RngCore;
+use std::path::PathBuf;
+use tool_error::ToolError;
+
+pub mod lcg;
+pub mod mt;
+
+use lcg::{Lcg, Params};
+
+/// Well-known LCGs for the demo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Generator {
+    /// The example rand() of the C standard, full 31-bit outputs
+    Posix,
+    /// Park-Miller minimal standard, full 31-bit outputs
+    Minstd,
+    /// java.util.Random, 32-bit outputs that hide 16 state bits
+    Java,
+}
+
+impl Generator {
+    fn params(self) -> Params {
+        match self {
+            Generator::Posix => Params::POSIX,
+            Generator::Minstd => Params::MINSTD,
+            Generator::Java => Params::JAVA,
+        }
+    }
+
+    /// Low state bits the generator drops from each output
+    fn shift(self) -> u32 {
+        match self {
+            Generator::Java => 16,
+            Generator::Posix | Generator::Minstd => 0,
+        }
+    }
+}
+
+/// Command-line arguments for the PRNG cracker.
+#[derive(Parser, Debug)]
+#[command(name = "prng_crack", about = "Recovers the state of non-cryptographic PRNGs and predicts their future outputs")]
+#[command(after_help = "Exit codes: 0 generator predicted, 1 state not recovered, 2 invalid arguments")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Crack a linear congruential generator
+    Lcg {
+        /// Consecutive outputs to crack instead of running the demo
+        #[arg(help = "Consecutive full outputs of an unknown LCG (default: demo)", conflicts_with = "generator")]
+        outputs: Vec<u64>,
+
+        /// Generator the demo attacks
+        #[arg(short, long, value_enum, default_value = "posix", help = "Generator to attack in the demo")]
+        generator: Generator,
+
+        /// Outputs the attacker observes in the demo
+        #[arg(short, long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(2..=1000), help = "Number of outputs the attacker observes")]
+        observe: u64,
+
+        /// Outputs to predict
+        #[arg(short, long, default_value_t = 5, help = "Number of future outputs to predict")]
+        predict: u64,
+
+        #[command(flatten)]
+        rng: RngArgs,
+    },
+    /// Clone a Mersenne Twister (MT19937) from 624 outputs
+    Mt {
+        /// File with consecutive 32-bit outputs instead of running the demo
+        #[arg(short, long, help = "File with at least 624 consecutive outputs as decimal numbers (default: demo)")]
+        input: Option<PathBuf>,
+
+        /// Seed of the victim's generator in the demo
+        #[arg(long, value_name = "SEED", help = "Seed of the victim's generator (default: random)")]
+        seed: Option<String>,
+
+        /// Outputs to predict
+        #[arg(short, long, default_value_t = 5, help = "Number of future outputs to predict")]
+        predict: u64,
+    },
+    /// Break an LFSR stream cipher from known plaintext (Berlekamp-Massey)
+    Lfsr {
+        /// Length of the secret register
+        #[arg(short, long, default_value_t = 32, value_parser = clap::value_parser!(u32).range(2..=i64::from(lfsr::MAX_LENGTH)), help = "Length of the victim's LFSR in bits")]
+        length: u32,
+
+        /// Message the victim encrypts
+        #[arg(short, long, default_value = DEFAULT_MESSAGE, help = "Message the victim encrypts")]
+        message: String,
+
+        /// Bytes at the start of the message the attacker knows
+        #[arg(short, long, help = "Known plaintext bytes (default: just enough, 2L bits)")]
+        known: Option<usize>,
+
+        #[command(flatten)]
+        rng: RngArgs,
+    },
+}
+
+/// Default message; the attacker knows how its mail header starts
+const DEFAULT_MESSAGE: &str = "From: alice@example.org\\nTo: bob@example.org\\nSubject: Meeting\\n\\nThe new server password is hunter2.";
+
+/// Prints predicted next to actual outputs and checks that they agree
+fn compare(predicted: &[u64], actual: &[u64]) -> tool_error::Result<()> {
+    println!("Predicted   Actual");
+    for (p, a) in predicted.iter().zip(actual) {
+        println!("{:>10} {:>10}{}", p, a, if p == a { "" } else { "  wrong" });
+    }
+    if predicted != actual {
+        return Err(ToolError::failed("predictions do not match the generator"));
+    }
+    println!("All {} predictions match: any key drawn from this generator is known to the attacker.", predicted.len());
+    Ok(())
+}
+
+fn run_lcg(outputs: &[u64], generator: Generator, observe: usize, predict: usize, rng: &RngArgs) -> tool_error::Result<()> {
+    if !outputs.is_empty() {
+        let params = lcg::recover(outputs).ok_or_else(|| ToolError::failed(format!("no LCG fits these outputs, give at least {} consecutive full outputs", lcg::MIN_OUTPUTS)))?;
+        println!("Recovered: a = {}, c = {}, m = {}", params.multiplier, params.increment, params.modulus);
+        let mut lcg = Lcg::new(params, outputs[outputs.len() - 1]);
+        let next: Vec<String> = (0..predict).map(|_| lcg.next_state().to_string()).collect();
+        println!("Next:      {}", next.join(" "));
+        return Ok(());
+    }
+
+    let params = generator.params();
+    let shift = generator.shift();
+    let seed = rng.rng().gen_range(1..params.modulus);
+    let mut victim = Lcg::new(params, seed);
+    let mut outputs = || victim.next_state() >> shift;
+    let observed: Vec<u64> = (0..observe).map(|_| outputs()).collect();
+    let actual: Vec<u64> = (0..predict).map(|_| outputs()).collect();
+
+    println!("Generator: {:?}, a = {}, c = {}, m = {}, {} hidden bits per output", generator, params.multiplier, params.increment, params.modulus, shift);
+    println!("Observed:  {} outputs", observe);
+    let (recovered, state) = if shift == 0 {
+        // The attacker does not need to know the parameters
+        let recovered = lcg::recover(&observed).ok_or_else(|| ToolError::failed("parameters not recovered, observe more outputs"))?;
+        println!("Recovered: a = {}, c = {}, m = {}", recovered.multiplier, recovered.increment, recovered.modulus);
+        (recovered, observed[observed.len() - 1])
+    } else {
+        // The parameters are public, only the hidden bits are missing
+        info!("Searching 2^{} completions of the first output", shift);
+        let state = lcg::recover_truncated(params, shift, &observed).ok_or_else(|| ToolError::failed("no state fits the outputs"))?;
+        println!("Recovered: state {:#x}", state);
+        (params, state)
+    };
+
+    let mut attacker = Lcg::new(recovered, state);
+    let predicted: Vec<u64> = (0..predict).map(|_| attacker.next_state() >> shift).collect();
+    compare(&predicted, &actual)
+}
+
+fn run_mt(input: Option<&PathBuf>, seed: Option<&str>, predict: usize) -> tool_error::Result<()> {
+    if let Some(path) = input {
+        let text = codec::file::read_text(path)?;
+        let outputs = text.split_whitespace().map(str::parse).collect::<Result<Vec<u32>, _>>().map_err(|e| ToolError::data(format!("{}: {}", path.display(), e)))?;
+        let mut clone = mt::recover(&outputs).ok_or_else(|| ToolError::failed(format!("need at least {} consecutive MT19937 outputs", csprng::mt19937::N)))?;
+        let next: Vec<String> = (0..predict).map(|_| clone.next_u32().to_string()).collect();
+        println!("Next: {}", next.join(" "));
+        return Ok(());
+    }
+
+    // The victim uses the same generator as `rsa-keygen --drbg insecure-mt19937`
+    let mut victim = match seed {
+        Some(seed) => Drbg::from_seed(Algorithm::Mt19937, seed.as_bytes()),
+        None => Drbg::from_entropy(Algorithm::Mt19937),
+    };
+    let tokens: Vec<u32> = (0..csprng::mt19937::N).map(|_| victim.next_u32()).collect();
+    let mut key = [0u8; 16];
+    victim.fill_bytes(&mut key);
+    let actual: Vec<u64> = (0..predict).map(|_| u64::from(victim.next_u32())).collect();
+
+    println!("Observed:  {} session tokens, e.g. {} {} {} ...", tokens.len(), tokens[0], tokens[1], tokens[2]);
+    let mut clone = mt::recover(&tokens).ok_or_else(|| ToolError::failed("state not recovered"))?;
+    let mut predicted_key = [0u8; 16];
+    clone.fill_bytes(&mut predicted_key);
+    println!("Victim's next key: {}", codec::hex::encode(&key));
+    println!("Predicted key:     {}", codec::hex::encode(&predicted_key));
+    if predicted_key != key {
+        return Err(ToolError::failed("predicted key is wrong"));
+    }
+    let predicted: Vec<u64> = (0..predict).map(|_| u64::from(clone.next_u32())).collect();
+    compare(&predicted, &actual)
+}
+
+fn run_lfsr(length: u32, message: &str, known: Option<usize>, rng: &RngArgs) -> tool_error::Result<()> {
+    let message = message.replace("\\n", "\n");
+    let known = known.unwrap_or((2 * length as usize).div_ceil(8)).min(message.len());
+
+    // The victim's key: a random register of full length and its start state
+    let mut rng = rng.rng();
+    let connection = 1 | (1 << length) | (rng.r#gen::<u128>() & ((1 << length) - 2));
+    let initial = rng.r#gen::<u128>() | 1;
+    let ciphertext = Lfsr::new(connection, length, initial).apply(message.as_bytes());
+    println!("Victim:    LFSR of length {}, connection polynomial {:#x}", length, connection);
+    println!("Known:     {:?} ({} bits of keystream)", String::from_utf8_lossy(&message.as_bytes()[..known]), 8 * known);
+
+    let keystream: Vec<u8> = ciphertext.iter().zip(message.as_bytes()).take(known)
+        .flat_map(|(c, p)| (0..8).rev().map(move |i| (c ^ p) >> i & 1))
+        .collect();
+    let mut clone = Lfsr::from_sequence(&keystream).ok_or_else(|| ToolError::failed("keystream is not generated by a short LFSR"))?;
+    println!("Recovered: LFSR of length {}, connection polynomial {:#x}", clone.length(), clone.connection());
+    let plaintext = clone.apply(&ciphertext);
+    println!("Decrypted: {:?}", String::from_utf8_lossy(&plaintext));
+    if plaintext != message.as_bytes() {
+        return Err(ToolError::failed(format!("decryption failed, {} known bits are not enough for a register of length {}", 8 * known, length)));
+    }
+    println!("The whole message is decrypted from {} known bytes.", known);
+    Ok(())
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+
+    match cli.command {
+        Command::Lcg { outputs, generator, observe, predict, rng } => run_lcg(&outputs, generator, observe as usize, predict as usize, &rng),
+        Command::Mt { input, seed, predict } => run_mt(input.as_ref(), seed.as_deref(), predict as usize),
+        Command::Lfsr { length, message, known, rng } => run_lfsr(length, &message, known, &rng),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_demo() {
+        for generator in ["posix", "minstd", "java"] {
+            let args = ["prng_crack", "lcg", "--generator", generator, "--seed", "prng"];
+            assert!(run(args).is_ok(), "{}", generator);
+        }
+    }
+
+    #[test]
+    fn test_mt_demo() {
+        assert!(run(["prng_crack", "mt", "--seed", "victim"]).is_ok());
+    }
+
+    #[test]
+    fn test_lfsr_demo() {
+        assert!(run(["prng_crack", "lfsr", "--seed", "lfsr"]).is_ok());
+        assert!(run(["prng_crack", "lfsr", "--length", "64", "--seed", "lfsr"]).is_ok());
+        // Half the required keystream yields a shorter, wrong register
+        assert!(run(["prng_crack", "lfsr", "--known", "4", "--seed", "lfsr"]).is_err());
+    }
+
+    #[test]
+    fn test_given_outputs() {
+        assert!(run(["prng_crack", "lcg", "16807", "282475249", "1622650073", "984943658", "1144108930", "470211272"]).is_ok());
+        assert!(run(["prng_crack", "lcg", "1", "2", "3"]).is_err());
+    }
+}
@@ -0,0 +1,74 @@
+//! State recovery for the Mersenne Twister MT19937
+//!
+//! Each output is one word of the 624-word state, passed through an
+//! invertible "tempering" of shifts and masks. Undoing the tempering on 624
+//! consecutive outputs yields 624 consecutive state words, and the twist
+//! computes every later word from those. No seed search is needed.
+
+use csprng::mt19937::{Mt19937, N};
+
+/// Inverts `y ^= y >> shift`
+fn undo_right(y: u32, shift: u32) -> u32 {
+    // Each pass fixes another `shift` bits from the top
+    (0..32 / shift).fold(y, |x, _| y ^ (x >> shift))
+}
+
+/// Inverts `y ^= (y << shift) & mask`
+fn undo_left(y: u32, shift: u32, mask: u32) -> u32 {
+    (0..32 / shift).fold(y, |x, _| y ^ ((x << shift) & mask))
+}
+
+/// Inverts [`csprng::mt19937::temper`], giving back the state word
+pub fn untemper(output: u32) -> u32 {
+    let y = undo_right(output, 18);
+    let y = undo_left(y, 15, 0xefc6_0000);
+    let y = undo_left(y, 7, 0x9d2c_5680);
+    undo_right(y, 11)
+}
+
+/// Clones the generator from at least 624 consecutive outputs
+///
+/// Outputs beyond the first 624 must match the clone. The returned
+/// generator continues right after the last output.
+pub fn recover(outputs: &[u32]) -> Option<Mt19937> {
+    let (window, rest) = outputs.split_at_checked(N)?;
+    let state: [u32; N] = std::array::from_fn(|i| untemper(window[i]));
+    let mut clone = Mt19937::from_state(state);
+    rest.iter().all(|&output| clone.next_u32() == output).then_some(clone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::mt19937::temper;
+
+    #[test]
+    fn test_untemper() {
+        for y in [0, 1, 0x8000_0000, 0xffff_ffff, 0xdead_beef, 0x1234_5678] {
+            assert_eq!(untemper(temper(y)), y);
+        }
+    }
+
+    #[test]
+    fn test_recover() {
+        let mut victim = Mt19937::new(0xC0FFEE);
+        // Any window works, not only one that starts at a twist
+        for _ in 0..100 {
+            victim.next_u32();
+        }
+        let outputs: Vec<u32> = (0..N + 10).map(|_| victim.next_u32()).collect();
+        let mut clone = recover(&outputs).unwrap();
+        for _ in 0..2000 {
+            assert_eq!(clone.next_u32(), victim.next_u32());
+        }
+    }
+
+    #[test]
+    fn test_recover_rejects() {
+        let mut victim = Mt19937::new(1);
+        let mut outputs: Vec<u32> = (0..N + 2).map(|_| victim.next_u32()).collect();
+        assert!(recover(&outputs[..N - 1]).is_none());
+        outputs[N + 1] ^= 1;
+        assert!(recover(&outputs).is_none());
+    }
+}
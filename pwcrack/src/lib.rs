@@ -0,0 +1,173 @@
+//! pwcrack: Wörterbuchangriff auf Passwort-Hashes
+//!
+//! Probiert die Wörter einer Liste gegen einen Hash im PHC-Format, wie ihn
+//! `pwhash hash` erzeugt, auf allen CPU-Kernen. Der Verteidiger berechnet
+//! den Hash einmal pro Anmeldung, der Angreifer einmal pro geratenem Wort:
+//! Die Ausgabe stellt die Kosten eines Versuchs der Größe der Wortliste
+//! gegenüber. Ein schwaches Passwort steht früh in jeder Liste und fällt
+//! auch bei teurem Hashing schnell; ein starkes Passwort steht in keiner.
+
+use clap::Parser;
+use logging::{Verbosity, info, warn};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use tool_error::ToolError;
+
+#[derive(Parser)]
+#[command(name = "pwcrack")]
+#[command(about = "Wörterbuchangriff auf Passwort-Hashes von pwhash (PBKDF2, scrypt, Argon2id)")]
+#[command(version = "1.0")]
+#[command(after_help = "Exit-Codes: 0 Passwort gefunden, 1 nicht in der Wortliste, 65 Hash nicht lesbar, 74 Datei nicht lesbar")]
+struct Args {
+    /// Hash im PHC-Format
+    #[arg(short = 'H', long, required_unless_present = "hash_file", conflicts_with = "hash_file", help = "Hash im PHC-Format, z. B. aus 'pwhash hash'")]
+    hash: Option<String>,
+
+    /// Datei, deren erste Zeile der Hash ist
+    #[arg(short = 'f', long, help = "Datei mit dem Hash in der ersten Zeile")]
+    hash_file: Option<String>,
+
+    /// Wortliste, ein Passwort pro Zeile
+    #[arg(short, long, help = "Wortliste, ein Passwort pro Zeile")]
+    wordlist: String,
+
+    /// Anzahl der Threads (Standard: Anzahl CPU-Kerne)
+    #[arg(short, long, help = "Anzahl paralleler Threads")]
+    threads: Option<usize>,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+/// Ergebnis eines Angriffs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attack {
+    pub password: Option<String>,
+    pub attempts: usize,
+    pub elapsed: Duration,
+}
+
+/// Probiert die Wörter auf `threads` Threads, bis eines passt
+///
+/// `progress` wird etwa zweimal pro Sekunde mit der Anzahl der bisherigen
+/// Versuche aufgerufen. Ein unlesbarer Hash fällt schon beim ersten Versuch auf.
+pub fn attack(phc: &str, words: &[&str], threads: usize, mut progress: impl FnMut(usize)) -> Result<Attack, pwhash::Error> {
+    // Prüft Format und Verfahren, bevor die Threads starten
+    pwhash::verify(phc, b"")?;
+
+    let next = AtomicUsize::new(0);
+    let attempts = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let found = Mutex::new(None);
+    let started = Instant::now();
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.max(1))
+            .map(|_| scope.spawn(|| {
+                while !stop.load(Ordering::Relaxed) {
+                    let Some(word) = words.get(next.fetch_add(1, Ordering::Relaxed)) else { break };
+                    let matches = pwhash::verify(phc, word.as_bytes()).unwrap_or(false);
+                    attempts.fetch_add(1, Ordering::Relaxed);
+                    if matches {
+                        *found.lock().expect("kein Thread bricht mit gesperrtem Mutex ab") = Some(word.to_string());
+                        stop.store(true, Ordering::Relaxed);
+                    }
+                }
+            }))
+            .collect();
+
+        while !workers.iter().all(|worker| worker.is_finished()) {
+            thread::sleep(Duration::from_millis(500));
+            progress(attempts.load(Ordering::Relaxed));
+        }
+    });
+
+    Ok(Attack {
+        password: found.into_inner().expect("alle Threads sind beendet"),
+        attempts: attempts.into_inner(),
+        elapsed: started.elapsed(),
+    })
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+
+    let phc = match (&args.hash, &args.hash_file) {
+        (Some(hash), _) => hash.trim().to_string(),
+        (None, Some(path)) => tool_error::fs::read_to_string(path)?.lines().next().unwrap_or_default().trim().to_string(),
+        (None, None) => unreachable!("clap verlangt --hash oder --hash-file"),
+    };
+    let wordlist = tool_error::fs::read(&args.wordlist)?;
+    let wordlist = String::from_utf8_lossy(&wordlist);
+    let words: Vec<&str> = wordlist.lines().map(|line| line.trim_end_matches('\r')).filter(|word| !word.is_empty()).collect();
+    if words.is_empty() {
+        return Err(ToolError::data("Wortliste ist leer"));
+    }
+
+    let threads = args.threads.unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get())).clamp(1, words.len());
+    info!("{} Wörter, {} Threads", words.len(), threads);
+
+    let show_progress = !args.verbosity.is_quiet();
+    let total = words.len();
+    let started = Instant::now();
+    let result = attack(&phc, &words, threads, |attempts| {
+        if show_progress {
+            let rate = attempts as f64 / started.elapsed().as_secs_f64();
+            eprint!("\rVersuche: {}/{} ({:.1}/s)", attempts, total, rate);
+        }
+    })
+    .map_err(|e| ToolError::data(format!("Hash nicht lesbar: {}", e)))?;
+    if show_progress {
+        eprintln!();
+    }
+
+    let per_attempt = result.elapsed.as_secs_f64() * threads as f64 / result.attempts.max(1) as f64;
+    println!("{} Versuche in {:.2?}, {:.1} ms pro Versuch und Kern", result.attempts, result.elapsed, per_attempt * 1000.0);
+    println!("Ganze Wortliste auf einem Kern: etwa {:.1} s", per_attempt * total as f64);
+
+    match result.password {
+        Some(password) => {
+            println!("Passwort gefunden: {}", password);
+            Ok(())
+        }
+        None => {
+            warn!("Kein Wort der Liste passt");
+            Err(ToolError::failed("Passwort nicht in der Wortliste"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // pwhash hash -a pbkdf2 --iterations 1000 mit dem Passwort "sommer2024"
+    const HASH: &str = "$pbkdf2-sha256$i=1000,l=32$c2FsdHNhbHRzYWx0$KohYf4Ri3o2k1bw8D65Q0kJF1V521uLLI9j3pxBw0TA";
+
+    #[test]
+    fn test_finds_password() {
+        let words = ["passwort", "123456", "hallo", "sommer2024", "qwertz"];
+        let result = attack(HASH, &words, 2, |_| {}).unwrap();
+        assert_eq!(result.password.as_deref(), Some("sommer2024"));
+        assert!(result.attempts >= 4);
+    }
+
+    #[test]
+    fn test_not_in_wordlist() {
+        let result = attack(HASH, &["passwort", "123456"], 3, |_| {}).unwrap();
+        assert_eq!(result.password, None);
+        assert_eq!(result.attempts, 2);
+    }
+
+    #[test]
+    fn test_invalid_hash() {
+        assert!(attack("$md5$x$y", &["a"], 1, |_| {}).is_err());
+    }
+}
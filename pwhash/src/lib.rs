@@ -0,0 +1,317 @@
+//! pwhash: Passwörter speicherintensiv hashen und prüfen
+//!
+//! PBKDF2 wiederholt HMAC nur oft hintereinander. Das kostet Zeit, aber
+//! kaum Speicher, und eine Grafikkarte rechnet tausende Versuche parallel.
+//! scrypt und Argon2id verlangen für jeden Versuch zusätzlich viel Speicher
+//! (mit den Standardwerten 128 MiB bzw. 19 MiB), auf den sie in
+//! datenabhängiger bzw. gemischter Reihenfolge zugreifen. Parallele Angriffe
+//! werden dadurch durch Speicherbandbreite und -menge begrenzt statt durch
+//! Rechenleistung.
+//!
+//! Ergebnisse stehen im PHC-Format (`$argon2id$v=19$m=…`), das Algorithmus,
+//! Parameter, Salt und Hash enthält; `verify` erkennt den Algorithmus daran.
+
+use argon2::Argon2;
+use clap::{Parser, Subcommand, ValueEnum};
+use csprng::RngArgs;
+use logging::{Verbosity, info};
+use password_hash::{PasswordHash, PasswordHasher, SaltString};
+use pbkdf2::Pbkdf2;
+use rand_core::{CryptoRng, RngCore};
+use scrypt::Scrypt;
+use secret::Secret;
+use std::io::BufRead;
+use std::time::Instant;
+use tool_error::{Context, ExitStatus, ToolError};
+
+pub use password_hash::Error;
+
+/// Verfügbare Verfahren
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Algorithm {
+    Argon2id,
+    Scrypt,
+    /// PBKDF2-HMAC-SHA256, nur zum Vergleich
+    Pbkdf2,
+}
+
+/// Kostenparameter eines Verfahrens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Params {
+    /// Speicher in KiB, Durchläufe über den Speicher, parallele Spuren
+    Argon2id { memory_kib: u32, iterations: u32, parallelism: u32 },
+    /// N = 2^log_n Blöcke zu je 128 · r Bytes, p unabhängige Durchläufe
+    Scrypt { log_n: u8, r: u32, p: u32 },
+    /// Anzahl der HMAC-Runden
+    Pbkdf2 { rounds: u32 },
+}
+
+impl Params {
+    /// Empfehlungen der OWASP bzw. der Bibliotheken
+    pub fn recommended(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Argon2id => Params::Argon2id {
+                memory_kib: argon2::Params::DEFAULT_M_COST,
+                iterations: argon2::Params::DEFAULT_T_COST,
+                parallelism: argon2::Params::DEFAULT_P_COST,
+            },
+            Algorithm::Scrypt => Params::Scrypt {
+                log_n: scrypt::Params::RECOMMENDED_LOG_N,
+                r: scrypt::Params::RECOMMENDED_R,
+                p: scrypt::Params::RECOMMENDED_P,
+            },
+            Algorithm::Pbkdf2 => Params::Pbkdf2 { rounds: pbkdf2::Params::RECOMMENDED_ROUNDS as u32 },
+        }
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        match self {
+            Params::Argon2id { .. } => Algorithm::Argon2id,
+            Params::Scrypt { .. } => Algorithm::Scrypt,
+            Params::Pbkdf2 { .. } => Algorithm::Pbkdf2,
+        }
+    }
+
+    /// Speicher, den ein einzelner Rateversuch mindestens braucht
+    pub fn memory_bytes(&self) -> u64 {
+        match *self {
+            Params::Argon2id { memory_kib, .. } => memory_kib as u64 * 1024,
+            Params::Scrypt { log_n, r, .. } => 128 * r as u64 * (1u64 << log_n),
+            // Zustand von HMAC-SHA256: zwei Hashzustände und ein Block
+            Params::Pbkdf2 { .. } => 256,
+        }
+    }
+}
+
+/// Hasht ein Passwort mit zufälligem Salt, Ergebnis im PHC-Format
+pub fn hash(password: &[u8], params: &Params, rng: &mut (impl RngCore + CryptoRng)) -> Result<String, Error> {
+    let salt = SaltString::generate(rng);
+    let hash = match *params {
+        Params::Argon2id { memory_kib, iterations, parallelism } => {
+            let params = argon2::Params::new(memory_kib, iterations, parallelism, None)?;
+            Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params).hash_password(password, &salt)?
+        }
+        Params::Scrypt { log_n, r, p } => {
+            let params = scrypt::Params::new(log_n, r, p, scrypt::Params::RECOMMENDED_LEN).map_err(|_| Error::ParamValueInvalid(password_hash::errors::InvalidValue::Malformed))?;
+            Scrypt.hash_password_customized(password, None, None, params, &salt)?
+        }
+        Params::Pbkdf2 { rounds } => {
+            let params = pbkdf2::Params { rounds, output_length: 32 };
+            Pbkdf2.hash_password_customized(password, Some(pbkdf2::Algorithm::Pbkdf2Sha256.ident()), None, params, &salt)?
+        }
+    };
+    Ok(hash.to_string())
+}
+
+/// Prüft ein Passwort gegen einen Hash im PHC-Format
+///
+/// `Ok(false)` bei falschem Passwort, `Err` bei unlesbarem Hash oder
+/// unbekanntem Verfahren.
+pub fn verify(phc: &str, password: &[u8]) -> Result<bool, Error> {
+    let hash = PasswordHash::new(phc)?;
+    match hash.verify_password(&[&Argon2::default(), &Scrypt, &Pbkdf2], password) {
+        Ok(()) => Ok(true),
+        Err(Error::Password) => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
+#[derive(Parser)]
+#[command(name = "pwhash")]
+#[command(about = "Passwort-Hashing mit Argon2id und scrypt, PBKDF2 zum Vergleich")]
+#[command(version = "1.0")]
+#[command(after_help = "Das Passwort wird aus --password-file oder als erste Zeile von STDIN gelesen.\n\
+Exit-Codes beim Prüfen: 0 Passwort richtig, 1 Passwort falsch, 65 Hash nicht lesbar")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Datei, deren erste Zeile das Passwort ist
+    #[arg(long, global = true, help = "Passwort aus der ersten Zeile dieser Datei lesen")]
+    password_file: Option<String>,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Passwort hashen und den PHC-String ausgeben
+    Hash {
+        #[arg(short, long, value_enum, default_value_t = Algorithm::Argon2id, help = "Verfahren")]
+        algorithm: Algorithm,
+
+        /// Argon2id: Speicher in KiB
+        #[arg(long, help = "Argon2id: Speicher in KiB (Standard 19456)")]
+        memory: Option<u32>,
+
+        /// Argon2id: Durchläufe; PBKDF2: Runden
+        #[arg(long, help = "Argon2id: Durchläufe (Standard 2), PBKDF2: Runden (Standard 600000)")]
+        iterations: Option<u32>,
+
+        /// Argon2id: Spuren; scrypt: p
+        #[arg(long, help = "Argon2id: parallele Spuren, scrypt: p (Standard 1)")]
+        parallelism: Option<u32>,
+
+        /// scrypt: log2 von N
+        #[arg(long, help = "scrypt: log2(N) (Standard 17)")]
+        log_n: Option<u8>,
+
+        /// scrypt: Blockgröße r
+        #[arg(long, help = "scrypt: Blockgröße r (Standard 8)")]
+        block_size: Option<u32>,
+
+        #[command(flatten)]
+        rng: RngArgs,
+    },
+    /// Passwort gegen einen PHC-String prüfen
+    Verify {
+        /// Hash im PHC-Format, etwa aus `pwhash hash`
+        hash: String,
+    },
+    /// Alle Verfahren mit Standardparametern hashen und Zeit und Speicher vergleichen
+    Compare {
+        #[command(flatten)]
+        rng: RngArgs,
+    },
+}
+
+fn format_memory(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..0x10_0000 => format!("{} KiB", bytes >> 10),
+        _ => format!("{} MiB", bytes >> 20),
+    }
+}
+
+fn read_password(path: Option<&str>) -> tool_error::Result<Secret<String>> {
+    let mut line = String::new();
+    match path {
+        Some(path) => line.push_str(tool_error::fs::read_to_string(path)?.lines().next().unwrap_or_default()),
+        None => {
+            std::io::stdin().lock().read_line(&mut line).context("Fehler beim Lesen des Passworts von stdin")?;
+        }
+    }
+    let password = Secret::new(line.trim_end_matches(['\r', '\n']).to_string());
+    line.clear();
+    if password.is_empty() {
+        return Err(ToolError::usage("Leeres Passwort"));
+    }
+    Ok(password)
+}
+
+fn params_from_args(
+    algorithm: Algorithm,
+    memory: Option<u32>,
+    iterations: Option<u32>,
+    parallelism: Option<u32>,
+    log_n: Option<u8>,
+    block_size: Option<u32>,
+) -> Params {
+    match Params::recommended(algorithm) {
+        Params::Argon2id { memory_kib, iterations: t, parallelism: p } => Params::Argon2id {
+            memory_kib: memory.unwrap_or(memory_kib),
+            iterations: iterations.unwrap_or(t),
+            parallelism: parallelism.unwrap_or(p),
+        },
+        Params::Scrypt { log_n: n, r, p } => Params::Scrypt {
+            log_n: log_n.unwrap_or(n),
+            r: block_size.unwrap_or(r),
+            p: parallelism.unwrap_or(p),
+        },
+        Params::Pbkdf2 { rounds } => Params::Pbkdf2 { rounds: iterations.unwrap_or(rounds) },
+    }
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+
+    match args.command {
+        Command::Hash { algorithm, memory, iterations, parallelism, log_n, block_size, rng } => {
+            let params = params_from_args(algorithm, memory, iterations, parallelism, log_n, block_size);
+            let password = read_password(args.password_file.as_deref())?;
+            let started = Instant::now();
+            let phc = hash(password.as_bytes(), &params, &mut rng.rng())
+                .map_err(|e| ToolError::usage(format!("Ungültige Parameter: {}", e)))?;
+            info!("{:?}: {:.2?}, {} Speicher pro Versuch", algorithm, started.elapsed(), format_memory(params.memory_bytes()));
+            println!("{}", phc);
+            Ok(())
+        }
+        Command::Verify { hash } => {
+            let password = read_password(args.password_file.as_deref())?;
+            if verify(&hash, password.as_bytes()).map_err(|e| ToolError::data(format!("Hash nicht lesbar: {}", e)))? {
+                println!("Passwort richtig");
+                Ok(())
+            } else {
+                println!("Passwort falsch");
+                Err(ToolError::Silent(ExitStatus::Failure))
+            }
+        }
+        Command::Compare { rng } => {
+            let password = read_password(args.password_file.as_deref())?;
+            let mut rng = rng.rng();
+            println!("{:<10} {:>12} {:>10}  Hash", "Verfahren", "Zeit", "Speicher");
+            for algorithm in [Algorithm::Pbkdf2, Algorithm::Scrypt, Algorithm::Argon2id] {
+                let params = Params::recommended(algorithm);
+                let started = Instant::now();
+                let phc = hash(password.as_bytes(), &params, &mut rng).map_err(|e| ToolError::failed(e.to_string()))?;
+                let elapsed = started.elapsed();
+                println!("{:<10} {:>12.2?} {:>10}  {}", format!("{:?}", algorithm), elapsed, format_memory(params.memory_bytes()), phc);
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::{Algorithm as DrbgAlgorithm, Drbg};
+
+    fn cheap(algorithm: Algorithm) -> Params {
+        match algorithm {
+            Algorithm::Argon2id => Params::Argon2id { memory_kib: 64, iterations: 1, parallelism: 1 },
+            Algorithm::Scrypt => Params::Scrypt { log_n: 4, r: 8, p: 1 },
+            Algorithm::Pbkdf2 => Params::Pbkdf2 { rounds: 1000 },
+        }
+    }
+
+    #[test]
+    fn test_hash_verify() {
+        let mut rng = Drbg::from_seed(DrbgAlgorithm::ChaCha20, b"pwhash");
+        for (algorithm, prefix) in [(Algorithm::Argon2id, "$argon2id$v=19$m=64,t=1,p=1$"), (Algorithm::Scrypt, "$scrypt$ln=4,r=8,p=1$"), (Algorithm::Pbkdf2, "$pbkdf2-sha256$i=1000,l=32$")] {
+            let phc = hash(b"correct horse", &cheap(algorithm), &mut rng).unwrap();
+            assert!(phc.starts_with(prefix), "{}", phc);
+            assert!(verify(&phc, b"correct horse").unwrap());
+            assert!(!verify(&phc, b"battery staple").unwrap());
+        }
+    }
+
+    #[test]
+    fn test_salt_is_random() {
+        let mut rng = Drbg::from_seed(DrbgAlgorithm::ChaCha20, b"pwhash");
+        let params = cheap(Algorithm::Pbkdf2);
+        assert_ne!(hash(b"pw", &params, &mut rng).unwrap(), hash(b"pw", &params, &mut rng).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(verify("kein hash", b"pw").is_err());
+        assert!(verify("$md5$abc$def", b"pw").is_err());
+        let mut rng = Drbg::from_seed(DrbgAlgorithm::ChaCha20, b"pwhash");
+        assert!(hash(b"pw", &Params::Argon2id { memory_kib: 1, iterations: 1, parallelism: 1 }, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_memory() {
+        assert_eq!(Params::recommended(Algorithm::Scrypt).memory_bytes(), 128 << 20);
+        assert_eq!(Params::recommended(Algorithm::Argon2id).memory_bytes(), 19 << 20);
+        assert_eq!(format_memory(256), "256 B");
+        assert_eq!(format_memory(19 << 20), "19 MiB");
+    }
+}
@@ -0,0 +1,258 @@
+//! Rail-fence (zigzag) transposition cipher.
+//!
+//! The plaintext is written diagonally across a number of rails and read off
+//! rail by rail. An offset starts the zigzag at a later position of its cycle.
+//! Since a transposition keeps all letter frequencies intact, the brute-force
+//! mode ranks candidates by bigram statistics instead of unigram frequencies.
+
+use clap::{Parser, ValueEnum};
+use logging::{English, Verbosity};
+use classical::{Alphabet, Language, NgramModel};
+use tool_error::ToolError;
+
+/// Command-line arguments for the rail-fence cipher program.
+#[derive(Parser, Debug)]
+struct Cli {
+    /// Path to the input file
+    #[arg(short, long, help = "Path to the input file")]
+    file: String,
+
+    /// Path to the output file where the result will be saved
+    #[arg(short, long, help = "Path to the output file")]
+    output: String,
+
+    /// Mode of operation
+    #[arg(short, long, help = "Mode of operation (encrypt/decrypt/brute-force)")]
+    mode: OperationMode,
+
+    /// Number of rails
+    #[arg(short, long, required_if_eq_any = [("mode", "encrypt"), ("mode", "decrypt")], help = "Number of rails (at least 2)")]
+    rails: Option<usize>,
+
+    /// Starting position within the zigzag cycle
+    #[arg(long, default_value_t = 0, help = "Offset into the zigzag cycle (0 to 2 * (rails - 1) - 1)")]
+    offset: usize,
+
+    /// Largest rail count tried in brute-force mode
+    #[arg(long, default_value_t = 20, help = "Maximum number of rails for brute-force mode")]
+    max_rails: usize,
+
+    /// Number of ranked candidates printed in brute-force mode
+    #[arg(long, default_value_t = 5, help = "Number of candidates to print in brute-force mode")]
+    top: usize,
+
+    /// Language used to score brute-force candidates
+    #[arg(short, long, value_enum, default_value = "de", help = "Plaintext language (de, en, fr, es, it)")]
+    language: Language,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+/// Enum representing the mode of operation for the cipher.
+#[derive(Clone, Debug, ValueEnum)]
+enum OperationMode {
+    /// Encrypt mode
+    Encrypt,
+    /// Decrypt mode
+    Decrypt,
+    /// Try all rail counts and offsets and rank the results
+    BruteForce,
+}
+
+/// A decryption candidate found in brute-force mode.
+struct Candidate {
+    rails: usize,
+    offset: usize,
+    score: f64,
+    plaintext: String,
+}
+
+/// Main entry point for the rail-fence cipher program.
+///
+/// The first argument is the program name, as with `std::env::args_os`.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+
+    let content: String = tool_error::fs::read_to_string(&cli.file)?;
+
+    // Keep a trailing newline of the file out of the transposition
+    let (text, newline) = match content.strip_suffix('\n') {
+        Some(text) => (text, "\n"),
+        None => (content.as_str(), ""),
+    };
+
+    let result = match cli.mode {
+        OperationMode::Encrypt | OperationMode::Decrypt => {
+            let rails = cli.rails.expect("clap enforces --rails");
+            validate_key(rails, cli.offset).map_err(ToolError::usage)?;
+
+            if matches!(cli.mode, OperationMode::Encrypt) {
+                encrypt(text, rails, cli.offset)
+            } else {
+                decrypt(text, rails, cli.offset)
+            }
+        }
+        OperationMode::BruteForce => {
+            let model = NgramModel::bigrams(cli.language);
+            let candidates = brute_force(text, cli.max_rails, &model, &Alphabet::latin());
+
+            let Some(best) = candidates.first() else {
+                return Err(ToolError::data("text is too short for brute-force analysis"));
+            };
+
+            println!("{:>5} {:>6} {:>10}  Preview", "Rails", "Offset", "Score");
+            for candidate in candidates.iter().take(cli.top) {
+                let preview: String = candidate.plaintext.chars().take(60).collect();
+                println!("{:>5} {:>6} {:>10.2}  {}", candidate.rails, candidate.offset, candidate.score, preview);
+            }
+            best.plaintext.clone()
+        }
+    };
+
+    tool_error::fs::write(&cli.output, format!("{}{}", result, newline))
+}
+
+/// Checks that the rail count and offset describe a valid zigzag.
+fn validate_key(rails: usize, offset: usize) -> Result<(), String> {
+    if rails < 2 {
+        return Err("Number of rails must be at least 2".to_string());
+    }
+    if offset >= cycle_length(rails) {
+        return Err(format!("Offset must be smaller than {}", cycle_length(rails)));
+    }
+    Ok(())
+}
+
+/// Length of one full zigzag period (down and back up).
+fn cycle_length(rails: usize) -> usize {
+    2 * (rails - 1)
+}
+
+/// Computes the rail of every text position.
+fn rail_pattern(len: usize, rails: usize, offset: usize) -> Vec<usize> {
+    let cycle = cycle_length(rails);
+    (0..len)
+        .map(|i| {
+            let position = (i + offset) % cycle;
+            if position < rails { position } else { cycle - position }
+        })
+        .collect()
+}
+
+/// Encrypts text by reading the zigzag rail by rail.
+///
+/// # Arguments
+///
+/// * `text` - The input text to encrypt.
+/// * `rails` - The number of rails.
+/// * `offset` - The starting position within the zigzag cycle.
+///
+/// # Returns
+///
+/// A `String` containing the encrypted text.
+fn encrypt(text: &str, rails: usize, offset: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let pattern = rail_pattern(chars.len(), rails, offset);
+
+    (0..rails)
+        .flat_map(|rail| {
+            chars.iter()
+                .zip(&pattern)
+                .filter(move |(_, &r)| r == rail)
+                .map(|(&c, _)| c)
+        })
+        .collect()
+}
+
+/// Decrypts text by refilling the rails and reading the zigzag.
+///
+/// # Arguments
+///
+/// * `text` - The input text to decrypt.
+/// * `rails` - The number of rails.
+/// * `offset` - The starting position within the zigzag cycle.
+///
+/// # Returns
+///
+/// A `String` containing the decrypted text.
+fn decrypt(text: &str, rails: usize, offset: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let pattern = rail_pattern(chars.len(), rails, offset);
+
+    // Cut the ciphertext into one segment per rail
+    let mut segments: Vec<std::slice::Iter<char>> = Vec::with_capacity(rails);
+    let mut start = 0;
+    for rail in 0..rails {
+        let length = pattern.iter().filter(|&&r| r == rail).count();
+        segments.push(chars[start..start + length].iter());
+        start += length;
+    }
+
+    // Follow the zigzag and take the next character from each rail
+    pattern.iter()
+        .map(|&rail| *segments[rail].next().expect("segment lengths follow the pattern"))
+        .collect()
+}
+
+/// Tries all rail counts and offsets and ranks the results by language score.
+fn brute_force(text: &str, max_rails: usize, model: &NgramModel, alphabet: &Alphabet) -> Vec<Candidate> {
+    let length = text.chars().count();
+    let mut candidates = Vec::new();
+
+    // More rails than characters only repeats earlier results
+    for rails in 2..=max_rails.min(length.saturating_sub(1)) {
+        for offset in 0..cycle_length(rails) {
+            let plaintext = decrypt(text, rails, offset);
+            let score = model.score(&plaintext, alphabet);
+            candidates.push(Candidate { rails, offset, score, plaintext });
+        }
+    }
+
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_classic_example() {
+        let ciphertext = encrypt("WEAREDISCOVEREDFLEEATONCE", 3, 0);
+        assert_eq!(ciphertext, "WECRLTEERDSOEEFEAOCAIVDEN");
+    }
+
+    #[test]
+    fn test_roundtrip_with_offset() {
+        let text = "Die Kryptologie ist eine Wissenschaft!";
+        for rails in 2..6 {
+            for offset in 0..cycle_length(rails) {
+                let ciphertext = encrypt(text, rails, offset);
+                assert_eq!(decrypt(&ciphertext, rails, offset), text);
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_key() {
+        assert!(validate_key(1, 0).is_err());
+        assert!(validate_key(3, 4).is_err());
+        assert!(validate_key(3, 3).is_ok());
+    }
+
+    #[test]
+    fn test_brute_force_finds_key() {
+        let text = "the quick brown fox jumps over the lazy dog and then it rests in the shade of the tree";
+        let ciphertext = encrypt(text, 4, 2);
+
+        let candidates = brute_force(&ciphertext, 10, &NgramModel::bigrams(Language::En), &Alphabet::latin());
+        assert_eq!(candidates[0].plaintext, text);
+        assert_eq!((candidates[0].rails, candidates[0].offset), (4, 2));
+    }
+}
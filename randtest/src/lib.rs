@@ -0,0 +1,199 @@
+//! randtest: Statistische Tests aus NIST SP 800-22 für Bitfolgen
+//!
+//! Prüft eine Datei (z. B. einen Schlüsselstrom von `aes-128` im CTR-Modus)
+//! oder direkt die Ausgabe eines DRBG aus `csprng` mit einer Auswahl der
+//! Tests aus SP 800-22. Die Tests erkennen grobe Fehler wie eine schiefe
+//! Verteilung oder periodische Muster; ein Beweis für kryptographische
+//! Sicherheit ist ein bestandener Lauf nicht.
+
+use clap::{Parser, ValueEnum};
+use csprng::RngArgs;
+use logging::{Verbosity, info, warn};
+use rand_core::RngCore;
+use std::path::PathBuf;
+use tool_error::{ExitStatus, ToolError};
+
+pub mod nist;
+pub mod special;
+
+use nist::TestResult;
+
+/// Format der Eingabedatei
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum InputFormat {
+    /// Rohe Bytes, höchstwertiges Bit zuerst
+    Binary,
+    /// Hexadezimalziffern, Leerraum wird ignoriert
+    Hex,
+    /// Zeichen '0' und '1', Leerraum wird ignoriert
+    Bits,
+}
+
+#[derive(Parser)]
+#[command(name = "randtest")]
+#[command(about = "Statistische Tests aus NIST SP 800-22 (Frequency, Block Frequency, Runs, Approximate Entropy, Serial)")]
+#[command(version = "1.0")]
+#[command(after_help = "Exit-Codes: 0 alle Tests bestanden, 1 mindestens ein Test nicht bestanden, 65 ungültige Eingabe, 74 Datei nicht lesbar")]
+struct Args {
+    /// Zu prüfende Datei
+    #[arg(short = 'i', long = "input", required_unless_present = "drbg_bytes", conflicts_with = "drbg_bytes")]
+    input: Option<PathBuf>,
+
+    /// Format der Eingabedatei
+    #[arg(short = 'f', long = "format", value_enum, default_value_t = InputFormat::Binary)]
+    format: InputFormat,
+
+    /// Statt einer Datei N Bytes aus dem DRBG prüfen (siehe --drbg, --seed)
+    #[arg(long = "drbg-bytes", value_name = "N")]
+    drbg_bytes: Option<usize>,
+
+    /// Blocklänge M für den Block-Frequency-Test
+    #[arg(long = "block-size", default_value = "128")]
+    block_size: usize,
+
+    /// Musterlänge m für den Approximate-Entropy-Test
+    #[arg(long = "entropy-block", default_value = "10")]
+    entropy_block: usize,
+
+    /// Musterlänge m für den Serial-Test
+    #[arg(long = "serial-block", default_value = "16")]
+    serial_block: usize,
+
+    /// Signifikanzniveau: ein Test mit p-Wert darunter gilt als nicht bestanden
+    #[arg(long = "alpha", default_value = "0.01")]
+    alpha: f64,
+
+    #[command(flatten)]
+    rng: RngArgs,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+/// Zerlegt Bytes in Bits, höchstwertiges Bit zuerst
+pub fn to_bits(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().flat_map(|&byte| (0..8).rev().map(move |i| (byte >> i) & 1)).collect()
+}
+
+fn parse_bits(text: &str) -> tool_error::Result<Vec<u8>> {
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '0' => Ok(0),
+            '1' => Ok(1),
+            _ => Err(ToolError::data(format!("ungültiges Zeichen '{}' in Bitfolge", c))),
+        })
+        .collect()
+}
+
+fn read_input(args: &Args) -> tool_error::Result<Vec<u8>> {
+    if let Some(count) = args.drbg_bytes {
+        let mut rng = args.rng.rng();
+        let mut bytes = vec![0u8; count];
+        rng.fill_bytes(&mut bytes);
+        info!("{} Bytes aus {:?}", count, rng.algorithm());
+        return Ok(to_bits(&bytes));
+    }
+
+    let path = args.input.as_ref().expect("clap verlangt --input oder --drbg-bytes");
+    match args.format {
+        InputFormat::Binary => Ok(to_bits(&tool_error::fs::read(path)?)),
+        InputFormat::Hex => Ok(to_bits(&codec::hex::decode(&tool_error::fs::read_to_string(path)?)?)),
+        InputFormat::Bits => parse_bits(&tool_error::fs::read_to_string(path)?),
+    }
+}
+
+/// Führt alle Tests aus; Parameter außerhalb der Empfehlungen aus SP 800-22
+/// werden nur gemeldet, nicht abgelehnt
+pub fn run_tests(bits: &[u8], block_size: usize, entropy_block: usize, serial_block: usize) -> Vec<TestResult> {
+    let n = bits.len();
+    if n < 100 {
+        warn!("Nur {} Bits, SP 800-22 empfiehlt mindestens 100", n);
+    }
+    if block_size < 20 || block_size * 100 < n {
+        warn!("Blocklänge {} außerhalb der Empfehlung M ≥ 20, M > n/100", block_size);
+    }
+    if entropy_block + 5 >= n.ilog2() as usize {
+        warn!("Musterlänge {} für Approximate Entropy zu groß, empfohlen m < log2(n) - 5", entropy_block);
+    }
+    if serial_block + 2 >= n.ilog2() as usize {
+        warn!("Musterlänge {} für Serial zu groß, empfohlen m < log2(n) - 2", serial_block);
+    }
+
+    vec![
+        nist::frequency(bits),
+        nist::block_frequency(bits, block_size),
+        nist::runs(bits),
+        nist::approximate_entropy(bits, entropy_block),
+        nist::serial(bits, serial_block),
+    ]
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+
+    if !(0.0..1.0).contains(&args.alpha) {
+        return Err(ToolError::usage("--alpha muss zwischen 0 und 1 liegen"));
+    }
+    if args.block_size == 0 || args.entropy_block == 0 || args.serial_block < 2 {
+        return Err(ToolError::usage("Blocklängen müssen positiv sein, die Musterlänge für Serial mindestens 2"));
+    }
+
+    let bits = read_input(&args)?;
+    if bits.len() < args.block_size.max(args.serial_block).max(args.entropy_block + 1) {
+        return Err(ToolError::data(format!("Eingabe mit {} Bits ist zu kurz für die gewählten Blocklängen", bits.len())));
+    }
+    info!("Prüfe {} Bits mit α = {}", bits.len(), args.alpha);
+
+    let results = run_tests(&bits, args.block_size, args.entropy_block, args.serial_block);
+    let mut all_passed = true;
+    for result in &results {
+        let passed = result.passed(args.alpha);
+        all_passed &= passed;
+        let p_values: Vec<String> = result.p_values.iter().map(|p| format!("{:.6}", p)).collect();
+        println!("{:<20} {:<18} {}", result.name, p_values.join(" "), if passed { "bestanden" } else { "nicht bestanden" });
+    }
+
+    if all_passed {
+        Ok(())
+    } else {
+        Err(ToolError::Silent(ExitStatus::Failure))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::{Algorithm, Drbg};
+
+    #[test]
+    fn test_to_bits() {
+        assert_eq!(to_bits(&[0xa5, 0x01]), [1, 0, 1, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(parse_bits("01 1\n0").unwrap(), [0, 1, 1, 0]);
+        assert!(parse_bits("012").is_err());
+    }
+
+    #[test]
+    fn test_drbg_passes() {
+        for algorithm in [Algorithm::ChaCha20, Algorithm::HmacSha256] {
+            let mut rng = Drbg::from_seed(algorithm, b"randtest");
+            let mut bytes = vec![0u8; 1 << 17];
+            rng.fill_bytes(&mut bytes);
+            for result in run_tests(&to_bits(&bytes), 128, 10, 16) {
+                assert!(result.passed(0.001), "{:?}: {:?}", algorithm, result);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pattern_fails() {
+        let bytes: Vec<u8> = (0..1 << 14).map(|i| i as u8).collect();
+        let results = run_tests(&to_bits(&bytes), 128, 10, 16);
+        assert!(results.iter().any(|result| !result.passed(0.01)));
+    }
+}
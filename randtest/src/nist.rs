@@ -0,0 +1,163 @@
+//! Die Tests aus NIST SP 800-22 Rev. 1a, Abschnitt 2
+//!
+//! Alle Tests arbeiten auf einer Folge von Bits (je ein `u8` mit 0 oder 1)
+//! und liefern p-Werte. Unter der Hypothese einer echt zufälligen Folge sind
+//! die p-Werte gleichverteilt; ein p-Wert unter dem Signifikanzniveau α
+//! (üblich 0,01) gilt als Hinweis auf einen Defekt.
+
+use crate::special::{erfc, igamc};
+
+/// Ergebnis eines Tests; der Serial-Test liefert zwei p-Werte
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    pub name: &'static str,
+    pub p_values: Vec<f64>,
+}
+
+impl TestResult {
+    pub fn passed(&self, alpha: f64) -> bool {
+        self.p_values.iter().all(|&p| p >= alpha)
+    }
+}
+
+/// 2.1 Frequency (Monobit): gleich viele Einsen und Nullen?
+pub fn frequency(bits: &[u8]) -> TestResult {
+    let n = bits.len() as f64;
+    let sum: i64 = bits.iter().map(|&bit| 2 * bit as i64 - 1).sum();
+    let s_obs = sum.unsigned_abs() as f64 / n.sqrt();
+    TestResult { name: "Frequency", p_values: vec![erfc(s_obs / std::f64::consts::SQRT_2)] }
+}
+
+/// 2.2 Frequency within a Block: Anteil der Einsen in Blöcken der Länge M
+pub fn block_frequency(bits: &[u8], block_len: usize) -> TestResult {
+    let blocks = bits.len() / block_len;
+    let chi_squared: f64 = bits.chunks_exact(block_len)
+        .map(|block| {
+            let pi = block.iter().filter(|&&bit| bit == 1).count() as f64 / block_len as f64;
+            (pi - 0.5).powi(2)
+        })
+        .sum::<f64>() * 4.0 * block_len as f64;
+    TestResult { name: "Block Frequency", p_values: vec![igamc(blocks as f64 / 2.0, chi_squared / 2.0)] }
+}
+
+/// 2.3 Runs: wechseln Nullen und Einsen so oft wie erwartet?
+pub fn runs(bits: &[u8]) -> TestResult {
+    let n = bits.len() as f64;
+    let pi = bits.iter().filter(|&&bit| bit == 1).count() as f64 / n;
+
+    // Vortest: ohne ausgewogene Häufigkeit ist der Runs-Test nicht aussagekräftig
+    let p_value = if (pi - 0.5).abs() >= 2.0 / n.sqrt() {
+        0.0
+    } else {
+        let v_obs = 1 + bits.windows(2).filter(|pair| pair[0] != pair[1]).count();
+        let expected = 2.0 * n * pi * (1.0 - pi);
+        erfc((v_obs as f64 - expected).abs() / (2.0 * (2.0 * n).sqrt() * pi * (1.0 - pi)))
+    };
+    TestResult { name: "Runs", p_values: vec![p_value] }
+}
+
+/// Häufigkeiten aller überlappenden m-Bit-Muster, die Folge zyklisch fortgesetzt
+fn pattern_counts(bits: &[u8], m: usize) -> Vec<u64> {
+    let mut counts = vec![0u64; 1 << m];
+    if m == 0 {
+        return counts;
+    }
+    let mask = (1usize << m) - 1;
+    let mut pattern = bits[..m - 1].iter().fold(0usize, |acc, &bit| (acc << 1) | bit as usize);
+    for &bit in bits[m - 1..].iter().chain(&bits[..m - 1]) {
+        pattern = ((pattern << 1) | bit as usize) & mask;
+        counts[pattern] += 1;
+    }
+    counts
+}
+
+/// 2.12 Approximate Entropy: Häufigkeit der Muster der Länge m und m + 1
+pub fn approximate_entropy(bits: &[u8], m: usize) -> TestResult {
+    let n = bits.len() as f64;
+    let phi = |m: usize| -> f64 {
+        pattern_counts(bits, m).iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| {
+                let c = count as f64 / n;
+                c * c.ln()
+            })
+            .sum()
+    };
+
+    let ap_en = phi(m) - phi(m + 1);
+    let chi_squared = 2.0 * n * (std::f64::consts::LN_2 - ap_en);
+    TestResult { name: "Approximate Entropy", p_values: vec![igamc(2f64.powi(m as i32 - 1), chi_squared / 2.0)] }
+}
+
+/// 2.11 Serial: sind alle Muster der Länge m gleich häufig?
+pub fn serial(bits: &[u8], m: usize) -> TestResult {
+    let n = bits.len() as f64;
+    let psi_squared = |m: usize| -> f64 {
+        if m == 0 {
+            return 0.0;
+        }
+        let sum: f64 = pattern_counts(bits, m).iter().map(|&count| (count as f64).powi(2)).sum();
+        2f64.powi(m as i32) / n * sum - n
+    };
+
+    let (psi_m, psi_m1, psi_m2) = (psi_squared(m), psi_squared(m - 1), psi_squared(m.saturating_sub(2)));
+    let delta = psi_m - psi_m1;
+    let delta2 = psi_m - 2.0 * psi_m1 + psi_m2;
+    TestResult {
+        name: "Serial",
+        p_values: vec![
+            igamc(2f64.powi(m as i32 - 2), delta / 2.0),
+            igamc(2f64.powi(m as i32 - 3), delta2 / 2.0),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(text: &str) -> Vec<u8> {
+        text.bytes().map(|b| b - b'0').collect()
+    }
+
+    fn assert_p(result: TestResult, expected: &[f64]) {
+        assert_eq!(result.p_values.len(), expected.len());
+        for (actual, expected) in result.p_values.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-6, "{}: {} != {}", result.name, actual, expected);
+        }
+    }
+
+    // Die Beispiele aus SP 800-22, jeweils Abschnitt 2.x.4 und 2.x.8
+
+    #[test]
+    fn test_frequency() {
+        assert_p(frequency(&bits("1011010101")), &[0.527089]);
+    }
+
+    #[test]
+    fn test_block_frequency() {
+        assert_p(block_frequency(&bits("0110011010"), 3), &[0.801252]);
+    }
+
+    #[test]
+    fn test_runs() {
+        assert_p(runs(&bits("1001101011")), &[0.147232]);
+        assert_eq!(runs(&[1; 100]).p_values, [0.0]);
+    }
+
+    #[test]
+    fn test_approximate_entropy() {
+        assert_p(approximate_entropy(&bits("0100110101"), 3), &[0.261961]);
+    }
+
+    #[test]
+    fn test_serial() {
+        assert_p(serial(&bits("0011011101"), 3), &[0.808792, 0.670320]);
+    }
+
+    #[test]
+    fn test_pattern_counts_wrap_around() {
+        // 0011011101 mit Fortsetzung 00: 001 011 110 101 011 111 110 101 010 100
+        assert_eq!(pattern_counts(&bits("0011011101"), 3), [0, 1, 1, 2, 1, 2, 2, 1]);
+    }
+}
@@ -0,0 +1,132 @@
+//! Spezielle Funktionen für die p-Werte
+//!
+//! Die Tests aus SP 800-22 brauchen nur die komplementäre Fehlerfunktion und
+//! die obere regularisierte unvollständige Gammafunktion (`igamc` im
+//! Referenzcode von NIST). Beide sind hier nach Numerical Recipes umgesetzt:
+//! Reihenentwicklung für x < a + 1, sonst Kettenbruch.
+
+const EPSILON: f64 = 1e-15;
+const MAX_ITERATIONS: usize = 1000;
+
+/// ln Γ(x) nach Lanczos (g = 7, n = 9), für x > 0
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        // Spiegelung Γ(x) Γ(1 − x) = π / sin(πx)
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let sum = COEFFICIENTS[1..].iter().enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64 + 1.0));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// Q(a, x) = Γ(a, x) / Γ(a), die Wahrscheinlichkeit, dass eine
+/// χ²-verteilte Größe mit 2a Freiheitsgraden 2x übersteigt
+pub fn igamc(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    if x < a + 1.0 {
+        1.0 - lower_series(a, x)
+    } else {
+        upper_fraction(a, x)
+    }
+}
+
+/// erfc(x) = Q(1/2, x²) für x ≥ 0
+pub fn erfc(x: f64) -> f64 {
+    if x < 0.0 {
+        2.0 - erfc(-x)
+    } else {
+        igamc(0.5, x * x)
+    }
+}
+
+/// P(a, x) als Reihe
+fn lower_series(a: f64, x: f64) -> f64 {
+    let mut term = 1.0 / a;
+    let mut sum = term;
+    for n in 1..MAX_ITERATIONS {
+        term *= x / (a + n as f64);
+        sum += term;
+        if term.abs() < sum.abs() * EPSILON {
+            break;
+        }
+    }
+    sum * (-x + a * x.ln() - ln_gamma(a)).exp()
+}
+
+/// Q(a, x) als Kettenbruch (modifizierter Lentz-Algorithmus)
+fn upper_fraction(a: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / TINY;
+    let mut d = 1.0 / b;
+    let mut h = d;
+    for i in 1..MAX_ITERATIONS {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = b + an / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    (-x + a * x.ln() - ln_gamma(a)).exp() * h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn test_ln_gamma() {
+        assert_close(ln_gamma(1.0), 0.0);
+        assert_close(ln_gamma(5.0), 24f64.ln());
+        assert_close(ln_gamma(0.5), std::f64::consts::PI.sqrt().ln());
+    }
+
+    #[test]
+    fn test_erfc() {
+        assert_close(erfc(0.0), 1.0);
+        assert_close(erfc(1.0), 0.157_299_207_050_285_1);
+        assert_close(erfc(-1.0), 1.842_700_792_949_715);
+    }
+
+    #[test]
+    fn test_igamc() {
+        // Q(1, x) = e^(−x)
+        assert_close(igamc(1.0, 0.5), (-0.5f64).exp());
+        assert_close(igamc(1.0, 3.0), (-3.0f64).exp());
+        // χ² mit 4 Freiheitsgraden: Q(2, x) = (1 + x) e^(−x)
+        assert_close(igamc(2.0, 1.5), 2.5 * (-1.5f64).exp());
+    }
+}
@@ -0,0 +1,271 @@
+//! ratchet: Double Ratchet zum Ausprobieren
+//!
+//! `simulate` spielt ein Gespräch mit verspäteten, verlorenen und
+//! wiederholten Nachrichten lokal durch und zeigt, wann sich welche
+//! Schlüssel ändern. `listen` und `connect` verbinden zwei Terminals über
+//! TCP; jede eingegebene Zeile wird als eigene Nachricht verschickt.
+//!
+//! Das gemeinsame Startgeheimnis entsteht aus einem unauthentisierten
+//! X25519-Austausch. Beide Seiten zeigen deshalb eine Sicherheitsnummer an,
+//! die über einen anderen Kanal verglichen werden muss, um einen Angreifer
+//! in der Mitte auszuschließen.
+
+mod session;
+
+pub use session::{Header, MAX_SKIP, Message, RatchetError, Session};
+
+use clap::{Parser, Subcommand};
+use csprng::{Drbg, RngArgs};
+use dh_exchange::net::{read_bytes, write_bytes};
+use hkdf::Hkdf;
+use logging::{Verbosity, debug, info, warn};
+use secret::Secret;
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::Mutex;
+use std::thread;
+use tool_error::{Context, ToolError};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+const START_INFO: &[u8] = b"cryptolab ratchet Start";
+
+#[derive(Parser)]
+#[command(name = "ratchet")]
+#[command(about = "Double Ratchet mit X25519, HKDF und AES-256-GCM")]
+#[command(version = "1.0")]
+#[command(after_help = "Beispiel mit zwei Terminals:\n  ratchet listen --port 4444\n  ratchet connect localhost:4444\n\
+Jede Zeile wird als Nachricht verschickt, Strg-D beendet das Gespräch.")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Gespräch zwischen Alice und Bob lokal durchspielen
+    Simulate {
+        #[command(flatten)]
+        rng: RngArgs,
+    },
+    /// Als Bob auf einen Gesprächspartner warten
+    Listen {
+        /// TCP-Port
+        #[arg(short, long, default_value_t = 4444, help = "Port, an dem gewartet wird")]
+        port: u16,
+
+        #[command(flatten)]
+        rng: RngArgs,
+    },
+    /// Als Alice mit einem wartenden Partner verbinden
+    Connect {
+        /// Adresse des Partners
+        #[arg(value_name = "HOST:PORT")]
+        address: String,
+
+        #[command(flatten)]
+        rng: RngArgs,
+    },
+}
+
+/// Startgeheimnis aus dem X25519-Austausch
+fn start_secret(dh_output: &[u8; 32]) -> Secret<[u8; 32]> {
+    let mut secret = Secret::new([0u8; 32]);
+    Hkdf::<Sha256>::new(None, dh_output)
+        .expand(START_INFO, &mut *secret)
+        .expect("32 Bytes liegen unter der Grenze von HKDF");
+    secret
+}
+
+/// Zum Vergleich über einen zweiten Kanal: Hash beider öffentlicher Schlüssel in Fünfergruppen
+pub fn safety_number(bob: &PublicKey, alice: &PublicKey) -> String {
+    let hash = Sha256::new().chain_update(bob.as_bytes()).chain_update(alice.as_bytes()).finalize();
+    hash[..10]
+        .chunks(2)
+        .map(|pair| format!("{:05}", u16::from_be_bytes([pair[0], pair[1]])))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn short_key(key: &[u8; 32]) -> String {
+    codec::hex::encode(&key[..4])
+}
+
+fn describe(header: &Header) -> String {
+    format!("[Schlüssel {}…, vorige Kette {}, Nr. {}]", short_key(&header.ratchet_key), header.previous, header.number)
+}
+
+fn failed(error: RatchetError) -> ToolError {
+    ToolError::failed(error.to_string())
+}
+
+fn deliver(name: &str, session: &mut Session, message: &Message, rng: &mut Drbg) {
+    match session.decrypt(message, rng) {
+        Ok(text) => println!("  {} liest {}: \"{}\"", name, describe(&message.header), String::from_utf8_lossy(&text)),
+        Err(e) => println!("  {} verwirft {}: {}", name, describe(&message.header), e),
+    }
+}
+
+fn simulate(rng: &mut Drbg) -> tool_error::Result<()> {
+    let bob_ratchet = StaticSecret::random_from_rng(&mut *rng);
+    let bob_public = PublicKey::from(&bob_ratchet);
+    let alice_ephemeral = StaticSecret::random_from_rng(&mut *rng);
+    let shared = start_secret(&alice_ephemeral.diffie_hellman(&bob_public).to_bytes());
+    let mut alice = Session::alice(&shared, bob_public, rng);
+    let mut bob = Session::bob(&shared, bob_ratchet);
+
+    println!("Alice schickt drei Nachrichten, die zweite verspätet sich:");
+    let first = alice.encrypt(b"Hallo Bob").map_err(failed)?;
+    let delayed = alice.encrypt(b"Wie geht's?").map_err(failed)?;
+    let third = alice.encrypt(b"Bist du da?").map_err(failed)?;
+    deliver("Bob", &mut bob, &first, rng);
+    deliver("Bob", &mut bob, &third, rng);
+    println!("  Bob bewahrt {} Schlüssel für fehlende Nachrichten auf", bob.skipped_keys());
+
+    println!("Bob antwortet mit einem neuen Ratschenschlüssel:");
+    let reply = bob.encrypt(b"Ja, alles gut").map_err(failed)?;
+    deliver("Alice", &mut alice, &reply, rng);
+
+    println!("Alice schreibt zurück, ebenfalls mit neuem Schlüssel:");
+    let answer = alice.encrypt(b"Schoen!").map_err(failed)?;
+    deliver("Bob", &mut bob, &answer, rng);
+
+    println!("Die verspätete Nachricht trifft ein:");
+    deliver("Bob", &mut bob, &delayed, rng);
+
+    println!("Ein Angreifer spielt die erste Nachricht erneut ein:");
+    deliver("Bob", &mut bob, &first, rng);
+    Ok(())
+}
+
+/// Empfängt Nachrichten, bis die Verbindung endet
+fn receive_loop(mut stream: TcpStream, session: &Mutex<Session>, mut rng: Drbg) {
+    let mut remote_key = None;
+    loop {
+        let bytes = match read_bytes(&mut stream) {
+            Ok(bytes) => bytes,
+            Err(_) => {
+                info!("Verbindung beendet");
+                return;
+            }
+        };
+        let message = match Message::from_bytes(&bytes) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("{}", e);
+                continue;
+            }
+        };
+        if remote_key != Some(message.header.ratchet_key) {
+            info!("DH-Ratschenschritt: Gegenüber verwendet Schlüssel {}…", short_key(&message.header.ratchet_key));
+            remote_key = Some(message.header.ratchet_key);
+        }
+        debug!("Empfangen {}", describe(&message.header));
+
+        match session.lock().expect("kein Thread bricht mit gesperrtem Mutex ab").decrypt(&message, &mut rng) {
+            // Alices erste, leere Nachricht gibt Bob eine Sendekette
+            Ok(text) if text.is_empty() => {}
+            Ok(text) => println!("> {}", String::from_utf8_lossy(&text)),
+            Err(e) => warn!("Nachricht verworfen: {}", e),
+        }
+    }
+}
+
+/// Liest Zeilen von STDIN und empfängt parallel, bis STDIN endet
+fn converse(stream: TcpStream, session: Session, mut rng: Drbg) -> tool_error::Result<()> {
+    let session = Mutex::new(session);
+    let receiver = stream.try_clone().context("Kann Verbindung nicht teilen")?;
+    let receiver_rng = rng.fork();
+    let mut sender = stream;
+
+    thread::scope(|scope| {
+        scope.spawn(|| receive_loop(receiver, &session, receiver_rng));
+
+        let result = (|| {
+            for line in std::io::stdin().lock().lines() {
+                let line = line.context("Fehler beim Lesen von stdin")?;
+                let message = session.lock().expect("kein Thread bricht mit gesperrtem Mutex ab").encrypt(line.as_bytes());
+                match message {
+                    Ok(message) => {
+                        debug!("Gesendet {}", describe(&message.header));
+                        write_bytes(&mut sender, &message.to_bytes()).context("Fehler beim Senden")?;
+                        sender.flush().context("Fehler beim Senden")?;
+                    }
+                    Err(e) => warn!("{}", e),
+                }
+            }
+            Ok(())
+        })();
+        // Beendet auch den Empfangsthread
+        let _ = sender.shutdown(Shutdown::Both);
+        result
+    })
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+
+    match args.command {
+        Command::Simulate { rng } => simulate(&mut rng.rng()),
+        Command::Listen { port, rng } => {
+            let mut rng = rng.rng();
+            let listener = TcpListener::bind(("0.0.0.0", port)).context("Kann Port nicht öffnen")?;
+            info!("Warte auf Verbindung an Port {}...", port);
+            let (mut stream, peer) = listener.accept().context("Fehler beim Annehmen der Verbindung")?;
+            info!("Verbunden mit {}", peer);
+
+            let ratchet = StaticSecret::random_from_rng(&mut rng);
+            let public = PublicKey::from(&ratchet);
+            write_bytes(&mut stream, public.as_bytes()).context("Fehler beim Senden")?;
+            let alice: [u8; 32] = read_bytes(&mut stream).context("Fehler beim Empfangen")?
+                .try_into()
+                .map_err(|_| ToolError::data("Schlüssel des Gegenübers muss 32 Bytes lang sein"))?;
+            let alice = PublicKey::from(alice);
+
+            println!("Sicherheitsnummer: {}", safety_number(&public, &alice));
+            let shared = start_secret(&ratchet.diffie_hellman(&alice).to_bytes());
+            converse(stream, Session::bob(&shared, ratchet), rng)
+        }
+        Command::Connect { address, rng } => {
+            let mut rng = rng.rng();
+            let mut stream = TcpStream::connect(&address).with_context(|| format!("Kann keine Verbindung zu {} aufbauen", address))?;
+            let bob: [u8; 32] = read_bytes(&mut stream).context("Fehler beim Empfangen")?
+                .try_into()
+                .map_err(|_| ToolError::data("Schlüssel des Gegenübers muss 32 Bytes lang sein"))?;
+            let bob = PublicKey::from(bob);
+            let ephemeral = StaticSecret::random_from_rng(&mut rng);
+            let public = PublicKey::from(&ephemeral);
+            write_bytes(&mut stream, public.as_bytes()).context("Fehler beim Senden")?;
+
+            println!("Sicherheitsnummer: {}", safety_number(&bob, &public));
+            let shared = start_secret(&ephemeral.diffie_hellman(&bob).to_bytes());
+            let mut session = Session::alice(&shared, bob, &mut rng);
+            let hello = session.encrypt(b"").map_err(failed)?;
+            write_bytes(&mut stream, &hello.to_bytes()).context("Fehler beim Senden")?;
+            converse(stream, session, rng)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safety_number_format() {
+        let bob = PublicKey::from([1u8; 32]);
+        let alice = PublicKey::from([2u8; 32]);
+        let number = safety_number(&bob, &alice);
+        assert_eq!(number.len(), 5 * 5 + 4);
+        assert_eq!(number, safety_number(&bob, &alice));
+        assert_ne!(number, safety_number(&alice, &bob));
+    }
+}
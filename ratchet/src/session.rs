@@ -0,0 +1,381 @@
+//! Double Ratchet nach der Signal-Spezifikation (Perrin, Marlinspike 2016)
+//!
+//! Zwei Ratschen greifen ineinander:
+//!
+//! - Die symmetrische Ratsche leitet aus dem Kettenschlüssel per HMAC für
+//!   jede Nachricht einen neuen Nachrichtenschlüssel und den nächsten
+//!   Kettenschlüssel ab. Alte Schlüssel werden verworfen; wer den aktuellen
+//!   Zustand stiehlt, kann frühere Nachrichten nicht entschlüsseln.
+//! - Die DH-Ratsche mischt bei jedem Wechsel der Senderichtung ein neues
+//!   X25519-Geheimnis in den Wurzelschlüssel (HKDF). Ein gestohlener Zustand
+//!   wird dadurch nach einem Hin und Her wertlos.
+//!
+//! Nachrichten sind mit AES-256-GCM verschlüsselt, der Kopf (aktueller
+//! Ratschenschlüssel, Länge der vorigen Kette, Nummer) ist als zusätzliche
+//! Daten authentisiert. Übersprungene Nachrichtenschlüssel werden für
+//! verspätete Nachrichten aufbewahrt, höchstens [`MAX_SKIP`] pro Kette.
+
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use rand_core::{CryptoRng, RngCore};
+use secret::Secret;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::fmt;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+/// Höchstens so viele Nachrichtenschlüssel werden für eine Kette vorausberechnet
+pub const MAX_SKIP: u32 = 100;
+
+const ROOT_INFO: &[u8] = b"cryptolab ratchet Wurzel";
+const HEADER_LEN: usize = 32 + 4 + 4;
+const TAG_LEN: usize = 16;
+/// Jeder Nachrichtenschlüssel verschlüsselt genau eine Nachricht
+const NONCE: [u8; 12] = [0; 12];
+
+type Key = Secret<[u8; 32]>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RatchetError {
+    /// Noch keine Sendekette: Bob muss zuerst eine Nachricht von Alice empfangen
+    NotReady,
+    /// Nachricht ist zu kurz
+    Malformed,
+    /// GCM-Tag falsch: verändert, wiederholt oder mit verworfenem Schlüssel
+    Authentication,
+    /// Mehr als MAX_SKIP fehlende Nachrichten in einer Kette
+    TooManySkipped,
+}
+
+impl fmt::Display for RatchetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RatchetError::NotReady => write!(f, "Senden erst nach der ersten Nachricht des Gegenübers möglich"),
+            RatchetError::Malformed => write!(f, "Nachricht ist unvollständig"),
+            RatchetError::Authentication => write!(f, "Nachricht nicht authentisch oder Schlüssel bereits verworfen"),
+            RatchetError::TooManySkipped => write!(f, "Mehr als {} Nachrichten fehlen", MAX_SKIP),
+        }
+    }
+}
+
+impl std::error::Error for RatchetError {}
+
+/// Unverschlüsselter, aber authentisierter Kopf einer Nachricht
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    /// Aktueller Ratschenschlüssel des Absenders
+    pub ratchet_key: [u8; 32],
+    /// Anzahl Nachrichten in der vorigen Sendekette des Absenders
+    pub previous: u32,
+    /// Nummer der Nachricht in der aktuellen Kette
+    pub number: u32,
+}
+
+impl Header {
+    fn to_bytes(self) -> [u8; HEADER_LEN] {
+        let mut bytes = [0u8; HEADER_LEN];
+        bytes[..32].copy_from_slice(&self.ratchet_key);
+        bytes[32..36].copy_from_slice(&self.previous.to_be_bytes());
+        bytes[36..].copy_from_slice(&self.number.to_be_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; HEADER_LEN]) -> Self {
+        Header {
+            ratchet_key: bytes[..32].try_into().expect("32 Bytes"),
+            previous: u32::from_be_bytes(bytes[32..36].try_into().expect("4 Bytes")),
+            number: u32::from_be_bytes(bytes[36..].try_into().expect("4 Bytes")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub header: Header,
+    pub ciphertext: Vec<u8>,
+}
+
+impl Message {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.header.to_bytes().as_slice(), &self.ciphertext].concat()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, RatchetError> {
+        if bytes.len() < HEADER_LEN + TAG_LEN {
+            return Err(RatchetError::Malformed);
+        }
+        let (header, ciphertext) = bytes.split_at(HEADER_LEN);
+        Ok(Message { header: Header::from_bytes(header.try_into().expect("Länge ist geprüft")), ciphertext: ciphertext.to_vec() })
+    }
+}
+
+/// KDF_RK: neuer Wurzelschlüssel und Kettenschlüssel aus einem DH-Ergebnis
+fn kdf_root(root_key: &[u8; 32], dh_output: &[u8; 32]) -> (Key, Key) {
+    let mut material = Secret::new([0u8; 64]);
+    Hkdf::<Sha256>::new(Some(root_key), dh_output)
+        .expand(ROOT_INFO, &mut *material)
+        .expect("64 Bytes liegen unter der Grenze von HKDF");
+    split_keys(&material)
+}
+
+/// KDF_CK: nächster Kettenschlüssel (HMAC mit 0x02) und Nachrichtenschlüssel (0x01)
+fn kdf_chain(chain_key: &[u8; 32]) -> (Key, Key) {
+    let step = |constant: u8| {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(chain_key).expect("HMAC akzeptiert jede Schlüssellänge");
+        mac.update(&[constant]);
+        Secret::new(<[u8; 32]>::from(mac.finalize().into_bytes()))
+    };
+    (step(0x02), step(0x01))
+}
+
+fn split_keys(material: &[u8; 64]) -> (Key, Key) {
+    let mut first = Secret::new([0u8; 32]);
+    let mut second = Secret::new([0u8; 32]);
+    first.copy_from_slice(&material[..32]);
+    second.copy_from_slice(&material[32..]);
+    (first, second)
+}
+
+fn dh(own: &StaticSecret, remote: &PublicKey) -> Key {
+    Secret::new(own.diffie_hellman(remote).to_bytes())
+}
+
+fn seal(message_key: &[u8; 32], header: Header, plaintext: &[u8]) -> Message {
+    let cipher = Aes256Gcm::new(message_key.into());
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&NONCE), Payload { msg: plaintext, aad: &header.to_bytes() })
+        .expect("GCM verschlüsselt Nachrichten bis 64 GiB");
+    Message { header, ciphertext }
+}
+
+fn open(message_key: &[u8; 32], message: &Message) -> Result<Vec<u8>, RatchetError> {
+    let cipher = Aes256Gcm::new(message_key.into());
+    cipher
+        .decrypt(Nonce::from_slice(&NONCE), Payload { msg: &message.ciphertext, aad: &message.header.to_bytes() })
+        .map_err(|_| RatchetError::Authentication)
+}
+
+/// Zustand einer Partei
+#[derive(Clone)]
+pub struct Session {
+    own_ratchet: StaticSecret,
+    remote_ratchet: Option<PublicKey>,
+    root_key: Key,
+    sending_chain: Option<Key>,
+    receiving_chain: Option<Key>,
+    sent: u32,
+    received: u32,
+    previous_sent: u32,
+    skipped: HashMap<([u8; 32], u32), Key>,
+}
+
+impl Session {
+    /// Alice kennt Bobs ersten Ratschenschlüssel und kann sofort senden
+    pub fn alice(shared_secret: &[u8; 32], bob_ratchet: PublicKey, rng: &mut (impl RngCore + CryptoRng)) -> Self {
+        let own_ratchet = StaticSecret::random_from_rng(rng);
+        let (root_key, sending_chain) = kdf_root(shared_secret, &dh(&own_ratchet, &bob_ratchet));
+        Session {
+            own_ratchet,
+            remote_ratchet: Some(bob_ratchet),
+            root_key,
+            sending_chain: Some(sending_chain),
+            receiving_chain: None,
+            sent: 0,
+            received: 0,
+            previous_sent: 0,
+            skipped: HashMap::new(),
+        }
+    }
+
+    /// Bob wartet mit dem Schlüsselpaar, dessen öffentliche Hälfte Alice kennt
+    pub fn bob(shared_secret: &[u8; 32], own_ratchet: StaticSecret) -> Self {
+        Session {
+            own_ratchet,
+            remote_ratchet: None,
+            root_key: Secret::new(*shared_secret),
+            sending_chain: None,
+            receiving_chain: None,
+            sent: 0,
+            received: 0,
+            previous_sent: 0,
+            skipped: HashMap::new(),
+        }
+    }
+
+    /// Aktueller eigener Ratschenschlüssel
+    pub fn ratchet_key(&self) -> PublicKey {
+        PublicKey::from(&self.own_ratchet)
+    }
+
+    /// Anzahl aufbewahrter Schlüssel für noch fehlende Nachrichten
+    pub fn skipped_keys(&self) -> usize {
+        self.skipped.len()
+    }
+
+    pub fn encrypt(&mut self, plaintext: &[u8]) -> Result<Message, RatchetError> {
+        let chain = self.sending_chain.as_ref().ok_or(RatchetError::NotReady)?;
+        let (next_chain, message_key) = kdf_chain(chain);
+        let header = Header { ratchet_key: self.ratchet_key().to_bytes(), previous: self.previous_sent, number: self.sent };
+
+        self.sending_chain = Some(next_chain);
+        self.sent += 1;
+        Ok(seal(&message_key, header, plaintext))
+    }
+
+    /// Entschlüsselt eine Nachricht; bei einem Fehler bleibt der Zustand unverändert
+    pub fn decrypt(&mut self, message: &Message, rng: &mut (impl RngCore + CryptoRng)) -> Result<Vec<u8>, RatchetError> {
+        let mut next = self.clone();
+        let plaintext = next.decrypt_in_place(message, rng)?;
+        *self = next;
+        Ok(plaintext)
+    }
+
+    fn decrypt_in_place(&mut self, message: &Message, rng: &mut (impl RngCore + CryptoRng)) -> Result<Vec<u8>, RatchetError> {
+        let header = message.header;
+        if let Some(message_key) = self.skipped.remove(&(header.ratchet_key, header.number)) {
+            return open(&message_key, message);
+        }
+
+        if self.remote_ratchet.map(|key| key.to_bytes()) != Some(header.ratchet_key) {
+            self.skip_until(header.previous)?;
+            self.dh_ratchet(PublicKey::from(header.ratchet_key), rng);
+        }
+        self.skip_until(header.number)?;
+
+        let chain = self.receiving_chain.as_ref().expect("nach dem DH-Schritt gibt es eine Empfangskette");
+        let (next_chain, message_key) = kdf_chain(chain);
+        self.receiving_chain = Some(next_chain);
+        self.received += 1;
+        open(&message_key, message)
+    }
+
+    /// Bewahrt die Schlüssel der Nachrichten bis ausschließlich `until` auf
+    fn skip_until(&mut self, until: u32) -> Result<(), RatchetError> {
+        let (Some(remote), Some(mut chain)) = (self.remote_ratchet, self.receiving_chain.clone()) else {
+            return Ok(());
+        };
+        if until > self.received.saturating_add(MAX_SKIP) {
+            return Err(RatchetError::TooManySkipped);
+        }
+        while self.received < until {
+            let (next_chain, message_key) = kdf_chain(&chain);
+            self.skipped.insert((remote.to_bytes(), self.received), message_key);
+            chain = next_chain;
+            self.received += 1;
+        }
+        self.receiving_chain = Some(chain);
+        Ok(())
+    }
+
+    /// DH-Schritt: neue Empfangskette zum neuen Schlüssel des Gegenübers, dann eigenes neues Schlüsselpaar
+    fn dh_ratchet(&mut self, remote: PublicKey, rng: &mut (impl RngCore + CryptoRng)) {
+        self.previous_sent = self.sent;
+        self.sent = 0;
+        self.received = 0;
+        self.remote_ratchet = Some(remote);
+
+        let (root_key, receiving_chain) = kdf_root(&self.root_key, &dh(&self.own_ratchet, &remote));
+        self.own_ratchet = StaticSecret::random_from_rng(rng);
+        let (root_key, sending_chain) = kdf_root(&root_key, &dh(&self.own_ratchet, &remote));
+
+        self.root_key = root_key;
+        self.receiving_chain = Some(receiving_chain);
+        self.sending_chain = Some(sending_chain);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::{Algorithm, Drbg};
+
+    fn pair(rng: &mut Drbg) -> (Session, Session) {
+        let shared = [7u8; 32];
+        let bob_ratchet = StaticSecret::random_from_rng(&mut *rng);
+        let bob_public = PublicKey::from(&bob_ratchet);
+        (Session::alice(&shared, bob_public, rng), Session::bob(&shared, bob_ratchet))
+    }
+
+    #[test]
+    fn test_conversation() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"ratchet");
+        let (mut alice, mut bob) = pair(&mut rng);
+        assert_eq!(bob.encrypt(b"zu frueh"), Err(RatchetError::NotReady));
+
+        for round in 0..3 {
+            let text = format!("Alice {}", round);
+            let message = alice.encrypt(text.as_bytes()).unwrap();
+            assert_eq!(bob.decrypt(&message, &mut rng).unwrap(), text.as_bytes());
+
+            // Jeder Wechsel der Richtung erneuert den Ratschenschlüssel
+            let reply = format!("Bob {}", round);
+            let alice_key = alice.ratchet_key().to_bytes();
+            let message = bob.encrypt(reply.as_bytes()).unwrap();
+            assert_eq!(alice.decrypt(&message, &mut rng).unwrap(), reply.as_bytes());
+            assert_ne!(alice.ratchet_key().to_bytes(), alice_key);
+        }
+    }
+
+    #[test]
+    fn test_out_of_order_and_lost_messages() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"ratchet");
+        let (mut alice, mut bob) = pair(&mut rng);
+        let messages: Vec<Message> = (0..4).map(|i| alice.encrypt(&[i]).unwrap()).collect();
+
+        assert_eq!(bob.decrypt(&messages[2], &mut rng).unwrap(), [2]);
+        assert_eq!(bob.skipped_keys(), 2);
+        assert_eq!(bob.decrypt(&messages[0], &mut rng).unwrap(), [0]);
+        assert_eq!(bob.decrypt(&messages[3], &mut rng).unwrap(), [3]);
+
+        // Nachricht 1 aus der alten Kette trifft erst nach Bobs Antwort und Alices neuem Schlüssel ein
+        let reply = bob.encrypt(b"ok").unwrap();
+        alice.decrypt(&reply, &mut rng).unwrap();
+        let next = alice.encrypt(b"neue Kette").unwrap();
+        assert_eq!(bob.decrypt(&next, &mut rng).unwrap(), b"neue Kette");
+        assert_eq!(bob.decrypt(&messages[1], &mut rng).unwrap(), [1]);
+        assert_eq!(bob.skipped_keys(), 0);
+    }
+
+    #[test]
+    fn test_replay_and_tampering_rejected() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"ratchet");
+        let (mut alice, mut bob) = pair(&mut rng);
+        let message = alice.encrypt(b"Betrag 10").unwrap();
+
+        let mut tampered = message.clone();
+        tampered.ciphertext[0] ^= 1;
+        assert_eq!(bob.decrypt(&tampered, &mut rng), Err(RatchetError::Authentication));
+        let mut renumbered = message.clone();
+        renumbered.header.number = 1;
+        assert_eq!(bob.decrypt(&renumbered, &mut rng), Err(RatchetError::Authentication));
+
+        // Fehlversuche ändern den Zustand nicht, der Schlüssel wird danach verworfen
+        assert_eq!(bob.decrypt(&message, &mut rng).unwrap(), b"Betrag 10");
+        assert_eq!(bob.decrypt(&message, &mut rng), Err(RatchetError::Authentication));
+    }
+
+    #[test]
+    fn test_skip_limit() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"ratchet");
+        let (mut alice, mut bob) = pair(&mut rng);
+        bob.decrypt(&alice.encrypt(b"erste").unwrap(), &mut rng).unwrap();
+        let mut last = None;
+        for _ in 0..=MAX_SKIP + 1 {
+            last = Some(alice.encrypt(b"x").unwrap());
+        }
+        assert_eq!(bob.decrypt(&last.unwrap(), &mut rng), Err(RatchetError::TooManySkipped));
+    }
+
+    #[test]
+    fn test_wire_format() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"ratchet");
+        let (mut alice, _) = pair(&mut rng);
+        let message = alice.encrypt(b"Hallo").unwrap();
+        let bytes = message.to_bytes();
+        assert_eq!(bytes.len(), HEADER_LEN + 5 + TAG_LEN);
+        assert_eq!(Message::from_bytes(&bytes).unwrap(), message);
+        assert_eq!(Message::from_bytes(&bytes[..HEADER_LEN + TAG_LEN - 1]), Err(RatchetError::Malformed));
+    }
+}
@@ -0,0 +1,225 @@
+use clap::Parser;
+use logging::{Verbosity, warn};
+use std::path::{Path, PathBuf};
+use num_bigint::{BigUint, RandBigInt};
+use rand::Rng;
+use crypto_math::{is_probable_prime, mod_inverse};
+use csprng::RngArgs;
+use key_formats::{Format, KeyFile, RsaPrimes, RsaPrivateKey, RsaPublicKey};
+use secret::Secret;
+use tool_error::ToolError;
+
+/// RSA-Schlüsselgenerierung
+/// 
+/// Generiert RSA-Schlüsselpaare mit den angegebenen Parametern.
+/// Implementiert Miller-Rabin Primzahltest und Square-and-Multiply für modulare Exponentiation.
+#[derive(Parser)]
+#[command(
+    name = "rsa-keygen",
+    about = "RSA key generation utility",
+    long_about = "
+Generiert RSA-Schlüsselpaare durch:
+1. Erzeugung zweier großer Primzahlen p, q
+2. Berechnung von n = p * q und φ(n) = (p-1)(q-1)  
+3. Wahl von e (oft 65537) teilerfremd zu φ(n)
+4. Berechnung von d ≡ e^(-1) (mod φ(n)) mit erweitertem euklidischen Algorithmus
+
+AUSGABEFORMAT (--format zeilen, Standard):
+- Privater Schlüssel: d (Zeile 1), n (Zeile 2)
+- Öffentlicher Schlüssel: e (Zeile 1), n (Zeile 2)  
+- Primzahlen: p (Zeile 1), q (Zeile 2)
+Mit --format json oder toml werden dieselben Werte unter ihren Namen gespeichert.
+"
+)]
+#[command(version)]
+struct Args {
+    /// Gewünschte Bitlänge des Schlüssels (ca., als Zahl)
+    #[arg(long, value_name = "LÄNGE")]
+    length: u32,
+
+    /// Ausgabedatei für privaten Schlüssel (d, n)
+    #[arg(long, value_name = "OUTPUT_PRIVAT")]
+    private_output: PathBuf,
+
+    /// Ausgabedatei für öffentlichen Schlüssel (e, n)
+    #[arg(long, value_name = "OUTPUT_ÖFFENTLICH")]
+    public_output: PathBuf,
+
+    /// Ausgabedatei für benutzte Primzahlen (p, q)
+    #[arg(long, value_name = "OUTPUT_BENUTZTE_PRIMZAHLEN")]
+    primes_output: PathBuf,
+
+    /// Format der Schlüsseldateien: zeilen, json oder toml
+    #[arg(long, default_value = "zeilen")]
+    format: Format,
+
+    /// Erlaubt Schlüssel ab 32 Bit statt ab 512 Bit
+    ///
+    /// Nur für Übungen: `factor --key` bricht solche Schlüssel mit dem
+    /// quadratischen Sieb in Sekunden.
+    #[arg(long)]
+    insecure: bool,
+
+    #[command(flatten)]
+    rng: RngArgs,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+/// Generiert Primzahl mit ungefähr der gewünschten Bitlänge
+/// 
+/// Verwendet die Optimierung aus dem Lab: Teste 30z + i für i ∈ {1,7,11,13,17,19,23,29,...}
+/// um Zahlen zu vermeiden, die durch kleine Primzahlen teilbar sind.
+fn generate_prime(bit_length: u32, rng: &mut impl Rng) -> Secret<BigUint> {
+    let offsets = [1u32, 7, 11, 13, 17, 19, 23, 29];
+    
+    loop {
+        // Generiere zufällige Basis z; sie verrät die Primzahl fast vollständig
+        let z = Secret::new(rng.gen_biguint(bit_length as u64));
+        let base = Secret::new((&*z / 30u32) * 30u32);
+        
+        // Teste 30z + i für verschiedene i
+        for &offset in &offsets {
+            let candidate = Secret::new(&*base + BigUint::from(offset));
+            
+            // Stelle sicher, dass die Bitlänge stimmt
+            if candidate.bits() as u32 != bit_length {
+                continue;
+            }
+            
+            // Miller-Rabin Test mit 20 Runden (hohe Sicherheit)
+            if is_probable_prime(&candidate, 20) {
+                return candidate;
+            }
+        }
+    }
+}
+
+/// RSA-Schlüsselmaterial: Primzahlen (p, q), öffentlicher (e, n) und privater Schlüssel (d, n)
+type RsaKeyMaterial = (RsaPrimes, RsaPublicKey, RsaPrivateKey);
+
+/// Generiert RSA-Schlüsselpaar
+/// 
+/// Algorithmus:
+/// 1. Generiere zwei verschiedene Primzahlen p, q
+/// 2. Berechne n = p * q und φ(n) = (p-1)(q-1)
+/// 3. Wähle e teilerfremd zu φ(n) (verwendet 2^16 + 1 = 65537)
+/// 4. Berechne d ≡ e^(-1) (mod φ(n))
+fn generate_keypair(bit_length: u32, rng: &mut impl Rng) -> tool_error::Result<RsaKeyMaterial> {
+    // Generiere zwei verschiedene Primzahlen
+    let p = generate_prime(bit_length / 2, rng);
+    let mut q = generate_prime(bit_length / 2, rng);
+    
+    // Stelle sicher, dass p ≠ q
+    while p == q {
+        q = generate_prime(bit_length / 2, rng);
+    }
+    
+    let n = &*p * &*q;
+    let phi = Secret::new((&*p - 1u32) * (&*q - 1u32));
+    
+    // Wähle e = 2^16 + 1 = 65537 (häufig verwendet, da prim und kleine Hamming-Gewicht)
+    let e = BigUint::from(65537u32);
+    
+    // Berechne d als modulares Inverses von e modulo φ(n),
+    // das existiert genau dann, wenn gcd(e, φ(n)) = 1
+    let d = mod_inverse(&e, &phi)
+        .map(Secret::new)
+        .ok_or("e ist nicht teilerfremd zu φ(n)")?;
+    
+    Ok((RsaPrimes { p, q }, RsaPublicKey { e, n: n.clone() }, RsaPrivateKey { d, n }))
+}
+
+/// Schreibt eine Schlüsseldatei im gewählten Format
+fn write_key_file(file_path: &Path, key: &impl KeyFile, format: Format) -> tool_error::Result<()> {
+    // Erstelle Elternverzeichnis falls nötig
+    if let Some(parent) = file_path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| ToolError::io(parent, e))?;
+    }
+    
+    key.write(file_path, format)?;
+    
+    Ok(())
+}
+
+/// Startet das Werkzeug mit den übergebenen Kommandozeilenargumenten
+///
+/// Das erste Argument ist wie bei `std::env::args_os` der Programmname;
+/// so lässt sich das Werkzeug auch aus `cryptolab` heraus aufrufen.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+    
+    // Validiere Eingabe
+    if args.length < 32 {
+        return Err(ToolError::usage("Bitlänge muss mindestens 32 sein"));
+    }
+    if args.length < 512 {
+        if !args.insecure {
+            return Err(ToolError::usage("Bitlänge sollte mindestens 512 sein für Sicherheit (--insecure für Übungsschlüssel)"));
+        }
+        warn!("{}-Bit-Schlüssel sind in Sekunden faktorisiert und nur für Übungen gedacht", args.length);
+    }
+    
+    // Generiere RSA-Schlüsselpaar
+    // Geheime Teile werden beim Verlassen der Funktion überschrieben
+    let (primes, public_key, private_key) = generate_keypair(args.length, &mut args.rng.rng())?;
+    
+    // Schreibe privaten Schlüssel (d, n)
+    write_key_file(&args.private_output, &private_key, args.format)?;
+    
+    // Schreibe öffentlichen Schlüssel (e, n)
+    write_key_file(&args.public_output, &public_key, args.format)?;
+    
+    // Schreibe benutzte Primzahlen (p, q)
+    write_key_file(&args.primes_output, &primes, args.format)?;
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::{Algorithm, Drbg};
+    use num_traits::One;
+    
+    #[test]
+    fn test_keypair_generation_small() {
+        let (RsaPrimes { p, q }, RsaPublicKey { e, n }, private_key) = generate_keypair(1024, &mut Drbg::from_seed(Algorithm::ChaCha20, b"test")).unwrap();
+        
+        // Teste, dass p und q prim sind (approximativ)
+        assert!(is_probable_prime(&p, 10));
+        assert!(is_probable_prime(&q, 10));
+        
+        // Teste, dass n = p * q
+        assert_eq!(n, &*p * &*q);
+        assert_eq!(private_key.n, n);
+        
+        // Teste, dass e * d ≡ 1 (mod φ(n))
+        let phi = (&*p - 1u32) * (&*q - 1u32);
+        assert_eq!((&e * &*private_key.d) % &phi, BigUint::one());
+    }
+
+    #[test]
+    fn test_insecure_length() {
+        let dir = std::env::temp_dir().join(format!("rsa-keygen-insecure-{}", std::process::id()));
+        let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+        let args = |length: &str, insecure: bool| {
+            let mut args = vec!["rsa-keygen".to_string(), "--length".into(), length.into(), "--private-output".into(), path("priv"), "--public-output".into(), path("pub"), "--primes-output".into(), path("primes")];
+            if insecure {
+                args.push("--insecure".into());
+            }
+            args
+        };
+        assert!(run(args("64", false)).is_err());
+        assert!(run(args("16", true)).is_err());
+        assert!(run(args("64", true)).is_ok());
+        assert!((63..=64).contains(&RsaPublicKey::read(path("pub")).unwrap().n.bits()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
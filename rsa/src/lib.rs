@@ -0,0 +1,230 @@
+pub mod pkcs1;
+
+use clap::{Parser, ValueEnum};
+use logging::{Verbosity, info};
+use std::path::{Path, PathBuf};
+use num_bigint::BigUint;
+use num_traits::Zero;
+use crypto_math::mod_pow;
+use key_formats::{KeyFile, RsaPrivateKey, RsaPublicKey};
+use secret::Secret;
+use tool_error::{Context, ToolError};
+
+/// RSA Verschlüsselung/Entschlüsselung mit Textbook RSA (ungepolstert)
+/// 
+/// SICHERHEITSHINWEIS: Dies ist eine Bildungsimplementierung von Textbook RSA
+/// ohne Padding. Nicht für produktive Kryptographie verwenden!
+#[derive(Parser)]
+#[command(
+    name = "rsa-textbook",
+    about = "Simple RSA encryption/decryption utility using textbook (unpadded) RSA",
+    long_about = "
+Diese Implementierung verwendet den klassischen Square-and-Multiply Algorithmus
+für modulare Exponentiation. Alle Werte werden als Dezimalzahlen gelesen und geschrieben.
+
+EINGABEFORMAT:
+- Input-Datei: Eine einzige Dezimalzahl (Klartext oder Geheimtext)
+- Schlüssel-Datei: Zwei Zeilen in Dezimal:
+  * Zeile 1: Exponent (e für Verschlüsselung, d für Entschlüsselung)  
+  * Zeile 2: Modulus n
+  oder JSON/TOML aus rsa-keygen --format mit den Feldern e bzw. d und n
+
+ALGORITHMUS:
+Modular Exponentiation via Square-and-Multiply (LSB-first Bit-Scanning).
+Für jedes gesetzte Bit i im Exponenten: y = (y * x) mod n
+Nach jedem Schritt: x = (x * x) mod n
+"
+)]
+#[command(version, author)]
+struct Args {
+    /// Operation: encrypt oder decrypt
+    #[arg(long, value_enum)]
+    operation: Operation,
+
+    /// Pfad zur Eingabedatei (enthält eine Dezimalzahl)
+    #[arg(long, value_name = "INPUT_FILE")]
+    file: PathBuf,
+
+    /// Pfad zur Schlüsseldatei (zwei Zeilen: Exponent, Modulus)  
+    #[arg(long, value_name = "KEY_FILE")]
+    key: PathBuf,
+
+    /// Ausgabeziel (Datei oder Verzeichnis)
+    /// 
+    /// Wenn ein Verzeichnis angegeben wird, wird die Ausgabedatei
+    /// dort mit dem gleichen Namen wie die Eingabedatei erstellt.
+    #[arg(long, value_name = "OUTPUT_DESTINATION")]
+    output: PathBuf,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum Operation {
+    /// RSA-Verschlüsselung: ciphertext = plaintext^e mod n
+    Encrypt,
+    /// RSA-Entschlüsselung: plaintext = ciphertext^d mod n  
+    Decrypt,
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operation::Encrypt => write!(f, "Verschlüsselung"),
+            Operation::Decrypt => write!(f, "Entschlüsselung"),
+        }
+    }
+}
+
+/// RSA Verschlüsselung: ciphertext = plaintext^e mod n
+/// 
+/// # Hinweis
+/// Kein Padding wird angewendet. Der Aufrufer muss sicherstellen,
+/// dass 0 ≤ plaintext < n.
+fn encrypt(plaintext: &BigUint, e: &BigUint, n: &BigUint) -> BigUint {
+    mod_pow(plaintext, e, n)
+}
+
+/// RSA Entschlüsselung: plaintext = ciphertext^d mod n
+/// 
+/// # Hinweis  
+/// Kein Padding wird entfernt. Der Aufrufer muss sicherstellen,
+/// dass 0 ≤ ciphertext < n.
+fn decrypt(ciphertext: &BigUint, d: &BigUint, n: &BigUint) -> BigUint {
+    mod_pow(ciphertext, d, n)
+}
+
+/// Liest eine große Ganzzahl aus einer Datei
+/// 
+/// Die Datei sollte eine einzige Dezimalzahl enthalten.
+/// Führende und nachfolgende Leerzeichen werden ignoriert.
+fn read_big_uint(file_path: &Path) -> tool_error::Result<BigUint> {
+    let content = tool_error::fs::read_to_string(file_path)?;
+    
+    let trimmed = content.trim();
+    BigUint::parse_bytes(trimmed.as_bytes(), 10)
+        .ok_or_else(|| ToolError::data(format!("Ungültiges Zahlenformat in {}: '{}'", 
+                                               file_path.display(), trimmed)))
+}
+
+/// Liest den RSA-Schlüssel für die gewählte Operation
+/// 
+/// Zum Verschlüsseln wird ein öffentlicher Schlüssel (e, n) erwartet, zum
+/// Entschlüsseln ein privater (d, n). Im Zeilenformat sind beide gleich
+/// aufgebaut:
+/// 1. Exponent (e für Verschlüsselung oder d für Entschlüsselung)
+/// 2. Modulus n
+///
+/// Der Exponent wird in beiden Fällen als Geheimnis behandelt und beim
+/// Freigeben überschrieben.
+fn read_key(file_path: &Path, operation: Operation) -> tool_error::Result<(Secret<BigUint>, BigUint)> {
+    let context = || format!("Schlüsseldatei {}", file_path.display());
+    let (exponent, modulus) = match operation {
+        Operation::Encrypt => {
+            let key = RsaPublicKey::read(file_path).with_context(context)?;
+            (Secret::new(key.e), key.n)
+        }
+        Operation::Decrypt => {
+            let key = RsaPrivateKey::read(file_path).with_context(context)?;
+            (key.d, key.n)
+        }
+    };
+    
+    if modulus.is_zero() {
+        return Err(ToolError::data("Modulus darf nicht null sein"));
+    }
+    
+    Ok((exponent, modulus))
+}
+
+/// Bestimmt den finalen Ausgabepfad
+/// 
+/// Wenn `output_destination` ein Verzeichnis ist, wird der Dateiname
+/// von `input_path` verwendet. Andernfalls wird `output_destination`
+/// direkt als Dateipfad verwendet.
+fn resolve_output_path(output_destination: &Path, input_path: &Path) -> tool_error::Result<PathBuf> {
+    let output_path = if output_destination.exists() && output_destination.is_dir() {
+        let input_filename = input_path
+            .file_name()
+            .ok_or_else(|| ToolError::usage("Ungültiger Eingabedateiname"))?;
+        output_destination.join(input_filename)
+    } else {
+        output_destination.to_path_buf()
+    };
+    
+    // Elternverzeichnis erstellen falls nötig
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| ToolError::io(parent, e))
+            .context("Kann Verzeichnis nicht erstellen")?;
+    }
+    
+    Ok(output_path)
+}
+
+/// Startet das Werkzeug mit den übergebenen Kommandozeilenargumenten
+///
+/// Das erste Argument ist wie bei `std::env::args_os` der Programmname;
+/// so lässt sich das Werkzeug auch aus `cryptolab` heraus aufrufen.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+    
+    // Eingabewert lesen
+    let value = read_big_uint(&args.file)?;
+    
+    // Schlüssel lesen (Exponent, Modulus)  
+    let (exponent, modulus) = read_key(&args.key, args.operation)?;
+    
+    // Ausgabepfad bestimmen
+    let output_path = resolve_output_path(&args.output, &args.file)?;
+    
+    // RSA-Operation durchführen
+    let result = match args.operation {
+        Operation::Encrypt => encrypt(&value, &exponent, &modulus),
+        Operation::Decrypt => decrypt(&value, &exponent, &modulus),
+    };
+    
+    // Ergebnis in Datei schreiben
+    tool_error::fs::write(&output_path, result.to_string())?;
+    
+    info!("{} abgeschlossen.", args.operation);
+    info!("Ergebnis geschrieben nach: {}", output_path.display());
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigUint;
+    
+    #[test] 
+    fn test_rsa_encrypt_decrypt_cycle() {
+        // Kleine Testzahlen (p=7, q=11, n=77, phi=60)
+        let p = BigUint::from(7u32);
+        let q = BigUint::from(11u32);
+        let n = &p * &q; // n = 77
+        let _phi = (&p - 1u32) * (&q - 1u32); // phi = 60
+        
+        let e = BigUint::from(13u32); // e = 13 (teilerfremd zu 60)
+        let message = BigUint::from(42u32);
+        
+        // Verschlüsseln
+        let ciphertext = encrypt(&message, &e, &n);
+        
+        // Für Entschlüsselung brauchen wir d mit e*d ≡ 1 (mod phi)
+        // 13*d ≡ 1 (mod 60), d = 37 (manuell berechnet für diesen Test)
+        let d = BigUint::from(37u32);
+        
+        // Entschlüsseln
+        let decrypted = decrypt(&ciphertext, &d, &n);
+        
+        assert_eq!(message, decrypted);
+    }
+}
@@ -0,0 +1,101 @@
+//! RSA-Signaturen nach PKCS #1 v1.5 mit SHA-256 (RFC 8017, Abschnitt 8.2)
+//!
+//! Anders als das Textbook-RSA des Werkzeugs wird hier nicht die Nachricht
+//! selbst potenziert, sondern die Kodierung EMSA-PKCS1-v1_5 ihres Hashs.
+//! Der Modul braucht dafür mindestens 62 Bytes (496 Bit).
+
+use crypto_math::mod_pow;
+use key_formats::{RsaPrivateKey, RsaPublicKey};
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+const SHA256: &[u64] = &[2, 16, 840, 1, 101, 3, 4, 2, 1];
+
+/// `DigestInfo ::= SEQUENCE { AlgorithmIdentifier, OCTET STRING }` (RFC 8017, Abschnitt 9.2)
+fn digest_info(message: &[u8]) -> Vec<u8> {
+    let algorithm = asn1::sequence(&[asn1::oid(SHA256), asn1::null()]);
+    asn1::sequence(&[algorithm, asn1::octet_string(&Sha256::digest(message))])
+}
+
+/// EMSA-PKCS1-v1_5: 00 01 FF … FF 00 DigestInfo, so lang wie der Modul
+fn encode(message: &[u8], length: usize) -> Option<Vec<u8>> {
+    let digest_info = digest_info(message);
+    let padding = length.checked_sub(3 + digest_info.len()).filter(|&p| p >= 8)?;
+    let mut encoded = vec![0x00, 0x01];
+    encoded.extend(std::iter::repeat_n(0xff, padding));
+    encoded.push(0x00);
+    encoded.extend(digest_info);
+    Some(encoded)
+}
+
+fn modulus_bytes(n: &BigUint) -> usize {
+    (n.bits() as usize).div_ceil(8)
+}
+
+/// Signatur so lang wie der Modul; `None`, wenn der Modul zu kurz ist
+pub fn sign(message: &[u8], key: &RsaPrivateKey) -> Option<Vec<u8>> {
+    let length = modulus_bytes(&key.n);
+    let encoded = encode(message, length)?;
+    let signature = mod_pow(&BigUint::from_bytes_be(&encoded), &key.d, &key.n).to_bytes_be();
+    let mut fixed = vec![0u8; length - signature.len()];
+    fixed.extend(signature);
+    Some(fixed)
+}
+
+pub fn verify(message: &[u8], signature: &[u8], key: &RsaPublicKey) -> bool {
+    let length = modulus_bytes(&key.n);
+    let value = BigUint::from_bytes_be(signature);
+    if signature.len() != length || value >= key.n {
+        return false;
+    }
+    let recovered = mod_pow(&value, &key.e, &key.n).to_bytes_be();
+    // Die führende Null der Kodierung fehlt in der Zahl
+    encode(message, length).is_some_and(|expected| expected[1..] == recovered[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secret::Secret;
+
+    /// RSA-Schlüssel mit 576 Bit, gerade groß genug für PKCS #1 mit SHA-256
+    fn keys() -> (RsaPrivateKey, RsaPublicKey) {
+        let n: BigUint = "191677726143854315674598428926330859363608329588293420380333240985455824521179813035337110510248831071087008746466405096838035678566522676019644319734501401554761112169596389".parse().unwrap();
+        let d: BigUint = "190852953591363861167675061104226928723808864416804051862591294085873931201147271001432259701997425425908853217310742424346992958524465651074542509542066127393363380985475733".parse().unwrap();
+        (RsaPrivateKey { d: Secret::new(d), n: n.clone() }, RsaPublicKey { e: BigUint::from(65537u32), n })
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let (private, public) = keys();
+        let mut signature = sign(b"Nachricht", &private).unwrap();
+        assert_eq!(signature.len(), 72);
+        assert!(verify(b"Nachricht", &signature, &public));
+        assert!(!verify(b"nachricht", &signature, &public));
+        assert!(!verify(b"Nachricht", &signature[1..], &public));
+        signature[40] ^= 1;
+        assert!(!verify(b"Nachricht", &signature, &public));
+    }
+
+    #[test]
+    fn test_digest_info_prefix() {
+        // Das feste Präfix aus RFC 8017, Abschnitt 9.2, Anmerkung 1
+        let prefix = [0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00, 0x04, 0x20];
+        let info = digest_info(b"abc");
+        assert_eq!(info[..19], prefix);
+        assert_eq!(info[19..], Sha256::digest(b"abc")[..]);
+    }
+
+    #[test]
+    fn test_encoding() {
+        let encoded = encode(b"", 64).unwrap();
+        assert_eq!(encoded.len(), 64);
+        assert_eq!(&encoded[..3], &[0x00, 0x01, 0xff]);
+        assert_eq!(encoded[12], 0x00);
+        assert!(encode(b"", 62).is_some());
+        assert!(encode(b"", 61).is_none());
+
+        let tiny = RsaPrivateKey { d: Secret::new(BigUint::from(37u32)), n: BigUint::from(77u32) };
+        assert_eq!(sign(b"", &tiny), None);
+    }
+}
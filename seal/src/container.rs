@@ -0,0 +1,122 @@
+//! Binärformat und ASCII-Hülle des Containers
+//!
+//! ```text
+//! "SEAL" 0x01
+//! u16 Länge || RSA-gekapselter Schlüssel
+//! u32 Länge || Geheimtext
+//! HMAC-SHA256 über alles davor (32 Bytes)
+//! ```
+//!
+//! Die Hülle ist Base64 mit 64 Zeichen pro Zeile zwischen
+//! `-----BEGIN CRYPTOLAB SEALED MESSAGE-----` und der passenden END-Zeile.
+
+const MAGIC: &[u8] = b"SEAL\x01";
+const BEGIN: &str = "-----BEGIN CRYPTOLAB SEALED MESSAGE-----";
+const END: &str = "-----END CRYPTOLAB SEALED MESSAGE-----";
+const LINE_WIDTH: usize = 64;
+pub const TAG_LENGTH: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sealed {
+    pub encapsulated_key: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+    pub tag: [u8; TAG_LENGTH],
+}
+
+impl Sealed {
+    /// Die vom HMAC geschützten Bytes: alles außer dem Tag
+    pub fn authenticated_data(&self) -> Vec<u8> {
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(&(self.encapsulated_key.len() as u16).to_be_bytes());
+        data.extend_from_slice(&self.encapsulated_key);
+        data.extend_from_slice(&(self.ciphertext.len() as u32).to_be_bytes());
+        data.extend_from_slice(&self.ciphertext);
+        data
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.authenticated_data();
+        bytes.extend_from_slice(&self.tag);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader(bytes);
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err("Kein versiegelter Container (Kennung oder Version falsch)".to_string());
+        }
+        let key_length = u16::from_be_bytes(reader.array()?) as usize;
+        let encapsulated_key = reader.take(key_length)?.to_vec();
+        let ciphertext_length = u32::from_be_bytes(reader.array()?) as usize;
+        let ciphertext = reader.take(ciphertext_length)?.to_vec();
+        let tag = reader.array()?;
+        if !reader.0.is_empty() {
+            return Err(format!("{} überzählige Bytes am Ende des Containers", reader.0.len()));
+        }
+        Ok(Sealed { encapsulated_key, ciphertext, tag })
+    }
+
+    pub fn armor(&self) -> String {
+        let encoded = codec::base64::encode(&self.to_bytes());
+        let mut text = format!("{}\n", BEGIN);
+        for line in encoded.as_bytes().chunks(LINE_WIDTH) {
+            text.push_str(std::str::from_utf8(line).expect("Base64 ist ASCII"));
+            text.push('\n');
+        }
+        text.push_str(END);
+        text.push('\n');
+        text
+    }
+
+    pub fn dearmor(text: &str) -> Result<Self, String> {
+        let body = text.trim()
+            .strip_prefix(BEGIN)
+            .and_then(|rest| rest.strip_suffix(END))
+            .ok_or_else(|| format!("Container muss mit '{}' beginnen und mit '{}' enden", BEGIN, END))?;
+        let bytes = codec::base64::decode(body).map_err(|e| e.to_string())?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// Liest Felder nacheinander aus einem Byte-Slice
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+        if self.0.len() < length {
+            return Err("Container ist abgeschnitten".to_string());
+        }
+        let (head, rest) = self.0.split_at(length);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.take(N)?.try_into().expect("Länge ist geprüft"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sealed() -> Sealed {
+        Sealed { encapsulated_key: vec![1, 2, 3], ciphertext: (0..100).collect(), tag: [7; TAG_LENGTH] }
+    }
+
+    #[test]
+    fn test_armor_roundtrip() {
+        let armored = sealed().armor();
+        assert!(armored.lines().all(|line| line.len() <= LINE_WIDTH || line.starts_with("-----")));
+        assert_eq!(Sealed::dearmor(&armored).unwrap(), sealed());
+    }
+
+    #[test]
+    fn test_malformed() {
+        let bytes = sealed().to_bytes();
+        assert!(Sealed::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Sealed::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+        assert!(Sealed::from_bytes(&bytes[1..]).is_err());
+        assert!(Sealed::dearmor("Zm9v").is_err());
+    }
+}
@@ -0,0 +1,346 @@
+//! seal: signierte und hybrid verschlüsselte Container
+//!
+//! Ablauf beim Versiegeln, ähnlich wie bei PGP:
+//!
+//! 1. Der Absender signiert mit DSA den Fingerabdruck des Empfängerschlüssels
+//!    und die Nachricht. Durch den Fingerabdruck kann der Empfänger die
+//!    signierte Nachricht nicht unbemerkt an Dritte weiterversiegeln.
+//! 2. RSA-KEM: Eine Zufallszahl r < n wird als r^e mod n an den Empfänger
+//!    gekapselt, HKDF-SHA256 leitet aus r einen AES- und einen HMAC-Schlüssel ab.
+//! 3. Signatur und Nachricht werden mit AES-128-CTR verschlüsselt, der HMAC
+//!    über den ganzen Container schützt vor Veränderungen (Encrypt-then-MAC).
+//!
+//! Beim Öffnen wird zuerst der HMAC geprüft, dann entschlüsselt und zuletzt
+//! die Signatur geprüft.
+
+mod container;
+
+use aes_128::Aes128;
+use cipher_modes::{CipherModes, KeyInit};
+use clap::{Parser, Subcommand};
+use csprng::RngArgs;
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use key_formats::{DsaPrivateKey, DsaPublicKey, DsaSignature, KeyFile, RsaPrivateKey, RsaPublicKey};
+use logging::{Verbosity, info};
+use num_bigint::{BigUint, RandBigInt};
+use rand::{CryptoRng, Rng};
+use secret::Secret;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io::{Read, Write};
+use tool_error::{Context, ToolError};
+
+pub use container::Sealed;
+use container::TAG_LENGTH;
+
+/// Kontext für HKDF, trennt die Schlüssel von anderen Verwendungen von r
+const KDF_INFO: &[u8] = b"cryptolab seal v1";
+const BLOCK_SIZE: usize = 16;
+/// Jeder Container hat einen frischen Schlüssel, eine feste Nonce genügt
+const NONCE: [u8; BLOCK_SIZE - 4] = [0; BLOCK_SIZE - 4];
+
+/// Grund, aus dem ein Container nicht geöffnet werden konnte
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenError {
+    /// HMAC falsch: Container verändert oder für einen anderen Schlüssel
+    Integrity,
+    /// Entschlüsselter Inhalt hat nicht das erwartete Format
+    Malformed,
+    /// Signatur passt nicht zum Absenderschlüssel
+    Signature,
+}
+
+impl fmt::Display for OpenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OpenError::Integrity => write!(f, "Integritätsprüfung fehlgeschlagen: Container verändert oder nicht für diesen Schlüssel"),
+            OpenError::Malformed => write!(f, "Entschlüsselter Inhalt ist fehlerhaft"),
+            OpenError::Signature => write!(f, "Signatur des Absenders ungültig"),
+        }
+    }
+}
+
+impl std::error::Error for OpenError {}
+
+/// AES- und HMAC-Schlüssel eines Containers
+struct SessionKeys {
+    encryption: Aes128,
+    mac: Secret<[u8; 32]>,
+}
+
+impl SessionKeys {
+    fn derive(shared: &[u8]) -> Self {
+        let mut material = Secret::new([0u8; 48]);
+        Hkdf::<Sha256>::new(None, shared)
+            .expand(KDF_INFO, &mut *material)
+            .expect("48 Bytes liegen unter der Grenze von HKDF");
+
+        let mut mac = Secret::new([0; 32]);
+        mac.copy_from_slice(&material[16..]);
+        SessionKeys { encryption: Aes128::new(&material[..16]).expect("AES-128 braucht 16 Bytes"), mac }
+    }
+
+    fn mac(&self, sealed: &Sealed) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&*self.mac).expect("HMAC akzeptiert jede Schlüssellänge");
+        mac.update(&sealed.authenticated_data());
+        mac
+    }
+
+    fn apply_keystream(&self, data: &[u8]) -> Vec<u8> {
+        CipherModes::ctr_encrypt_with_nonce(&self.encryption, &NONCE, data)
+            .expect("Blockgröße und Nonce passen zu AES")
+    }
+}
+
+/// Zahl als Big-Endian-Bytes fester Länge
+fn to_fixed_bytes(value: &BigUint, length: usize) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    let mut fixed = vec![0u8; length.saturating_sub(bytes.len())];
+    fixed.extend(bytes);
+    fixed
+}
+
+fn modulus_bytes(n: &BigUint) -> usize {
+    (n.bits() as usize).div_ceil(8)
+}
+
+/// Vom Absender signierte Daten: SHA-256 des Empfängermoduls, dann die Nachricht
+fn signed_data(recipient: &BigUint, message: &[u8]) -> Vec<u8> {
+    let mut data = Sha256::digest(recipient.to_bytes_be()).to_vec();
+    data.extend_from_slice(message);
+    data
+}
+
+fn push_number(buffer: &mut Vec<u8>, value: &BigUint) {
+    let bytes = value.to_bytes_be();
+    buffer.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+    buffer.extend(bytes);
+}
+
+fn take_number(buffer: &mut &[u8]) -> Option<BigUint> {
+    let length = u16::from_be_bytes(buffer.get(..2)?.try_into().ok()?) as usize;
+    let bytes = buffer.get(2..2 + length)?;
+    *buffer = &buffer[2 + length..];
+    Some(BigUint::from_bytes_be(bytes))
+}
+
+/// Signiert und verschlüsselt eine Nachricht für einen Empfänger
+pub fn seal(message: &[u8], recipient: &RsaPublicKey, signer: &DsaPrivateKey, rng: &mut (impl Rng + CryptoRng)) -> tool_error::Result<Sealed> {
+    if recipient.n < BigUint::from(3u32) {
+        return Err(ToolError::data("RSA-Modul des Empfängers ist zu klein"));
+    }
+    let signature = dsa_sign::dsa_sign(&signed_data(&recipient.n, message), &signer.params, &signer.x, rng)?;
+
+    let r = Secret::new(rng.gen_biguint_range(&BigUint::from(2u32), &recipient.n));
+    let length = modulus_bytes(&recipient.n);
+    let encapsulated_key = to_fixed_bytes(&crypto_math::mod_pow(&r, &recipient.e, &recipient.n), length);
+    let keys = SessionKeys::derive(&Secret::new(to_fixed_bytes(&r, length)));
+
+    let mut plaintext = Secret::new(Vec::with_capacity(message.len() + 64));
+    push_number(&mut plaintext, &signature.r);
+    push_number(&mut plaintext, &signature.s);
+    plaintext.extend_from_slice(message);
+
+    let mut sealed = Sealed { encapsulated_key, ciphertext: keys.apply_keystream(&plaintext), tag: [0; TAG_LENGTH] };
+    sealed.tag = keys.mac(&sealed).finalize().into_bytes().into();
+    Ok(sealed)
+}
+
+/// Prüft, entschlüsselt und verifiziert einen Container
+pub fn open(sealed: &Sealed, recipient: &RsaPrivateKey, sender: &DsaPublicKey) -> Result<Secret<Vec<u8>>, OpenError> {
+    let length = modulus_bytes(&recipient.n);
+    let encapsulated = BigUint::from_bytes_be(&sealed.encapsulated_key);
+    if sealed.encapsulated_key.len() != length || encapsulated >= recipient.n {
+        return Err(OpenError::Integrity);
+    }
+    let r = Secret::new(crypto_math::mod_pow(&encapsulated, &recipient.d, &recipient.n));
+    let keys = SessionKeys::derive(&Secret::new(to_fixed_bytes(&r, length)));
+
+    keys.mac(sealed).verify_slice(&sealed.tag).map_err(|_| OpenError::Integrity)?;
+
+    let plaintext = Secret::new(keys.apply_keystream(&sealed.ciphertext));
+    let mut rest = plaintext.as_slice();
+    let r = take_number(&mut rest).ok_or(OpenError::Malformed)?;
+    let s = take_number(&mut rest).ok_or(OpenError::Malformed)?;
+
+    let signature = DsaSignature { r, s };
+    let valid = dsa_verify::dsa_verify(&signed_data(&recipient.n, rest), &signature, &sender.params, &sender.y)
+        .map_err(|_| OpenError::Signature)?;
+    if !valid {
+        return Err(OpenError::Signature);
+    }
+    Ok(Secret::new(rest.to_vec()))
+}
+
+#[derive(Parser)]
+#[command(name = "seal")]
+#[command(about = "Signierte und hybrid verschlüsselte Container (RSA-KEM, AES-128-CTR, HMAC-SHA256, DSA)")]
+#[command(version = "1.0")]
+#[command(after_help = "Schlüssel stammen von rsa-keygen und dsa-keygen.\n\
+Exit-Codes beim Öffnen: 0 in Ordnung, 1 Integrität oder Signatur verletzt, 65 Container oder Schlüssel nicht lesbar")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Nachricht signieren und für einen Empfänger verschlüsseln
+    Seal {
+        /// Nachricht (Standard: STDIN)
+        #[arg(short, long, help = "Zu versiegelnde Datei (Standard: STDIN)")]
+        input: Option<String>,
+
+        /// Container (Standard: STDOUT)
+        #[arg(short, long, help = "Ausgabedatei für den Container (Standard: STDOUT)")]
+        output: Option<String>,
+
+        /// Öffentlicher RSA-Schlüssel des Empfängers
+        #[arg(short, long, help = "Öffentlicher RSA-Schlüssel des Empfängers (e, n)")]
+        recipient: String,
+
+        /// Privater DSA-Schlüssel des Absenders
+        #[arg(short, long, help = "Privater DSA-Schlüssel des Absenders (p, q, g, x)")]
+        signer: String,
+
+        #[command(flatten)]
+        rng: RngArgs,
+    },
+    /// Container prüfen und entschlüsseln
+    Open {
+        /// Container (Standard: STDIN)
+        #[arg(short, long, help = "Container (Standard: STDIN)")]
+        input: Option<String>,
+
+        /// Nachricht (Standard: STDOUT)
+        #[arg(short, long, help = "Ausgabedatei für die Nachricht (Standard: STDOUT)")]
+        output: Option<String>,
+
+        /// Privater RSA-Schlüssel des Empfängers
+        #[arg(short, long, help = "Privater RSA-Schlüssel des Empfängers (d, n)")]
+        key: String,
+
+        /// Öffentlicher DSA-Schlüssel des Absenders
+        #[arg(short, long, help = "Öffentlicher DSA-Schlüssel des Absenders (p, q, g, y)")]
+        sender: String,
+    },
+}
+
+fn read_input(path: &Option<String>) -> tool_error::Result<Vec<u8>> {
+    match path {
+        Some(path) => tool_error::fs::read(path),
+        None => {
+            let mut input = Vec::new();
+            std::io::stdin().read_to_end(&mut input).context("Fehler beim Lesen der Eingabe von stdin")?;
+            Ok(input)
+        }
+    }
+}
+
+fn write_output(path: &Option<String>, data: &[u8]) -> tool_error::Result<()> {
+    match path {
+        Some(path) => tool_error::fs::write(path, data),
+        None => std::io::stdout().write_all(data).context("Fehler beim Schreiben auf stdout"),
+    }
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+
+    match args.command {
+        Command::Seal { input, output, recipient, signer, rng } => {
+            let recipient = RsaPublicKey::read(&recipient)
+                .with_context(|| format!("Kann Empfängerschlüssel '{}' nicht lesen", recipient))?;
+            let signer = DsaPrivateKey::read(&signer)
+                .with_context(|| format!("Kann Absenderschlüssel '{}' nicht lesen", signer))?;
+            let message = read_input(&input)?;
+
+            let sealed = seal(&message, &recipient, &signer, &mut rng.rng())?;
+            info!("{} Bytes versiegelt, RSA-Modul mit {} Bit", message.len(), recipient.n.bits());
+            write_output(&output, sealed.armor().as_bytes())
+        }
+        Command::Open { input, output, key, sender } => {
+            let key = RsaPrivateKey::read(&key)
+                .with_context(|| format!("Kann Empfängerschlüssel '{}' nicht lesen", key))?;
+            let sender = DsaPublicKey::read(&sender)
+                .with_context(|| format!("Kann Absenderschlüssel '{}' nicht lesen", sender))?;
+            let text = String::from_utf8(read_input(&input)?).map_err(|_| ToolError::data("Container ist kein ASCII-Text"))?;
+            let sealed = Sealed::dearmor(&text).map_err(ToolError::data)?;
+
+            let message = open(&sealed, &key, &sender).map_err(|e| ToolError::failed(e.to_string()))?;
+            info!("Integrität und Signatur in Ordnung, {} Bytes", message.len());
+            write_output(&output, &message)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::{Algorithm, Drbg};
+    use key_formats::DsaParameters;
+
+    /// RSA mit n = 1000003 · 1000033 und DSA mit p = 283, q = 47, g = 64
+    fn keys() -> (RsaPublicKey, RsaPrivateKey, DsaPrivateKey, DsaPublicKey) {
+        let n = BigUint::from(1_000_003u64 * 1_000_033);
+        let phi = BigUint::from(1_000_002u64 * 1_000_032);
+        let e = BigUint::from(65537u32);
+        let d = crypto_math::mod_inverse(&e, &phi).unwrap();
+        let params = DsaParameters { p: 283u32.into(), q: 47u32.into(), g: 64u32.into() };
+        let x = BigUint::from(24u32);
+        let y = crypto_math::mod_pow(&params.g, &x, &params.p);
+        (
+            RsaPublicKey { e, n: n.clone() },
+            RsaPrivateKey { d: Secret::new(d), n },
+            DsaPrivateKey { params: params.clone(), x: Secret::new(x) },
+            DsaPublicKey { params, y },
+        )
+    }
+
+    fn rng() -> Drbg {
+        Drbg::from_seed(Algorithm::ChaCha20, b"seal")
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let (public, private, signer, sender) = keys();
+        let message = b"Treffpunkt morgen um acht am alten Bahnhof";
+        let sealed = seal(message, &public, &signer, &mut rng()).unwrap();
+        // r und s sind kleiner als q = 47, also je zwei Längenbytes und ein Byte
+        assert_eq!(sealed.ciphertext.len(), message.len() + 6);
+
+        let reopened = Sealed::dearmor(&sealed.armor()).unwrap();
+        assert_eq!(open(&reopened, &private, &sender).unwrap().as_slice(), message);
+    }
+
+    #[test]
+    fn test_tampering_detected() {
+        let (public, private, signer, sender) = keys();
+        let sealed = seal(b"Betrag: 100 EUR", &public, &signer, &mut rng()).unwrap();
+
+        let mut flipped = sealed.clone();
+        *flipped.ciphertext.last_mut().unwrap() ^= 1;
+        assert_eq!(open(&flipped, &private, &sender), Err(OpenError::Integrity));
+
+        let mut rewrapped = sealed.clone();
+        rewrapped.encapsulated_key[0] ^= 1;
+        assert_eq!(open(&rewrapped, &private, &sender), Err(OpenError::Integrity));
+    }
+
+    #[test]
+    fn test_wrong_sender() {
+        let (public, private, signer, mut sender) = keys();
+        let sealed = seal(b"Gruss", &public, &signer, &mut rng()).unwrap();
+        sender.y = crypto_math::mod_pow(&sender.params.g, &BigUint::from(25u32), &sender.params.p);
+        assert_eq!(open(&sealed, &private, &sender), Err(OpenError::Signature));
+    }
+}
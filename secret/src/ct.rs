@@ -0,0 +1,301 @@
+//! Vergleiche und Auswahl in konstanter Zeit
+//!
+//! Ein gewöhnliches `==` bricht beim ersten unterschiedlichen Byte ab. Wer
+//! MAC-Tags, Einmalpasswörter oder Padding so prüft, verrät über die
+//! Laufzeit, wie viele Bytes bereits stimmen. Die Funktionen hier sehen
+//! sich immer alle Bytes an und verzweigen nicht am Inhalt; nur die Längen
+//! gelten als öffentlich.
+//!
+//! Ob das gelingt, lässt sich nicht beweisen, nur messen: [`timing`] stellt
+//! dafür einen statistischen Test bereit, den die Tests der Werkzeuge nutzen.
+//! Unter der Last parallel laufender Tests schwanken die Messungen; diese
+//! Tests sind daher `#[ignore]` und laufen mit `cargo test -- --ignored`.
+
+use num_bigint::BigUint;
+use std::hint::black_box;
+
+/// `0xFF`, wenn `choice` gilt, sonst `0x00`
+fn mask(choice: bool) -> u8 {
+    0u8.wrapping_sub(black_box(choice as u8))
+}
+
+/// `true`, wenn beide Folgen gleich sind
+///
+/// Die Laufzeit hängt nur von den Längen ab. Verschieden lange Folgen sind
+/// sofort ungleich.
+pub fn eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let difference = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | black_box(x ^ y));
+    black_box(difference) == 0
+}
+
+/// `a`, wenn `choice` gilt, sonst `b`, ohne am Wert von `choice` zu verzweigen
+///
+/// Beide Folgen müssen gleich lang sein.
+pub fn select(choice: bool, a: &[u8], b: &[u8]) -> Vec<u8> {
+    assert_eq!(a.len(), b.len(), "select braucht gleich lange Folgen");
+    let mask = mask(choice);
+    a.iter().zip(b).map(|(x, y)| y ^ (mask & (x ^ y))).collect()
+}
+
+/// Ziffern beider Zahlen, mit Nullen auf dieselbe Anzahl aufgefüllt
+fn limbs(a: &BigUint, b: &BigUint) -> (Vec<u64>, Vec<u64>) {
+    let (mut a, mut b) = (a.to_u64_digits(), b.to_u64_digits());
+    let length = a.len().max(b.len());
+    a.resize(length, 0);
+    b.resize(length, 0);
+    (a, b)
+}
+
+/// Vergleicht zwei Zahlen Ziffer für Ziffer
+///
+/// Die Anzahl der 64-Bit-Ziffern, also die ungefähre Größe der größeren
+/// Zahl, bleibt sichtbar.
+pub fn eq_biguint(a: &BigUint, b: &BigUint) -> bool {
+    let (a, b) = limbs(a, b);
+    let difference = a.iter().zip(&b).fold(0u64, |acc, (x, y)| acc | black_box(x ^ y));
+    black_box(difference) == 0
+}
+
+/// `a`, wenn `choice` gilt, sonst `b`; beide Ziffernfolgen werden ganz gelesen
+pub fn select_biguint(choice: bool, a: &BigUint, b: &BigUint) -> BigUint {
+    let (a, b) = limbs(a, b);
+    let mask = u64::from(mask(choice)) * 0x0101_0101_0101_0101;
+    let digits: Vec<u32> = a.iter().zip(&b)
+        .map(|(x, y)| y ^ (mask & (x ^ y)))
+        .flat_map(|digit| [digit as u32, (digit >> 32) as u32])
+        .collect();
+    BigUint::new(digits)
+}
+
+pub mod timing {
+    //! Statistischer Test auf Laufzeitunterschiede
+    //!
+    //! Nach dem Verfahren von dudect (Reparaz, Balasch, Verbauwhede 2017):
+    //! Dieselbe Operation läuft in zufälliger Folge mit Eingaben zweier Klassen, etwa
+    //! „Tag stimmt“ und „erstes Byte falsch“. Die langsamsten Messungen
+    //! beider Klassen werden verworfen, weil Unterbrechungen durch das
+    //! Betriebssystem sie verfälschen. Welchs t-Test entscheidet dann, ob
+    //! sich die Mittelwerte unterscheiden. Ab |t| > 10 gilt eine Operation
+    //! als undicht; zufällige Schwankungen erreichen das praktisch nie.
+
+    use std::fmt;
+    use std::hint::black_box;
+    use std::time::Instant;
+
+    /// Schwelle für |t|, ab der ein Unterschied als Leck gilt
+    pub const THRESHOLD: f64 = 10.0;
+
+    /// Anteil der Messungen, der nach dem Verwerfen der Ausreißer bleibt
+    const KEPT: f64 = 0.9;
+
+    /// Kennzahlen einer Messreihe in Nanosekunden
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Summary {
+        pub count: usize,
+        pub mean: f64,
+        pub median: f64,
+        pub variance: f64,
+    }
+
+    impl Summary {
+        pub fn new(samples: &[u64]) -> Self {
+            let mut sorted = samples.to_vec();
+            sorted.sort_unstable();
+            let count = sorted.len();
+            let mean = sorted.iter().sum::<u64>() as f64 / count.max(1) as f64;
+            let median = if count == 0 { 0.0 } else { sorted[count / 2] as f64 };
+            let variance = if count < 2 {
+                0.0
+            } else {
+                sorted.iter().map(|&x| (x as f64 - mean).powi(2)).sum::<f64>() / (count - 1) as f64
+            };
+            Summary { count, mean, median, variance }
+        }
+    }
+
+    /// Ergebnis eines Vergleichs zweier Messreihen
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Comparison {
+        pub a: Summary,
+        pub b: Summary,
+        /// Welchs t-Statistik der bereinigten Messreihen
+        pub t: f64,
+    }
+
+    impl Comparison {
+        /// `true`, wenn sich die Laufzeiten deutlich unterscheiden
+        pub fn leaks(&self) -> bool {
+            self.t.abs() > THRESHOLD
+        }
+    }
+
+    impl fmt::Display for Comparison {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "A: Median {:.0} ns, B: Median {:.0} ns, t = {:.2}", self.a.median, self.b.median, self.t)
+        }
+    }
+
+    /// Misst `a` und `b` jeweils `samples`-mal in zufälliger Reihenfolge, in Nanosekunden
+    ///
+    /// Zwei Closures liegen an verschiedenen Stellen im Code; schon das kann
+    /// ein Prozent Laufzeit ausmachen und reicht bei vielen Messungen für
+    /// |t| > 10. Für den Nachweis, dass *kein* Leck vorliegt, ist daher
+    /// [`measure_inputs`] die bessere Wahl.
+    pub fn measure<A, B, R, S>(samples: usize, mut a: A, mut b: B) -> (Vec<u64>, Vec<u64>)
+    where
+        A: FnMut() -> R,
+        B: FnMut() -> S,
+    {
+        let mut a = || { black_box(a()); };
+        let mut b = || { black_box(b()); };
+        let mut classes: [&mut dyn FnMut(); 2] = [&mut a, &mut b];
+        measure_inputs(samples, [0, 1], |&class| classes[class]())
+    }
+
+    /// Misst dieselbe Funktion `f` mit zwei Eingaben, jeweils `samples`-mal, in Nanosekunden
+    ///
+    /// Beide Klassen laufen über denselben Code und unterscheiden sich nur in
+    /// den Daten; feste Abwechslung oder getrennte Zweige messen sonst die
+    /// Position mit statt der Eingabe. Die Reihenfolge kommt aus einem festen
+    /// Xorshift, Messungen sind also wiederholbar, soweit der Rechner es zulässt.
+    pub fn measure_inputs<I, F, R>(samples: usize, inputs: [I; 2], mut f: F) -> (Vec<u64>, Vec<u64>)
+    where
+        F: FnMut(&I) -> R,
+    {
+        let mut times = [Vec::with_capacity(samples), Vec::with_capacity(samples)];
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+
+        while times.iter().any(|t| t.len() < samples) {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let class = (state & 1) as usize;
+            if times[class].len() == samples {
+                continue;
+            }
+            let input = black_box(&inputs[class]);
+            let start = Instant::now();
+            black_box(f(input));
+            times[class].push(start.elapsed().as_nanos() as u64);
+        }
+        let [a, b] = times;
+        (a, b)
+    }
+
+    /// Verwirft Ausreißer und vergleicht beide Messreihen mit Welchs t-Test
+    pub fn compare(a: &[u64], b: &[u64]) -> Comparison {
+        // Gemeinsame Grenze, damit beide Klassen nach demselben Maß beschnitten werden
+        let mut all: Vec<u64> = a.iter().chain(b).copied().collect();
+        all.sort_unstable();
+        let limit = all.get(((all.len() as f64 * KEPT) as usize).min(all.len().saturating_sub(1))).copied().unwrap_or(0);
+        let crop = |samples: &[u64]| samples.iter().copied().filter(|&x| x <= limit).collect::<Vec<u64>>();
+
+        let (a, b) = (Summary::new(&crop(a)), Summary::new(&crop(b)));
+        let error = (a.variance / a.count.max(1) as f64 + b.variance / b.count.max(1) as f64).sqrt();
+        let difference = a.mean - b.mean;
+        let t = if difference == 0.0 {
+            0.0
+        } else if error == 0.0 {
+            difference.signum() * f64::INFINITY
+        } else {
+            difference / error
+        };
+        Comparison { a, b, t }
+    }
+
+    /// Misst und vergleicht in einem Schritt
+    pub fn test<A, B, R, S>(samples: usize, a: A, b: B) -> Comparison
+    where
+        A: FnMut() -> R,
+        B: FnMut() -> S,
+    {
+        let (a, b) = measure(samples, a, b);
+        compare(&a, &b)
+    }
+
+    /// Misst und vergleicht zwei Eingaben derselben Funktion in einem Schritt
+    pub fn test_inputs<I, F, R>(samples: usize, inputs: [I; 2], f: F) -> Comparison
+    where
+        F: FnMut(&I) -> R,
+    {
+        let (a, b) = measure_inputs(samples, inputs, f);
+        compare(&a, &b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eq() {
+        assert!(eq(b"", b""));
+        assert!(eq(b"Tag", b"Tag"));
+        assert!(!eq(b"Tag", b"Tah"));
+        assert!(!eq(b"Tag", b"Tags"));
+        assert!(!eq(&[0x80], &[0x00]));
+    }
+
+    #[test]
+    fn test_select() {
+        assert_eq!(select(true, b"ja", b"no"), b"ja");
+        assert_eq!(select(false, b"ja", b"no"), b"no");
+    }
+
+    #[test]
+    fn test_biguint() {
+        let a: BigUint = "123456789012345678901234567890".parse().unwrap();
+        let b = &a + 1u32;
+        let small = BigUint::from(7u32);
+        assert!(eq_biguint(&a, &a.clone()));
+        assert!(!eq_biguint(&a, &b));
+        assert!(!eq_biguint(&a, &small));
+        assert!(eq_biguint(&BigUint::default(), &BigUint::from(0u32)));
+
+        assert_eq!(select_biguint(true, &a, &small), a);
+        assert_eq!(select_biguint(false, &a, &small), small);
+        assert_eq!(select_biguint(false, &a, &BigUint::default()), BigUint::default());
+    }
+
+    #[test]
+    fn test_summary_and_compare() {
+        let summary = timing::Summary::new(&[1, 2, 3, 4]);
+        assert_eq!((summary.count, summary.mean, summary.median), (4, 2.5, 3.0));
+        assert!((summary.variance - 5.0 / 3.0).abs() < 1e-9);
+
+        let same = timing::compare(&[10, 11, 10, 11], &[11, 10, 11, 10]);
+        assert_eq!(same.t, 0.0);
+        assert!(!same.leaks());
+        let different: Vec<u64> = (0..100).map(|i| 1000 + i % 3).collect();
+        let slow: Vec<u64> = (0..100).map(|i| 1100 + i % 3).collect();
+        assert!(timing::compare(&different, &slow).leaks());
+    }
+
+    /// Vier KiB, damit ein früher Abbruch deutlich messbar ist
+    fn buffers() -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let tag = vec![0x5a; 4096];
+        let mut wrong = tag.clone();
+        wrong[0] ^= 1;
+        (tag.clone(), tag, wrong)
+    }
+
+    #[test]
+    #[ignore = "misst Laufzeiten, mit cargo test -- --ignored ausführen"]
+    fn test_harness_detects_early_exit() {
+        let (tag, right, wrong) = buffers();
+        let naive = |a: &[u8], b: &[u8]| a.iter().zip(b).all(|(x, y)| black_box(x) == black_box(y));
+        let comparison = timing::test(5000, || naive(&tag, &right), || naive(&tag, &wrong));
+        assert!(comparison.leaks(), "{}", comparison);
+    }
+
+    #[test]
+    #[ignore = "misst Laufzeiten, mit cargo test -- --ignored ausführen"]
+    fn test_eq_does_not_leak() {
+        let (tag, right, wrong) = buffers();
+        let comparison = timing::test_inputs(5000, [right, wrong], |other| eq(&tag, other));
+        assert!(!comparison.leaks(), "{}", comparison);
+    }
+}
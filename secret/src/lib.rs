@@ -0,0 +1,157 @@
+//! Geheimnisse, die beim Freigeben überschrieben werden
+//!
+//! Private Schlüssel, AES-Rundenschlüssel, Diffie-Hellman-Geheimnisse und
+//! DSA-Nonces sollen nach Gebrauch nicht im freigegebenen Speicher
+//! zurückbleiben. [`Secret`] überschreibt seinen Inhalt beim `drop` und
+//! verrät ihn auch nicht über `Debug`.
+//!
+//! Für Bytes übernimmt das `zeroize`. Bei `BigUint` kommt man an den Puffer
+//! nicht heran; hier werden die Ziffern an Ort und Stelle überschrieben,
+//! bevor der Puffer freigegeben wird. Zwischenergebnisse, die `num-bigint`
+//! beim Rechnen anlegt und selbst wieder freigibt, erfasst das nicht.
+//!
+//! Vergleiche von Tags, Codes und Padding in konstanter Zeit stehen in [`ct`].
+
+pub mod ct;
+
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use num_bigint::BigUint;
+use zeroize::Zeroize;
+
+/// Werte, deren Speicher sich überschreiben lässt
+pub trait Wipe {
+    /// Überschreibt den Inhalt; danach ist der Wert leer bzw. null
+    fn wipe(&mut self);
+}
+
+impl Wipe for Vec<u8> {
+    fn wipe(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Wipe for String {
+    fn wipe(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<const N: usize> Wipe for [u8; N] {
+    fn wipe(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Wipe for Vec<u32> {
+    fn wipe(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<const N: usize> Wipe for [u32; N] {
+    fn wipe(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<const N: usize> Wipe for [u64; N] {
+    fn wipe(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Wipe for BigUint {
+    fn wipe(&mut self) {
+        // Gleich viele Ziffern ändern weder Länge noch Kapazität, die alten
+        // Ziffern werden also im selben Puffer überschrieben. Erst danach
+        // darf der Puffer leer werden.
+        let digits = self.bits().div_ceil(32) as usize;
+        self.assign_from_slice(&vec![u32::MAX; digits]);
+        std::hint::black_box(&*self);
+        self.assign_from_slice(&[]);
+    }
+}
+
+/// Wert, der beim Freigeben überschrieben wird
+///
+/// Über `Deref` lässt sich ein `Secret<T>` wie ein `&T` verwenden.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Secret<T: Wipe>(T);
+
+impl<T: Wipe> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Secret(value)
+    }
+}
+
+impl<T: Wipe> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Secret(value)
+    }
+}
+
+impl<T: Wipe> Borrow<T> for Secret<T> {
+    fn borrow(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Wipe> Deref for Secret<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Wipe> DerefMut for Secret<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Wipe> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.wipe();
+    }
+}
+
+impl<T: Wipe> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Secret(***)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wipe() {
+        let mut number: BigUint = "123456789012345678901234567890".parse().unwrap();
+        number.wipe();
+        assert_eq!(number, BigUint::default());
+
+        let mut bytes = vec![1u8, 2, 3];
+        bytes.wipe();
+        assert!(bytes.is_empty());
+
+        let mut array = [0xAAu8; 16];
+        array.wipe();
+        assert_eq!(array, [0; 16]);
+
+        let mut words = [0xAAAA_AAAAu32; 18];
+        words.wipe();
+        assert_eq!(words, [0; 18]);
+    }
+
+    #[test]
+    fn test_deref_and_debug() {
+        let secret = Secret::new(BigUint::from(42u32));
+        assert_eq!(&*secret + 1u32, BigUint::from(43u32));
+        assert_eq!(format!("{:?}", secret), "Secret(***)");
+    }
+}
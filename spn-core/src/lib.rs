@@ -0,0 +1,266 @@
+//! SPN (Substitution-Permutation Network) aus der Vorlesung
+//!
+//! 16-Bit-Blöcke, vier Runden mit derselben 4-Bit-S-Box auf allen Nibbles
+//! und einer Bitpermutation, die in der letzten Runde entfällt. Der 16-Bit-
+//! Schlüssel wird in jeder Runde unverändert als Rundenschlüssel verwendet.
+//!
+//! Die Werkzeuge zur Verschlüsselung und zur Kryptoanalyse teilen sich diese
+//! Implementierung, damit Angriff und Chiffre garantiert zusammenpassen.
+
+pub mod modes;
+pub mod pairs;
+pub mod statistics;
+
+/// S-Box aus der Vorlesung【2-5】【2-6】
+pub const S_BOX: [u8; 16] = [
+    0xE, 0x4, 0xD, 0x1, 0x2, 0xF, 0xB, 0x8,
+    0x3, 0xA, 0x6, 0xC, 0x5, 0x9, 0x0, 0x7
+];
+
+/// Permutation aus der Vorlesung【2-5】【2-6】: Bit i wandert an Position P[i]
+pub const PERMUTATION: [usize; 16] = [
+    0,  4,  8, 12,
+    1,  5,  9, 13,
+    2,  6, 10, 14,
+    3,  7, 11, 15
+];
+
+/// Anzahl der Runden N
+pub const ROUNDS: usize = 4;
+
+/// SPN-Verschlüsselungsstruktur
+#[derive(Debug, Clone)]
+pub struct SpnCipher {
+    pub s_box: [u8; 16],
+    pub inverse_s_box: [u8; 16],
+    pub permutation: [usize; 16],
+    pub inverse_permutation: [usize; 16],
+    pub round_key: u16,
+}
+
+impl SpnCipher {
+    pub fn new(key: u16) -> Self {
+        // Umkehrungen für die Entschlüsselung
+        let mut inverse_s_box = [0u8; 16];
+        for (input, &output) in S_BOX.iter().enumerate() {
+            inverse_s_box[output as usize] = input as u8;
+        }
+        let mut inverse_permutation = [0usize; 16];
+        for (position, &target) in PERMUTATION.iter().enumerate() {
+            inverse_permutation[target] = position;
+        }
+
+        SpnCipher {
+            s_box: S_BOX,
+            inverse_s_box,
+            permutation: PERMUTATION,
+            inverse_permutation,
+            round_key: key,
+        }
+    }
+
+    pub fn apply_sbox(&self, input: u16) -> u16 {
+        Self::substitute(&self.s_box, input)
+    }
+
+    pub fn apply_inverse_sbox(&self, input: u16) -> u16 {
+        Self::substitute(&self.inverse_s_box, input)
+    }
+
+    pub fn apply_permutation(&self, input: u16) -> u16 {
+        Self::permute(&self.permutation, input)
+    }
+
+    pub fn apply_inverse_permutation(&self, input: u16) -> u16 {
+        Self::permute(&self.inverse_permutation, input)
+    }
+
+    /// Wendet eine S-Box auf alle vier Nibbles an
+    fn substitute(s_box: &[u8; 16], input: u16) -> u16 {
+        let mut result = 0u16;
+        for i in 0..4 {
+            let nibble = ((input >> (i * 4)) & 0xF) as usize;
+            let substituted = s_box[nibble] as u16;
+            result |= substituted << (i * 4);
+        }
+        result
+    }
+
+    /// Verschiebt Bit i an Position permutation[i]
+    fn permute(permutation: &[usize; 16], input: u16) -> u16 {
+        let mut result = 0u16;
+        for (i, &target) in permutation.iter().enumerate() {
+            let bit = (input >> i) & 1;
+            result |= bit << target;
+        }
+        result
+    }
+
+    /// Berechnet u^N, die Eingabe der letzten S-Box-Schicht【22-1】
+    ///
+    /// Auf diesen Wert beziehen sich die Approximationen der linearen Analyse.
+    pub fn compute_last_round_input(&self, plaintext: u16) -> u16 {
+        let mut w = plaintext;
+
+        // Runden 1 bis N-1
+        for _round in 1..ROUNDS {
+            // Schlüsseladdition: u^r = w^(r-1) ⊕ K^r
+            let u = w ^ self.round_key;
+
+            // Substitution: v^r = S(u^r)
+            let v = self.apply_sbox(u);
+
+            // Permutation: w^r = P(v^r)
+            w = self.apply_permutation(v);
+        }
+
+        // Finale Schlüsseladdition: u^N = w^(N-1) ⊕ K^N
+        w ^ self.round_key
+    }
+
+    /// Verschlüsselt einen 16-Bit Block
+    pub fn encrypt_block(&self, plaintext: u16) -> u16 {
+        // Finale Substitution: v^N = S(u^N)
+        let v_final = self.apply_sbox(self.compute_last_round_input(plaintext));
+
+        // Finale Schlüsseladdition: y = v^N ⊕ K^(N+1)
+        v_final ^ self.round_key
+    }
+
+    /// Entschlüsselt einen 16-Bit Block: alle Schritte von `encrypt_block`
+    /// in umgekehrter Reihenfolge mit inverser S-Box und Permutation
+    pub fn decrypt_block(&self, ciphertext: u16) -> u16 {
+        // Finale Runde rückgängig machen: v^N = y ⊕ K^(N+1), u^N = S⁻¹(v^N)
+        let u_final = self.apply_inverse_sbox(ciphertext ^ self.round_key);
+        let mut w = u_final ^ self.round_key;
+
+        // Runden N-1 bis 1 rückwärts
+        for _round in (1..ROUNDS).rev() {
+            // v^r = P⁻¹(w^r)
+            let v = self.apply_inverse_permutation(w);
+
+            // u^r = S⁻¹(v^r)
+            let u = self.apply_inverse_sbox(v);
+
+            // w^(r-1) = u^r ⊕ K^r
+            w = u ^ self.round_key;
+        }
+
+        w
+    }
+
+    /// Verschlüsselt eine Folge von Hexadezimalziffern im ECB-Modus
+    pub fn encrypt(&self, input: &str) -> Result<String, String> {
+        // Je 4 Hexadezimalziffern ein Block, der letzte wird mit Nullen aufgefüllt
+        let blocks = codec::hex::decode_words(input)
+            .map_err(|e| format!("Eingabe enthält ungültige Hexadezimalzeichen: {}", e))?;
+
+        let ciphertexts: Vec<u16> = blocks.iter()
+            .map(|&plaintext| self.encrypt_block(plaintext))
+            .collect();
+
+        Ok(codec::hex::encode_words(&ciphertexts))
+    }
+
+    /// Entschlüsselt eine Folge von Hexadezimalziffern im ECB-Modus
+    ///
+    /// Beim Verschlüsseln aufgefüllte Nullen bleiben im Klartext erhalten.
+    pub fn decrypt(&self, input: &str) -> Result<String, String> {
+        let blocks = codec::hex::decode_words(input)
+            .map_err(|e| format!("Eingabe enthält ungültige Hexadezimalzeichen: {}", e))?;
+
+        let plaintexts: Vec<u16> = blocks.iter()
+            .map(|&ciphertext| self.decrypt_block(ciphertext))
+            .collect();
+
+        Ok(codec::hex::encode_words(&plaintexts))
+    }
+}
+
+/// Validiert und parst einen Hexadezimal-Schlüssel (16 Bit / 4 Ziffern)
+pub fn parse_key(key_str: &str) -> Result<u16, String> {
+    let clean_key = key_str.replace(char::is_whitespace, "").to_uppercase();
+
+    if clean_key.len() != 4 {
+        return Err(format!("Schlüssel muss genau 4 Hexadezimalziffern haben, gefunden: {}", clean_key.len()));
+    }
+
+    if !clean_key.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("Schlüssel enthält ungültige Hexadezimalzeichen".to_string());
+    }
+
+    u16::from_str_radix(&clean_key, 16)
+        .map_err(|_| "Fehler beim Parsen des Schlüssels".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sbox() {
+        let cipher = SpnCipher::new(0x0000);
+
+        // Teste bekannte S-Box Werte【2-5】
+        assert_eq!(cipher.apply_sbox(0x0000), 0xEEEE); // 0->E für jedes Nibble
+        assert_eq!(cipher.apply_sbox(0x1111), 0x4444); // 1->4 für jedes Nibble
+        assert_eq!(cipher.apply_sbox(0xF000), 0x7EEE); // F->7, 0->E
+    }
+
+    #[test]
+    fn test_key_parsing() {
+        assert_eq!(parse_key("1234").unwrap(), 0x1234);
+        assert_eq!(parse_key("ABCD").unwrap(), 0xABCD);
+        assert_eq!(parse_key("ab cd").unwrap(), 0xABCD);
+
+        assert!(parse_key("123").is_err());  // Zu kurz
+        assert!(parse_key("12345").is_err()); // Zu lang
+        assert!(parse_key("12GH").is_err());  // Ungültiges Zeichen
+    }
+
+    #[test]
+    fn test_last_round_input() {
+        let cipher = SpnCipher::new(0x2D55);
+
+        for plaintext in [0x0000, 0x1234, 0xFFFF] {
+            let u_final = cipher.compute_last_round_input(plaintext);
+            assert_eq!(cipher.apply_sbox(u_final) ^ 0x2D55, cipher.encrypt_block(plaintext));
+        }
+    }
+
+    #[test]
+    fn test_full_encryption() {
+        let cipher = SpnCipher::new(0x2D55);
+
+        // Test komplette Verschlüsselung
+        let result = cipher.encrypt("1234ABCD").unwrap();
+        assert_eq!(result.len(), 8); // 2 Blöcke à 4 Hex-Zeichen
+
+        // Test mit Padding
+        let result_padded = cipher.encrypt("123").unwrap(); // Wird zu "1230"
+        assert_eq!(result_padded.len(), 4); // 1 Block à 4 Hex-Zeichen
+    }
+
+    #[test]
+    fn test_inverse_components() {
+        let cipher = SpnCipher::new(0x0000);
+
+        for value in [0x0000, 0x1234, 0xBEEF, 0xFFFF] {
+            assert_eq!(cipher.apply_inverse_sbox(cipher.apply_sbox(value)), value);
+            assert_eq!(cipher.apply_inverse_permutation(cipher.apply_permutation(value)), value);
+        }
+    }
+
+    #[test]
+    fn test_decryption_roundtrip() {
+        let cipher = SpnCipher::new(0x2D55);
+
+        // Alle Blöcke müssen sich wiederherstellen lassen
+        for block in 0..=u16::MAX {
+            assert_eq!(cipher.decrypt_block(cipher.encrypt_block(block)), block);
+        }
+
+        let ciphertext = cipher.encrypt("1234ABCD 123").unwrap();
+        assert_eq!(cipher.decrypt(&ciphertext).unwrap(), "1234ABCD1230");
+    }
+}
@@ -0,0 +1,91 @@
+//! Das SPN als Blockchiffre für die Betriebsmodi
+//!
+//! Ein Block sind 2 Bytes, der Schlüssel ebenfalls; beide werden wie in den
+//! Hexadezimal-Ein- und Ausgaben als Big Endian gelesen. Wegen der kleinen
+//! Blockgröße eignet sich das SPN gut, um Schwächen der Modi wie sich
+//! wiederholende ECB-Blöcke sichtbar zu machen.
+
+use std::ops::RangeInclusive;
+
+use cipher_modes::{BlockCipher, CipherModeError, KeyInit};
+
+use crate::SpnCipher;
+
+/// Blockgröße des SPN in Bytes
+pub const BLOCK_SIZE: usize = 2;
+
+/// Erstellt die Chiffre aus einem 2-Byte-Schlüssel, sonst
+/// [`CipherModeError::InvalidKeyLength`]
+impl KeyInit for SpnCipher {
+    fn new(key: &[u8]) -> Result<Self, CipherModeError> {
+        let key: [u8; 2] = key.try_into()
+            .map_err(|_| CipherModeError::InvalidKeyLength { expected: 2, got: key.len() })?;
+        Ok(SpnCipher::new(u16::from_be_bytes(key)))
+    }
+}
+
+/// Verwendet den Rundenschlüssel der Instanz; Blöcke müssen genau 2 Bytes lang sein.
+impl BlockCipher for SpnCipher {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        block.copy_from_slice(&SpnCipher::encrypt_block(self, word(block)).to_be_bytes());
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        block.copy_from_slice(&SpnCipher::decrypt_block(self, word(block)).to_be_bytes());
+    }
+
+    fn valid_key_lengths() -> RangeInclusive<usize> {
+        2..=2
+    }
+}
+
+fn word(block: &[u8]) -> u16 {
+    let block: [u8; 2] = block.try_into()
+        .unwrap_or_else(|_| panic!("SPN-Block muss 2 Bytes lang sein, nicht {}", block.len()));
+    u16::from_be_bytes(block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cipher_modes::{CipherModes, Padding};
+
+    #[test]
+    fn test_matches_hex_encryption() {
+        let cipher = <SpnCipher as KeyInit>::new(&[0x2D, 0x55]).unwrap();
+        let ciphertext = CipherModes::ecb_encrypt(&cipher, &[0x12, 0x34, 0xAB, 0xCD], Padding::None).unwrap();
+        assert_eq!(codec::hex::encode_upper(&ciphertext), cipher.encrypt("1234ABCD").unwrap());
+    }
+
+    #[test]
+    fn test_modes_roundtrip() {
+        let cipher = SpnCipher::new(0x2D55);
+        let iv = [0xBE, 0xEF];
+        let plaintext = b"Betriebsmodi mit dem SPN";
+
+        let cbc = CipherModes::cbc_encrypt(&cipher, &iv, plaintext, Padding::Pkcs7).unwrap();
+        assert_eq!(CipherModes::cbc_decrypt(&cipher, &iv, &cbc, Padding::Pkcs7).unwrap(), plaintext);
+
+        let ofb = CipherModes::ofb_encrypt(&cipher, &iv, plaintext).unwrap();
+        assert_eq!(CipherModes::ofb_decrypt(&cipher, &iv, &ofb).unwrap(), plaintext);
+
+        let ctr = CipherModes::ctr_encrypt(&cipher, &iv, plaintext).unwrap();
+        assert_eq!(CipherModes::ctr_decrypt(&cipher, &iv, &ctr).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn test_ecb_reveals_repeated_blocks() {
+        let cipher = SpnCipher::new(0x2D55);
+        let plaintext = [0x41; 16];
+
+        // ECB: gleiche Klartextblöcke ergeben gleiche Kryptotextblöcke
+        let ecb = CipherModes::ecb_encrypt(&cipher, &plaintext, Padding::None).unwrap();
+        assert!(ecb.chunks(BLOCK_SIZE).all(|block| block == &ecb[..BLOCK_SIZE]));
+
+        // CBC: die Verkettung verdeckt die Wiederholungen
+        let cbc = CipherModes::cbc_encrypt(&cipher, &[0x13, 0x37], &plaintext, Padding::None).unwrap();
+        assert!(cbc.chunks(BLOCK_SIZE).skip(1).any(|block| block != &cbc[..BLOCK_SIZE]));
+    }
+}
@@ -0,0 +1,273 @@
+//! Klartext-Kryptotext-Paare für Known- und Chosen-Plaintext-Angriffe
+//!
+//! # Dateiformat
+//!
+//! Paardateien sind Textdateien, die die Werkzeuge der linearen und der
+//! differentiellen Kryptoanalyse gemeinsam lesen:
+//!
+//! ```text
+//! # spn-pairs 1
+//! # modus: differenz
+//! # differenz: 0B00
+//! # schluessel: 2D55
+//! 1234 5F1A
+//! 1934 C0DE
+//! ```
+//!
+//! - Die erste Zeile `# spn-pairs 1` kennzeichnet Format und Version.
+//! - Weitere Kopfzeilen haben die Form `# name: wert`. `modus` ist
+//!   `zufall`, `zaehler` oder `differenz`; bei `differenz` gibt `differenz`
+//!   das XOR zweier aufeinanderfolgender Klartexte an. Die Zeile
+//!   `schluessel` ist optional und dient nur zur Kontrolle von Testangriffen.
+//! - Jede weitere Zeile enthält einen Klartext und seinen Kryptotext als je
+//!   4 Hexadezimalziffern. Im Modus `differenz` bilden die Zeilen 1 und 2,
+//!   3 und 4 usw. je ein Paar mit der angegebenen Differenz.
+//! - Leerzeilen und unbekannte Kopfzeilen werden ignoriert.
+
+use std::fmt;
+use std::path::Path;
+
+use rand::Rng;
+
+use crate::{SpnCipher, parse_key};
+
+/// Erste Zeile jeder Paardatei
+const MAGIC: &str = "# spn-pairs 1";
+
+/// Wie die Klartexte gewählt werden
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    /// Gleichverteilt zufällige Klartexte (Known Plaintext)
+    Random,
+    /// Aufeinanderfolgende Klartexte ab `start`
+    Counter { start: u16 },
+    /// Zufällige Klartextpaare x, x ⊕ Δ (Chosen Plaintext)
+    Difference { difference: u16 },
+}
+
+impl Selection {
+    /// Wählt `count` Klartexte, bei [`Selection::Difference`] `count` Paare
+    pub fn plaintexts(&self, count: usize) -> Vec<u16> {
+        let mut rng = rand::rng();
+        match *self {
+            Selection::Random => (0..count).map(|_| rng.random()).collect(),
+            Selection::Counter { start } => (0..count).map(|i| start.wrapping_add(i as u16)).collect(),
+            Selection::Difference { difference } => (0..count)
+                .flat_map(|_| {
+                    let plaintext: u16 = rng.random();
+                    [plaintext, plaintext ^ difference]
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Inhalt einer Paardatei
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairFile {
+    pub selection: Selection,
+    pub plaintexts: Vec<u16>,
+    pub ciphertexts: Vec<u16>,
+    /// Schlüssel, mit dem die Paare erzeugt wurden, falls bekannt gegeben
+    pub key: Option<u16>,
+}
+
+impl PairFile {
+    /// Wählt Klartexte und verschlüsselt sie mit `cipher`
+    pub fn generate(cipher: &SpnCipher, selection: Selection, count: usize) -> Self {
+        let plaintexts = selection.plaintexts(count);
+        let ciphertexts = encrypt_all(cipher, &plaintexts);
+        PairFile { selection, plaintexts, ciphertexts, key: None }
+    }
+
+    /// Liest eine Paardatei im oben beschriebenen Format
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().map(str::trim).enumerate();
+        if lines.next().map(|(_, line)| line) != Some(MAGIC) {
+            return Err(format!("Keine Paardatei: erste Zeile muss '{}' lauten", MAGIC));
+        }
+
+        let mut mode = None;
+        let mut difference = None;
+        let mut key = None;
+        let mut plaintexts = Vec::new();
+        let mut ciphertexts = Vec::new();
+
+        for (index, line) in lines {
+            let line_number = index + 1;
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('#') {
+                let Some((name, value)) = header.split_once(':') else { continue };
+                let value = value.trim();
+                match name.trim() {
+                    "modus" => mode = Some(value.to_string()),
+                    "differenz" => difference = Some(parse_block(value, line_number)?),
+                    "schluessel" => key = Some(parse_key(value).map_err(|e| format!("Zeile {}: {}", line_number, e))?),
+                    _ => {}
+                }
+                continue;
+            }
+
+            let mut blocks = line.split_whitespace();
+            let (Some(plaintext), Some(ciphertext), None) = (blocks.next(), blocks.next(), blocks.next()) else {
+                return Err(format!("Zeile {}: erwartet Klartext und Kryptotext, gefunden '{}'", line_number, line));
+            };
+            plaintexts.push(parse_block(plaintext, line_number)?);
+            ciphertexts.push(parse_block(ciphertext, line_number)?);
+        }
+
+        let selection = match mode.as_deref() {
+            Some("zufall") => Selection::Random,
+            Some("zaehler") => Selection::Counter { start: plaintexts.first().copied().unwrap_or(0) },
+            Some("differenz") => {
+                let difference = difference.ok_or("Modus 'differenz' ohne Kopfzeile 'differenz'")?;
+                check_differences(&plaintexts, difference)?;
+                Selection::Difference { difference }
+            }
+            Some(other) => return Err(format!("Unbekannter Modus '{}'", other)),
+            None => return Err("Kopfzeile 'modus' fehlt".to_string()),
+        };
+
+        Ok(PairFile { selection, plaintexts, ciphertexts, key })
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, String> {
+        let text = codec::file::read_text(path).map_err(|e| e.to_string())?;
+        Self::parse(&text)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        codec::file::write_binary(path, self.to_string().as_bytes()).map_err(|e| e.to_string())
+    }
+}
+
+impl fmt::Display for PairFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", MAGIC)?;
+        match self.selection {
+            Selection::Random => writeln!(f, "# modus: zufall")?,
+            Selection::Counter { .. } => writeln!(f, "# modus: zaehler")?,
+            Selection::Difference { difference } => {
+                writeln!(f, "# modus: differenz")?;
+                writeln!(f, "# differenz: {:04X}", difference)?;
+            }
+        }
+        if let Some(key) = self.key {
+            writeln!(f, "# schluessel: {:04X}", key)?;
+        }
+        for (plaintext, ciphertext) in self.plaintexts.iter().zip(&self.ciphertexts) {
+            writeln!(f, "{:04X} {:04X}", plaintext, ciphertext)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_block(text: &str, line_number: usize) -> Result<u16, String> {
+    if text.len() != 4 {
+        return Err(format!("Zeile {}: Block '{}' muss 4 Hexadezimalziffern haben", line_number, text));
+    }
+    u16::from_str_radix(text, 16)
+        .map_err(|_| format!("Zeile {}: '{}' ist keine Hexadezimalzahl", line_number, text))
+}
+
+/// Prüft, dass die Klartexte paarweise die angegebene Differenz haben
+fn check_differences(plaintexts: &[u16], difference: u16) -> Result<(), String> {
+    if !plaintexts.len().is_multiple_of(2) {
+        return Err("Modus 'differenz' erwartet eine gerade Anzahl an Zeilen".to_string());
+    }
+    match plaintexts.chunks(2).position(|pair| pair[0] ^ pair[1] != difference) {
+        Some(index) => Err(format!("Paar {} hat nicht die Differenz {:04X}", index + 1, difference)),
+        None => Ok(()),
+    }
+}
+
+/// Verschlüsselt `count` zufällige Klartexte
+pub fn generate_pairs(cipher: &SpnCipher, count: usize) -> (Vec<u16>, Vec<u16>) {
+    let file = PairFile::generate(cipher, Selection::Random, count);
+    (file.plaintexts, file.ciphertexts)
+}
+
+/// Verschlüsselt jeden Klartext einzeln, etwa für gewählte Klartexte
+pub fn encrypt_all(cipher: &SpnCipher, plaintexts: &[u16]) -> Vec<u16> {
+    plaintexts.iter().map(|&plaintext| cipher.encrypt_block(plaintext)).collect()
+}
+
+/// Liest Klartexte und Kryptotexte als Hexadezimalziffern (je 4 ein Block)
+///
+/// Beide Folgen müssen gleich viele Blöcke enthalten.
+pub fn parse_pairs(plaintexts: &str, ciphertexts: &str) -> Result<(Vec<u16>, Vec<u16>), String> {
+    let plaintexts = codec::hex::decode_words(plaintexts)
+        .map_err(|e| format!("Fehler beim Parsen der Klartexte: {}", e))?;
+    let ciphertexts = codec::hex::decode_words(ciphertexts)
+        .map_err(|e| format!("Fehler beim Parsen der Kryptotexte: {}", e))?;
+
+    if plaintexts.len() != ciphertexts.len() {
+        return Err(format!("Anzahl Klartexte ({}) != Anzahl Kryptotexte ({})",
+                           plaintexts.len(), ciphertexts.len()));
+    }
+
+    Ok((plaintexts, ciphertexts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_pairs() {
+        let cipher = SpnCipher::new(0x2D55);
+        let (plaintexts, ciphertexts) = generate_pairs(&cipher, 100);
+
+        assert_eq!(plaintexts.len(), 100);
+        assert_eq!(ciphertexts.len(), 100);
+
+        // Prüfe dass Paare korrekt sind
+        for (&pt, &ct) in plaintexts.iter().zip(ciphertexts.iter()) {
+            assert_eq!(cipher.encrypt_block(pt), ct);
+        }
+    }
+
+    #[test]
+    fn test_parse_pairs() {
+        assert_eq!(parse_pairs("12345678", "ABCD EF01").unwrap(), (vec![0x1234, 0x5678], vec![0xABCD, 0xEF01]));
+        assert!(parse_pairs("12345678", "ABCD").is_err());
+        assert!(parse_pairs("12X4", "ABCD").is_err());
+    }
+
+    #[test]
+    fn test_selections() {
+        assert_eq!(Selection::Counter { start: 0xFFFE }.plaintexts(3), vec![0xFFFE, 0xFFFF, 0x0000]);
+
+        let plaintexts = Selection::Difference { difference: 0x0B00 }.plaintexts(10);
+        assert_eq!(plaintexts.len(), 20);
+        assert!(check_differences(&plaintexts, 0x0B00).is_ok());
+    }
+
+    #[test]
+    fn test_pair_file_roundtrip() {
+        let cipher = SpnCipher::new(0x2D55);
+        for selection in [Selection::Random, Selection::Counter { start: 0x0100 }, Selection::Difference { difference: 0x0B00 }] {
+            let mut file = PairFile::generate(&cipher, selection, 8);
+            file.key = Some(0x2D55);
+            assert_eq!(PairFile::parse(&file.to_string()).unwrap(), file);
+        }
+    }
+
+    #[test]
+    fn test_pair_file_errors() {
+        assert!(PairFile::parse("1234 5678\n").is_err());
+        assert!(PairFile::parse("# spn-pairs 1\n1234 5678\n").is_err());
+        assert!(PairFile::parse("# spn-pairs 1\n# modus: zufall\n1234\n").is_err());
+        assert!(PairFile::parse("# spn-pairs 1\n# modus: zufall\n12G4 5678\n").is_err());
+
+        // Differenz passt nicht zu den Klartexten
+        let text = "# spn-pairs 1\n# modus: differenz\n# differenz: 0B00\n0000 1111\n0A00 2222\n";
+        assert_eq!(PairFile::parse(text).unwrap_err(), "Paar 1 hat nicht die Differenz 0B00");
+
+        let text = "# spn-pairs 1\n# modus: zufall\n\n1234 5678\n";
+        assert_eq!(PairFile::parse(text).unwrap().plaintexts, vec![0x1234]);
+    }
+}
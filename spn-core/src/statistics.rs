@@ -0,0 +1,151 @@
+//! Erfolgswahrscheinlichkeit von linearen und differentiellen Angriffen
+//!
+//! Nach Selçuk, „On Probability of Success in Linear and Differential
+//! Cryptanalysis“ (J. Cryptology 21, 2008). Ein Angriff mit Vorteil a Bit
+//! ist erfolgreich, wenn der richtige Teilschlüssel unter den besten
+//! 2^(m-a) von 2^m Kandidaten landet; für die vollständige Bestimmung der
+//! m geratenen Schlüsselbits ist a = m.
+//!
+//! Linear:        P_S = Φ(2√N·|ε| − Φ⁻¹(1 − 2^(−a−1)))
+//! Differentiell: P_S = Φ((√(μ·S_N) − Φ⁻¹(1 − 2^(−a))) / √(S_N + 1))
+//!
+//! mit μ = p·N richtigen Paaren und dem Signal-Rausch-Verhältnis
+//! S_N = p·2^m. Die Faustregel der Vorlesung N ≈ 8·ε⁻² liegt für 8
+//! geratene Bits bei einer Erfolgswahrscheinlichkeit von knapp 100 %.
+
+/// Statistische Eigenschaft, auf der ein Angriff beruht
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distinguisher {
+    /// Lineare Approximation mit Güte ε
+    Linear { bias: f64 },
+    /// Differentielle Charakteristik mit Wahrscheinlichkeit p
+    Differential { probability: f64 },
+}
+
+/// Wahrscheinlichkeit, mit `pairs` Paaren die `key_bits` Teilschlüsselbits zu bestimmen
+pub fn success_probability(distinguisher: Distinguisher, pairs: usize, key_bits: u32) -> f64 {
+    let n = pairs as f64;
+    match distinguisher {
+        Distinguisher::Linear { bias } => {
+            normal_cdf(2.0 * n.sqrt() * bias.abs() - threshold(key_bits + 1))
+        }
+        Distinguisher::Differential { probability } => {
+            let signal_to_noise = signal_to_noise(probability, key_bits);
+            let right_pairs = probability * n;
+            normal_cdf(((right_pairs * signal_to_noise).sqrt() - threshold(key_bits)) / (signal_to_noise + 1.0).sqrt())
+        }
+    }
+}
+
+/// Anzahl der Paare für die gewünschte Erfolgswahrscheinlichkeit (Umkehrung von [`success_probability`])
+pub fn required_pairs(distinguisher: Distinguisher, success: f64, key_bits: u32) -> usize {
+    let quantile = normal_quantile(success);
+    let pairs = match distinguisher {
+        Distinguisher::Linear { bias } => {
+            ((quantile + threshold(key_bits + 1)) / (2.0 * bias.abs())).powi(2)
+        }
+        Distinguisher::Differential { probability } => {
+            let signal_to_noise = signal_to_noise(probability, key_bits);
+            let root = quantile * (signal_to_noise + 1.0).sqrt() + threshold(key_bits);
+            root.max(0.0).powi(2) / (probability * signal_to_noise)
+        }
+    };
+    pairs.ceil() as usize
+}
+
+/// Φ⁻¹(1 − 2^(−bits))
+fn threshold(bits: u32) -> f64 {
+    normal_quantile(1.0 - 0.5f64.powi(bits as i32))
+}
+
+fn signal_to_noise(probability: f64, key_bits: u32) -> f64 {
+    probability * 2f64.powi(key_bits as i32)
+}
+
+/// Verteilungsfunktion Φ der Standardnormalverteilung
+pub fn normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+/// Fehlerfunktion nach Abramowitz/Stegun 7.1.26 (Fehler < 1,5·10⁻⁷)
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let polynomial = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let value = 1.0 - polynomial * (-x * x).exp();
+    if x < 0.0 { -value } else { value }
+}
+
+/// Quantilfunktion Φ⁻¹ der Standardnormalverteilung nach Acklam
+/// (relativer Fehler < 1,2·10⁻⁹)
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e1, 2.209460984245205e2, -2.759285104469687e2, 1.38357751867269e2, -3.066479806614716e1, 2.506628277459239];
+    const B: [f64; 5] = [-5.447609879822406e1, 1.615858368580409e2, -1.556989798598866e2, 6.680131188771972e1, -1.328068155288572e1];
+    const C: [f64; 6] = [-7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838, -2.549732539343734, 4.374664141464968, 2.938163982698783];
+    const D: [f64; 4] = [7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996, 3.754408661907416];
+    const LOW: f64 = 0.02425;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+
+    // Randbereiche über die Symmetrie Φ⁻¹(1 − p) = −Φ⁻¹(p)
+    let tail = |p: f64| {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+
+    if p < LOW {
+        tail(p)
+    } else if p > 1.0 - LOW {
+        -tail(1.0 - p)
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normal_distribution() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-4);
+        assert!((normal_quantile(0.975) - 1.959964).abs() < 1e-5);
+        assert!((normal_quantile(0.001) + 3.090232).abs() < 1e-5);
+
+        for p in [0.01, 0.3, 0.5, 0.9, 0.999] {
+            assert!((normal_cdf(normal_quantile(p)) - p).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_linear_estimate() {
+        // Approximation der Vorlesung: ε = 1/32, 8 Schlüsselbits
+        let distinguisher = Distinguisher::Linear { bias: 1.0 / 32.0 };
+        assert!(success_probability(distinguisher, 8000, 8) > 0.99);
+        assert!(success_probability(distinguisher, 500, 8) < 0.5);
+
+        // Umkehrung liefert die Paare für die gewünschte Wahrscheinlichkeit
+        let pairs = required_pairs(distinguisher, 0.95, 8);
+        assert!(success_probability(distinguisher, pairs, 8) >= 0.95);
+        assert!(success_probability(distinguisher, pairs - 1, 8) < 0.95);
+    }
+
+    #[test]
+    fn test_differential_estimate() {
+        // Charakteristik aus Heys' Tutorial: p = 27/1024, 8 Schlüsselbits
+        let distinguisher = Distinguisher::Differential { probability: 27.0 / 1024.0 };
+        let pairs = required_pairs(distinguisher, 0.9, 8);
+        assert!((50..5000).contains(&pairs));
+        assert!(success_probability(distinguisher, pairs, 8) >= 0.9);
+        assert!(success_probability(distinguisher, pairs / 4, 8) < 0.9);
+    }
+}
@@ -0,0 +1,154 @@
+//! spn_bruteforce: Vollständige Schlüsselsuche für das SPN
+//!
+//! Probiert alle 2^16 Schlüssel des SPN aus der Vorlesung mit einigen
+//! bekannten Klartext-Kryptotext-Paaren durch, verteilt auf alle CPU-Kerne.
+//! Das ist die Vergleichsgröße für die lineare und die differentielle
+//! Kryptoanalyse: Diese brauchen tausende Paare, aber nur einen Bruchteil
+//! der Rechenzeit für die geratenen Teilschlüssel; die vollständige Suche
+//! kommt mit zwei bis drei Paaren aus, muss aber jeden Schlüssel prüfen.
+//!
+//! Die Suche selbst (Modul [`search`]) ist unabhängig von der Chiffre und
+//! unterstützt Schlüssel bis 64 Bit, etwa für ein SPN mit längerem Schlüssel.
+
+use clap::Parser;
+use logging::{Verbosity, info, warn};
+use spn_core::pairs::PairFile;
+use spn_core::SpnCipher;
+use std::thread;
+use tool_error::{Context, ToolError};
+
+pub mod search;
+
+use search::exhaustive_search;
+
+/// Schlüssellänge des SPN aus der Vorlesung
+const KEY_BITS: u32 = 16;
+
+#[derive(Parser)]
+#[command(name = "spn_bruteforce")]
+#[command(about = "Vollständige Schlüsselsuche für das SPN mit bekannten Klartext-Kryptotext-Paaren")]
+#[command(version = "1.0")]
+struct Args {
+    /// Paardatei im Format von `linear_approximation generate-pairs`; nur die ersten Paare werden gebraucht
+    #[arg(short = 'f', long, required_unless_present = "pairs", help = "Paardatei aus 'generate-pairs'")]
+    pairs_file: Option<String>,
+
+    /// Einzelnes Paar als KLARTEXT:KRYPTOTEXT mit je 4 Hexadezimalziffern, mehrfach angebbar
+    #[arg(short = 'p', long = "pair", value_parser = parse_pair, help = "Paar KLARTEXT:KRYPTOTEXT, z. B. 1234:5F1A")]
+    pairs: Vec<(u16, u16)>,
+
+    /// Höchstens so viele Paare verwenden
+    #[arg(short = 'n', long, default_value_t = 4, help = "Anzahl der verwendeten Paare")]
+    max_pairs: usize,
+
+    /// Anzahl der Threads (Standard: Anzahl CPU-Kerne)
+    #[arg(short = 't', long, help = "Anzahl paralleler Such-Threads")]
+    threads: Option<usize>,
+
+    /// Beim ersten passenden Schlüssel aufhören
+    #[arg(long, help = "Beim ersten passenden Schlüssel aufhören")]
+    first: bool,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+fn parse_pair(text: &str) -> Result<(u16, u16), String> {
+    let (plaintext, ciphertext) = text.split_once(':').ok_or("Paar muss die Form KLARTEXT:KRYPTOTEXT haben")?;
+    Ok((parse_block(plaintext)?, parse_block(ciphertext)?))
+}
+
+fn parse_block(text: &str) -> Result<u16, String> {
+    if text.len() != 4 {
+        return Err(format!("Block '{}' muss 4 Hexadezimalziffern haben", text));
+    }
+    u16::from_str_radix(text, 16).map_err(|_| format!("'{}' ist keine Hexadezimalzahl", text))
+}
+
+/// Alle Schlüssel, unter denen jeder Klartext auf seinen Kryptotext abgebildet wird
+pub fn search_spn_key(pairs: &[(u16, u16)], threads: usize, stop_at_first: bool) -> search::SearchResult {
+    exhaustive_search(KEY_BITS, threads, stop_at_first, |key| {
+        let cipher = SpnCipher::new(key as u16);
+        pairs.iter().all(|&(plaintext, ciphertext)| cipher.encrypt_block(plaintext) == ciphertext)
+    })
+}
+
+/// Erwartete Anzahl falscher Schlüssel, die zufällig zu allen Paaren passen
+fn expected_false_keys(pairs: usize) -> f64 {
+    (2f64.powi(KEY_BITS as i32) - 1.0) / 2f64.powi(16 * pairs as i32)
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+
+    let (mut pairs, file_key) = match &args.pairs_file {
+        Some(path) => {
+            let file = PairFile::read(path)
+                .map_err(ToolError::data)
+                .context("Fehler beim Laden der Paare")?;
+            let pairs: Vec<(u16, u16)> = file.plaintexts.into_iter().zip(file.ciphertexts).collect();
+            (pairs, file.key)
+        }
+        None => (Vec::new(), None),
+    };
+    pairs.extend(&args.pairs);
+    pairs.truncate(args.max_pairs);
+    if pairs.is_empty() {
+        return Err(ToolError::data("Keine Klartext-Kryptotext-Paare gefunden"));
+    }
+    if pairs.len() == 1 {
+        warn!("Mit nur einem Paar passen im Mittel {:.1} falsche Schlüssel", expected_false_keys(1));
+    }
+
+    let threads = args.threads.unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()));
+    info!("Suche 2^{} Schlüssel mit {} Paaren auf {} Threads", KEY_BITS, pairs.len(), threads);
+
+    let result = search_spn_key(&pairs, threads, args.first);
+    for &key in &result.keys {
+        println!("{:04X}", key);
+    }
+    println!(
+        "{} Schlüssel geprüft in {:.2?} ({:.0} Schlüssel/s), {} passend, erwartet {:.2e} falsche",
+        result.tested, result.elapsed, result.keys_per_second(), result.keys.len(), expected_false_keys(pairs.len()),
+    );
+
+    if let Some(key) = file_key {
+        if result.keys.contains(&(key as u64)) {
+            info!("Schlüssel {:04X} aus der Paardatei gefunden", key);
+        } else if !args.first {
+            warn!("Schlüssel {:04X} aus der Paardatei ist nicht unter den Kandidaten", key);
+        }
+    }
+
+    if result.keys.is_empty() {
+        return Err(ToolError::failed("Kein Schlüssel passt zu allen Paaren"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovers_key() {
+        let cipher = SpnCipher::new(0x2D55);
+        let pairs: Vec<(u16, u16)> = [0x1234, 0xABCD, 0x0F0F].iter().map(|&p| (p, cipher.encrypt_block(p))).collect();
+
+        let result = search_spn_key(&pairs, 4, false);
+        assert_eq!(result.keys, [0x2D55]);
+        assert_eq!(result.tested, 1 << 16);
+    }
+
+    #[test]
+    fn test_parse_pair() {
+        assert_eq!(parse_pair("1234:5F1A").unwrap(), (0x1234, 0x5F1A));
+        assert!(parse_pair("1234").is_err());
+        assert!(parse_pair("12345:5F1A").is_err());
+    }
+}
@@ -0,0 +1,103 @@
+//! Parallele vollständige Suche über einen Schlüsselraum von 2^k Schlüsseln
+//!
+//! Die Suche kennt die Chiffre nicht, sondern nur eine Prüffunktion, die für
+//! einen Schlüssel (als Zahl) sagt, ob er zu allen bekannten Paaren passt.
+//! So lässt sie sich für das 16-Bit-SPN ebenso verwenden wie für größere
+//! Varianten mit bis zu 64 Schlüsselbits. Die Threads holen sich Blöcke von
+//! [`CHUNK`] Schlüsseln aus einem gemeinsamen Zähler und bleiben so auch bei
+//! ungleich schnellen Kernen gleichmäßig ausgelastet.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Anzahl der Schlüssel, die ein Thread am Stück prüft
+pub const CHUNK: u64 = 1 << 12;
+
+/// Ergebnis einer Suche
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    /// Alle passenden Schlüssel, aufsteigend sortiert
+    pub keys: Vec<u64>,
+    /// Anzahl der geprüften Schlüssel
+    pub tested: u64,
+    pub elapsed: Duration,
+}
+
+impl SearchResult {
+    pub fn keys_per_second(&self) -> f64 {
+        self.tested as f64 / self.elapsed.as_secs_f64().max(1e-9)
+    }
+}
+
+/// Prüft alle Schlüssel 0 .. 2^key_bits auf `threads` Threads
+///
+/// Mit `stop_at_first` endet die Suche beim ersten passenden Schlüssel;
+/// sonst werden alle gefunden, auch falsche Kandidaten bei zu wenigen Paaren.
+pub fn exhaustive_search(key_bits: u32, threads: usize, stop_at_first: bool, matches: impl Fn(u64) -> bool + Sync) -> SearchResult {
+    assert!((1..=64).contains(&key_bits), "Schlüssellänge muss zwischen 1 und 64 Bit liegen");
+    let key_count = 1u128 << key_bits;
+    let chunks = key_count.div_ceil(CHUNK as u128) as u64;
+
+    let next_chunk = AtomicU64::new(0);
+    let tested = AtomicU64::new(0);
+    let stop = AtomicBool::new(false);
+    let found = Mutex::new(Vec::new());
+    let started = Instant::now();
+
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| {
+                while !stop.load(Ordering::Relaxed) {
+                    let chunk = next_chunk.fetch_add(1, Ordering::Relaxed);
+                    if chunk >= chunks {
+                        break;
+                    }
+                    let first = chunk * CHUNK;
+                    let last = (first as u128 + CHUNK as u128).min(key_count) - 1;
+                    for key in first..=last as u64 {
+                        if matches(key) {
+                            found.lock().expect("kein Thread bricht mit gesperrtem Mutex ab").push(key);
+                            if stop_at_first {
+                                stop.store(true, Ordering::Relaxed);
+                            }
+                        }
+                    }
+                    tested.fetch_add(last as u64 - first + 1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+
+    let mut keys = found.into_inner().expect("alle Threads sind beendet");
+    keys.sort_unstable();
+    SearchResult { keys, tested: tested.into_inner(), elapsed: started.elapsed() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_all_matches() {
+        let result = exhaustive_search(16, 4, false, |key| key % 5000 == 1234);
+        assert_eq!(result.keys, [1234, 6234, 11234, 16234, 21234, 26234, 31234, 36234, 41234, 46234, 51234, 56234, 61234]);
+        assert_eq!(result.tested, 1 << 16);
+    }
+
+    #[test]
+    fn test_small_key_space() {
+        // Weniger Schlüssel als ein Block
+        let result = exhaustive_search(3, 2, false, |key| key == 7);
+        assert_eq!(result.keys, [7]);
+        assert_eq!(result.tested, 8);
+    }
+
+    #[test]
+    fn test_stop_at_first() {
+        let result = exhaustive_search(32, 2, true, |key| key == 100);
+        assert_eq!(result.keys, [100]);
+        assert!(result.tested < 1 << 20);
+    }
+}
@@ -0,0 +1,106 @@
+//! The attacker: recovers the private exponent from per-iteration timings
+//!
+//! Square-and-multiply walks the exponent from the lowest bit up. Every
+//! iteration squares, and multiplies first if the bit is set, so iterations
+//! for set bits take about twice as long. Single measurements are noisy:
+//! the attacker submits many chosen ciphertexts, takes the median duration
+//! of every iteration and splits the medians into a fast and a slow cluster.
+//! Slow iterations are set bits.
+//!
+//! The first multiplication is by 1 and costs almost nothing, so the lowest
+//! bit would come out as 0. An RSA exponent is always odd, so the attacker
+//! simply sets it. Whether the guess is right is checked with the public
+//! key alone: (m^e)^d must give back m.
+
+use crypto_math::mod_pow;
+use logging::debug;
+use num_bigint::BigUint;
+
+/// Result of one attack
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recovery {
+    /// The recovered exponent, lowest bit first as in the trace
+    pub exponent: BigUint,
+    /// Median duration of fast (bit clear) and slow (bit set) iterations in nanoseconds
+    pub fast: f64,
+    pub slow: f64,
+}
+
+/// Median duration of every iteration over all traces
+///
+/// Traces of different length are cut to the shortest.
+pub fn medians(traces: &[Vec<u64>]) -> Vec<f64> {
+    let length = traces.iter().map(Vec::len).min().unwrap_or(0);
+    (0..length)
+        .map(|i| {
+            let mut durations: Vec<u64> = traces.iter().map(|trace| trace[i]).collect();
+            durations.sort_unstable();
+            durations[durations.len() / 2] as f64
+        })
+        .collect()
+}
+
+/// Centres of a fast and a slow cluster, by two-means starting at the extremes
+pub fn clusters(values: &[f64]) -> (f64, f64) {
+    let mut fast = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let mut slow = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    for _ in 0..32 {
+        let threshold = (fast + slow) / 2.0;
+        let (low, high): (Vec<f64>, Vec<f64>) = values.iter().partition(|&&v| v <= threshold);
+        let mean = |group: &[f64], fallback: f64| if group.is_empty() { fallback } else { group.iter().sum::<f64>() / group.len() as f64 };
+        let (next_fast, next_slow) = (mean(&low, fast), mean(&high, slow));
+        if (next_fast, next_slow) == (fast, slow) {
+            break;
+        }
+        (fast, slow) = (next_fast, next_slow);
+    }
+    (fast, slow)
+}
+
+/// Guesses the exponent from the traces of many decryptions
+pub fn recover(traces: &[Vec<u64>]) -> Recovery {
+    let medians = medians(traces);
+    let (fast, slow) = clusters(&medians);
+    let threshold = (fast + slow) / 2.0;
+    debug!("Iterations: fast {:.0} ns, slow {:.0} ns", fast, slow);
+
+    let mut exponent = BigUint::default();
+    for (i, &median) in medians.iter().enumerate() {
+        exponent.set_bit(i as u64, i == 0 || median > threshold);
+    }
+    Recovery { exponent, fast, slow }
+}
+
+/// Whether `exponent` decrypts like the private key, checked with the public key only
+pub fn verify(exponent: &BigUint, e: &BigUint, n: &BigUint) -> bool {
+    [2u32, 3, 0xC0FFEE].into_iter().map(|m| BigUint::from(m) % n).all(|m| mod_pow(&mod_pow(&m, e, n), exponent, n) == m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_medians_and_clusters() {
+        let traces = vec![vec![10, 20, 11], vec![12, 21, 10, 99], vec![11, 500, 10]];
+        assert_eq!(medians(&traces), vec![11.0, 21.0, 10.0]);
+        assert_eq!(clusters(&[10.0, 11.0, 20.0, 22.0, 9.0]), (10.0, 21.0));
+        assert_eq!(clusters(&[]), (f64::INFINITY, f64::NEG_INFINITY));
+    }
+
+    #[test]
+    fn test_recover_from_synthetic_traces() {
+        // Exponent 0b1011001, the lowest iteration is cheap as in the real victim
+        let trace: Vec<u64> = [15, 100, 100, 200, 200, 100, 200].to_vec();
+        let recovery = recover(&[trace.clone(), trace.clone(), trace]);
+        assert_eq!(recovery.exponent, BigUint::from(0b1011001u32));
+    }
+
+    #[test]
+    fn test_verify() {
+        // n = 61 · 53, e = 17, d = 2753
+        let (e, n) = (BigUint::from(17u32), BigUint::from(3233u32));
+        assert!(verify(&BigUint::from(2753u32), &e, &n));
+        assert!(!verify(&BigUint::from(2755u32), &e, &n));
+    }
+}
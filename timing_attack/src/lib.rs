@@ -0,0 +1,116 @@
+//! Demonstrates a timing attack on RSA decryption and how to stop it.
+//!
+//! A server decrypts with a private RSA exponent d using square-and-multiply,
+//! which multiplies only for set bits of d. The attacker submits chosen
+//! ciphertexts, times every iteration of the exponentiation and recovers d
+//! bit by bit from the statistics of those timings (Kocher, 1996).
+//!
+//! With `--defense ladder` the server uses a Montgomery ladder that does the
+//! same work for every bit; with `--defense blinding` it exponentiates by a
+//! freshly randomised d + k·φ(n). Either way the timings no longer add up to
+//! d and the attack fails.
+
+use clap::Parser;
+use csprng::RngArgs;
+use logging::{English, Verbosity, info};
+use num_bigint::{BigUint, RandBigInt};
+use tool_error::ToolError;
+
+pub mod attack;
+pub mod victim;
+
+use victim::{Defense, E, Victim};
+
+/// Command-line arguments for the timing-attack demo.
+#[derive(Parser, Debug)]
+#[command(name = "timing_attack", about = "Timing attack demo against square-and-multiply RSA decryption")]
+#[command(after_help = "Exit codes: 0 private exponent recovered, 1 attack failed (expected with --defense ladder or blinding)")]
+struct Cli {
+    /// Size of the server's RSA modulus
+    #[arg(short, long, default_value_t = 512, value_parser = clap::value_parser!(u64).range(64..=4096), help = "Bits of the RSA modulus")]
+    bits: u64,
+
+    /// Chosen ciphertexts the attacker submits
+    #[arg(short, long, default_value_t = 31, value_parser = clap::value_parser!(u64).range(1..), help = "Number of chosen ciphertexts to time")]
+    samples: u64,
+
+    /// How the server exponentiates
+    #[arg(short, long, value_enum, default_value = "none", help = "Server countermeasure")]
+    defense: Defense,
+
+    #[command(flatten)]
+    rng: RngArgs,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+/// Number of exponent bits that `recovered` got right, out of the longer of both
+pub fn correct_bits(recovered: &BigUint, actual: &BigUint) -> (u64, u64) {
+    let length = recovered.bits().max(actual.bits());
+    let correct = (0..length).filter(|&i| recovered.bit(i) == actual.bit(i)).count() as u64;
+    (correct, length)
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+
+    let mut rng = cli.rng.rng();
+    info!("Generating a {}-bit RSA key", cli.bits);
+    let victim = Victim::new(cli.defense, cli.bits, &mut rng);
+    let n = victim.modulus();
+
+    let traces: Vec<Vec<u64>> = (0..cli.samples)
+        .map(|_| victim.decrypt(&rng.gen_biguint_below(n), &mut rng))
+        .collect();
+    let recovery = attack::recover(&traces);
+    let (correct, length) = correct_bits(&recovery.exponent, victim.exponent());
+
+    println!("Server defense: {:?}", victim.defense());
+    println!("Modulus:        {} bits", n.bits());
+    println!("Samples:        {} chosen ciphertexts, {} iterations each", cli.samples, traces.iter().map(Vec::len).min().unwrap_or(0));
+    println!("Iterations:     fast {:.0} ns, slow {:.0} ns", recovery.fast, recovery.slow);
+    println!("Correct bits:   {} of {}", correct, length);
+
+    if !attack::verify(&recovery.exponent, &BigUint::from(E), n) {
+        return Err(ToolError::failed("recovered exponent does not decrypt, the timings do not reveal d"));
+    }
+    println!("Recovered d:    {:x}", recovery.exponent);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csprng::{Algorithm, Drbg};
+
+    fn attack(defense: Defense) -> bool {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"timing");
+        let victim = Victim::new(defense, 256, &mut rng);
+        let traces: Vec<Vec<u64>> = (0..31).map(|_| victim.decrypt(&rng.gen_biguint_below(victim.modulus()), &mut rng)).collect();
+        let recovery = attack::recover(&traces);
+        attack::verify(&recovery.exponent, &BigUint::from(E), victim.modulus())
+    }
+
+    #[test]
+    fn test_attack_succeeds_without_defense() {
+        assert!(attack(Defense::None));
+    }
+
+    #[test]
+    fn test_attack_fails_against_defenses() {
+        assert!(!attack(Defense::Ladder));
+        assert!(!attack(Defense::Blinding));
+    }
+
+    #[test]
+    fn test_correct_bits() {
+        assert_eq!(correct_bits(&BigUint::from(0b1011u32), &BigUint::from(0b1011u32)), (4, 4));
+        assert_eq!(correct_bits(&BigUint::from(0b0011u32), &BigUint::from(0b1001u32)), (2, 4));
+    }
+}
@@ -0,0 +1,135 @@
+//! The server side: decrypts chosen ciphertexts with its private RSA exponent
+//!
+//! The attacker may submit ciphertexts and observe when each step of the
+//! exponentiation ends, the way a cache or power probe sees the loop of a
+//! co-located process. [`Victim::decrypt`] returns exactly that: the
+//! duration of each loop iteration in nanoseconds.
+//!
+//! [`Defense::None`] uses the textbook square-and-multiply of
+//! [`crypto_math::mod_pow`], which multiplies only for set exponent bits.
+//! [`Defense::Ladder`] uses the Montgomery ladder of
+//! [`crypto_math::mod_pow_ct`], whose steps all do the same work.
+//! [`Defense::Blinding`] keeps square-and-multiply but raises to d + k·φ(n)
+//! with a fresh random k each time, so every trace shows a different
+//! exponent that never repeats.
+
+use clap::ValueEnum;
+use crypto_math::{is_probable_prime, mod_inverse, montgomery_ladder, square_and_multiply};
+use num_bigint::{BigUint, RandBigInt};
+use rand::Rng;
+use secret::Secret;
+use std::time::Instant;
+
+/// RSA public exponent
+pub const E: u32 = 65537;
+
+/// Bits of the random blinding factor k
+const BLINDING_BITS: u64 = 32;
+
+/// How the server computes c^d mod n
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Defense {
+    /// Square-and-multiply, leaks every exponent bit
+    None,
+    /// Montgomery ladder with constant-time swaps
+    Ladder,
+    /// Square-and-multiply with a freshly blinded exponent
+    Blinding,
+}
+
+/// The server with its RSA key
+pub struct Victim {
+    defense: Defense,
+    n: BigUint,
+    d: Secret<BigUint>,
+    phi: Secret<BigUint>,
+}
+
+fn random_prime(bits: u64, rng: &mut impl Rng) -> BigUint {
+    loop {
+        // Top two bits set so that the product has the full length, bottom bit for an odd candidate
+        let candidate = rng.gen_biguint(bits) | (BigUint::from(3u32) << (bits - 2)) | BigUint::from(1u32);
+        if is_probable_prime(&candidate, 20) {
+            return candidate;
+        }
+    }
+}
+
+impl Victim {
+    /// Creates a server with a fresh RSA key of `bits` bits
+    pub fn new(defense: Defense, bits: u64, rng: &mut impl Rng) -> Self {
+        loop {
+            let (p, q) = (random_prime(bits / 2, rng), random_prime(bits - bits / 2, rng));
+            let phi = Secret::new((&p - 1u32) * (&q - 1u32));
+            if p == q {
+                continue;
+            }
+            if let Some(d) = mod_inverse(&BigUint::from(E), &phi) {
+                return Victim { defense, n: p * q, d: Secret::new(d), phi };
+            }
+        }
+    }
+
+    pub fn defense(&self) -> Defense {
+        self.defense
+    }
+
+    /// Public modulus
+    pub fn modulus(&self) -> &BigUint {
+        &self.n
+    }
+
+    /// The private exponent, only to score the attack afterwards
+    pub fn exponent(&self) -> &BigUint {
+        &self.d
+    }
+
+    /// Decrypts `ciphertext` and returns the duration of every loop iteration
+    pub fn decrypt(&self, ciphertext: &BigUint, rng: &mut impl Rng) -> Vec<u64> {
+        let mut trace = Vec::with_capacity(self.n.bits() as usize + BLINDING_BITS as usize);
+        let mut last = Instant::now();
+        let mut step = || {
+            let now = Instant::now();
+            trace.push((now - last).as_nanos() as u64);
+            last = now;
+        };
+
+        match self.defense {
+            Defense::None => square_and_multiply(ciphertext, &self.d, &self.n, &mut step),
+            Defense::Ladder => montgomery_ladder(ciphertext, &self.d, &self.n, &mut step),
+            Defense::Blinding => {
+                let blinded = Secret::new(&*self.d + rng.gen_biguint(BLINDING_BITS) * &*self.phi);
+                square_and_multiply(ciphertext, &blinded, &self.n, &mut step)
+            }
+        };
+        trace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto_math::mod_pow;
+    use csprng::{Algorithm, Drbg};
+
+    #[test]
+    fn test_key_and_traces() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"victim");
+        for defense in [Defense::None, Defense::Ladder, Defense::Blinding] {
+            let victim = Victim::new(defense, 128, &mut rng);
+            let n = victim.modulus();
+            assert_eq!(n.bits(), 128);
+            let message = BigUint::from(0xC0FFEEu32);
+            let ciphertext = mod_pow(&message, &BigUint::from(E), n);
+            assert_eq!(mod_pow(&ciphertext, victim.exponent(), n), message);
+
+            let trace = victim.decrypt(&ciphertext, &mut rng);
+            let expected = match defense {
+                Defense::None => victim.exponent().bits(),
+                Defense::Ladder => n.bits(),
+                Defense::Blinding => trace.len() as u64,
+            };
+            assert_eq!(trace.len() as u64, expected, "{:?}", defense);
+        }
+    }
+}
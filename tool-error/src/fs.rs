@@ -0,0 +1,33 @@
+//! Dateizugriffe, deren Fehler den Dateinamen enthalten
+
+use std::path::Path;
+
+use crate::{Result, ToolError};
+
+pub fn read(path: impl AsRef<Path>) -> Result<Vec<u8>> {
+    let path = path.as_ref();
+    std::fs::read(path).map_err(|e| ToolError::io(path, e))
+}
+
+pub fn read_to_string(path: impl AsRef<Path>) -> Result<String> {
+    let path = path.as_ref();
+    std::fs::read_to_string(path).map_err(|e| ToolError::io(path, e))
+}
+
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<()> {
+    let path = path.as_ref();
+    std::fs::write(path, contents).map_err(|e| ToolError::io(path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ExitStatus;
+
+    #[test]
+    fn test_missing_file_names_path() {
+        let error = read_to_string("/nicht/vorhanden.txt").unwrap_err();
+        assert!(error.to_string().starts_with("/nicht/vorhanden.txt: "));
+        assert_eq!(error.exit_status(), ExitStatus::Io);
+    }
+}
@@ -0,0 +1,217 @@
+//! Gemeinsamer Fehlertyp für alle Werkzeuge
+//!
+//! Jedes Werkzeug liefert aus `run` ein [`Result`] und überlässt Ausgabe und
+//! Exit Code [`report`]. So sehen Fehlermeldungen überall gleich aus
+//! (`error: ...` auf STDERR, wie bei clap) und Skripte können am Exit Code
+//! erkennen, ob die Eingabe, eine Datei oder der Aufruf selbst das Problem war.
+//!
+//! Mit [`Context`] lässt sich einem Fehler voranstellen, was gerade versucht
+//! wurde, ohne den ursprünglichen Exit Code zu verlieren.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use codec::CodecError;
+use key_formats::KeyFileError;
+
+pub mod fs;
+
+pub type Result<T, E = ToolError> = std::result::Result<T, E>;
+
+/// Exit Codes, angelehnt an sysexits.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// Allgemeiner Fehlschlag, z. B. ungültige Signatur oder erfolgloser Angriff
+    Failure = 1,
+    /// Unzulässiger Aufruf (auch clap beendet sich mit 2)
+    Usage = 2,
+    /// Eingabedaten haben ein falsches Format
+    Data = 65,
+    /// Datei oder Verbindung konnte nicht gelesen oder geschrieben werden
+    Io = 74,
+}
+
+impl From<ExitStatus> for ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ToolError {
+    /// Argumente passen nicht zusammen, was clap nicht prüfen kann
+    #[error("{0}")]
+    Usage(String),
+    /// Eingabe, Schlüssel oder Parameter sind ungültig
+    #[error("{0}")]
+    Data(String),
+    /// Lesen oder Schreiben ist fehlgeschlagen
+    #[error("{}", match path {
+        Some(path) => format!("{}: {}", path.display(), source),
+        None => source.to_string(),
+    })]
+    Io { path: Option<PathBuf>, #[source] source: io::Error },
+    /// Das Werkzeug ist gelaufen, hat aber kein Ergebnis gefunden
+    #[error("{0}")]
+    Failed(String),
+    /// Ergebnis wurde schon ausgegeben, es zählt nur noch der Exit Code
+    #[error("Exit Code {}", *.0 as u8)]
+    Silent(ExitStatus),
+    /// Fehler mit vorangestellter Beschreibung
+    #[error("{context}: {source}")]
+    Context { context: String, #[source] source: Box<dyn Error> },
+    /// Fehler aus einer Bibliothek ohne eigene Zuordnung
+    #[error(transparent)]
+    Other(Box<dyn Error>),
+}
+
+impl ToolError {
+    pub fn usage(message: impl Into<String>) -> Self {
+        ToolError::Usage(message.into())
+    }
+
+    pub fn data(message: impl fmt::Display) -> Self {
+        ToolError::Data(message.to_string())
+    }
+
+    pub fn failed(message: impl Into<String>) -> Self {
+        ToolError::Failed(message.into())
+    }
+
+    /// Ein-/Ausgabefehler mit der betroffenen Datei
+    pub fn io(path: impl AsRef<Path>, source: io::Error) -> Self {
+        ToolError::Io { path: Some(path.as_ref().to_path_buf()), source }
+    }
+
+    pub fn exit_status(&self) -> ExitStatus {
+        match self {
+            ToolError::Usage(_) => ExitStatus::Usage,
+            ToolError::Data(_) => ExitStatus::Data,
+            ToolError::Io { .. } => ExitStatus::Io,
+            ToolError::Failed(_) | ToolError::Other(_) => ExitStatus::Failure,
+            ToolError::Silent(status) => *status,
+            ToolError::Context { source, .. } => match source.downcast_ref::<ToolError>() {
+                Some(inner) => inner.exit_status(),
+                None => ExitStatus::Failure,
+            },
+        }
+    }
+}
+
+impl From<io::Error> for ToolError {
+    fn from(source: io::Error) -> Self {
+        ToolError::Io { path: None, source }
+    }
+}
+
+impl From<String> for ToolError {
+    fn from(message: String) -> Self {
+        ToolError::Failed(message)
+    }
+}
+
+impl From<&str> for ToolError {
+    fn from(message: &str) -> Self {
+        ToolError::Failed(message.to_string())
+    }
+}
+
+impl From<Box<dyn Error>> for ToolError {
+    fn from(error: Box<dyn Error>) -> Self {
+        let error = match error.downcast::<ToolError>() {
+            Ok(error) => return *error,
+            Err(error) => error,
+        };
+        match error.downcast::<io::Error>() {
+            Ok(source) => ToolError::Io { path: None, source: *source },
+            Err(error) => ToolError::Other(error),
+        }
+    }
+}
+
+impl From<CodecError> for ToolError {
+    fn from(error: CodecError) -> Self {
+        match error {
+            CodecError::Io { path, message } => {
+                ToolError::io(path, io::Error::other(message))
+            }
+            error => ToolError::data(error),
+        }
+    }
+}
+
+impl From<KeyFileError> for ToolError {
+    fn from(error: KeyFileError) -> Self {
+        match error {
+            KeyFileError::Io { path, source } => ToolError::io(path, source),
+            error => ToolError::data(error),
+        }
+    }
+}
+
+/// Stellt einem Fehler voran, was gerade versucht wurde
+pub trait Context<T> {
+    fn context(self, context: impl Into<String>) -> Result<T>;
+
+    fn with_context<C: Into<String>>(self, context: impl FnOnce() -> C) -> Result<T>;
+}
+
+impl<T, E: Into<ToolError>> Context<T> for std::result::Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T> {
+        self.with_context(|| context)
+    }
+
+    fn with_context<C: Into<String>>(self, context: impl FnOnce() -> C) -> Result<T> {
+        self.map_err(|error| ToolError::Context {
+            context: context().into(),
+            source: Box::new(error.into()),
+        })
+    }
+}
+
+/// Gibt einen Fehler auf STDERR aus und liefert den passenden Exit Code
+///
+/// Gedacht für `main`: `fn main() -> ExitCode { tool_error::report(werkzeug::run(std::env::args_os())) }`
+pub fn report(result: Result<()>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(ToolError::Silent(status)) => status.into(),
+        Err(error) => {
+            eprintln!("error: {}", error);
+            error.exit_status().into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_status() {
+        assert_eq!(ToolError::usage("x").exit_status(), ExitStatus::Usage);
+        assert_eq!(ToolError::data("x").exit_status(), ExitStatus::Data);
+        assert_eq!(ToolError::from("x").exit_status(), ExitStatus::Failure);
+        assert_eq!(ToolError::from(io::Error::other("x")).exit_status(), ExitStatus::Io);
+        assert_eq!(ToolError::from(CodecError::InvalidPadding).exit_status(), ExitStatus::Data);
+    }
+
+    #[test]
+    fn test_context_keeps_exit_status() {
+        let result: Result<()> = Err(ToolError::data("ungültige Zahl"));
+        let error = result.context("Schlüsseldatei 'key.txt'").context("Entschlüsseln").unwrap_err();
+        assert_eq!(error.to_string(), "Entschlüsseln: Schlüsseldatei 'key.txt': ungültige Zahl");
+        assert_eq!(error.exit_status(), ExitStatus::Data);
+    }
+
+    #[test]
+    fn test_boxed_tool_error_is_unwrapped() {
+        let boxed: Box<dyn Error> = Box::new(ToolError::io("a.txt", io::Error::other("weg")));
+        let error = ToolError::from(boxed);
+        assert_eq!(error.exit_status(), ExitStatus::Io);
+        assert_eq!(error.to_string(), "a.txt: weg");
+    }
+}
@@ -0,0 +1,181 @@
+use clap::{Parser, ValueEnum};
+use logging::{English, Verbosity};
+use classical::Alphabet;
+use tool_error::ToolError;
+
+/// Command-line arguments for the Vigenère cipher program.
+#[derive(Parser, Debug)]
+struct Cli {
+    /// Path to the input file containing text to encrypt/decrypt
+    #[arg(short, long, help = "Path to the input file")]
+    file: String,
+
+    /// Key string for the Vigenère cipher
+    #[arg(short, long, conflicts_with = "running_key", required_unless_present = "running_key", help = "Key string for the cipher")]
+    key: Option<String>,
+
+    /// Text file used as a running key, at least as long as the message
+    #[arg(long, value_name = "FILE", help = "Path to a text file used as running key (must be at least as long as the message)")]
+    running_key: Option<String>,
+
+    /// Path to the output file where result will be saved
+    #[arg(short, long, help = "Path to the output file")]
+    output: String,
+
+    /// Mode of operation (encrypt or decrypt)
+    #[arg(short, long, help = "Mode of operation (encrypt/decrypt)")]
+    mode: OperationMode,
+
+    /// Alphabet the cipher operates on
+    #[arg(short, long, default_value = "latin", help = "Alphabet: latin, german, alnum, printable or a literal list of symbols")]
+    alphabet: String,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+/// Enum representing the mode of operation for the cipher.
+#[derive(Clone, Debug, ValueEnum)]
+enum OperationMode {
+    /// Encrypt mode
+    Encrypt,
+    /// Decrypt mode
+    Decrypt,
+}
+
+/// Main entry point for the Vigenère cipher program.
+///
+/// The first argument is the program name, as with `std::env::args_os`.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    // Parse command-line arguments
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+
+    // Read input file content
+    let content: String = tool_error::fs::read_to_string(&cli.file)?;
+
+    let alphabet = Alphabet::parse(&cli.alphabet).map_err(ToolError::usage)?;
+
+    // A running key is only the key text reduced to the alphabet; since it
+    // never repeats, the regular Vigenère routines can use it unchanged
+    let (key, description) = match (&cli.key, &cli.running_key) {
+        (_, Some(path)) => {
+            let key_text: String = tool_error::fs::read_to_string(path)?;
+            (running_key(&content, &key_text, &alphabet), format!("running key from: {}", path))
+        }
+        (Some(key), None) => (Ok(key.clone()), format!("key: {}", key)),
+        (None, None) => unreachable!("clap requires --key or --running-key"),
+    };
+
+    // Process based on selected mode
+    let result = key.and_then(|key| match cli.mode {
+        OperationMode::Encrypt => {
+            println!("Encrypting with {}", description);
+            encrypt(&content, &key, &alphabet)
+        }
+        OperationMode::Decrypt => {
+            println!("Decrypting with {}", description);
+            decrypt(&content, &key, &alphabet)
+        }
+    });
+    let result = result.map_err(ToolError::data)?;
+
+    // Write result to output file
+    tool_error::fs::write(&cli.output, result)?;
+    
+    println!("Operation completed successfully! Output saved to: {}", cli.output);
+    Ok(())
+}
+
+/// Prepares a running key: the symbols of the key text that belong to the
+/// alphabet, cut to the length of the message.
+///
+/// # Returns
+///
+/// The key, or an error if the key text is shorter than the message.
+fn running_key(content: &str, key_text: &str, alphabet: &Alphabet) -> Result<String, String> {
+    let message_length = alphabet.normalize(content).chars().count();
+    let key: String = alphabet.normalize(key_text).chars().take(message_length).collect();
+    let key_length = key.chars().count();
+
+    if key_length < message_length {
+        return Err(format!(
+            "Running key has only {} symbols of the alphabet, the message needs {}",
+            key_length, message_length
+        ));
+    }
+    Ok(key)
+}
+
+/// Encrypts text using the Vigenère cipher algorithm.
+///
+/// # Arguments
+///
+/// * `content` - The input text to encrypt.
+/// * `key` - The encryption key string.
+/// * `alphabet` - The alphabet the shifts are applied in.
+///
+/// # Returns
+///
+/// A `String` containing the encrypted text, or an error if the key
+/// contains symbols outside the alphabet.
+fn encrypt(content: &str, key: &str, alphabet: &Alphabet) -> Result<String, String> {
+    let shifts: Vec<usize> = alphabet.key_shifts(key)?;
+    let mut result: String = String::new();
+    let mut key_index: usize = 0;
+
+    // Process each character in the input content
+    for c in content.chars() {
+        if alphabet.index_of(c).is_some() {
+            // Apply Vigenère encryption: (plaintext + key) mod alphabet length
+            result.push(alphabet.shift(c, shifts[key_index % shifts.len()]));
+
+            // Only advance key index for characters of the alphabet
+            key_index += 1;
+        } else {
+            // Characters outside the alphabet remain unchanged
+            result.push(c);
+        }
+    }
+
+    Ok(result)
+}
+
+
+/// Decrypts text using the Vigenère cipher algorithm.
+///
+/// # Arguments
+///
+/// * `content` - The input text to decrypt.
+/// * `key` - The decryption key string.
+/// * `alphabet` - The alphabet the shifts are applied in.
+///
+/// # Returns
+///
+/// A `String` containing the decrypted text, or an error if the key
+/// contains symbols outside the alphabet.
+fn decrypt(content: &str, key: &str, alphabet: &Alphabet) -> Result<String, String> {
+    let shifts: Vec<usize> = alphabet.key_shifts(key)?;
+    let mut result: String = String::new();
+    let mut key_index: usize = 0;
+
+    // Process each character in the input content
+    for c in content.chars() {
+        if alphabet.index_of(c).is_some() {
+            // Apply Vigenère decryption: (ciphertext - key) mod alphabet length
+            result.push(alphabet.unshift(c, shifts[key_index % shifts.len()]));
+
+            // Only advance key index for characters of the alphabet
+            key_index += 1;
+        } else {
+            // Characters outside the alphabet remain unchanged
+            result.push(c);
+        }
+    }
+
+    Ok(result)
+}
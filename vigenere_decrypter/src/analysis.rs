@@ -0,0 +1,161 @@
+//! Key-length analysis report: IC per key length, Kasiski examination and
+//! the Friedman test, printable as a table or as JSON.
+
+use std::collections::HashMap;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Output format of the analysis report
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ReportFormat {
+    /// Human-readable tables
+    Table,
+    /// Machine-readable JSON
+    Json,
+}
+
+/// Length of the repeated sequences searched by the Kasiski examination
+const KASISKI_SEQUENCE_LENGTH: usize = 3;
+
+/// Number of repeated sequences listed in the report
+const KASISKI_SEQUENCES: usize = 10;
+
+#[derive(Debug, Serialize)]
+pub struct AnalysisReport {
+    pub text_length: usize,
+    pub index_of_coincidence: f64,
+    pub reference_ic: f64,
+    /// Key length estimated by the Friedman test, if the text allows one
+    pub friedman_estimate: Option<f64>,
+    pub ic_per_length: Vec<LengthIc>,
+    pub kasiski_repetitions: Vec<Repetition>,
+    pub kasiski_factors: Vec<FactorCount>,
+}
+
+/// Average IC of the columns for one key length
+#[derive(Debug, Serialize)]
+pub struct LengthIc {
+    pub key_length: usize,
+    pub average_ic: f64,
+}
+
+/// A repeated ciphertext sequence and the distances between its occurrences
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Repetition {
+    pub sequence: String,
+    pub positions: Vec<usize>,
+    pub distances: Vec<usize>,
+}
+
+/// How many Kasiski distances are divisible by a candidate key length
+#[derive(Debug, PartialEq, Serialize)]
+pub struct FactorCount {
+    pub factor: usize,
+    pub count: usize,
+}
+
+/// Finds all sequences that occur more than once, most frequent first.
+pub fn kasiski_repetitions(text: &str) -> Vec<Repetition> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut positions: HashMap<String, Vec<usize>> = HashMap::new();
+
+    for (i, window) in chars.windows(KASISKI_SEQUENCE_LENGTH).enumerate() {
+        positions.entry(window.iter().collect()).or_default().push(i);
+    }
+
+    let mut repetitions: Vec<Repetition> = positions.into_iter()
+        .filter(|(_, positions)| positions.len() > 1)
+        .map(|(sequence, positions)| {
+            let distances = positions.windows(2).map(|pair| pair[1] - pair[0]).collect();
+            Repetition { sequence, positions, distances }
+        })
+        .collect();
+    repetitions.sort_by(|a, b| {
+        b.positions.len().cmp(&a.positions.len())
+            .then(a.positions[0].cmp(&b.positions[0]))
+    });
+    repetitions
+}
+
+/// Counts for every factor from 2 up to `max_factor` how many distances it divides.
+pub fn kasiski_factors(repetitions: &[Repetition], max_factor: usize) -> Vec<FactorCount> {
+    (2..=max_factor)
+        .map(|factor| {
+            let count = repetitions.iter()
+                .flat_map(|repetition| &repetition.distances)
+                .filter(|distance| distance.is_multiple_of(factor))
+                .count();
+            FactorCount { factor, count }
+        })
+        .collect()
+}
+
+impl AnalysisReport {
+    /// Prints the report in the requested format.
+    pub fn print(&self, format: ReportFormat) {
+        match format {
+            ReportFormat::Table => self.print_table(),
+            ReportFormat::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(self).expect("report is serializable")
+            ),
+        }
+    }
+
+    fn print_table(&self) {
+        println!("Text length:          {}", self.text_length);
+        println!("Index of coincidence: {:.4} (language: {:.4})", self.index_of_coincidence, self.reference_ic);
+        match self.friedman_estimate {
+            Some(estimate) => println!("Friedman estimate:    {:.2}", estimate),
+            None => println!("Friedman estimate:    n/a"),
+        }
+
+        println!();
+        println!("{:>10} {:>10}", "Key length", "Avg. IC");
+        for entry in &self.ic_per_length {
+            println!("{:>10} {:>10.4}", entry.key_length, entry.average_ic);
+        }
+
+        println!();
+        println!("{:<8} {:>5}  Distances", "Sequence", "Count");
+        for repetition in self.kasiski_repetitions.iter().take(KASISKI_SEQUENCES) {
+            let distances: Vec<String> = repetition.distances.iter().map(|d| d.to_string()).collect();
+            println!("{:<8} {:>5}  {}", repetition.sequence, repetition.positions.len(), distances.join(", "));
+        }
+
+        println!();
+        println!("{:>6} {:>9}", "Factor", "Distances");
+        for factor in &self.kasiski_factors {
+            println!("{:>6} {:>9}", factor.factor, factor.count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kasiski_repetitions() {
+        let repetitions = kasiski_repetitions("ABCXXABCYYYYABC");
+        assert_eq!(repetitions[0], Repetition {
+            sequence: "ABC".to_string(),
+            positions: vec![0, 5, 12],
+            distances: vec![5, 7],
+        });
+        assert!(repetitions.iter().all(|r| r.sequence != "XXA"));
+    }
+
+    #[test]
+    fn test_kasiski_factors() {
+        let repetitions = vec![Repetition {
+            sequence: "ABC".to_string(),
+            positions: vec![0, 6, 18],
+            distances: vec![6, 12],
+        }];
+        let factors = kasiski_factors(&repetitions, 6);
+        let counts: Vec<usize> = factors.iter().map(|f| f.count).collect();
+        assert_eq!(counts, vec![2, 2, 1, 0, 2]);
+    }
+}
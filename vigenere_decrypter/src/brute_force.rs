@@ -0,0 +1,172 @@
+//! Exhaustive search over short repeating keys.
+//!
+//! Key-length detection needs enough text per column: with 30 symbols and a
+//! key of length 5 every column holds six letters, far too few for IC or
+//! chi-square to mean anything. A short key can simply be tried instead.
+//! Every key up to the maximum length decrypts the ciphertext and the
+//! n-gram model scores the result; the keys are split into ranges that
+//! rayon spreads over all cores. Over the Latin alphabet length 6 means
+//! about 3·10^8 keys, a matter of seconds to minutes depending on the text
+//! and the number of cores.
+
+use rayon::prelude::*;
+
+use classical::{Alphabet, NgramModel};
+
+use crate::running_key::{ngram_scores, MAX_TABLE_SIZE};
+
+/// Longest key the search accepts
+pub const MAX_LENGTH: usize = 8;
+
+/// Most keys the search is willing to try
+const MAX_KEYS: u64 = 1 << 36;
+
+/// Only the start of a long ciphertext is scored during the search
+const MAX_SCORED_SYMBOLS: usize = 120;
+
+/// Returns the `keep` best keys of length 1 to `max_length`, best first.
+///
+/// Keys that repeat themselves ("ABAB") are skipped, their period was
+/// already tried as a shorter key.
+pub fn search(ciphertext: &str, model: &NgramModel, alphabet: &Alphabet, max_length: usize, keep: usize) -> Result<Vec<String>, String> {
+    assert!((1..=MAX_LENGTH).contains(&max_length), "key length out of range");
+    let n = model.n();
+    let size = alphabet.len();
+    if size.checked_pow(n as u32).is_none_or(|count| count > MAX_TABLE_SIZE) {
+        return Err(format!("{}-grams over {} symbols are too many for the brute-force search", n, size));
+    }
+    let total: u64 = (1..=max_length as u32)
+        .map(|length| (size as u64).checked_pow(length))
+        .try_fold(0u64, |total, count| total.checked_add(count?))
+        .filter(|&total| total <= MAX_KEYS)
+        .ok_or_else(|| format!("Keys of up to {} symbols over {} symbols are too many to try", max_length, size))?;
+
+    let cipher: Vec<usize> = ciphertext.chars()
+        .take(MAX_SCORED_SYMBOLS)
+        .map(|c| alphabet.index_of(c).expect("ciphertext is normalized"))
+        .collect();
+    if cipher.len() < n {
+        return Err(format!("Ciphertext needs at least {} symbols", n));
+    }
+    let plain: Vec<usize> = cipher.iter()
+        .flat_map(|&symbol| (0..size).map(move |shift| (symbol + size - shift) % size))
+        .collect();
+    logging::info!("Trying {} keys of up to {} symbols", total, max_length);
+
+    let table = ngram_scores(model, alphabet);
+    let mut best: Vec<(f64, Vec<usize>)> = Vec::new();
+    for length in 1..=max_length {
+        let found = (0..(size as u64).pow(length as u32))
+            .into_par_iter()
+            .fold(Vec::new, |mut top, index| {
+                let digits = digits(index, length, size);
+                let shifts = &digits[..length];
+                let score = score(&plain, shifts, &table, n, size);
+                if (top.len() < keep || score > worst(&top)) && is_primitive(shifts) {
+                    insert(&mut top, (score, shifts.to_vec()), keep);
+                }
+                top
+            })
+            .reduce(Vec::new, |mut top, other| {
+                other.into_iter().for_each(|candidate| insert(&mut top, candidate, keep));
+                top
+            });
+        found.into_iter().for_each(|candidate| insert(&mut best, candidate, keep));
+    }
+
+    Ok(best.into_iter()
+        .map(|(_, shifts)| shifts.into_iter().map(|shift| alphabet.symbol(shift)).collect())
+        .collect())
+}
+
+/// Sum of the n-gram scores of the ciphertext decrypted with `shifts`
+///
+/// `plain[position * size + shift]` is the decrypted symbol, so the inner
+/// loops only look up values and slide the n-gram index along.
+fn score(plain: &[usize], shifts: &[usize], table: &[f64], n: usize, size: usize) -> f64 {
+    let mut decrypted = [0; MAX_SCORED_SYMBOLS];
+    let mut column = 0;
+    for (symbol, choices) in decrypted.iter_mut().zip(plain.chunks_exact(size)) {
+        *symbol = choices[shifts[column]];
+        column = if column + 1 == shifts.len() { 0 } else { column + 1 };
+    }
+
+    let length = plain.len() / size;
+    let oldest = size.pow(n as u32 - 1);
+    let mut window = decrypted[..n - 1].iter().fold(0, |window, &symbol| window * size + symbol);
+    let mut total = 0.0;
+    for position in n - 1..length {
+        window = window * size + decrypted[position];
+        total += table[window];
+        window -= decrypted[position + 1 - n] * oldest;
+    }
+    total
+}
+
+/// Lowest score in a list sorted best first
+fn worst(top: &[(f64, Vec<usize>)]) -> f64 {
+    top.last().map_or(f64::NEG_INFINITY, |(score, _)| *score)
+}
+
+/// Inserts a candidate into a list sorted best first that holds at most `keep` entries
+fn insert(top: &mut Vec<(f64, Vec<usize>)>, candidate: (f64, Vec<usize>), keep: usize) {
+    let position = top.partition_point(|(score, _)| *score >= candidate.0);
+    if position < keep {
+        top.insert(position, candidate);
+        top.truncate(keep);
+    }
+}
+
+/// Whether a key is not a repetition of a shorter one, see `reduce_key`
+fn is_primitive(shifts: &[usize]) -> bool {
+    !(1..shifts.len())
+        .filter(|period| shifts.len().is_multiple_of(*period))
+        .any(|period| (period..shifts.len()).all(|i| shifts[i] == shifts[i - period]))
+}
+
+/// Splits a key index into `length` shifts, most significant first
+fn digits(mut index: u64, length: usize, size: usize) -> [usize; MAX_LENGTH] {
+    let mut shifts = [0; MAX_LENGTH];
+    for shift in shifts[..length].iter_mut().rev() {
+        *shift = (index % size as u64) as usize;
+        index /= size as u64;
+    }
+    shifts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use classical::Language;
+
+    #[test]
+    fn test_search_finds_short_key() {
+        let alphabet = Alphabet::latin();
+        let model = NgramModel::quadgrams(Language::En).unwrap();
+        let plaintext = alphabet.normalize("the other men went north with the horses from the station");
+        let ciphertext: String = plaintext.chars()
+            .zip("LUX".chars().cycle())
+            .map(|(p, k)| alphabet.shift(p, alphabet.index_of(k).unwrap()))
+            .collect();
+
+        let keys = search(&ciphertext, &model, &alphabet, 3, 5).unwrap();
+        assert_eq!(keys[0], "LUX");
+        assert_eq!(keys.len(), 5);
+        assert!(keys.iter().all(|key| is_primitive(&alphabet.key_shifts(key).unwrap())));
+    }
+
+    #[test]
+    fn test_search_rejects_too_many_keys() {
+        let alphabet = Alphabet::parse("printable").unwrap();
+        let model = NgramModel::bigrams(Language::En);
+        assert!(search("ABCDEFGH", &model, &alphabet, MAX_LENGTH, 5).is_err());
+    }
+
+    #[test]
+    fn test_is_primitive() {
+        assert!(is_primitive(&[3]));
+        assert!(is_primitive(&[1, 2, 1]));
+        assert!(!is_primitive(&[1, 2, 1, 2]));
+        assert!(!is_primitive(&[0, 0]));
+    }
+}
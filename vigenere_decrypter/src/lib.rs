@@ -0,0 +1,638 @@
+use analysis::{AnalysisReport, LengthIc, ReportFormat};
+use std::time::Instant;
+
+use clap::Parser;
+use logging::{English, Verbosity, debug, info, warn};
+use serde::Serialize;
+use classical::{
+    count_frequencies, friedman_key_length, index_of_coincidence, rank_shifts, Alphabet, FrequencyTable, Language,
+    NgramModel,
+};
+use tool_error::{Context, ToolError};
+
+mod analysis;
+mod brute_force;
+mod running_key;
+
+/// Command-line arguments for the Vigenère decryptor program.
+#[derive(Parser, Debug)]
+struct Cli {
+    /// Path to the input file containing encrypted text
+    #[arg(short, long, help = "Path to the input file containing encrypted text")]
+    file: String,
+
+    /// Path to the output file where decrypted text will be saved
+    #[arg(short, long, required_unless_present = "analysis_report", help = "Path to the output file for decrypted text")]
+    output: Option<String>,
+
+    /// Language of the plaintext, selects the built-in frequency table
+    #[arg(short, long, value_enum, default_value = "de", help = "Plaintext language (de, en, fr, es, it)")]
+    language: Language,
+
+    /// Custom letter frequency distribution, overrides --language
+    #[arg(long, help = "Path to a custom frequency table (one 'LETTER WEIGHT' pair per line)")]
+    freq_file: Option<String>,
+
+    /// Alphabet the ciphertext was encrypted over
+    #[arg(short, long, default_value = "latin", help = "Alphabet: latin, german, alnum, printable or a literal list of symbols")]
+    alphabet: String,
+
+    /// N-gram statistics used to validate the decryption
+    #[arg(long, help = "Path to a quadgram statistics file (one 'NGRAM COUNT' pair per line)")]
+    quadgram_file: Option<String>,
+
+    /// Only print the key-length analysis instead of decrypting
+    #[arg(long, value_enum, value_name = "FORMAT", help = "Print IC per key length, Kasiski distances and the Friedman estimate (table/json)")]
+    analysis_report: Option<ReportFormat>,
+
+    /// Attack a running-key cipher instead of a repeating key
+    #[arg(long, conflicts_with = "analysis_report", help = "Attack a running-key cipher (key is a text as long as the message)")]
+    running_key: bool,
+
+    /// N-gram statistics for the running-key attack; the built-in tables are small,
+    /// a real quadgram file works much better
+    #[arg(long, requires = "running_key", help = "Path to an n-gram statistics file for --running-key (up to quadgrams)")]
+    ngram_file: Option<String>,
+
+    /// Try every short key instead of detecting the key length, for texts too
+    /// short for the statistics
+    #[arg(long, conflicts_with_all = ["analysis_report", "running_key"], help = "Try every key up to --max-key-length in parallel (for very short ciphertexts)")]
+    brute_force: bool,
+
+    /// Longest key tried by --brute-force
+    #[arg(long, default_value_t = 6, requires = "brute_force",
+          value_parser = clap::value_parser!(u32).range(1..=brute_force::MAX_LENGTH as i64),
+          help = "Longest key tried by --brute-force")]
+    max_key_length: u32,
+
+    /// Print the result as JSON instead of text
+    #[arg(long, conflicts_with = "analysis_report", help = "Print key, confidence and run time as JSON")]
+    json: bool,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+/// Result of a decryption as printed by --json
+#[derive(Debug, Serialize)]
+struct DecryptionReport {
+    /// "repeating-key", "brute-force" or "running-key"
+    mode: &'static str,
+    key: String,
+    key_length: usize,
+    /// Only known for repeating keys
+    confidence: Option<f64>,
+    output: String,
+    elapsed_ms: f64,
+}
+
+impl DecryptionReport {
+    fn print(&self) {
+        println!("{}", serde_json::to_string_pretty(self).expect("report is serializable"));
+    }
+}
+
+/// A key together with the n-gram score of the resulting plaintext
+struct Candidate {
+    key: String,
+    score: f64,
+}
+
+const MAX_KEY_LENGTH: usize = 100;
+
+/// Average IC (relative to the language reference IC) that is good enough to stop searching
+const EARLY_STOP_IC_RATIO: f64 = 0.92;
+
+/// Below this ratio of the reference IC no key length is considered convincing
+const FALLBACK_IC_RATIO: f64 = 0.59;
+
+/// Minimum relative improvement of the score per n-gram over the ciphertext
+/// for a decryption to look like language
+const PLAUSIBLE_SCORE_GAIN: f64 = 0.015;
+
+/// Keys kept by the brute-force search for the confidence estimate
+const BRUTE_FORCE_CANDIDATES: usize = 20;
+
+/// Number of alternative key lengths tried when the decryption looks implausible
+const ALTERNATIVE_KEY_LENGTHS: usize = 4;
+
+/// Largest key length considered for the alternatives (long lengths overfit the IC)
+const MAX_ALTERNATIVE_KEY_LENGTH: usize = 20;
+
+/// Runs the tool with the given command-line arguments
+///
+/// The first argument is the program name, as with `std::env::args_os`,
+/// so the tool can also be started from `cryptolab`.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let started = Instant::now();
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+    let input: String = tool_error::fs::read_to_string(&cli.file)?;
+
+    let alphabet = Alphabet::parse(&cli.alphabet).map_err(ToolError::usage)?;
+
+    let table = match &cli.freq_file {
+        Some(path) => {
+            let content = tool_error::fs::read_to_string(path)?;
+            FrequencyTable::parse(&content)
+                .map_err(ToolError::data)
+                .with_context(|| format!("invalid frequency file '{}'", path))?
+        }
+        None => cli.language.table(),
+    };
+    let frequencies = table.expected(&alphabet);
+
+    // Clean text: only symbols of the alphabet, case-folded
+    let clean_text = alphabet.normalize(&input);
+    
+    if clean_text.chars().count() < 50 && !cli.brute_force {
+        warn!("Text may be too short for reliable analysis, --brute-force may help");
+    }
+
+    let reference_ic = table.reference_ic(&alphabet);
+    let text_length = clean_text.chars().count();
+    let friedman_estimate = friedman_key_length(calc_ic(&clean_text, &alphabet), reference_ic, alphabet.len(), text_length);
+    debug!("Index of coincidence {:.4} (reference {:.4}), Friedman estimate {:.1?}",
+           calc_ic(&clean_text, &alphabet), reference_ic, friedman_estimate);
+
+    if let Some(format) = cli.analysis_report {
+        let repetitions = analysis::kasiski_repetitions(&clean_text);
+        let report = AnalysisReport {
+            text_length,
+            index_of_coincidence: calc_ic(&clean_text, &alphabet),
+            reference_ic,
+            friedman_estimate,
+            ic_per_length: ic_per_length(&clean_text, &alphabet)
+                .into_iter()
+                .map(|(key_length, average_ic)| LengthIc { key_length, average_ic })
+                .collect(),
+            kasiski_factors: analysis::kasiski_factors(&repetitions, MAX_ALTERNATIVE_KEY_LENGTH),
+            kasiski_repetitions: repetitions,
+        };
+        report.print(format);
+        return Ok(());
+    }
+
+    if cli.running_key {
+        let model = match &cli.ngram_file {
+            Some(path) => {
+                let content = tool_error::fs::read_to_string(path)?;
+                NgramModel::parse(&content)
+                    .map_err(ToolError::data)
+                    .with_context(|| format!("invalid n-gram file '{}'", path))?
+            }
+            None => NgramModel::quadgrams(cli.language)
+                .unwrap_or_else(|| NgramModel::bigrams(cli.language)),
+        };
+        let key = running_key::attack(&clean_text, &model, &alphabet).map_err(ToolError::failed)?;
+
+        let output = cli.output.expect("clap enforces --output without --analysis-report");
+        tool_error::fs::write(&output, vigenere_decrypt(&input, &key, &alphabet))?;
+
+        if cli.json {
+            DecryptionReport {
+                mode: "running-key",
+                key_length: key.chars().count(),
+                key,
+                confidence: None,
+                output,
+                elapsed_ms: started.elapsed().as_secs_f64() * 1000.0,
+            }
+            .print();
+        } else {
+            // Plaintext and key are interchangeable, the key stream is worth a look too
+            println!("Recovered running key:");
+            println!("{}", key);
+        }
+        return Ok(());
+    }
+
+    let model = match &cli.quadgram_file {
+        Some(path) => {
+            let content = tool_error::fs::read_to_string(path)?;
+            NgramModel::parse(&content)
+                .map_err(ToolError::data)
+                .with_context(|| format!("invalid quadgram file '{}'", path))?
+        }
+        None => NgramModel::quadgrams(cli.language)
+            .unwrap_or_else(|| NgramModel::bigrams(cli.language)),
+    };
+
+    let (mode, candidates) = if cli.brute_force {
+        let keys = brute_force::search(&clean_text, &model, &alphabet, cli.max_key_length as usize, BRUTE_FORCE_CANDIDATES)
+            .map_err(ToolError::usage)?;
+        ("brute-force", score_candidates(&clean_text, keys, &model, &alphabet))
+    } else {
+        // Step 1: Find key length using Index of Coincidence
+        let key_length = find_key_length(&clean_text, reference_ic, &alphabet);
+        info!("Key length from index of coincidence: {}", key_length);
+    
+        // Step 2: Reconstruct the key using frequency analysis, together with
+        // the variants that use the second-best shift in one column
+        let mut candidates = score_candidates(
+            &clean_text,
+            key_candidates(&clean_text, key_length, &frequencies, &alphabet),
+            &model,
+            &alphabet,
+        );
+    
+        // Step 3: Validate the plaintext; retry other key lengths if it does not look like language
+        let cipher_score = model.score_per_ngram(&clean_text, &alphabet);
+        let plain_score = per_ngram(best_candidate(&candidates).score, &clean_text, model.n());
+        if !is_plausible(plain_score, cipher_score) {
+            warn!("Decryption scores poorly, trying alternative key lengths");
+            for length in alternative_key_lengths(&clean_text, &alphabet, key_length, friedman_estimate) {
+                let keys = key_candidates(&clean_text, length, &frequencies, &alphabet)
+                    .into_iter()
+                    .filter(|key| candidates.iter().all(|candidate| candidate.key != *key))
+                    .collect();
+                candidates.extend(score_candidates(&clean_text, keys, &model, &alphabet));
+            }
+        }
+        ("repeating-key", candidates)
+    };
+
+    let best = best_candidate(&candidates);
+    let key = best.key.clone();
+    let confidence = confidence(best, &candidates);
+    
+    // Step 4: Decrypt the original text
+    let decrypted_text = vigenere_decrypt(&input, &key, &alphabet);
+    
+    // Write decrypted text to file
+    let output = cli.output.expect("clap enforces --output without --analysis-report");
+    tool_error::fs::write(&output, &decrypted_text)?;
+
+    if cli.json {
+        DecryptionReport {
+            mode,
+            key_length: key.chars().count(),
+            key,
+            confidence: Some(confidence),
+            output,
+            elapsed_ms: started.elapsed().as_secs_f64() * 1000.0,
+        }
+        .print();
+        return Ok(());
+    }
+
+    println!("Found key length: {}", key.chars().count());
+    println!("Confidence: {:.1}%", confidence * 100.0);
+    
+    // Output key to stdout (as required)
+    println!("{}", key);
+    Ok(())
+}
+
+/// Finds the most likely key length using Index of Coincidence analysis
+///
+/// The thresholds scale with the expected IC of the plaintext language
+/// (for German roughly 0.07 for early termination and 0.045 for the fallback).
+fn find_key_length(text: &str, reference_ic: f64, alphabet: &Alphabet) -> usize {
+    let mut best_key_length: usize = 1;
+    let mut best_average_ic: f64 = 0.0;
+    let mut results: Vec<(usize, f64)> = Vec::new();
+    
+    // Test key lengths from 1 to MAX_KEY_LENGTH
+    for key_length in 1..=MAX_KEY_LENGTH {
+        let subtexts: Vec<String> = split_text_by_key_length(text, key_length);
+        
+        // Calculate IC for each subtext and compute average
+        let mut total_ic = 0.0;
+        let mut valid_subtexts = 0;
+        
+        for subtext in &subtexts {
+            // Only consider subtexts with sufficient length for reliable IC
+            if subtext.chars().count() >= 2 {
+                total_ic += calc_ic(subtext, alphabet);
+                valid_subtexts += 1;
+            }
+        }
+        
+        // Skip if no valid subtexts
+        if valid_subtexts == 0 {
+            continue;
+        }
+        
+        let average_ic: f64 = total_ic / valid_subtexts as f64;
+        results.push((key_length, average_ic));
+        
+        // Update best candidate if this one is better
+        if average_ic > best_average_ic {
+            best_average_ic = average_ic;
+            best_key_length = key_length;
+        }
+        
+        // Early termination if we find a very good candidate
+        if average_ic > reference_ic * EARLY_STOP_IC_RATIO && key_length <= 20 {
+            break;
+        }
+    }
+    
+    // Prefer shorter keys if IC values are close
+    let best_ic = best_average_ic;
+    for (length, ic) in &results {
+        // If a shorter key has IC within 5% of the best, prefer it
+        if *length < best_key_length && *ic >= best_ic * 0.95 && *length <= 10 {
+            return *length;
+        }
+    }
+    
+    // Fallback: if no good candidate found, try common key lengths
+    if best_average_ic < reference_ic * FALLBACK_IC_RATIO {
+        let common_lengths = [2, 3, 4, 5, 6, 7, 8, 9, 10];
+        let mut fallback_best = 1;
+        let mut fallback_best_ic = 0.0;
+        
+        for &length in &common_lengths {
+            let subtexts = split_text_by_key_length(text, length);
+            let avg_ic: f64 = subtexts.iter()
+                .map(|s| calc_ic(s, alphabet))
+                .sum::<f64>() / subtexts.len() as f64;
+                
+            if avg_ic > fallback_best_ic {
+                fallback_best_ic = avg_ic;
+                fallback_best = length;
+            }
+        }
+        
+        if fallback_best_ic > best_average_ic {
+            return fallback_best;
+        }
+    }
+    
+    best_key_length
+}
+
+/// Average column IC for every key length up to `MAX_ALTERNATIVE_KEY_LENGTH`
+fn ic_per_length(text: &str, alphabet: &Alphabet) -> Vec<(usize, f64)> {
+    (1..=MAX_ALTERNATIVE_KEY_LENGTH)
+        .map(|length| {
+            let subtexts = split_text_by_key_length(text, length);
+            let average = subtexts.iter().map(|s| calc_ic(s, alphabet)).sum::<f64>() / length as f64;
+            (length, average)
+        })
+        .collect()
+}
+
+/// Key lengths to retry after an implausible decryption: the Friedman
+/// estimate first, then the lengths with the highest average IC
+fn alternative_key_lengths(text: &str, alphabet: &Alphabet, tried: usize, friedman_estimate: Option<f64>) -> Vec<usize> {
+    let mut ranking = ic_per_length(text, alphabet);
+    ranking.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let friedman = friedman_estimate
+        .map(|estimate| estimate.round() as usize)
+        .filter(|length| (1..=MAX_ALTERNATIVE_KEY_LENGTH).contains(length));
+
+    let mut lengths: Vec<usize> = Vec::new();
+    for length in friedman.into_iter().chain(ranking.into_iter().map(|(length, _)| length)) {
+        if length != tried && !lengths.contains(&length) {
+            lengths.push(length);
+        }
+    }
+    lengths.truncate(ALTERNATIVE_KEY_LENGTHS);
+    lengths
+}
+
+/// Reconstructs the Vigenère key using frequency analysis and chi-square test.
+///
+/// Returns the most likely key for a key length first, followed by one
+/// variant per column that uses the second-best shift of that column instead.
+fn key_candidates(text: &str, key_length: usize, expected_frequencies: &[f64], alphabet: &Alphabet) -> Vec<String> {
+    let rankings: Vec<Vec<(usize, f64)>> = split_text_by_key_length(text, key_length)
+        .iter()
+        .map(|subtext| rank_shifts(&count_frequencies(subtext, alphabet), expected_frequencies))
+        .collect();
+    let best: Vec<usize> = rankings.iter().map(|ranking| ranking[0].0).collect();
+
+    let mut keys = vec![best.clone()];
+    for (column, ranking) in rankings.iter().enumerate() {
+        let mut variant = best.clone();
+        variant[column] = ranking[1].0;
+        keys.push(variant);
+    }
+
+    let mut candidates: Vec<String> = Vec::with_capacity(keys.len());
+    for shifts in keys {
+        let key = reduce_key(&shifts.into_iter().map(|shift| alphabet.symbol(shift)).collect::<String>());
+        if !candidates.contains(&key) {
+            candidates.push(key);
+        }
+    }
+    candidates
+}
+
+/// Decrypts the cleaned text with every key and scores the result
+fn score_candidates(text: &str, keys: Vec<String>, model: &NgramModel, alphabet: &Alphabet) -> Vec<Candidate> {
+    keys.into_iter()
+        .map(|key| {
+            let score = model.score(&vigenere_decrypt(text, &key, alphabet), alphabet);
+            Candidate { key, score }
+        })
+        .collect()
+}
+
+/// Returns the candidate with the highest score
+fn best_candidate(candidates: &[Candidate]) -> &Candidate {
+    candidates.iter()
+        .max_by(|a, b| a.score.total_cmp(&b.score))
+        .expect("at least one candidate")
+}
+
+/// Checks whether a decryption scores clearly better than the ciphertext itself.
+///
+/// Scores are negative log10 probabilities per n-gram, so the improvement is
+/// taken relative to the ciphertext score to work for any model size.
+fn is_plausible(plain_score: f64, cipher_score: f64) -> bool {
+    (plain_score - cipher_score) / cipher_score.abs() >= PLAUSIBLE_SCORE_GAIN
+}
+
+/// Shortens a key that repeats itself to its period, e.g. "KEYKEY" to "KEY"
+fn reduce_key(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    let period = (1..chars.len())
+        .filter(|period| chars.len().is_multiple_of(*period))
+        .find(|&period| (period..chars.len()).all(|i| chars[i] == chars[i - period]))
+        .unwrap_or(chars.len());
+    chars[..period].iter().collect()
+}
+
+/// Converts a total n-gram score into a score per n-gram
+fn per_ngram(score: f64, text: &str, n: usize) -> f64 {
+    score / text.chars().count().saturating_sub(n - 1).max(1) as f64
+}
+
+/// Estimates how certain the chosen key is compared to all other candidates.
+///
+/// Scores are log10 likelihoods, so the relative probability of a candidate
+/// is 10^(score - best score); the confidence is the share of the best one.
+fn confidence(best: &Candidate, candidates: &[Candidate]) -> f64 {
+    let total: f64 = candidates.iter()
+        .map(|candidate| 10f64.powf(candidate.score - best.score))
+        .sum();
+    1.0 / total
+}
+
+/// Splits text into subtexts based on key length
+/// Each subtext contains characters encrypted with the same key character
+fn split_text_by_key_length(text: &str, key_length: usize) -> Vec<String> {
+    let mut subtexts = vec![String::new(); key_length];
+    
+    for (i, c) in text.chars().enumerate() {
+        subtexts[i % key_length].push(c);
+    }
+    
+    subtexts
+}
+
+/// Decrypts Vigenère cipher text with given key, preserving original formatting
+/// and all characters outside the alphabet
+fn vigenere_decrypt(ciphertext: &str, key: &str, alphabet: &Alphabet) -> String {
+    let mut result: String = String::new();
+    let shifts: Vec<usize> = key.chars()
+        .map(|c| alphabet.index_of(c).expect("key is built from alphabet symbols"))
+        .collect();
+    let mut key_index: usize = 0;
+
+    // Process each character in the input content
+    for c in ciphertext.chars() {
+        if alphabet.index_of(c).is_some() {
+            // Apply Vigenère decryption: (ciphertext - key) mod alphabet length
+            result.push(alphabet.unshift(c, shifts[key_index % shifts.len()]));
+            
+            // Only advance key index for characters of the alphabet
+            key_index += 1;
+        } else {
+            // Characters outside the alphabet remain unchanged
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+/// Calculates the Index of Coincidence (IC) for the given text.
+///
+/// # Arguments
+///
+/// * `text` - The input text to analyze.
+/// * `alphabet` - The alphabet whose symbols are counted.
+///
+/// # Returns
+///
+/// The Index of Coincidence value (0.0 if text has < 2 symbols of the alphabet).
+fn calc_ic(text: &str, alphabet: &Alphabet) -> f64 {
+    index_of_coincidence(&count_frequencies(text, alphabet))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ic_calculation() {
+        let uniform_text = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let ic = calc_ic(uniform_text, &Alphabet::latin());
+        assert!(ic < 0.05); // Should be low for uniform distribution
+    }
+
+    #[test]
+    fn test_split_text() {
+        let text = "ABCDEFGH";
+        let subtexts = split_text_by_key_length(text, 3);
+        assert_eq!(subtexts[0], "ADG");
+        assert_eq!(subtexts[1], "BEH"); 
+        assert_eq!(subtexts[2], "CF");
+    }
+
+    #[test]
+    fn test_reconstruct_key_with_english_table() {
+        let alphabet = Alphabet::latin();
+        let plaintext = alphabet.normalize(
+            "It was the best of times, it was the worst of times, it was the age of wisdom, \
+             it was the age of foolishness, it was the epoch of belief, it was the epoch of \
+             incredulity, it was the season of light, it was the season of darkness",
+        );
+        let ciphertext: String = plaintext.bytes().enumerate()
+            .map(|(i, b)| ((b - b'A' + b"KEY"[i % 3] - b'A') % 26 + b'A') as char)
+            .collect();
+
+        let frequencies = Language::En.table().expected(&alphabet);
+        let key = &key_candidates(&ciphertext, 3, &frequencies, &alphabet)[0];
+        assert_eq!(key, "KEY");
+    }
+
+    #[test]
+    fn test_key_candidates_are_scored() {
+        let alphabet = Alphabet::latin();
+        let plaintext = alphabet.normalize(
+            "the quick brown fox jumps over the lazy dog while the other animals watch the scene \
+             from the river bank and nothing else happens in the quiet little village",
+        );
+        let ciphertext = vigenere_decrypt(&plaintext, "WXY", &alphabet);
+        let model = NgramModel::quadgrams(Language::En).unwrap();
+        let frequencies = Language::En.table().expected(&alphabet);
+
+        let candidates = score_candidates(
+            &ciphertext,
+            key_candidates(&ciphertext, 3, &frequencies, &alphabet),
+            &model,
+            &alphabet,
+        );
+        let best = best_candidate(&candidates);
+        assert_eq!(candidates.len(), 4);
+        assert_eq!(vigenere_decrypt(&ciphertext, &best.key, &alphabet), plaintext);
+        assert!(confidence(best, &candidates) > 0.5);
+    }
+
+    #[test]
+    fn test_alternative_key_lengths_start_with_friedman() {
+        let alphabet = Alphabet::latin();
+        let text = "QWERTZUIOPASDFGHJKLYXCVBNM".repeat(3);
+        let lengths = alternative_key_lengths(&text, &alphabet, 1, Some(7.4));
+        assert_eq!(lengths[0], 7);
+        assert_eq!(lengths.len(), ALTERNATIVE_KEY_LENGTHS);
+        assert!(!lengths.contains(&1));
+    }
+
+    #[test]
+    fn test_reduce_key() {
+        assert_eq!(reduce_key("KEYKEYKEY"), "KEY");
+        assert_eq!(reduce_key("AAAA"), "A");
+        assert_eq!(reduce_key("KEYKE"), "KEYKE");
+        assert!(is_plausible(-3.0, -3.4));
+        assert!(!is_plausible(-3.39, -3.4));
+    }
+
+    #[test]
+    fn test_confidence_of_equal_candidates() {
+        let candidates = vec![
+            Candidate { key: "A".to_string(), score: -10.0 },
+            Candidate { key: "B".to_string(), score: -10.0 },
+            Candidate { key: "C".to_string(), score: -12.0 },
+        ];
+        let confidence = confidence(&candidates[0], &candidates);
+        assert!((confidence - 1.0 / 2.01).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_decrypt() {
+        let ciphertext = "RIJVS";
+        let key = "KEY";
+        let decrypted = vigenere_decrypt(ciphertext, key, &Alphabet::latin());
+        assert_eq!(decrypted, "HELLO");
+    }
+
+    #[test]
+    fn test_decrypt_with_german_alphabet() {
+        // Key "B" shifts every symbol of A-ZÄÖÜẞ by one, Z is followed by Ä
+        let alphabet = Alphabet::parse("german").unwrap();
+        let decrypted = vigenere_decrypt("Äpomf ẞcfs!", "B", &alphabet);
+        assert_eq!(decrypted, "Zonle Über!");
+    }
+}
@@ -0,0 +1,220 @@
+//! Attack on the running-key cipher.
+//!
+//! With a running key the key is ordinary text as long as the message, so
+//! the periodicity that IC and Kasiski rely on is gone. What remains is that
+//! both the plaintext and the key are language: every ciphertext symbol is
+//! the sum of two letters that each follow the n-gram statistics. Choosing a
+//! plaintext letter fixes the key letter at the same position, so a Viterbi
+//! search over the plaintext finds the split whose two streams score best
+//! together.
+//!
+//! The two streams play symmetric roles; the search cannot tell which one
+//! was the message and may even swap them in places. Expect a partial
+//! decryption: with quadgram statistics from a matching corpus roughly 40%
+//! of the letters come out right, enough to guess words and extend them.
+
+use std::collections::HashMap;
+
+use classical::{Alphabet, NgramModel};
+
+/// Number of partial solutions kept at every position
+const BEAM_WIDTH: usize = 2000;
+
+/// Largest dense n-gram table the attack builds
+pub(crate) const MAX_TABLE_SIZE: usize = 1 << 20;
+
+/// Splits a normalized ciphertext into plaintext and key stream and returns
+/// the key stream; decrypting with it gives the plaintext.
+///
+/// The score of a split is the sum of the conditional n-gram log
+/// probabilities of both streams. Partial splits that end in the same
+/// `n - 1` plaintext symbols are merged (Viterbi), and only the best
+/// `BEAM_WIDTH` of them are extended at every position.
+pub fn attack(ciphertext: &str, model: &NgramModel, alphabet: &Alphabet) -> Result<String, String> {
+    let n = model.n();
+    let size = alphabet.len();
+    if n < 2 {
+        return Err("Running-key attack needs n-grams of at least two symbols".to_string());
+    }
+    if size.checked_pow(n as u32).is_none_or(|count| count > MAX_TABLE_SIZE) {
+        return Err(format!("{}-grams over {} symbols are too many for the running-key attack", n, size));
+    }
+
+    let cipher: Vec<usize> = ciphertext.chars()
+        .map(|c| alphabet.index_of(c).expect("ciphertext is normalized"))
+        .collect();
+    if cipher.len() < n {
+        return Err(format!("Ciphertext needs at least {} symbols", n));
+    }
+
+    let table = ngram_table(model, alphabet);
+    let key_of = |position: usize, plain: usize| (cipher[position] + size - plain) % size;
+
+    // A hypothesis remembers the last n - 1 plaintext symbols, the oldest one
+    // as the most significant digit, and where it came from
+    let order = n - 1;
+    let states = size.pow(order as u32);
+    let mut beam: Vec<Hypothesis> = (0..states)
+        .map(|state| Hypothesis { state, score: 0.0, parent: 0 })
+        .collect();
+    let mut history: Vec<Vec<Hypothesis>> = Vec::with_capacity(cipher.len() - order);
+    let mut best_in_state: HashMap<usize, usize> = HashMap::new();
+
+    for position in order..cipher.len() {
+        best_in_state.clear();
+        let mut next: Vec<Hypothesis> = Vec::with_capacity(beam.len() * size);
+
+        for (parent, hypothesis) in beam.iter().enumerate() {
+            let key_history = digits(hypothesis.state, order, size).iter()
+                .enumerate()
+                .fold(0, |index, (offset, &plain)| index * size + key_of(position - order + offset, plain));
+
+            for plain in 0..size {
+                let window = hypothesis.state * size + plain;
+                let score = hypothesis.score + table[window] + table[key_history * size + key_of(position, plain)];
+                let candidate = Hypothesis { state: window % states, score, parent };
+
+                match best_in_state.get(&candidate.state) {
+                    Some(&index) if next[index].score >= score => {}
+                    Some(&index) => next[index] = candidate,
+                    None => {
+                        best_in_state.insert(candidate.state, next.len());
+                        next.push(candidate);
+                    }
+                }
+            }
+        }
+
+        if next.len() > BEAM_WIDTH {
+            next.select_nth_unstable_by(BEAM_WIDTH, |a, b| b.score.total_cmp(&a.score));
+            next.truncate(BEAM_WIDTH);
+        }
+        history.push(beam);
+        beam = next;
+    }
+
+    // Follow the parents back from the best final hypothesis
+    let mut index = (0..beam.len())
+        .max_by(|&a, &b| beam[a].score.total_cmp(&beam[b].score))
+        .expect("beam is never empty");
+    let mut current = &beam;
+    let mut plain = vec![0; cipher.len()];
+    for (position, previous) in (order..cipher.len()).zip(&history).rev() {
+        plain[position] = current[index].state % size;
+        index = current[index].parent;
+        current = previous;
+    }
+    plain[..order].copy_from_slice(&digits(current[index].state, order, size));
+
+    Ok(plain.iter().enumerate().map(|(position, &p)| alphabet.symbol(key_of(position, p))).collect())
+}
+
+/// Partial split of the ciphertext, see `attack`
+struct Hypothesis {
+    state: usize,
+    score: f64,
+    parent: usize,
+}
+
+/// Score of every n-gram as given by the model, indexed by its symbols in
+/// base `alphabet.len()`.
+pub(crate) fn ngram_scores(model: &NgramModel, alphabet: &Alphabet) -> Vec<f64> {
+    let n = model.n();
+    let mut ngram = String::with_capacity(n);
+    (0..alphabet.len().pow(n as u32))
+        .map(|index| {
+            ngram.clear();
+            ngram.extend(digits(index, n, alphabet.len()).into_iter().map(|d| alphabet.symbol(d)));
+            model.score(&ngram, alphabet)
+        })
+        .collect()
+}
+
+/// Log probability of every n-gram given its first `n - 1` symbols, indexed
+/// like `ngram_scores`.
+fn ngram_table(model: &NgramModel, alphabet: &Alphabet) -> Vec<f64> {
+    ngram_scores(model, alphabet)
+        .chunks(alphabet.len())
+        .flat_map(|history| {
+            let total = history.iter().map(|p| 10f64.powf(*p)).sum::<f64>().log10();
+            history.iter().map(move |p| p - total)
+        })
+        .collect()
+}
+
+/// Splits an index into `count` digits of base `size`, most significant first.
+fn digits(mut index: usize, count: usize, size: usize) -> Vec<usize> {
+    let mut digits = vec![0; count];
+    for digit in digits.iter_mut().rev() {
+        *digit = index % size;
+        index /= size;
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAINTEXT: &str = "the enemy will attack the northern bridge at dawn so move all \
+        reserves to the river and hold the line until the relief arrives from the south";
+
+    const KEY: &str = "it was the best of times it was the worst of times it was the age of wisdom \
+        it was the age of foolishness it was the epoch of belief it was the epoch of incredulity";
+
+    /// Trigram model built from both texts
+    fn trigrams(alphabet: &Alphabet) -> NgramModel {
+        let text: Vec<char> = alphabet.normalize(&format!("{} {}", PLAINTEXT, KEY)).chars().collect();
+        let mut counts = std::collections::HashMap::new();
+        for window in text.windows(3) {
+            *counts.entry(window.iter().collect::<String>()).or_insert(0.0) += 1.0;
+        }
+        NgramModel::from_counts(counts.into_iter().collect()).unwrap()
+    }
+
+    #[test]
+    fn test_attack_separates_streams() {
+        let alphabet = Alphabet::latin();
+        let model = trigrams(&alphabet);
+        let plaintext = alphabet.normalize(PLAINTEXT);
+        let ciphertext: String = plaintext.chars()
+            .zip(alphabet.normalize(KEY).chars())
+            .map(|(p, k)| alphabet.shift(p, alphabet.index_of(k).unwrap()))
+            .collect();
+
+        let recovered_key = attack(&ciphertext, &model, &alphabet).unwrap();
+        let recovered_plaintext: String = ciphertext.chars()
+            .zip(recovered_key.chars())
+            .map(|(c, k)| alphabet.unshift(c, alphabet.index_of(k).unwrap()))
+            .collect();
+
+        // The streams may be swapped in places, so count a symbol as recovered
+        // if it appears in either stream
+        let key = alphabet.normalize(KEY);
+        let recovered = plaintext.chars()
+            .zip(key.chars())
+            .zip(recovered_plaintext.chars().zip(recovered_key.chars()))
+            .filter(|((p, k), (a, b))| (p == a && k == b) || (p == b && k == a))
+            .count();
+        assert!(recovered * 10 >= plaintext.len() * 8, "only {} of {} positions recovered", recovered, plaintext.len());
+    }
+
+    #[test]
+    fn test_attack_rejects_unsupported_models() {
+        let alphabet = Alphabet::latin();
+        let unigrams = NgramModel::from_counts(vec![("E".to_string(), 1.0)]).unwrap();
+        assert!(attack("ABCDEFGH", &unigrams, &alphabet).is_err());
+
+        let pentagrams = NgramModel::from_counts(vec![("THERE".to_string(), 1.0)]).unwrap();
+        assert!(attack("ABCDEFGH", &pentagrams, &alphabet).is_err());
+
+        let bigrams = NgramModel::from_counts(vec![("TH".to_string(), 1.0)]).unwrap();
+        assert!(attack("A", &bigrams, &alphabet).is_err());
+    }
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(digits(27, 2, 26), vec![1, 1]);
+        assert_eq!(digits(5, 3, 10), vec![0, 0, 5]);
+    }
+}
@@ -0,0 +1,456 @@
+//! Zertifikate im X.509-v3-Format, beschränkt auf das Nötigste
+//!
+//! ```text
+//! Certificate ::= SEQUENCE {
+//!     tbsCertificate      SEQUENCE {
+//!         version         [0] INTEGER 2,
+//!         serialNumber    INTEGER,
+//!         signature       AlgorithmIdentifier,
+//!         issuer          Name,
+//!         validity        SEQUENCE { notBefore Time, notAfter Time },
+//!         subject         Name,
+//!         subjectPublicKeyInfo,
+//!         extensions      [3] SEQUENCE { basicConstraints }
+//!     },
+//!     signatureAlgorithm  AlgorithmIdentifier,
+//!     signatureValue      BIT STRING
+//! }
+//! ```
+//!
+//! Namen bestehen nur aus einem Common Name. RSA signiert mit PKCS #1 v1.5
+//! und SHA-256, DSA mit SHA-224 wie `dsa_sign`. Weil `dsa_sign` den Hash
+//! modulo q reduziert statt ihn wie FIPS 186 auf die Bitlänge von q zu
+//! kürzen, lehnen andere Implementierungen DSA-Signaturen mit q < 224 Bit ab.
+
+use asn1::{DerError, Reader};
+use key_formats::{DsaParameters, DsaPrivateKey, DsaPublicKey, DsaSignature, RsaPrivateKey, RsaPublicKey};
+use num_bigint::BigUint;
+use rand::{CryptoRng, Rng};
+use std::fmt;
+
+const BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+const END: &str = "-----END CERTIFICATE-----";
+const LINE_WIDTH: usize = 64;
+
+const RSA_ENCRYPTION: &[u64] = &[1, 2, 840, 113549, 1, 1, 1];
+const SHA256_WITH_RSA: &[u64] = &[1, 2, 840, 113549, 1, 1, 11];
+const DSA: &[u64] = &[1, 2, 840, 10040, 4, 1];
+const DSA_WITH_SHA224: &[u64] = &[2, 16, 840, 1, 101, 3, 4, 3, 1];
+const COMMON_NAME: &[u64] = &[2, 5, 4, 3];
+const BASIC_CONSTRAINTS: &[u64] = &[2, 5, 29, 19];
+
+/// Signaturverfahren bzw. Art des öffentlichen Schlüssels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Method {
+    Dsa,
+    /// RSA mit PKCS #1 v1.5 und SHA-256, Modul mindestens 496 Bit
+    Rsa,
+}
+
+impl Method {
+    fn algorithm_identifier(self) -> Vec<u8> {
+        match self {
+            Method::Rsa => asn1::sequence(&[asn1::oid(SHA256_WITH_RSA), asn1::null()]),
+            Method::Dsa => asn1::sequence(&[asn1::oid(DSA_WITH_SHA224)]),
+        }
+    }
+
+    fn read_algorithm_identifier(reader: &mut Reader) -> Result<Self, CertificateError> {
+        let mut algorithm = reader.sequence()?;
+        let method = match algorithm.oid()?.as_slice() {
+            SHA256_WITH_RSA => {
+                algorithm.null()?;
+                Method::Rsa
+            }
+            DSA_WITH_SHA224 => Method::Dsa,
+            _ => return Err(CertificateError::Unsupported("Signaturverfahren")),
+        };
+        algorithm.finish()?;
+        Ok(method)
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Method::Dsa => write!(f, "DSA mit SHA-224"),
+            Method::Rsa => write!(f, "RSA PKCS #1 v1.5 mit SHA-256"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CertificateError {
+    Der(DerError),
+    Pem(String),
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for CertificateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CertificateError::Der(e) => write!(f, "Zertifikat ist kein gültiges DER: {}", e),
+            CertificateError::Pem(e) => write!(f, "{}", e),
+            CertificateError::Unsupported(what) => write!(f, "Nicht unterstützt: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for CertificateError {}
+
+impl From<DerError> for CertificateError {
+    fn from(error: DerError) -> Self {
+        CertificateError::Der(error)
+    }
+}
+
+/// Öffentlicher Schlüssel des Inhabers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PublicKey {
+    Dsa(DsaPublicKey),
+    Rsa(RsaPublicKey),
+}
+
+impl PublicKey {
+    pub fn method(&self) -> Method {
+        match self {
+            PublicKey::Dsa(_) => Method::Dsa,
+            PublicKey::Rsa(_) => Method::Rsa,
+        }
+    }
+
+    pub fn bits(&self) -> u64 {
+        match self {
+            PublicKey::Dsa(key) => key.params.p.bits(),
+            PublicKey::Rsa(key) => key.n.bits(),
+        }
+    }
+
+    /// SubjectPublicKeyInfo nach RFC 3279
+    fn to_der(&self) -> Vec<u8> {
+        let (algorithm, key) = match self {
+            PublicKey::Rsa(key) => (
+                asn1::sequence(&[asn1::oid(RSA_ENCRYPTION), asn1::null()]),
+                asn1::sequence(&[asn1::integer(&key.n), asn1::integer(&key.e)]),
+            ),
+            PublicKey::Dsa(key) => {
+                let params = asn1::sequence(&[asn1::integer(&key.params.p), asn1::integer(&key.params.q), asn1::integer(&key.params.g)]);
+                (asn1::sequence(&[asn1::oid(DSA), params]), asn1::integer(&key.y))
+            }
+        };
+        asn1::sequence(&[algorithm, asn1::bit_string(&key)])
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, CertificateError> {
+        let mut info = reader.sequence()?;
+        let mut algorithm = info.sequence()?;
+        let algorithm_oid = algorithm.oid()?;
+        let mut key = Reader::new(info.bit_string()?);
+        info.finish()?;
+
+        let public_key = match algorithm_oid.as_slice() {
+            RSA_ENCRYPTION => {
+                algorithm.null()?;
+                let mut numbers = key.sequence()?;
+                let n = numbers.integer()?;
+                let e = numbers.integer()?;
+                numbers.finish()?;
+                PublicKey::Rsa(RsaPublicKey { e, n })
+            }
+            DSA => {
+                let mut params = algorithm.sequence()?;
+                let (p, q, g) = (params.integer()?, params.integer()?, params.integer()?);
+                params.finish()?;
+                PublicKey::Dsa(DsaPublicKey { params: DsaParameters { p, q, g }, y: key.integer()? })
+            }
+            _ => return Err(CertificateError::Unsupported("Schlüsselart")),
+        };
+        algorithm.finish()?;
+        key.finish()?;
+        Ok(public_key)
+    }
+
+    /// Prüft eine Signatur im Format des Zertifikats
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            PublicKey::Rsa(key) => rsa::pkcs1::verify(message, signature, key),
+            PublicKey::Dsa(key) => DsaSignature::from_der(signature)
+                .is_ok_and(|signature| dsa_verify::dsa_verify(message, &signature, &key.params, &key.y).unwrap_or(false)),
+        }
+    }
+}
+
+/// Privater Schlüssel des Ausstellers
+pub enum PrivateKey {
+    Dsa(DsaPrivateKey),
+    Rsa(RsaPrivateKey),
+}
+
+impl PrivateKey {
+    pub fn method(&self) -> Method {
+        match self {
+            PrivateKey::Dsa(_) => Method::Dsa,
+            PrivateKey::Rsa(_) => Method::Rsa,
+        }
+    }
+
+    pub fn sign(&self, message: &[u8], rng: &mut (impl Rng + CryptoRng)) -> Result<Vec<u8>, String> {
+        match self {
+            PrivateKey::Rsa(key) => rsa::pkcs1::sign(message, key).ok_or_else(|| "RSA-Modul ist zu kurz für PKCS #1 mit SHA-256".to_string()),
+            PrivateKey::Dsa(key) => {
+                let signature = dsa_sign::dsa_sign(message, &key.params, &key.x, rng).map_err(|e| e.to_string())?;
+                Ok(signature.to_der())
+            }
+        }
+    }
+}
+
+/// Der signierte Teil des Zertifikats
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TbsCertificate {
+    pub serial: BigUint,
+    pub issuer: String,
+    pub subject: String,
+    /// Gültigkeit in Sekunden seit 1970, beide Grenzen eingeschlossen
+    pub not_before: u64,
+    pub not_after: u64,
+    pub public_key: PublicKey,
+    /// Darf weitere Zertifikate ausstellen (basicConstraints cA)
+    pub ca: bool,
+}
+
+fn name(common_name: &str) -> Vec<u8> {
+    let attribute = asn1::sequence(&[asn1::oid(COMMON_NAME), asn1::utf8_string(common_name)]);
+    asn1::sequence(&[asn1::constructed(asn1::SET, &[attribute])])
+}
+
+fn read_name(reader: &mut Reader) -> Result<String, CertificateError> {
+    let unsupported = CertificateError::Unsupported("Namen mit anderen Attributen als genau einem CN");
+    let mut name = reader.sequence()?;
+    let mut set = name.constructed(asn1::SET)?;
+    let mut attribute = set.sequence()?;
+    if attribute.oid()? != COMMON_NAME {
+        return Err(unsupported);
+    }
+    let common_name = attribute.utf8_string()?.to_string();
+    if !(attribute.is_empty() && set.is_empty() && name.is_empty()) {
+        return Err(unsupported);
+    }
+    Ok(common_name)
+}
+
+impl TbsCertificate {
+    /// DER-Kodierung mit dem Signaturverfahren des Ausstellers
+    pub fn to_der(&self, method: Method) -> Vec<u8> {
+        // Der Standardwert cA = FALSE wird in DER weggelassen
+        let constraints = if self.ca { asn1::sequence(&[asn1::boolean(true)]) } else { asn1::sequence(&[]) };
+        let extension = asn1::sequence(&[asn1::oid(BASIC_CONSTRAINTS), asn1::boolean(true), asn1::octet_string(&constraints)]);
+
+        asn1::sequence(&[
+            asn1::constructed(asn1::context(0), &[asn1::integer(&BigUint::from(2u32))]),
+            asn1::integer(&self.serial),
+            method.algorithm_identifier(),
+            name(&self.issuer),
+            asn1::sequence(&[asn1::time(self.not_before), asn1::time(self.not_after)]),
+            name(&self.subject),
+            self.public_key.to_der(),
+            asn1::constructed(asn1::context(3), &[asn1::sequence(&[extension])]),
+        ])
+    }
+
+    fn read(reader: &mut Reader) -> Result<(Self, Method), CertificateError> {
+        let mut tbs = reader.sequence()?;
+        let mut version = tbs.constructed(asn1::context(0))?;
+        if version.integer()? != BigUint::from(2u32) {
+            return Err(CertificateError::Unsupported("andere Versionen als X.509 v3"));
+        }
+        version.finish()?;
+
+        let serial = tbs.integer()?;
+        let method = Method::read_algorithm_identifier(&mut tbs)?;
+        let issuer = read_name(&mut tbs)?;
+        let mut validity = tbs.sequence()?;
+        let (not_before, not_after) = (validity.time()?, validity.time()?);
+        validity.finish()?;
+        let subject = read_name(&mut tbs)?;
+        let public_key = PublicKey::read(&mut tbs)?;
+
+        let mut ca = false;
+        if let Some(mut extensions) = tbs.optional(asn1::context(3))? {
+            let mut list = extensions.sequence()?;
+            extensions.finish()?;
+            while !list.is_empty() {
+                let mut extension = list.sequence()?;
+                let id = extension.oid()?;
+                let critical = if extension.peek_tag() == Some(asn1::BOOLEAN) { extension.boolean()? } else { false };
+                let value = extension.octet_string()?;
+                extension.finish()?;
+
+                if id == BASIC_CONSTRAINTS {
+                    let mut outer = Reader::new(value);
+                    let mut constraints = outer.sequence()?;
+                    outer.finish()?;
+                    if !constraints.is_empty() {
+                        ca = constraints.boolean()?;
+                    }
+                } else if critical {
+                    // RFC 5280: unbekannte kritische Erweiterungen machen das Zertifikat unbrauchbar
+                    return Err(CertificateError::Unsupported("kritische Erweiterung"));
+                }
+            }
+        }
+        tbs.finish()?;
+
+        Ok((TbsCertificate { serial, issuer, subject, not_before, not_after, public_key, ca }, method))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    pub tbs: TbsCertificate,
+    pub method: Method,
+    /// Die signierten Bytes, so wie sie im Zertifikat stehen
+    tbs_der: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl Certificate {
+    pub fn sign(tbs: TbsCertificate, key: &PrivateKey, rng: &mut (impl Rng + CryptoRng)) -> Result<Self, String> {
+        let method = key.method();
+        let tbs_der = tbs.to_der(method);
+        let signature = key.sign(&tbs_der, rng)?;
+        Ok(Certificate { tbs, method, tbs_der, signature })
+    }
+
+    pub fn is_self_issued(&self) -> bool {
+        self.tbs.issuer == self.tbs.subject
+    }
+
+    /// Prüft die Signatur mit dem Schlüssel des Ausstellers
+    pub fn verify_signature(&self, issuer: &PublicKey) -> bool {
+        issuer.method() == self.method && issuer.verify(&self.tbs_der, &self.signature)
+    }
+
+    pub fn is_valid_at(&self, unix: u64) -> bool {
+        (self.tbs.not_before..=self.tbs.not_after).contains(&unix)
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        asn1::sequence(&[self.tbs_der.clone(), self.method.algorithm_identifier(), asn1::bit_string(&self.signature)])
+    }
+
+    pub fn from_der(bytes: &[u8]) -> Result<Self, CertificateError> {
+        let mut outer = Reader::new(bytes);
+        let mut certificate = outer.sequence()?;
+        outer.finish()?;
+
+        let tbs_der = certificate.raw(asn1::SEQUENCE)?.to_vec();
+        let (tbs, method) = TbsCertificate::read(&mut Reader::new(&tbs_der))?;
+        if Method::read_algorithm_identifier(&mut certificate)? != method {
+            return Err(CertificateError::Unsupported("abweichende Signaturverfahren innerhalb des Zertifikats"));
+        }
+        let signature = certificate.bit_string()?.to_vec();
+        certificate.finish()?;
+        Ok(Certificate { tbs, method, tbs_der, signature })
+    }
+
+    pub fn to_pem(&self) -> String {
+        let encoded = codec::base64::encode(&self.to_der());
+        let mut text = format!("{}\n", BEGIN);
+        for line in encoded.as_bytes().chunks(LINE_WIDTH) {
+            text.push_str(std::str::from_utf8(line).expect("Base64 ist ASCII"));
+            text.push('\n');
+        }
+        text.push_str(END);
+        text.push('\n');
+        text
+    }
+
+    pub fn from_pem(text: &str) -> Result<Self, CertificateError> {
+        let body = text.trim()
+            .strip_prefix(BEGIN)
+            .and_then(|rest| rest.strip_suffix(END))
+            .ok_or_else(|| CertificateError::Pem(format!("Zertifikat muss mit '{}' beginnen und mit '{}' enden", BEGIN, END)))?;
+        let bytes = codec::base64::decode(body).map_err(|e| CertificateError::Pem(e.to_string()))?;
+        Self::from_der(&bytes)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crypto_math::mod_pow;
+    use csprng::{Algorithm, Drbg};
+    use secret::Secret;
+
+    /// RSA-Schlüssel mit 576 Bit, gerade groß genug für PKCS #1 mit SHA-256
+    pub(crate) fn rsa_keys() -> (PrivateKey, PublicKey) {
+        let n: BigUint = "191677726143854315674598428926330859363608329588293420380333240985455824521179813035337110510248831071087008746466405096838035678566522676019644319734501401554761112169596389".parse().unwrap();
+        let d: BigUint = "190852953591363861167675061104226928723808864416804051862591294085873931201147271001432259701997425425908853217310742424346992958524465651074542509542066127393363380985475733".parse().unwrap();
+        (PrivateKey::Rsa(RsaPrivateKey { d: Secret::new(d), n: n.clone() }), PublicKey::Rsa(RsaPublicKey { e: BigUint::from(65537u32), n }))
+    }
+
+    pub(crate) fn dsa_keys() -> (PrivateKey, PublicKey) {
+        let params = DsaParameters { p: 283u32.into(), q: 47u32.into(), g: 64u32.into() };
+        let y = mod_pow(&params.g, &BigUint::from(24u32), &params.p);
+        (
+            PrivateKey::Dsa(DsaPrivateKey { params: params.clone(), x: Secret::new(24u32.into()) }),
+            PublicKey::Dsa(DsaPublicKey { params, y }),
+        )
+    }
+
+    pub(crate) fn tbs(subject: &str, issuer: &str, public_key: PublicKey, ca: bool) -> TbsCertificate {
+        TbsCertificate {
+            serial: BigUint::from(0x1234_5678u32),
+            issuer: issuer.to_string(),
+            subject: subject.to_string(),
+            not_before: 1_700_000_000,
+            not_after: 1_800_000_000,
+            public_key,
+            ca,
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"x509");
+        for (private, public) in [rsa_keys(), dsa_keys()] {
+            for ca in [false, true] {
+                let certificate = Certificate::sign(tbs("Wurzel", "Wurzel", public.clone(), ca), &private, &mut rng).unwrap();
+                assert!(certificate.verify_signature(&public));
+
+                let decoded = Certificate::from_der(&certificate.to_der()).unwrap();
+                assert_eq!(decoded, certificate);
+                assert_eq!(Certificate::from_pem(&certificate.to_pem()).unwrap(), certificate);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rsa_signature_covers_fields() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"x509");
+        let (private, public) = rsa_keys();
+        let certificate = Certificate::sign(tbs("Wurzel", "Wurzel", public.clone(), false), &private, &mut rng).unwrap();
+        let mut der = certificate.to_der();
+        // Ein Zeichen im Namen ändern: DER bleibt gültig, die Signatur nicht
+        let position = der.windows(6).position(|window| window == b"Wurzel").unwrap();
+        der[position] = b'V';
+        let forged = Certificate::from_der(&der).unwrap();
+        assert_eq!(forged.tbs.issuer, "Vurzel");
+        assert!(!forged.verify_signature(&public));
+
+        let (_, dsa_public) = dsa_keys();
+        assert!(!certificate.verify_signature(&dsa_public));
+    }
+
+    #[test]
+    fn test_malformed() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"x509");
+        let (private, public) = rsa_keys();
+        let der = Certificate::sign(tbs("Wurzel", "Wurzel", public, false), &private, &mut rng).unwrap().to_der();
+        assert!(Certificate::from_der(&der[..der.len() - 1]).is_err());
+        assert!(Certificate::from_der(&[der.as_slice(), &[0]].concat()).is_err());
+        assert!(Certificate::from_pem("-----BEGIN CERTIFICATE-----\nMAA=\n-----END CERTIFICATE-----").is_err());
+        assert!(Certificate::from_pem("MAA=").is_err());
+    }
+}
@@ -0,0 +1,320 @@
+//! x509-lite: Zertifikate erstellen und Ketten prüfen
+//!
+//! Ein Zertifikat bindet einen Namen an einen öffentlichen Schlüssel und ist
+//! vom Aussteller signiert. `create` erstellt selbstsignierte
+//! Wurzelzertifikate oder, mit `--issuer`, von einer Wurzel ausgestellte
+//! Zertifikate; `verify` prüft Ketten der Länge 1 (das Wurzelzertifikat
+//! selbst) oder 2 (Wurzel und ein ausgestelltes Zertifikat).
+//!
+//! Die Kodierung ist echtes DER nach RFC 5280, aber stark beschnitten: Namen
+//! nur mit CN, als Erweiterung nur basicConstraints, keine Sperrlisten.
+
+mod certificate;
+
+pub use certificate::{Certificate, CertificateError, Method, PrivateKey, PublicKey, TbsCertificate};
+
+use clap::{Parser, Subcommand};
+use csprng::RngArgs;
+use key_formats::{DsaPrivateKey, DsaPublicKey, KeyFile, RsaPrivateKey, RsaPublicKey};
+use logging::{Verbosity, info};
+use num_bigint::BigUint;
+use rand::Rng;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tool_error::{Context, ExitStatus, ToolError};
+
+/// Grund, warum eine Kette nicht gültig ist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainError {
+    RootNotSelfSigned,
+    RootSignature,
+    NotYetValid { subject: String },
+    Expired { subject: String },
+    IssuerMismatch { issuer: String, root: String },
+    NotCa { subject: String },
+    Signature { subject: String },
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChainError::RootNotSelfSigned => write!(f, "Vertrauensanker ist nicht selbstsigniert"),
+            ChainError::RootSignature => write!(f, "Signatur des Vertrauensankers ist ungültig"),
+            ChainError::NotYetValid { subject } => write!(f, "Zertifikat '{}' ist noch nicht gültig", subject),
+            ChainError::Expired { subject } => write!(f, "Zertifikat '{}' ist abgelaufen", subject),
+            ChainError::IssuerMismatch { issuer, root } => write!(f, "Aussteller '{}' ist nicht der Vertrauensanker '{}'", issuer, root),
+            ChainError::NotCa { subject } => write!(f, "'{}' darf keine Zertifikate ausstellen (kein cA)", subject),
+            ChainError::Signature { subject } => write!(f, "Signatur von '{}' ist ungültig", subject),
+        }
+    }
+}
+
+impl std::error::Error for ChainError {}
+
+fn check_validity(certificate: &Certificate, now: u64) -> Result<(), ChainError> {
+    let subject = certificate.tbs.subject.clone();
+    if now < certificate.tbs.not_before {
+        Err(ChainError::NotYetValid { subject })
+    } else if now > certificate.tbs.not_after {
+        Err(ChainError::Expired { subject })
+    } else {
+        Ok(())
+    }
+}
+
+/// Prüft `certificate` gegen den Vertrauensanker `root` und gibt die Länge der Kette zurück
+///
+/// Ist `certificate` der Anker selbst, hat die Kette die Länge 1, sonst muss
+/// der Anker es ausgestellt haben (Länge 2). Zwischenzertifikate gibt es nicht.
+pub fn verify_chain(certificate: &Certificate, root: &Certificate, now: u64) -> Result<usize, ChainError> {
+    if !root.is_self_issued() {
+        return Err(ChainError::RootNotSelfSigned);
+    }
+    if !root.verify_signature(&root.tbs.public_key) {
+        return Err(ChainError::RootSignature);
+    }
+    check_validity(root, now)?;
+    if certificate == root {
+        return Ok(1);
+    }
+
+    if certificate.tbs.issuer != root.tbs.subject {
+        return Err(ChainError::IssuerMismatch { issuer: certificate.tbs.issuer.clone(), root: root.tbs.subject.clone() });
+    }
+    if !root.tbs.ca {
+        return Err(ChainError::NotCa { subject: root.tbs.subject.clone() });
+    }
+    if !certificate.verify_signature(&root.tbs.public_key) {
+        return Err(ChainError::Signature { subject: certificate.tbs.subject.clone() });
+    }
+    check_validity(certificate, now)?;
+    Ok(2)
+}
+
+#[derive(Parser)]
+#[command(name = "x509-lite")]
+#[command(about = "Minimale X.509-Zertifikate mit RSA- oder DSA-Signatur erstellen und prüfen")]
+#[command(version = "1.0")]
+#[command(after_help = "Beispiel:\n  x509-lite create -s Wurzel -m rsa -p rsa_oeffentlich.txt -k rsa_privat.txt --ca -o wurzel.pem\n  \
+x509-lite create -s Server -m dsa -p dsa_oeffentlich.txt -k rsa_privat.txt --issuer wurzel.pem -o server.pem\n  \
+x509-lite verify server.pem --trust wurzel.pem\n\
+Exit-Codes beim Prüfen: 0 gültig, 1 ungültig, 65 Zertifikat nicht lesbar")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+
+    #[command(flatten)]
+    verbosity: Verbosity,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Zertifikat erstellen, ohne --issuer selbstsigniert
+    Create {
+        /// Common Name des Inhabers
+        #[arg(short, long, help = "Name des Inhabers (CN)")]
+        subject: String,
+
+        /// Öffentlicher Schlüssel des Inhabers
+        #[arg(short, long, help = "Öffentlicher Schlüssel des Inhabers")]
+        public_key: String,
+
+        /// Art des öffentlichen Schlüssels
+        #[arg(short, long, value_enum, default_value_t = Method::Rsa)]
+        method: Method,
+
+        /// Privater Schlüssel des Ausstellers
+        #[arg(short, long, help = "Privater Schlüssel des Ausstellers (selbstsigniert: des Inhabers)")]
+        key: String,
+
+        /// Zertifikat des Ausstellers
+        #[arg(long, help = "Zertifikat des Ausstellers; fehlt es, wird selbstsigniert")]
+        issuer: Option<String>,
+
+        /// Inhaber darf selbst Zertifikate ausstellen
+        #[arg(long, help = "Als Zertifizierungsstelle kennzeichnen (basicConstraints cA)")]
+        ca: bool,
+
+        /// Gültigkeitsdauer in Tagen ab jetzt
+        #[arg(short, long, default_value_t = 365, value_parser = clap::value_parser!(u64).range(1..=36500), help = "Gültigkeit in Tagen ab jetzt")]
+        days: u64,
+
+        /// Seriennummer, Standard: zufällig
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..), help = "Seriennummer (Standard: zufällig)")]
+        serial: Option<u64>,
+
+        /// Ausgabedatei
+        #[arg(short, long, help = "Ausgabedatei (Standard: STDOUT)")]
+        output: Option<String>,
+
+        #[command(flatten)]
+        rng: RngArgs,
+    },
+    /// Kette aus Zertifikat und Vertrauensanker prüfen
+    Verify {
+        /// Zu prüfendes Zertifikat
+        certificate: String,
+
+        /// Selbstsigniertes Wurzelzertifikat, dem vertraut wird
+        #[arg(short, long, help = "Vertrauensanker (Standard: das Zertifikat selbst)")]
+        trust: Option<String>,
+
+        /// Zeitpunkt der Prüfung (Standard: jetzt)
+        #[arg(long, help = "Unix-Zeit statt der aktuellen Uhrzeit")]
+        time: Option<u64>,
+    },
+    /// Felder eines Zertifikats ausgeben
+    Show {
+        certificate: String,
+    },
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("Systemuhr nach 1970").as_secs()
+}
+
+fn read_certificate(path: &str) -> tool_error::Result<Certificate> {
+    Certificate::from_pem(&tool_error::fs::read_to_string(path)?)
+        .map_err(|e| ToolError::data(format!("'{}': {}", path, e)))
+}
+
+fn read_public_key(method: Method, path: &str) -> tool_error::Result<PublicKey> {
+    let context = || format!("Kann öffentlichen Schlüssel '{}' nicht lesen", path);
+    Ok(match method {
+        Method::Dsa => PublicKey::Dsa(DsaPublicKey::read(path).with_context(context)?),
+        Method::Rsa => PublicKey::Rsa(RsaPublicKey::read(path).with_context(context)?),
+    })
+}
+
+fn read_private_key(method: Method, path: &str) -> tool_error::Result<PrivateKey> {
+    let context = || format!("Kann privaten Schlüssel '{}' nicht lesen", path);
+    Ok(match method {
+        Method::Dsa => PrivateKey::Dsa(DsaPrivateKey::read(path).with_context(context)?),
+        Method::Rsa => PrivateKey::Rsa(RsaPrivateKey::read(path).with_context(context)?),
+    })
+}
+
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let args = Args::parse_from(args);
+    args.verbosity.init();
+
+    match args.command {
+        Command::Create { subject, public_key, method, key, issuer, ca, days, serial, output, rng } => {
+            let mut rng = rng.rng();
+            let public_key = read_public_key(method, &public_key)?;
+            let (issuer_name, issuer_key) = match issuer {
+                Some(path) => {
+                    let issuer = read_certificate(&path)?;
+                    if !issuer.tbs.ca {
+                        return Err(ToolError::usage(format!("'{}' ist keine Zertifizierungsstelle (mit --ca erstellen)", issuer.tbs.subject)));
+                    }
+                    (issuer.tbs.subject, issuer.tbs.public_key)
+                }
+                None => (subject.clone(), public_key.clone()),
+            };
+            let private_key = read_private_key(issuer_key.method(), &key)?;
+
+            let not_before = now();
+            let tbs = TbsCertificate {
+                serial: BigUint::from(serial.unwrap_or_else(|| rng.gen_range(1..=u64::MAX >> 1))),
+                issuer: issuer_name,
+                subject,
+                not_before,
+                not_after: not_before + days * 86400,
+                public_key,
+                ca,
+            };
+            let certificate = Certificate::sign(tbs, &private_key, &mut rng).map_err(ToolError::usage)?;
+            if !certificate.verify_signature(&issuer_key) {
+                return Err(ToolError::usage(format!("Privater Schlüssel '{}' gehört nicht zum Aussteller '{}'", key, certificate.tbs.issuer)));
+            }
+            info!("Zertifikat für '{}', ausgestellt von '{}', gültig bis {}", certificate.tbs.subject, certificate.tbs.issuer, asn1::format_time(certificate.tbs.not_after));
+
+            match output {
+                Some(path) => tool_error::fs::write(&path, certificate.to_pem()),
+                None => {
+                    print!("{}", certificate.to_pem());
+                    Ok(())
+                }
+            }
+        }
+        Command::Verify { certificate, trust, time } => {
+            let root = read_certificate(trust.as_deref().unwrap_or(&certificate))?;
+            let certificate = read_certificate(&certificate)?;
+            match verify_chain(&certificate, &root, time.unwrap_or_else(now)) {
+                Ok(length) => {
+                    println!("Zertifikat gültig, Kette der Länge {}", length);
+                    if trust.is_none() {
+                        info!("Selbstsigniert: Vertrauen nur, wenn das Zertifikat auf anderem Weg geprüft wurde");
+                    }
+                    Ok(())
+                }
+                Err(e) => {
+                    println!("Zertifikat ungültig: {}", e);
+                    Err(ToolError::Silent(ExitStatus::Failure))
+                }
+            }
+        }
+        Command::Show { certificate } => {
+            let certificate = read_certificate(&certificate)?;
+            let tbs = &certificate.tbs;
+            println!("Inhaber:      {}", tbs.subject);
+            println!("Aussteller:   {}", tbs.issuer);
+            println!("Seriennummer: {}", tbs.serial);
+            println!("Gültig ab:    {}", asn1::format_time(tbs.not_before));
+            println!("Gültig bis:   {}", asn1::format_time(tbs.not_after));
+            println!("Schlüssel:    {:?}, {} Bit", tbs.public_key.method(), tbs.public_key.bits());
+            println!("CA:           {}", if tbs.ca { "ja" } else { "nein" });
+            println!("Signatur:     {}", certificate.method);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use certificate::tests::{dsa_keys, rsa_keys, tbs};
+    use csprng::{Algorithm, Drbg};
+
+    const NOW: u64 = 1_750_000_000;
+
+    #[test]
+    fn test_chain_of_two() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"x509 Kette");
+        let (root_private, root_public) = rsa_keys();
+        let (_, leaf_public) = dsa_keys();
+        let root = Certificate::sign(tbs("Wurzel", "Wurzel", root_public, true), &root_private, &mut rng).unwrap();
+        let leaf = Certificate::sign(tbs("Server", "Wurzel", leaf_public, false), &root_private, &mut rng).unwrap();
+
+        assert_eq!(verify_chain(&root, &root, NOW), Ok(1));
+        assert_eq!(verify_chain(&leaf, &root, NOW), Ok(2));
+        assert_eq!(verify_chain(&leaf, &root, 1_900_000_000), Err(ChainError::Expired { subject: "Wurzel".to_string() }));
+        assert_eq!(verify_chain(&root, &leaf, NOW), Err(ChainError::RootNotSelfSigned));
+    }
+
+    #[test]
+    fn test_chain_rejects() {
+        let mut rng = Drbg::from_seed(Algorithm::ChaCha20, b"x509 Kette");
+        let (root_private, root_public) = rsa_keys();
+        let (dsa_private, dsa_public) = dsa_keys();
+
+        // Wurzel ohne cA darf nichts ausstellen
+        let not_ca = Certificate::sign(tbs("Wurzel", "Wurzel", root_public.clone(), false), &root_private, &mut rng).unwrap();
+        let leaf = Certificate::sign(tbs("Server", "Wurzel", dsa_public.clone(), false), &root_private, &mut rng).unwrap();
+        assert_eq!(verify_chain(&leaf, &not_ca, NOW), Err(ChainError::NotCa { subject: "Wurzel".to_string() }));
+
+        // Gleicher Name, aber von einem anderen Schlüssel signiert
+        let root = Certificate::sign(tbs("Wurzel", "Wurzel", root_public, true), &root_private, &mut rng).unwrap();
+        let impostor = Certificate::sign(tbs("Server", "Wurzel", dsa_public.clone(), false), &dsa_private, &mut rng).unwrap();
+        assert_eq!(verify_chain(&impostor, &root, NOW), Err(ChainError::Signature { subject: "Server".to_string() }));
+
+        let other = Certificate::sign(tbs("Server", "Andere", dsa_public, false), &root_private, &mut rng).unwrap();
+        assert!(matches!(verify_chain(&other, &root, NOW), Err(ChainError::IssuerMismatch { .. })));
+        assert_eq!(verify_chain(&root, &root, 1_600_000_000), Err(ChainError::NotYetValid { subject: "Wurzel".to_string() }));
+    }
+}
@@ -0,0 +1,333 @@
+//! Breaks repeating-key XOR, the byte-level counterpart of the Vigenère cipher.
+//!
+//! Many home-grown file "encryptions" XOR the data with a short repeating
+//! key. The attack follows the same steps as `vigenere_decrypter`:
+//!
+//! 1. The key length is the one whose ciphertext blocks have the smallest
+//!    normalized Hamming distance to each other. Bytes encrypted with the
+//!    same key byte keep the bit distance of the plaintext, which is small
+//!    for text; bytes encrypted with different key bytes look random.
+//! 2. Every column of bytes encrypted with the same key byte is a single-byte
+//!    XOR; all 256 values are tried and the most plausible plaintext wins.
+//! 3. The few best key lengths are compared by the score of their plaintext.
+
+use clap::{Parser, ValueEnum};
+use logging::{English, Verbosity, warn};
+use classical::{FrequencyTable, Language};
+use tool_error::ToolError;
+
+/// Command-line arguments for the repeating-key XOR breaker.
+#[derive(Parser, Debug)]
+struct Cli {
+    /// Path to the input file containing the ciphertext
+    #[arg(short, long, help = "Path to the input file containing the ciphertext")]
+    file: String,
+
+    /// Path to the output file where the decrypted data will be saved
+    #[arg(short, long, help = "Path to the output file for the decrypted data")]
+    output: String,
+
+    /// Known key; skips the attack and just applies the key
+    #[arg(short, long, help = "Apply this key instead of breaking the cipher (text, or hex with --hex-key)")]
+    key: Option<String>,
+
+    /// Interpret --key as hexadecimal bytes
+    #[arg(long, requires = "key", help = "The key is given as hex bytes")]
+    hex_key: bool,
+
+    /// Input file contains hex digits instead of raw bytes
+    #[arg(long, help = "The input file is hex encoded (whitespace is ignored)")]
+    hex: bool,
+
+    /// What the plaintext is expected to look like
+    #[arg(short, long, value_enum, default_value = "text", help = "Expected plaintext: text or binary (many zero bytes)")]
+    plaintext: PlaintextKind,
+
+    /// Language of a text plaintext, selects the letter frequencies
+    #[arg(short, long, value_enum, default_value = "de", help = "Plaintext language (de, en, fr, es, it)")]
+    language: Language,
+
+    /// Longest key length considered
+    #[arg(long, default_value_t = 40, help = "Maximum key length to try")]
+    max_key_length: usize,
+
+    #[command(flatten)]
+    verbosity: Verbosity<English>,
+}
+
+/// Expected kind of plaintext, selects the scoring function.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum PlaintextKind {
+    /// Natural-language text
+    Text,
+    /// Binary data; zero bytes expose the key
+    Binary,
+}
+
+/// Number of best key lengths whose keys are recovered and compared
+const KEY_LENGTH_CANDIDATES: usize = 5;
+
+/// Improvement of the score per byte a longer key needs over a shorter one;
+/// with fewer bytes per column a longer key always fits a little better
+const MIN_SCORE_GAIN: f64 = 0.05;
+
+/// Share of spaces in typical text
+const SPACE_PROBABILITY: f64 = 0.15;
+
+/// Share of letters in typical text
+const LETTER_PROBABILITY: f64 = 0.75;
+
+/// Share of digits, punctuation and line breaks, spread over all of them
+const OTHER_PRINTABLE_PROBABILITY: f64 = 0.10;
+
+/// Probability of a byte that hardly ever occurs in text
+const UNLIKELY_PROBABILITY: f64 = 1e-6;
+
+/// Scores candidate plaintext bytes; higher means more plausible.
+enum Scorer {
+    /// Log probability of every byte value
+    Text(Vec<f64>),
+    /// Counts zero bytes
+    Binary,
+}
+
+impl Scorer {
+    fn new(kind: PlaintextKind, table: &FrequencyTable) -> Self {
+        match kind {
+            PlaintextKind::Text => Scorer::Text(byte_log_probabilities(table)),
+            PlaintextKind::Binary => Scorer::Binary,
+        }
+    }
+
+    /// Average score per byte of data XORed with a single key byte.
+    fn score(&self, data: impl Iterator<Item = u8>, key: u8) -> f64 {
+        let mut total = 0.0;
+        let mut count = 0;
+        for byte in data {
+            let plain = byte ^ key;
+            total += match self {
+                Scorer::Text(log_probabilities) => log_probabilities[plain as usize],
+                Scorer::Binary => f64::from(u8::from(plain == 0)),
+            };
+            count += 1;
+        }
+        if count == 0 { 0.0 } else { total / count as f64 }
+    }
+}
+
+/// A recovered key together with the score of its plaintext
+struct Candidate {
+    key: Vec<u8>,
+    score: f64,
+}
+
+/// Main entry point for the repeating-key XOR breaker.
+///
+/// The first argument is the program name, as with `std::env::args_os`.
+pub fn run<I, T>(args: I) -> tool_error::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let cli: Cli = Cli::parse_from(args);
+    cli.verbosity.init();
+
+    let data = codec::file::read_binary(&cli.file).and_then(|raw| {
+        if cli.hex { codec::hex::decode(&String::from_utf8_lossy(&raw)) } else { Ok(raw) }
+    });
+    let data = data?;
+
+    let key = match &cli.key {
+        Some(key) if cli.hex_key => codec::hex::decode(key)
+            .map_err(|e| ToolError::usage(format!("invalid key: {}", e)))?,
+        Some(key) => key.as_bytes().to_vec(),
+        None => {
+            if data.len() < 50 {
+                warn!("Input may be too short for reliable analysis");
+            }
+
+            let lengths = rank_key_lengths(&data, cli.max_key_length);
+            println!("Key length  Normalized distance");
+            for &(length, distance) in lengths.iter().take(KEY_LENGTH_CANDIDATES) {
+                println!("{:>10}  {:.3}", length, distance);
+            }
+
+            let scorer = Scorer::new(cli.plaintext, &cli.language.table());
+            let Some(best) = best_key(&data, &lengths, &scorer) else {
+                return Err(ToolError::data("Input is too short to break"));
+            };
+
+            println!("Found key length: {}", best.key.len());
+            best.key
+        }
+    };
+
+    if key.is_empty() {
+        return Err(ToolError::usage("Key must not be empty"));
+    }
+    println!("Key (hex): {}", codec::hex::encode(&key));
+    if key.iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+        println!("Key (text): {}", String::from_utf8_lossy(&key));
+    }
+
+    tool_error::fs::write(&cli.output, xor(&data, &key))
+}
+
+/// Number of bits in which two byte strings differ.
+fn hamming_distance(a: &[u8], b: &[u8]) -> u32 {
+    a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum()
+}
+
+/// Average Hamming distance between consecutive blocks of `key_length`
+/// bytes, in bits per byte. Close to 4 for random data, lower for the
+/// correct key length.
+fn normalized_distance(data: &[u8], key_length: usize) -> Option<f64> {
+    let blocks: Vec<&[u8]> = data.chunks_exact(key_length).collect();
+    if blocks.len() < 2 {
+        return None;
+    }
+
+    let total: u32 = blocks.windows(2)
+        .map(|pair| hamming_distance(pair[0], pair[1]))
+        .sum();
+    Some(total as f64 / ((blocks.len() - 1) * key_length) as f64)
+}
+
+/// Key lengths up to `max_length`, most likely first.
+fn rank_key_lengths(data: &[u8], max_length: usize) -> Vec<(usize, f64)> {
+    let mut lengths: Vec<(usize, f64)> = (1..=max_length)
+        .filter_map(|length| normalized_distance(data, length).map(|distance| (length, distance)))
+        .collect();
+    lengths.sort_by(|a, b| a.1.total_cmp(&b.1));
+    lengths
+}
+
+/// Recovers the keys for the best ranked key lengths and picks the most
+/// plausible one. Shorter keys win unless a longer one scores clearly better.
+fn best_key(data: &[u8], lengths: &[(usize, f64)], scorer: &Scorer) -> Option<Candidate> {
+    let mut candidates: Vec<usize> = lengths.iter()
+        .take(KEY_LENGTH_CANDIDATES)
+        .map(|&(length, _)| length)
+        .collect();
+    candidates.sort_unstable();
+
+    candidates.into_iter()
+        .map(|length| recover_key(data, length, scorer))
+        .reduce(|best, candidate| if candidate.score > best.score + MIN_SCORE_GAIN { candidate } else { best })
+}
+
+/// Recovers the key byte of every column by trying all 256 values.
+///
+/// The key is reduced to its shortest period, so multiples of the true
+/// key length lead to the same key.
+fn recover_key(data: &[u8], key_length: usize, scorer: &Scorer) -> Candidate {
+    let key: Vec<u8> = (0..key_length)
+        .map(|column| {
+            let bytes = || data.iter().skip(column).step_by(key_length).copied();
+            (0..=255u8)
+                .max_by(|&a, &b| scorer.score(bytes(), a).total_cmp(&scorer.score(bytes(), b)))
+                .expect("256 candidates")
+        })
+        .collect();
+    let key = reduce_key(&key);
+
+    let score = (0..key.len())
+        .map(|column| scorer.score(data.iter().skip(column).step_by(key.len()).copied(), key[column]))
+        .sum::<f64>() / key.len() as f64;
+    Candidate { key, score }
+}
+
+/// Shortest period of a key, e.g. `ICEICE` becomes `ICE`.
+fn reduce_key(key: &[u8]) -> Vec<u8> {
+    (1..=key.len())
+        .find(|&period| key.len().is_multiple_of(period) && key.iter().zip(key.iter().skip(period)).all(|(a, b)| a == b))
+        .map_or_else(|| key.to_vec(), |period| key[..period].to_vec())
+}
+
+/// Log probability of every byte value in text of the given language.
+///
+/// Letters follow the frequency table regardless of case, spaces and other
+/// printable characters get a fixed share, everything else is unlikely.
+fn byte_log_probabilities(table: &FrequencyTable) -> Vec<f64> {
+    let others = (0..=255u8)
+        .filter(|&b| is_other_printable(b))
+        .count() as f64;
+
+    (0..=255u8)
+        .map(|byte| {
+            let probability = if byte.is_ascii_alphabetic() {
+                LETTER_PROBABILITY * table.frequency(byte.to_ascii_uppercase() as char)
+            } else if byte == b' ' {
+                SPACE_PROBABILITY
+            } else if is_other_printable(byte) {
+                OTHER_PRINTABLE_PROBABILITY / others
+            } else {
+                0.0
+            };
+            probability.max(UNLIKELY_PROBABILITY).log10()
+        })
+        .collect()
+}
+
+/// Digits, punctuation and line breaks
+fn is_other_printable(byte: u8) -> bool {
+    (byte.is_ascii_graphic() && !byte.is_ascii_alphabetic()) || matches!(byte, b'\n' | b'\r' | b'\t')
+}
+
+/// XORs data with a repeating key; the same operation encrypts and decrypts.
+fn xor(data: &[u8], key: &[u8]) -> Vec<u8> {
+    data.iter().zip(key.iter().cycle()).map(|(d, k)| d ^ k).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "Burning 'em, if you ain't quick and nimble\n\
+        I go crazy when I hear a cymbal and a hi-hat with a souped up tempo\n\
+        I'm on a roll, it's time to go solo, ollin' in my five point oh\n\
+        ridin' down the street with the top down, making all the girls stop and stare";
+
+    #[test]
+    fn test_hamming_distance() {
+        assert_eq!(hamming_distance(b"this is a test", b"wokka wokka!!!"), 37);
+    }
+
+    #[test]
+    fn test_xor_vector() {
+        let ciphertext = xor(b"Burning 'em, if you ain't quick and nimble\nI go crazy when I hear a cymbal", b"ICE");
+        assert_eq!(
+            codec::hex::encode(&ciphertext),
+            "0b3637272a2b2e63622c2e69692a23693a2a3c6324202d623d63343c2a26226324272765272\
+             a282b2f20430a652e2c652a3124333a653e2b2027630c692b20283165286326302e27282f"
+        );
+    }
+
+    #[test]
+    fn test_breaks_text() {
+        let key = b"Terminator X";
+        let ciphertext = xor(TEXT.as_bytes(), key);
+        let scorer = Scorer::new(PlaintextKind::Text, &Language::En.table());
+
+        let best = best_key(&ciphertext, &rank_key_lengths(&ciphertext, 40), &scorer).unwrap();
+        assert_eq!(best.key, key);
+    }
+
+    #[test]
+    fn test_breaks_binary() {
+        let mut data = b"\x7fELF\x02\x01\x01".to_vec();
+        data.extend(std::iter::repeat_n(0u8, 200));
+        data.extend(b"\x01\x00\x00\x00\x05\x00\x00\x00".repeat(10));
+        let ciphertext = xor(&data, &[0x13, 0x37, 0xc0, 0xde]);
+
+        let candidate = recover_key(&ciphertext, 8, &Scorer::new(PlaintextKind::Binary, &Language::En.table()));
+        assert_eq!(candidate.key, [0x13, 0x37, 0xc0, 0xde]);
+    }
+
+    #[test]
+    fn test_reduce_key() {
+        assert_eq!(reduce_key(b"ICEICE"), b"ICE");
+        assert_eq!(reduce_key(b"ICEIC"), b"ICEIC");
+        assert_eq!(reduce_key(b"AAAA"), b"A");
+    }
+}